@@ -1,3 +1,29 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type Coordinates = { type: string, coordinates: Array<number>, };
+/**
+ * GeoJSON-compliant coordinate representation for geographic locations.
+ *
+ * Follows the GeoJSON Point specification with longitude/latitude ordering.
+ * Used for spatial queries, map display, and geographic clustering.
+ *
+ * # Format
+ * - `type`: Always "Point" for single location coordinates
+ * - `coordinates`: [longitude, latitude] in decimal degrees (WGS84)
+ *
+ * # Example
+ * ```json
+ * {
+ *   "type": "Point",
+ *   "coordinates": [77.5946, 12.9716]  // Bangalore, India
+ * }
+ * ```
+ */
+export type Coordinates = { 
+/**
+ * GeoJSON geometry type, always "Point" for lettering locations
+ */
+type: string, 
+/**
+ * Coordinate pair: [longitude, latitude] in decimal degrees
+ */
+coordinates: Array<number>, };
@@ -4,4 +4,105 @@ import type { ImageMetadata } from "./ImageMetadata";
 import type { LetteringStatus } from "./LetteringStatus";
 import type { ThumbnailUrls } from "./ThumbnailUrls";
 
-export type Lettering = { id: string, city_id: string, contributor_tag: string, image_url: string, thumbnail_urls: ThumbnailUrls, location: Coordinates, pin_code: string, detected_text: string | null, ml_metadata: ImageMetadata | null, description: string | null, is_lettering: boolean, status: LetteringStatus, likes_count: number, comments_count: number, image_hash: string | null, report_count: number, report_reasons: Array<string>, cultural_context: string | null, created_at: string, updated_at: string, };
+/**
+ * Core domain entity representing a lettering/typography submission.
+ *
+ * A lettering captures visual text found in public spaces, along with its
+ * geographic location, contributor information, and processing metadata.
+ * Each lettering undergoes moderation before public visibility.
+ *
+ * # Lifecycle
+ * 1. **Uploaded** - Initial submission with basic metadata
+ * 2. **Pending** - Awaiting moderation review and ML processing
+ * 3. **Approved** - Publicly discoverable and searchable
+ * 4. **Rejected** - Hidden from public view with reason
+ * 5. **Reported** - Flagged by community for review
+ *
+ * # Invariants
+ * - `id` must be unique across all letterings
+ * - `location` coordinates must be valid longitude/latitude pairs
+ * - `pin_code` must follow regional formatting rules
+ * - `contributor_tag` identifies the submitter (may be pseudonymous)
+ * - Image URLs must point to accessible storage locations
+ */
+export type Lettering = { 
+/**
+ * Unique identifier for this lettering entity
+ */
+id: string, 
+/**
+ * Reference to the city/region where this lettering was found
+ */
+city_id: string, 
+/**
+ * Contributor's chosen display name or tag (may be pseudonymous)
+ */
+contributor_tag: string, 
+/**
+ * URL to the full-resolution image stored in persistent storage
+ */
+image_url: string, 
+/**
+ * Collection of thumbnail URLs for different display contexts
+ */
+thumbnail_urls: ThumbnailUrls, 
+/**
+ * Geographic coordinates where the lettering was photographed
+ */
+location: Coordinates, 
+/**
+ * Local postal/zip code for geographic clustering and discovery
+ */
+pin_code: string, 
+/**
+ * Machine-extracted text content from OCR processing (optional)
+ */
+detected_text: string | null, 
+/**
+ * ML-derived metadata about visual characteristics (optional)
+ */
+ml_metadata: ImageMetadata | null, 
+/**
+ * Human-provided description or story context (optional)
+ */
+description: string | null, 
+/**
+ * Whether ML analysis confirmed this contains readable text
+ */
+is_lettering: boolean, 
+/**
+ * Current moderation and visibility status
+ */
+status: LetteringStatus, 
+/**
+ * Number of user likes/favorites (cached for performance)
+ */
+likes_count: number, 
+/**
+ * Number of associated comments (cached for performance)
+ */
+comments_count: number, 
+/**
+ * Content-based hash for duplicate detection (optional)
+ */
+image_hash: string | null, 
+/**
+ * Number of community reports filed (cached for moderation)
+ */
+report_count: number, 
+/**
+ * Reasons provided in community reports
+ */
+report_reasons: Array<string>, 
+/**
+ * Additional cultural or historical context (optional)
+ */
+cultural_context: string | null, 
+/**
+ * Timestamp when this lettering was first uploaded
+ */
+created_at: string, 
+/**
+ * Timestamp of the most recent modification
+ */
+updated_at: string, };
@@ -1,3 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
+/**
+ * Moderation and visibility status for lettering entities.
+ *
+ * Controls public discoverability and determines which workflows
+ * are available for administrators and contributors.
+ */
 export type LetteringStatus = "Pending" | "Approved" | "Rejected" | "Reported";
@@ -1,3 +1,26 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type ThumbnailUrls = { small: string, medium: string, large: string, };
+/**
+ * Collection of thumbnail image URLs for responsive display contexts.
+ *
+ * Thumbnails are pre-generated at upload time to optimize loading performance
+ * across different UI components and screen sizes.
+ *
+ * # Size Guidelines
+ * - `small`: 200px width for map markers, grid previews
+ * - `medium`: 600px width for gallery cards, search results
+ * - `large`: 1200px width for detail views, full-screen display
+ */
+export type ThumbnailUrls = { 
+/**
+ * Small thumbnail (200px) for compact displays and map markers
+ */
+small: string, 
+/**
+ * Medium thumbnail (600px) for gallery cards and search results
+ */
+medium: string, 
+/**
+ * Large thumbnail (1200px) for detail views and zine-style display
+ */
+large: string, };
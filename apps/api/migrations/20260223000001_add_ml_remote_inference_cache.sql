@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS ml_remote_inference_cache (
+    image_hash TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    result_text TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (image_hash, provider)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ml_remote_inference_cache_created_at
+    ON ml_remote_inference_cache(created_at);
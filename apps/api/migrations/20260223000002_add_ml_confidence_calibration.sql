@@ -0,0 +1,26 @@
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS ml_text_confidence REAL,
+    ADD COLUMN IF NOT EXISTS ml_script_confidence REAL,
+    ADD COLUMN IF NOT EXISTS ml_low_confidence_fields TEXT[] NOT NULL DEFAULT '{}';
+
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS low_confidence BOOLEAN
+        GENERATED ALWAYS AS (cardinality(ml_low_confidence_fields) > 0) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_low_confidence
+    ON letterings(created_at)
+    WHERE low_confidence;
+
+-- Low-confidence OCR text is kept for moderators but must not be searchable.
+CREATE OR REPLACE FUNCTION update_lettering_tsv() RETURNS trigger AS $$
+BEGIN
+    NEW.detected_text_tsv := to_tsvector(
+        'english',
+        CASE
+            WHEN 'detected_text' = ANY(NEW.ml_low_confidence_fields) THEN ''
+            ELSE COALESCE(NEW.detected_text, '')
+        END || ' ' || COALESCE(NEW.description, '')
+    );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS ml_metadata_corrections (
+    id UUID PRIMARY KEY,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    field_name TEXT NOT NULL,
+    model_value TEXT,
+    previous_value TEXT,
+    corrected_value TEXT,
+    actor_type TEXT NOT NULL DEFAULT 'ADMIN',
+    actor_sub TEXT,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_ml_metadata_corrections_field_name
+        CHECK (field_name IN ('detected_text', 'ml_style', 'ml_script')),
+    CONSTRAINT chk_ml_metadata_corrections_actor_type
+        CHECK (actor_type IN ('ADMIN', 'USER'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_ml_metadata_corrections_lettering_created
+    ON ml_metadata_corrections(lettering_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_ml_metadata_corrections_created
+    ON ml_metadata_corrections(created_at);
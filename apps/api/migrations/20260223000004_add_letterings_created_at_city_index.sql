@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_letterings_created_at_city
+    ON letterings(created_at, city_id);
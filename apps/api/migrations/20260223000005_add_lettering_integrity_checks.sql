@@ -0,0 +1,17 @@
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS integrity_status TEXT,
+    ADD COLUMN IF NOT EXISTS integrity_checked_at TIMESTAMPTZ;
+
+ALTER TABLE letterings
+    DROP CONSTRAINT IF EXISTS chk_letterings_integrity_status;
+
+ALTER TABLE letterings
+    ADD CONSTRAINT chk_letterings_integrity_status
+        CHECK (integrity_status IS NULL OR integrity_status IN ('OK', 'MISSING', 'CORRUPT'));
+
+CREATE INDEX IF NOT EXISTS idx_letterings_integrity_checked_at
+    ON letterings(integrity_checked_at ASC NULLS FIRST);
+
+CREATE INDEX IF NOT EXISTS idx_letterings_integrity_failures
+    ON letterings(integrity_checked_at)
+    WHERE integrity_status IN ('MISSING', 'CORRUPT');
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS rate_limit_overrides (
+    route TEXT PRIMARY KEY,
+    limit_per_day INTEGER NOT NULL CHECK (limit_per_day >= 0),
+    updated_by TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
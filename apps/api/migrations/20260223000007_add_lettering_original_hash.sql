@@ -0,0 +1,8 @@
+-- SHA-256 of the uploaded original, computed while streaming so exact
+-- re-uploads can be rejected before decoding.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS original_hash TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_original_hash
+    ON letterings(original_hash)
+    WHERE original_hash IS NOT NULL;
@@ -0,0 +1,28 @@
+-- Geo lookups cached per /24 (IPv4) or /48 (IPv6); full addresses are never stored.
+CREATE TABLE IF NOT EXISTS ip_geo_cache (
+    network INET PRIMARY KEY,
+    country_code TEXT NOT NULL,
+    region TEXT,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ip_geo_cache_fetched_at
+    ON ip_geo_cache(fetched_at);
+
+CREATE TABLE IF NOT EXISTS ip_geo_annotations (
+    id UUID PRIMARY KEY,
+    event_type TEXT NOT NULL CHECK (event_type IN ('UPLOAD', 'LOGIN', 'REPORT')),
+    subject_id UUID,
+    country_code TEXT NOT NULL,
+    region TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ip_geo_annotations_created_at
+    ON ip_geo_annotations(created_at);
+
+CREATE INDEX IF NOT EXISTS idx_ip_geo_annotations_subject
+    ON ip_geo_annotations(subject_id);
+
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS upload_country_code TEXT;
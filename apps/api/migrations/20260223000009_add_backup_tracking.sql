@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS backup_runs (
+    id UUID PRIMARY KEY,
+    status TEXT NOT NULL DEFAULT 'RUNNING' CHECK (status IN ('RUNNING', 'COMPLETED', 'FAILED')),
+    objects_copied INTEGER NOT NULL DEFAULT 0,
+    objects_failed INTEGER NOT NULL DEFAULT 0,
+    bytes_copied BIGINT NOT NULL DEFAULT 0,
+    metadata_key TEXT,
+    metadata_rows INTEGER,
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_runs_started_at
+    ON backup_runs(started_at DESC);
+
+-- One row per lettering whose image has been copied to the secondary bucket.
+CREATE TABLE IF NOT EXISTS backup_objects (
+    lettering_id UUID PRIMARY KEY REFERENCES letterings(id) ON DELETE CASCADE,
+    run_id UUID NOT NULL REFERENCES backup_runs(id) ON DELETE CASCADE,
+    source_key TEXT NOT NULL,
+    backup_key TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    backed_up_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_objects_run
+    ON backup_objects(run_id);
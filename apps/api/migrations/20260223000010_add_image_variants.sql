@@ -0,0 +1,8 @@
+-- Resized renditions served by GET /images/{id}, tracked so they can be
+-- removed from storage along with the lettering.
+CREATE TABLE IF NOT EXISTS image_variants (
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    storage_key TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (lettering_id, storage_key)
+);
@@ -0,0 +1,19 @@
+-- First-party analytics. Only daily counters are stored: no IPs, user agents,
+-- session ids or timestamps finer than a day.
+CREATE TABLE IF NOT EXISTS analytics_daily_events (
+    date DATE NOT NULL,
+    event_type TEXT NOT NULL,
+    dimension TEXT NOT NULL DEFAULT '',
+    count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (date, event_type, dimension)
+);
+
+ALTER TABLE analytics_daily_events
+    DROP CONSTRAINT IF EXISTS chk_analytics_daily_events_type;
+
+ALTER TABLE analytics_daily_events
+    ADD CONSTRAINT chk_analytics_daily_events_type
+        CHECK (event_type IN ('page_view', 'search', 'map_interaction'));
+
+CREATE INDEX IF NOT EXISTS idx_analytics_daily_events_type_date
+    ON analytics_daily_events(event_type, date);
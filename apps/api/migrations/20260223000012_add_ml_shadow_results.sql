@@ -0,0 +1,28 @@
+-- Version of the model that produced letterings.detected_text
+-- ('onnx:<sha256 prefix>' or 'huggingface:<model>').
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS ml_model_version TEXT;
+
+-- Production vs candidate ONNX outputs recorded while a shadow model runs.
+CREATE TABLE IF NOT EXISTS ml_shadow_results (
+    id UUID PRIMARY KEY,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    production_version TEXT,
+    candidate_version TEXT,
+    production_text TEXT,
+    production_confidence REAL,
+    production_latency_ms REAL NOT NULL,
+    candidate_text TEXT,
+    candidate_confidence REAL,
+    candidate_latency_ms REAL NOT NULL,
+    candidate_error TEXT,
+    -- NULL when either model failed to produce a result.
+    agreed BOOLEAN,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ml_shadow_results_created_at
+    ON ml_shadow_results(created_at);
+
+CREATE INDEX IF NOT EXISTS idx_ml_shadow_results_versions
+    ON ml_shadow_results(candidate_version, production_version, created_at);
@@ -0,0 +1,21 @@
+-- Short links are the base62 encoding of a per-lettering sequence number.
+-- Starting above 62^3 keeps every code at least four characters long.
+CREATE SEQUENCE IF NOT EXISTS lettering_short_id_seq START WITH 238328;
+
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS short_id BIGINT NOT NULL DEFAULT nextval('lettering_short_id_seq');
+
+ALTER SEQUENCE lettering_short_id_seq OWNED BY letterings.short_id;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_letterings_short_id ON letterings(short_id);
+
+CREATE TABLE IF NOT EXISTS lettering_share_clicks (
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    channel TEXT NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (lettering_id, date, channel)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lettering_share_clicks_date
+    ON lettering_share_clicks(date);
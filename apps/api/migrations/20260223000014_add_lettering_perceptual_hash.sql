@@ -0,0 +1,13 @@
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS perceptual_hash BIGINT,
+    ADD COLUMN IF NOT EXISTS perceptual_hash_bands INT[],
+    ADD COLUMN IF NOT EXISTS near_duplicate_of UUID REFERENCES letterings(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS near_duplicate_distance SMALLINT;
+
+-- Multi-index lookup: candidates share at least one positional hash band.
+CREATE INDEX IF NOT EXISTS idx_letterings_perceptual_hash_bands
+    ON letterings USING GIN (perceptual_hash_bands);
+
+CREATE INDEX IF NOT EXISTS idx_letterings_near_duplicates
+    ON letterings(created_at)
+    WHERE near_duplicate_of IS NOT NULL;
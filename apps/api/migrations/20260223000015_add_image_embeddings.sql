@@ -0,0 +1,9 @@
+CREATE EXTENSION IF NOT EXISTS vector;
+
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS image_embedding vector(128),
+    ADD COLUMN IF NOT EXISTS image_embedding_version TEXT;
+
+-- Approximate nearest-neighbour search for "more like this".
+CREATE INDEX IF NOT EXISTS idx_letterings_image_embedding
+    ON letterings USING hnsw (image_embedding vector_cosine_ops);
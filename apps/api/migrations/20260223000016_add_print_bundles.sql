@@ -0,0 +1,24 @@
+-- Curated print exports: selected originals plus metadata and attribution,
+-- assembled into a zip by the print bundle worker.
+CREATE TABLE IF NOT EXISTS print_bundles (
+    id UUID PRIMARY KEY,
+    requested_by TEXT NOT NULL,
+    title TEXT NOT NULL,
+    lettering_ids UUID[] NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'RUNNING', 'COMPLETED', 'FAILED')),
+    object_key TEXT,
+    size_bytes BIGINT,
+    items_included INTEGER NOT NULL DEFAULT 0,
+    items_missing INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_print_bundles_created_at
+    ON print_bundles(created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_print_bundles_pending
+    ON print_bundles(created_at)
+    WHERE status IN ('PENDING', 'RUNNING');
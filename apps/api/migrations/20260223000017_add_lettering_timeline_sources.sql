@@ -0,0 +1,28 @@
+-- Individual reports with their time; letterings.report_reasons only keeps
+-- the reasons.
+CREATE TABLE IF NOT EXISTS lettering_reports (
+    id UUID PRIMARY KEY,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_lettering_reports_lettering_created
+    ON lettering_reports(lettering_id, created_at);
+
+-- One row per completed ML pass, so reprocessing doesn't erase earlier output.
+CREATE TABLE IF NOT EXISTS ml_runs (
+    id UUID PRIMARY KEY,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    model_version TEXT,
+    detected_text TEXT,
+    text_confidence REAL,
+    ml_style TEXT,
+    style_confidence REAL,
+    ml_script TEXT,
+    low_confidence_fields TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_ml_runs_lettering_created
+    ON ml_runs(lettering_id, created_at);
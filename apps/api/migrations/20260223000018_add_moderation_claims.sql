@@ -0,0 +1,9 @@
+-- Short-lived moderator claims so GET /admin/moderation/next never hands the
+-- same item to two reviewers.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS review_claimed_by TEXT,
+    ADD COLUMN IF NOT EXISTS review_claimed_until TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_review_queue
+    ON letterings(report_count DESC, created_at)
+    WHERE status IN ('PENDING', 'REPORTED');
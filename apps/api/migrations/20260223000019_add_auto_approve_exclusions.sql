@@ -0,0 +1,5 @@
+-- Signals the pending auto-approve worker checks before approving an item,
+-- and the reason it last left one for human review.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS nsfw_flagged BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS auto_approve_skip_reason TEXT;
@@ -0,0 +1,15 @@
+-- Squared RGB distance between two `#RRGGBB` colours, used to match
+-- `ml_color_palette` entries against a searched colour.
+CREATE OR REPLACE FUNCTION hex_color_distance_sq(a TEXT, b TEXT)
+RETURNS INTEGER
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT (ar - br) * (ar - br) + (ag - bg) * (ag - bg) + (ab - bb) * (ab - bb)
+    FROM (
+        SELECT ('x' || substr(a, 2, 2))::bit(8)::int AS ar,
+               ('x' || substr(a, 4, 2))::bit(8)::int AS ag,
+               ('x' || substr(a, 6, 2))::bit(8)::int AS ab,
+               ('x' || substr(b, 2, 2))::bit(8)::int AS br,
+               ('x' || substr(b, 4, 2))::bit(8)::int AS bg,
+               ('x' || substr(b, 6, 2))::bit(8)::int AS bb
+    ) rgb
+$$;
@@ -0,0 +1,7 @@
+-- Which detector produced `detected_text`: `huggingface`, `onnx` or
+-- `tesseract`. NULL for placeholder text.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS detected_text_source TEXT;
+
+ALTER TABLE ml_runs
+    ADD COLUMN IF NOT EXISTS detected_text_source TEXT;
@@ -0,0 +1,25 @@
+-- Consistent backup markers: the WAL position and transaction snapshot at
+-- which the storage object manifest was read, so a point-in-time restore of
+-- the database can be paired with exactly the objects it references.
+CREATE TABLE IF NOT EXISTS backup_snapshots (
+    id UUID PRIMARY KEY,
+    requested_by TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'RUNNING', 'COMPLETED', 'FAILED')),
+    wal_lsn TEXT,
+    pg_snapshot TEXT,
+    snapshot_at TIMESTAMPTZ,
+    manifest_key TEXT,
+    object_count INTEGER NOT NULL DEFAULT 0,
+    lettering_count INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_backup_snapshots_created_at
+    ON backup_snapshots(created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_backup_snapshots_pending
+    ON backup_snapshots(created_at)
+    WHERE status IN ('PENDING', 'RUNNING');
@@ -0,0 +1,27 @@
+-- Corpus-wide ML reprocessing runs. The reprocess worker walks matching
+-- letterings in id order from `cursor_id`, feeding them to the low-priority
+-- ML queue; the ML worker counts each finished job in `processed`/`failed`.
+CREATE TABLE IF NOT EXISTS ml_reprocess_runs (
+    id UUID PRIMARY KEY,
+    requested_by TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'PENDING' CHECK (status IN ('PENDING', 'RUNNING', 'COMPLETED', 'CANCELLED')),
+    created_from TIMESTAMPTZ,
+    created_to TIMESTAMPTZ,
+    missing_text_only BOOLEAN NOT NULL DEFAULT FALSE,
+    model_version TEXT,
+    total INTEGER NOT NULL DEFAULT 0,
+    enqueued INTEGER NOT NULL DEFAULT 0,
+    processed INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    cursor_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_ml_reprocess_runs_created_at
+    ON ml_reprocess_runs(created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_ml_reprocess_runs_active
+    ON ml_reprocess_runs(created_at)
+    WHERE status IN ('PENDING', 'RUNNING');
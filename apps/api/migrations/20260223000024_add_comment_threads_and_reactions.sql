@@ -0,0 +1,29 @@
+-- One level of replies under a top-level comment, per-user reactions, and
+-- soft deletion so a removed comment with replies can stay as a tombstone.
+ALTER TABLE comments
+    ADD COLUMN IF NOT EXISTS parent_id UUID REFERENCES comments(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS reactions_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS comment_reactions (
+    comment_id UUID NOT NULL REFERENCES comments(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (comment_id, user_id, kind)
+);
+
+-- Public listing: top-level comments by time (either direction) or by
+-- reactions, and the replies under one comment. `id` breaks ties so cursors
+-- are stable.
+CREATE INDEX IF NOT EXISTS idx_comments_top_level_created
+    ON comments(lettering_id, created_at, id)
+    WHERE parent_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_comments_top_level_reactions
+    ON comments(lettering_id, reactions_count DESC, id DESC)
+    WHERE parent_id IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_comments_replies_created
+    ON comments(parent_id, created_at, id)
+    WHERE parent_id IS NOT NULL;
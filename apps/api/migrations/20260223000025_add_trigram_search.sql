@@ -0,0 +1,12 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Typo-tolerant search: the `<%` word-similarity matches in
+-- search_with_locale use these.
+CREATE INDEX IF NOT EXISTS idx_letterings_detected_text_trgm
+    ON letterings USING gin (detected_text gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_letterings_contributor_tag_trgm
+    ON letterings USING gin (contributor_tag gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_cities_name_trgm
+    ON cities USING gin (name gin_trgm_ops);
@@ -0,0 +1,9 @@
+-- Contributors can hide where exactly a lettering is. `location` is what every
+-- public query and export reads, so it now holds the published point (exact,
+-- fuzzed ~100m, or the city centre); the true point is kept for admins.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS location_privacy TEXT NOT NULL DEFAULT 'exact'
+        CHECK (location_privacy IN ('exact', 'fuzzed', 'city')),
+    ADD COLUMN IF NOT EXISTS exact_location GEOGRAPHY(Point, 4326);
+
+UPDATE letterings SET exact_location = location WHERE exact_location IS NULL;
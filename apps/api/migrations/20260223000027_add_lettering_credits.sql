@@ -0,0 +1,12 @@
+-- Photographer/documenter credit chosen by the uploader, separate from the
+-- contributor tag. A linked user may dispute it, which hides it until an
+-- admin resolves the dispute.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS credit_name TEXT,
+    ADD COLUMN IF NOT EXISTS credit_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS credit_disputed_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS credit_dispute_reason TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_credit_disputed
+    ON letterings(credit_disputed_at)
+    WHERE credit_disputed_at IS NOT NULL;
@@ -0,0 +1,31 @@
+-- Age-restricted letterings (bars, adult venues) are hidden from public
+-- queries in regions whose policy gates them, unless the client sends
+-- age_ack=true after showing its own age gate.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS age_restricted BOOLEAN NOT NULL DEFAULT false,
+    -- 'moderator' or 'classifier'; a moderator's decision is never overridden.
+    ADD COLUMN IF NOT EXISTS age_restricted_by TEXT;
+
+ALTER TABLE region_policies
+    ADD COLUMN IF NOT EXISTS age_gate_enabled BOOLEAN NOT NULL DEFAULT false;
+
+-- Whatever sets nsfw_flagged (the NSFW classifier) also age-restricts the
+-- lettering, unless a moderator has already decided.
+CREATE OR REPLACE FUNCTION age_restrict_nsfw_flagged() RETURNS trigger AS $$
+BEGIN
+    IF NEW.nsfw_flagged AND NEW.age_restricted_by IS DISTINCT FROM 'moderator' THEN
+        NEW.age_restricted := true;
+        NEW.age_restricted_by := 'classifier';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS letterings_age_restrict_nsfw ON letterings;
+CREATE TRIGGER letterings_age_restrict_nsfw
+    BEFORE INSERT OR UPDATE OF nsfw_flagged ON letterings
+    FOR EACH ROW EXECUTE FUNCTION age_restrict_nsfw_flagged();
+
+UPDATE letterings
+SET age_restricted = true, age_restricted_by = 'classifier'
+WHERE nsfw_flagged AND age_restricted_by IS NULL;
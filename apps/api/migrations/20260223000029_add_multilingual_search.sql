@@ -0,0 +1,53 @@
+-- Per-script full-text search. Hindi, Tamil and Arabic have Snowball stemmers
+-- from PostgreSQL 14; Kannada and Bengali have none, so they (and any stemmer
+-- missing on an older server) get an unstemmed copy of `simple` under the same
+-- name. Search routes the requested locale to its config and column.
+DO $$
+DECLARE
+    cfg TEXT;
+BEGIN
+    FOREACH cfg IN ARRAY ARRAY['hindi', 'kannada', 'tamil', 'bengali', 'arabic'] LOOP
+        IF NOT EXISTS (SELECT 1 FROM pg_ts_config WHERE cfgname = cfg) THEN
+            EXECUTE format('CREATE TEXT SEARCH CONFIGURATION %I (COPY = simple)', cfg);
+        END IF;
+    END LOOP;
+END;
+$$;
+
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS detected_text_tsv_hi tsvector,
+    ADD COLUMN IF NOT EXISTS detected_text_tsv_kn tsvector,
+    ADD COLUMN IF NOT EXISTS detected_text_tsv_ta tsvector,
+    ADD COLUMN IF NOT EXISTS detected_text_tsv_bn tsvector,
+    ADD COLUMN IF NOT EXISTS detected_text_tsv_ar tsvector;
+
+-- A script's column is only filled when the text contains that script, so
+-- the indexes stay small on a mostly-Latin archive. Low-confidence OCR text
+-- stays unsearchable, as in the English column.
+CREATE OR REPLACE FUNCTION update_lettering_tsv() RETURNS trigger AS $$
+DECLARE
+    doc TEXT;
+BEGIN
+    doc := CASE
+               WHEN 'detected_text' = ANY(NEW.ml_low_confidence_fields) THEN ''
+               ELSE COALESCE(NEW.detected_text, '')
+           END || ' ' || COALESCE(NEW.description, '');
+
+    NEW.detected_text_tsv := to_tsvector('english', doc);
+    NEW.detected_text_tsv_hi := CASE WHEN doc ~ '[ऀ-ॿ]' THEN to_tsvector('hindi', doc) END;
+    NEW.detected_text_tsv_kn := CASE WHEN doc ~ '[ಀ-೿]' THEN to_tsvector('kannada', doc) END;
+    NEW.detected_text_tsv_ta := CASE WHEN doc ~ '[஀-௿]' THEN to_tsvector('tamil', doc) END;
+    NEW.detected_text_tsv_bn := CASE WHEN doc ~ '[ঀ-৿]' THEN to_tsvector('bengali', doc) END;
+    NEW.detected_text_tsv_ar := CASE WHEN doc ~ '[؀-ۿ]' THEN to_tsvector('arabic', doc) END;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Re-run the trigger for existing rows.
+UPDATE letterings SET detected_text = detected_text;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_fts_hi ON letterings USING gin(detected_text_tsv_hi) WHERE detected_text_tsv_hi IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_letterings_fts_kn ON letterings USING gin(detected_text_tsv_kn) WHERE detected_text_tsv_kn IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_letterings_fts_ta ON letterings USING gin(detected_text_tsv_ta) WHERE detected_text_tsv_ta IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_letterings_fts_bn ON letterings USING gin(detected_text_tsv_bn) WHERE detected_text_tsv_bn IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_letterings_fts_ar ON letterings USING gin(detected_text_tsv_ar) WHERE detected_text_tsv_ar IS NOT NULL;
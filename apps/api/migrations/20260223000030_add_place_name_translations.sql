@@ -0,0 +1,54 @@
+-- Localized display names for cities and countries. `cities.name` stays the
+-- canonical name (it is part of the unique key); countries have no table of
+-- their own, so an untranslated country falls back to its code.
+CREATE TABLE IF NOT EXISTS city_name_translations (
+    city_id UUID NOT NULL REFERENCES cities(id) ON DELETE CASCADE,
+    locale TEXT NOT NULL,
+    name TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (city_id, locale)
+);
+
+CREATE TABLE IF NOT EXISTS country_name_translations (
+    country_code VARCHAR(2) NOT NULL,
+    locale TEXT NOT NULL,
+    name TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (country_code, locale),
+    CONSTRAINT chk_country_name_translation_code_upper
+        CHECK (country_code = UPPER(country_code))
+);
+
+CREATE INDEX IF NOT EXISTS idx_city_name_translations_name_trgm
+    ON city_name_translations USING gin (name gin_trgm_ops);
+
+-- First translation in `locales` order (the request's fallback chain), else
+-- the fallback.
+CREATE OR REPLACE FUNCTION localized_city_name(p_city_id UUID, p_fallback TEXT, p_locales TEXT[])
+RETURNS TEXT AS $$
+    SELECT COALESCE(
+        (SELECT t.name
+         FROM city_name_translations t
+         WHERE t.city_id = p_city_id AND t.locale = ANY(p_locales)
+         ORDER BY array_position(p_locales, t.locale)
+         LIMIT 1),
+        p_fallback
+    );
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION localized_country_name(p_country_code TEXT, p_locales TEXT[])
+RETURNS TEXT AS $$
+    SELECT COALESCE(
+        (SELECT t.name
+         FROM country_name_translations t
+         WHERE t.country_code = p_country_code AND t.locale = ANY(p_locales)
+         ORDER BY array_position(p_locales, t.locale)
+         LIMIT 1),
+        p_country_code
+    );
+$$ LANGUAGE sql STABLE;
+
+-- Canonical names are English.
+INSERT INTO country_name_translations (country_code, locale, name)
+VALUES ('IN', 'en', 'India')
+ON CONFLICT (country_code, locale) DO NOTHING;
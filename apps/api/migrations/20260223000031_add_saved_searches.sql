@@ -0,0 +1,35 @@
+-- Searches a user saved to hear about new approvals. Every filter is
+-- optional but at least one is set; an area is a centre plus radius.
+CREATE TABLE IF NOT EXISTS saved_searches (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    query TEXT,
+    script TEXT,
+    style TEXT,
+    city_id UUID REFERENCES cities(id) ON DELETE CASCADE,
+    center GEOGRAPHY(Point, 4326),
+    radius_m DOUBLE PRECISION,
+    notify BOOLEAN NOT NULL DEFAULT true,
+    -- Approvals up to here have been evaluated.
+    last_checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_saved_search_area
+        CHECK ((center IS NULL) = (radius_m IS NULL)),
+    CONSTRAINT chk_saved_search_not_empty
+        CHECK (query IS NOT NULL OR script IS NOT NULL OR style IS NOT NULL
+               OR city_id IS NOT NULL OR center IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_user
+    ON saved_searches(user_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_saved_searches_due
+    ON saved_searches(last_checked_at)
+    WHERE notify;
+
+-- The worker looks up approvals since each search's last check.
+CREATE INDEX IF NOT EXISTS idx_lettering_status_history_approved
+    ON lettering_status_history(created_at)
+    WHERE to_status = 'APPROVED';
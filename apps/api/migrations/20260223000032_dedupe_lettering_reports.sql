@@ -0,0 +1,29 @@
+-- One open report per reporter and lettering. `reporter_key` identifies the
+-- reporter (account or salted IP hash); a repeat report updates the reason
+-- instead of counting again. Clearing reports closes them (`cleared_at`), so
+-- the same person can report again later and the timeline keeps history.
+ALTER TABLE lettering_reports
+    ADD COLUMN IF NOT EXISTS reporter_key TEXT,
+    ADD COLUMN IF NOT EXISTS cleared_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+
+-- Backfill. Rows beyond a lettering's current report_count were cleared by
+-- a moderator before this column existed; close them, newest kept open.
+UPDATE lettering_reports r
+SET cleared_at = NOW()
+FROM (
+    SELECT lr.id,
+           row_number() OVER (PARTITION BY lr.lettering_id ORDER BY lr.created_at DESC, lr.id DESC) AS rn,
+           l.report_count
+    FROM lettering_reports lr
+    JOIN letterings l ON l.id = lr.lettering_id
+) ranked
+WHERE r.id = ranked.id AND ranked.rn > ranked.report_count AND r.cleared_at IS NULL;
+
+-- Existing reports can't be deduplicated: lettering_reports never recorded
+-- who reported, so every row predating this migration has no reporter_key
+-- and stays open as its own report. Only reports filed from here on are
+-- merged per reporter.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_lettering_reports_open_per_reporter
+    ON lettering_reports(lettering_id, reporter_key)
+    WHERE reporter_key IS NOT NULL AND cleared_at IS NULL;
@@ -0,0 +1,89 @@
+-- Colour search in CIE Lab. Distances between `#RRGGBB` colours are CIE76
+-- ΔE (Euclidean in Lab, D65 white), which tracks perceived difference far
+-- better than RGB. `ml_color_bins` quantizes each palette colour into a
+-- 16-unit Lab cell so a search can narrow candidates with a GIN `&&` lookup
+-- before the exact distance check. Cell layout must match
+-- `infrastructure::imaging::color_palette::lab_bin`.
+
+CREATE OR REPLACE FUNCTION srgb_to_linear(c DOUBLE PRECISION)
+RETURNS DOUBLE PRECISION
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT CASE WHEN c <= 0.04045 THEN c / 12.92 ELSE power((c + 0.055) / 1.055, 2.4) END
+$$;
+
+CREATE OR REPLACE FUNCTION lab_f(t DOUBLE PRECISION)
+RETURNS DOUBLE PRECISION
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT CASE WHEN t > 216.0 / 24389.0 THEN cbrt(t) ELSE (24389.0 / 27.0 * t + 16.0) / 116.0 END
+$$;
+
+-- `{L, a, b}` for a `#RRGGBB` colour.
+CREATE OR REPLACE FUNCTION hex_color_lab(hex TEXT)
+RETURNS DOUBLE PRECISION[]
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT ARRAY[
+        116.0 * fy - 16.0,
+        500.0 * (fx - fy),
+        200.0 * (fy - fz)
+    ]
+    FROM (
+        SELECT lab_f((0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047) AS fx,
+               lab_f(0.2126729 * r + 0.7151522 * g + 0.0721750 * b) AS fy,
+               lab_f((0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883) AS fz
+        FROM (
+            SELECT srgb_to_linear(('x' || substr(hex, 2, 2))::bit(8)::int / 255.0) AS r,
+                   srgb_to_linear(('x' || substr(hex, 4, 2))::bit(8)::int / 255.0) AS g,
+                   srgb_to_linear(('x' || substr(hex, 6, 2))::bit(8)::int / 255.0) AS b
+        ) linear_rgb
+    ) f
+$$;
+
+-- CIE76 ΔE between two `#RRGGBB` colours.
+CREATE OR REPLACE FUNCTION hex_color_lab_distance(a TEXT, b TEXT)
+RETURNS DOUBLE PRECISION
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT sqrt(power(la[1] - lb[1], 2) + power(la[2] - lb[2], 2) + power(la[3] - lb[3], 2))
+    FROM (SELECT hex_color_lab(a) AS la, hex_color_lab(b) AS lb) lab
+$$;
+
+-- Lab cell of a colour: L in 0..6, a and b (offset by 128) in 0..15.
+CREATE OR REPLACE FUNCTION lab_color_bin(lab DOUBLE PRECISION[])
+RETURNS INTEGER
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT LEAST(GREATEST(floor(lab[1] / 16.0)::int, 0), 6) * 256
+         + LEAST(GREATEST(floor((lab[2] + 128.0) / 16.0)::int, 0), 15) * 16
+         + LEAST(GREATEST(floor((lab[3] + 128.0) / 16.0)::int, 0), 15)
+$$;
+
+-- Distinct cells of a palette; NULL when there is nothing to index.
+CREATE OR REPLACE FUNCTION color_palette_bins(palette JSONB)
+RETURNS INTEGER[]
+LANGUAGE SQL IMMUTABLE AS $$
+    SELECT array_agg(DISTINCT lab_color_bin(hex_color_lab(p.color)) ORDER BY lab_color_bin(hex_color_lab(p.color)))
+    FROM jsonb_array_elements_text(
+        CASE WHEN jsonb_typeof(palette) = 'array' THEN palette ELSE '[]'::jsonb END
+    ) p(color)
+    WHERE p.color ~ '^#[0-9A-Fa-f]{6}$'
+$$;
+
+ALTER TABLE letterings ADD COLUMN IF NOT EXISTS ml_color_bins INTEGER[];
+
+CREATE OR REPLACE FUNCTION update_lettering_color_bins()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.ml_color_bins := color_palette_bins(NEW.ml_color_palette);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_lettering_color_bins ON letterings;
+CREATE TRIGGER trg_lettering_color_bins
+    BEFORE INSERT OR UPDATE OF ml_color_palette ON letterings
+    FOR EACH ROW EXECUTE FUNCTION update_lettering_color_bins();
+
+UPDATE letterings
+SET ml_color_bins = color_palette_bins(ml_color_palette)
+WHERE ml_color_palette IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_color_bins
+    ON letterings USING GIN (ml_color_bins);
@@ -0,0 +1,6 @@
+-- Letterings hidden automatically once weighted reports crossed the
+-- configured threshold. The status they held is kept so clearing the
+-- reports puts them back where they were.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS auto_hidden_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS auto_hidden_from_status TEXT;
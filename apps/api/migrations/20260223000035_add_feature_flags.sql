@@ -0,0 +1,10 @@
+-- Percentage rollouts of new query paths; mirrored into Redis at startup and
+-- on every change. Flags without a row send all traffic to the control path.
+CREATE TABLE IF NOT EXISTS feature_flags (
+    name TEXT PRIMARY KEY,
+    rollout_percent SMALLINT NOT NULL DEFAULT 0,
+    updated_by TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_feature_flags_rollout_percent
+        CHECK (rollout_percent BETWEEN 0 AND 100)
+);
@@ -0,0 +1,13 @@
+-- Whether letterings in a city may be listed or opened individually. Regions
+-- with discoverability disabled only contribute to aggregate statistics.
+CREATE OR REPLACE FUNCTION city_discoverable(p_city_id UUID)
+RETURNS BOOLEAN
+LANGUAGE SQL STABLE AS $$
+    SELECT COALESCE(
+        (SELECT rp.discoverability_enabled
+         FROM cities c
+         JOIN region_policies rp ON rp.country_code = c.country_code
+         WHERE c.id = p_city_id),
+        true
+    )
+$$;
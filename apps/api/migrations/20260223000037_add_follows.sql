@@ -0,0 +1,36 @@
+-- Contributors a user follows to hear about their new approvals. A follow
+-- targets either a contributor tag (which anonymous uploads also carry) or
+-- a user account, never both.
+CREATE TABLE IF NOT EXISTS follows (
+    id UUID PRIMARY KEY,
+    follower_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    contributor_tag TEXT,
+    followed_user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    -- Approvals up to here have been evaluated.
+    last_checked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_follow_one_target
+        CHECK ((contributor_tag IS NULL) <> (followed_user_id IS NULL)),
+    CONSTRAINT chk_follow_not_self
+        CHECK (followed_user_id IS DISTINCT FROM follower_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_follows_unique_tag
+    ON follows(follower_id, contributor_tag)
+    WHERE contributor_tag IS NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_follows_unique_user
+    ON follows(follower_id, followed_user_id)
+    WHERE followed_user_id IS NOT NULL;
+
+-- Follower counts on contributor profiles.
+CREATE INDEX IF NOT EXISTS idx_follows_contributor_tag
+    ON follows(contributor_tag)
+    WHERE contributor_tag IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_follows_followed_user
+    ON follows(followed_user_id)
+    WHERE followed_user_id IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_follows_due
+    ON follows(last_checked_at);
@@ -0,0 +1,4 @@
+-- Like rollbacks select one identity's likes (an address or a network via
+-- `<<=`, which btree on inet supports) over a time window.
+CREATE INDEX IF NOT EXISTS idx_likes_user_ip_created_at
+    ON likes(user_ip, created_at);
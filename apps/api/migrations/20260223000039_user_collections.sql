@@ -0,0 +1,42 @@
+-- Collections owned by user accounts, ordered, and shareable by slug while
+-- public. Collections created before accounts keep their creator_tag and
+-- have no owner, so nobody can edit them.
+ALTER TABLE collections
+    ADD COLUMN IF NOT EXISTS user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS slug TEXT,
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+ALTER TABLE collections ALTER COLUMN creator_tag DROP NOT NULL;
+UPDATE collections SET is_public = true WHERE is_public IS NULL;
+ALTER TABLE collections ALTER COLUMN is_public SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_collections_slug
+    ON collections(slug)
+    WHERE slug IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_collections_user
+    ON collections(user_id, created_at DESC)
+    WHERE user_id IS NOT NULL;
+
+ALTER TABLE collection_items
+    ADD COLUMN IF NOT EXISTS position INTEGER,
+    ADD COLUMN IF NOT EXISTS added_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- Existing items keep the newest-first order they were listed in.
+UPDATE collection_items ci
+SET position = ordered.position
+FROM (
+    SELECT ci2.collection_id, ci2.lettering_id,
+           (ROW_NUMBER() OVER (PARTITION BY ci2.collection_id ORDER BY l.created_at DESC) - 1)::int AS position
+    FROM collection_items ci2
+    JOIN letterings l ON l.id = ci2.lettering_id
+) ordered
+WHERE ci.collection_id = ordered.collection_id
+  AND ci.lettering_id = ordered.lettering_id
+  AND ci.position IS NULL;
+
+ALTER TABLE collection_items ALTER COLUMN position SET DEFAULT 0;
+ALTER TABLE collection_items ALTER COLUMN position SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_collection_items_position
+    ON collection_items(collection_id, position);
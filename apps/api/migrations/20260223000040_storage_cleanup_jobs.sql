@@ -0,0 +1,21 @@
+-- Storage objects left to delete after their database record is gone.
+-- A job is written in the same transaction that removes the record, so the
+-- database never points at deleted files; the objects go afterwards, with
+-- retries by the storage GC worker.
+CREATE TABLE IF NOT EXISTS storage_cleanup_jobs (
+    id UUID PRIMARY KEY,
+    -- Not a foreign key: the lettering is deleted with the job's creation.
+    lettering_id UUID,
+    -- Keys still to delete; failed attempts keep only the ones that failed.
+    object_keys TEXT[] NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    -- Also the claim lease: claiming a job moves this into the future.
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_storage_cleanup_jobs_due
+    ON storage_cleanup_jobs(next_attempt_at)
+    WHERE completed_at IS NULL;
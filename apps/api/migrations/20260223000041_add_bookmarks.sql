@@ -0,0 +1,11 @@
+-- Private bookmarks. Unlike likes, they are tied to an account and never
+-- shown to anyone but their owner.
+CREATE TABLE IF NOT EXISTS bookmarks (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, lettering_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bookmarks_user_created
+    ON bookmarks(user_id, created_at DESC);
@@ -0,0 +1,24 @@
+-- Escalation of items left pending too long. Each step raises the item's
+-- review priority, which the moderation queue and /moderation/next sort on.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS review_priority INT NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS escalated_at TIMESTAMPTZ,
+    -- 'secondary' once routed to the escalation reviewer pool.
+    ADD COLUMN IF NOT EXISTS review_pool TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_pending_escalation
+    ON letterings(created_at)
+    WHERE status = 'PENDING';
+
+-- One row per escalation step, for weekly escalation metrics.
+CREATE TABLE IF NOT EXISTS moderation_escalations (
+    id UUID PRIMARY KEY,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    level INT NOT NULL,
+    review_pool TEXT,
+    pending_since TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_escalations_created_at
+    ON moderation_escalations(created_at);
@@ -0,0 +1,11 @@
+-- Browse pages by script and style: counts per value and the letterings of
+-- one value, over public letterings the classifier was confident about.
+CREATE INDEX IF NOT EXISTS idx_letterings_browse_script
+    ON letterings(ml_script, created_at DESC)
+    WHERE status = 'APPROVED' AND ml_script IS NOT NULL
+      AND NOT ('script' = ANY(ml_low_confidence_fields));
+
+CREATE INDEX IF NOT EXISTS idx_letterings_browse_style
+    ON letterings(ml_style, created_at DESC)
+    WHERE status = 'APPROVED' AND ml_style IS NOT NULL
+      AND NOT ('style' = ANY(ml_low_confidence_fields));
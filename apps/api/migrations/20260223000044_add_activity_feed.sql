@@ -0,0 +1,88 @@
+-- Personal activity feeds. Triggers record what happened in
+-- `activity_outbox` in the same transaction as the change; the activity
+-- fan-out worker turns each row into `activity_events` for the users it
+-- concerns and marks it processed.
+CREATE TABLE IF NOT EXISTS activity_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    -- FOLLOWED_UPLOAD, COMMENT_REPLY or LETTERING_LIKED.
+    kind TEXT NOT NULL,
+    lettering_id UUID NOT NULL,
+    comment_id UUID,
+    actor_user_id UUID,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    processed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_outbox_unprocessed
+    ON activity_outbox(id)
+    WHERE processed_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS activity_events (
+    id UUID PRIMARY KEY,
+    -- Whose feed the event is in.
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    outbox_id BIGINT NOT NULL,
+    kind TEXT NOT NULL,
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    actor_user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    UNIQUE (outbox_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_events_feed
+    ON activity_events(user_id, created_at DESC, id DESC);
+
+-- Approvals: new uploads for the uploader's followers.
+CREATE OR REPLACE FUNCTION record_approval_activity()
+RETURNS trigger AS $$
+BEGIN
+    IF NEW.to_status = 'APPROVED' AND NEW.from_status IS DISTINCT FROM 'APPROVED' THEN
+        INSERT INTO activity_outbox (kind, lettering_id, occurred_at)
+        VALUES ('FOLLOWED_UPLOAD', NEW.lettering_id, NEW.created_at);
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_record_approval_activity ON lettering_status_history;
+
+CREATE TRIGGER trg_record_approval_activity
+AFTER INSERT ON lettering_status_history
+FOR EACH ROW
+EXECUTE FUNCTION record_approval_activity();
+
+-- Replies: for the author of the comment replied to.
+CREATE OR REPLACE FUNCTION record_reply_activity()
+RETURNS trigger AS $$
+BEGIN
+    INSERT INTO activity_outbox (kind, lettering_id, comment_id, actor_user_id, occurred_at)
+    VALUES ('COMMENT_REPLY', NEW.lettering_id, NEW.id, NEW.user_id, NEW.created_at);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_record_reply_activity ON comments;
+
+CREATE TRIGGER trg_record_reply_activity
+AFTER INSERT ON comments
+FOR EACH ROW
+WHEN (NEW.parent_id IS NOT NULL)
+EXECUTE FUNCTION record_reply_activity();
+
+-- Likes: for the uploader. Likes are per client, so there is no actor.
+CREATE OR REPLACE FUNCTION record_like_activity()
+RETURNS trigger AS $$
+BEGIN
+    INSERT INTO activity_outbox (kind, lettering_id, occurred_at)
+    VALUES ('LETTERING_LIKED', NEW.lettering_id, NEW.created_at);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trg_record_like_activity ON likes;
+
+CREATE TRIGGER trg_record_like_activity
+AFTER INSERT ON likes
+FOR EACH ROW
+EXECUTE FUNCTION record_like_activity();
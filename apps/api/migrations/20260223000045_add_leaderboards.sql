@@ -0,0 +1,18 @@
+-- Contributor leaderboards, recomputed by the analytics worker. One row per
+-- ranked contributor per period (weekly, monthly, all-time) and metric
+-- (approved_uploads, likes, cities).
+CREATE TABLE IF NOT EXISTS leaderboard_entries (
+    period TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    contributor_tag TEXT NOT NULL,
+    rank INT NOT NULL,
+    value BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (period, metric, contributor_tag)
+);
+
+CREATE INDEX IF NOT EXISTS idx_leaderboard_entries_rank
+    ON leaderboard_entries(period, metric, rank);
+
+CREATE INDEX IF NOT EXISTS idx_likes_created_at
+    ON likes(created_at);
@@ -0,0 +1,20 @@
+-- Opt-in weekly email digests. A row is created when a user opts in; the
+-- digest worker sends on Mondays at `send_hour` in the user's `timezone`,
+-- written in `locale`. `unsubscribe_token` backs the link in each email so
+-- recipients can opt out without signing in.
+CREATE TABLE IF NOT EXISTS digest_subscriptions (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    -- IANA name, checked against pg_timezone_names when saved.
+    timezone TEXT NOT NULL DEFAULT 'UTC',
+    locale TEXT NOT NULL DEFAULT 'en',
+    send_hour INT NOT NULL DEFAULT 9 CHECK (send_hour BETWEEN 0 AND 23),
+    unsubscribe_token UUID NOT NULL UNIQUE DEFAULT uuid_generate_v4(),
+    last_sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_digest_subscriptions_due
+    ON digest_subscriptions(last_sent_at NULLS FIRST)
+    WHERE enabled;
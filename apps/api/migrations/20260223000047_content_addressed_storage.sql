@@ -0,0 +1,12 @@
+-- Reference counts for content-addressed storage objects. Served images are
+-- stored under `sha256/{aa}/{hash}.{ext}` keys (region-prefixed where
+-- routed), shared by every row that uploads identical bytes. An object is
+-- deleted once its count drops to zero. Keys from before content addressing
+-- have no row here and keep being deleted with their lettering.
+CREATE TABLE IF NOT EXISTS storage_objects (
+    key TEXT PRIMARY KEY,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    ref_count INT NOT NULL CHECK (ref_count >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
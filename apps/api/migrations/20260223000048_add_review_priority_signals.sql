@@ -0,0 +1,23 @@
+-- Engagement signals for moderation priority. The review priority job
+-- scores open reports, recent traffic and NSFW likelihood into
+-- `priority_boost` and keeps it added into `review_priority`, on top of
+-- what escalation or the comment prefilter set.
+ALTER TABLE letterings
+    -- Classifier probability in [0, 1]; NULL falls back to `nsfw_flagged`.
+    ADD COLUMN IF NOT EXISTS nsfw_score REAL,
+    ADD COLUMN IF NOT EXISTS priority_boost INT NOT NULL DEFAULT 0;
+
+ALTER TABLE comments
+    ADD COLUMN IF NOT EXISTS priority_boost INT NOT NULL DEFAULT 0;
+
+-- Detail views per lettering and hour. Counts only; nothing about the
+-- viewer is kept. Rows older than a week are pruned by the job.
+CREATE TABLE IF NOT EXISTS lettering_view_counts (
+    lettering_id UUID NOT NULL REFERENCES letterings(id) ON DELETE CASCADE,
+    hour TIMESTAMPTZ NOT NULL,
+    count INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (lettering_id, hour)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lettering_view_counts_hour
+    ON lettering_view_counts(hour);
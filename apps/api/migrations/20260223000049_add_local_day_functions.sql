@@ -0,0 +1,15 @@
+-- Day boundaries in a given IANA time zone rather than the server's, for
+-- daily quotas and analytics. A local day may be 23 or 25 hours long across
+-- a daylight saving change, so it is bounded by local midnights, not by a
+-- start plus 24 hours.
+CREATE OR REPLACE FUNCTION local_day_start(p_at TIMESTAMPTZ, p_timezone TEXT)
+RETURNS TIMESTAMPTZ
+LANGUAGE SQL STABLE AS $$
+    SELECT date_trunc('day', p_at AT TIME ZONE p_timezone) AT TIME ZONE p_timezone
+$$;
+
+CREATE OR REPLACE FUNCTION local_date(p_at TIMESTAMPTZ, p_timezone TEXT)
+RETURNS DATE
+LANGUAGE SQL STABLE AS $$
+    SELECT (p_at AT TIME ZONE p_timezone)::date
+$$;
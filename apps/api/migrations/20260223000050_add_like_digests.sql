@@ -0,0 +1,10 @@
+-- Like notifications are batched per lettering. The like digest job counts
+-- likes after `notified_through` into one LETTERING_LIKES notification to
+-- the uploader at most once per window, and folds them into the previous
+-- one while it is still unread.
+CREATE TABLE IF NOT EXISTS like_digest_marks (
+    lettering_id UUID PRIMARY KEY REFERENCES letterings(id) ON DELETE CASCADE,
+    notified_through TIMESTAMPTZ NOT NULL,
+    notified_at TIMESTAMPTZ NOT NULL,
+    notification_id UUID
+);
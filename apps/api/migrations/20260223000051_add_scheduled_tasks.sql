@@ -0,0 +1,28 @@
+-- Cron-style scheduled tasks. Schedules default in code and can be
+-- overridden by the SCHEDULES setting, then per task by an admin here
+-- (a NULL cron keeps the configured schedule and only toggles `enabled`).
+CREATE TABLE IF NOT EXISTS scheduled_tasks (
+    name TEXT PRIMARY KEY,
+    cron TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT true,
+    updated_by TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One row per fired tick. The Redis tick lock decides which instance runs
+-- a tick; the unique key backs it up should the lock ever be lost.
+CREATE TABLE IF NOT EXISTS scheduled_task_runs (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    scheduled_for TIMESTAMPTZ NOT NULL,
+    instance TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'RUNNING'
+        CHECK (status IN ('RUNNING', 'SUCCEEDED', 'FAILED', 'SKIPPED')),
+    error TEXT,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    UNIQUE (name, scheduled_for)
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_task_runs_name_started
+    ON scheduled_task_runs (name, started_at DESC);
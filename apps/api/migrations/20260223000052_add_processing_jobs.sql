@@ -0,0 +1,15 @@
+-- Progress of each upload through its processing stages, for clients
+-- waiting on an upload. The virus scan and thumbnails run during the upload
+-- request; ML runs afterwards on the queue.
+CREATE TABLE IF NOT EXISTS processing_jobs (
+    lettering_id UUID PRIMARY KEY REFERENCES letterings(id) ON DELETE CASCADE,
+    virus_scan TEXT NOT NULL DEFAULT 'PENDING',
+    thumbnails TEXT NOT NULL DEFAULT 'PENDING',
+    ml TEXT NOT NULL DEFAULT 'PENDING',
+    ml_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (virus_scan IN ('PENDING', 'RUNNING', 'RETRYING', 'DONE', 'FAILED', 'SKIPPED')),
+    CHECK (thumbnails IN ('PENDING', 'RUNNING', 'RETRYING', 'DONE', 'FAILED', 'SKIPPED')),
+    CHECK (ml IN ('PENDING', 'RUNNING', 'RETRYING', 'DONE', 'FAILED', 'SKIPPED'))
+);
@@ -0,0 +1,36 @@
+-- Activity counted once per time bucket by the analytics rollup, so
+-- dashboards read a handful of rows instead of counting letterings, likes
+-- and comments on every request. Each bucket has a row per country with
+-- activity plus a '*' row for all countries, which is written even for
+-- buckets without any. Approvals count by when they happened, from the
+-- status history; active contributors are distinct uploading tags.
+CREATE TABLE IF NOT EXISTS analytics_hourly_rollups (
+    hour_start TIMESTAMPTZ NOT NULL,
+    country_code TEXT NOT NULL,
+    uploads INTEGER NOT NULL DEFAULT 0,
+    approvals INTEGER NOT NULL DEFAULT 0,
+    likes INTEGER NOT NULL DEFAULT 0,
+    comments INTEGER NOT NULL DEFAULT 0,
+    active_contributors INTEGER NOT NULL DEFAULT 0,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (hour_start, country_code)
+);
+
+-- Days run midnight to midnight in DAY_BOUNDARY_TIMEZONE.
+CREATE TABLE IF NOT EXISTS analytics_daily_rollups (
+    date DATE NOT NULL,
+    country_code TEXT NOT NULL,
+    uploads INTEGER NOT NULL DEFAULT 0,
+    approvals INTEGER NOT NULL DEFAULT 0,
+    likes INTEGER NOT NULL DEFAULT 0,
+    comments INTEGER NOT NULL DEFAULT 0,
+    active_contributors INTEGER NOT NULL DEFAULT 0,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (date, country_code)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lettering_status_history_approved_created
+    ON lettering_status_history (created_at)
+    WHERE to_status = 'APPROVED';
+CREATE INDEX IF NOT EXISTS idx_likes_created_at ON likes (created_at);
+CREATE INDEX IF NOT EXISTS idx_comments_created_at ON comments (created_at);
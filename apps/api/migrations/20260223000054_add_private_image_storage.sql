@@ -0,0 +1,15 @@
+-- Images of letterings that are not approved live in the private buckets.
+-- images_public says which bucket a lettering's image and thumbnails are in
+-- and original_private whether its original has moved out of the public
+-- bucket; the image publisher moves whatever disagrees with the status.
+-- Everything uploaded before now is in the public buckets, so existing
+-- letterings start out public and are swept private unless approved.
+ALTER TABLE letterings
+    ADD COLUMN IF NOT EXISTS images_public BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS original_private BOOLEAN NOT NULL DEFAULT true;
+
+UPDATE letterings SET images_public = true, original_private = false;
+
+CREATE INDEX IF NOT EXISTS idx_letterings_misplaced_images
+    ON letterings (id)
+    WHERE images_public <> (status = 'APPROVED') OR NOT original_private;
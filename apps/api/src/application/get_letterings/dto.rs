@@ -10,3 +10,11 @@ pub struct PaginatedResponse {
     pub limit: i64,
     pub offset: i64,
 }
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DiscoverResponse {
+    pub letterings: Vec<Lettering>,
+    /// Every id served so far; pass back as `seen` on the next request.
+    pub seen: String,
+}
@@ -6,5 +6,8 @@ use uuid::Uuid;
 #[ts(export)]
 pub struct AddCommentRequest {
     pub lettering_id: Uuid,
+    /// Comment being replied to, if any.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
     pub content: String,
 }
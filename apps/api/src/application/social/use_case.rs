@@ -1,7 +1,7 @@
 use super::dto::AddCommentRequest;
 use crate::domain::lettering::errors::DomainError;
 use crate::domain::social::{
-    comment::{Comment, CommentModerationInput},
+    comment::{Comment, CommentListQuery, CommentModerationInput, CommentPage},
     repository::SocialRepository,
 };
 use uuid::Uuid;
@@ -34,6 +34,7 @@ impl SocialUseCase {
         self.repository
             .add_comment(
                 request.lettering_id,
+                request.parent_id,
                 user_id,
                 request.content,
                 user_ip,
@@ -42,7 +43,11 @@ impl SocialUseCase {
             .await
     }
 
-    pub async fn get_comments(&self, lettering_id: Uuid) -> Result<Vec<Comment>, DomainError> {
-        self.repository.get_comments(lettering_id).await
+    pub async fn list_comments(
+        &self,
+        lettering_id: Uuid,
+        query: &CommentListQuery,
+    ) -> Result<CommentPage, DomainError> {
+        self.repository.list_comments(lettering_id, query).await
     }
 }
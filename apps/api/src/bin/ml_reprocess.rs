@@ -0,0 +1,54 @@
+//! Queue a corpus-wide ML reprocessing run from the command line.
+//!
+//! ```text
+//! ml_reprocess [--from <RFC 3339>] [--to <RFC 3339>] [--missing-text]
+//!              [--model-version <version>] [--requested-by <name>]
+//! ```
+//!
+//! Only records the run; the reprocess worker in a running API instance feeds
+//! it to the ML queue. Follow progress with
+//! `GET /api/v1/admin/ml/reprocess/{id}`.
+
+use anyhow::Context;
+use api::{infrastructure::database::pool::create_pool, workers::ml_reprocess};
+use chrono::{DateTime, Utc};
+
+fn parse_time(flag: &str, value: Option<String>) -> anyhow::Result<DateTime<Utc>> {
+    let value = value.with_context(|| format!("{} needs a value", flag))?;
+    Ok(DateTime::parse_from_rfc3339(&value)
+        .with_context(|| format!("{} must be an RFC 3339 timestamp", flag))?
+        .with_timezone(&Utc))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+
+    let mut filter = ml_reprocess::ReprocessFilter::default();
+    let mut requested_by = "cli".to_string();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => filter.created_from = Some(parse_time("--from", args.next())?),
+            "--to" => filter.created_to = Some(parse_time("--to", args.next())?),
+            "--missing-text" => filter.missing_text_only = true,
+            "--model-version" => {
+                filter.model_version = Some(args.next().context("--model-version needs a value")?)
+            }
+            "--requested-by" => {
+                requested_by = args.next().context("--requested-by needs a value")?
+            }
+            other => anyhow::bail!("Unknown argument: {}", other),
+        }
+    }
+
+    let database_url = std::env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
+    let db = create_pool(&database_url, 1).await?;
+    let id = ml_reprocess::create_reprocess_run(&db, &requested_by, &filter).await?;
+    let total: i32 = sqlx::query_scalar("SELECT total FROM ml_reprocess_runs WHERE id = $1")
+        .bind(id)
+        .fetch_one(&db)
+        .await?;
+    println!("Queued reprocess run {} covering {} letterings", id, total);
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Window a leaderboard is computed over, ending at computation time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardPeriod {
+    Weekly,
+    Monthly,
+    AllTime,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [Self; 3] = [Self::Weekly, Self::Monthly, Self::AllTime];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "weekly" => Some(Self::Weekly),
+            "monthly" => Some(Self::Monthly),
+            "all-time" => Some(Self::AllTime),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::AllTime => "all-time",
+        }
+    }
+
+    /// Length of the trailing window in days; `None` for all time.
+    pub fn window_days(self) -> Option<i32> {
+        match self {
+            Self::Weekly => Some(7),
+            Self::Monthly => Some(30),
+            Self::AllTime => None,
+        }
+    }
+}
+
+/// What contributors are ranked by. Only publicly listed letterings count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardMetric {
+    /// Approved letterings uploaded in the window.
+    ApprovedUploads,
+    /// Likes given in the window to the contributor's letterings.
+    Likes,
+    /// Distinct cities of the approved letterings uploaded in the window.
+    Cities,
+}
+
+impl LeaderboardMetric {
+    pub const ALL: [Self; 3] = [Self::ApprovedUploads, Self::Likes, Self::Cities];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|metric| metric.as_str() == value)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ApprovedUploads => "approved_uploads",
+            Self::Likes => "likes",
+            Self::Cities => "cities",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export)]
+pub struct LeaderboardEntry {
+    /// 1-based; tied contributors share a rank.
+    pub rank: i32,
+    pub contributor_tag: String,
+    #[ts(type = "number")]
+    pub value: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Leaderboards {
+    /// `weekly`, `monthly` or `all-time`.
+    pub period: String,
+    pub most_approved: Vec<LeaderboardEntry>,
+    pub most_liked: Vec<LeaderboardEntry>,
+    pub most_cities: Vec<LeaderboardEntry>,
+    /// When the analytics worker last computed these; absent before its
+    /// first pass.
+    pub computed_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periods_round_trip_through_their_path_names() {
+        for period in LeaderboardPeriod::ALL {
+            assert_eq!(LeaderboardPeriod::parse(period.as_str()), Some(period));
+        }
+        assert_eq!(LeaderboardPeriod::parse("daily"), None);
+        assert_eq!(LeaderboardPeriod::AllTime.window_days(), None);
+    }
+}
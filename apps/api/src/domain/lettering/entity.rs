@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::types::ipnetwork::IpNetwork;
 use ts_rs::TS;
 use uuid::Uuid;
@@ -165,7 +166,7 @@ impl Coordinates {
         }
         let lng = self.coordinates[0];
         let lat = self.coordinates[1];
-        lng >= -180.0 && lng <= 180.0 && lat >= -90.0 && lat <= 90.0
+        (-180.0..=180.0).contains(&lng) && (-90.0..=90.0).contains(&lat)
     }
 
     /// Returns the longitude component.
@@ -221,3 +222,114 @@ impl LetteringStatus {
         matches!(self, LetteringStatus::Pending | LetteringStatus::Reported)
     }
 }
+
+/// How precisely a lettering's location is shown publicly, chosen by the
+/// contributor at upload.
+///
+/// The true location is kept in `exact_location` for admins; `location`, which
+/// every public query and export reads, holds the point derived here.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum LocationPrivacy {
+    /// Public location is the true location
+    #[default]
+    Exact,
+
+    /// Public location is moved 50-100m in a direction fixed per lettering
+    Fuzzed,
+
+    /// Public location is the city centre
+    City,
+}
+
+impl LocationPrivacy {
+    /// Smallest and largest distance in metres a fuzzed point is moved.
+    pub const FUZZ_RADIUS_M: (f64, f64) = (50.0, 100.0);
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocationPrivacy::Exact => "exact",
+            LocationPrivacy::Fuzzed => "fuzzed",
+            LocationPrivacy::City => "city",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "exact" => Some(LocationPrivacy::Exact),
+            "fuzzed" => Some(LocationPrivacy::Fuzzed),
+            "city" => Some(LocationPrivacy::City),
+            _ => None,
+        }
+    }
+
+    /// The `(longitude, latitude)` to publish for a lettering at `exact`
+    /// found in a city centred at `city_center`.
+    ///
+    /// The fuzz offset is derived from a hash of the lettering id, so it is
+    /// stable for a lettering and can't be averaged away by re-reading.
+    pub fn public_point(&self, id: Uuid, exact: (f64, f64), city_center: (f64, f64)) -> (f64, f64) {
+        match self {
+            LocationPrivacy::Exact => exact,
+            LocationPrivacy::City => city_center,
+            LocationPrivacy::Fuzzed => {
+                let digest = Sha256::digest(id.as_bytes());
+                let unit = |i: usize| {
+                    let word = u32::from_be_bytes(digest[i..i + 4].try_into().unwrap());
+                    word as f64 / u32::MAX as f64
+                };
+                let (min, max) = Self::FUZZ_RADIUS_M;
+                let distance = min + (max - min) * unit(0);
+                let bearing = std::f64::consts::TAU * unit(4);
+                let (lng, lat) = exact;
+                let dlat = distance * bearing.cos() / 111_320.0;
+                let dlng =
+                    distance * bearing.sin() / (111_320.0 * lat.to_radians().cos().max(0.01));
+                (lng + dlng, (lat + dlat).clamp(-90.0, 90.0))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Equirectangular distance in metres, accurate enough at these scales.
+    fn metres_between(a: (f64, f64), b: (f64, f64)) -> f64 {
+        let x = (b.0 - a.0).to_radians() * ((a.1 + b.1) / 2.0).to_radians().cos();
+        let y = (b.1 - a.1).to_radians();
+        (x * x + y * y).sqrt() * 6_371_000.0
+    }
+
+    #[test]
+    fn fuzzed_points_move_a_stable_50_to_100_metres() {
+        let exact = (77.5946, 12.9716);
+        let city = (77.6, 12.97);
+        for _ in 0..50 {
+            let id = Uuid::now_v7();
+            let public = LocationPrivacy::Fuzzed.public_point(id, exact, city);
+            let moved = metres_between(exact, public);
+            assert!((49.0..=101.0).contains(&moved), "moved {moved}m");
+            assert_eq!(
+                LocationPrivacy::Fuzzed.public_point(id, exact, city),
+                public
+            );
+        }
+    }
+
+    #[test]
+    fn exact_and_city_points() {
+        let id = Uuid::now_v7();
+        let exact = (77.5946, 12.9716);
+        let city = (77.6, 12.97);
+        assert_eq!(LocationPrivacy::Exact.public_point(id, exact, city), exact);
+        assert_eq!(LocationPrivacy::City.public_point(id, exact, city), city);
+        assert_eq!(
+            LocationPrivacy::parse(" Fuzzed "),
+            Some(LocationPrivacy::Fuzzed)
+        );
+        assert_eq!(LocationPrivacy::parse("precise"), None);
+    }
+}
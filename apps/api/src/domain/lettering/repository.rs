@@ -1,6 +1,7 @@
 use super::entity::Lettering;
 use super::errors::DomainError;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 #[async_trait]
@@ -11,7 +12,12 @@ pub trait LetteringRepository: Send + Sync {
     async fn update(&self, lettering: &Lettering) -> Result<Lettering, DomainError>;
     async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
     async fn search(&self, query: &str) -> Result<Vec<Lettering>, DomainError>;
-    async fn count_by_contributor_today(&self, contributor_tag: &str) -> Result<i64, DomainError>;
+    /// Letterings uploaded under `contributor_tag` at or after `since`.
+    async fn count_by_contributor_since(
+        &self,
+        contributor_tag: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64, DomainError>;
     async fn find_by_image_hash(&self, hash: &str) -> Result<Option<Lettering>, DomainError>;
     async fn find_by_contributor(
         &self,
@@ -26,4 +32,11 @@ pub trait LetteringRepository: Send + Sync {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Lettering>, DomainError>;
+    /// Approved letterings nearest to `id` by image embedding, with cosine
+    /// similarity. Empty when `id` has no embedding yet.
+    async fn find_similar(
+        &self,
+        id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<(Lettering, f32)>, DomainError>;
 }
@@ -0,0 +1,82 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A lettering by a contributor the user follows was approved.
+pub const FOLLOWED_UPLOAD: &str = "FOLLOWED_UPLOAD";
+/// Someone replied to one of the user's comments.
+pub const COMMENT_REPLY: &str = "COMMENT_REPLY";
+/// One of the user's uploads was liked.
+pub const LETTERING_LIKED: &str = "LETTERING_LIKED";
+
+/// One entry of a user's activity feed.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export)]
+pub struct ActivityItem {
+    pub id: Uuid,
+    /// `FOLLOWED_UPLOAD`, `COMMENT_REPLY` or `LETTERING_LIKED`.
+    pub kind: String,
+    pub lettering_id: Uuid,
+    pub thumbnail_small: String,
+    /// The reply, for `COMMENT_REPLY`.
+    pub comment_id: Option<Uuid>,
+    pub comment_excerpt: Option<String>,
+    /// Who did it, where known: the replier, or the followed contributor.
+    pub actor_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Position after the last item of a feed page: its time in microseconds and
+/// the id breaking ties. Clients get it as an opaque string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivityCursor {
+    pub created_at_micros: i64,
+    pub id: Uuid,
+}
+
+impl ActivityCursor {
+    pub fn after(item: &ActivityItem) -> Self {
+        Self {
+            created_at_micros: item.created_at.timestamp_micros(),
+            id: item.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.created_at_micros, self.id))
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(value).ok()?).ok()?;
+        let (key, id) = raw.split_once(':')?;
+        Some(Self {
+            created_at_micros: key.parse().ok()?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ActivityPage {
+    pub items: Vec<ActivityItem>,
+    /// Pass back as `cursor` for the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_and_rejects_garbage() {
+        let cursor = ActivityCursor {
+            created_at_micros: 1_771_804_800_000_000,
+            id: Uuid::now_v7(),
+        };
+        assert_eq!(ActivityCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(ActivityCursor::decode("not a cursor"), None);
+    }
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A lettering a user bookmarked. Bookmarks are private to their owner.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export)]
+pub struct Bookmark {
+    pub lettering_id: Uuid,
+    pub image_url: String,
+    pub thumbnail_small: String,
+    pub detected_text: Option<String>,
+    pub contributor_tag: String,
+    pub bookmarked_at: DateTime<Utc>,
+}
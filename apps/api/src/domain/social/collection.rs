@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Longest collection name, in characters.
+pub const MAX_COLLECTION_NAME_CHARS: usize = 80;
+
+/// Longest collection description, in characters.
+pub const MAX_COLLECTION_DESCRIPTION_CHARS: usize = 500;
+
+/// Most letterings one collection can hold.
+pub const MAX_COLLECTION_ITEMS: i64 = 500;
+
+/// A named, ordered set of letterings. Collections made before user accounts
+/// have no owner and can no longer be edited.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export)]
+pub struct Collection {
+    pub id: Uuid,
+    pub owner_id: Option<Uuid>,
+    pub name: String,
+    pub description: Option<String>,
+    /// The owner's display name, or the tag an ownerless collection was
+    /// created under.
+    pub creator_name: Option<String>,
+    pub is_public: bool,
+    /// Share link slug; only resolves while the collection is public.
+    pub slug: Option<String>,
+    #[ts(type = "number")]
+    pub item_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A lettering in a collection, as listed publicly.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export)]
+pub struct CollectionItem {
+    pub lettering_id: Uuid,
+    pub position: i32,
+    pub image_url: String,
+    pub thumbnail_small: String,
+    pub detected_text: Option<String>,
+    pub contributor_tag: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Name, description and visibility of a collection being created or edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectionDraft {
+    pub name: String,
+    pub description: Option<String>,
+    pub is_public: bool,
+}
+
+/// Share slug for a collection: the name lowercased to ASCII words joined by
+/// `-`, then `suffix` so collections with the same name get distinct links.
+pub fn share_slug(name: &str, suffix: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let mut base = String::new();
+    for word in words {
+        if base.len() + word.len() + 1 > 48 {
+            break;
+        }
+        if !base.is_empty() {
+            base.push('-');
+        }
+        base.push_str(&word);
+    }
+    if base.is_empty() {
+        format!("collection-{}", suffix)
+    } else {
+        format!("{}-{}", base, suffix)
+    }
+}
+
+/// The order of a collection's items after moving `requested` to the front.
+/// Items not named keep their relative order after them; naming an item
+/// twice or one not in the collection is an error.
+pub fn apply_order(current: &[Uuid], requested: &[Uuid]) -> Result<Vec<Uuid>, String> {
+    let mut order: Vec<Uuid> = Vec::with_capacity(current.len());
+    for id in requested {
+        if order.contains(id) {
+            return Err(format!("Lettering {} is listed more than once", id));
+        }
+        if !current.contains(id) {
+            return Err(format!("Lettering {} is not in this collection", id));
+        }
+        order.push(*id);
+    }
+    order.extend(current.iter().filter(|id| !requested.contains(id)));
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_keep_ascii_words_of_the_name() {
+        assert_eq!(
+            share_slug("Hand-painted signs of Mysuru!", "a1b2c3"),
+            "hand-painted-signs-of-mysuru-a1b2c3"
+        );
+        assert_eq!(share_slug("ಮೈಸೂರು", "a1b2c3"), "collection-a1b2c3");
+        let long = share_slug(&"signboards ".repeat(20), "a1b2c3");
+        assert!(long.len() <= 48 + 7);
+        assert!(long.ends_with("-a1b2c3"));
+    }
+
+    #[test]
+    fn reordering_moves_named_items_first() {
+        let ids: Vec<Uuid> = (0..4).map(|_| Uuid::now_v7()).collect();
+        assert_eq!(
+            apply_order(&ids, &[ids[2], ids[0]]).unwrap(),
+            vec![ids[2], ids[0], ids[1], ids[3]]
+        );
+        assert!(apply_order(&ids, &[ids[1], ids[1]]).is_err());
+        assert!(apply_order(&ids, &[Uuid::now_v7()]).is_err());
+    }
+}
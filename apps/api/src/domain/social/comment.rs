@@ -1,3 +1,4 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::types::ipnetwork::IpNetwork;
@@ -9,6 +10,8 @@ use uuid::Uuid;
 pub struct Comment {
     pub id: Uuid,
     pub lettering_id: Uuid,
+    /// Top-level comment this replies to.
+    pub parent_id: Option<Uuid>,
     pub content: String,
     pub user_id: Option<Uuid>,
     pub commenter_name: Option<String>,
@@ -38,3 +41,106 @@ pub struct CommentModerationInput {
     pub moderated_by: Option<String>,
     pub moderation_reason: Option<String>,
 }
+
+/// Reactions a user can leave on a comment.
+pub const REACTION_KINDS: &[&str] = &["like", "love", "laugh", "insightful"];
+
+/// A comment as shown publicly. Hidden or deleted comments that still have
+/// visible replies are returned as tombstones, without content or author, so
+/// the thread keeps its shape.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, sqlx::FromRow)]
+#[ts(export)]
+pub struct CommentView {
+    pub id: Uuid,
+    pub lettering_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub content: Option<String>,
+    pub commenter_name: Option<String>,
+    pub reactions_count: i32,
+    /// Visible replies.
+    pub reply_count: i32,
+    pub tombstone: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum CommentSort {
+    #[default]
+    Newest,
+    Oldest,
+    /// Most reactions first.
+    Top,
+}
+
+/// Position after the last comment of a page: the sort key (creation time in
+/// microseconds, or the reaction count for `top`) and the id breaking ties.
+/// Clients get it as an opaque string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommentCursor {
+    pub key: i64,
+    pub id: Uuid,
+}
+
+impl CommentCursor {
+    pub fn after(comment: &CommentView, sort: CommentSort) -> Self {
+        let key = match sort {
+            CommentSort::Top => comment.reactions_count as i64,
+            CommentSort::Newest | CommentSort::Oldest => comment.created_at.timestamp_micros(),
+        };
+        Self {
+            key,
+            id: comment.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.key, self.id))
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(value).ok()?).ok()?;
+        let (key, id) = raw.split_once(':')?;
+        Some(Self {
+            key: key.parse().ok()?,
+            id: id.parse().ok()?,
+        })
+    }
+}
+
+/// Which comments to list: top-level ones, or the replies under `parent_id`.
+#[derive(Debug, Clone, Default)]
+pub struct CommentListQuery {
+    pub parent_id: Option<Uuid>,
+    pub sort: CommentSort,
+    pub cursor: Option<CommentCursor>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CommentPage {
+    pub items: Vec<CommentView>,
+    /// Pass back as `cursor` for the next page; absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips_and_rejects_garbage() {
+        let cursor = CommentCursor {
+            key: 1_771_804_800_000_000,
+            id: Uuid::now_v7(),
+        };
+        assert_eq!(CommentCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(CommentCursor::decode("not a cursor"), None);
+        assert_eq!(
+            CommentCursor::decode(&URL_SAFE_NO_PAD.encode("12:not-a-uuid")),
+            None
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod activity;
+pub mod bookmark;
+pub mod collection;
 pub mod comment;
 pub mod like;
 pub mod repository;
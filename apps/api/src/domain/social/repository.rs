@@ -1,4 +1,9 @@
-use super::comment::{Comment, CommentModerationInput};
+use super::{
+    activity::{ActivityCursor, ActivityPage},
+    bookmark::Bookmark,
+    collection::{Collection, CollectionDraft, CollectionItem},
+    comment::{Comment, CommentListQuery, CommentModerationInput, CommentPage},
+};
 use crate::domain::lettering::errors::DomainError;
 use async_trait::async_trait;
 use uuid::Uuid;
@@ -13,12 +18,105 @@ pub trait SocialRepository: Send + Sync {
     async fn add_comment(
         &self,
         lettering_id: Uuid,
+        parent_id: Option<Uuid>,
         user_id: Uuid,
         content: String,
         user_ip: Option<&str>,
         moderation: CommentModerationInput,
     ) -> Result<Comment, DomainError>;
-    async fn get_comments(&self, lettering_id: Uuid) -> Result<Vec<Comment>, DomainError>;
+    /// Visible comments of a lettering, one page at a time, with tombstones
+    /// for hidden or deleted comments that still have visible replies.
+    async fn list_comments(
+        &self,
+        lettering_id: Uuid,
+        query: &CommentListQuery,
+    ) -> Result<CommentPage, DomainError>;
+    /// Add or remove `user_id`'s reaction of `kind`; returns whether it is now
+    /// set and the comment's reaction count.
+    async fn toggle_comment_reaction(
+        &self,
+        comment_id: Uuid,
+        user_id: Uuid,
+        kind: &str,
+    ) -> Result<(bool, i32), DomainError>;
     async fn has_liked(&self, lettering_id: Uuid, user_ip: &str) -> Result<bool, DomainError>;
     async fn get_likes_count(&self, lettering_id: Uuid) -> Result<i32, DomainError>;
+
+    /// Bookmark a publicly listed lettering for `user_id`; returns whether
+    /// the bookmark is new.
+    async fn add_bookmark(&self, user_id: Uuid, lettering_id: Uuid) -> Result<bool, DomainError>;
+    async fn remove_bookmark(&self, user_id: Uuid, lettering_id: Uuid) -> Result<(), DomainError>;
+    /// One page of `user_id`'s bookmarks, newest first, and how many there
+    /// are. Letterings no longer publicly listed are left out of both.
+    async fn list_bookmarks(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+        age_ack: bool,
+    ) -> Result<(Vec<Bookmark>, i64), DomainError>;
+    /// One page of `user_id`'s activity feed, newest first. Events about
+    /// letterings no longer publicly listed, or replies since removed, are
+    /// left out.
+    async fn list_activity(
+        &self,
+        user_id: Uuid,
+        cursor: Option<ActivityCursor>,
+        limit: i64,
+        age_ack: bool,
+    ) -> Result<ActivityPage, DomainError>;
+
+    /// A new collection of `owner_id`'s; a public one gets a share slug.
+    async fn create_collection(
+        &self,
+        owner_id: Uuid,
+        draft: &CollectionDraft,
+    ) -> Result<Collection, DomainError>;
+    /// Public collections, newest first.
+    async fn list_public_collections(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Collection>, DomainError>;
+    async fn list_user_collections(&self, owner_id: Uuid) -> Result<Vec<Collection>, DomainError>;
+    async fn find_collection(&self, id: Uuid) -> Result<Option<Collection>, DomainError>;
+    /// The public collection shared under `slug`.
+    async fn find_shared_collection(&self, slug: &str) -> Result<Option<Collection>, DomainError>;
+    /// Replace the name, description and visibility of one of `owner_id`'s
+    /// collections. Making it public keeps any earlier slug so old links work.
+    async fn update_collection(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        draft: &CollectionDraft,
+    ) -> Result<Collection, DomainError>;
+    async fn delete_collection(&self, id: Uuid, owner_id: Uuid) -> Result<(), DomainError>;
+    /// Publicly listed letterings of a collection, in the owner's order.
+    async fn list_collection_items(
+        &self,
+        id: Uuid,
+        age_ack: bool,
+    ) -> Result<Vec<CollectionItem>, DomainError>;
+    /// Append a publicly listed lettering to one of `owner_id`'s collections;
+    /// adding one already there changes nothing.
+    async fn add_collection_item(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        lettering_id: Uuid,
+    ) -> Result<(), DomainError>;
+    async fn remove_collection_item(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        lettering_id: Uuid,
+    ) -> Result<(), DomainError>;
+    /// Put `lettering_ids` first, in that order; items left out follow in
+    /// their current order.
+    async fn reorder_collection(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        lettering_ids: &[Uuid],
+    ) -> Result<(), DomainError>;
 }
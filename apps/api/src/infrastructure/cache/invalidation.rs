@@ -0,0 +1,262 @@
+//! Dropping cached reads when the letterings behind them change.
+//!
+//! Whatever creates, edits, moderates or deletes a lettering reports it to
+//! the [`CacheInvalidator`]: the lettering repository on its own writes, and
+//! the handlers and workers that update letterings in SQL of their own. The
+//! invalidator works out every cached read the lettering may appear in and
+//! drops it through the [`TieredCache`], on every instance:
+//!
+//! - the lettering by id, or that it was not found, and its context panel;
+//! - feeds: gallery pages, browse facets and map views;
+//! - its city page;
+//! - its contributor's page;
+//! - whole responses of the feed, the lettering and its city's stats kept
+//!   by the response cache.
+//!
+//! Keys for these reads are built here, so readers and the invalidator agree
+//! on them. Region aggregates and leaderboards only ever show counts and are
+//! left to their TTLs.
+//!
+//! [`CacheInvalidator::changed`] wakes the cache warmer after each change, so
+//! the heaviest reads are filled again before a user asks for them.
+
+use crate::{
+    domain::lettering::entity::Lettering, infrastructure::cache::tiered_cache::TieredCache,
+};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Prefix of every gallery page.
+pub const GALLERY_PREFIX: &str = "gallery:";
+
+/// Prefix of every browse facet list.
+pub const BROWSE_PREFIX: &str = "browse:";
+
+/// Prefix of every cached map view.
+pub const MAP_PREFIX: &str = "map:";
+
+pub fn lettering_key(id: Uuid) -> String {
+    format!("lettering:{}", id)
+}
+
+/// Marks a lettering id that was looked up and not found.
+pub fn missing_lettering_key(id: Uuid) -> String {
+    format!("lettering_missing:{}", id)
+}
+
+/// Prefix of every response kept by the response cache.
+pub const RESPONSE_PREFIX: &str = "response:";
+
+/// Prefix of the cached responses of `path`, whatever their query.
+pub fn response_prefix(path: &str) -> String {
+    format!("{}{}?", RESPONSE_PREFIX, path)
+}
+
+/// Prefix of a lettering's context panel, in every locale.
+pub fn lettering_context_prefix(id: Uuid) -> String {
+    format!("lettering_context:{}:", id)
+}
+
+/// Prefix of a city's page, in every locale.
+pub fn city_prefix(city_id: Uuid) -> String {
+    format!("city:{}:", city_id)
+}
+
+/// Prefix of every page of a contributor's letterings.
+pub fn contributor_prefix(tag: &str) -> String {
+    format!("contributor:{}:", tag)
+}
+
+/// A lettering that was created, edited, moderated or deleted. Its city and
+/// contributor, where known, say which of their pages to drop.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LetteringChange {
+    pub id: Uuid,
+    pub city_id: Option<Uuid>,
+    pub contributor_tag: Option<String>,
+}
+
+impl From<&Lettering> for LetteringChange {
+    fn from(lettering: &Lettering) -> Self {
+        Self {
+            id: lettering.id,
+            city_id: Some(lettering.city_id),
+            contributor_tag: Some(lettering.contributor_tag.clone()),
+        }
+    }
+}
+
+impl LetteringChange {
+    /// Exact keys, then key prefixes, of reads that may include the
+    /// lettering.
+    fn dependents(&self) -> (Vec<String>, Vec<String>) {
+        let mut prefixes = vec![
+            lettering_context_prefix(self.id),
+            GALLERY_PREFIX.to_string(),
+            BROWSE_PREFIX.to_string(),
+            MAP_PREFIX.to_string(),
+            response_prefix("/api/v1/letterings"),
+            response_prefix(&format!("/api/v1/letterings/{}", self.id)),
+        ];
+        prefixes.extend(self.city_id.map(city_prefix));
+        prefixes.extend(
+            self.city_id
+                .map(|city_id| response_prefix(&format!("/api/v1/cities/{}/stats", city_id))),
+        );
+        prefixes.extend(self.contributor_tag.as_deref().map(contributor_prefix));
+        (
+            vec![lettering_key(self.id), missing_lettering_key(self.id)],
+            prefixes,
+        )
+    }
+}
+
+struct Targets {
+    db: PgPool,
+    cache: Arc<TieredCache>,
+    changed: Notify,
+}
+
+impl Targets {
+    /// Drops the reads of every change, each shared prefix once.
+    async fn drop_reads(&self, changes: &[LetteringChange]) {
+        let mut keys = Vec::new();
+        let mut prefixes = Vec::new();
+        for change in changes {
+            let (exact, under) = change.dependents();
+            keys.extend(exact);
+            prefixes.extend(under);
+        }
+        prefixes.sort();
+        prefixes.dedup();
+        for key in &keys {
+            self.cache.invalidate(key).await;
+        }
+        for prefix in &prefixes {
+            self.cache.invalidate_prefix(prefix).await;
+        }
+        self.changed.notify_one();
+    }
+}
+
+/// Cheap-to-clone handle lettering writers report changes through.
+/// Failures to drop a key are logged by the cache; the read then lingers
+/// for its TTL at most.
+#[derive(Clone)]
+pub struct CacheInvalidator {
+    /// `None` when nothing is cached.
+    targets: Option<Arc<Targets>>,
+}
+
+impl CacheInvalidator {
+    /// Drops reads from `cache`, looking up letterings known only by id in
+    /// `db`.
+    pub fn new(db: PgPool, cache: Arc<TieredCache>) -> Self {
+        Self {
+            targets: Some(Arc::new(Targets {
+                db,
+                cache,
+                changed: Notify::new(),
+            })),
+        }
+    }
+
+    /// A handle that drops nothing.
+    pub fn disabled() -> Self {
+        Self { targets: None }
+    }
+
+    /// Resolves after reads are next dropped, or at once if some were
+    /// dropped since the last call. Never resolves when disabled.
+    pub async fn changed(&self) {
+        match &self.targets {
+            Some(targets) => targets.changed.notified().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Drops every read that may include `change`.
+    pub async fn lettering_changed(&self, change: &LetteringChange) {
+        if let Some(targets) = &self.targets {
+            targets.drop_reads(std::slice::from_ref(change)).await;
+        }
+    }
+
+    /// [`Self::lettering_changed`] for letterings known only by id. Their
+    /// city and contributor are read back, so call this after the change
+    /// commits and before any delete.
+    pub async fn letterings_changed(&self, ids: &[Uuid]) {
+        let Some(targets) = &self.targets else {
+            return;
+        };
+        if ids.is_empty() {
+            return;
+        }
+        let found = sqlx::query_as::<_, (Uuid, Uuid, String)>(
+            "SELECT id, city_id, contributor_tag FROM letterings WHERE id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_all(&targets.db)
+        .await;
+        let mut changes: Vec<LetteringChange> = match found {
+            Ok(rows) => rows
+                .into_iter()
+                .map(|(id, city_id, contributor_tag)| LetteringChange {
+                    id,
+                    city_id: Some(city_id),
+                    contributor_tag: Some(contributor_tag),
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to look up changed letterings: {}", e);
+                Vec::new()
+            }
+        };
+        // Anything not found still has its own reads dropped.
+        for id in ids {
+            if !changes.iter().any(|change| change.id == *id) {
+                changes.push(LetteringChange {
+                    id: *id,
+                    city_id: None,
+                    contributor_tag: None,
+                });
+            }
+        }
+        targets.drop_reads(&changes).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_reach_the_feeds_and_the_pages_they_appear_on() {
+        let id = Uuid::now_v7();
+        let city_id = Uuid::now_v7();
+        let change = LetteringChange {
+            id,
+            city_id: Some(city_id),
+            contributor_tag: Some("Walker".to_string()),
+        };
+        let (keys, prefixes) = change.dependents();
+        assert_eq!(keys, vec![lettering_key(id), missing_lettering_key(id)]);
+        assert!(prefixes.contains(&lettering_context_prefix(id)));
+        assert!(prefixes.contains(&GALLERY_PREFIX.to_string()));
+        assert!(prefixes.contains(&BROWSE_PREFIX.to_string()));
+        assert!(prefixes.contains(&MAP_PREFIX.to_string()));
+        assert!(prefixes.contains(&city_prefix(city_id)));
+        assert!(prefixes.contains(&"contributor:Walker:".to_string()));
+        assert!(prefixes.contains(&format!("response:/api/v1/letterings/{}?", id)));
+        assert!(prefixes.contains(&format!("response:/api/v1/cities/{}/stats?", city_id)));
+
+        let by_id = LetteringChange {
+            id,
+            city_id: None,
+            contributor_tag: None,
+        };
+        assert_eq!(by_id.dependents().1.len(), 6);
+    }
+}
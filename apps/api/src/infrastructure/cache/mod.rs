@@ -1 +1,3 @@
+pub mod invalidation;
 pub mod redis_cache;
+pub mod tiered_cache;
@@ -1,7 +1,8 @@
+use crate::infrastructure::fault_injection::FaultInjector;
 use anyhow::Result;
 use redis::{AsyncCommands, Client};
 use serde::{Serialize, de::DeserializeOwned};
-use std::future::Future;
+use std::{future::Future, sync::Arc};
 use tracing::{debug, error, warn};
 
 /// Lock TTL in seconds. Short-lived to avoid deadlocks if the holder crashes.
@@ -16,16 +17,46 @@ const LOCK_MAX_RETRIES: u32 = 60; // 60 * 50ms = 3 seconds max wait
 /// Extra TTL added to stale data beyond the main TTL, enabling stale-while-revalidate.
 const STALE_EXTENSION_SECONDS: u64 = 60;
 
+/// Keys asked for per SCAN step when deleting by prefix.
+const SCAN_BATCH: u64 = 500;
+
+/// `prefix` as a SCAN pattern matching itself literally.
+fn escape_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
+
 pub struct RedisCache {
     client: Client,
+    faults: Option<Arc<FaultInjector>>,
 }
 
 impl RedisCache {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            faults: None,
+        }
+    }
+
+    /// Fail a share of `get`, `set` and `delete` calls as configured.
+    pub fn with_fault_injector(mut self, faults: Arc<FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    fn inject_fault(&self) -> Result<()> {
+        self.faults.as_ref().map_or(Ok(()), |f| f.redis())
     }
 
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        self.inject_fault()?;
         let mut conn = self.client.get_multiplexed_async_connection().await?;
         let value: Option<String> = conn.get(key).await?;
         match value {
@@ -35,6 +66,7 @@ impl RedisCache {
     }
 
     pub async fn set<T: Serialize>(&self, key: &str, value: &T, ttl: u64) -> Result<()> {
+        self.inject_fault()?;
         let mut conn = self.client.get_multiplexed_async_connection().await?;
         let json = serde_json::to_string(value)?;
         let _: () = conn.set_ex(key, json, ttl).await?;
@@ -42,11 +74,40 @@ impl RedisCache {
     }
 
     pub async fn delete(&self, key: &str) -> Result<()> {
+        self.inject_fault()?;
         let mut conn = self.client.get_multiplexed_async_connection().await?;
         let _: () = conn.del(key).await?;
         Ok(())
     }
 
+    /// Deletes every key starting with `prefix`, stale copies included.
+    /// Walks the keyspace with SCAN so Redis is never blocked for long;
+    /// returns how many keys went.
+    pub async fn delete_prefix(&self, prefix: &str) -> Result<u64> {
+        self.inject_fault()?;
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let pattern = format!("{}*", escape_pattern(prefix));
+        let mut cursor: u64 = 0;
+        let mut deleted = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH)
+                .query_async(&mut conn)
+                .await?;
+            if !keys.is_empty() {
+                deleted += conn.del::<_, u64>(&keys).await?;
+            }
+            if next == 0 {
+                return Ok(deleted);
+            }
+            cursor = next;
+        }
+    }
+
     /// Fetch-through cache with stampede protection.
     ///
     /// On cache miss, only one request fetches from the source (lock winner).
@@ -62,6 +123,23 @@ impl RedisCache {
         T: Serialize + DeserializeOwned,
         F: FnOnce() -> Fut,
         Fut: Future<Output = Result<T>>,
+    {
+        self.get_or_fetch_with_ttl(key, |_| ttl, fetch_fn).await
+    }
+
+    /// [`Self::get_or_fetch`] with the TTL chosen per fetched value, e.g.
+    /// shorter for "not found". A TTL of 0 leaves the value uncached.
+    pub async fn get_or_fetch_with_ttl<T, L, F, Fut>(
+        &self,
+        key: &str,
+        ttl_for: L,
+        fetch_fn: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        L: FnOnce(&T) -> u64,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
     {
         // Step 1: Try fresh cache. If Redis is down, log and skip to fetch.
         match self.get::<T>(key).await {
@@ -119,8 +197,11 @@ impl RedisCache {
         if lock_acquired {
             debug!("Lock acquired for key={}, fetching from source", key);
             let result = fetch_fn().await;
+            let ttl = result.as_ref().map_or(0, ttl_for);
 
-            if let Ok(value) = &result {
+            if let Ok(value) = &result
+                && ttl > 0
+            {
                 // Cache write failures are logged but don't fail the request.
                 // The fetch succeeded — the caller gets their data regardless.
                 // But we make noise so operators know the cache isn't working.
@@ -204,3 +285,18 @@ impl RedisCache {
         fetch_fn().await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_match_literally() {
+        assert_eq!(escape_pattern("gallery:"), "gallery:");
+        assert_eq!(
+            escape_pattern("contributor:a*b?[c]:"),
+            r"contributor:a\*b\?\[c\]:"
+        );
+        assert_eq!(escape_pattern(r"x\y"), r"x\\y");
+    }
+}
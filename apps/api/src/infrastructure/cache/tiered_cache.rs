@@ -0,0 +1,354 @@
+//! In-process cache in front of [`RedisCache`] for the hottest reads.
+//!
+//! Each instance keeps recently read values in memory for at most
+//! `LOCAL_CACHE_TTL_SECONDS`, so repeated reads of the same lettering, city
+//! or region policy skip the Redis round trip. Misses fall through to
+//! [`RedisCache::get_or_fetch`] with its stampede protection.
+//!
+//! Writers call [`TieredCache::invalidate`], which drops the key from Redis
+//! and publishes it on [`INVALIDATION_CHANNEL`]; every instance listening
+//! drops its own copy. [`TieredCache::invalidate_prefix`] does the same for
+//! every key under a prefix, over [`PREFIX_INVALIDATION_CHANNEL`]. An
+//! instance that loses its subscription clears its memory before subscribing
+//! again, since it may have missed messages. The local TTL bounds how long a
+//! missed message can leave a value stale.
+//!
+//! Lookups are counted per tier, `local` and `redis`, in the business
+//! metrics of the [`PerformanceMonitor`].
+
+use crate::infrastructure::{
+    cache::redis_cache::RedisCache,
+    monitoring::{BusinessEvent, PerformanceMonitor},
+    shutdown::Shutdown,
+};
+use anyhow::Result;
+use futures_util::StreamExt;
+use redis::{AsyncCommands, Client};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Channel carrying keys to drop from every instance's memory.
+pub const INVALIDATION_CHANNEL: &str = "cache_invalidations";
+
+/// Channel carrying key prefixes to drop from every instance's memory.
+pub const PREFIX_INVALIDATION_CHANNEL: &str = "cache_invalidations:prefix";
+
+/// Wait before subscribing again after the subscription drops.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+struct LocalEntry {
+    json: String,
+    expires_at: Instant,
+}
+
+/// Bounded map of serialized values with per-entry expiry.
+struct LocalCache {
+    entries: Mutex<HashMap<String, LocalEntry>>,
+    max_entries: usize,
+    ttl: Duration,
+}
+
+impl LocalCache {
+    fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            max_entries,
+            ttl,
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.max_entries > 0 && !self.ttl.is_zero()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, LocalEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.lock();
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.json.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Keeps `json` for the local TTL or `ttl`, whichever is shorter. When
+    /// full, expired entries go first, then the one closest to expiring.
+    fn insert(&self, key: &str, json: String, ttl: Duration) {
+        if !self.enabled() {
+            return;
+        }
+        let now = Instant::now();
+        let mut entries = self.lock();
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            entries.retain(|_, entry| entry.expires_at > now);
+            if entries.len() >= self.max_entries {
+                let soonest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.expires_at)
+                    .map(|(k, _)| k.clone());
+                if let Some(soonest) = soonest {
+                    entries.remove(&soonest);
+                }
+            }
+        }
+        entries.insert(
+            key.to_string(),
+            LocalEntry {
+                json,
+                expires_at: now + self.ttl.min(ttl),
+            },
+        );
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().remove(key);
+    }
+
+    fn remove_prefix(&self, prefix: &str) {
+        self.lock().retain(|key, _| !key.starts_with(prefix));
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+pub struct TieredCache {
+    redis: Arc<RedisCache>,
+    /// For publishing and following invalidations.
+    client: Client,
+    local: LocalCache,
+    performance: Option<Arc<PerformanceMonitor>>,
+}
+
+impl TieredCache {
+    /// Keeps up to `max_entries` values in memory for at most `local_ttl`;
+    /// zero for either turns the in-process tier off.
+    pub fn new(
+        redis: Arc<RedisCache>,
+        client: Client,
+        max_entries: usize,
+        local_ttl: Duration,
+    ) -> Self {
+        Self {
+            redis,
+            client,
+            local: LocalCache::new(max_entries, local_ttl),
+            performance: None,
+        }
+    }
+
+    /// Count hits and misses per tier in `performance`.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
+    }
+
+    async fn record(&self, tier: &str, hit: bool) {
+        let Some(performance) = &self.performance else {
+            return;
+        };
+        let cache_type = tier.to_string();
+        let event = if hit {
+            BusinessEvent::CacheHit { cache_type }
+        } else {
+            BusinessEvent::CacheMiss { cache_type }
+        };
+        performance.record_business_event(event).await;
+    }
+
+    /// The value at `key` from memory, else Redis, else `fetch_fn`. Values
+    /// live in Redis for `ttl` seconds and in memory for at most that.
+    pub async fn get_or_fetch<T, F, Fut>(&self, key: &str, ttl: u64, fetch_fn: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.get_or_fetch_with_ttl(key, |_| ttl, fetch_fn).await
+    }
+
+    /// [`Self::get_or_fetch`] with the TTL chosen per value, as for
+    /// [`RedisCache::get_or_fetch_with_ttl`].
+    pub async fn get_or_fetch_with_ttl<T, L, F, Fut>(
+        &self,
+        key: &str,
+        ttl_for: L,
+        fetch_fn: F,
+    ) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        L: Fn(&T) -> u64,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if self.local.enabled() {
+            let cached = self
+                .local
+                .get(key)
+                .and_then(|json| serde_json::from_str(&json).ok());
+            self.record("local", cached.is_some()).await;
+            if let Some(value) = cached {
+                return Ok(value);
+            }
+        }
+
+        // A failing Redis is left to `get_or_fetch`, which goes to the
+        // source and logs it.
+        let value = match self.redis.get::<T>(key).await {
+            Ok(Some(value)) => {
+                self.record("redis", true).await;
+                value
+            }
+            Ok(None) => {
+                self.record("redis", false).await;
+                self.redis
+                    .get_or_fetch_with_ttl(key, &ttl_for, fetch_fn)
+                    .await?
+            }
+            Err(_) => {
+                self.redis
+                    .get_or_fetch_with_ttl(key, &ttl_for, fetch_fn)
+                    .await?
+            }
+        };
+        let ttl = ttl_for(&value);
+        if ttl > 0
+            && let Ok(json) = serde_json::to_string(&value)
+        {
+            self.local.insert(key, json, Duration::from_secs(ttl));
+        }
+        Ok(value)
+    }
+
+    /// Drops `key` here, in Redis and on every other instance. Failures are
+    /// logged; the value then lingers for its TTL at most.
+    pub async fn invalidate(&self, key: &str) {
+        self.local.remove(key);
+        for stored in [key.to_string(), format!("{}:stale", key)] {
+            if let Err(e) = self.redis.delete(&stored).await {
+                tracing::warn!("Failed to drop cached key={}: {}", stored, e);
+            }
+        }
+        self.publish(INVALIDATION_CHANNEL, key).await;
+    }
+
+    /// Drops every key starting with `prefix` here, in Redis and on every
+    /// other instance. Failures are logged as for [`Self::invalidate`].
+    pub async fn invalidate_prefix(&self, prefix: &str) {
+        self.local.remove_prefix(prefix);
+        if let Err(e) = self.redis.delete_prefix(prefix).await {
+            tracing::warn!("Failed to drop cached keys under prefix={}: {}", prefix, e);
+        }
+        self.publish(PREFIX_INVALIDATION_CHANNEL, prefix).await;
+    }
+
+    async fn publish(&self, channel: &str, payload: &str) {
+        let published = async {
+            let mut conn = self.client.get_multiplexed_async_connection().await?;
+            conn.publish::<_, _, ()>(channel, payload).await
+        }
+        .await;
+        if let Err(e) = published {
+            tracing::warn!("Failed to publish invalidation of {}: {}", payload, e);
+        }
+    }
+
+    /// Drops keys other instances invalidate until `shutdown` fires.
+    pub async fn listen_for_invalidations(&self, shutdown: Shutdown) {
+        if !self.local.enabled() {
+            return;
+        }
+        while !shutdown.is_triggered() {
+            if let Err(e) = self.follow_invalidations(&shutdown).await {
+                tracing::warn!("Cache invalidation subscription failed: {}", e);
+            }
+            // Anything published while unsubscribed was missed.
+            self.local.clear();
+            if !shutdown.sleep(RESUBSCRIBE_DELAY).await {
+                break;
+            }
+        }
+    }
+
+    async fn follow_invalidations(&self, shutdown: &Shutdown) -> Result<()> {
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub
+            .subscribe(&[INVALIDATION_CHANNEL, PREFIX_INVALIDATION_CHANNEL])
+            .await?;
+        let mut messages = pubsub.on_message();
+        loop {
+            tokio::select! {
+                message = messages.next() => {
+                    let Some(message) = message else {
+                        anyhow::bail!("subscription closed");
+                    };
+                    let key: String = message.get_payload()?;
+                    if message.get_channel_name() == PREFIX_INVALIDATION_CHANNEL {
+                        self.local.remove_prefix(&key);
+                    } else {
+                        self.local.remove(&key);
+                    }
+                }
+                _ = shutdown.triggered() => return Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_entries_expire_and_make_room_when_full() {
+        let local = LocalCache::new(2, Duration::from_secs(60));
+        local.insert("a", "1".into(), Duration::from_secs(60));
+        local.insert("b", "2".into(), Duration::from_secs(30));
+        assert_eq!(local.get("a").as_deref(), Some("1"));
+
+        // Full: the entry closest to expiring makes room.
+        local.insert("c", "3".into(), Duration::from_secs(60));
+        assert_eq!(local.get("b"), None);
+        assert_eq!(local.get("c").as_deref(), Some("3"));
+
+        local.insert("d", "4".into(), Duration::ZERO);
+        assert_eq!(local.get("d"), None);
+        local.remove("a");
+        assert_eq!(local.get("a"), None);
+    }
+
+    #[test]
+    fn prefixes_drop_every_key_under_them() {
+        let local = LocalCache::new(10, Duration::from_secs(60));
+        for key in ["city:1:en", "city:1:kn", "city:12:en"] {
+            local.insert(key, "1".into(), Duration::from_secs(60));
+        }
+        local.remove_prefix("city:1:");
+        assert_eq!(local.get("city:1:en"), None);
+        assert_eq!(local.get("city:1:kn"), None);
+        assert_eq!(local.get("city:12:en").as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn a_zero_size_or_ttl_disables_the_local_tier() {
+        for local in [
+            LocalCache::new(0, Duration::from_secs(60)),
+            LocalCache::new(10, Duration::ZERO),
+        ] {
+            local.insert("a", "1".into(), Duration::from_secs(60));
+            assert_eq!(local.get("a"), None);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Cloudflare edge cache purge.
+//!
+//! Deleted or un-approved images can linger at the edge for the full
+//! `max-age` we upload them with. Purges are attempted inline; failures are
+//! handed to the retry queue and picked up by `CdnPurgeRetryWorker`.
+
+use crate::infrastructure::queue::redis_queue::{CdnPurgeJob, RedisQueue};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// Cloudflare accepts at most 30 files per purge request.
+const MAX_FILES_PER_REQUEST: usize = 30;
+
+pub struct CloudflarePurger {
+    client: reqwest::Client,
+    zone_id: Option<String>,
+    api_token: Option<String>,
+    queue: Arc<RedisQueue>,
+}
+
+impl CloudflarePurger {
+    pub fn new(zone_id: Option<String>, api_token: Option<String>, queue: Arc<RedisQueue>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            zone_id: zone_id.filter(|v| !v.trim().is_empty()),
+            api_token: api_token.filter(|v| !v.trim().is_empty()),
+            queue,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.zone_id.is_some() && self.api_token.is_some()
+    }
+
+    /// Purge `urls` from the edge cache.
+    pub async fn purge(&self, urls: &[String]) -> anyhow::Result<()> {
+        let (Some(zone_id), Some(token)) = (&self.zone_id, &self.api_token) else {
+            return Ok(());
+        };
+        let endpoint = format!(
+            "https://api.cloudflare.com/client/v4/zones/{}/purge_cache",
+            zone_id
+        );
+
+        for chunk in urls.chunks(MAX_FILES_PER_REQUEST) {
+            let res = self
+                .client
+                .post(&endpoint)
+                .bearer_auth(token)
+                .json(&serde_json::json!({ "files": chunk }))
+                .send()
+                .await?;
+            let status = res.status();
+            let body: serde_json::Value = res.json().await.unwrap_or_default();
+            let success = body
+                .get("success")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if !status.is_success() || !success {
+                anyhow::bail!(
+                    "Cloudflare purge failed (HTTP {}): {}",
+                    status,
+                    body.get("errors").cloned().unwrap_or_default()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Purge now, or queue a retry if the purge API is unavailable.
+    /// Never fails the caller: the moderation action has already happened.
+    pub async fn purge_or_schedule(&self, urls: Vec<String>) {
+        if !self.is_enabled() || urls.is_empty() {
+            return;
+        }
+        if let Err(e) = self.purge(&urls).await {
+            tracing::warn!("CDN purge failed, scheduling retry: {}", e);
+            let job = CdnPurgeJob {
+                id: Uuid::now_v7(),
+                urls,
+                attempts: 1,
+            };
+            if let Err(e) = self.queue.schedule_cdn_purge(&job, retry_delay(1)).await {
+                tracing::error!(job_id = %job.id, "Failed to schedule CDN purge retry: {}", e);
+            }
+        }
+    }
+}
+
+/// Exponential backoff: 30s, 60s, 120s, ... capped at one hour.
+pub fn retry_delay(attempts: u32) -> Duration {
+    let secs = 30u64.saturating_mul(1u64 << attempts.saturating_sub(1).min(7));
+    Duration::from_secs(secs.min(3600))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1), Duration::from_secs(30));
+        assert_eq!(retry_delay(2), Duration::from_secs(60));
+        assert_eq!(retry_delay(3), Duration::from_secs(120));
+        assert_eq!(retry_delay(20), Duration::from_secs(3600));
+    }
+}
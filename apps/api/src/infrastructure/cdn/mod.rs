@@ -0,0 +1 @@
+pub mod cloudflare_purge;
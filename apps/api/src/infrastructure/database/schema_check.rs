@@ -0,0 +1,239 @@
+//! Startup schema compatibility probe.
+//!
+//! During a rolling (blue/green) deploy the database can be migrated ahead of
+//! some instances, or an instance can start against a database that hasn't
+//! caught up. Queries then fail at runtime as opaque 500s. Before serving,
+//! every probe below is described by Postgres (`Executor::describe`, which
+//! plans the statement without running it) and the result columns are checked
+//! against the types this build decodes. The applied migration version is
+//! compared with the newest one compiled in, so the diagnostic says which
+//! side is out of date.
+
+use sqlx::{Column, Executor, PgPool, TypeInfo, migrate::Migrator};
+use std::fmt;
+
+/// Columns a critical query reads, with the Postgres type it decodes them as.
+struct Probe {
+    table: &'static str,
+    columns: &'static [(&'static str, &'static str)],
+}
+
+const PROBES: &[Probe] = &[
+    Probe {
+        table: "letterings",
+        columns: &[
+            ("id", "UUID"),
+            ("city_id", "UUID"),
+            ("contributor_tag", "TEXT"),
+            ("image_url", "TEXT"),
+            ("thumbnail_small", "TEXT"),
+            ("thumbnail_medium", "TEXT"),
+            ("thumbnail_large", "TEXT"),
+            ("pin_code", "TEXT"),
+            ("status", "TEXT"),
+            ("created_at", "TIMESTAMPTZ"),
+            ("updated_at", "TIMESTAMPTZ"),
+            ("likes_count", "INT4"),
+            ("comments_count", "INT4"),
+            ("detected_text", "TEXT"),
+            ("description", "TEXT"),
+            ("image_hash", "TEXT"),
+            ("report_count", "INT4"),
+            ("report_reasons", "JSONB"),
+            ("cultural_context", "TEXT"),
+            ("ml_style", "TEXT"),
+            ("ml_script", "TEXT"),
+            ("ml_confidence", "FLOAT4"),
+            ("ml_color_palette", "JSONB"),
+            ("uploaded_by_ip", "INET"),
+        ],
+    },
+    // Written by the ML worker on every processed upload.
+    Probe {
+        table: "letterings",
+        columns: &[
+            ("ml_text_confidence", "FLOAT4"),
+            ("ml_script_confidence", "FLOAT4"),
+            ("ml_low_confidence_fields", "TEXT[]"),
+            ("ml_model_version", "TEXT"),
+            ("image_embedding_version", "TEXT"),
+            ("detected_text_source", "TEXT"),
+            ("near_duplicate_of", "UUID"),
+        ],
+    },
+    Probe {
+        table: "cities",
+        columns: &[
+            ("id", "UUID"),
+            ("name", "TEXT"),
+            ("country_code", "TEXT"),
+            ("center_lat", "FLOAT8"),
+            ("center_lng", "FLOAT8"),
+            ("default_zoom", "INT4"),
+            ("description", "TEXT"),
+            ("cover_image_url", "TEXT"),
+            ("is_active", "BOOL"),
+        ],
+    },
+    Probe {
+        table: "comments",
+        columns: &[
+            ("id", "UUID"),
+            ("lettering_id", "UUID"),
+            ("content", "TEXT"),
+            ("created_at", "TIMESTAMPTZ"),
+        ],
+    },
+];
+
+/// Result of [`check_schema`].
+#[derive(Debug)]
+pub struct SchemaReport {
+    /// Newest successfully applied migration, if any.
+    pub applied_version: Option<i64>,
+    /// Newest migration compiled into this build.
+    pub known_version: i64,
+    pub problems: Vec<String>,
+}
+
+impl SchemaReport {
+    pub fn is_compatible(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Where the database stands relative to this build.
+    pub fn drift(&self) -> &'static str {
+        match self.applied_version {
+            Some(applied) if applied > self.known_version => "ahead of",
+            Some(applied) if applied == self.known_version => "in step with",
+            _ => "behind",
+        }
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "database schema is {} this build (applied migration {}, build expects {})",
+            self.drift(),
+            self.applied_version
+                .map_or_else(|| "none".to_string(), |v| v.to_string()),
+            self.known_version
+        )?;
+        for problem in &self.problems {
+            write!(f, "\n  - {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+/// Type names that decode the same way, so e.g. `VARCHAR` widened to `TEXT`
+/// isn't reported.
+fn type_family(name: &str) -> String {
+    match name.to_ascii_uppercase().as_str() {
+        "VARCHAR" | "BPCHAR" | "NAME" | "CITEXT" => "TEXT".to_string(),
+        "VARCHAR[]" | "BPCHAR[]" | "NAME[]" => "TEXT[]".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Differences between a probe's expected columns and what Postgres described.
+fn compare_columns(
+    table: &str,
+    expected: &[(&str, &str)],
+    described: &[(String, String)],
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (name, expected_type) in expected {
+        match described.iter().find(|(n, _)| n == name) {
+            None => problems.push(format!("{}.{}: column missing", table, name)),
+            Some((_, found)) if type_family(found) != type_family(expected_type) => {
+                problems.push(format!(
+                    "{}.{}: expected {}, found {}",
+                    table, name, expected_type, found
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+    problems
+}
+
+/// Describe every probe and compare the migration versions.
+pub async fn check_schema(db: &PgPool, migrator: &Migrator) -> anyhow::Result<SchemaReport> {
+    let applied_version = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT MAX(version) FROM _sqlx_migrations WHERE success",
+    )
+    .fetch_one(db)
+    .await?;
+    let known_version = migrator.iter().map(|m| m.version).max().unwrap_or(0);
+
+    let mut problems = Vec::new();
+    for probe in PROBES {
+        let columns: Vec<&str> = probe.columns.iter().map(|(name, _)| *name).collect();
+        let sql = format!("SELECT {} FROM {} LIMIT 0", columns.join(", "), probe.table);
+        match db.describe(&sql).await {
+            Ok(describe) => {
+                let described: Vec<(String, String)> = describe
+                    .columns()
+                    .iter()
+                    .map(|c| (c.name().to_string(), c.type_info().name().to_string()))
+                    .collect();
+                problems.extend(compare_columns(probe.table, probe.columns, &described));
+            }
+            // Postgres stops at the first unknown column; name it and move on.
+            Err(e) => problems.push(format!("{}: {}", probe.table, e)),
+        }
+    }
+
+    Ok(SchemaReport {
+        applied_version,
+        known_version,
+        problems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_and_retyped_columns() {
+        let described = vec![
+            ("id".to_string(), "UUID".to_string()),
+            ("status".to_string(), "VARCHAR".to_string()),
+            ("likes_count".to_string(), "INT8".to_string()),
+        ];
+        let problems = compare_columns(
+            "letterings",
+            &[
+                ("id", "UUID"),
+                ("status", "TEXT"),
+                ("likes_count", "INT4"),
+                ("detected_text", "TEXT"),
+            ],
+            &described,
+        );
+        assert_eq!(
+            problems,
+            vec![
+                "letterings.likes_count: expected INT4, found INT8",
+                "letterings.detected_text: column missing",
+            ]
+        );
+    }
+
+    #[test]
+    fn describes_drift_direction() {
+        let report = |applied| SchemaReport {
+            applied_version: applied,
+            known_version: 20,
+            problems: Vec::new(),
+        };
+        assert_eq!(report(Some(21)).drift(), "ahead of");
+        assert_eq!(report(Some(20)).drift(), "in step with");
+        assert_eq!(report(Some(19)).drift(), "behind");
+        assert_eq!(report(None).drift(), "behind");
+    }
+}
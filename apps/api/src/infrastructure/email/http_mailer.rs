@@ -0,0 +1,83 @@
+//! Outgoing email through a transactional email HTTP API.
+//!
+//! Messages are posted as JSON (`from`, `to`, `subject`, `text`, `headers`)
+//! with the API key as a bearer token, the shape Resend-style providers
+//! accept. Sending is disabled until `EMAIL_API_URL`, `EMAIL_API_KEY` and
+//! `EMAIL_FROM` are all set.
+
+use std::time::Duration;
+
+/// One plain-text message.
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    /// Target of the `List-Unsubscribe` header, for bulk mail.
+    pub unsubscribe_url: Option<String>,
+}
+
+pub struct HttpMailer {
+    client: reqwest::Client,
+    api_url: Option<String>,
+    api_key: Option<String>,
+    from: Option<String>,
+}
+
+impl HttpMailer {
+    pub fn new(api_url: Option<String>, api_key: Option<String>, from: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            api_url: api_url.filter(|v| !v.trim().is_empty()),
+            api_key: api_key.filter(|v| !v.trim().is_empty()),
+            from: from.filter(|v| !v.trim().is_empty()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.api_url.is_some() && self.api_key.is_some() && self.from.is_some()
+    }
+
+    pub async fn send(&self, email: &OutgoingEmail) -> anyhow::Result<()> {
+        let (Some(url), Some(key), Some(from)) = (&self.api_url, &self.api_key, &self.from) else {
+            anyhow::bail!("Email sending is not configured");
+        };
+
+        let mut headers = serde_json::Map::new();
+        if let Some(unsubscribe) = &email.unsubscribe_url {
+            // RFC 8058 one-click unsubscribe: mail clients POST to the URL.
+            headers.insert(
+                "List-Unsubscribe".to_string(),
+                format!("<{}>", unsubscribe).into(),
+            );
+            headers.insert(
+                "List-Unsubscribe-Post".to_string(),
+                "List-Unsubscribe=One-Click".into(),
+            );
+        }
+
+        let res = self
+            .client
+            .post(url)
+            .bearer_auth(key)
+            .json(&serde_json::json!({
+                "from": from,
+                "to": [email.to],
+                "subject": email.subject,
+                "text": email.text,
+                "headers": headers,
+            }))
+            .send()
+            .await?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("Email API returned HTTP {}: {}", status, body);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! Runtime fault injection for resilience testing.
+//!
+//! Lets staging exercise the degraded paths end to end: Redis errors in the
+//! cache and queue, slow storage, and failing ML jobs, which drive the cache
+//! bypass, retry/dead-letter and circuit-breaker logic. Settings can be changed
+//! while running through `PUT /api/v1/admin/faults`.
+//!
+//! Only built into debug builds or with the `fault-injection` feature, and even
+//! then only active when `ENABLE_FAULT_INJECTION` is set, so a production
+//! release can't be configured into failing.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+/// Whether this build may inject faults at all.
+pub const AVAILABLE: bool = cfg!(any(debug_assertions, feature = "fault-injection"));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultSettings {
+    /// Share of Redis cache and queue calls that fail, 0-100.
+    #[serde(default)]
+    pub redis_error_percent: u8,
+    /// Delay added before every storage call.
+    #[serde(default)]
+    pub storage_latency_ms: u64,
+    /// Fail every k-th ML job; 0 disables.
+    #[serde(default)]
+    pub ml_fail_every: u32,
+}
+
+impl FaultSettings {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.redis_error_percent > 100 {
+            return Err("redis_error_percent must be between 0 and 100");
+        }
+        if self.storage_latency_ms > 60_000 {
+            return Err("storage_latency_ms must be at most 60000");
+        }
+        Ok(())
+    }
+}
+
+/// Error returned for an injected fault, so logs make the cause obvious.
+#[derive(Debug)]
+pub struct InjectedFault(pub &'static str);
+
+impl std::fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "injected {} fault", self.0)
+    }
+}
+
+impl std::error::Error for InjectedFault {}
+
+pub struct FaultInjector {
+    settings: RwLock<FaultSettings>,
+    ml_jobs: AtomicU64,
+}
+
+impl FaultInjector {
+    pub fn new(settings: FaultSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            ml_jobs: AtomicU64::new(0),
+        }
+    }
+
+    /// The injector configured by `ENABLE_FAULT_INJECTION` and the `FAULT_*`
+    /// settings, or `None` when disabled or unavailable in this build.
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        if !config.enable_fault_injection {
+            return None;
+        }
+        if !AVAILABLE {
+            tracing::warn!(
+                "ENABLE_FAULT_INJECTION is set, but this build was compiled without fault injection; ignoring"
+            );
+            return None;
+        }
+        let settings = FaultSettings {
+            redis_error_percent: config.fault_redis_error_percent.min(100),
+            storage_latency_ms: config.fault_storage_latency_ms,
+            ml_fail_every: config.fault_ml_fail_every,
+        };
+        tracing::warn!(?settings, "Fault injection is enabled");
+        Some(Arc::new(Self::new(settings)))
+    }
+
+    pub fn settings(&self) -> FaultSettings {
+        *self.settings.read().unwrap()
+    }
+
+    pub fn update(&self, settings: FaultSettings) {
+        *self.settings.write().unwrap() = settings;
+        self.ml_jobs.store(0, Ordering::Relaxed);
+    }
+
+    /// Fails the calling Redis operation `redis_error_percent`% of the time.
+    pub fn redis(&self) -> anyhow::Result<()> {
+        let percent = self.settings().redis_error_percent as u64;
+        if percent > 0 && random_u64() % 100 < percent {
+            return Err(InjectedFault("redis").into());
+        }
+        Ok(())
+    }
+
+    /// Sleeps for `storage_latency_ms` before a storage call.
+    pub async fn storage_delay(&self) {
+        let ms = self.settings().storage_latency_ms;
+        if ms > 0 {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+    }
+
+    /// Fails every `ml_fail_every`-th ML job.
+    pub fn ml_job(&self) -> anyhow::Result<()> {
+        let every = self.settings().ml_fail_every as u64;
+        if every == 0 {
+            return Ok(());
+        }
+        let n = self.ml_jobs.fetch_add(1, Ordering::Relaxed) + 1;
+        if n.is_multiple_of(every) {
+            return Err(InjectedFault("ML job").into());
+        }
+        Ok(())
+    }
+}
+
+/// Good enough randomness for picking faults without a `rand` dependency:
+/// every `RandomState` is freshly keyed.
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fails_every_kth_ml_job() {
+        let faults = FaultInjector::new(FaultSettings {
+            ml_fail_every: 3,
+            ..Default::default()
+        });
+        let failed: Vec<bool> = (0..6).map(|_| faults.ml_job().is_err()).collect();
+        assert_eq!(failed, [false, false, true, false, false, true]);
+    }
+
+    #[test]
+    fn redis_errors_follow_the_configured_share() {
+        let faults = FaultInjector::new(FaultSettings::default());
+        assert!((0..100).all(|_| faults.redis().is_ok()));
+
+        faults.update(FaultSettings {
+            redis_error_percent: 100,
+            ..Default::default()
+        });
+        let err = faults.redis().unwrap_err();
+        assert!(err.is::<InjectedFault>());
+    }
+}
@@ -0,0 +1,302 @@
+//! Percentage rollouts of new query paths.
+//!
+//! Each rollout flag names one endpoint's candidate implementation (a feed
+//! read from a materialized view, a new search backend) and carries the share
+//! of requests, 0-100, sent to it. Shares are persisted in `feature_flags`,
+//! mirrored into a Redis hash so every replica sees changes at once, and set
+//! through `PUT /api/v1/admin/feature-flags/{name}`.
+//!
+//! [`split`] picks the path per request. A subject (account or client IP)
+//! always hashes into the same bucket, so raising the share only ever moves
+//! clients from control to candidate. Requests, errors and latency are
+//! counted per variant per day for a side-by-side comparison.
+
+use redis::AsyncCommands;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::{future::Future, time::Instant};
+
+/// A rollout the admin API can adjust.
+#[derive(Debug, Clone, Copy)]
+pub struct RolloutFlag {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+pub const ROLLOUT_FLAGS: &[RolloutFlag] = &[
+    RolloutFlag {
+        name: "gallery_feed_view",
+        description: "GET /api/v1/letterings served from the feed materialized view",
+    },
+    RolloutFlag {
+        name: "search_backend_v2",
+        description: "GET /api/v1/letterings/search served by the new search backend",
+    },
+];
+
+/// Redis hash of flag -> rollout percent, mirrored from `feature_flags`.
+pub const FLAGS_KEY: &str = "feature_flags";
+
+/// Per-variant counters are kept for a week.
+pub const STATS_RETENTION_DAYS: i64 = 7;
+
+const STATS_TTL_SECONDS: i64 = (STATS_RETENTION_DAYS + 1) * 86_400;
+
+/// Upper bounds of the latency histogram, in milliseconds; slower requests
+/// land in a final open bucket.
+pub const LATENCY_BUCKETS_MS: &[u64] = &[10, 25, 50, 100, 250, 500, 1_000, 2_500];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Variant {
+    Control,
+    Candidate,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Control, Variant::Candidate];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Variant::Control => "control",
+            Variant::Candidate => "candidate",
+        }
+    }
+}
+
+pub fn find_flag(name: &str) -> Option<&'static RolloutFlag> {
+    ROLLOUT_FLAGS.iter().find(|f| f.name == name)
+}
+
+pub fn stats_key(flag: &str, date: &str) -> String {
+    format!("feature_flag_stats:{}:{}", flag, date)
+}
+
+/// Stable bucket, 0-99, of a subject within a flag. Salting with the flag
+/// keeps each rollout's candidate group independent of the others.
+pub fn bucket(flag: &str, subject: &str) -> u8 {
+    let digest = Sha256::digest(format!("{}:{}", flag, subject).as_bytes());
+    (u16::from_be_bytes([digest[0], digest[1]]) % 100) as u8
+}
+
+pub fn variant_for(flag: &str, subject: &str, percent: u8) -> Variant {
+    if bucket(flag, subject) < percent.min(100) {
+        Variant::Candidate
+    } else {
+        Variant::Control
+    }
+}
+
+fn latency_bucket(elapsed_ms: u64) -> String {
+    LATENCY_BUCKETS_MS
+        .iter()
+        .find(|&&bound| elapsed_ms <= bound)
+        .map_or_else(|| "inf".to_string(), |bound| bound.to_string())
+}
+
+/// Current share of `flag` sent to the candidate. Unknown flags and Redis
+/// errors mean 0, so an outage falls back to the proven path.
+pub async fn rollout_percent(redis: &redis::Client, flag: &str) -> u8 {
+    let result: redis::RedisResult<Option<u8>> = async {
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        conn.hget(FLAGS_KEY, flag).await
+    }
+    .await;
+    match result {
+        Ok(percent) => percent.unwrap_or(0).min(100),
+        Err(e) => {
+            tracing::warn!(flag, "Failed to read rollout percent: {}", e);
+            0
+        }
+    }
+}
+
+/// Run `control` or `candidate` for this request, per the flag's rollout
+/// share, and record the outcome under the chosen variant.
+pub async fn split<T, E, C, CF, N, NF>(
+    redis: &redis::Client,
+    flag: &'static str,
+    subject: &str,
+    control: C,
+    candidate: N,
+) -> Result<T, E>
+where
+    C: FnOnce() -> CF,
+    CF: Future<Output = Result<T, E>>,
+    N: FnOnce() -> NF,
+    NF: Future<Output = Result<T, E>>,
+{
+    let variant = variant_for(flag, subject, rollout_percent(redis, flag).await);
+    let started = Instant::now();
+    let result = match variant {
+        Variant::Control => control().await,
+        Variant::Candidate => candidate().await,
+    };
+    record(
+        redis,
+        flag,
+        variant,
+        started.elapsed().as_millis() as u64,
+        result.is_err(),
+    )
+    .await;
+    result
+}
+
+/// Count one request. Best effort: a failure is logged, never surfaced.
+async fn record(
+    redis: &redis::Client,
+    flag: &str,
+    variant: Variant,
+    elapsed_ms: u64,
+    failed: bool,
+) {
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let key = stats_key(flag, &date);
+    let v = variant.as_str();
+    let mut pipe = redis::pipe();
+    pipe.hincr(&key, format!("{}:requests", v), 1_u64)
+        .ignore()
+        .hincr(&key, format!("{}:latency_ms", v), elapsed_ms)
+        .ignore()
+        .hincr(
+            &key,
+            format!("{}:le:{}", v, latency_bucket(elapsed_ms)),
+            1_u64,
+        )
+        .ignore();
+    if failed {
+        pipe.hincr(&key, format!("{}:errors", v), 1_u64).ignore();
+    }
+    pipe.expire(&key, STATS_TTL_SECONDS).ignore();
+
+    let result = async {
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        pipe.query_async::<()>(&mut conn).await
+    }
+    .await;
+    if let Err(e) = result {
+        tracing::warn!(flag, "Failed to record rollout stats: {}", e);
+    }
+}
+
+/// One variant's traffic over the requested days.
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantStats {
+    pub variant: Variant,
+    pub requests: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    pub mean_latency_ms: Option<f64>,
+    /// Upper bound of the histogram bucket holding the 95th percentile;
+    /// `None` without traffic or when it falls past the last bound.
+    pub p95_latency_ms: Option<u64>,
+}
+
+impl VariantStats {
+    /// Summarise the counters of `variant` from `feature_flag_stats` hashes
+    /// already summed over days.
+    pub fn from_counters(
+        variant: Variant,
+        counters: &std::collections::HashMap<String, u64>,
+    ) -> Self {
+        let v = variant.as_str();
+        let get = |field: String| counters.get(&field).copied().unwrap_or(0);
+        let requests = get(format!("{}:requests", v));
+        let errors = get(format!("{}:errors", v));
+        let latency_ms = get(format!("{}:latency_ms", v));
+
+        let mut p95_latency_ms = None;
+        if requests > 0 {
+            let target = requests.saturating_mul(95).div_ceil(100);
+            let mut seen = 0;
+            for bound in LATENCY_BUCKETS_MS {
+                seen += get(format!("{}:le:{}", v, bound));
+                if seen >= target {
+                    p95_latency_ms = Some(*bound);
+                    break;
+                }
+            }
+        }
+
+        Self {
+            variant,
+            requests,
+            errors,
+            error_rate: if requests > 0 {
+                errors as f64 / requests as f64
+            } else {
+                0.0
+            },
+            mean_latency_ms: (requests > 0).then(|| latency_ms as f64 / requests as f64),
+            p95_latency_ms,
+        }
+    }
+}
+
+/// Copy persisted rollout shares into Redis so they survive a cache flush.
+pub async fn sync_flags(db: &PgPool, redis: &redis::Client) -> anyhow::Result<usize> {
+    let rows =
+        sqlx::query_as::<_, (String, i16)>("SELECT name, rollout_percent FROM feature_flags")
+            .fetch_all(db)
+            .await?;
+
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let _: () = conn.del(FLAGS_KEY).await?;
+    for (name, percent) in &rows {
+        let _: () = conn.hset(FLAGS_KEY, name, *percent).await?;
+    }
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn raising_the_share_only_adds_candidates() {
+        let subjects: Vec<String> = (0..500).map(|i| format!("ip:{}", i)).collect();
+        let candidates = |percent| {
+            subjects
+                .iter()
+                .filter(|s| variant_for("search_backend_v2", s, percent) == Variant::Candidate)
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        assert!(candidates(0).is_empty());
+        assert_eq!(candidates(100).len(), subjects.len());
+        let ten = candidates(10);
+        let fifty = candidates(50);
+        assert!(ten.iter().all(|s| fifty.contains(s)));
+        assert!((150..350).contains(&fifty.len()));
+    }
+
+    #[test]
+    fn summarises_counters_per_variant() {
+        let counters: HashMap<String, u64> = [
+            ("candidate:requests", 20),
+            ("candidate:errors", 1),
+            ("candidate:latency_ms", 2_000),
+            ("candidate:le:50", 10),
+            ("candidate:le:100", 8),
+            ("candidate:le:250", 2),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let candidate = VariantStats::from_counters(Variant::Candidate, &counters);
+        assert_eq!(candidate.requests, 20);
+        assert_eq!(candidate.error_rate, 0.05);
+        assert_eq!(candidate.mean_latency_ms, Some(100.0));
+        assert_eq!(candidate.p95_latency_ms, Some(250));
+
+        let control = VariantStats::from_counters(Variant::Control, &counters);
+        assert_eq!(control.requests, 0);
+        assert_eq!(control.mean_latency_ms, None);
+        assert_eq!(control.p95_latency_ms, None);
+    }
+}
@@ -0,0 +1,282 @@
+//! Coarse IP geolocation for abuse heuristics and country analytics.
+//!
+//! Lookups go to an HTTP geolocation API (`IP_GEO_LOOKUP_URL`, with `{ip}`
+//! substituted) and are cached locally per network prefix (/24 for IPv4, /48
+//! for IPv6) so the full address is never persisted. Cached entries are
+//! re-fetched after `refresh_days`; annotations and cache rows older than the
+//! retention window are pruned by `GeoRetentionWorker`.
+
+use serde::Deserialize;
+use sqlx::{PgPool, types::ipnetwork::IpNetwork};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+    time::Duration,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoInfo {
+    pub country_code: String,
+    pub region: Option<String>,
+}
+
+/// Accepts the field names used by the common providers (ipapi.co,
+/// ip-api.com, ipinfo.io).
+#[derive(Debug, Deserialize)]
+struct LookupResponse {
+    #[serde(alias = "countryCode", alias = "country")]
+    country_code: Option<String>,
+    #[serde(alias = "regionName")]
+    region: Option<String>,
+}
+
+/// Kinds of request annotated with geo info.
+#[derive(Debug, Clone, Copy)]
+pub enum GeoEvent {
+    Upload,
+    Login,
+    Report,
+}
+
+impl GeoEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Upload => "UPLOAD",
+            Self::Login => "LOGIN",
+            Self::Report => "REPORT",
+        }
+    }
+}
+
+/// Truncate `ip` to the prefix used as the cache key.
+pub fn coarse_network(ip: IpAddr) -> IpNetwork {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, _] = v4.octets();
+            IpNetwork::new(IpAddr::V4(Ipv4Addr::new(a, b, c, 0)), 24).expect("valid /24 prefix")
+        }
+        IpAddr::V6(v6) => {
+            let s = v6.segments();
+            IpNetwork::new(
+                IpAddr::V6(Ipv6Addr::new(s[0], s[1], s[2], 0, 0, 0, 0, 0)),
+                48,
+            )
+            .expect("valid /48 prefix")
+        }
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            !(v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast())
+        }
+        IpAddr::V6(v6) => {
+            !(v6.is_loopback() || v6.is_unspecified() || (v6.segments()[0] & 0xfe00) == 0xfc00)
+        }
+    }
+}
+
+pub struct IpGeolocator {
+    db: PgPool,
+    client: reqwest::Client,
+    lookup_url: Option<String>,
+    refresh_days: i32,
+}
+
+impl IpGeolocator {
+    pub fn new(db: PgPool, lookup_url: Option<String>, refresh_days: i32) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .unwrap_or_default();
+        Self {
+            db,
+            client,
+            lookup_url: lookup_url.filter(|v| !v.trim().is_empty()),
+            refresh_days: refresh_days.max(1),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.lookup_url.is_some()
+    }
+
+    /// Resolve `ip` to a country/region, preferring the local cache. A stale
+    /// cache entry is still returned if the refresh fails.
+    pub async fn lookup(&self, ip: IpAddr) -> anyhow::Result<Option<GeoInfo>> {
+        let Some(template) = &self.lookup_url else {
+            return Ok(None);
+        };
+        if !is_public(ip) {
+            return Ok(None);
+        }
+        let network = coarse_network(ip);
+
+        let cached = sqlx::query_as::<_, (String, Option<String>, bool)>(
+            "SELECT country_code, region, fetched_at >= NOW() - make_interval(days => $2)
+             FROM ip_geo_cache
+             WHERE network = $1",
+        )
+        .bind(network)
+        .bind(self.refresh_days)
+        .fetch_optional(&self.db)
+        .await?;
+
+        if let Some((country_code, region, true)) = &cached {
+            return Ok(Some(GeoInfo {
+                country_code: country_code.clone(),
+                region: region.clone(),
+            }));
+        }
+
+        let fetched = match self.fetch(template, ip).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::warn!(network = %network, "IP geolocation lookup failed: {}", e);
+                return Ok(cached.map(|(country_code, region, _)| GeoInfo {
+                    country_code,
+                    region,
+                }));
+            }
+        };
+        let Some(info) = fetched else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "INSERT INTO ip_geo_cache (network, country_code, region, fetched_at)
+             VALUES ($1, $2, $3, NOW())
+             ON CONFLICT (network) DO UPDATE
+             SET country_code = EXCLUDED.country_code,
+                 region = EXCLUDED.region,
+                 fetched_at = NOW()",
+        )
+        .bind(network)
+        .bind(&info.country_code)
+        .bind(&info.region)
+        .execute(&self.db)
+        .await?;
+
+        Ok(Some(info))
+    }
+
+    async fn fetch(&self, template: &str, ip: IpAddr) -> anyhow::Result<Option<GeoInfo>> {
+        let url = template.replace("{ip}", &ip.to_string());
+        let res = self.client.get(&url).send().await?.error_for_status()?;
+        let body: LookupResponse = res.json().await?;
+        let country_code = body
+            .country_code
+            .map(|c| c.trim().to_uppercase())
+            .filter(|c| c.len() == 2 && c.chars().all(|ch| ch.is_ascii_alphabetic()));
+        Ok(country_code.map(|country_code| GeoInfo {
+            country_code,
+            region: body
+                .region
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty()),
+        }))
+    }
+
+    /// Record coarse geo info for a request. Only the country and region are
+    /// stored; uploads also get `letterings.upload_country_code`.
+    pub async fn annotate(
+        &self,
+        event: GeoEvent,
+        subject_id: Option<Uuid>,
+        ip: IpAddr,
+    ) -> anyhow::Result<()> {
+        let Some(info) = self.lookup(ip).await? else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            "INSERT INTO ip_geo_annotations (id, event_type, subject_id, country_code, region)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(Uuid::now_v7())
+        .bind(event.as_str())
+        .bind(subject_id)
+        .bind(&info.country_code)
+        .bind(&info.region)
+        .execute(&self.db)
+        .await?;
+
+        if let (GeoEvent::Upload, Some(lettering_id)) = (event, subject_id) {
+            sqlx::query("UPDATE letterings SET upload_country_code = $1 WHERE id = $2")
+                .bind(&info.country_code)
+                .bind(lettering_id)
+                .execute(&self.db)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Annotate in the background so lookups never add request latency.
+    pub fn spawn_annotate(
+        self: &Arc<Self>,
+        event: GeoEvent,
+        subject_id: Option<Uuid>,
+        ip: Option<IpAddr>,
+    ) {
+        let Some(ip) = ip.filter(|_| self.is_enabled()) else {
+            return;
+        };
+        let geolocator = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = geolocator.annotate(event, subject_id, ip).await {
+                tracing::warn!(
+                    event = event.as_str(),
+                    "Failed to record geo annotation: {}",
+                    e
+                );
+            }
+        });
+    }
+
+    /// Delete annotations and cached lookups older than `retention_days`.
+    pub async fn prune(&self, retention_days: i32) -> anyhow::Result<(u64, u64)> {
+        let annotations = sqlx::query(
+            "DELETE FROM ip_geo_annotations WHERE created_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(retention_days)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+        let cache = sqlx::query(
+            "DELETE FROM ip_geo_cache WHERE fetched_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(retention_days)
+        .execute(&self.db)
+        .await?
+        .rows_affected();
+        Ok((annotations, cache))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_to_coarse_prefix() {
+        let v4: IpAddr = "203.0.113.77".parse().unwrap();
+        assert_eq!(coarse_network(v4).to_string(), "203.0.113.0/24");
+
+        let v6: IpAddr = "2001:db8:abcd:12::1".parse().unwrap();
+        assert_eq!(coarse_network(v6).to_string(), "2001:db8:abcd::/48");
+    }
+
+    #[test]
+    fn skips_non_public_addresses() {
+        assert!(!is_public("10.1.2.3".parse().unwrap()));
+        assert!(!is_public("127.0.0.1".parse().unwrap()));
+        assert!(!is_public("fd00::1".parse().unwrap()));
+        assert!(is_public("8.8.8.8".parse().unwrap()));
+    }
+}
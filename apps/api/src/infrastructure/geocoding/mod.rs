@@ -1,3 +1,4 @@
+pub mod ip_geolocation;
 pub mod pincode_coords;
 
 pub use pincode_coords::coordinates_for_pincode;
@@ -0,0 +1,230 @@
+//! Dominant colour palette by median cut.
+//!
+//! The image is reduced to at most 64x64 pixels, then the colour box with the
+//! widest channel range is repeatedly split at its median until there are
+//! `count` boxes. Each box contributes its mean colour, ordered by how many
+//! pixels it holds, so the same image always yields the same palette.
+
+use image::DynamicImage;
+
+/// Colours stored in `ml_color_palette` for each lettering.
+pub const PALETTE_SIZE: usize = 5;
+
+/// CIE76 ΔE within which a palette colour matches a searched colour when the
+/// search gives no `tolerance`.
+pub const DEFAULT_MATCH_TOLERANCE: f64 = 12.0;
+
+/// Largest accepted search `tolerance`; wider than this matches most palettes.
+pub const MAX_MATCH_TOLERANCE: f64 = 50.0;
+
+/// Edge of a `ml_color_bins` cell in Lab units. Must match `lab_color_bin`
+/// in the database.
+const LAB_BIN_SIZE: f64 = 16.0;
+const LAB_L_BINS: i32 = 7;
+const LAB_AB_BINS: i32 = 16;
+
+const SAMPLE_EDGE: u32 = 64;
+
+/// Up to `count` dominant colours as `#RRGGBB`, most common first.
+pub fn dominant_colors(image: &DynamicImage, count: usize) -> Vec<String> {
+    let sample = if image.width() > SAMPLE_EDGE || image.height() > SAMPLE_EDGE {
+        image.thumbnail(SAMPLE_EDGE, SAMPLE_EDGE).to_rgb8()
+    } else {
+        image.to_rgb8()
+    };
+    let pixels: Vec<[u8; 3]> = sample.pixels().map(|p| p.0).collect();
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![pixels];
+    while boxes.len() < count {
+        let Some((index, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (channel, range) = widest_channel(b);
+                (i, channel, range)
+            })
+            .filter(|&(_, _, range)| range > 0)
+            .max_by_key(|&(_, _, range)| range)
+            .map(|(i, channel, _)| (i, channel))
+        else {
+            break;
+        };
+
+        let mut bucket = boxes.swap_remove(index);
+        bucket.sort_unstable_by_key(|p| p[channel]);
+        let upper = bucket.split_off(bucket.len() / 2);
+        boxes.push(bucket);
+        boxes.push(upper);
+    }
+
+    // Boxes of identical colour (e.g. a large flat background split in two)
+    // are merged before ranking.
+    let mut weighted: Vec<(String, usize)> = Vec::with_capacity(boxes.len());
+    for b in &boxes {
+        let hex = to_hex(mean(b));
+        match weighted.iter_mut().find(|(h, _)| *h == hex) {
+            Some((_, weight)) => *weight += b.len(),
+            None => weighted.push((hex, b.len())),
+        }
+    }
+    weighted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    weighted.into_iter().map(|(hex, _)| hex).collect()
+}
+
+/// Parse `#RRGGBB` or `RRGGBB` (any case) into the canonical `#RRGGBB` form
+/// stored in palettes.
+pub fn normalize_hex_color(value: &str) -> Option<String> {
+    let hex = value.trim().trim_start_matches('#');
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| format!("#{}", hex.to_ascii_uppercase()))
+}
+
+/// CIE Lab (D65) coordinates of a `#RRGGBB` colour.
+pub fn hex_to_lab(hex: &str) -> Option<[f64; 3]> {
+    let hex = normalize_hex_color(hex)?;
+    let channel = |i: usize| {
+        let c = f64::from(u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0)) / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (channel(1), channel(3), channel(5));
+    let f = |t: f64| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let fx = f((0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047);
+    let fy = f(0.2126729 * r + 0.7151522 * g + 0.0721750 * b);
+    let fz = f((0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883);
+    Some([116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)])
+}
+
+fn bin_axis(value: f64, offset: f64, bins: i32) -> i32 {
+    (((value + offset) / LAB_BIN_SIZE).floor() as i32).clamp(0, bins - 1)
+}
+
+fn bin_id(l: i32, a: i32, b: i32) -> i32 {
+    l * 256 + a * 16 + b
+}
+
+/// The `ml_color_bins` cell holding a Lab colour.
+pub fn lab_bin([l, a, b]: [f64; 3]) -> i32 {
+    bin_id(
+        bin_axis(l, 0.0, LAB_L_BINS),
+        bin_axis(a, 128.0, LAB_AB_BINS),
+        bin_axis(b, 128.0, LAB_AB_BINS),
+    )
+}
+
+/// Every cell that could hold a colour within `tolerance` of `lab`, for the
+/// indexed `ml_color_bins &&` prefilter. A little slack covers float
+/// differences between this and the SQL conversion; the exact distance check
+/// follows anyway.
+pub fn lab_bins_within([l, a, b]: [f64; 3], tolerance: f64) -> Vec<i32> {
+    let reach = tolerance.max(0.0) + 0.5;
+    let range = |value: f64, offset: f64, bins: i32| {
+        bin_axis(value - reach, offset, bins)..=bin_axis(value + reach, offset, bins)
+    };
+    let mut cells = Vec::new();
+    for lb in range(l, 0.0, LAB_L_BINS) {
+        for ab in range(a, 128.0, LAB_AB_BINS) {
+            for bb in range(b, 128.0, LAB_AB_BINS) {
+                cells.push(bin_id(lb, ab, bb));
+            }
+        }
+    }
+    cells
+}
+
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|c| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(lo, hi), p| {
+                (lo.min(p[c]), hi.max(p[c]))
+            });
+            (c, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn mean(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for (total, &value) in sum.iter_mut().zip(p) {
+            *total += u64::from(value);
+        }
+    }
+    let n = pixels.len().max(1) as u64;
+    sum.map(|s| (s / n) as u8)
+}
+
+fn to_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn solid_image_has_one_colour() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(100, 80, Rgb([200, 30, 30])));
+        assert_eq!(dominant_colors(&image, PALETTE_SIZE), vec!["#C81E1E"]);
+    }
+
+    #[test]
+    fn larger_region_comes_first() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(40, 10, |x, _| {
+            if x < 30 {
+                Rgb([255, 255, 255])
+            } else {
+                Rgb([0, 0, 128])
+            }
+        }));
+        assert_eq!(
+            dominant_colors(&image, PALETTE_SIZE),
+            vec!["#FFFFFF", "#000080"]
+        );
+    }
+
+    #[test]
+    fn normalizes_hex_input() {
+        assert_eq!(normalize_hex_color("ff8800").as_deref(), Some("#FF8800"));
+        assert_eq!(normalize_hex_color(" #a1B2c3 ").as_deref(), Some("#A1B2C3"));
+        assert_eq!(normalize_hex_color("#fff"), None);
+        assert_eq!(normalize_hex_color("zzzzzz"), None);
+    }
+
+    #[test]
+    fn converts_hex_to_lab() {
+        let close = |a: [f64; 3], b: [f64; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 0.05);
+        assert!(close(hex_to_lab("#FFFFFF").unwrap(), [100.0, 0.0, 0.0]));
+        assert!(close(hex_to_lab("#000000").unwrap(), [0.0, 0.0, 0.0]));
+        assert!(close(hex_to_lab("ff0000").unwrap(), [53.24, 80.09, 67.20]));
+        assert_eq!(hex_to_lab("#12"), None);
+    }
+
+    #[test]
+    fn nearby_colours_fall_in_searched_bins() {
+        let target = hex_to_lab("#C81E1E").unwrap();
+        let near = hex_to_lab("#D02A20").unwrap();
+        let far = hex_to_lab("#1E1EC8").unwrap();
+        let bins = lab_bins_within(target, DEFAULT_MATCH_TOLERANCE);
+
+        assert!(bins.contains(&lab_bin(target)));
+        assert!(bins.contains(&lab_bin(near)));
+        assert!(!bins.contains(&lab_bin(far)));
+        assert!(lab_bins_within(target, MAX_MATCH_TOLERANCE).len() <= 7 * 8 * 8);
+    }
+}
@@ -0,0 +1,126 @@
+//! Fixed-length visual descriptor for "more like this" lookups.
+//!
+//! The vector concatenates a coarse RGB colour histogram with a 4x4 grid of
+//! gradient-orientation histograms, so letterings with a similar palette and
+//! similar stroke layout land close together. Both halves are square-rooted
+//! (Hellinger) and L2-normalised, which makes cosine distance in pgvector a
+//! reasonable similarity measure without a learned model.
+
+use image::{DynamicImage, imageops::FilterType};
+
+/// Dimension of the `letterings.image_embedding` column.
+pub const EMBEDDING_DIM: usize = COLOR_BINS + GRID * GRID * ORIENTATIONS;
+
+/// Stored alongside the vector so embeddings from a future descriptor are
+/// never compared against this one.
+pub const EMBEDDING_VERSION: &str = "hist-hog-v1";
+
+const SIDE: u32 = 64;
+const LEVELS: usize = 4;
+const COLOR_BINS: usize = LEVELS * LEVELS * LEVELS;
+const GRID: usize = 4;
+const ORIENTATIONS: usize = 4;
+
+pub fn embed(image: &DynamicImage) -> Vec<f32> {
+    let rgb = image
+        .resize_exact(SIDE, SIDE, FilterType::Triangle)
+        .to_rgb8();
+
+    let mut color = vec![0f32; COLOR_BINS];
+    for pixel in rgb.pixels() {
+        let [r, g, b] = pixel.0.map(|c| c as usize * LEVELS / 256);
+        color[(r * LEVELS + g) * LEVELS + b] += 1.0;
+    }
+
+    let luma: Vec<f32> = rgb
+        .pixels()
+        .map(|p| 0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32)
+        .collect();
+    let at = |x: u32, y: u32| luma[(y * SIDE + x) as usize];
+    let cell = SIDE as usize / GRID;
+    let mut gradients = vec![0f32; GRID * GRID * ORIENTATIONS];
+    for y in 1..SIDE - 1 {
+        for x in 1..SIDE - 1 {
+            let gx = at(x + 1, y) - at(x - 1, y);
+            let gy = at(x, y + 1) - at(x, y - 1);
+            let magnitude = (gx * gx + gy * gy).sqrt();
+            if magnitude == 0.0 {
+                continue;
+            }
+            // Unsigned orientation: dark-on-light and light-on-dark strokes
+            // in the same direction should match.
+            let angle = gy.atan2(gx).rem_euclid(std::f32::consts::PI);
+            let bin = ((angle / std::f32::consts::PI * ORIENTATIONS as f32) as usize)
+                .min(ORIENTATIONS - 1);
+            let (cx, cy) = (x as usize / cell, y as usize / cell);
+            gradients[(cy * GRID + cx) * ORIENTATIONS + bin] += magnitude;
+        }
+    }
+
+    let mut embedding = hellinger(color);
+    embedding.extend(hellinger(gradients));
+    normalize(&mut embedding);
+    embedding
+}
+
+fn hellinger(mut values: Vec<f32>) -> Vec<f32> {
+    for v in &mut values {
+        *v = v.sqrt();
+    }
+    normalize(&mut values);
+    values
+}
+
+fn normalize(values: &mut [f32]) {
+    let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in values {
+            *v /= norm;
+        }
+    }
+}
+
+/// pgvector text literal (`[0.1,0.2,...]`); bind it and cast with `::vector`.
+pub fn to_pgvector(embedding: &[f32]) -> String {
+    let values: Vec<String> = embedding.iter().map(|v| v.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    fn stripes(width: u32, height: u32, vertical: bool, ink: [u8; 3]) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let band = if vertical {
+                x * 8 / width
+            } else {
+                y * 8 / height
+            };
+            Rgb(if band % 2 == 0 { ink } else { [245, 240, 230] })
+        }))
+    }
+
+    #[test]
+    fn embeddings_are_unit_length_and_rank_lookalikes_first() {
+        let original = stripes(800, 600, true, [180, 20, 20]);
+        let resized = original.resize_exact(400, 300, FilterType::Nearest);
+        let rotated = stripes(800, 600, false, [20, 40, 160]);
+
+        let (a, b, c) = (embed(&original), embed(&resized), embed(&rotated));
+        assert_eq!(a.len(), EMBEDDING_DIM);
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-4);
+        assert!(cosine(&a, &b) > 0.95);
+        assert!(cosine(&a, &b) > cosine(&a, &c) + 0.2);
+    }
+
+    #[test]
+    fn pgvector_literal() {
+        assert_eq!(to_pgvector(&[0.5, -1.0, 0.0]), "[0.5,-1,0]");
+    }
+}
@@ -0,0 +1,7 @@
+pub mod bitmap_font;
+pub mod color_palette;
+pub mod image_embedding;
+pub mod perceptual_hash;
+pub mod qr_code;
+pub mod resize;
+pub mod share_card;
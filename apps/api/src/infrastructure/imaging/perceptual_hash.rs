@@ -0,0 +1,81 @@
+//! 64-bit difference hash (dHash) for near-duplicate detection.
+//!
+//! The image is reduced to a 9x8 grayscale grid and each bit records whether
+//! a cell is brighter than its right-hand neighbour, so re-encoding, resizing
+//! and mild colour or crop changes flip only a few bits.
+//!
+//! Lookups use multi-index hashing: the hash is split into eight one-byte
+//! bands stored as a GIN-indexed `INT[]`. Two hashes within Hamming distance 7
+//! must share at least one band, so the band overlap finds every candidate
+//! and `bit_count` on the full hash filters them.
+
+use image::{DynamicImage, imageops::FilterType};
+
+const BANDS: u32 = 8;
+
+/// Largest distance the band index is guaranteed to find.
+pub const MAX_INDEXED_DISTANCE: u32 = BANDS - 1;
+
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let gray = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = gray.get_pixel(x, y)[0];
+            let right = gray.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    hash
+}
+
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Band values tagged with their position (`band * 256 + byte`) so equal
+/// bytes in different positions don't match.
+pub fn hash_bands(hash: u64) -> Vec<i32> {
+    (0..BANDS)
+        .map(|band| (band * 256 + ((hash >> (band * 8)) & 0xff) as u32) as i32)
+        .collect()
+}
+
+/// Postgres has no unsigned 64-bit type; store the bit pattern as `BIGINT`.
+pub fn to_db(hash: u64) -> i64 {
+    hash as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn gradient(width: u32, height: u32, flip: bool) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let v = ((x * 255 / width) ^ (y * 64 / height)) as u8;
+            Rgb(if flip { [255 - v; 3] } else { [v; 3] })
+        }))
+    }
+
+    #[test]
+    fn resized_copies_hash_close_and_different_images_far() {
+        let original = gradient(640, 480, false);
+        let resized = original.resize_exact(320, 240, FilterType::Nearest);
+        let inverted = gradient(640, 480, true);
+
+        assert!(hamming_distance(dhash(&original), dhash(&resized)) <= 4);
+        assert!(hamming_distance(dhash(&original), dhash(&inverted)) > MAX_INDEXED_DISTANCE);
+    }
+
+    #[test]
+    fn nearby_hashes_share_a_band() {
+        let a = 0x0123_4567_89ab_cdef_u64;
+        let b = a ^ 0x0101_0101_0101_0100; // 7 bits flipped, lowest band untouched
+        assert_eq!(hamming_distance(a, b), 7);
+        let (bands_a, bands_b) = (hash_bands(a), hash_bands(b));
+        assert!(bands_a.iter().any(|band| bands_b.contains(band)));
+        assert_eq!(bands_a.len(), 8);
+        assert!(bands_a.iter().all(|&band| (0..2048).contains(&band)));
+    }
+}
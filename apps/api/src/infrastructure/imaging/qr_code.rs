@@ -0,0 +1,507 @@
+//! Minimal QR Code encoder for plaque links.
+//!
+//! Byte mode at error-correction level M, versions 1-10 (up to 213 bytes),
+//! which comfortably covers the site's short links. Rendering to PNG and SVG
+//! lives here too so callers only deal with the finished image.
+
+use image::{GrayImage, ImageFormat, Luma};
+use std::io::Cursor;
+
+const MAX_VERSION: usize = 10;
+
+/// Error-correction codewords per block at level M, indexed by version.
+const ECC_PER_BLOCK: [usize; MAX_VERSION + 1] = [0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26];
+
+/// Reed-Solomon block count at level M, indexed by version.
+const NUM_BLOCKS: [usize; MAX_VERSION + 1] = [0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5];
+
+/// Two-bit format indicator for level M.
+const ECC_FORMAT_BITS: u32 = 0;
+
+/// Light modules around the symbol required by the spec.
+pub const QUIET_ZONE: usize = 4;
+
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    pub fn encode(data: &[u8]) -> anyhow::Result<Self> {
+        let version = (1..=MAX_VERSION)
+            .find(|&v| 4 + char_count_bits(v) + data.len() * 8 <= data_codewords(v) * 8)
+            .ok_or_else(|| anyhow::anyhow!("{} bytes is too long for a QR code", data.len()))?;
+
+        let capacity = data_codewords(version) * 8;
+        let mut bits = BitBuffer::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, char_count_bits(version));
+        for &byte in data {
+            bits.push(u32::from(byte), 8);
+        }
+        bits.push(0, (capacity - bits.len()).min(4));
+        bits.push(0, (8 - bits.len() % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.len() >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let size = version * 4 + 17;
+        let mut qr = Self {
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&add_ecc_and_interleave(version, &bits.to_bytes()));
+
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                qr.apply_mask(mask);
+                qr.draw_format_bits(mask);
+                let penalty = qr.penalty_score();
+                qr.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        qr.apply_mask(mask);
+        qr.draw_format_bits(mask);
+        Ok(qr)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// PNG at `scale` pixels per module, including the quiet zone.
+    pub fn to_png(&self, scale: u32) -> anyhow::Result<Vec<u8>> {
+        let scale = scale.max(1);
+        let side = (self.size + 2 * QUIET_ZONE) as u32 * scale;
+        let image = GrayImage::from_fn(side, side, |px, py| {
+            let x = (px / scale) as usize;
+            let y = (py / scale) as usize;
+            let dark = (QUIET_ZONE..QUIET_ZONE + self.size).contains(&x)
+                && (QUIET_ZONE..QUIET_ZONE + self.size).contains(&y)
+                && self.is_dark(x - QUIET_ZONE, y - QUIET_ZONE);
+            Luma([if dark { 0 } else { 255 }])
+        });
+
+        let mut buf = Cursor::new(Vec::new());
+        image.write_to(&mut buf, ImageFormat::Png)?;
+        Ok(buf.into_inner())
+    }
+
+    /// SVG scaled to `pixels` wide, one path for all dark modules.
+    pub fn to_svg(&self, pixels: u32) -> String {
+        let side = self.size + 2 * QUIET_ZONE;
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.is_dark(x, y) {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x + QUIET_ZONE, y + QUIET_ZONE));
+                }
+            }
+        }
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" version="1.1" "#,
+                r#"width="{px}" height="{px}" viewBox="0 0 {side} {side}" shape-rendering="crispEdges">"#,
+                r##"<rect width="100%" height="100%" fill="#ffffff"/>"##,
+                r##"<path d="{path}" fill="#000000"/></svg>"##
+            ),
+            px = pixels,
+            side = side,
+            path = path
+        )
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        let i = y * self.size + x;
+        self.modules[i] = dark;
+        self.is_function[i] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            self.draw_finder(x, y);
+        }
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &y) in positions.iter().enumerate() {
+            for (j, &x) in positions.iter().enumerate() {
+                // The three corners already hold finder patterns.
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                self.draw_alignment(x, y);
+            }
+        }
+
+        // Reserve the format areas; real bits are drawn once a mask is chosen.
+        self.draw_format_bits(0);
+        self.draw_version(version);
+    }
+
+    fn draw_finder(&mut self, cx: usize, cy: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                if (0..self.size as i32).contains(&x) && (0..self.size as i32).contains(&y) {
+                    let dist = dx.abs().max(dy.abs());
+                    self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, cx: usize, cy: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let dark = dx.abs().max(dy.abs()) != 1;
+                self.set_function((cx as i32 + dx) as usize, (cy as i32 + dy) as usize, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = (ECC_FORMAT_BITS << 3) | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = ((data << 10) | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self, version: usize) {
+        if version < 7 {
+            return;
+        }
+        let mut rem = version as u32;
+        for _ in 0..12 {
+            rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+        }
+        let bits = ((version as u32) << 12) | rem;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Place data bits in the two-column zigzag, skipping function modules.
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let total_bits = data.len() * 8;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            let upward = (right + 1) & 2 == 0;
+            for vert in 0..size {
+                let y = if upward { size - 1 - vert } else { vert };
+                for x in [right, right - 1] {
+                    let idx = y * size + x;
+                    if !self.is_function[idx] && i < total_bits {
+                        self.modules[idx] = (data[i >> 3] >> (7 - (i & 7))) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// XOR-ing twice restores the original, which `encode` relies on.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let idx = y * self.size + x;
+                if invert && !self.is_function[idx] {
+                    self.modules[idx] = !self.modules[idx];
+                }
+            }
+        }
+    }
+
+    fn penalty_score(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+
+        let lines = (0..size).flat_map(|i| {
+            [
+                (0..size).map(|j| self.is_dark(j, i)).collect::<Vec<_>>(),
+                (0..size).map(|j| self.is_dark(i, j)).collect::<Vec<_>>(),
+            ]
+        });
+        for line in lines {
+            // Rule 1: runs of five or more same-coloured modules.
+            let mut run = 1;
+            for w in line.windows(2) {
+                if w[0] == w[1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        penalty += run - 2;
+                    }
+                    run = 1;
+                }
+            }
+            if run >= 5 {
+                penalty += run - 2;
+            }
+
+            // Rule 3: finder-like 1:1:3:1:1 patterns with four light modules
+            // on one side.
+            const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+            for start in 0..size.saturating_sub(10) {
+                let window = &line[start..start + 11];
+                let light = |s: &[bool]| s.iter().all(|&m| !m);
+                if (window[4..] == FINDER && light(&window[..4]))
+                    || (window[..7] == FINDER && light(&window[7..]))
+                {
+                    penalty += 40;
+                }
+            }
+        }
+
+        // Rule 2: 2x2 blocks of one colour.
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = self.is_dark(x, y);
+                if c == self.is_dark(x + 1, y)
+                    && c == self.is_dark(x, y + 1)
+                    && c == self.is_dark(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        // Rule 4: balance of dark and light modules.
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let total = size * size;
+        let k = (dark * 20)
+            .abs_diff(total * 10)
+            .div_ceil(total)
+            .saturating_sub(1);
+        penalty + k * 10
+    }
+}
+
+fn char_count_bits(version: usize) -> usize {
+    if version <= 9 { 8 } else { 16 }
+}
+
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let num_align = version / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8 - ECC_PER_BLOCK[version] * NUM_BLOCKS[version]
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let num_align = version / 7 + 2;
+    let step = (version * 8 + num_align * 3 + 5) / (num_align * 4 - 4) * 2;
+    let size = version * 4 + 17;
+    let mut positions: Vec<usize> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+fn add_ecc_and_interleave(version: usize, data: &[u8]) -> Vec<u8> {
+    let num_blocks = NUM_BLOCKS[version];
+    let ecc_len = ECC_PER_BLOCK[version];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let num_short_blocks = num_blocks - raw_codewords % num_blocks;
+    let short_block_len = raw_codewords / num_blocks;
+
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut blocks = Vec::with_capacity(num_blocks);
+    let mut offset = 0;
+    for i in 0..num_blocks {
+        let data_len = short_block_len - ecc_len + usize::from(i >= num_short_blocks);
+        let mut block = data[offset..offset + data_len].to_vec();
+        offset += data_len;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        if i < num_short_blocks {
+            // Placeholder so every block has the same length while interleaving.
+            block.push(0);
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_block_len {
+        for (j, block) in blocks.iter().enumerate() {
+            if i != short_block_len - ecc_len || j >= num_short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((u32::from(y) >> i) & 1) * u32::from(x);
+    }
+    z as u8
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root: u8 = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result[0];
+        result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(d, factor);
+        }
+    }
+    result
+}
+
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, len: usize) {
+        for i in (0..len).rev() {
+            self.bits.push((value >> i) & 1 == 1);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.bits
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |b, &bit| (b << 1) | u8::from(bit)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_tables_match_the_spec() {
+        let capacities: Vec<usize> = (1..=MAX_VERSION).map(data_codewords).collect();
+        assert_eq!(capacities, [16, 28, 44, 64, 86, 108, 124, 154, 182, 216]);
+        assert_eq!(alignment_positions(7), [6, 22, 38]);
+    }
+
+    #[test]
+    fn reed_solomon_matches_reference_vector() {
+        // HELLO WORLD, version 1-M, from the ISO/IEC 18004 worked example.
+        let data = [
+            0x20, 0x5B, 0x0B, 0x78, 0xD1, 0x72, 0xDC, 0x4D, 0x43, 0x40, 0xEC, 0x11, 0xEC, 0x11,
+            0xEC, 0x11,
+        ];
+        let ecc = reed_solomon_remainder(&data, &reed_solomon_divisor(10));
+        assert_eq!(
+            ecc,
+            [0xC4, 0x23, 0x27, 0x77, 0xEB, 0xD7, 0xE7, 0xE2, 0x5D, 0x17]
+        );
+    }
+
+    #[test]
+    fn short_links_fit_and_keep_finder_patterns() {
+        let qr = QrCode::encode(b"https://throughyourletters.online/s/1a2B?c=qr").unwrap();
+        assert_eq!(qr.size(), 33); // version 4
+        for (x, y) in [(0, 0), (qr.size() - 7, 0), (0, qr.size() - 7)] {
+            assert!(qr.is_dark(x, y) && qr.is_dark(x + 6, y + 6));
+            assert!(!qr.is_dark(x + 1, y + 1));
+            assert!(qr.is_dark(x + 3, y + 3));
+        }
+        assert!(QrCode::encode(&[b'a'; 300]).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! On-demand image variants: the stored original scaled to fit a requested
+//! box and re-encoded as JPEG, PNG or WebP.
+
+use image::{
+    DynamicImage, ImageFormat,
+    codecs::{jpeg::JpegEncoder, webp::WebPEncoder},
+    imageops::FilterType,
+};
+use serde::Deserialize;
+use std::io::Cursor;
+
+/// Largest edge a variant may have.
+pub const MAX_DIMENSION: u32 = 2048;
+
+/// Requested edges are rounded up to a multiple of this, which bounds how
+/// many distinct variants a single image can accumulate in storage.
+pub const DIMENSION_STEP: u32 = 32;
+
+const JPEG_QUALITY: u8 = 82;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariantFormat {
+    #[serde(alias = "jpg")]
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl VariantFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+        }
+    }
+}
+
+/// Round a requested edge up to the next `DIMENSION_STEP`, capped at
+/// `MAX_DIMENSION`. Zero is treated as unset.
+pub fn normalize_dimension(requested: Option<u32>) -> Option<u32> {
+    requested
+        .filter(|&d| d > 0)
+        .map(|d| d.div_ceil(DIMENSION_STEP) * DIMENSION_STEP)
+        .map(|d| d.min(MAX_DIMENSION))
+}
+
+/// Scale `image` to fit within `width` x `height`, keeping its aspect ratio
+/// and never enlarging it, then encode it as `format`. A missing edge leaves
+/// that side unconstrained.
+pub fn render_variant(
+    image: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    format: VariantFormat,
+) -> anyhow::Result<Vec<u8>> {
+    let max_width = width.unwrap_or(u32::MAX).min(image.width());
+    let max_height = height.unwrap_or(u32::MAX).min(image.height());
+    let resized = if max_width < image.width() || max_height < image.height() {
+        image.resize(max_width, max_height, FilterType::Lanczos3)
+    } else {
+        image.clone()
+    };
+
+    let mut out = Vec::new();
+    match format {
+        VariantFormat::Jpeg => {
+            JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)
+                .encode_image(&DynamicImage::ImageRgb8(resized.to_rgb8()))?;
+        }
+        VariantFormat::Png => {
+            resized.write_to(&mut Cursor::new(&mut out), ImageFormat::Png)?;
+        }
+        VariantFormat::Webp => {
+            DynamicImage::ImageRgba8(resized.to_rgba8())
+                .write_with_encoder(WebPEncoder::new_lossless(&mut out))?;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn photo(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([120, 40, 200])))
+    }
+
+    #[test]
+    fn dimensions_round_up_to_step_and_cap() {
+        assert_eq!(normalize_dimension(None), None);
+        assert_eq!(normalize_dimension(Some(0)), None);
+        assert_eq!(normalize_dimension(Some(1)), Some(DIMENSION_STEP));
+        assert_eq!(normalize_dimension(Some(320)), Some(320));
+        assert_eq!(normalize_dimension(Some(321)), Some(352));
+        assert_eq!(normalize_dimension(Some(10_000)), Some(MAX_DIMENSION));
+    }
+
+    #[test]
+    fn fits_box_keeping_aspect_ratio() {
+        let bytes =
+            render_variant(&photo(800, 400), Some(200), Some(200), VariantFormat::Png).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (200, 100));
+
+        let bytes = render_variant(&photo(800, 400), None, Some(100), VariantFormat::Jpeg).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (200, 100));
+    }
+
+    #[test]
+    fn never_upscales() {
+        let bytes = render_variant(&photo(64, 48), Some(1024), None, VariantFormat::Webp).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (64, 48));
+    }
+}
@@ -0,0 +1,154 @@
+//! Open Graph share cards: the lettering photo, cropped to 1200x630, with the
+//! detected text, contributor and city set on a darkened band along the
+//! bottom.
+
+use super::bitmap_font::{GLYPH_HEIGHT, draw_text, renderable, text_width};
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage, imageops::FilterType};
+use std::io::Cursor;
+
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+
+const MARGIN: u32 = 48;
+const BAND_HEIGHT: u32 = 300;
+const TEXT_SCALE: u32 = 5;
+const TAG_SCALE: u32 = 7;
+const CITY_SCALE: u32 = 4;
+const BRAND_SCALE: u32 = 3;
+const LINE_GAP: u32 = 24;
+const BRAND: &str = "Through Your Letters";
+
+const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+const SHADOW: Rgb<u8> = Rgb([0, 0, 0]);
+const MUTED: Rgb<u8> = Rgb([200, 200, 200]);
+
+/// Render the card and encode it as PNG. Detected text the bitmap font
+/// can't draw, e.g. in non-Latin scripts, is left off.
+pub fn render_share_card(
+    photo: &DynamicImage,
+    detected_text: Option<&str>,
+    contributor_tag: &str,
+    city_name: &str,
+) -> anyhow::Result<Vec<u8>> {
+    let mut card = photo
+        .resize_to_fill(CARD_WIDTH, CARD_HEIGHT, FilterType::Triangle)
+        .to_rgb8();
+    darken_band(&mut card);
+
+    let content_width = CARD_WIDTH - 2 * MARGIN;
+    let city_y = CARD_HEIGHT - MARGIN - GLYPH_HEIGHT * CITY_SCALE;
+    let tag_y = city_y - LINE_GAP - GLYPH_HEIGHT * TAG_SCALE;
+
+    let tag = fit(&format!("@{}", contributor_tag), TAG_SCALE, content_width);
+    draw_shadowed(&mut card, &tag, MARGIN, tag_y, TAG_SCALE, WHITE);
+
+    if let Some(text) = detected_text.map(quoted).filter(|t| !t.is_empty()) {
+        let text = fit(&text, TEXT_SCALE, content_width);
+        let text_y = tag_y - LINE_GAP - GLYPH_HEIGHT * TEXT_SCALE;
+        draw_shadowed(&mut card, &text, MARGIN, text_y, TEXT_SCALE, MUTED);
+    }
+
+    let brand_width = text_width(BRAND, BRAND_SCALE);
+    let city = fit(
+        city_name,
+        CITY_SCALE,
+        content_width.saturating_sub(brand_width + LINE_GAP),
+    );
+    draw_shadowed(&mut card, &city, MARGIN, city_y, CITY_SCALE, WHITE);
+
+    let brand_y = city_y + (GLYPH_HEIGHT * (CITY_SCALE - BRAND_SCALE));
+    draw_shadowed(
+        &mut card,
+        BRAND,
+        CARD_WIDTH - MARGIN - brand_width,
+        brand_y,
+        BRAND_SCALE,
+        MUTED,
+    );
+
+    let mut png = Vec::new();
+    DynamicImage::ImageRgb8(card).write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+    Ok(png)
+}
+
+/// Fade the bottom of the photo towards black so text stays legible on any
+/// background.
+fn darken_band(card: &mut RgbImage) {
+    let start = CARD_HEIGHT - BAND_HEIGHT;
+    for y in start..CARD_HEIGHT {
+        let t = (y - start) as f32 / BAND_HEIGHT as f32;
+        let keep = 1.0 - 0.8 * (t * 1.5).min(1.0);
+        for x in 0..CARD_WIDTH {
+            let pixel = card.get_pixel_mut(x, y);
+            for channel in pixel.0.iter_mut() {
+                *channel = (*channel as f32 * keep) as u8;
+            }
+        }
+    }
+}
+
+fn draw_shadowed(card: &mut RgbImage, text: &str, x: u32, y: u32, scale: u32, color: Rgb<u8>) {
+    let offset = (scale / 2).max(1);
+    draw_text(card, text, x + offset, y + offset, scale, SHADOW);
+    draw_text(card, text, x, y, scale, color);
+}
+
+/// The drawable part of `text` on one line and in quotes, or empty when
+/// nothing of it can be drawn.
+fn quoted(text: &str) -> String {
+    let line = renderable(&text.split_whitespace().collect::<Vec<_>>().join(" ")).replace('"', "");
+    let line = line.trim();
+    if line.is_empty() {
+        String::new()
+    } else {
+        format!("\"{}\"", line)
+    }
+}
+
+/// The drawable part of `text`, shortened with `...` to fit `max_width`.
+fn fit(text: &str, scale: u32, max_width: u32) -> String {
+    let text = renderable(text).trim().to_string();
+    if text_width(&text, scale) <= max_width {
+        return text;
+    }
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate = format!("{}...", chars.iter().collect::<String>().trim_end());
+        if text_width(&candidate, scale) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_png_at_open_graph_size() {
+        let photo = DynamicImage::ImageRgb8(RgbImage::from_pixel(800, 1200, Rgb([180, 60, 30])));
+        let png = render_share_card(&photo, Some("Chai Point"), "sign_hunter", "Mumbai").unwrap();
+
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert_eq!(
+            (decoded.width(), decoded.height()),
+            (CARD_WIDTH, CARD_HEIGHT)
+        );
+    }
+
+    #[test]
+    fn long_text_is_truncated_to_fit() {
+        let fitted = fit("a very long contributor tag indeed", TAG_SCALE, 400);
+        assert!(fitted.ends_with("..."));
+        assert!(text_width(&fitted, TAG_SCALE) <= 400);
+        assert_eq!(fit("Pune", CITY_SCALE, 400), "PUNE");
+    }
+
+    #[test]
+    fn detected_text_is_quoted_on_one_line() {
+        assert_eq!(quoted("Irani\n  Cafe"), "\"IRANI CAFE\"");
+        assert_eq!(quoted("हिन्दी"), "");
+    }
+}
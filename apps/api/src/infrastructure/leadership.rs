@@ -0,0 +1,251 @@
+//! Leader election for workers that must run on one instance at a time.
+//!
+//! Each singleton worker has a lease in Redis, `leader:{role}`, holding the
+//! id of the instance running it. Every instance competes for the lease; the
+//! one holding it runs the worker and renews the lease three times per
+//! lifetime, the others retry as often. When the leader dies its lease
+//! expires and another instance takes over within one lifetime. A leader
+//! that cannot renew stops its worker before the lease can lapse, so a role
+//! never runs on two instances at once. A leader whose worker stops for
+//! shutdown hands the lease over straight away.
+
+use crate::infrastructure::shutdown::Shutdown;
+use redis::Script;
+use std::{
+    future::Future,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+/// Extends the lease only while this instance still holds it.
+const RENEW_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Gives up the lease only while this instance still holds it.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+pub fn lease_key(role: &str) -> String {
+    format!("leader:{}", role)
+}
+
+/// This process among the replicas: the platform's replica id or the host
+/// name, plus the process id to tell apart several processes on one host.
+pub fn instance_id() -> &'static str {
+    static INSTANCE: OnceLock<String> = OnceLock::new();
+    INSTANCE.get_or_init(|| {
+        let host = std::env::var("RAILWAY_REPLICA_ID")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "local".to_string());
+        format!("{}:{}", host, std::process::id())
+    })
+}
+
+/// The instance currently leading each of `roles`, in order.
+pub async fn current_leaders(
+    redis: &redis::Client,
+    roles: &[&str],
+) -> redis::RedisResult<Vec<Option<String>>> {
+    if roles.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let keys: Vec<String> = roles.iter().map(|role| lease_key(role)).collect();
+    redis::cmd("MGET").arg(&keys).query_async(&mut conn).await
+}
+
+#[derive(Clone)]
+pub struct LeaderElection {
+    /// `None` when every role runs on this instance without election.
+    redis: Option<redis::Client>,
+    instance: String,
+    lease: Duration,
+    shutdown: Shutdown,
+}
+
+impl LeaderElection {
+    pub fn new(redis: redis::Client, lease: Duration) -> Self {
+        Self {
+            redis: Some(redis),
+            instance: instance_id().to_string(),
+            lease,
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Runs every role here, for deployments with a single instance.
+    pub fn local() -> Self {
+        Self {
+            redis: None,
+            instance: instance_id().to_string(),
+            lease: Duration::ZERO,
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Name this instance holds leases under.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = instance.into();
+        self
+    }
+
+    /// Stop competing for leases once `shutdown` fires.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn instance(&self) -> &str {
+        &self.instance
+    }
+
+    fn retry_interval(&self) -> Duration {
+        self.lease / 3
+    }
+
+    /// Runs `work` whenever this instance leads `role`. On losing the lease
+    /// the running `work` is dropped at its next await and this instance
+    /// goes back to competing for it. Returns once `work` completes, or on
+    /// shutdown while not leading.
+    pub async fn run<F, Fut>(&self, role: &str, mut work: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        if self.redis.is_none() {
+            return work().await;
+        }
+        while !self.shutdown.is_triggered() {
+            match self.try_acquire(role).await {
+                Ok(true) => {
+                    tracing::info!(role, instance = %self.instance, "Took worker leadership");
+                    tokio::select! {
+                        _ = work() => {
+                            if let Err(e) = self.release(role).await {
+                                tracing::warn!(role, "Failed to release leader lease: {}", e);
+                            }
+                            return;
+                        }
+                        _ = self.hold(role) => {
+                            tracing::warn!(role, instance = %self.instance, "Lost worker leadership");
+                        }
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!(role, "Leader election failed: {}", e),
+            }
+            self.shutdown.sleep(self.retry_interval()).await;
+        }
+    }
+
+    /// Takes the lease for `role` if it is free, or renews it if this
+    /// instance already holds it.
+    pub async fn try_acquire(&self, role: &str) -> redis::RedisResult<bool> {
+        let Some(redis) = &self.redis else {
+            return Ok(true);
+        };
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        let taken = redis::cmd("SET")
+            .arg(lease_key(role))
+            .arg(&self.instance)
+            .arg("NX")
+            .arg("PX")
+            .arg(self.lease.as_millis() as u64)
+            .query_async::<Option<String>>(&mut conn)
+            .await?
+            .is_some();
+        if taken {
+            return Ok(true);
+        }
+        self.renew(role).await
+    }
+
+    /// Extends the lease, returning false when this instance no longer
+    /// holds it.
+    pub async fn renew(&self, role: &str) -> redis::RedisResult<bool> {
+        let Some(redis) = &self.redis else {
+            return Ok(true);
+        };
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        let script = Script::new(RENEW_SCRIPT);
+        let renewed: i64 = script
+            .key(lease_key(role))
+            .arg(&self.instance)
+            .arg(self.lease.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(renewed == 1)
+    }
+
+    /// Hands the lease back so another instance can take over at once
+    /// rather than after it expires.
+    pub async fn release(&self, role: &str) -> redis::RedisResult<()> {
+        let Some(redis) = &self.redis else {
+            return Ok(());
+        };
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        let script = Script::new(RELEASE_SCRIPT);
+        let _: i64 = script
+            .key(lease_key(role))
+            .arg(&self.instance)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Renews the lease until it is lost, or until renewals have failed for
+    /// so long that it may lapse before the next attempt.
+    async fn hold(&self, role: &str) {
+        let mut renewed = Instant::now();
+        loop {
+            tokio::time::sleep(self.retry_interval()).await;
+            match self.renew(role).await {
+                Ok(true) => renewed = Instant::now(),
+                Ok(false) => return,
+                Err(e) => {
+                    tracing::warn!(role, "Failed to renew leader lease: {}", e);
+                    if renewed.elapsed() + self.retry_interval() >= self.lease {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    #[test]
+    fn instance_id_is_stable_and_names_the_process() {
+        assert_eq!(instance_id(), instance_id());
+        assert!(instance_id().ends_with(&format!(":{}", std::process::id())));
+    }
+
+    #[tokio::test]
+    async fn local_election_runs_work_without_redis() {
+        let runs = Arc::new(AtomicU32::new(0));
+        let counted = runs.clone();
+        let election = LeaderElection::local();
+        election
+            .run("test_role", || async {
+                counted.fetch_add(1, Ordering::SeqCst);
+            })
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(election.try_acquire("test_role").await.unwrap());
+    }
+}
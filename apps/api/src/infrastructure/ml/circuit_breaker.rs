@@ -0,0 +1,232 @@
+//! Circuit breaker for remote inference providers.
+//!
+//! After `failure_threshold` consecutive failures the breaker opens and calls
+//! are refused for `cool_down`, so an outage costs each job nothing instead of
+//! a full timeout. Once the cool-down has passed a single half-open probe is
+//! let through: success closes the breaker, failure re-opens it. A probe that
+//! never reports back (cancelled, or skipped after acquiring) is given up on
+//! after another cool-down.
+
+use crate::infrastructure::monitoring::{Alert, AlertSeverity, PerformanceMonitor};
+use serde::Serialize;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Returned instead of calling the provider while the breaker is open.
+#[derive(Debug)]
+pub struct CircuitOpen(pub &'static str);
+
+impl std::fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} circuit breaker is open", self.0)
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+#[derive(Debug)]
+enum Inner {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { probe_started: Instant },
+}
+
+impl Inner {
+    fn state(&self) -> CircuitState {
+        match self {
+            Self::Closed { .. } => CircuitState::Closed,
+            Self::Open { .. } => CircuitState::Open,
+            Self::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+}
+
+pub struct CircuitBreaker {
+    name: &'static str,
+    failure_threshold: u32,
+    cool_down: Duration,
+    inner: Mutex<Inner>,
+    performance: Option<Arc<PerformanceMonitor>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: &'static str, failure_threshold: u32, cool_down: Duration) -> Self {
+        Self {
+            name,
+            failure_threshold: failure_threshold.max(1),
+            cool_down,
+            inner: Mutex::new(Inner::Closed { failures: 0 }),
+            performance: None,
+        }
+    }
+
+    /// Record state transitions as `circuit_breaker.{name}.{state}` counters.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap().state()
+    }
+
+    /// Whether a call may go ahead. Every `Ok(())` must be followed by
+    /// `record_success` or `record_failure`.
+    pub async fn acquire(&self) -> Result<(), CircuitOpen> {
+        let now = Instant::now();
+        let transition = {
+            let mut inner = self.inner.lock().unwrap();
+            match *inner {
+                Inner::Closed { .. } => return Ok(()),
+                Inner::Open { until } if now < until => return Err(CircuitOpen(self.name)),
+                Inner::HalfOpen { probe_started }
+                    if now.duration_since(probe_started) < self.cool_down =>
+                {
+                    return Err(CircuitOpen(self.name));
+                }
+                _ => {
+                    let from = inner.state();
+                    *inner = Inner::HalfOpen { probe_started: now };
+                    (from != CircuitState::HalfOpen).then_some(from)
+                }
+            }
+        };
+        if let Some(from) = transition {
+            self.transitioned(from, CircuitState::HalfOpen).await;
+        }
+        Ok(())
+    }
+
+    pub async fn record_success(&self) {
+        let from = {
+            let mut inner = self.inner.lock().unwrap();
+            let from = inner.state();
+            *inner = Inner::Closed { failures: 0 };
+            from
+        };
+        if from != CircuitState::Closed {
+            self.transitioned(from, CircuitState::Closed).await;
+        }
+    }
+
+    pub async fn record_failure(&self) {
+        let from = {
+            let mut inner = self.inner.lock().unwrap();
+            let from = inner.state();
+            match *inner {
+                Inner::Closed { failures } if failures + 1 < self.failure_threshold => {
+                    *inner = Inner::Closed {
+                        failures: failures + 1,
+                    };
+                    return;
+                }
+                Inner::Open { .. } => return,
+                _ => {
+                    *inner = Inner::Open {
+                        until: Instant::now() + self.cool_down,
+                    };
+                }
+            }
+            from
+        };
+        self.transitioned(from, CircuitState::Open).await;
+    }
+
+    async fn transitioned(&self, from: CircuitState, to: CircuitState) {
+        if to == CircuitState::Open {
+            let alert = Alert::new(
+                AlertSeverity::Warning,
+                "Remote inference circuit opened",
+                &format!(
+                    "{} calls suspended for {}s after repeated failures",
+                    self.name,
+                    self.cool_down.as_secs()
+                ),
+                &format!("circuit_breaker.{}", self.name),
+                self.failure_threshold as f64,
+                self.failure_threshold as f64,
+            );
+            let payload = serde_json::to_string(&alert).unwrap_or_default();
+            tracing::warn!(alert = %payload, from = from.as_str(), "{}", alert.title);
+        } else {
+            tracing::info!(
+                breaker = self.name,
+                from = from.as_str(),
+                to = to.as_str(),
+                "Circuit breaker state changed"
+            );
+        }
+        if let Some(performance) = &self.performance {
+            performance
+                .record_circuit_breaker_transition(self.name, to.as_str())
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new("test", 2, Duration::from_secs(60));
+        breaker.record_failure().await;
+        breaker.record_success().await;
+        breaker.record_failure().await;
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.record_failure().await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.acquire().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn half_open_allows_a_single_probe() {
+        let breaker = CircuitBreaker::new("test", 1, Duration::from_millis(20));
+        breaker.record_failure().await;
+        tokio::time::sleep(Duration::from_millis(25)).await;
+
+        assert!(breaker.acquire().await.is_ok());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.acquire().await.is_err());
+
+        breaker.record_success().await;
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.acquire().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn failed_probe_reopens() {
+        let breaker = CircuitBreaker::new("test", 3, Duration::from_millis(20));
+        for _ in 0..3 {
+            breaker.record_failure().await;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+        assert!(breaker.acquire().await.is_ok());
+
+        breaker.record_failure().await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.acquire().await.is_err());
+    }
+}
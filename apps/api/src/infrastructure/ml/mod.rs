@@ -1,4 +1,8 @@
+pub mod circuit_breaker;
+pub mod onnx_style_classifier;
 pub mod onnx_text_detector;
+pub mod remote_inference_cache;
+pub mod tesseract_service;
 pub mod traits;
 
 pub use onnx_text_detector::OnnxTextDetector;
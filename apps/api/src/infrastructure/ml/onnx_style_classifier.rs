@@ -0,0 +1,146 @@
+//! Local lettering-style classifier.
+//!
+//! Expects an image classification model taking a `[N, 3, 224, 224]`
+//! ImageNet-normalised tensor and returning `[N, 5]` logits in `STYLE_LABELS`
+//! order. Runs on CPU; the model is small enough that an accelerator buys
+//! little.
+
+use super::traits::StyleClassification;
+use image::imageops::FilterType;
+use ndarray::{Array, IxDyn};
+use ort::{session::Session, value::Value};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Output classes, in model output order.
+pub const STYLE_LABELS: [&str; 5] = ["hand-painted", "neon", "carved", "stencil", "vinyl"];
+
+const INPUT_SIZE: u32 = 224;
+const MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+pub struct OnnxStyleClassifier {
+    session: Option<Mutex<Session>>,
+    min_confidence: f32,
+}
+
+impl OnnxStyleClassifier {
+    /// A missing model file leaves the classifier inactive rather than
+    /// failing startup, like the text detector.
+    pub fn new(model_path: &str, enabled: bool, min_confidence: f32) -> anyhow::Result<Self> {
+        let mut classifier = Self {
+            session: None,
+            min_confidence,
+        };
+        if !enabled {
+            return Ok(classifier);
+        }
+        if !Path::new(model_path).exists() {
+            tracing::warn!(
+                "Style model not found at {}. Falling back to heuristic style detection.",
+                model_path
+            );
+            return Ok(classifier);
+        }
+
+        let mut session = Session::builder()?.commit_from_file(model_path)?;
+        let blank =
+            image::RgbImage::from_pixel(INPUT_SIZE, INPUT_SIZE, image::Rgb([255, 255, 255]));
+        let probabilities = Self::infer(&mut session, &blank)?;
+        if probabilities.len() != STYLE_LABELS.len() {
+            anyhow::bail!(
+                "Style model returned {} classes, expected {}",
+                probabilities.len(),
+                STYLE_LABELS.len()
+            );
+        }
+
+        tracing::info!(path = %model_path, "ONNX style classifier loaded");
+        classifier.session = Some(Mutex::new(session));
+        Ok(classifier)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Most likely style, or `None` when no model is loaded or the top class
+    /// is below the configured minimum confidence.
+    pub fn classify(&self, image_data: &[u8]) -> anyhow::Result<Option<StyleClassification>> {
+        let Some(session) = &self.session else {
+            return Ok(None);
+        };
+
+        let image = image::load_from_memory(image_data)?
+            .resize_exact(INPUT_SIZE, INPUT_SIZE, FilterType::Triangle)
+            .to_rgb8();
+        let probabilities = {
+            let mut session = session
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Failed to acquire style session lock"))?;
+            Self::infer(&mut session, &image)?
+        };
+
+        Ok(top_class(&probabilities).filter(|c| c.confidence >= self.min_confidence))
+    }
+
+    fn infer(session: &mut Session, image: &image::RgbImage) -> anyhow::Result<Vec<f32>> {
+        let input = preprocess(image);
+        let shape: Vec<i64> = input.shape().iter().map(|&d| d as i64).collect();
+        let (data, _offset) = input.into_raw_vec_and_offset();
+        let outputs = session.run(ort::inputs![Value::from_array((shape, data))?])?;
+        let (_, logits) = outputs[0].try_extract_tensor::<f32>()?;
+        Ok(softmax(logits))
+    }
+}
+
+fn preprocess(image: &image::RgbImage) -> Array<f32, IxDyn> {
+    let size = INPUT_SIZE as usize;
+    let mut array = Array::zeros(IxDyn(&[1, 3, size, size]));
+    for (x, y, pixel) in image.enumerate_pixels() {
+        for c in 0..3 {
+            array[[0, c, y as usize, x as usize]] = (pixel[c] as f32 / 255.0 - MEAN[c]) / STD[c];
+        }
+    }
+    array
+}
+
+fn softmax(logits: &[f32]) -> Vec<f32> {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exps: Vec<f32> = logits.iter().map(|&l| (l - max).exp()).collect();
+    let sum: f32 = exps.iter().sum();
+    exps.into_iter().map(|e| e / sum).collect()
+}
+
+fn top_class(probabilities: &[f32]) -> Option<StyleClassification> {
+    probabilities
+        .iter()
+        .zip(STYLE_LABELS)
+        .max_by(|a, b| a.0.total_cmp(b.0))
+        .map(|(&confidence, style)| StyleClassification {
+            style: style.to_string(),
+            confidence,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_most_probable_label() {
+        let probabilities = softmax(&[0.1, 3.0, 0.2, -1.0, 0.5]);
+        assert!((probabilities.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+
+        let top = top_class(&probabilities).unwrap();
+        assert_eq!(top.style, "neon");
+        assert!(top.confidence > 0.8);
+    }
+
+    #[test]
+    fn inactive_without_a_model() {
+        let classifier = OnnxStyleClassifier::new("missing.onnx", true, 0.5).unwrap();
+        assert!(!classifier.is_active());
+        assert!(classifier.classify(b"not an image").unwrap().is_none());
+    }
+}
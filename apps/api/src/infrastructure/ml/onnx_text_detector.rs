@@ -1,19 +1,80 @@
 use super::traits::{MlService, StyleClassification, TextDetectionResult};
+use crate::config::ExecutionProviderKind;
+use crate::infrastructure::monitoring::PerformanceMonitor;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use image::imageops::FilterType;
 use ndarray::{Array, IxDyn};
-use ort::{session::Session, value::Value};
+use ort::{
+    ep::{self, ExecutionProvider},
+    session::{Session, builder::SessionBuilder},
+    value::Value,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+/// Side length of the square model input.
+const INPUT_SIZE: u32 = 640;
+
+/// Metadata about the model currently serving inference.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    pub source: String,
+    pub sha256: String,
+    pub size_bytes: usize,
+    pub loaded_at: DateTime<Utc>,
+    /// Highest-priority provider that registered for this session. Operators
+    /// it does not support still run on CPU inside ONNX Runtime.
+    pub execution_provider: String,
+    pub validation: ModelValidation,
+}
 
-pub struct OnnxTextDetector {
+/// Result of running a candidate model on the golden image.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelValidation {
+    /// `None` when no golden image is configured and a blank frame was used.
+    pub golden_image: Option<String>,
+    pub output_shape: Vec<usize>,
+    pub detected_text: String,
+}
+
+struct LoadedModel {
     // Wrap Session in Mutex to allow mutable access (run) from immutable &self
-    session: Option<Mutex<Session>>,
+    session: Arc<Mutex<Session>>,
+    info: ModelInfo,
+}
+
+pub struct OnnxTextDetector {
+    /// Swapped wholesale on reload. In-flight inferences keep the `Arc` of
+    /// the session they started with.
+    model: RwLock<Option<LoadedModel>>,
     enabled: bool,
+    golden_image_path: Option<String>,
+    execution_providers: Vec<ExecutionProviderKind>,
+    gpu_device_id: i32,
+    performance: Option<Arc<PerformanceMonitor>>,
 }
 
 impl OnnxTextDetector {
-    pub fn new(model_path: &str, enabled: bool) -> anyhow::Result<Self> {
+    pub fn new(
+        model_path: &str,
+        enabled: bool,
+        golden_image_path: Option<String>,
+        execution_providers: &[ExecutionProviderKind],
+        gpu_device_id: i32,
+    ) -> anyhow::Result<Self> {
+        let detector = Self {
+            model: RwLock::new(None),
+            enabled,
+            golden_image_path,
+            execution_providers: execution_providers.to_vec(),
+            gpu_device_id,
+            performance: None,
+        };
+
         if !enabled || !Path::new(model_path).exists() {
             if enabled {
                 tracing::warn!(
@@ -21,92 +82,253 @@ impl OnnxTextDetector {
                     model_path
                 );
             }
-            return Ok(Self {
-                session: None,
-                enabled: false,
-            });
+            return Ok(detector);
         }
 
-        let session = Session::builder()?.commit_from_file(model_path)?;
+        detector.reload_from_file(model_path)?;
+        Ok(detector)
+    }
 
-        Ok(Self {
-            session: Some(Mutex::new(session)),
-            enabled: true,
-        })
+    /// Record per-provider and per-model inference latency, and inference
+    /// failures, in `performance`.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
     }
 
-    fn preprocess_image(&self, image_data: &[u8]) -> anyhow::Result<Array<f32, IxDyn>> {
-        let img = image::load_from_memory(image_data)?;
-        let img_resized = img.resize_exact(640, 640, FilterType::Triangle);
-        let img_rgb = img_resized.to_rgb8();
-
-        let (width, height) = img_rgb.dimensions();
-        let mut array = Array::zeros(IxDyn(&[1, 3, height as usize, width as usize]));
-
-        for y in 0..height {
-            for x in 0..width {
-                let pixel = img_rgb.get_pixel(x, y);
-                array[[0, 0, y as usize, x as usize]] = pixel[0] as f32 / 255.0;
-                array[[0, 1, y as usize, x as usize]] = pixel[1] as f32 / 255.0;
-                array[[0, 2, y as usize, x as usize]] = pixel[2] as f32 / 255.0;
+    /// Whether a model is loaded and local inference should run.
+    fn is_active(&self) -> bool {
+        self.enabled && self.model.read().is_ok_and(|m| m.is_some())
+    }
+
+    pub fn model_info(&self) -> Option<ModelInfo> {
+        self.model
+            .read()
+            .ok()
+            .and_then(|m| m.as_ref().map(|m| m.info.clone()))
+    }
+
+    pub fn reload_from_file(&self, path: &str) -> anyhow::Result<ModelInfo> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read model file {}: {}", path, e))?;
+        self.load_model(&bytes, path)
+    }
+
+    /// Build a session from `model_bytes`, validate it against the golden
+    /// image and swap it in. On any failure the current model keeps serving.
+    /// Blocking: callers on the async runtime should use `spawn_blocking`.
+    pub fn load_model(&self, model_bytes: &[u8], source: &str) -> anyhow::Result<ModelInfo> {
+        if !self.enabled {
+            anyhow::bail!("ML processing is disabled");
+        }
+
+        let (mut session, execution_provider) = self.build_session(model_bytes)?;
+        let validation = self.validate(&mut session)?;
+
+        let info = ModelInfo {
+            source: source.to_string(),
+            sha256: Self::model_hash(model_bytes),
+            size_bytes: model_bytes.len(),
+            loaded_at: Utc::now(),
+            execution_provider: execution_provider.to_string(),
+            validation,
+        };
+
+        let mut model = self
+            .model
+            .write()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire model lock"))?;
+        *model = Some(LoadedModel {
+            session: Arc::new(Mutex::new(session)),
+            info: info.clone(),
+        });
+
+        tracing::info!(
+            source = %info.source,
+            sha256 = %info.sha256,
+            execution_provider = %info.execution_provider,
+            "ONNX text detection model loaded"
+        );
+        Ok(info)
+    }
+
+    /// Commit a session on the configured providers. If registration or the
+    /// commit fails on an accelerator, the model is loaded on CPU instead.
+    fn build_session(&self, model_bytes: &[u8]) -> anyhow::Result<(Session, &'static str)> {
+        let mut builder = Session::builder()?;
+        let mut registered = None;
+        for &kind in &self.execution_providers {
+            if kind == ExecutionProviderKind::Cpu {
+                break;
+            }
+            match self.register_provider(kind, &mut builder) {
+                Ok(()) => {
+                    registered.get_or_insert(kind.as_str());
+                }
+                Err(e) => tracing::warn!(
+                    provider = kind.as_str(),
+                    "Execution provider unavailable, skipping: {}",
+                    e
+                ),
             }
         }
 
-        Ok(array)
+        let Some(provider) = registered else {
+            return Ok((
+                builder.commit_from_memory(model_bytes)?,
+                ExecutionProviderKind::Cpu.as_str(),
+            ));
+        };
+        match builder.commit_from_memory(model_bytes) {
+            Ok(session) => Ok((session, provider)),
+            Err(e) => {
+                tracing::warn!(
+                    provider,
+                    "Failed to create ONNX session, falling back to CPU: {}",
+                    e
+                );
+                Ok((
+                    Session::builder()?.commit_from_memory(model_bytes)?,
+                    ExecutionProviderKind::Cpu.as_str(),
+                ))
+            }
+        }
     }
 
-    fn extract_text_from_detections(&self, output: &Array<f32, IxDyn>) -> String {
-        let shape = output.shape();
-        if shape.len() < 2 {
-            return String::new();
+    fn register_provider(
+        &self,
+        kind: ExecutionProviderKind,
+        builder: &mut SessionBuilder,
+    ) -> Result<(), ep::RegisterError> {
+        match kind {
+            ExecutionProviderKind::TensorRt => ep::TensorRT::default()
+                .with_device_id(self.gpu_device_id)
+                .register(builder),
+            ExecutionProviderKind::Cuda => ep::CUDA::default()
+                .with_device_id(self.gpu_device_id)
+                .register(builder),
+            ExecutionProviderKind::CoreMl => ep::CoreML::default().register(builder),
+            ExecutionProviderKind::Cpu => ep::CPU::default().register(builder),
         }
+    }
 
-        let threshold = 0.5;
-        let mut detected_regions = Vec::new();
+    pub fn model_hash(model_bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(model_bytes))
+    }
 
-        if shape.len() == 3 && shape[2] >= 5 {
-            for detection_idx in 0..shape[1] {
-                let confidence = output[[0, detection_idx, 4]];
-                if confidence > threshold {
-                    detected_regions.push(format!("text_region_{}", detected_regions.len()));
-                }
+    /// A candidate must produce `[batch, detections, >=5]` output. With a
+    /// golden image configured it must also find text in it.
+    fn validate(&self, session: &mut Session) -> anyhow::Result<ModelValidation> {
+        let image = match &self.golden_image_path {
+            Some(path) => {
+                let data = std::fs::read(path)
+                    .map_err(|e| anyhow::anyhow!("Failed to read golden image {}: {}", path, e))?;
+                Self::load_input_image(&data)?
             }
+            None => {
+                image::RgbImage::from_pixel(INPUT_SIZE, INPUT_SIZE, image::Rgb([255, 255, 255]))
+            }
+        };
+
+        let output = Self::infer(session, self.preprocess_images(&[image]))?;
+        let output_shape = output.shape().to_vec();
+        if output_shape.len() != 3 || output_shape[2] < 5 {
+            anyhow::bail!(
+                "Model output shape {:?} is not [batch, detections, >=5]",
+                output_shape
+            );
         }
 
-        if detected_regions.is_empty() {
-            "No text detected".to_string()
-        } else {
-            format!("Detected {} text regions", detected_regions.len())
+        let detected_text = self.extract_text_from_detections(&output, 0);
+        if self.golden_image_path.is_some() && detected_text == "No text detected" {
+            anyhow::bail!("Model detected no text in the golden image");
         }
+
+        Ok(ModelValidation {
+            golden_image: self.golden_image_path.clone(),
+            output_shape,
+            detected_text,
+        })
     }
-}
 
-#[async_trait]
-impl MlService for OnnxTextDetector {
-    async fn detect_text(&self, image_data: &[u8]) -> anyhow::Result<TextDetectionResult> {
-        if !self.enabled || self.session.is_none() {
-            return Ok(TextDetectionResult {
-                detected_text: String::new(),
-                confidence: 0.0,
-                language: None,
-            });
+    fn load_input_image(image_data: &[u8]) -> anyhow::Result<image::RgbImage> {
+        let img = image::load_from_memory(image_data)?;
+        Ok(img
+            .resize_exact(INPUT_SIZE, INPUT_SIZE, FilterType::Triangle)
+            .to_rgb8())
+    }
+
+    /// Build an NCHW tensor with one slot per image.
+    fn preprocess_images(&self, images: &[image::RgbImage]) -> Array<f32, IxDyn> {
+        let size = INPUT_SIZE as usize;
+        let mut array = Array::zeros(IxDyn(&[images.len(), 3, size, size]));
+
+        for (n, img_rgb) in images.iter().enumerate() {
+            for (x, y, pixel) in img_rgb.enumerate_pixels() {
+                let (x, y) = (x as usize, y as usize);
+                array[[n, 0, y, x]] = pixel[0] as f32 / 255.0;
+                array[[n, 1, y, x]] = pixel[1] as f32 / 255.0;
+                array[[n, 2, y, x]] = pixel[2] as f32 / 255.0;
+            }
         }
 
-        // Prepare input
-        let input_tensor = self.preprocess_image(image_data)?;
+        array
+    }
+
+    async fn run_session(
+        &self,
+        input_tensor: Array<f32, IxDyn>,
+    ) -> anyhow::Result<Array<f32, IxDyn>> {
+        let (session, provider, model) = self
+            .model
+            .read()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire model lock"))?
+            .as_ref()
+            .map(|m| {
+                (
+                    m.session.clone(),
+                    m.info.execution_provider.clone(),
+                    format!("onnx:{}", &m.info.sha256[..12]),
+                )
+            })
+            .ok_or_else(|| anyhow::anyhow!("ONNX session not loaded"))?;
+
+        let images = input_tensor.shape()[0];
+        let started = Instant::now();
+        // LOCK THE SESSION
+        // We need a mutable reference to run the session, so we lock the Mutex.
+        // The guard is dropped before any await below.
+        let output = session
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Failed to acquire session lock"))
+            .and_then(|mut session| Self::infer(&mut session, input_tensor));
+
+        if let Some(performance) = &self.performance {
+            match &output {
+                Ok(_) => {
+                    let elapsed = started.elapsed();
+                    performance
+                        .record_ml_inference(&provider, images, elapsed)
+                        .await;
+                    performance
+                        .record_ml_model_latency(&model, images, elapsed)
+                        .await;
+                }
+                Err(_) => performance.record_ml_failure("onnx_inference").await,
+            }
+        }
+        output
+    }
 
+    fn infer(
+        session: &mut Session,
+        input_tensor: Array<f32, IxDyn>,
+    ) -> anyhow::Result<Array<f32, IxDyn>> {
         // Manual conversion (Shape + Data) to avoid version mismatch errors
         let input_shape: Vec<i64> = input_tensor.shape().iter().map(|&d| d as i64).collect();
         let (input_data, _offset) = input_tensor.into_raw_vec_and_offset();
         let input_value = Value::from_array((input_shape, input_data))?;
 
-        // LOCK THE SESSION
-        // We need a mutable reference to run the session, so we lock the Mutex.
-        let session_mutex = self.session.as_ref().unwrap();
-        let mut session = session_mutex
-            .lock()
-            .map_err(|_| anyhow::anyhow!("Failed to acquire session lock"))?;
-
         // Run inference
         let outputs = session.run(ort::inputs![input_value])?;
 
@@ -115,24 +337,143 @@ impl MlService for OnnxTextDetector {
 
         // Reconstruct ndarray
         let shape_vec: Vec<usize> = extract_shape.iter().map(|&d| d as usize).collect();
-        let output_array = Array::from_shape_vec(IxDyn(&shape_vec), extract_data.to_vec())?;
+        Ok(Array::from_shape_vec(
+            IxDyn(&shape_vec),
+            extract_data.to_vec(),
+        )?)
+    }
+
+    fn extract_text_from_detections(
+        &self,
+        output: &Array<f32, IxDyn>,
+        batch_index: usize,
+    ) -> String {
+        let shape = output.shape();
+        if shape.len() < 2 {
+            return String::new();
+        }
+
+        let threshold = 0.5;
+        let mut detected_regions = Vec::new();
+
+        if shape.len() == 3 && shape[2] >= 5 && batch_index < shape[0] {
+            for detection_idx in 0..shape[1] {
+                let confidence = output[[batch_index, detection_idx, 4]];
+                if confidence > threshold {
+                    detected_regions.push(format!("text_region_{}", detected_regions.len()));
+                }
+            }
+        }
+
+        if detected_regions.is_empty() {
+            "No text detected".to_string()
+        } else {
+            format!("Detected {} text regions", detected_regions.len())
+        }
+    }
 
-        let detected_text = self.extract_text_from_detections(&output_array);
+    fn detection_result(detected_text: String) -> TextDetectionResult {
         let confidence = if detected_text.is_empty() || detected_text == "No text detected" {
             0.0
         } else {
             0.85
         };
 
-        Ok(TextDetectionResult {
+        TextDetectionResult {
             detected_text,
             confidence,
             language: Some("multi".to_string()),
-        })
+        }
+    }
+
+    fn disabled_result() -> TextDetectionResult {
+        TextDetectionResult {
+            detected_text: String::new(),
+            confidence: 0.0,
+            language: None,
+        }
+    }
+}
+
+#[async_trait]
+impl MlService for OnnxTextDetector {
+    async fn detect_text(&self, image_data: &[u8]) -> anyhow::Result<TextDetectionResult> {
+        if !self.is_active() {
+            return Ok(Self::disabled_result());
+        }
+
+        let input_tensor = self.preprocess_images(&[Self::load_input_image(image_data)?]);
+        let output_array = self.run_session(input_tensor).await?;
+
+        Ok(Self::detection_result(
+            self.extract_text_from_detections(&output_array, 0),
+        ))
+    }
+
+    /// Runs every decodable image through a single `[N, 3, 640, 640]`
+    /// inference. Models exported with a fixed batch dimension reject that, in
+    /// which case each image is run on its own.
+    async fn detect_text_batch(
+        &self,
+        images: &[&[u8]],
+    ) -> Vec<anyhow::Result<TextDetectionResult>> {
+        if !self.is_active() {
+            return images.iter().map(|_| Ok(Self::disabled_result())).collect();
+        }
+
+        let mut results: Vec<Option<anyhow::Result<TextDetectionResult>>> =
+            images.iter().map(|_| None).collect();
+        let mut decoded = Vec::with_capacity(images.len());
+        let mut slots = Vec::with_capacity(images.len());
+        for (i, image_data) in images.iter().enumerate() {
+            match Self::load_input_image(image_data) {
+                Ok(img) => {
+                    decoded.push(img);
+                    slots.push(i);
+                }
+                Err(e) => results[i] = Some(Err(e)),
+            }
+        }
+
+        if !decoded.is_empty() {
+            match self.run_session(self.preprocess_images(&decoded)).await {
+                Ok(output) => {
+                    for (batch_index, &slot) in slots.iter().enumerate() {
+                        results[slot] = Some(Ok(Self::detection_result(
+                            self.extract_text_from_detections(&output, batch_index),
+                        )));
+                    }
+                }
+                Err(e) if decoded.len() > 1 => {
+                    tracing::warn!(
+                        batch = decoded.len(),
+                        "Batched ONNX inference failed ({}); running images individually",
+                        e
+                    );
+                    for (img, &slot) in decoded.iter().zip(&slots) {
+                        let single = self
+                            .run_session(self.preprocess_images(std::slice::from_ref(img)))
+                            .await
+                            .map(|output| {
+                                Self::detection_result(
+                                    self.extract_text_from_detections(&output, 0),
+                                )
+                            });
+                        results[slot] = Some(single);
+                    }
+                }
+                Err(e) => results[slots[0]] = Some(Err(e)),
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|r| r.unwrap_or_else(|| Err(anyhow::anyhow!("image was not processed"))))
+            .collect()
     }
 
     async fn classify_style(&self, image_data: &[u8]) -> anyhow::Result<StyleClassification> {
-        if !self.enabled {
+        if !self.is_active() {
             return Ok(StyleClassification {
                 style: "unknown".to_string(),
                 confidence: 0.0,
@@ -236,8 +577,45 @@ impl MlService for OnnxTextDetector {
         }
 
         let mut colors: Vec<_> = color_counts.into_iter().collect();
-        colors.sort_by(|a, b| b.1.cmp(&a.1));
+        colors.sort_by_key(|c| std::cmp::Reverse(c.1));
 
         Ok(colors.into_iter().take(5).map(|(color, _)| color).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batched_tensors_keep_images_in_their_own_slot() {
+        let detector = OnnxTextDetector::new("", false, None, &[], 0).unwrap();
+        let white =
+            image::RgbImage::from_pixel(INPUT_SIZE, INPUT_SIZE, image::Rgb([255, 255, 255]));
+        let black = image::RgbImage::new(INPUT_SIZE, INPUT_SIZE);
+
+        let input = detector.preprocess_images(&[white, black]);
+        assert_eq!(input.shape(), &[2, 3, 640, 640]);
+        assert_eq!(input[[0, 1, 10, 10]], 1.0);
+        assert_eq!(input[[1, 1, 10, 10]], 0.0);
+
+        let mut output = Array::zeros(IxDyn(&[2, 3, 6]));
+        output[[1, 0, 4]] = 0.9;
+        output[[1, 2, 4]] = 0.7;
+        assert_eq!(
+            detector.extract_text_from_detections(&output, 0),
+            "No text detected"
+        );
+        assert_eq!(
+            detector.extract_text_from_detections(&output, 1),
+            "Detected 2 text regions"
+        );
+    }
+
+    #[test]
+    fn reload_is_refused_when_ml_is_disabled() {
+        let detector = OnnxTextDetector::new("", false, None, &[], 0).unwrap();
+        assert!(detector.load_model(b"not a model", "test").is_err());
+        assert!(detector.model_info().is_none());
+    }
+}
@@ -0,0 +1,218 @@
+//! Caching and daily budgeting for paid remote inference (HuggingFace).
+//!
+//! Results are keyed by the SHA-256 of the image bytes, which is the same
+//! value stored in `letterings.image_hash`. Redis is the hot path; Postgres
+//! keeps a durable copy so a Redis flush doesn't send us back to the paid API.
+
+use crate::infrastructure::monitoring::{Alert, AlertSeverity, PerformanceMonitor};
+use chrono::Utc;
+use redis::{AsyncCommands, Client};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+/// Redis TTL for cached remote results (30 days).
+const REDIS_RESULT_TTL_SECONDS: u64 = 30 * 24 * 3600;
+
+/// Budget counters outlive the day they count so late reads still see them.
+const BUDGET_KEY_TTL_SECONDS: i64 = 2 * 24 * 3600;
+
+/// Fraction of the daily budget at which a warning alert is raised.
+const BUDGET_WARNING_RATIO: f64 = 0.8;
+
+pub struct RemoteInferenceCache {
+    db: PgPool,
+    redis: Client,
+    daily_budget: u32,
+    performance: Option<Arc<PerformanceMonitor>>,
+}
+
+impl RemoteInferenceCache {
+    pub fn new(db: PgPool, redis: Client, daily_budget: u32) -> Self {
+        Self {
+            db,
+            redis,
+            daily_budget,
+            performance: None,
+        }
+    }
+
+    /// Raise budget alerts through the performance monitor.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
+    }
+
+    pub fn image_hash(data: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Look up a cached result. Redis first, then Postgres (re-warming Redis on hit).
+    pub async fn get(&self, provider: &str, image_hash: &str) -> Option<String> {
+        let key = Self::result_key(provider, image_hash);
+
+        if let Ok(mut conn) = self.redis.get_multiplexed_async_connection().await {
+            match conn.get::<_, Option<String>>(&key).await {
+                Ok(Some(text)) => return Some(text),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Remote inference cache read failed: {}", e),
+            }
+        }
+
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT result_text FROM ml_remote_inference_cache WHERE image_hash = $1 AND provider = $2",
+        )
+        .bind(image_hash)
+        .bind(provider)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| tracing::warn!("Remote inference cache DB read failed: {}", e))
+        .ok()
+        .flatten();
+
+        let text = row.map(|(t,)| t)?;
+        self.set_redis(&key, &text).await;
+        Some(text)
+    }
+
+    pub async fn put(&self, provider: &str, image_hash: &str, text: &str) {
+        if let Err(e) = sqlx::query(
+            "INSERT INTO ml_remote_inference_cache (image_hash, provider, result_text) VALUES ($1, $2, $3) ON CONFLICT (image_hash, provider) DO UPDATE SET result_text = EXCLUDED.result_text, created_at = NOW()",
+        )
+        .bind(image_hash)
+        .bind(provider)
+        .bind(text)
+        .execute(&self.db)
+        .await
+        {
+            tracing::warn!("Remote inference cache DB write failed: {}", e);
+        }
+
+        self.set_redis(&Self::result_key(provider, image_hash), text)
+            .await;
+    }
+
+    /// Reserve one remote call from today's budget.
+    ///
+    /// Returns `false` once the budget is exhausted. A budget of 0 disables the
+    /// cap. If Redis is unreachable we allow the call — the cache is an
+    /// optimisation, not a gate on ML processing.
+    pub async fn try_consume_budget(&self, provider: &str) -> bool {
+        if self.daily_budget == 0 {
+            return true;
+        }
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+        let key = format!("ml_remote_budget:{}:{}", provider, date);
+
+        let Ok(mut conn) = self.redis.get_multiplexed_async_connection().await else {
+            return true;
+        };
+        let used: u32 = match conn.incr(&key, 1).await {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!("Remote inference budget counter failed: {}", e);
+                return true;
+            }
+        };
+        if used == 1 {
+            let _: () = conn
+                .expire(&key, BUDGET_KEY_TTL_SECONDS)
+                .await
+                .unwrap_or(());
+        }
+
+        let warning_at = ((self.daily_budget as f64) * BUDGET_WARNING_RATIO).ceil() as u32;
+        if used == warning_at && used < self.daily_budget {
+            self.raise_alert(provider, AlertSeverity::Warning, used)
+                .await;
+        }
+        if used == self.daily_budget + 1 {
+            self.raise_alert(provider, AlertSeverity::Critical, used)
+                .await;
+        }
+
+        used <= self.daily_budget
+    }
+
+    async fn raise_alert(&self, provider: &str, severity: AlertSeverity, used: u32) {
+        let (title, description) = match severity {
+            AlertSeverity::Critical => (
+                "Remote inference budget exhausted",
+                format!(
+                    "{} daily call budget of {} exhausted; falling back to local models until tomorrow (UTC)",
+                    provider, self.daily_budget
+                ),
+            ),
+            _ => (
+                "Remote inference budget nearly exhausted",
+                format!(
+                    "{} has used {} of {} daily calls",
+                    provider, used, self.daily_budget
+                ),
+            ),
+        };
+        let metric = format!("ml_remote_calls_daily.{}", provider);
+        if let Some(performance) = &self.performance {
+            performance
+                .create_alert(
+                    severity,
+                    title,
+                    &description,
+                    &metric,
+                    self.daily_budget as f64,
+                    used as f64,
+                )
+                .await;
+            return;
+        }
+        let alert = Alert::new(
+            severity.clone(),
+            title,
+            &description,
+            &metric,
+            self.daily_budget as f64,
+            used as f64,
+        );
+        let payload = serde_json::to_string(&alert).unwrap_or_default();
+        if severity == AlertSeverity::Critical {
+            tracing::error!(alert = %payload, "{}", alert.title);
+        } else {
+            tracing::warn!(alert = %payload, "{}", alert.title);
+        }
+    }
+
+    async fn set_redis(&self, key: &str, text: &str) {
+        if let Ok(mut conn) = self.redis.get_multiplexed_async_connection().await {
+            let res: redis::RedisResult<()> =
+                conn.set_ex(key, text, REDIS_RESULT_TTL_SECONDS).await;
+            if let Err(e) = res {
+                tracing::warn!("Remote inference cache write failed: {}", e);
+            }
+        }
+    }
+
+    fn result_key(provider: &str, image_hash: &str) -> String {
+        format!("ml_remote:{}:{}", provider, image_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_hash_matches_upload_hash_format() {
+        let hash = RemoteInferenceCache::image_hash(b"abc");
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            RemoteInferenceCache::result_key("huggingface", &hash),
+            format!("ml_remote:huggingface:{}", hash)
+        );
+    }
+}
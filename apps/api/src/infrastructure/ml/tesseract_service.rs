@@ -1,106 +1,94 @@
-use async_trait::async_trait;
-use image::ImageFormat;
-use std::io::Cursor;
-use super::traits::{MlService, TextDetectionResult, StyleClassification};
+//! Tesseract OCR, used when the ONNX detector is unavailable or unsure.
+//!
+//! Only compiled in with the `tesseract` Cargo feature, which needs the
+//! Tesseract and Leptonica libraries plus the traineddata for the configured
+//! languages on the host. Without the feature the service stays inactive and
+//! callers keep whatever text they already have.
+
+use super::traits::TextDetectionResult;
+use image::DynamicImage;
+
+/// Label stored in `detected_text_source` for text from this service.
+pub const SOURCE: &str = "tesseract";
 
 pub struct TesseractService {
-    enabled: bool,
+    /// `+`-joined traineddata names, or `None` when inactive.
+    languages: Option<String>,
 }
 
 impl TesseractService {
-    pub fn new(enabled: bool) -> Self {
-        Self { enabled }
-    }
-    
-    fn extract_dominant_colors_from_image(image_data: &[u8]) -> anyhow::Result<Vec<String>> {
-        let img = image::load_from_memory(image_data)?;
-        let img = img.to_rgb8();
-        
-        // Simple color extraction - take sample pixels
-        let mut colors = Vec::new();
-        let (width, height) = img.dimensions();
-        
-        for y in (0..height).step_by(height as usize / 10) {
-            for x in (0..width).step_by(width as usize / 10) {
-                let pixel = img.get_pixel(x, y);
-                let hex = format!("#{:02X}{:02X}{:02X}", pixel[0], pixel[1], pixel[2]);
-                if !colors.contains(&hex) && colors.len() < 5 {
-                    colors.push(hex);
-                }
-            }
+    pub fn new(enabled: bool, languages: &str) -> Self {
+        if enabled && !cfg!(feature = "tesseract") {
+            tracing::warn!(
+                "Tesseract fallback enabled but the binary was built without the `tesseract` feature"
+            );
+        }
+        let active = enabled && cfg!(feature = "tesseract") && !languages.trim().is_empty();
+        Self {
+            languages: active.then(|| languages.trim().to_string()),
         }
-        
-        Ok(colors)
     }
-}
 
-#[async_trait]
-impl MlService for TesseractService {
-    async fn detect_text(&self, image_data: &[u8]) -> anyhow::Result<TextDetectionResult> {
-        if !self.enabled {
-            return Ok(TextDetectionResult {
-                detected_text: String::new(),
-                confidence: 0.0,
-                language: None,
-            });
-        }
-        
-        // Production OCR using tesseract-rs
-        use tesseract::Tesseract;
-        
-        let tess = Tesseract::new(None, Some("eng+kan+hin+tam+tel"))
-            .map_err(|e| anyhow::anyhow!("Tesseract init failed: {}", e))?;
-        
-        let text = tess
-            .set_image_from_mem(image_data)
-            .map_err(|e| anyhow::anyhow!("Image load failed: {}", e))?
-            .get_text()
-            .map_err(|e| anyhow::anyhow!("OCR failed: {}", e))?;
-        
-        Ok(TextDetectionResult {
-            detected_text: text.trim().to_string(),
-            confidence: 0.85,
-            language: Some("multi".to_string()),
-        })
+    pub fn is_active(&self) -> bool {
+        self.languages.is_some()
     }
-    
-    async fn classify_style(&self, image_data: &[u8]) -> anyhow::Result<StyleClassification> {
-        if !self.enabled {
-            return Ok(StyleClassification {
-                style: "unknown".to_string(),
-                confidence: 0.0,
-            });
-        }
-        
-        // Production style classification using simple heuristics
-        let img = image::load_from_memory(image_data)?;
-        let gray = img.to_luma8();
-        
-        // Analyze edge density for style detection
-        let edges = imageproc::edges::canny(&gray, 50.0, 100.0);
-        let edge_count = edges.pixels().filter(|p| p[0] > 0).count();
-        let total_pixels = (edges.width() * edges.height()) as usize;
-        let edge_ratio = edge_count as f32 / total_pixels as f32;
-        
-        let style = if edge_ratio > 0.3 {
-            "decorative"
-        } else if edge_ratio > 0.15 {
-            "handwritten"
-        } else {
-            "printed"
+
+    /// Text in `image`, or `None` when the service is inactive or found
+    /// nothing. Runs on the blocking pool.
+    pub async fn detect_text(
+        &self,
+        image: DynamicImage,
+    ) -> anyhow::Result<Option<TextDetectionResult>> {
+        let Some(languages) = self.languages.clone() else {
+            return Ok(None);
         };
-        
-        Ok(StyleClassification {
-            style: style.to_string(),
-            confidence: 0.75,
-        })
+        let result = tokio::task::spawn_blocking(move || recognize(&image, &languages)).await??;
+        Ok(Some(result).filter(|r| !r.detected_text.is_empty()))
     }
-    
-    async fn extract_colors(&self, image_data: &[u8]) -> anyhow::Result<Vec<String>> {
-        if !self.enabled {
-            return Ok(vec![]);
-        }
-        
-        Self::extract_dominant_colors_from_image(image_data)
+}
+
+#[cfg(feature = "tesseract")]
+fn recognize(image: &DynamicImage, languages: &str) -> anyhow::Result<TextDetectionResult> {
+    use image::ImageFormat;
+    use std::io::Cursor;
+    use tesseract::Tesseract;
+
+    // Leptonica's format support depends on how it was built; grayscale PNG
+    // is always readable and is what Tesseract binarises from anyway.
+    let mut png = Vec::new();
+    DynamicImage::ImageLuma8(image.to_luma8())
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)?;
+
+    let mut tess = Tesseract::new(None, Some(languages))
+        .map_err(|e| anyhow::anyhow!("Tesseract init failed: {}", e))?
+        .set_image_from_mem(&png)
+        .map_err(|e| anyhow::anyhow!("Image load failed: {}", e))?;
+    let text = tess
+        .get_text()
+        .map_err(|e| anyhow::anyhow!("OCR failed: {}", e))?;
+    let confidence = (tess.mean_text_conf().clamp(0, 100) as f32) / 100.0;
+
+    Ok(TextDetectionResult {
+        detected_text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        confidence,
+        language: Some(languages.to_string()),
+    })
+}
+
+#[cfg(not(feature = "tesseract"))]
+fn recognize(_image: &DynamicImage, _languages: &str) -> anyhow::Result<TextDetectionResult> {
+    anyhow::bail!("built without the `tesseract` feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn inactive_when_disabled() {
+        let service = TesseractService::new(false, "eng");
+        assert!(!service.is_active());
+        let image = DynamicImage::new_luma8(8, 8);
+        assert!(service.detect_text(image).await.unwrap().is_none());
     }
-}
\ No newline at end of file
+}
@@ -18,6 +18,19 @@ pub trait MlService: Send + Sync {
     /// Detect text in image using OCR
     async fn detect_text(&self, image_data: &[u8]) -> anyhow::Result<TextDetectionResult>;
 
+    /// Detect text in several images at once. Results are returned in input
+    /// order; implementations that cannot batch fall back to one call per image.
+    async fn detect_text_batch(
+        &self,
+        images: &[&[u8]],
+    ) -> Vec<anyhow::Result<TextDetectionResult>> {
+        let mut results = Vec::with_capacity(images.len());
+        for image in images {
+            results.push(self.detect_text(image).await);
+        }
+        results
+    }
+
     /// Classify lettering style
     async fn classify_style(&self, image_data: &[u8]) -> anyhow::Result<StyleClassification>;
 
@@ -1,9 +1,16 @@
 pub mod cache;
+pub mod cdn;
 pub mod database;
+pub mod email;
+pub mod fault_injection;
+pub mod feature_flags;
 pub mod geocoding;
+pub mod imaging;
+pub mod leadership;
 pub mod ml;
 pub mod monitoring;
 pub mod queue;
 pub mod repositories;
 pub mod security;
+pub mod shutdown;
 pub mod storage;
@@ -0,0 +1,316 @@
+//! Liveness of background workers, as seen from Redis.
+//!
+//! Workers beat into `worker_heartbeat:{worker}:{instance}`, a hash holding
+//! when they last beat, when the next beat is due, what they are working on
+//! and how many jobs they processed and failed. The hash outlives its last
+//! beat by an hour, so a worker that died shows as stale before it drops
+//! off. `GET /api/v1/admin/workers` reads them all back.
+
+use crate::infrastructure::leadership::instance_id;
+use chrono::{DateTime, TimeDelta, Utc};
+use redis::AsyncCommands;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Set of every heartbeat hash written, so readers need not scan.
+pub const HEARTBEATS_KEY: &str = "worker_heartbeats";
+
+/// How long a heartbeat stays listed after it was due.
+const RETENTION_SECONDS: i64 = 3600;
+
+/// A beat this many seconds late is reported stale rather than merely
+/// slow.
+const STALE_AFTER_SECONDS: i64 = 120;
+
+/// Idle beats closer together than this are skipped; the worker is still
+/// well within its due time.
+const MIN_IDLE_BEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+pub fn heartbeat_key(worker: &str, instance: &str) -> String {
+    format!("worker_heartbeat:{}:{}", worker, instance)
+}
+
+/// Cheap-to-clone handle workers beat through. Failures to write are logged
+/// and otherwise ignored; a heartbeat never holds up the work.
+#[derive(Clone)]
+pub struct Heartbeats {
+    /// `None` when heartbeats are not recorded.
+    redis: Option<redis::Client>,
+    instance: String,
+    /// When each worker last beat idle from this process.
+    idle_since: Arc<Mutex<HashMap<&'static str, Instant>>>,
+}
+
+impl Heartbeats {
+    pub fn new(redis: redis::Client) -> Self {
+        Self {
+            redis: Some(redis),
+            instance: instance_id().to_string(),
+            idle_since: Arc::default(),
+        }
+    }
+
+    /// A handle that records nothing.
+    pub fn disabled() -> Self {
+        Self {
+            redis: None,
+            instance: instance_id().to_string(),
+            idle_since: Arc::default(),
+        }
+    }
+
+    /// Name this instance beats under.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = instance.into();
+        self
+    }
+
+    /// The worker is alive and idle, and will beat again within
+    /// `next_within`.
+    pub async fn beat(&self, worker: &'static str, next_within: Duration) {
+        {
+            let mut idle_since = self.idle_since.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            match idle_since.get(worker) {
+                Some(at) if now.duration_since(*at) < MIN_IDLE_BEAT_INTERVAL => return,
+                _ => idle_since.insert(worker, now),
+            };
+        }
+        self.write(worker, next_within, None).await;
+    }
+
+    /// The worker took on `job` and will beat again within `next_within`.
+    pub async fn working_on(&self, worker: &'static str, job: &str, next_within: Duration) {
+        self.idle_since
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(worker);
+        self.write(worker, next_within, Some(job)).await;
+    }
+
+    /// Adds to the worker's processed and failed job counts.
+    pub async fn count(&self, worker: &'static str, processed: u64, failed: u64) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let key = heartbeat_key(worker, &self.instance);
+        let result = async {
+            let mut conn = redis.get_multiplexed_async_connection().await?;
+            redis::pipe()
+                .hincr(&key, "processed", processed)
+                .ignore()
+                .hincr(&key, "failed", failed)
+                .ignore()
+                .expire(&key, RETENTION_SECONDS)
+                .ignore()
+                .query_async::<()>(&mut conn)
+                .await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(worker, "Failed to record worker counts: {}", e);
+        }
+    }
+
+    /// Drops this instance's heartbeats once its workers have stopped, so
+    /// they are not reported stale as if they had died.
+    pub async fn clear(&self) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let suffix = format!(":{}", self.instance);
+        let result = async {
+            let mut conn = redis.get_multiplexed_async_connection().await?;
+            let keys: Vec<String> = conn.smembers(HEARTBEATS_KEY).await?;
+            let ours: Vec<String> = keys.into_iter().filter(|k| k.ends_with(&suffix)).collect();
+            if ours.is_empty() {
+                return Ok(());
+            }
+            redis::pipe()
+                .del(&ours)
+                .ignore()
+                .srem(HEARTBEATS_KEY, &ours)
+                .ignore()
+                .query_async::<()>(&mut conn)
+                .await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to clear worker heartbeats: {}", e);
+        }
+    }
+
+    async fn write(&self, worker: &'static str, next_within: Duration, job: Option<&str>) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let key = heartbeat_key(worker, &self.instance);
+        let now = Utc::now();
+        let next_beat_by = now + TimeDelta::from_std(next_within).unwrap_or(TimeDelta::days(365));
+        let result = async {
+            let mut conn = redis.get_multiplexed_async_connection().await?;
+            let mut pipe = redis::pipe();
+            pipe.hset_multiple(
+                &key,
+                &[
+                    ("worker", worker.to_string()),
+                    ("instance", self.instance.clone()),
+                    ("last_beat_at", now.to_rfc3339()),
+                    ("next_beat_by", next_beat_by.to_rfc3339()),
+                ],
+            )
+            .ignore();
+            match job {
+                Some(job) => pipe
+                    .hset_multiple(
+                        &key,
+                        &[
+                            ("current_job", job.to_string()),
+                            ("current_job_started_at", now.to_rfc3339()),
+                        ],
+                    )
+                    .ignore(),
+                None => pipe
+                    .hdel(&key, &["current_job", "current_job_started_at"])
+                    .ignore(),
+            };
+            pipe.expire(&key, next_within.as_secs() as i64 + RETENTION_SECONDS)
+                .ignore()
+                .sadd(HEARTBEATS_KEY, &key)
+                .ignore()
+                .query_async::<()>(&mut conn)
+                .await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::warn!(worker, "Failed to write worker heartbeat: {}", e);
+        }
+    }
+}
+
+/// One worker's heartbeat on one instance.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerHeartbeat {
+    pub worker: String,
+    pub instance: String,
+    pub last_beat_at: DateTime<Utc>,
+    pub next_beat_by: DateTime<Utc>,
+    /// No beat within two minutes of when one was due.
+    pub stale: bool,
+    pub processed: u64,
+    pub failed: u64,
+    pub current_job: Option<String>,
+    pub current_job_started_at: Option<DateTime<Utc>>,
+}
+
+impl WorkerHeartbeat {
+    /// `None` for a hash that expired or was never fully written.
+    fn from_fields(fields: &HashMap<String, String>, now: DateTime<Utc>) -> Option<Self> {
+        let time = |field: &str| {
+            fields
+                .get(field)
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|t| t.with_timezone(&Utc))
+        };
+        let count = |field: &str| fields.get(field).and_then(|v| v.parse().ok()).unwrap_or(0);
+        let next_beat_by = time("next_beat_by")?;
+        Some(Self {
+            worker: fields.get("worker")?.clone(),
+            instance: fields.get("instance")?.clone(),
+            last_beat_at: time("last_beat_at")?,
+            next_beat_by,
+            stale: now > next_beat_by + TimeDelta::seconds(STALE_AFTER_SECONDS),
+            processed: count("processed"),
+            failed: count("failed"),
+            current_job: fields.get("current_job").cloned(),
+            current_job_started_at: time("current_job_started_at"),
+        })
+    }
+}
+
+/// Every listed heartbeat, by worker then instance. Hashes that have
+/// expired are dropped from the list.
+pub async fn read_heartbeats(redis: &redis::Client) -> redis::RedisResult<Vec<WorkerHeartbeat>> {
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let keys: Vec<String> = conn.smembers(HEARTBEATS_KEY).await?;
+    let now = Utc::now();
+    let mut heartbeats = Vec::with_capacity(keys.len());
+    let mut expired = Vec::new();
+    for key in keys {
+        let fields: HashMap<String, String> = conn.hgetall(&key).await?;
+        match WorkerHeartbeat::from_fields(&fields, now) {
+            Some(heartbeat) => heartbeats.push(heartbeat),
+            None => expired.push(key),
+        }
+    }
+    if !expired.is_empty() {
+        let _: () = conn.srem(HEARTBEATS_KEY, &expired).await?;
+    }
+    heartbeats.sort_by(|a, b| (&a.worker, &a.instance).cmp(&(&b.worker, &b.instance)));
+    Ok(heartbeats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, String)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn heartbeats_go_stale_two_minutes_after_they_were_due() {
+        let now = Utc::now();
+        let beat = |due: DateTime<Utc>| {
+            fields(&[
+                ("worker", "ml_processor".to_string()),
+                ("instance", "host:1".to_string()),
+                ("last_beat_at", (due - TimeDelta::seconds(30)).to_rfc3339()),
+                ("next_beat_by", due.to_rfc3339()),
+                ("processed", "12".to_string()),
+            ])
+        };
+
+        let late = WorkerHeartbeat::from_fields(&beat(now - TimeDelta::seconds(90)), now).unwrap();
+        assert!(!late.stale);
+        assert_eq!(late.processed, 12);
+        assert_eq!(late.failed, 0);
+        assert_eq!(late.current_job, None);
+
+        let dead = WorkerHeartbeat::from_fields(&beat(now - TimeDelta::minutes(3)), now).unwrap();
+        assert!(dead.stale);
+    }
+
+    #[test]
+    fn partial_hashes_are_not_heartbeats() {
+        let counts_only = fields(&[("processed", "3".to_string())]);
+        assert!(WorkerHeartbeat::from_fields(&counts_only, Utc::now()).is_none());
+    }
+
+    #[tokio::test]
+    async fn idle_beats_are_spaced_out() {
+        let heartbeats = Heartbeats::disabled();
+        heartbeats.beat("test_worker", Duration::from_secs(1)).await;
+        let first = heartbeats.idle_since.lock().unwrap()["test_worker"];
+        heartbeats.beat("test_worker", Duration::from_secs(1)).await;
+        assert_eq!(heartbeats.idle_since.lock().unwrap()["test_worker"], first);
+
+        heartbeats
+            .working_on("test_worker", "job", Duration::from_secs(1))
+            .await;
+        assert!(
+            !heartbeats
+                .idle_since
+                .lock()
+                .unwrap()
+                .contains_key("test_worker")
+        );
+    }
+}
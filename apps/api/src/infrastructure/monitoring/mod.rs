@@ -10,8 +10,10 @@
 //! The monitoring system is designed to be lightweight, thread-safe, and
 //! suitable for high-throughput production environments.
 
+pub mod heartbeat;
 pub mod metrics;
 pub mod performance;
+pub mod throttle;
 
 pub use performance::{
     PerformanceMonitor, PerformanceMonitor as MetricsService,
@@ -14,7 +14,6 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn, instrument};
-use uuid::Uuid;
 
 /// Internal monitoring state with categorized collectors
 #[derive(Default)]
@@ -210,21 +209,13 @@ impl PerformanceMonitor {
                         (business.duplicate_detection_rate * business.total_uploads as f64 + 1.0) / (business.total_uploads + 1) as f64;
                 }
             }
-            BusinessEvent::CacheHit { cache_type: _ } => {
-                let total_cache_ops = business.cache_hit_rate + business.cache_miss_rate;
-                if total_cache_ops > 0.0 {
-                    business.cache_hit_rate = (business.cache_hit_rate * total_cache_ops + 1.0) / (total_cache_ops + 1.0);
-                } else {
-                    business.cache_hit_rate = 1.0;
-                }
+            BusinessEvent::CacheHit { cache_type } => {
+                business.cache_lookups.entry(cache_type).or_default().0 += 1;
+                Self::update_cache_rates(business);
             }
-            BusinessEvent::CacheMiss { cache_type: _ } => {
-                let total_cache_ops = business.cache_hit_rate + business.cache_miss_rate;
-                if total_cache_ops > 0.0 {
-                    business.cache_miss_rate = (business.cache_miss_rate * total_cache_ops + 1.0) / (total_cache_ops + 1.0);
-                } else {
-                    business.cache_miss_rate = 1.0;
-                }
+            BusinessEvent::CacheMiss { cache_type } => {
+                business.cache_lookups.entry(cache_type).or_default().1 += 1;
+                Self::update_cache_rates(business);
             }
             BusinessEvent::MlProcessingCompleted { success, processing_time_ms: _ } => {
                 let current_rate = business.ml_processing_success_rate;
@@ -243,6 +234,7 @@ impl PerformanceMonitor {
 
     /// Updates system resource utilization metrics
     #[instrument(skip(self))]
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_resource_metrics(
         &self,
         memory_mb: f64,
@@ -289,7 +281,7 @@ impl PerformanceMonitor {
     }
 
     /// Creates an alert for monitoring systems
-    async fn create_alert(
+    pub async fn create_alert(
         &self,
         severity: AlertSeverity,
         title: &str,
@@ -298,17 +290,7 @@ impl PerformanceMonitor {
         threshold: f64,
         current_value: f64,
     ) {
-        let alert = Alert {
-            id: Uuid::now_v7().to_string(),
-            severity,
-            title: title.to_string(),
-            description: description.to_string(),
-            metric: metric.to_string(),
-            threshold,
-            current_value,
-            created_at: chrono::Utc::now(),
-            resolved_at: None,
-        };
+        let alert = Alert::new(severity, title, description, metric, threshold, current_value);
 
         warn!("Alert created: {:?}", alert);
     }
@@ -422,6 +404,17 @@ impl PerformanceMonitor {
         }
     }
 
+    /// Overall hit and miss rates across every cache type
+    fn update_cache_rates(business: &mut BusinessMetrics) {
+        let (hits, misses) = business
+            .cache_lookups
+            .values()
+            .fold((0, 0), |(hits, misses), (h, m)| (hits + h, misses + m));
+        let total = (hits + misses).max(1) as f64;
+        business.cache_hit_rate = hits as f64 / total;
+        business.cache_miss_rate = misses as f64 / total;
+    }
+
     fn calculate_business_summary(&self, business: &BusinessMetrics) -> BusinessSummary {
         let engagement_rate = if business.total_uploads > 0 {
             (business.total_likes + business.total_comments) as f64 / business.total_uploads as f64
@@ -437,6 +430,13 @@ impl PerformanceMonitor {
             approval_rate: business.upload_approval_rate,
             engagement_rate,
             cache_hit_rate: business.cache_hit_rate,
+            cache_hit_rate_by_type: business
+                .cache_lookups
+                .iter()
+                .map(|(cache_type, (hits, misses))| {
+                    (cache_type.clone(), *hits as f64 / (hits + misses).max(1) as f64)
+                })
+                .collect(),
             ml_processing_success_rate: business.ml_processing_success_rate,
             moderation_backlog: business.pending_moderation_queue_size,
             content_quality_score,
@@ -670,11 +670,198 @@ impl PerformanceMonitor {
         }
     }
 
+    /// Records ONNX inference latency per image, keyed by execution provider
+    /// so GPU and CPU deployments can be compared.
+    pub async fn record_ml_inference(&self, provider: &str, images: usize, duration: Duration) {
+        let name = format!("ml_inference_latency_ms.{}", provider);
+        let per_image_ms = duration.as_secs_f64() * 1000.0 / images.max(1) as f64;
+
+        let mut inner = self.inner.write().await;
+        inner
+            .custom_metrics
+            .entry(name.clone())
+            .or_insert_with(|| {
+                CustomMetric::new(
+                    name,
+                    format!("ONNX inference latency per image on the {} provider", provider),
+                    MetricType::Histogram,
+                    HashMap::from([("provider".to_string(), provider.to_string())]),
+                    None,
+                    None,
+                )
+            })
+            .record(per_image_ms);
+    }
+
+    /// Count `count` occurrences of `event` for a background worker, e.g.
+    /// `worker.pending_auto_approve.skipped.reported`.
+    pub async fn record_worker_count(&self, worker: &str, event: &str, count: usize) {
+        let name = format!("worker.{}.{}", worker, event);
+
+        let mut inner = self.inner.write().await;
+        inner
+            .custom_metrics
+            .entry(name.clone())
+            .or_insert_with(|| {
+                CustomMetric::new(
+                    name,
+                    format!("{} events from the {} worker", event, worker),
+                    MetricType::Counter,
+                    HashMap::from([
+                        ("worker".to_string(), worker.to_string()),
+                        ("event".to_string(), event.to_string()),
+                    ]),
+                    None,
+                    None,
+                )
+            })
+            .record(count as f64);
+    }
+
+    /// Count transitions of a circuit breaker into `state`, e.g.
+    /// `circuit_breaker.huggingface.open`.
+    pub async fn record_circuit_breaker_transition(&self, breaker: &str, state: &str) {
+        let name = format!("circuit_breaker.{}.{}", breaker, state);
+
+        let mut inner = self.inner.write().await;
+        inner
+            .custom_metrics
+            .entry(name.clone())
+            .or_insert_with(|| {
+                CustomMetric::new(
+                    name,
+                    format!("Transitions of the {} circuit breaker to {}", breaker, state),
+                    MetricType::Counter,
+                    HashMap::from([
+                        ("breaker".to_string(), breaker.to_string()),
+                        ("state".to_string(), state.to_string()),
+                    ]),
+                    None,
+                    None,
+                )
+            })
+            .record(1.0);
+    }
+
+    /// Records per-image latency of one ML model, e.g. `ml.latency_ms.onnx:1a2b3c4d5e6f`
+    /// or `ml.latency_ms.huggingface`.
+    pub async fn record_ml_model_latency(&self, model: &str, images: usize, duration: Duration) {
+        let per_image_ms = duration.as_secs_f64() * 1000.0 / images.max(1) as f64;
+        self.record_labelled_metric(
+            format!("ml.latency_ms.{}", model),
+            format!("Per-image latency of the {} model", model),
+            MetricType::Histogram,
+            &[("model", model)],
+            per_image_ms,
+        ).await;
+    }
+
+    /// Records how long an ML job waited on `queue` (`high`, `normal` or
+    /// `bulk`)
+    /// before the worker picked it up.
+    pub async fn record_ml_queue_wait(&self, queue: &str, wait: Duration) {
+        self.record_labelled_metric(
+            format!("ml.queue_wait_ms.{}", queue),
+            format!("Time ML jobs spent on the {} queue", queue),
+            MetricType::Histogram,
+            &[("queue", queue)],
+            wait.as_secs_f64() * 1000.0,
+        ).await;
+    }
+
+    /// Records the number of jobs taken for one batched inference pass.
+    pub async fn record_ml_batch_size(&self, size: usize) {
+        self.record_labelled_metric(
+            "ml.batch_size".to_string(),
+            "Jobs per ML processing batch".to_string(),
+            MetricType::Histogram,
+            &[],
+            size as f64,
+        ).await;
+    }
+
+    /// Records the upload backlog on the ML queue, raising a warning while
+    /// it is past `max_depth`.
+    pub async fn record_ml_queue_depth(&self, depth: usize, max_depth: usize) {
+        self.record_labelled_metric(
+            "ml.queue_depth".to_string(),
+            "Upload jobs waiting on the ML queue".to_string(),
+            MetricType::Gauge,
+            &[],
+            depth as f64,
+        ).await;
+
+        if max_depth > 0 && depth > max_depth {
+            self.create_alert(
+                AlertSeverity::Warning,
+                "ML Queue Backlog",
+                &format!("{} uploads waiting for ML processing", depth),
+                "ml_queue_depth",
+                max_depth as f64,
+                depth as f64,
+            ).await;
+        }
+    }
+
+    /// Counts an ML failure by cause, e.g. `ml.failures.image_fetch`.
+    pub async fn record_ml_failure(&self, cause: &str) {
+        self.record_labelled_metric(
+            format!("ml.failures.{}", cause),
+            format!("ML failures caused by {}", cause),
+            MetricType::Counter,
+            &[("cause", cause)],
+            1.0,
+        ).await;
+    }
+
+    /// Records `value` on the custom metric `name`, creating it on first use.
+    async fn record_labelled_metric(
+        &self,
+        name: String,
+        description: String,
+        metric_type: MetricType,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        let mut inner = self.inner.write().await;
+        inner
+            .custom_metrics
+            .entry(name.clone())
+            .or_insert_with(|| {
+                CustomMetric::new(
+                    name,
+                    description,
+                    metric_type,
+                    labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+                    None,
+                    None,
+                )
+            })
+            .record(value);
+    }
+
     pub async fn update_disk_io_metrics(&self, reads_per_sec: f64, writes_per_sec: f64) {
         let mut inner = self.inner.write().await;
         inner.resource_metrics.update_disk_io(reads_per_sec, writes_per_sec);
     }
 
+    pub async fn update_db_pool_metrics(&self, active: u32, idle: u32, max: u32) {
+        let mut inner = self.inner.write().await;
+        inner.resource_metrics.db_pool_active_connections = active;
+        inner.resource_metrics.db_pool_idle_connections = idle;
+        inner.resource_metrics.db_pool_max_connections = max;
+    }
+
+    /// Database and host resource health, the inputs background workers
+    /// throttle on.
+    pub async fn background_health(&self) -> (HealthStatus, HealthStatus) {
+        let inner = self.inner.read().await;
+        (
+            self.assess_database_health(&inner.db_metrics),
+            self.assess_resource_health(&inner.resource_metrics),
+        )
+    }
+
     pub async fn get_error_breakdown(&self) -> HashMap<String, Vec<(u16, u64)>> {
         let inner = self.inner.read().await;
         inner.error_metrics.iter()
@@ -762,17 +949,18 @@ impl PerformanceMonitor {
                         value,
                     ).await;
                 }
-            } else if let Some(warning) = metric.warning_threshold() {
-                if value > warning {
-                    self.create_alert(
-                        AlertSeverity::Warning,
-                        &format!("Warning threshold exceeded for {}", name),
-                        &format!("Value {} exceeds warning threshold {}", value, warning),
-                        name,
-                        warning,
-                        value,
-                    ).await;
-                }
+            } else if let Some(warning) = metric.warning_threshold()
+                && value > warning
+            {
+                self.create_alert(
+                    AlertSeverity::Warning,
+                    &format!("Warning threshold exceeded for {}", name),
+                    &format!("Value {} exceeds warning threshold {}", value, warning),
+                    name,
+                    warning,
+                    value,
+                )
+                .await;
             }
         }
     }
@@ -784,6 +972,9 @@ impl PerformanceMonitor {
                 name: metric.name().to_string(),
                 description: metric.description().to_string(),
                 current_value: metric.current_value(),
+                p50: metric.percentile(50.0),
+                p95: metric.percentile(95.0),
+                p99: metric.percentile(99.0),
                 labels: metric.labels().clone(),
                 warning_threshold: metric.warning_threshold(),
                 critical_threshold: metric.critical_threshold(),
@@ -844,6 +1035,22 @@ mod tests {
         assert!(snapshot.business_summary.approval_rate > 0.0);
     }
 
+    #[tokio::test]
+    async fn test_cache_hit_rates_by_type() {
+        let monitor = PerformanceMonitor::new();
+
+        for _ in 0..3 {
+            monitor.record_business_event(BusinessEvent::CacheHit { cache_type: "local".to_string() }).await;
+        }
+        monitor.record_business_event(BusinessEvent::CacheMiss { cache_type: "local".to_string() }).await;
+        monitor.record_business_event(BusinessEvent::CacheMiss { cache_type: "redis".to_string() }).await;
+
+        let summary = monitor.generate_snapshot().await.business_summary;
+        assert_eq!(summary.cache_hit_rate, 0.6);
+        assert_eq!(summary.cache_hit_rate_by_type["local"], 0.75);
+        assert_eq!(summary.cache_hit_rate_by_type["redis"], 0.0);
+    }
+
     #[tokio::test]
     async fn test_custom_metric_registration() {
         let monitor = PerformanceMonitor::new();
@@ -871,6 +1078,52 @@ mod tests {
         assert_eq!(metric.data_points[1].1, 75.0);
     }
 
+    #[tokio::test]
+    async fn test_ml_inference_recorded_per_provider() {
+        let monitor = PerformanceMonitor::new();
+
+        monitor.record_ml_inference("cuda", 4, Duration::from_millis(40)).await;
+        monitor.record_ml_inference("cpu", 1, Duration::from_millis(120)).await;
+
+        let inner = monitor.inner.read().await;
+        let cuda = &inner.custom_metrics["ml_inference_latency_ms.cuda"];
+        assert_eq!(cuda.labels()["provider"], "cuda");
+        assert_eq!(cuda.current_value(), 10.0);
+        assert_eq!(inner.custom_metrics["ml_inference_latency_ms.cpu"].current_value(), 120.0);
+    }
+
+    #[tokio::test]
+    async fn test_ml_queue_depth_recorded_as_gauge() {
+        let monitor = PerformanceMonitor::new();
+
+        monitor.record_ml_queue_depth(120, 100).await;
+        monitor.record_ml_queue_depth(40, 100).await;
+
+        let inner = monitor.inner.read().await;
+        let depth = &inner.custom_metrics["ml.queue_depth"];
+        assert_eq!(depth.current_value(), 40.0);
+        assert_eq!(depth.data_points.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_ml_metrics_summarised_with_percentiles() {
+        let monitor = PerformanceMonitor::new();
+
+        for ms in 1..=10 {
+            monitor.record_ml_model_latency("huggingface", 1, Duration::from_millis(ms * 10)).await;
+        }
+        monitor.record_ml_failure("image_fetch").await;
+        monitor.record_ml_failure("image_fetch").await;
+
+        let summary = monitor.get_custom_metrics_summary().await;
+        let latency = summary.iter().find(|m| m.name == "ml.latency_ms.huggingface").unwrap();
+        assert_eq!(latency.p50, Some(50.0));
+        assert_eq!(latency.p95, Some(100.0));
+        let failures = summary.iter().find(|m| m.name == "ml.failures.image_fetch").unwrap();
+        assert_eq!(failures.current_value, 2.0);
+        assert_eq!(failures.p50, None);
+    }
+
     #[tokio::test]
     async fn test_health_assessment() {
         let monitor = PerformanceMonitor::new();
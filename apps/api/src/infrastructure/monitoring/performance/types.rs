@@ -76,6 +76,8 @@ pub struct BusinessMetrics {
     /// Cache performance
     pub cache_hit_rate: f64,
     pub cache_miss_rate: f64,
+    /// Cache hits and misses by cache type
+    pub cache_lookups: HashMap<String, (u64, u64)>,
 }
 
 /// System resource utilization tracking
@@ -262,6 +264,17 @@ impl CustomMetric {
         }
     }
 
+    /// Percentile (0-100) of the recorded values; histograms only
+    pub fn percentile(&self, percentile: f64) -> Option<f64> {
+        if self.metric_type != MetricType::Histogram || self.data_points.is_empty() {
+            return None;
+        }
+        let mut values: Vec<f64> = self.data_points.iter().map(|(_, v)| *v).collect();
+        values.sort_by(f64::total_cmp);
+        let rank = (percentile / 100.0 * values.len() as f64).ceil() as usize;
+        Some(values[rank.saturating_sub(1).min(values.len() - 1)])
+    }
+
     pub fn name(&self) -> &str { &self.name }
     pub fn description(&self) -> &str { &self.description }
     pub fn labels(&self) -> &HashMap<String, String> { &self.labels }
@@ -292,6 +305,13 @@ pub struct CustomMetricSummary {
     pub name: String,
     pub description: String,
     pub current_value: f64,
+    /// Percentiles over the retained window, for histograms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p50: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p95: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p99: Option<f64>,
     pub labels: HashMap<String, String>,
     pub warning_threshold: Option<f64>,
     pub critical_threshold: Option<f64>,
@@ -347,6 +367,7 @@ pub struct BusinessSummary {
     pub approval_rate: f64,
     pub engagement_rate: f64,
     pub cache_hit_rate: f64,
+    pub cache_hit_rate_by_type: HashMap<String, f64>,
     pub ml_processing_success_rate: f64,
     pub moderation_backlog: u64,
     pub content_quality_score: f64,
@@ -410,6 +431,29 @@ pub struct Alert {
     pub resolved_at: Option<DateTime<Utc>>,
 }
 
+impl Alert {
+    pub fn new(
+        severity: AlertSeverity,
+        title: &str,
+        description: &str,
+        metric: &str,
+        threshold: f64,
+        current_value: f64,
+    ) -> Self {
+        Self {
+            id: Uuid::now_v7().to_string(),
+            severity,
+            title: title.to_string(),
+            description: description.to_string(),
+            metric: metric.to_string(),
+            threshold,
+            current_value,
+            created_at: Utc::now(),
+            resolved_at: None,
+        }
+    }
+}
+
 /// Alert severity levels
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub enum AlertSeverity {
@@ -0,0 +1,216 @@
+//! Back-pressure for background workers.
+//!
+//! A refresher task probes the database every few seconds, feeds the result
+//! into the `PerformanceMonitor`, and publishes a `ThrottleLevel` derived from
+//! its database and resource health. Workers consult the shared
+//! `WorkerThrottle` between batches: full speed while both are healthy,
+//! slowed while either is degraded, and paused while either is unhealthy or
+//! critical. Pacing doubles as the workers' heartbeat, and ends early once
+//! workers are shutting down.
+
+use super::{HealthStatus, PerformanceMonitor, heartbeat::Heartbeats};
+use crate::infrastructure::shutdown::Shutdown;
+use sqlx::PgPool;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Multiplier on a worker's normal sleep while slowed.
+const SLOW_FACTOR: u32 = 4;
+
+/// Pause between batches within one pass while slowed.
+const SLOWED_BATCH_DELAY: Duration = Duration::from_secs(2);
+
+/// Ordered from least to most restrictive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThrottleLevel {
+    Normal,
+    Slowed,
+    Paused,
+}
+
+impl ThrottleLevel {
+    /// The stricter of the levels implied by database and resource health.
+    pub fn from_health(database: &HealthStatus, resources: &HealthStatus) -> Self {
+        let level = |status: &HealthStatus| match status {
+            HealthStatus::Healthy => Self::Normal,
+            HealthStatus::Degraded => Self::Slowed,
+            HealthStatus::Unhealthy | HealthStatus::Critical => Self::Paused,
+        };
+        level(database).max(level(resources))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Slowed => "slowed",
+            Self::Paused => "paused",
+        }
+    }
+}
+
+/// Cheap-to-clone handle on the current throttle level.
+#[derive(Clone)]
+pub struct WorkerThrottle {
+    level: watch::Receiver<ThrottleLevel>,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl WorkerThrottle {
+    /// Start the refresher task and return a handle to its signal.
+    pub fn spawn(performance: Arc<PerformanceMonitor>, db: PgPool) -> Self {
+        let (tx, rx) = watch::channel(ThrottleLevel::Normal);
+        tokio::spawn(async move {
+            loop {
+                probe_database(&performance, &db).await;
+                let (database, resources) = performance.background_health().await;
+                let level = ThrottleLevel::from_health(&database, &resources);
+                tx.send_if_modified(|current| {
+                    if *current == level {
+                        return false;
+                    }
+                    tracing::warn!(
+                        from = current.as_str(),
+                        to = level.as_str(),
+                        database = ?database,
+                        resources = ?resources,
+                        "Background worker throttle changed"
+                    );
+                    *current = level;
+                    true
+                });
+                tokio::time::sleep(REFRESH_INTERVAL).await;
+            }
+        });
+        Self {
+            level: rx,
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// A handle that always reports `Normal`.
+    pub fn unthrottled() -> Self {
+        let (_tx, rx) = watch::channel(ThrottleLevel::Normal);
+        Self {
+            level: rx,
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Beat for each worker as it paces, so idle and paused workers still
+    /// show as alive.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Stop pacing, and tell workers to stop, once `shutdown` fires.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub fn level(&self) -> ThrottleLevel {
+        *self.level.borrow()
+    }
+
+    /// Sleep between passes: `base` normally, stretched while slowed, and
+    /// then for as long as background work is paused. Returns false, at
+    /// once, when the worker should stop instead.
+    pub async fn pace(&self, worker: &'static str, base: Duration) -> bool {
+        if self.shutdown.is_triggered() {
+            return false;
+        }
+        let wait = match self.level() {
+            ThrottleLevel::Normal => base,
+            _ => base * SLOW_FACTOR,
+        };
+        self.heartbeats.beat(worker, wait).await;
+        self.shutdown.sleep(wait).await && self.wait_while_paused(worker).await
+    }
+
+    /// Yield between batches within a pass. Free at `Normal`. Returns false
+    /// when the pass should end early because the worker is stopping.
+    pub async fn between_batches(&self, worker: &'static str) -> bool {
+        if self.level() == ThrottleLevel::Slowed && !self.shutdown.sleep(SLOWED_BATCH_DELAY).await {
+            return false;
+        }
+        self.wait_while_paused(worker).await
+    }
+
+    /// Returns false if shutdown came first.
+    async fn wait_while_paused(&self, worker: &'static str) -> bool {
+        if self.level() != ThrottleLevel::Paused {
+            return !self.shutdown.is_triggered();
+        }
+        tracing::info!(worker, "Pausing until database and resource health recover");
+        while self.level() == ThrottleLevel::Paused {
+            self.heartbeats.beat(worker, PAUSE_POLL_INTERVAL).await;
+            if !self.shutdown.sleep(PAUSE_POLL_INTERVAL).await {
+                return false;
+            }
+        }
+        tracing::info!(worker, "Resuming after health recovered");
+        !self.shutdown.is_triggered()
+    }
+}
+
+/// Time a trivial query and record pool usage, so database health reflects
+/// the pool even when nothing else reports queries.
+async fn probe_database(performance: &PerformanceMonitor, db: &PgPool) {
+    let max = db.options().get_max_connections();
+    let idle = db.num_idle() as u32;
+    let active = db.size().saturating_sub(idle);
+    performance.update_db_pool_metrics(active, idle, max).await;
+
+    let started = Instant::now();
+    let ok = sqlx::query("SELECT 1").execute(db).await.is_ok();
+    performance
+        .record_database_query(
+            "health_probe",
+            started.elapsed(),
+            0,
+            ok,
+            active as f32 / max.max(1) as f32,
+        )
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stricter_health_wins() {
+        use HealthStatus::*;
+        assert_eq!(
+            ThrottleLevel::from_health(&Healthy, &Healthy),
+            ThrottleLevel::Normal
+        );
+        assert_eq!(
+            ThrottleLevel::from_health(&Degraded, &Healthy),
+            ThrottleLevel::Slowed
+        );
+        assert_eq!(
+            ThrottleLevel::from_health(&Degraded, &Unhealthy),
+            ThrottleLevel::Paused
+        );
+        assert_eq!(
+            ThrottleLevel::from_health(&Healthy, &Critical),
+            ThrottleLevel::Paused
+        );
+    }
+
+    #[test]
+    fn unthrottled_stays_normal() {
+        assert_eq!(WorkerThrottle::unthrottled().level(), ThrottleLevel::Normal);
+    }
+}
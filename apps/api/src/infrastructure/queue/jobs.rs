@@ -0,0 +1,957 @@
+//! Typed background jobs on Redis with retries and dead-lettering.
+//!
+//! A job kind is a serializable payload implementing [`Job`], queued through
+//! a [`JobQueue`] for it. Workers [`claim`](JobQueue::claim) jobs, which moves
+//! them into an in-flight set until they are acknowledged or failed; a job
+//! whose worker died stays there until its visibility timeout passes and is
+//! then failed like any other. Failed jobs are retried with exponential
+//! backoff and dead-lettered once they have used up their attempts, where
+//! they are kept for inspection and requeueing.
+//!
+//! Keys per kind, all named by [`JobKeys`]:
+//! - `high`, `normal`, `bulk`: lists jobs are claimed from by [`Priority`],
+//!   each normally only while those above it are empty;
+//! - `inflight`: sorted set of claimed jobs scored by their visibility
+//!   deadline;
+//! - `retry`: sorted set of failed jobs scored by when they are due again;
+//! - `dead`: list of dead-lettered jobs, newest first and capped;
+//! - `idempotency`: prefix of the keys marking recently queued jobs by
+//!   [`Job::idempotency_key`], each holding the id of the job queued and
+//!   expiring with the queue's idempotency window.
+//!
+//! Times are unix seconds from the worker's clock, as for CDN purge retries.
+
+use crate::infrastructure::{
+    fault_injection::FaultInjector, monitoring::heartbeat::Heartbeats, shutdown::Shutdown,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use redis::{AsyncCommands, Client, Script, aio::MultiplexedConnection};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Oldest dead-lettered jobs are dropped beyond this many per kind.
+const DEAD_LETTER_CAP: isize = 10_000;
+
+/// How long a [`JobRunner`] may spend on one job before its heartbeat goes
+/// overdue.
+const JOB_BEAT_WITHIN: Duration = Duration::from_secs(60);
+
+/// How often an idle [`JobRunner`] beats.
+const IDLE_BEAT_WITHIN: Duration = Duration::from_secs(30);
+
+/// Takes up to `ARGV[1]` jobs from the first non-empty list among `KEYS[2..]`
+/// and adds them to the in-flight set `KEYS[1]` with deadline `ARGV[2]`, in
+/// one step so a crash between the two can't lose a job. Returns the
+/// position among `KEYS[2..]` of the list they came from, 0 for none, and
+/// the jobs.
+const CLAIM_SCRIPT: &str = r#"
+for i = 2, #KEYS do
+    local items = redis.call('RPOP', KEYS[i], ARGV[1])
+    if items then
+        for _, item in ipairs(items) do
+            redis.call('ZADD', KEYS[1], ARGV[2], item)
+        end
+        return {i - 1, items}
+    end
+end
+return {0, {}}
+"#;
+
+/// Pushes job `ARGV[1]` onto list `KEYS[1]` and marks it queued under the
+/// idempotency key `KEYS[2]` with its id `ARGV[2]` for `ARGV[3]` seconds,
+/// unless the key is already set. Returns the id already queued under the
+/// key, or nil once pushed.
+const ENQUEUE_ONCE_SCRIPT: &str = r#"
+local existing = redis.call('GET', KEYS[2])
+if existing then
+    return existing
+end
+redis.call('SET', KEYS[2], ARGV[2], 'EX', ARGV[3])
+redis.call('LPUSH', KEYS[1], ARGV[1])
+return false
+"#;
+
+/// A kind of background job.
+pub trait Job: Clone + Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Names the kind in logs and spans, and its keys unless [`Job::keys`]
+    /// is overridden.
+    const KIND: &'static str;
+
+    fn keys() -> JobKeys {
+        JobKeys::for_kind(Self::KIND)
+    }
+
+    /// The list this job goes on unless queued with an explicit priority.
+    fn priority(&self) -> Priority {
+        Priority::Normal
+    }
+
+    /// Names the work the job does, so a repeat of it, such as one queued
+    /// again by a retried request, is dropped while the first is within
+    /// the queue's idempotency window. `None` never deduplicates.
+    fn idempotency_key(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Which list a job waits on. Ordered from most to least urgent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Bulk,
+}
+
+impl Priority {
+    /// Most urgent first, the order [`JobQueue::claim`] drains lists in.
+    pub const ALL: [Self; 3] = [Self::High, Self::Normal, Self::Bulk];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::High => "high",
+            Self::Normal => "normal",
+            Self::Bulk => "bulk",
+        }
+    }
+}
+
+/// Claim orders for a worker draining the priority lists in turns: most
+/// urgent first, except that every `normal_every`-th batch starts at
+/// `Normal` and every `bulk_every`-th at `Bulk`, so a steady stream of
+/// urgent jobs can't starve the lists below it.
+#[derive(Debug)]
+pub struct ClaimRotation {
+    normal_every: u64,
+    bulk_every: u64,
+    batches: AtomicU64,
+}
+
+impl ClaimRotation {
+    /// Intervals under 2 are raised to 2: a list never gets every turn, and
+    /// a zero interval from config can't reach the modulo.
+    pub fn new(normal_every: u64, bulk_every: u64) -> Self {
+        Self {
+            normal_every: normal_every.max(2),
+            bulk_every: bulk_every.max(2),
+            batches: AtomicU64::new(0),
+        }
+    }
+
+    /// The order for the next batch.
+    pub fn next_order(&self) -> [Priority; 3] {
+        let batch = self.batches.fetch_add(1, Ordering::Relaxed) + 1;
+        if batch.is_multiple_of(self.bulk_every) {
+            [Priority::Bulk, Priority::Normal, Priority::High]
+        } else if batch.is_multiple_of(self.normal_every) {
+            [Priority::Normal, Priority::High, Priority::Bulk]
+        } else {
+            Priority::ALL
+        }
+    }
+}
+
+/// Redis keys of one job kind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobKeys {
+    pub high: String,
+    pub normal: String,
+    pub bulk: String,
+    pub inflight: String,
+    pub retry: String,
+    pub dead: String,
+    pub idempotency: String,
+}
+
+impl JobKeys {
+    pub fn for_kind(kind: &str) -> Self {
+        Self {
+            high: format!("jobs:{}:high", kind),
+            normal: format!("jobs:{}", kind),
+            bulk: format!("jobs:{}:bulk", kind),
+            inflight: format!("jobs:{}:inflight", kind),
+            retry: format!("jobs:{}:retry", kind),
+            dead: format!("jobs:{}:dead", kind),
+            idempotency: format!("jobs:{}:idempotency", kind),
+        }
+    }
+
+    pub fn list(&self, priority: Priority) -> &str {
+        match priority {
+            Priority::High => &self.high,
+            Priority::Normal => &self.normal,
+            Priority::Bulk => &self.bulk,
+        }
+    }
+
+    /// Marker for jobs queued under idempotency key `key`.
+    pub fn idempotency_key(&self, key: &str) -> String {
+        format!("{}:{}", self.idempotency, key)
+    }
+}
+
+/// How often and how soon a failed job is tried again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts, the first included, before a job is dead-lettered.
+    pub max_attempts: u32,
+    /// Wait after the first failure, doubling after each further one.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// How long a claimed job may run before it is considered failed.
+    pub visibility_timeout: Duration,
+}
+
+impl RetryPolicy {
+    /// Backoff before retrying a job that has failed `attempts` times.
+    pub fn delay(&self, attempts: u32) -> Duration {
+        let factor = 1u32 << attempts.saturating_sub(1).min(16);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// A job with its bookkeeping. The payload's fields sit next to these in
+/// the stored JSON, so payloads queued before a kind moved onto this module
+/// still decode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope<J> {
+    #[serde(default = "Uuid::now_v7")]
+    pub id: Uuid,
+    /// Failed attempts so far.
+    #[serde(default)]
+    pub attempts: u32,
+    /// When the job was first queued.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enqueued_at: Option<DateTime<Utc>>,
+    /// Set when queued with a priority other than the job's own; retries
+    /// keep it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(flatten)]
+    pub job: J,
+}
+
+impl<J: Job> Envelope<J> {
+    pub fn priority(&self) -> Priority {
+        self.priority.unwrap_or_else(|| self.job.priority())
+    }
+}
+
+/// A job that failed too many times, kept for inspection and requeueing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter<J> {
+    pub job: Envelope<J>,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// A job claimed by a worker, to be acknowledged or failed.
+#[derive(Debug)]
+pub struct Claimed<J> {
+    pub envelope: Envelope<J>,
+    /// The list it was claimed from.
+    pub priority: Priority,
+    /// The stored JSON, which identifies it in the in-flight set.
+    member: String,
+}
+
+impl<J: Job> Claimed<J> {
+    pub fn job(&self) -> &J {
+        &self.envelope.job
+    }
+
+    /// Span for the work on this job.
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "job",
+            kind = J::KIND,
+            job_id = %self.envelope.id,
+            attempt = self.envelope.attempts + 1,
+            priority = self.priority.as_str(),
+        )
+    }
+}
+
+/// Jobs of one kind in each state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct JobCounts {
+    pub high: usize,
+    pub normal: usize,
+    pub bulk: usize,
+    pub inflight: usize,
+    pub retry: usize,
+    pub dead: usize,
+}
+
+/// What became of a failed job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// Scheduled to run again after the delay.
+    Retrying(Duration),
+    DeadLettered,
+    /// Already failed by the visibility timeout; nothing was done.
+    Expired,
+}
+
+/// Queue of one job kind.
+pub struct JobQueue<J> {
+    client: Client,
+    keys: JobKeys,
+    policy: RetryPolicy,
+    /// How long a queued job's idempotency key holds off repeats; zero
+    /// turns deduplication off.
+    idempotency_window: Duration,
+    faults: Option<Arc<FaultInjector>>,
+    _job: PhantomData<fn() -> J>,
+}
+
+// Not derived, which would require `J: Clone`.
+impl<J> Clone for JobQueue<J> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            keys: self.keys.clone(),
+            policy: self.policy,
+            idempotency_window: self.idempotency_window,
+            faults: self.faults.clone(),
+            _job: PhantomData,
+        }
+    }
+}
+
+impl<J: Job> JobQueue<J> {
+    pub fn new(client: Client, policy: RetryPolicy) -> Self {
+        Self {
+            client,
+            keys: J::keys(),
+            policy,
+            idempotency_window: Duration::ZERO,
+            faults: None,
+            _job: PhantomData,
+        }
+    }
+
+    /// Fail a share of queue calls as configured.
+    pub fn with_fault_injector(mut self, faults: Arc<FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    pub fn policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Drop jobs whose idempotency key was queued within `window`.
+    pub fn with_idempotency_window(mut self, window: Duration) -> Self {
+        self.idempotency_window = window;
+        self
+    }
+
+    /// Marker key for `job`, when it deduplicates and the window is open.
+    fn idempotency_key(&self, job: &J) -> Option<String> {
+        if self.idempotency_window.is_zero() {
+            return None;
+        }
+        job.idempotency_key()
+            .map(|key| self.keys.idempotency_key(&key))
+    }
+
+    async fn connection(&self) -> anyhow::Result<MultiplexedConnection> {
+        self.faults.as_ref().map_or(Ok(()), |f| f.redis())?;
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            self.client.get_multiplexed_async_connection(),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Redis connection timed out"))?
+        .map_err(Into::into)
+    }
+
+    /// Queue `job` on the list for its own priority and return its id. A
+    /// repeat of a job queued within the idempotency window is dropped, and
+    /// the id of the job already queued returned instead.
+    pub async fn enqueue(&self, job: J) -> anyhow::Result<Uuid> {
+        self.enqueue_envelope(Envelope {
+            id: Uuid::now_v7(),
+            attempts: 0,
+            enqueued_at: Some(Utc::now()),
+            priority: None,
+            job,
+        })
+        .await
+    }
+
+    /// Queue `job` on the `priority` list and return its id.
+    pub async fn enqueue_with_priority(&self, job: J, priority: Priority) -> anyhow::Result<Uuid> {
+        self.enqueue_envelope(Envelope {
+            id: Uuid::now_v7(),
+            attempts: 0,
+            enqueued_at: Some(Utc::now()),
+            priority: (priority != job.priority()).then_some(priority),
+            job,
+        })
+        .await
+    }
+
+    async fn enqueue_envelope(&self, envelope: Envelope<J>) -> anyhow::Result<Uuid> {
+        let list = self.keys.list(envelope.priority());
+        let payload = serde_json::to_string(&envelope)?;
+        let mut conn = self.connection().await?;
+        let Some(key) = self.idempotency_key(&envelope.job) else {
+            let _: usize = conn.lpush(list, payload).await?;
+            return Ok(envelope.id);
+        };
+        let existing: Option<String> = Script::new(ENQUEUE_ONCE_SCRIPT)
+            .key(list)
+            .key(&key)
+            .arg(payload)
+            .arg(envelope.id.to_string())
+            .arg(self.idempotency_window.as_secs().max(1))
+            .invoke_async(&mut conn)
+            .await?;
+        match existing {
+            Some(existing) => {
+                tracing::debug!(kind = J::KIND, key, "Dropped duplicate job");
+                Ok(existing.parse().unwrap_or(envelope.id))
+            }
+            None => Ok(envelope.id),
+        }
+    }
+
+    /// Queue several jobs at once. Jobs with an idempotency key are checked
+    /// one at a time, so repeats within the batch are dropped too.
+    pub async fn enqueue_all(&self, jobs: Vec<J>) -> anyhow::Result<()> {
+        if jobs.is_empty() {
+            return Ok(());
+        }
+        let now = Utc::now();
+        let mut pipe = redis::pipe();
+        let mut pipelined = 0;
+        let mut keyed = Vec::new();
+        for job in jobs {
+            let envelope = Envelope {
+                id: Uuid::now_v7(),
+                attempts: 0,
+                enqueued_at: Some(now),
+                priority: None,
+                job,
+            };
+            if self.idempotency_key(&envelope.job).is_some() {
+                keyed.push(envelope);
+                continue;
+            }
+            pipe.lpush(
+                self.keys.list(envelope.priority()),
+                serde_json::to_string(&envelope)?,
+            )
+            .ignore();
+            pipelined += 1;
+        }
+        if pipelined > 0 {
+            let mut conn = self.connection().await?;
+            let _: () = pipe.query_async(&mut conn).await?;
+        }
+        for envelope in keyed {
+            self.enqueue_envelope(envelope).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn counts(&self) -> anyhow::Result<JobCounts> {
+        let mut conn = self.connection().await?;
+        let (high, normal, bulk, inflight, retry, dead): (
+            usize,
+            usize,
+            usize,
+            usize,
+            usize,
+            usize,
+        ) = redis::pipe()
+            .llen(&self.keys.high)
+            .llen(&self.keys.normal)
+            .llen(&self.keys.bulk)
+            .zcard(&self.keys.inflight)
+            .zcard(&self.keys.retry)
+            .llen(&self.keys.dead)
+            .query_async(&mut conn)
+            .await?;
+        Ok(JobCounts {
+            high,
+            normal,
+            bulk,
+            inflight,
+            retry,
+            dead,
+        })
+    }
+
+    /// Jobs waiting on the `priority` list.
+    pub async fn backlog(&self, priority: Priority) -> anyhow::Result<usize> {
+        let mut conn = self.connection().await?;
+        Ok(conn.llen(self.keys.list(priority)).await?)
+    }
+
+    /// Claim up to `max` waiting jobs from the most urgent non-empty list.
+    pub async fn claim(&self, max: usize) -> anyhow::Result<Vec<Claimed<J>>> {
+        self.claim_in_order(max, &Priority::ALL).await
+    }
+
+    /// Claim up to `max` waiting jobs, all from the first non-empty list in
+    /// `order`. Malformed payloads are dropped.
+    pub async fn claim_in_order(
+        &self,
+        max: usize,
+        order: &[Priority],
+    ) -> anyhow::Result<Vec<Claimed<J>>> {
+        let mut conn = self.connection().await?;
+        let deadline = Utc::now().timestamp() + self.policy.visibility_timeout.as_secs() as i64;
+        let script = Script::new(CLAIM_SCRIPT);
+        let mut invocation = script.prepare_invoke();
+        invocation.key(&self.keys.inflight);
+        for &priority in order {
+            invocation.key(self.keys.list(priority));
+        }
+        let (list, members): (usize, Vec<String>) = invocation
+            .arg(max.max(1))
+            .arg(deadline)
+            .invoke_async(&mut conn)
+            .await?;
+        let Some(&priority) = list.checked_sub(1).and_then(|i| order.get(i)) else {
+            return Ok(Vec::new());
+        };
+
+        let mut claimed = Vec::with_capacity(members.len());
+        for member in members {
+            match serde_json::from_str(&member) {
+                Ok(envelope) => claimed.push(Claimed {
+                    envelope,
+                    priority,
+                    member,
+                }),
+                Err(e) => {
+                    tracing::warn!(kind = J::KIND, "Dropping malformed job payload: {}", e);
+                    let _: usize = conn.zrem(&self.keys.inflight, &member).await?;
+                }
+            }
+        }
+        Ok(claimed)
+    }
+
+    /// Mark a claimed job done.
+    pub async fn ack(&self, claimed: &Claimed<J>) -> anyhow::Result<()> {
+        let mut conn = self.connection().await?;
+        let _: usize = conn.zrem(&self.keys.inflight, &claimed.member).await?;
+        Ok(())
+    }
+
+    /// Hand a claimed job back unrun, to be claimed next from its list,
+    /// without counting an attempt. Returns false if it had already timed
+    /// out of the in-flight set.
+    pub async fn requeue(&self, claimed: &Claimed<J>) -> anyhow::Result<bool> {
+        let mut conn = self.connection().await?;
+        let removed: usize = conn.zrem(&self.keys.inflight, &claimed.member).await?;
+        if removed == 0 {
+            return Ok(false);
+        }
+        let _: usize = conn
+            .rpush(self.keys.list(claimed.priority), &claimed.member)
+            .await?;
+        Ok(true)
+    }
+
+    /// Retry a claimed job after its backoff, or dead-letter it once it has
+    /// used up its attempts.
+    pub async fn fail(
+        &self,
+        claimed: &Claimed<J>,
+        error: &anyhow::Error,
+    ) -> anyhow::Result<Failure> {
+        let mut conn = self.connection().await?;
+        let removed: usize = conn.zrem(&self.keys.inflight, &claimed.member).await?;
+        if removed == 0 {
+            return Ok(Failure::Expired);
+        }
+        self.retry_or_bury(&mut conn, claimed.envelope.clone(), format!("{:#}", error))
+            .await
+    }
+
+    async fn retry_or_bury(
+        &self,
+        conn: &mut MultiplexedConnection,
+        mut envelope: Envelope<J>,
+        error: String,
+    ) -> anyhow::Result<Failure> {
+        envelope.attempts += 1;
+        if envelope.attempts >= self.policy.max_attempts {
+            let entry = DeadLetter {
+                job: envelope,
+                error,
+                failed_at: Utc::now(),
+            };
+            let _: usize = conn
+                .lpush(&self.keys.dead, serde_json::to_string(&entry)?)
+                .await?;
+            let _: () = conn.ltrim(&self.keys.dead, 0, DEAD_LETTER_CAP - 1).await?;
+            return Ok(Failure::DeadLettered);
+        }
+
+        let delay = self.policy.delay(envelope.attempts);
+        let due_at = Utc::now().timestamp() + delay.as_secs() as i64;
+        let _: usize = conn
+            .zadd(&self.keys.retry, serde_json::to_string(&envelope)?, due_at)
+            .await?;
+        Ok(Failure::Retrying(delay))
+    }
+
+    /// Move up to `limit` due retries back onto their list, and fail up to
+    /// `limit` claimed jobs whose visibility timeout has passed. Like
+    /// `take_due_cdn_purges`, an entry is only moved by the caller that
+    /// removed it. Returns how many jobs were released.
+    pub async fn release_due(&self, limit: isize) -> anyhow::Result<usize> {
+        let mut conn = self.connection().await?;
+        let now = Utc::now().timestamp();
+        let mut released = 0;
+
+        let due: Vec<String> = conn
+            .zrangebyscore_limit(&self.keys.retry, "-inf", now, 0, limit)
+            .await?;
+        for member in due {
+            let removed: usize = conn.zrem(&self.keys.retry, &member).await?;
+            if removed == 1 {
+                let list = serde_json::from_str::<Envelope<J>>(&member)
+                    .map(|envelope| self.keys.list(envelope.priority()))
+                    .unwrap_or(self.keys.list(Priority::Normal));
+                let _: usize = conn.lpush(list, &member).await?;
+                released += 1;
+            }
+        }
+
+        let expired: Vec<String> = conn
+            .zrangebyscore_limit(&self.keys.inflight, "-inf", now, 0, limit)
+            .await?;
+        for member in expired {
+            let removed: usize = conn.zrem(&self.keys.inflight, &member).await?;
+            if removed == 0 {
+                continue;
+            }
+            match serde_json::from_str::<Envelope<J>>(&member) {
+                Ok(envelope) => {
+                    tracing::warn!(
+                        kind = J::KIND,
+                        job_id = %envelope.id,
+                        "Job visibility timeout expired"
+                    );
+                    self.retry_or_bury(&mut conn, envelope, "visibility timeout expired".into())
+                        .await?;
+                    released += 1;
+                }
+                Err(e) => tracing::warn!(kind = J::KIND, "Dropping malformed job payload: {}", e),
+            }
+        }
+        Ok(released)
+    }
+
+    /// A page of dead-lettered jobs, newest first, and the total count.
+    pub async fn dead_letters(
+        &self,
+        offset: isize,
+        limit: isize,
+    ) -> anyhow::Result<(Vec<DeadLetter<J>>, usize)> {
+        let mut conn = self.connection().await?;
+        let total: usize = conn.llen(&self.keys.dead).await?;
+        let members: Vec<String> = conn
+            .lrange(&self.keys.dead, offset, offset + limit - 1)
+            .await?;
+        let mut entries = Vec::with_capacity(members.len());
+        for member in members {
+            match serde_json::from_str(&member) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => tracing::warn!(kind = J::KIND, "Skipping malformed dead letter: {}", e),
+            }
+        }
+        Ok((entries, total))
+    }
+
+    /// Put the dead-lettered jobs `matches` accepts back on their list, or
+    /// the `priority` list if given, with a fresh attempt count, and return
+    /// them.
+    pub async fn requeue_dead_letters(
+        &self,
+        matches: impl Fn(&J) -> bool,
+        priority: Option<Priority>,
+    ) -> anyhow::Result<Vec<Envelope<J>>> {
+        let mut conn = self.connection().await?;
+        let members: Vec<String> = conn.lrange(&self.keys.dead, 0, -1).await?;
+        let mut requeued = Vec::new();
+        for member in members {
+            let Ok(entry) = serde_json::from_str::<DeadLetter<J>>(&member) else {
+                continue;
+            };
+            if !matches(&entry.job.job) {
+                continue;
+            }
+            let removed: usize = conn.lrem(&self.keys.dead, 1, &member).await?;
+            if removed == 0 {
+                continue;
+            }
+            let mut envelope = Envelope {
+                attempts: 0,
+                ..entry.job
+            };
+            if let Some(priority) = priority {
+                envelope.priority = (priority != envelope.job.priority()).then_some(priority);
+            }
+            let _: usize = conn
+                .lpush(
+                    self.keys.list(envelope.priority()),
+                    serde_json::to_string(&envelope)?,
+                )
+                .await?;
+            requeued.push(envelope);
+        }
+        Ok(requeued)
+    }
+}
+
+/// Does the work of one job kind.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    type Job: Job;
+
+    /// Runs a job. A job may be run again after it succeeded if its
+    /// acknowledgement is lost, so handlers should be idempotent, for
+    /// instance by keying writes on the envelope's id.
+    async fn handle(&self, job: &Envelope<Self::Job>) -> anyhow::Result<()>;
+}
+
+/// Runs claimed jobs one by one through a [`JobHandler`], each in its own
+/// span, for kinds that don't need batching.
+pub struct JobRunner<H: JobHandler> {
+    queue: JobQueue<H::Job>,
+    handler: H,
+    batch_size: usize,
+    idle_wait: Duration,
+    heartbeats: Heartbeats,
+    /// Name heartbeats are recorded under.
+    worker: &'static str,
+    shutdown: Shutdown,
+}
+
+impl<H: JobHandler> JobRunner<H> {
+    pub fn new(queue: JobQueue<H::Job>, handler: H) -> Self {
+        Self {
+            queue,
+            handler,
+            batch_size: 50,
+            idle_wait: Duration::from_secs(1),
+            heartbeats: Heartbeats::disabled(),
+            worker: H::Job::KIND,
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Once `shutdown` fires, finish the job in hand, hand the rest of the
+    /// batch back and return from `start`.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Beat as `worker` while idle, name each job while running it, and
+    /// count processed and failed jobs.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats, worker: &'static str) -> Self {
+        self.heartbeats = heartbeats;
+        self.worker = worker;
+        self
+    }
+
+    pub async fn start(&self) {
+        while !self.shutdown.is_triggered() {
+            match self.run_once().await {
+                Ok(0) => {
+                    self.heartbeats.beat(self.worker, IDLE_BEAT_WITHIN).await;
+                    self.shutdown.sleep(self.idle_wait).await;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(kind = H::Job::KIND, "Job pass failed: {}", e);
+                    self.shutdown.sleep(self.idle_wait).await;
+                }
+            }
+        }
+    }
+
+    /// Releases due jobs, then claims and runs one batch. Returns how many
+    /// jobs were run; on shutdown the ones not yet started go back.
+    pub async fn run_once(&self) -> anyhow::Result<usize> {
+        self.queue.release_due(self.batch_size as isize).await?;
+        let claimed = self.queue.claim(self.batch_size).await?;
+        for (run, job) in claimed.iter().enumerate() {
+            if self.shutdown.is_triggered() {
+                for unstarted in &claimed[run..] {
+                    self.queue.requeue(unstarted).await?;
+                }
+                return Ok(run);
+            }
+            let span = job.span();
+            self.heartbeats
+                .working_on(
+                    self.worker,
+                    &format!("{} {}", H::Job::KIND, job.envelope.id),
+                    JOB_BEAT_WITHIN,
+                )
+                .await;
+            async {
+                match self.handler.handle(&job.envelope).await {
+                    Ok(()) => {
+                        self.heartbeats.count(self.worker, 1, 0).await;
+                        self.queue.ack(job).await
+                    }
+                    Err(e) => {
+                        self.heartbeats.count(self.worker, 0, 1).await;
+                        match self.queue.fail(job, &e).await? {
+                            Failure::Retrying(delay) => tracing::warn!(
+                                retry_in_secs = delay.as_secs(),
+                                "Job failed, will retry: {:#}",
+                                e
+                            ),
+                            Failure::DeadLettered => {
+                                tracing::error!("Job failed, dead-lettered: {:#}", e)
+                            }
+                            Failure::Expired => {}
+                        }
+                        Ok::<_, anyhow::Error>(())
+                    }
+                }
+            }
+            .instrument(span)
+            .await?;
+        }
+        Ok(claimed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Ping {
+        target: String,
+    }
+
+    impl Job for Ping {
+        const KIND: &'static str = "ping";
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(1800),
+            visibility_timeout: Duration::from_secs(60),
+        };
+        assert_eq!(policy.delay(1), Duration::from_secs(30));
+        assert_eq!(policy.delay(2), Duration::from_secs(60));
+        assert_eq!(policy.delay(4), Duration::from_secs(240));
+        assert_eq!(policy.delay(7), Duration::from_secs(1800));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(1800));
+    }
+
+    #[test]
+    fn envelopes_flatten_the_payload() {
+        let envelope = Envelope {
+            id: Uuid::nil(),
+            attempts: 2,
+            enqueued_at: None,
+            priority: None,
+            job: Ping { target: "a".into() },
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["target"], "a");
+        assert_eq!(json["attempts"], 2);
+
+        // A bare payload, as queued before the kind used envelopes.
+        let legacy: Envelope<Ping> = serde_json::from_str(r#"{"target":"b"}"#).unwrap();
+        assert_eq!(legacy.attempts, 0);
+        assert_eq!(legacy.job.target, "b");
+    }
+
+    #[test]
+    fn keys_are_namespaced_by_kind() {
+        let keys = Ping::keys();
+        assert_eq!(keys.normal, "jobs:ping");
+        assert_eq!(keys.list(Priority::High), "jobs:ping:high");
+        assert_eq!(keys.dead, "jobs:ping:dead");
+        assert_eq!(keys.idempotency_key("a"), "jobs:ping:idempotency:a");
+    }
+
+    #[test]
+    fn jobs_without_a_key_or_window_are_never_deduplicated() {
+        let queue = JobQueue::<Ping>::new(
+            Client::open("redis://localhost:6379").unwrap(),
+            RetryPolicy {
+                max_attempts: 1,
+                base_delay: Duration::ZERO,
+                max_delay: Duration::ZERO,
+                visibility_timeout: Duration::from_secs(60),
+            },
+        );
+        let ping = Ping { target: "a".into() };
+        assert_eq!(queue.idempotency_key(&ping), None);
+        let queue = queue.with_idempotency_window(Duration::from_secs(60));
+        assert_eq!(queue.idempotency_key(&ping), None);
+    }
+
+    #[test]
+    fn explicit_priorities_override_the_jobs_own() {
+        let mut envelope: Envelope<Ping> = serde_json::from_str(r#"{"target":"c"}"#).unwrap();
+        assert_eq!(envelope.priority(), Priority::Normal);
+        envelope.priority = Some(Priority::High);
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["priority"], "high");
+    }
+
+    #[test]
+    fn rotation_gives_lower_lists_a_turn() {
+        let rotation = ClaimRotation::new(3, 5);
+        let starts: Vec<Priority> = (0..15).map(|_| rotation.next_order()[0]).collect();
+        let count = |p| starts.iter().filter(|&&s| s == p).count();
+        assert_eq!(count(Priority::Bulk), 3);
+        assert_eq!(count(Priority::Normal), 4);
+        assert_eq!(count(Priority::High), 8);
+        assert_eq!(starts[2], Priority::Normal);
+        assert_eq!(starts[4], Priority::Bulk);
+        // Batch 15 is a multiple of both; bulk wins.
+        assert_eq!(starts[14], Priority::Bulk);
+
+        let unset = ClaimRotation::new(0, 0);
+        let starts: Vec<Priority> = (0..4).map(|_| unset.next_order()[0]).collect();
+        assert_eq!(
+            starts,
+            [
+                Priority::High,
+                Priority::Bulk,
+                Priority::High,
+                Priority::Bulk
+            ]
+        );
+    }
+}
@@ -1,37 +1,249 @@
+use crate::infrastructure::{
+    fault_injection::FaultInjector,
+    queue::jobs::{DeadLetter, Job, JobKeys, JobQueue, Priority, RetryPolicy},
+};
 use redis::{AsyncCommands, Client};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use sha2::{Digest, Sha256};
+use std::{sync::Arc, time::Duration};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MlJob {
     pub lettering_id: Uuid,
     pub image_url: String,
+    /// Set for jobs queued by a corpus reprocessing run. These go on the
+    /// bulk queue and leave the lettering's moderation status alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reprocess_run_id: Option<Uuid>,
 }
 
+impl Job for MlJob {
+    const KIND: &'static str = "ml";
+
+    /// The keys ML jobs used before they moved onto [`JobQueue`], so jobs
+    /// queued by an older deploy are still picked up.
+    fn keys() -> JobKeys {
+        JobKeys {
+            high: "ml_jobs_high".into(),
+            normal: "ml_jobs".into(),
+            bulk: "ml_jobs_reprocess".into(),
+            inflight: "ml_jobs_inflight".into(),
+            retry: "ml_jobs_retry".into(),
+            dead: "ml_jobs_dead".into(),
+            idempotency: "ml_jobs_idempotency".into(),
+        }
+    }
+
+    fn priority(&self) -> Priority {
+        if self.reprocess_run_id.is_some() {
+            Priority::Bulk
+        } else {
+            Priority::Normal
+        }
+    }
+
+    /// One job per lettering for its upload, and one per reprocessing run.
+    fn idempotency_key(&self) -> Option<String> {
+        Some(match self.reprocess_run_id {
+            Some(run_id) => format!("{}:{}", self.lettering_id, run_id),
+            None => self.lettering_id.to_string(),
+        })
+    }
+}
+
+/// A notification to insert for a user, queued by request handlers so a
+/// slow or failing insert doesn't fail the request that caused it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationJob {
+    pub user_id: Uuid,
+    /// `notifications.type`, e.g. `COMMENT_DELETED`.
+    pub kind: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+}
+
+impl Job for NotificationJob {
+    const KIND: &'static str = "notification";
+
+    /// Identical notifications for the same user are sent once per window.
+    /// Notifications about different things differ in their metadata.
+    fn idempotency_key(&self) -> Option<String> {
+        let payload = serde_json::to_string(self).ok()?;
+        Some(format!("{:x}", Sha256::digest(payload.as_bytes())))
+    }
+}
+
+/// 30s, doubling up to 30 minutes. A claimed batch that hasn't finished
+/// within the visibility timeout is retried; inference on a full batch with
+/// remote fallbacks stays well under it.
+const ML_RETRY_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 5,
+    base_delay: Duration::from_secs(30),
+    max_delay: Duration::from_secs(1800),
+    visibility_timeout: Duration::from_secs(600),
+};
+
+const NOTIFICATION_RETRY_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 8,
+    base_delay: Duration::from_secs(5),
+    max_delay: Duration::from_secs(600),
+    visibility_timeout: Duration::from_secs(60),
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CdnPurgeJob {
+    pub id: Uuid,
+    pub urls: Vec<String>,
+    pub attempts: u32,
+}
+
+const CDN_PURGE_RETRY_KEY: &str = "cdn_purge_retry";
+
 pub struct RedisQueue {
     client: Client,
+    faults: Option<Arc<FaultInjector>>,
+    ml: JobQueue<MlJob>,
+    notifications: JobQueue<NotificationJob>,
 }
 impl RedisQueue {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            ml: JobQueue::new(client.clone(), ML_RETRY_POLICY),
+            notifications: JobQueue::new(client.clone(), NOTIFICATION_RETRY_POLICY),
+            client,
+            faults: None,
+        }
+    }
+    /// Fail a share of queue calls as configured.
+    pub fn with_fault_injector(mut self, faults: Arc<FaultInjector>) -> Self {
+        self.ml = self.ml.with_fault_injector(faults.clone());
+        self.notifications = self.notifications.with_fault_injector(faults.clone());
+        self.faults = Some(faults);
+        self
+    }
+    /// Drop ML jobs and notifications repeating one queued within `window`.
+    pub fn with_idempotency_window(mut self, window: Duration) -> Self {
+        self.ml = self.ml.with_idempotency_window(window);
+        self.notifications = self.notifications.with_idempotency_window(window);
+        self
+    }
+    /// Dead-letter ML jobs after `max_attempts` attempts.
+    pub fn with_ml_max_attempts(mut self, max_attempts: u32) -> Self {
+        let policy = RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            ..self.ml.policy()
+        };
+        self.ml = self.ml.with_policy(policy);
+        self
+    }
+    fn inject_fault(&self) -> anyhow::Result<()> {
+        self.faults.as_ref().map_or(Ok(()), |f| f.redis())
+    }
+    pub fn ml_jobs(&self) -> &JobQueue<MlJob> {
+        &self.ml
+    }
+    pub fn notifications(&self) -> &JobQueue<NotificationJob> {
+        &self.notifications
     }
     pub async fn enqueue_ml_job(&self, job: MlJob) -> anyhow::Result<()> {
+        self.ml.enqueue(job).await?;
+        Ok(())
+    }
+    /// Queue an ML job ahead of, or behind, the normal backlog.
+    pub async fn enqueue_ml_job_with_priority(
+        &self,
+        job: MlJob,
+        priority: Priority,
+    ) -> anyhow::Result<()> {
+        self.ml.enqueue_with_priority(job, priority).await?;
+        Ok(())
+    }
+    /// Append reprocessing jobs to the bulk queue.
+    pub async fn enqueue_ml_reprocess_jobs(&self, jobs: &[MlJob]) -> anyhow::Result<()> {
+        self.ml.enqueue_all(jobs.to_vec()).await
+    }
+    /// Reprocessing jobs waiting on the bulk queue.
+    pub async fn ml_reprocess_backlog(&self) -> anyhow::Result<usize> {
+        self.ml.backlog(Priority::Bulk).await
+    }
+    /// Upload jobs waiting on the ML queue. Reprocessing jobs on the bulk
+    /// queue are left out since uploads are claimed ahead of them.
+    pub async fn ml_upload_backlog(&self) -> anyhow::Result<usize> {
+        Ok(self.ml.backlog(Priority::High).await? + self.ml.backlog(Priority::Normal).await?)
+    }
+    /// Queue a notification for delivery.
+    pub async fn enqueue_notification(&self, job: NotificationJob) -> anyhow::Result<()> {
+        self.notifications.enqueue(job).await?;
+        Ok(())
+    }
+    /// Queue several notifications at once.
+    pub async fn enqueue_notifications(&self, jobs: Vec<NotificationJob>) -> anyhow::Result<()> {
+        self.notifications.enqueue_all(jobs).await
+    }
+    /// Schedule a CDN purge retry. Jobs sit in a sorted set scored by the
+    /// unix time at which they become due.
+    pub async fn schedule_cdn_purge(
+        &self,
+        job: &CdnPurgeJob,
+        delay: Duration,
+    ) -> anyhow::Result<()> {
+        self.inject_fault()?;
         let mut conn = tokio::time::timeout(
             Duration::from_secs(5),
             self.client.get_multiplexed_async_connection(),
         )
         .await
         .map_err(|_| anyhow::anyhow!("Redis connection timed out"))??;
-        let _: usize = conn.lpush("ml_jobs", serde_json::to_string(&job)?).await?;
+        let due_at = chrono::Utc::now().timestamp() + delay.as_secs() as i64;
+        let _: usize = conn
+            .zadd(CDN_PURGE_RETRY_KEY, serde_json::to_string(job)?, due_at)
+            .await?;
         Ok(())
     }
-    pub async fn dequeue_ml_job(&self) -> anyhow::Result<Option<MlJob>> {
+    /// Claim up to `limit` due purge jobs. A job is only returned to the
+    /// caller that managed to remove it, so concurrent workers don't double-run it.
+    pub async fn take_due_cdn_purges(&self, limit: isize) -> anyhow::Result<Vec<CdnPurgeJob>> {
+        self.inject_fault()?;
         let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let res: Option<(String, String)> = conn.brpop("ml_jobs", 5.0).await?;
-        match res {
-            Some((_, json)) => Ok(Some(serde_json::from_str(&json)?)),
-            None => Ok(None),
+        let now = chrono::Utc::now().timestamp();
+        let members: Vec<String> = conn
+            .zrangebyscore_limit(CDN_PURGE_RETRY_KEY, "-inf", now, 0, limit)
+            .await?;
+        let mut jobs = Vec::new();
+        for member in members {
+            let removed: usize = conn.zrem(CDN_PURGE_RETRY_KEY, &member).await?;
+            if removed == 1 {
+                jobs.push(serde_json::from_str(&member)?);
+            }
         }
+        Ok(jobs)
+    }
+    /// A page of dead-lettered ML jobs, newest first, and the total count.
+    pub async fn list_ml_dead_letters(
+        &self,
+        offset: isize,
+        limit: isize,
+    ) -> anyhow::Result<(Vec<DeadLetter<MlJob>>, usize)> {
+        self.ml.dead_letters(offset, limit).await
+    }
+    /// Put dead-lettered ML jobs back with a fresh attempt count: those for
+    /// `lettering_id` on the high-priority queue, as an admin is waiting on
+    /// them, or all of them on their own queue. Returns the jobs requeued.
+    pub async fn requeue_ml_dead_letters(
+        &self,
+        lettering_id: Option<Uuid>,
+    ) -> anyhow::Result<Vec<MlJob>> {
+        let requeued = self
+            .ml
+            .requeue_dead_letters(
+                |job| lettering_id.is_none_or(|id| id == job.lettering_id),
+                lettering_id.map(|_| Priority::High),
+            )
+            .await?;
+        Ok(requeued.into_iter().map(|envelope| envelope.job).collect())
     }
 }
@@ -0,0 +1,23 @@
+//! Age gating in public lettering queries.
+//!
+//! A lettering flagged `age_restricted` is left out of public results in
+//! regions whose policy has `age_gate_enabled`, unless the client passes
+//! `age_ack=true` to say it has shown its own age gate.
+
+use sqlx::{Postgres, QueryBuilder};
+
+/// Adds the age gate condition to a query over `letterings l` left-joined
+/// with `region_policies rp`.
+pub fn push_age_gate(qb: &mut QueryBuilder<'_, Postgres>, age_ack: bool) {
+    qb.push(" AND (NOT l.age_restricted OR NOT COALESCE(rp.age_gate_enabled, false) OR ")
+        .push_bind(age_ack)
+        .push(")");
+}
+
+/// [`push_age_gate`] with the acknowledgment bound at `$age_ack_param`.
+pub fn age_gate_sql(age_ack_param: usize) -> String {
+    format!(
+        "AND (NOT l.age_restricted OR NOT COALESCE(rp.age_gate_enabled, false) OR ${})",
+        age_ack_param
+    )
+}
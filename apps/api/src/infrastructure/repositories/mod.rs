@@ -1,2 +1,5 @@
+pub mod age_gate;
+pub mod processing_jobs;
+pub mod region_policy;
 pub mod sqlx_lettering_repository;
 pub mod sqlx_social_repository;
@@ -0,0 +1,170 @@
+//! Per-upload processing progress.
+//!
+//! Every upload gets a `processing_jobs` row with a status per stage. The
+//! virus scan and thumbnails finish within the upload request; ML is
+//! updated by the worker as it claims, retries and completes the job. Each
+//! change is also broadcast on the WebSocket feed as a `PROCESSING` event,
+//! so the uploading client can follow along without polling.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use tokio::sync::broadcast;
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    VirusScan,
+    Thumbnails,
+    Ml,
+}
+
+impl Stage {
+    /// Column and event name of the stage.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::VirusScan => "virus_scan",
+            Self::Thumbnails => "thumbnails",
+            Self::Ml => "ml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageStatus {
+    Pending,
+    Running,
+    /// Failed, and will be attempted again.
+    Retrying,
+    Done,
+    Failed,
+    /// Not run for this upload, e.g. ML while it is disabled.
+    Skipped,
+}
+
+impl StageStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Running => "RUNNING",
+            Self::Retrying => "RETRYING",
+            Self::Done => "DONE",
+            Self::Failed => "FAILED",
+            Self::Skipped => "SKIPPED",
+        }
+    }
+}
+
+/// Where an upload is in its processing.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct ProcessingJob {
+    pub lettering_id: Uuid,
+    pub virus_scan: String,
+    pub thumbnails: String,
+    pub ml: String,
+    /// Why ML last failed, while it is retrying or once it has failed.
+    pub ml_error: Option<String>,
+    /// Whether every stage has finished, one way or another.
+    pub complete: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The `PROCESSING` WebSocket event for one stage change.
+pub fn event(lettering_id: Uuid, stage: Stage, status: StageStatus) -> String {
+    serde_json::json!({
+        "type": "PROCESSING",
+        "id": lettering_id,
+        "stage": stage.as_str(),
+        "status": status.as_str(),
+    })
+    .to_string()
+}
+
+/// Records an upload whose scan and thumbnails finished during the request,
+/// with ML at `ml`.
+pub async fn create(db: &PgPool, lettering_id: Uuid, ml: StageStatus) -> sqlx::Result<()> {
+    sqlx::query(
+        "INSERT INTO processing_jobs (lettering_id, virus_scan, thumbnails, ml)
+         VALUES ($1, 'DONE', 'DONE', $2)
+         ON CONFLICT (lettering_id) DO UPDATE SET ml = EXCLUDED.ml, updated_at = NOW()",
+    )
+    .bind(lettering_id)
+    .bind(ml.as_str())
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Moves `stage` of each of `lettering_ids` to `status` and broadcasts the
+/// change. `error` is kept for ML; it is cleared when `None`. Letterings
+/// without a row, such as those uploaded before stages were tracked, are
+/// left alone.
+pub async fn set_stage(
+    db: &PgPool,
+    broadcaster: &broadcast::Sender<String>,
+    lettering_ids: &[Uuid],
+    stage: Stage,
+    status: StageStatus,
+    error: Option<&str>,
+) -> sqlx::Result<()> {
+    if lettering_ids.is_empty() {
+        return Ok(());
+    }
+    let error_column = match stage {
+        Stage::Ml => ", ml_error = $3",
+        _ => "",
+    };
+    let sql = format!(
+        "UPDATE processing_jobs SET {} = $2{}, updated_at = NOW()
+         WHERE lettering_id = ANY($1)
+         RETURNING lettering_id",
+        stage.as_str(),
+        error_column
+    );
+    let mut query = sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(lettering_ids)
+        .bind(status.as_str());
+    if stage == Stage::Ml {
+        query = query.bind(error);
+    }
+    let updated = query.fetch_all(db).await?;
+    for id in updated {
+        // Err only means nobody is connected.
+        let _ = broadcaster.send(event(id, stage, status));
+    }
+    Ok(())
+}
+
+pub async fn find(db: &PgPool, lettering_id: Uuid) -> sqlx::Result<Option<ProcessingJob>> {
+    sqlx::query_as(
+        "SELECT lettering_id, virus_scan, thumbnails, ml, ml_error,
+                (virus_scan IN ('DONE', 'FAILED', 'SKIPPED')
+                 AND thumbnails IN ('DONE', 'FAILED', 'SKIPPED')
+                 AND ml IN ('DONE', 'FAILED', 'SKIPPED')) AS complete,
+                created_at, updated_at
+         FROM processing_jobs
+         WHERE lettering_id = $1",
+    )
+    .bind(lettering_id)
+    .fetch_optional(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_name_the_stage_and_status() {
+        let id = Uuid::nil();
+        let event: serde_json::Value =
+            serde_json::from_str(&event(id, Stage::Ml, StageStatus::Retrying)).unwrap();
+        assert_eq!(event["type"], "PROCESSING");
+        assert_eq!(event["id"], id.to_string());
+        assert_eq!(event["stage"], "ml");
+        assert_eq!(event["status"], "RETRYING");
+    }
+}
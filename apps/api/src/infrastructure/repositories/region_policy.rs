@@ -0,0 +1,113 @@
+//! Region policy enforcement for public lettering reads.
+//!
+//! A lettering is public when it is approved and its country's policy allows
+//! discovery; countries without a policy do. Countries with discoverability
+//! off are aggregate-only: their letterings are counted in region aggregates
+//! but never listed or opened. Every public read uses one of the guards below,
+//! so the rule has a single definition:
+//!
+//! - list queries over `letterings l` with [`PUBLIC_LETTERING_JOINS`] add
+//!   [`push_public_filter`] (query builders) or [`public_filter_sql`]
+//!   (positional parameters), which include the age gate;
+//! - single-table lookups call the `city_discoverable(city_id)` SQL function;
+//! - handlers opening one lettering by id ask [`is_publicly_viewable`].
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use super::age_gate::{age_gate_sql, push_age_gate};
+
+/// Joins `letterings l` to its city `c` and the city's region policy `rp`.
+pub const PUBLIC_LETTERING_JOINS: &str = "JOIN cities c ON c.id = l.city_id
+     LEFT JOIN region_policies rp ON rp.country_code = c.country_code";
+
+/// Letterings that may be listed or opened, over `l` and `rp`.
+pub const PUBLICLY_LISTED: &str =
+    "l.status = 'APPROVED' AND COALESCE(rp.discoverability_enabled, true)";
+
+/// Adds the public condition and age gate to a query over `letterings l`
+/// with [`PUBLIC_LETTERING_JOINS`]. The caller pushes `WHERE` or `AND` first.
+pub fn push_public_filter(qb: &mut QueryBuilder<'_, Postgres>, age_ack: bool) {
+    qb.push(PUBLICLY_LISTED);
+    push_age_gate(qb, age_ack);
+}
+
+/// [`push_public_filter`] for a query with positional parameters, with the
+/// client's age acknowledgment bound at `$age_ack_param`.
+pub fn public_filter_sql(age_ack_param: usize) -> String {
+    format!("{} {}", PUBLICLY_LISTED, age_gate_sql(age_ack_param))
+}
+
+/// Whether lettering `id` may be shown to anyone other than its owner.
+pub async fn is_publicly_viewable(db: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let viewable: Option<bool> = sqlx::query_scalar(
+        "SELECT status = 'APPROVED' AND city_discoverable(city_id) FROM letterings WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+    Ok(viewable.unwrap_or(false))
+}
+
+/// The policy in force for one country.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct EffectivePolicy {
+    pub uploads_enabled: bool,
+    pub comments_enabled: bool,
+    pub discoverability_enabled: bool,
+    pub age_gate_enabled: bool,
+    pub auto_moderation_level: String,
+}
+
+/// What applies to countries without a policy.
+impl Default for EffectivePolicy {
+    fn default() -> Self {
+        Self {
+            uploads_enabled: true,
+            comments_enabled: true,
+            discoverability_enabled: true,
+            age_gate_enabled: false,
+            auto_moderation_level: "standard".to_string(),
+        }
+    }
+}
+
+/// The policy of `country_code`, or the default when it has none.
+pub async fn effective_policy(
+    db: &PgPool,
+    country_code: &str,
+) -> Result<EffectivePolicy, sqlx::Error> {
+    let policy = sqlx::query_as(
+        "SELECT uploads_enabled, comments_enabled, discoverability_enabled, age_gate_enabled,
+                auto_moderation_level
+         FROM region_policies WHERE country_code = $1",
+    )
+    .bind(country_code)
+    .fetch_optional(db)
+    .await?;
+    Ok(policy.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_and_positional_filters_agree() {
+        let mut qb = QueryBuilder::<Postgres>::new("SELECT l.id FROM letterings l WHERE ");
+        push_public_filter(&mut qb, false);
+        let built = qb.sql().replace("$1", "$4");
+
+        assert_eq!(
+            built,
+            format!(
+                "SELECT l.id FROM letterings l WHERE {}",
+                public_filter_sql(4)
+            )
+        );
+        assert!(built.contains("rp.discoverability_enabled"));
+        assert!(built.contains("l.status = 'APPROVED'"));
+        assert!(built.contains("rp.age_gate_enabled"));
+    }
+}
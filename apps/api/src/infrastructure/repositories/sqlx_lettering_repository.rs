@@ -1,7 +1,14 @@
+use super::region_policy::{PUBLIC_LETTERING_JOINS, public_filter_sql};
+use crate::config::Config;
 use crate::domain::lettering::{entity::*, errors::DomainError, repository::LetteringRepository};
+use crate::infrastructure::cache::{
+    invalidation::{CacheInvalidator, LetteringChange, missing_lettering_key},
+    redis_cache::RedisCache,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use sqlx::{FromRow, PgPool, types::ipnetwork::IpNetwork};
+use std::sync::Arc;
 use tracing::{error, info, debug, instrument};
 use uuid::Uuid;
 
@@ -34,6 +41,13 @@ struct LetteringRow {
     ml_color_palette: Option<serde_json::Value>,
 }
 
+#[derive(FromRow)]
+struct SimilarLetteringRow {
+    #[sqlx(flatten)]
+    lettering: LetteringRow,
+    similarity: f32,
+}
+
 impl From<LetteringRow> for Lettering {
     fn from(r: LetteringRow) -> Self {
         let coords = r
@@ -89,8 +103,53 @@ impl From<LetteringRow> for Lettering {
     }
 }
 
+/// Weights of the terms in the search score; see
+/// [`SqlxLetteringRepository::search_with_locale`] for the formula.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchRankingWeights {
+    pub text: f64,
+    pub likes: f64,
+    pub recency: f64,
+    pub recency_half_life_days: f64,
+    pub proximity: f64,
+    pub proximity_scale_km: f64,
+}
+
+impl Default for SearchRankingWeights {
+    /// Text relevance with a light popularity nudge.
+    fn default() -> Self {
+        Self {
+            text: 1.0,
+            likes: 0.05,
+            recency: 0.0,
+            recency_half_life_days: 180.0,
+            proximity: 0.0,
+            proximity_scale_km: 5.0,
+        }
+    }
+}
+
+impl From<&Config> for SearchRankingWeights {
+    /// Negative weights are treated as 0; decay lengths are kept positive.
+    fn from(config: &Config) -> Self {
+        Self {
+            text: config.search_weight_text.max(0.0),
+            likes: config.search_weight_likes.max(0.0),
+            recency: config.search_weight_recency.max(0.0),
+            recency_half_life_days: config.search_recency_half_life_days.max(0.1),
+            proximity: config.search_weight_proximity.max(0.0),
+            proximity_scale_km: config.search_proximity_scale_km.max(0.01),
+        }
+    }
+}
+
 pub struct SqlxLetteringRepository {
     pub pool: PgPool,
+    pub ranking: SearchRankingWeights,
+    /// Told about every lettering this repository writes.
+    invalidator: CacheInvalidator,
+    /// Where ids not found are remembered, and for how many seconds.
+    missing: Option<(Arc<RedisCache>, u64)>,
 }
 impl SqlxLetteringRepository {
     /// Creates a new instance of the repository with the provided database pool.
@@ -99,29 +158,109 @@ impl SqlxLetteringRepository {
     /// * `pool` - PostgreSQL connection pool for database operations
     pub fn new(pool: PgPool) -> Self {
         info!("Initializing SqlxLetteringRepository with connection pool");
-        Self { pool }
+        Self {
+            pool,
+            ranking: SearchRankingWeights::default(),
+            invalidator: CacheInvalidator::disabled(),
+            missing: None,
+        }
+    }
+
+    /// Rank search results with `ranking` instead of the defaults.
+    pub fn with_ranking(mut self, ranking: SearchRankingWeights) -> Self {
+        self.ranking = ranking;
+        self
+    }
+
+    /// Drop cached reads of every lettering created, updated or deleted here.
+    pub fn with_invalidator(mut self, invalidator: CacheInvalidator) -> Self {
+        self.invalidator = invalidator;
+        self
+    }
+
+    /// Remember ids `find_by_id` did not find in `cache` for `ttl_seconds`,
+    /// so lookups of deleted letterings skip the database. Creating the
+    /// lettering drops the mark through the invalidator. 0 remembers
+    /// nothing.
+    pub fn with_negative_cache(mut self, cache: Arc<RedisCache>, ttl_seconds: u64) -> Self {
+        self.missing = (ttl_seconds > 0).then_some((cache, ttl_seconds));
+        self
+    }
+
+    /// Whether `id` was recently looked up and not found. Redis failures
+    /// count as not known to be missing.
+    async fn known_missing(&self, id: Uuid) -> bool {
+        let Some((cache, _)) = &self.missing else {
+            return false;
+        };
+        match cache.get::<bool>(&missing_lettering_key(id)).await {
+            Ok(marked) => marked.unwrap_or(false),
+            Err(e) => {
+                debug!("Negative cache lookup failed for lettering {}: {}", id, e);
+                false
+            }
+        }
+    }
+
+    async fn mark_missing(&self, id: Uuid) {
+        if let Some((cache, ttl)) = &self.missing
+            && let Err(e) = cache.set(&missing_lettering_key(id), &true, *ttl).await
+        {
+            debug!("Failed to mark lettering {} missing: {}", id, e);
+        }
+    }
+
+    /// Where letterings changed outside this repository are reported, so
+    /// their cached reads go too.
+    pub fn invalidator(&self) -> &CacheInvalidator {
+        &self.invalidator
     }
 
-    fn ts_config_for_locale(locale: Option<&str>) -> &'static str {
+    /// Text search config and the tsvector column indexed with it for a
+    /// locale. Scripts we archive have their own column; anything else is
+    /// matched unstemmed against the English column.
+    fn ts_config_for_locale(locale: Option<&str>) -> (&'static str, &'static str) {
         let normalized = locale.unwrap_or("en").trim().to_ascii_lowercase();
+        let language = normalized.split(['-', '_']).next().unwrap_or_default();
 
-        if normalized.starts_with("en") {
-            "english"
-        } else {
-            "simple"
+        match language {
+            "en" => ("english", "detected_text_tsv"),
+            "hi" => ("hindi", "detected_text_tsv_hi"),
+            "kn" => ("kannada", "detected_text_tsv_kn"),
+            "ta" => ("tamil", "detected_text_tsv_ta"),
+            "bn" => ("bengali", "detected_text_tsv_bn"),
+            "ar" => ("arabic", "detected_text_tsv_ar"),
+            _ => ("simple", "detected_text_tsv"),
         }
     }
 
     /// Performs locale-aware search across lettering entities.
     ///
     /// This method combines full-text search using PostgreSQL's text search capabilities
-    /// with fuzzy matching on contributor tags and descriptions. Results are ranked by
-    /// relevance and filtered by approval status.
+    /// with substring matching on contributor tags and descriptions and `pg_trgm` word
+    /// similarity on detected text, contributor tags and city names, so misspellings
+    /// still match. Results are filtered by approval status and ordered by a score
+    /// weighted with [`SearchRankingWeights`] (`w`):
+    ///
+    /// ```text
+    /// score = w.text      * (ts_rank + best trigram similarity)
+    ///       + w.likes     * ln(1 + likes)
+    ///       + w.recency   * 0.5 ^ (age_days / w.recency_half_life_days)
+    ///       + w.proximity * e ^ (-distance_km / w.proximity_scale_km)
+    /// ```
+    ///
+    /// Recency and proximity each lie in 0-1; `ln(1 + likes)` grows slowly, so
+    /// with the default 0.05 a lettering with 100 likes gains about 0.23. The
+    /// proximity term is 0 without `near`. Ties fall back to likes, then newest
+    /// first.
     ///
     /// # Arguments
     /// * `query` - Search term or phrase
     /// * `locale` - Optional locale for language-specific search configuration
     /// * `limit` - Maximum number of results to return (clamped between 1-100)
+    /// * `age_ack` - Whether the client has shown its age gate; otherwise age-restricted
+    ///   letterings are left out in regions that gate them
+    /// * `near` - The searcher's `(lat, lng)`, for the proximity term
     ///
     /// # Returns
     /// Vector of matching lettering entities ordered by relevance
@@ -129,54 +268,86 @@ impl SqlxLetteringRepository {
     /// # Errors
     /// Returns `DomainError::InfrastructureError` for database connectivity issues
     /// or query execution failures
-    #[instrument(skip(self), fields(query_len = query.len(), limit = limit))]
+    #[instrument(skip(self), fields(query_len = query.len(), limit = limit, age_ack = age_ack))]
     pub async fn search_with_locale(
         &self,
         query: &str,
         locale: Option<&str>,
         limit: i64,
+        age_ack: bool,
+        near: Option<(f64, f64)>,
     ) -> Result<Vec<Lettering>, DomainError> {
         debug!("Starting search with query: '{}', locale: {:?}", query, locale);
 
-        let ts_config = Self::ts_config_for_locale(locale);
+        let (ts_config, tsv_column) = Self::ts_config_for_locale(locale);
         let like = format!("%{}%", query);
         let safe_limit = limit.clamp(1, 100);
 
         debug!("Using text search config: {}, safe_limit: {}", ts_config, safe_limit);
 
-        let rows = sqlx::query_as::<_, LetteringRow>(
-            r#"SELECT id, city_id, contributor_tag, image_url, thumbnail_small, thumbnail_medium, thumbnail_large,
-                      pin_code, status, created_at, updated_at, likes_count, comments_count,
-                      detected_text, description, image_hash, report_count, report_reasons, cultural_context,
-                      ml_style, ml_script, ml_confidence, ml_color_palette,
-                      ST_AsText(location) AS location_wkt, uploaded_by_ip
-               FROM letterings
-               WHERE status = 'APPROVED'
-                 AND COALESCE((
-                     SELECT rp.discoverability_enabled
-                     FROM cities c
-                     LEFT JOIN region_policies rp ON rp.country_code = c.country_code
-                     WHERE c.id = letterings.city_id
-                 ), true)
+        // `tsv_column` comes from the fixed list above, never from the request.
+        let sql = format!(
+            r#"SELECT l.id, l.city_id, l.contributor_tag, l.image_url, l.thumbnail_small, l.thumbnail_medium, l.thumbnail_large,
+                      l.pin_code, l.status, l.created_at, l.updated_at, l.likes_count, l.comments_count,
+                      l.detected_text, l.description, l.image_hash, l.report_count, l.report_reasons, l.cultural_context,
+                      l.ml_style, l.ml_script, l.ml_confidence, l.ml_color_palette,
+                      ST_AsText(l.location) AS location_wkt, l.uploaded_by_ip
+               FROM letterings l
+               {joins}
+               CROSS JOIN LATERAL (
+                   SELECT websearch_to_tsquery($1::regconfig, $2) AS tsq,
+                          NOT ('detected_text' = ANY(l.ml_low_confidence_fields)) AS text_trusted
+               ) q
+               WHERE {public}
                  AND (
-                     detected_text_tsv @@ websearch_to_tsquery($1::regconfig, $2)
-                     OR detected_text ILIKE $3
-                     OR description ILIKE $3
-                     OR contributor_tag ILIKE $3
+                     l.{tsv} @@ q.tsq
+                     OR (l.detected_text ILIKE $3 AND q.text_trusted)
+                     OR l.description ILIKE $3
+                     OR l.contributor_tag ILIKE $3
+                     OR ($2 <% l.detected_text AND q.text_trusted)
+                     OR $2 <% l.contributor_tag
+                     OR $2 <% c.name
                  )
-               ORDER BY likes_count DESC, created_at DESC
+               -- Full-text rank plus the best trigram similarity, so "Bangalor"
+               -- still ranks Bangalore letterings.
+               ORDER BY $8 * (COALESCE(ts_rank(l.{tsv}, q.tsq), 0)
+                              + GREATEST(
+                                  CASE WHEN q.text_trusted THEN word_similarity($2, COALESCE(l.detected_text, '')) ELSE 0 END,
+                                  word_similarity($2, l.contributor_tag),
+                                  COALESCE(word_similarity($2, c.name), 0)
+                                ))
+                        + $9 * ln(1 + GREATEST(l.likes_count, 0))
+                        + $10 * power(0.5, GREATEST(EXTRACT(EPOCH FROM NOW() - l.created_at), 0) / 86400.0 / $11)
+                        + CASE WHEN $6::float8 IS NULL OR $7::float8 IS NULL THEN 0
+                               ELSE $12 * exp(-ST_Distance(l.location, ST_SetSRID(ST_MakePoint($7, $6), 4326)::geography) / 1000.0 / $13)
+                          END DESC,
+                        l.likes_count DESC, l.created_at DESC
                LIMIT $4"#,
-        )
-        .bind(ts_config)
-        .bind(query)
-        .bind(like)
-        .bind(safe_limit)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| {
-            error!("Search query failed: {}", e);
-            DomainError::InfrastructureError(format!("Search operation failed: {}", e))
-        })?;
+            tsv = tsv_column,
+            joins = PUBLIC_LETTERING_JOINS,
+            public = public_filter_sql(5),
+        );
+
+        let rows = sqlx::query_as::<_, LetteringRow>(&sql)
+            .bind(ts_config)
+            .bind(query)
+            .bind(like)
+            .bind(safe_limit)
+            .bind(age_ack)
+            .bind(near.map(|(lat, _)| lat))
+            .bind(near.map(|(_, lng)| lng))
+            .bind(self.ranking.text)
+            .bind(self.ranking.likes)
+            .bind(self.ranking.recency)
+            .bind(self.ranking.recency_half_life_days)
+            .bind(self.ranking.proximity)
+            .bind(self.ranking.proximity_scale_km)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("Search query failed: {}", e);
+                DomainError::InfrastructureError(format!("Search operation failed: {}", e))
+            })?;
 
         let result_count = rows.len();
         debug!("Search completed successfully, found {} results", result_count);
@@ -221,6 +392,7 @@ impl LetteringRepository for SqlxLetteringRepository {
         })?;
 
         info!("Successfully created lettering {} by {}", l.id, l.contributor_tag);
+        self.invalidator.lettering_changed(&LetteringChange::from(l)).await;
         Ok(l.clone())
     }
 
@@ -250,9 +422,15 @@ impl LetteringRepository for SqlxLetteringRepository {
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<Lettering>, DomainError> {
+        if self.known_missing(id).await {
+            return Ok(None);
+        }
         let row = sqlx::query_as!(LetteringRow,
             r#"SELECT id, city_id, contributor_tag, image_url, thumbnail_small, thumbnail_medium, thumbnail_large, pin_code, status, created_at, updated_at, likes_count, comments_count, detected_text, description, image_hash, report_count, report_reasons, cultural_context, ml_style, ml_script, ml_confidence, ml_color_palette, ST_AsText(location) as "location_wkt!", uploaded_by_ip as "uploaded_by_ip: _" FROM letterings WHERE id = $1"#, id
         ).fetch_optional(&self.pool).await.map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if row.is_none() {
+            self.mark_missing(id).await;
+        }
         Ok(row.map(Lettering::from))
     }
 
@@ -264,13 +442,22 @@ impl LetteringRepository for SqlxLetteringRepository {
     }
 
     async fn search(&self, q: &str) -> Result<Vec<Lettering>, DomainError> {
-        self.search_with_locale(q, Some("en"), 50).await
+        self.search_with_locale(q, Some("en"), 50, false, None).await
     }
 
-    async fn count_by_contributor_today(&self, tag: &str) -> Result<i64, DomainError> {
-        let count = sqlx::query_scalar!("SELECT COUNT(*) FROM letterings WHERE contributor_tag = $1 AND created_at > CURRENT_DATE", tag)
-            .fetch_one(&self.pool).await.map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
-        Ok(count.unwrap_or(0))
+    async fn count_by_contributor_since(
+        &self,
+        tag: &str,
+        since: DateTime<Utc>,
+    ) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM letterings WHERE contributor_tag = $1 AND created_at >= $2",
+        )
+        .bind(tag)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))
     }
 
     async fn find_by_contributor(
@@ -279,22 +466,37 @@ impl LetteringRepository for SqlxLetteringRepository {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Lettering>, DomainError> {
-        let rows = sqlx::query_as!(LetteringRow,
-            r#"SELECT id, city_id, contributor_tag, image_url, thumbnail_small, thumbnail_medium, thumbnail_large, pin_code, status, created_at, updated_at, likes_count, comments_count, detected_text, description, image_hash, report_count, report_reasons, cultural_context, ml_style, ml_script, ml_confidence, ml_color_palette, ST_AsText(location) as "location_wkt!", uploaded_by_ip as "uploaded_by_ip: _" FROM letterings WHERE contributor_tag = $1 AND status = 'APPROVED' ORDER BY created_at DESC LIMIT $2 OFFSET $3"#,
-            tag, limit, offset
-        ).fetch_all(&self.pool).await.map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        // Letterings in aggregate-only regions are left out of item lists.
+        let rows = sqlx::query_as::<_, LetteringRow>(
+            r#"SELECT id, city_id, contributor_tag, image_url, thumbnail_small, thumbnail_medium, thumbnail_large,
+                      pin_code, status, created_at, updated_at, likes_count, comments_count,
+                      detected_text, description, image_hash, report_count, report_reasons, cultural_context,
+                      ml_style, ml_script, ml_confidence, ml_color_palette,
+                      ST_AsText(location) AS location_wkt, uploaded_by_ip
+               FROM letterings
+               WHERE contributor_tag = $1 AND status = 'APPROVED' AND city_discoverable(city_id)
+               ORDER BY created_at DESC
+               LIMIT $2 OFFSET $3"#,
+        )
+        .bind(tag)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
         Ok(rows.into_iter().map(Lettering::from).collect())
     }
 
     async fn count_by_contributor(&self, tag: &str) -> Result<i64, DomainError> {
-        let count = sqlx::query_scalar!(
-            "SELECT COUNT(*) FROM letterings WHERE contributor_tag = $1 AND status = 'APPROVED'",
-            tag
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM letterings
+             WHERE contributor_tag = $1 AND status = 'APPROVED' AND city_discoverable(city_id)",
         )
+        .bind(tag)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
-        Ok(count.unwrap_or(0))
+        Ok(count)
     }
 
     async fn find_by_city(
@@ -310,6 +512,47 @@ impl LetteringRepository for SqlxLetteringRepository {
         Ok(rows.into_iter().map(Lettering::from).collect())
     }
 
+    /// Nearest neighbours by cosine distance over the HNSW index. Only
+    /// embeddings from the same descriptor version are compared.
+    #[instrument(skip(self), fields(lettering_id = %id))]
+    async fn find_similar(
+        &self,
+        id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<(Lettering, f32)>, DomainError> {
+        let rows = sqlx::query_as::<_, SimilarLetteringRow>(
+            // The source vector is a scalar subquery rather than a join so
+            // the planner evaluates it once and can drive the HNSW index.
+            r#"SELECT id, city_id, contributor_tag, image_url, thumbnail_small, thumbnail_medium, thumbnail_large,
+                      pin_code, status, created_at, updated_at, likes_count, comments_count,
+                      detected_text, description, image_hash, report_count, report_reasons, cultural_context,
+                      ml_style, ml_script, ml_confidence, ml_color_palette,
+                      ST_AsText(location) AS location_wkt, uploaded_by_ip,
+                      (1 - (image_embedding <=> (SELECT image_embedding FROM letterings WHERE id = $1)))::real AS similarity
+               FROM letterings
+               WHERE id != $1
+                 AND status = 'APPROVED'
+                 AND city_discoverable(city_id)
+                 AND image_embedding_version = (SELECT image_embedding_version FROM letterings WHERE id = $1)
+               ORDER BY image_embedding <=> (SELECT image_embedding FROM letterings WHERE id = $1)
+               LIMIT $2"#,
+        )
+        .bind(id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to find letterings similar to {}: {}", id, e);
+            DomainError::InfrastructureError(format!("Failed to find similar letterings: {}", e))
+        })?;
+
+        debug!("Found {} visually similar letterings", rows.len());
+        Ok(rows
+            .into_iter()
+            .map(|r| (Lettering::from(r.lettering), r.similarity))
+            .collect())
+    }
+
     async fn update(&self, l: &Lettering) -> Result<Lettering, DomainError> {
         if l.location.coordinates.len() < 2 {
             return Err(DomainError::ValidationError(
@@ -391,13 +634,18 @@ impl LetteringRepository for SqlxLetteringRepository {
         .bind(report_reasons)
         .bind(l.likes_count)
         .bind(l.comments_count)
-        .bind(l.uploaded_by_ip.clone())
+        .bind(l.uploaded_by_ip)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
 
-        let updated = row.ok_or_else(|| DomainError::NotFound("Lettering not found".into()))?;
-        Ok(updated.into())
+        let updated: Lettering = row
+            .ok_or_else(|| DomainError::NotFound("Lettering not found".into()))?
+            .into();
+        self.invalidator
+            .lettering_changed(&LetteringChange::from(&updated))
+            .await;
+        Ok(updated)
     }
     /// Permanently deletes a lettering entity from the database.
     ///
@@ -411,20 +659,69 @@ impl LetteringRepository for SqlxLetteringRepository {
     /// Returns `DomainError::InfrastructureError` if the deletion fails
     #[instrument(skip(self), fields(lettering_id = %id))]
     async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
-        let result = sqlx::query!("DELETE FROM letterings WHERE id = $1", id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| {
-                error!("Failed to delete lettering {}: {}", id, e);
-                DomainError::InfrastructureError(format!("Failed to delete lettering: {}", e))
-            })?;
+        let deleted = sqlx::query_as::<_, (Uuid, String)>(
+            "DELETE FROM letterings WHERE id = $1 RETURNING city_id, contributor_tag",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete lettering {}: {}", id, e);
+            DomainError::InfrastructureError(format!("Failed to delete lettering: {}", e))
+        })?;
 
-        if result.rows_affected() == 0 {
-            debug!("No lettering found with id {} for deletion", id);
-        } else {
-            info!("Successfully deleted lettering {}", id);
+        match deleted {
+            None => debug!("No lettering found with id {} for deletion", id),
+            Some((city_id, contributor_tag)) => {
+                info!("Successfully deleted lettering {}", id);
+                self.invalidator
+                    .lettering_changed(&LetteringChange {
+                        id,
+                        city_id: Some(city_id),
+                        contributor_tag: Some(contributor_tag),
+                    })
+                    .await;
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{SearchRankingWeights, SqlxLetteringRepository};
+
+    #[test]
+    fn routes_locales_to_their_search_config() {
+        assert_eq!(
+            SqlxLetteringRepository::ts_config_for_locale(None),
+            ("english", "detected_text_tsv")
+        );
+        assert_eq!(
+            SqlxLetteringRepository::ts_config_for_locale(Some("kn-IN")),
+            ("kannada", "detected_text_tsv_kn")
+        );
+        assert_eq!(
+            SqlxLetteringRepository::ts_config_for_locale(Some(" HI ")),
+            ("hindi", "detected_text_tsv_hi")
+        );
+        assert_eq!(
+            SqlxLetteringRepository::ts_config_for_locale(Some("ar_EG")),
+            ("arabic", "detected_text_tsv_ar")
+        );
+        assert_eq!(
+            SqlxLetteringRepository::ts_config_for_locale(Some("fr")),
+            ("simple", "detected_text_tsv")
+        );
+    }
+
+    #[test]
+    fn default_ranking_is_relevance_then_likes() {
+        let ranking = SearchRankingWeights::default();
+        assert_eq!(ranking.text, 1.0);
+        assert_eq!(ranking.likes, 0.05);
+        assert_eq!(ranking.recency, 0.0);
+        assert_eq!(ranking.proximity, 0.0);
+    }
+}
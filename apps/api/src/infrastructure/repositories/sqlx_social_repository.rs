@@ -1,12 +1,25 @@
 use crate::domain::{
     lettering::errors::DomainError,
     social::{
-        comment::{Comment, CommentModerationInput},
+        activity::{ActivityCursor, ActivityItem, ActivityPage},
+        bookmark::Bookmark,
+        collection::{
+            Collection, CollectionDraft, CollectionItem, MAX_COLLECTION_ITEMS, apply_order,
+            share_slug,
+        },
+        comment::{
+            Comment, CommentCursor, CommentListQuery, CommentModerationInput, CommentPage,
+            CommentSort, CommentView,
+        },
         repository::SocialRepository,
     },
 };
+use crate::infrastructure::repositories::region_policy::{
+    PUBLIC_LETTERING_JOINS, push_public_filter,
+};
 use async_trait::async_trait;
-use sqlx::{PgPool, types::ipnetwork::IpNetwork};
+use chrono::DateTime;
+use sqlx::{PgPool, Postgres, QueryBuilder, types::ipnetwork::IpNetwork};
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -17,6 +30,40 @@ impl SqlxSocialRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
+
+    /// Locks one of `owner_id`'s collections for the rest of `tx`.
+    async fn lock_owned_collection(
+        tx: &mut sqlx::Transaction<'_, Postgres>,
+        id: Uuid,
+        owner_id: Uuid,
+    ) -> Result<(), DomainError> {
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM collections WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        )
+        .bind(id)
+        .bind(owner_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?
+        .ok_or_else(|| DomainError::NotFound("Collection not found".to_string()))?;
+        Ok(())
+    }
+}
+
+/// Collections with their owner's name and count of publicly listed items.
+const COLLECTION_SELECT: &str = "SELECT c.id, c.user_id AS owner_id, c.name, c.description,
+        COALESCE(NULLIF(u.display_name, ''), c.creator_tag) AS creator_name,
+        c.is_public, c.slug,
+        (SELECT COUNT(*) FROM collection_items ci
+         JOIN letterings l ON l.id = ci.lettering_id
+         WHERE ci.collection_id = c.id AND l.status = 'APPROVED'
+           AND city_discoverable(l.city_id))::bigint AS item_count,
+        c.created_at, c.updated_at
+    FROM collections c
+    LEFT JOIN users u ON u.id = c.user_id";
+
+fn slug_suffix() -> String {
+    Uuid::now_v7().simple().to_string()[24..].to_string()
 }
 
 #[async_trait]
@@ -92,6 +139,7 @@ impl SocialRepository for SqlxSocialRepository {
     async fn add_comment(
         &self,
         lettering_id: Uuid,
+        parent_id: Option<Uuid>,
         user_id: Uuid,
         content: String,
         user_ip: Option<&str>,
@@ -103,11 +151,11 @@ impl SocialRepository for SqlxSocialRepository {
             "INSERT INTO comments (
                 id, lettering_id, user_id, content, user_ip, status,
                 moderation_score, moderation_flags, auto_flagged, needs_review, review_priority,
-                moderated_at, moderated_by, moderation_reason
+                moderated_at, moderated_by, moderation_reason, parent_id
             ) VALUES (
                 $1, $2, $3, $4, $5, $6,
                 $7, $8::jsonb, $9, $10, $11,
-                CASE WHEN $6 = 'HIDDEN' THEN NOW() ELSE NULL END, $12, $13
+                CASE WHEN $6 = 'HIDDEN' THEN NOW() ELSE NULL END, $12, $13, $14
             )",
         )
         .bind(id)
@@ -123,6 +171,7 @@ impl SocialRepository for SqlxSocialRepository {
         .bind(moderation.review_priority)
         .bind(moderation.moderated_by)
         .bind(moderation.moderation_reason)
+        .bind(parent_id)
         .execute(&self.pool)
         .await
         .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
@@ -136,7 +185,7 @@ impl SocialRepository for SqlxSocialRepository {
         }
 
         let row = sqlx::query_as::<_, Comment>(
-            "SELECT c.id, c.lettering_id, c.content, c.user_id, \
+            "SELECT c.id, c.lettering_id, c.parent_id, c.content, c.user_id, \
                     COALESCE(NULLIF(u.display_name, ''), u.email, 'Anonymous') as commenter_name, \
                     c.status, c.moderation_score, \
                     COALESCE(ARRAY(SELECT jsonb_array_elements_text(c.moderation_flags)), ARRAY[]::text[]) as moderation_flags, \
@@ -154,24 +203,157 @@ impl SocialRepository for SqlxSocialRepository {
         Ok(row)
     }
 
-    async fn get_comments(&self, lettering_id: Uuid) -> Result<Vec<Comment>, DomainError> {
-        let rows = sqlx::query_as::<_, Comment>(
-            "SELECT c.id, c.lettering_id, c.content, c.user_id, \
-                    COALESCE(NULLIF(u.display_name, ''), u.email, 'Anonymous') as commenter_name, \
-                    c.status, c.moderation_score, \
-                    COALESCE(ARRAY(SELECT jsonb_array_elements_text(c.moderation_flags)), ARRAY[]::text[]) as moderation_flags, \
-                    c.auto_flagged, c.needs_review, c.review_priority, \
-                    c.user_ip, c.moderated_at, c.moderated_by, c.moderation_reason, c.created_at, c.updated_at \
-             FROM comments c \
-             LEFT JOIN users u ON u.id = c.user_id \
-             WHERE c.lettering_id = $1 AND c.status = 'VISIBLE' \
-             ORDER BY c.created_at DESC",
+    async fn list_comments(
+        &self,
+        lettering_id: Uuid,
+        query: &CommentListQuery,
+    ) -> Result<CommentPage, DomainError> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT c.id, c.lettering_id, c.parent_id,
+                    CASE WHEN v.visible THEN c.content END AS content,
+                    CASE WHEN v.visible
+                         THEN COALESCE(NULLIF(u.display_name, ''), u.email, 'Anonymous')
+                    END AS commenter_name,
+                    c.reactions_count, r.reply_count, NOT v.visible AS tombstone, c.created_at
+             FROM comments c
+             LEFT JOIN users u ON u.id = c.user_id
+             CROSS JOIN LATERAL (
+                 SELECT c.status = 'VISIBLE' AND c.deleted_at IS NULL AS visible
+             ) v
+             CROSS JOIN LATERAL (
+                 SELECT COUNT(*)::int AS reply_count
+                 FROM comments rc
+                 WHERE rc.parent_id = c.id AND rc.status = 'VISIBLE' AND rc.deleted_at IS NULL
+             ) r
+             WHERE c.lettering_id = ",
+        );
+        qb.push_bind(lettering_id);
+        match query.parent_id {
+            Some(parent_id) => {
+                qb.push(" AND c.parent_id = ").push_bind(parent_id);
+            }
+            None => {
+                qb.push(" AND c.parent_id IS NULL");
+            }
+        }
+        qb.push(" AND (v.visible OR r.reply_count > 0)");
+
+        if let Some(cursor) = query.cursor {
+            match query.sort {
+                CommentSort::Newest | CommentSort::Oldest => {
+                    let created_at =
+                        DateTime::from_timestamp_micros(cursor.key).ok_or_else(|| {
+                            DomainError::ValidationError("Invalid cursor".to_string())
+                        })?;
+                    let op = if query.sort == CommentSort::Newest {
+                        "<"
+                    } else {
+                        ">"
+                    };
+                    qb.push(format!(" AND (c.created_at, c.id) {} (", op))
+                        .push_bind(created_at)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push(")");
+                }
+                CommentSort::Top => {
+                    let reactions = i32::try_from(cursor.key)
+                        .map_err(|_| DomainError::ValidationError("Invalid cursor".to_string()))?;
+                    qb.push(" AND (c.reactions_count, c.id) < (")
+                        .push_bind(reactions)
+                        .push(", ")
+                        .push_bind(cursor.id)
+                        .push(")");
+                }
+            }
+        }
+
+        qb.push(match query.sort {
+            CommentSort::Newest => " ORDER BY c.created_at DESC, c.id DESC",
+            CommentSort::Oldest => " ORDER BY c.created_at ASC, c.id ASC",
+            CommentSort::Top => " ORDER BY c.reactions_count DESC, c.id DESC",
+        });
+        // One extra row tells whether there is a next page.
+        qb.push(" LIMIT ").push_bind(query.limit + 1);
+
+        let mut items: Vec<CommentView> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        let next_cursor = if items.len() as i64 > query.limit {
+            items.truncate(query.limit as usize);
+            items
+                .last()
+                .map(|last| CommentCursor::after(last, query.sort).encode())
+        } else {
+            None
+        };
+        Ok(CommentPage { items, next_cursor })
+    }
+
+    async fn toggle_comment_reaction(
+        &self,
+        comment_id: Uuid,
+        user_id: Uuid,
+        kind: &str,
+    ) -> Result<(bool, i32), DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+
+        // Locks the row so concurrent toggles keep reactions_count exact.
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM comments
+             WHERE id = $1 AND status = 'VISIBLE' AND deleted_at IS NULL
+             FOR UPDATE",
         )
-        .bind(lettering_id)
-        .fetch_all(&self.pool)
+        .bind(comment_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?
+        .ok_or_else(|| DomainError::NotFound("Comment not found".to_string()))?;
+
+        let removed = sqlx::query(
+            "DELETE FROM comment_reactions WHERE comment_id = $1 AND user_id = $2 AND kind = $3",
+        )
+        .bind(comment_id)
+        .bind(user_id)
+        .bind(kind)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?
+        .rows_affected()
+            > 0;
+        if !removed {
+            sqlx::query(
+                "INSERT INTO comment_reactions (comment_id, user_id, kind) VALUES ($1, $2, $3)
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(comment_id)
+            .bind(user_id)
+            .bind(kind)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        }
+
+        let count = sqlx::query_scalar::<_, i32>(
+            "UPDATE comments SET reactions_count = GREATEST(0, reactions_count + $2)
+             WHERE id = $1
+             RETURNING reactions_count",
+        )
+        .bind(comment_id)
+        .bind(if removed { -1 } else { 1 })
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
-        Ok(rows)
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        Ok((!removed, count))
     }
 
     async fn has_liked(&self, lettering_id: Uuid, user_ip: &str) -> Result<bool, DomainError> {
@@ -196,4 +378,423 @@ impl SocialRepository for SqlxSocialRepository {
         .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
         Ok(count)
     }
+
+    async fn add_bookmark(&self, user_id: Uuid, lettering_id: Uuid) -> Result<bool, DomainError> {
+        let listed: Option<bool> = sqlx::query_scalar(
+            "SELECT status = 'APPROVED' AND city_discoverable(city_id) FROM letterings WHERE id = $1",
+        )
+        .bind(lettering_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if !listed.unwrap_or(false) {
+            return Err(DomainError::NotFound("Lettering not found".to_string()));
+        }
+
+        let added = sqlx::query(
+            "INSERT INTO bookmarks (user_id, lettering_id) VALUES ($1, $2)
+             ON CONFLICT (user_id, lettering_id) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(lettering_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        Ok(added.rows_affected() > 0)
+    }
+
+    async fn remove_bookmark(&self, user_id: Uuid, lettering_id: Uuid) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM bookmarks WHERE user_id = $1 AND lettering_id = $2")
+            .bind(user_id)
+            .bind(lettering_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn list_bookmarks(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+        age_ack: bool,
+    ) -> Result<(Vec<Bookmark>, i64), DomainError> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT b.lettering_id, l.image_url, l.thumbnail_small, l.detected_text,
+                    l.contributor_tag, b.created_at AS bookmarked_at
+             FROM bookmarks b
+             JOIN letterings l ON l.id = b.lettering_id ",
+        );
+        qb.push(PUBLIC_LETTERING_JOINS)
+            .push(" WHERE b.user_id = ")
+            .push_bind(user_id)
+            .push(" AND ");
+        push_public_filter(&mut qb, age_ack);
+        qb.push(" ORDER BY b.created_at DESC, b.lettering_id LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let items: Vec<Bookmark> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT COUNT(*) FROM bookmarks b JOIN letterings l ON l.id = b.lettering_id ",
+        );
+        qb.push(PUBLIC_LETTERING_JOINS)
+            .push(" WHERE b.user_id = ")
+            .push_bind(user_id)
+            .push(" AND ");
+        push_public_filter(&mut qb, age_ack);
+        let total: i64 = qb
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+
+        Ok((items, total))
+    }
+
+    async fn list_activity(
+        &self,
+        user_id: Uuid,
+        cursor: Option<ActivityCursor>,
+        limit: i64,
+        age_ack: bool,
+    ) -> Result<ActivityPage, DomainError> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT e.id, e.kind, e.lettering_id, l.thumbnail_small, e.comment_id,
+                    LEFT(rc.content, 140) AS comment_excerpt,
+                    COALESCE(NULLIF(u.display_name, ''),
+                             CASE WHEN e.kind = 'FOLLOWED_UPLOAD' THEN l.contributor_tag END)
+                        AS actor_name,
+                    e.created_at
+             FROM activity_events e
+             JOIN letterings l ON l.id = e.lettering_id
+             LEFT JOIN users u ON u.id = e.actor_user_id
+             LEFT JOIN comments rc ON rc.id = e.comment_id ",
+        );
+        qb.push(PUBLIC_LETTERING_JOINS)
+            .push(" WHERE e.user_id = ")
+            .push_bind(user_id)
+            .push(" AND ");
+        push_public_filter(&mut qb, age_ack);
+        qb.push(" AND (e.comment_id IS NULL OR (rc.status = 'VISIBLE' AND rc.deleted_at IS NULL))");
+        if let Some(cursor) = cursor {
+            let created_at = DateTime::from_timestamp_micros(cursor.created_at_micros)
+                .ok_or_else(|| DomainError::ValidationError("Invalid cursor".to_string()))?;
+            qb.push(" AND (e.created_at, e.id) < (")
+                .push_bind(created_at)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+        // One extra row tells whether there is a next page.
+        qb.push(" ORDER BY e.created_at DESC, e.id DESC LIMIT ")
+            .push_bind(limit + 1);
+
+        let mut items: Vec<ActivityItem> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        let next_cursor = if items.len() as i64 > limit {
+            items.truncate(limit as usize);
+            items
+                .last()
+                .map(|last| ActivityCursor::after(last).encode())
+        } else {
+            None
+        };
+        Ok(ActivityPage { items, next_cursor })
+    }
+
+    async fn create_collection(
+        &self,
+        owner_id: Uuid,
+        draft: &CollectionDraft,
+    ) -> Result<Collection, DomainError> {
+        let id = Uuid::now_v7();
+        let slug = draft
+            .is_public
+            .then(|| share_slug(&draft.name, &slug_suffix()));
+        sqlx::query(
+            "INSERT INTO collections (id, user_id, name, description, is_public, slug)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(id)
+        .bind(owner_id)
+        .bind(&draft.name)
+        .bind(&draft.description)
+        .bind(draft.is_public)
+        .bind(slug)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+
+        self.find_collection(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("Collection not found".to_string()))
+    }
+
+    async fn list_public_collections(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Collection>, DomainError> {
+        sqlx::query_as::<_, Collection>(&format!(
+            "{} WHERE c.is_public ORDER BY c.created_at DESC LIMIT $1 OFFSET $2",
+            COLLECTION_SELECT
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn list_user_collections(&self, owner_id: Uuid) -> Result<Vec<Collection>, DomainError> {
+        sqlx::query_as::<_, Collection>(&format!(
+            "{} WHERE c.user_id = $1 ORDER BY c.updated_at DESC",
+            COLLECTION_SELECT
+        ))
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn find_collection(&self, id: Uuid) -> Result<Option<Collection>, DomainError> {
+        sqlx::query_as::<_, Collection>(&format!("{} WHERE c.id = $1", COLLECTION_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn find_shared_collection(&self, slug: &str) -> Result<Option<Collection>, DomainError> {
+        sqlx::query_as::<_, Collection>(&format!(
+            "{} WHERE c.slug = $1 AND c.is_public",
+            COLLECTION_SELECT
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn update_collection(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        draft: &CollectionDraft,
+    ) -> Result<Collection, DomainError> {
+        let updated = sqlx::query(
+            "UPDATE collections
+             SET name = $3, description = $4, is_public = $5,
+                 slug = CASE WHEN $5 AND slug IS NULL THEN $6 ELSE slug END,
+                 updated_at = NOW()
+             WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(owner_id)
+        .bind(&draft.name)
+        .bind(&draft.description)
+        .bind(draft.is_public)
+        .bind(share_slug(&draft.name, &slug_suffix()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if updated.rows_affected() == 0 {
+            return Err(DomainError::NotFound("Collection not found".to_string()));
+        }
+
+        self.find_collection(id)
+            .await?
+            .ok_or_else(|| DomainError::NotFound("Collection not found".to_string()))
+    }
+
+    async fn delete_collection(&self, id: Uuid, owner_id: Uuid) -> Result<(), DomainError> {
+        let deleted = sqlx::query("DELETE FROM collections WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(owner_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if deleted.rows_affected() == 0 {
+            return Err(DomainError::NotFound("Collection not found".to_string()));
+        }
+        Ok(())
+    }
+
+    async fn list_collection_items(
+        &self,
+        id: Uuid,
+        age_ack: bool,
+    ) -> Result<Vec<CollectionItem>, DomainError> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT ci.lettering_id, ci.position, l.image_url, l.thumbnail_small,
+                    l.detected_text, l.contributor_tag, ci.added_at
+             FROM collection_items ci
+             JOIN letterings l ON l.id = ci.lettering_id ",
+        );
+        qb.push(PUBLIC_LETTERING_JOINS)
+            .push(" WHERE ci.collection_id = ")
+            .push_bind(id)
+            .push(" AND ");
+        push_public_filter(&mut qb, age_ack);
+        qb.push(" ORDER BY ci.position, ci.added_at");
+
+        qb.build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn add_collection_item(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        lettering_id: Uuid,
+    ) -> Result<(), DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        Self::lock_owned_collection(&mut tx, id, owner_id).await?;
+
+        let listed: Option<bool> = sqlx::query_scalar(
+            "SELECT status = 'APPROVED' AND city_discoverable(city_id) FROM letterings WHERE id = $1",
+        )
+        .bind(lettering_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if !listed.unwrap_or(false) {
+            return Err(DomainError::NotFound("Lettering not found".to_string()));
+        }
+
+        let (count, already): (i64, bool) = sqlx::query_as(
+            "SELECT COUNT(*), COALESCE(bool_or(lettering_id = $2), false)
+             FROM collection_items WHERE collection_id = $1",
+        )
+        .bind(id)
+        .bind(lettering_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if already {
+            return Ok(());
+        }
+        if count >= MAX_COLLECTION_ITEMS {
+            return Err(DomainError::ValidationError(format!(
+                "A collection can hold at most {} letterings",
+                MAX_COLLECTION_ITEMS
+            )));
+        }
+
+        sqlx::query(
+            "INSERT INTO collection_items (collection_id, lettering_id, position)
+             SELECT $1, $2, COALESCE(MAX(position) + 1, 0)
+             FROM collection_items WHERE collection_id = $1",
+        )
+        .bind(id)
+        .bind(lettering_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        sqlx::query("UPDATE collections SET updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn remove_collection_item(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        lettering_id: Uuid,
+    ) -> Result<(), DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        Self::lock_owned_collection(&mut tx, id, owner_id).await?;
+
+        let removed = sqlx::query(
+            "DELETE FROM collection_items WHERE collection_id = $1 AND lettering_id = $2",
+        )
+        .bind(id)
+        .bind(lettering_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        if removed.rows_affected() == 0 {
+            return Err(DomainError::NotFound(
+                "Lettering is not in this collection".to_string(),
+            ));
+        }
+        sqlx::query("UPDATE collections SET updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
+
+    async fn reorder_collection(
+        &self,
+        id: Uuid,
+        owner_id: Uuid,
+        lettering_ids: &[Uuid],
+    ) -> Result<(), DomainError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        Self::lock_owned_collection(&mut tx, id, owner_id).await?;
+
+        let current: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT lettering_id FROM collection_items
+             WHERE collection_id = $1
+             ORDER BY position, added_at",
+        )
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        let order = apply_order(&current, lettering_ids).map_err(DomainError::ValidationError)?;
+
+        sqlx::query(
+            "UPDATE collection_items ci
+             SET position = (o.ord - 1)::int
+             FROM unnest($2::uuid[]) WITH ORDINALITY AS o(lettering_id, ord)
+             WHERE ci.collection_id = $1 AND ci.lettering_id = o.lettering_id",
+        )
+        .bind(id)
+        .bind(&order)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        sqlx::query("UPDATE collections SET updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::InfrastructureError(e.to_string()))
+    }
 }
@@ -0,0 +1,521 @@
+//! Mobile app attestation for uploads.
+//!
+//! The iOS and Android apps attach a platform attestation token to uploads so
+//! scripted clients and emulators can be told apart from the real apps:
+//! - iOS sends a DeviceCheck device token, validated with Apple's DeviceCheck
+//!   API; simulators can't produce one. (Full App Attest assertions would
+//!   need CBOR and certificate-chain parsing this crate doesn't carry.)
+//! - Android sends a Play Integrity token, decoded through Google's API; the
+//!   verdict must name our package, come from a Play-recognized build on a
+//!   device that meets device integrity, and be recent.
+//!
+//! - Browsers send a Cloudflare Turnstile token, checked with Turnstile's
+//!   siteverify API.
+//!
+//! Each platform is `off`, `monitor` or `enforce` in config, but the platform
+//! a request names is the client's word, so it only picks which check runs:
+//! every upload is held to the strictest mode configured. A request that
+//! names no app, or an app without an attestation token, needs a Turnstile
+//! token instead.
+
+use crate::config::{AttestationMode, Config};
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Integrity verdicts older than this are refused, so a token can't be
+/// harvested once and replayed indefinitely.
+const MAX_VERDICT_AGE_MS: i64 = 10 * 60 * 1000;
+
+const PLAY_INTEGRITY_SCOPE: &str = "https://www.googleapis.com/auth/playintegrity";
+const GOOGLE_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const TURNSTILE_VERIFY_URL: &str = "https://challenges.cloudflare.com/turnstile/v0/siteverify";
+
+/// Client the request says it comes from, per `X-Client-Platform`. It picks
+/// the check a request gets, never whether it gets one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientPlatform {
+    Ios,
+    Android,
+    Web,
+}
+
+impl ClientPlatform {
+    /// Anything but the two apps counts as web, which is challenged with
+    /// Turnstile.
+    pub fn from_header(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("ios") => Self::Ios,
+            Some("android") => Self::Android,
+            _ => Self::Web,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ios => "ios",
+            Self::Android => "android",
+            Self::Web => "web",
+        }
+    }
+}
+
+/// Result of checking a token the provider could evaluate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttestationOutcome {
+    Verified,
+    Rejected(String),
+}
+
+pub struct DeviceCheckCredentials {
+    pub team_id: String,
+    pub key_id: String,
+    pub private_key_pem: String,
+    pub development: bool,
+}
+
+pub struct PlayIntegrityCredentials {
+    pub package_name: String,
+    /// Service account JSON key.
+    pub service_account: String,
+}
+
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DeviceCheckClaims<'a> {
+    iss: &'a str,
+    iat: i64,
+}
+
+#[derive(Serialize)]
+struct GoogleAssertionClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct GoogleAccessToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+pub struct AppAttestationVerifier {
+    client: reqwest::Client,
+    ios_mode: AttestationMode,
+    android_mode: AttestationMode,
+    device_check: Option<DeviceCheckCredentials>,
+    play_integrity: Option<PlayIntegrityCredentials>,
+    web_mode: AttestationMode,
+    turnstile_secret_key: Option<String>,
+    /// Google access token and when it stops being usable.
+    google_token: Mutex<Option<(String, Instant)>>,
+}
+
+impl AppAttestationVerifier {
+    pub fn new(
+        ios_mode: AttestationMode,
+        android_mode: AttestationMode,
+        device_check: Option<DeviceCheckCredentials>,
+        play_integrity: Option<PlayIntegrityCredentials>,
+        web_mode: AttestationMode,
+        turnstile_secret_key: Option<String>,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            ios_mode,
+            android_mode,
+            device_check,
+            play_integrity,
+            web_mode,
+            turnstile_secret_key,
+            google_token: Mutex::new(None),
+        }
+    }
+
+    /// Modes and provider credentials from `config`. A platform whose
+    /// credentials are incomplete has every token check fail as unavailable,
+    /// which refuses uploads under `enforce`.
+    pub fn from_config(config: &Config) -> Self {
+        let present = |v: &Option<String>| v.clone().filter(|v| !v.trim().is_empty());
+        let device_check = match (
+            present(&config.apple_devicecheck_team_id),
+            present(&config.apple_devicecheck_key_id),
+            present(&config.apple_devicecheck_private_key),
+        ) {
+            (Some(team_id), Some(key_id), Some(private_key_pem)) => Some(DeviceCheckCredentials {
+                team_id,
+                key_id,
+                private_key_pem,
+                development: config.apple_devicecheck_development,
+            }),
+            _ => None,
+        };
+        let play_integrity = match (
+            present(&config.play_integrity_package_name),
+            present(&config.play_integrity_service_account),
+        ) {
+            (Some(package_name), Some(service_account)) => Some(PlayIntegrityCredentials {
+                package_name,
+                service_account,
+            }),
+            _ => None,
+        };
+        Self::new(
+            config.app_attestation_ios,
+            config.app_attestation_android,
+            device_check,
+            play_integrity,
+            config.app_attestation_web,
+            present(&config.turnstile_secret_key),
+        )
+    }
+
+    /// Every platform off.
+    pub fn disabled() -> Self {
+        Self::new(
+            AttestationMode::Off,
+            AttestationMode::Off,
+            None,
+            None,
+            AttestationMode::Off,
+            None,
+        )
+    }
+
+    /// The strictest platform mode, which every upload is held to: a client
+    /// could otherwise pick the laxest by naming another platform.
+    pub fn mode(&self) -> AttestationMode {
+        self.ios_mode.max(self.android_mode).max(self.web_mode)
+    }
+
+    /// Checks `token` with the platform's provider. Errors mean the provider
+    /// couldn't be asked, not that the token is bad.
+    pub async fn verify(
+        &self,
+        platform: ClientPlatform,
+        token: &str,
+    ) -> anyhow::Result<AttestationOutcome> {
+        match platform {
+            ClientPlatform::Ios => self.verify_device_check(token).await,
+            ClientPlatform::Android => self.verify_play_integrity(token).await,
+            ClientPlatform::Web => self.verify_turnstile(token).await,
+        }
+    }
+
+    async fn verify_turnstile(&self, token: &str) -> anyhow::Result<AttestationOutcome> {
+        let Some(secret) = &self.turnstile_secret_key else {
+            anyhow::bail!("Turnstile secret key is not configured");
+        };
+        let res = self
+            .client
+            .post(TURNSTILE_VERIFY_URL)
+            .json(&serde_json::json!({ "secret": secret, "response": token }))
+            .send()
+            .await?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("Turnstile returned HTTP {}: {}", status, body);
+        }
+        turnstile_verdict(&res.json().await?)
+    }
+
+    async fn verify_device_check(&self, token: &str) -> anyhow::Result<AttestationOutcome> {
+        let Some(creds) = &self.device_check else {
+            anyhow::bail!("DeviceCheck credentials are not configured");
+        };
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(creds.key_id.clone());
+        let jwt = encode(
+            &header,
+            &DeviceCheckClaims {
+                iss: &creds.team_id,
+                iat: chrono::Utc::now().timestamp(),
+            },
+            &EncodingKey::from_ec_pem(creds.private_key_pem.as_bytes())?,
+        )?;
+        let host = if creds.development {
+            "api.development.devicecheck.apple.com"
+        } else {
+            "api.devicecheck.apple.com"
+        };
+
+        let res = self
+            .client
+            .post(format!("https://{}/v1/validate_device_token", host))
+            .bearer_auth(jwt)
+            .json(&serde_json::json!({
+                "device_token": token,
+                "transaction_id": Uuid::now_v7().to_string(),
+                "timestamp": chrono::Utc::now().timestamp_millis(),
+            }))
+            .send()
+            .await?;
+        let status = res.status();
+        if status.is_success() {
+            return Ok(AttestationOutcome::Verified);
+        }
+        let body = res.text().await.unwrap_or_default();
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            return Ok(AttestationOutcome::Rejected(format!(
+                "DeviceCheck refused the device token: {}",
+                body.trim()
+            )));
+        }
+        anyhow::bail!("DeviceCheck returned HTTP {}: {}", status, body)
+    }
+
+    async fn verify_play_integrity(&self, token: &str) -> anyhow::Result<AttestationOutcome> {
+        let Some(creds) = &self.play_integrity else {
+            anyhow::bail!("Play Integrity credentials are not configured");
+        };
+        let access_token = self.google_access_token(creds).await?;
+        let res = self
+            .client
+            .post(format!(
+                "https://playintegrity.googleapis.com/v1/{}:decodeIntegrityToken",
+                creds.package_name
+            ))
+            .bearer_auth(access_token)
+            .json(&serde_json::json!({ "integrity_token": token }))
+            .send()
+            .await?;
+        let status = res.status();
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            return Ok(AttestationOutcome::Rejected(
+                "Play Integrity could not decode the token".to_string(),
+            ));
+        }
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("Play Integrity returned HTTP {}: {}", status, body);
+        }
+        let body: Value = res.json().await?;
+        Ok(play_integrity_verdict(
+            &body,
+            &creds.package_name,
+            chrono::Utc::now().timestamp_millis(),
+        ))
+    }
+
+    /// OAuth access token for the service account, reused until shortly
+    /// before it expires.
+    async fn google_access_token(
+        &self,
+        creds: &PlayIntegrityCredentials,
+    ) -> anyhow::Result<String> {
+        let mut cached = self.google_token.lock().await;
+        if let Some((token, valid_until)) = cached.as_ref()
+            && Instant::now() < *valid_until
+        {
+            return Ok(token.clone());
+        }
+
+        let key: ServiceAccountKey = serde_json::from_str(&creds.service_account)?;
+        let token_uri = key.token_uri.as_deref().unwrap_or(GOOGLE_TOKEN_URI);
+        let now = chrono::Utc::now().timestamp();
+        let assertion = encode(
+            &Header::new(Algorithm::RS256),
+            &GoogleAssertionClaims {
+                iss: &key.client_email,
+                scope: PLAY_INTEGRITY_SCOPE,
+                aud: token_uri,
+                iat: now,
+                exp: now + 3600,
+            },
+            &EncodingKey::from_rsa_pem(key.private_key.as_bytes())?,
+        )?;
+
+        // The assertion is base64url, so it needs no form encoding.
+        let res = self
+            .client
+            .post(token_uri)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .body(format!(
+                "grant_type=urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer&assertion={}",
+                assertion
+            ))
+            .send()
+            .await?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("Google token exchange returned HTTP {}: {}", status, body);
+        }
+        let token: GoogleAccessToken = res.json().await?;
+        let valid_until = Instant::now() + Duration::from_secs(token.expires_in.saturating_sub(60));
+        *cached = Some((token.access_token.clone(), valid_until));
+        Ok(token.access_token)
+    }
+}
+
+/// Judges a Turnstile siteverify response. Errors about our own secret mean
+/// the check couldn't be made, not that the token is bad.
+pub fn turnstile_verdict(body: &Value) -> anyhow::Result<AttestationOutcome> {
+    if body["success"].as_bool() == Some(true) {
+        return Ok(AttestationOutcome::Verified);
+    }
+    let codes: Vec<&str> = body["error-codes"]
+        .as_array()
+        .map(|codes| codes.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if codes
+        .iter()
+        .any(|code| code.ends_with("-input-secret") || *code == "internal-error")
+    {
+        anyhow::bail!("Turnstile could not check the token: {}", codes.join(", "));
+    }
+    Ok(AttestationOutcome::Rejected(format!(
+        "Turnstile refused the token: {}",
+        codes.join(", ")
+    )))
+}
+
+/// Judges a decoded Play Integrity response: the verdict must be for
+/// `package_name`, from a build Play recognizes, on a device meeting device
+/// integrity (emulators don't), and issued within the last ten minutes.
+pub fn play_integrity_verdict(body: &Value, package_name: &str, now_ms: i64) -> AttestationOutcome {
+    let payload = &body["tokenPayloadExternal"];
+    let request = &payload["requestDetails"];
+
+    if request["requestPackageName"].as_str() != Some(package_name) {
+        return AttestationOutcome::Rejected("verdict is for another package".to_string());
+    }
+    let issued_ms = match &request["timestampMillis"] {
+        Value::String(s) => s.parse::<i64>().ok(),
+        other => other.as_i64(),
+    };
+    match issued_ms {
+        Some(issued) if now_ms - issued <= MAX_VERDICT_AGE_MS => {}
+        _ => return AttestationOutcome::Rejected("verdict is stale".to_string()),
+    }
+    if payload["appIntegrity"]["appRecognitionVerdict"].as_str() != Some("PLAY_RECOGNIZED") {
+        return AttestationOutcome::Rejected("app is not recognized by Play".to_string());
+    }
+    let meets_device_integrity = payload["deviceIntegrity"]["deviceRecognitionVerdict"]
+        .as_array()
+        .is_some_and(|verdicts| {
+            verdicts
+                .iter()
+                .any(|v| v.as_str() == Some("MEETS_DEVICE_INTEGRITY"))
+        });
+    if !meets_device_integrity {
+        return AttestationOutcome::Rejected("device does not meet integrity".to_string());
+    }
+    AttestationOutcome::Verified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKAGE: &str = "online.throughyourletters.app";
+    const NOW_MS: i64 = 1_771_804_800_000;
+
+    fn verdict(package: &str, issued_ms: i64, device: &[&str]) -> Value {
+        serde_json::json!({
+            "tokenPayloadExternal": {
+                "requestDetails": {
+                    "requestPackageName": package,
+                    "timestampMillis": issued_ms.to_string(),
+                },
+                "appIntegrity": { "appRecognitionVerdict": "PLAY_RECOGNIZED" },
+                "deviceIntegrity": { "deviceRecognitionVerdict": device },
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_fresh_verdicts_from_real_devices_only() {
+        let ok = verdict(PACKAGE, NOW_MS - 1_000, &["MEETS_DEVICE_INTEGRITY"]);
+        assert_eq!(
+            play_integrity_verdict(&ok, PACKAGE, NOW_MS),
+            AttestationOutcome::Verified
+        );
+
+        let emulator = verdict(PACKAGE, NOW_MS - 1_000, &[]);
+        let stale = verdict(
+            PACKAGE,
+            NOW_MS - MAX_VERDICT_AGE_MS - 1,
+            &["MEETS_DEVICE_INTEGRITY"],
+        );
+        let other_app = verdict("com.example", NOW_MS, &["MEETS_DEVICE_INTEGRITY"]);
+        for body in [emulator, stale, other_app] {
+            assert!(matches!(
+                play_integrity_verdict(&body, PACKAGE, NOW_MS),
+                AttestationOutcome::Rejected(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn turnstile_failures_blame_the_token_unless_our_secret_is_wrong() {
+        let ok = serde_json::json!({ "success": true, "error-codes": [] });
+        assert_eq!(
+            turnstile_verdict(&ok).unwrap(),
+            AttestationOutcome::Verified
+        );
+
+        let expired =
+            serde_json::json!({ "success": false, "error-codes": ["timeout-or-duplicate"] });
+        assert!(matches!(
+            turnstile_verdict(&expired).unwrap(),
+            AttestationOutcome::Rejected(_)
+        ));
+        let misconfigured =
+            serde_json::json!({ "success": false, "error-codes": ["invalid-input-secret"] });
+        assert!(turnstile_verdict(&misconfigured).is_err());
+    }
+
+    #[test]
+    fn every_upload_gets_the_strictest_mode() {
+        let verifier = |ios, android, web| {
+            AppAttestationVerifier::new(ios, android, None, None, web, None).mode()
+        };
+        use AttestationMode::{Enforce, Monitor, Off};
+        assert_eq!(verifier(Off, Off, Off), Off);
+        assert_eq!(verifier(Enforce, Off, Off), Enforce);
+        assert_eq!(verifier(Off, Monitor, Off), Monitor);
+        assert_eq!(verifier(Monitor, Off, Enforce), Enforce);
+    }
+
+    #[test]
+    fn unknown_platforms_are_web() {
+        assert_eq!(
+            ClientPlatform::from_header(Some(" iOS ")),
+            ClientPlatform::Ios
+        );
+        assert_eq!(
+            ClientPlatform::from_header(Some("android")),
+            ClientPlatform::Android
+        );
+        assert_eq!(
+            ClientPlatform::from_header(Some("curl")),
+            ClientPlatform::Web
+        );
+        assert_eq!(
+            AppAttestationVerifier::disabled().mode(),
+            AttestationMode::Off
+        );
+    }
+}
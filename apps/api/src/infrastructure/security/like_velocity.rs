@@ -0,0 +1,84 @@
+//! Per-identity like velocity limits.
+//!
+//! Likes are keyed by client IP, so the IP is the identity limited here.
+//! Each identity gets a per-minute and a per-hour budget of like toggles in
+//! fixed Redis windows. Identities that run past a budget are counted per day
+//! in `like_velocity_offenders:{date}`, the list admins review before rolling
+//! their likes back.
+
+use redis::AsyncCommands;
+
+/// Flagged identities are kept for a week.
+pub const OFFENDERS_RETENTION_DAYS: i64 = 7;
+
+const OFFENDERS_TTL_SECONDS: i64 = (OFFENDERS_RETENTION_DAYS + 1) * 86_400;
+
+pub fn offenders_key(date: &str) -> String {
+    format!("like_velocity_offenders:{}", date)
+}
+
+/// Counter of `ip`'s likes in the `window_seconds` window holding `now`.
+fn window_key(ip: &str, window_seconds: u64, now: u64) -> String {
+    format!(
+        "like_velocity:{}:{}:{}",
+        window_seconds,
+        ip,
+        now / window_seconds
+    )
+}
+
+/// Counts one like toggle by `ip` and reports whether it is within every
+/// `(window_seconds, limit)` budget; a limit of 0 disables that window.
+/// Redis errors let the like through.
+pub async fn allow_like(redis: &redis::Client, ip: &str, windows: &[(u64, u32)]) -> bool {
+    let now = chrono::Utc::now();
+    let result: redis::RedisResult<bool> = async {
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        let mut within = true;
+        for &(window_seconds, limit) in windows.iter().filter(|(_, limit)| *limit > 0) {
+            let key = window_key(ip, window_seconds, now.timestamp() as u64);
+            let count: u32 = conn.incr(&key, 1_u32).await?;
+            if count == 1 {
+                let _: () = conn.expire(&key, window_seconds as i64).await?;
+            }
+            within &= count <= limit;
+        }
+        if !within {
+            let offenders = offenders_key(&now.format("%Y-%m-%d").to_string());
+            let _: () = redis::pipe()
+                .zincr(&offenders, ip, 1_u32)
+                .ignore()
+                .expire(&offenders, OFFENDERS_TTL_SECONDS)
+                .ignore()
+                .query_async(&mut conn)
+                .await?;
+        }
+        Ok(within)
+    }
+    .await;
+    result.unwrap_or_else(|e| {
+        tracing::warn!("Failed to check like velocity: {}", e);
+        true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_roll_over_on_their_boundary() {
+        assert_eq!(
+            window_key("203.0.113.9", 60, 119),
+            window_key("203.0.113.9", 60, 60)
+        );
+        assert_ne!(
+            window_key("203.0.113.9", 60, 120),
+            window_key("203.0.113.9", 60, 119)
+        );
+        assert_ne!(
+            window_key("203.0.113.9", 60, 60),
+            window_key("203.0.113.9", 3_600, 60)
+        );
+    }
+}
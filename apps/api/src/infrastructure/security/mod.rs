@@ -1,4 +1,6 @@
+pub mod app_attestation;
 pub mod comment_moderator;
+pub mod like_velocity;
 pub mod rate_limiter;
 pub mod validation;
 pub mod virus_scanner;
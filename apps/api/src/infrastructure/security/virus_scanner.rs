@@ -1,8 +1,12 @@
 use anyhow::Result;
 use bytes::Bytes;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::path::Path;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
+/// INSTREAM chunk size; well under clamd's default StreamMaxLength.
+const SCAN_CHUNK_SIZE: usize = 64 * 1024;
+
 pub struct VirusScanner {
     enabled: bool,
     host: String,
@@ -19,6 +23,19 @@ impl VirusScanner {
     }
 
     pub async fn scan(&self, data: &Bytes) -> Result<bool> {
+        self.scan_reader(&mut &data[..]).await
+    }
+
+    /// Scan a file on disk without loading it into memory.
+    pub async fn scan_file(&self, path: &Path) -> Result<bool> {
+        if !self.enabled {
+            return Ok(true);
+        }
+        let mut file = tokio::fs::File::open(path).await?;
+        self.scan_reader(&mut file).await
+    }
+
+    async fn scan_reader<R: AsyncRead + Unpin>(&self, reader: &mut R) -> Result<bool> {
         if !self.enabled {
             return Ok(true);
         }
@@ -35,14 +52,22 @@ impl VirusScanner {
             tracing::warn!("clamav write failed: {}", err);
             return Ok(true);
         }
-        let len = data.len() as u32;
-        if let Err(err) = stream.write_all(&len.to_be_bytes()).await {
-            tracing::warn!("clamav write length failed: {}", err);
-            return Ok(true);
-        }
-        if let Err(err) = stream.write_all(data).await {
-            tracing::warn!("clamav write data failed: {}", err);
-            return Ok(true);
+
+        let mut chunk = vec![0u8; SCAN_CHUNK_SIZE];
+        loop {
+            let read = reader.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            let len = read as u32;
+            if let Err(err) = stream.write_all(&len.to_be_bytes()).await {
+                tracing::warn!("clamav write length failed: {}", err);
+                return Ok(true);
+            }
+            if let Err(err) = stream.write_all(&chunk[..read]).await {
+                tracing::warn!("clamav write data failed: {}", err);
+                return Ok(true);
+            }
         }
         if let Err(err) = stream.write_all(&0u32.to_be_bytes()).await {
             tracing::warn!("clamav write terminator failed: {}", err);
@@ -0,0 +1,82 @@
+//! Stopping background workers without cutting jobs off halfway.
+//!
+//! `main` triggers the shared [`Shutdown`] as the HTTP server stops taking
+//! requests. Workers check it between jobs and between batches: they stop
+//! claiming work, finish or hand back what they hold, and return from
+//! `start`. `main` waits for them up to `WORKER_DRAIN_TIMEOUT_SECONDS`;
+//! queued jobs still held after that come back once their visibility
+//! timeout passes.
+
+use std::{sync::Arc, time::Duration};
+use tokio::sync::watch;
+
+/// Cheap-to-clone handle on the shutdown signal. One that is never
+/// triggered lets workers run forever.
+#[derive(Clone)]
+pub struct Shutdown {
+    tx: Arc<watch::Sender<bool>>,
+    rx: watch::Receiver<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (tx, rx) = watch::channel(false);
+        Self {
+            tx: Arc::new(tx),
+            rx,
+        }
+    }
+
+    /// Tells every worker holding a clone to wind down.
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.rx.borrow()
+    }
+
+    /// Resolves once shutdown is triggered.
+    pub async fn triggered(&self) {
+        let mut rx = self.rx.clone();
+        // The sender lives as long as `self`, so this only ends by firing.
+        let _ = rx.wait_for(|stopping| *stopping).await;
+    }
+
+    /// Sleeps for `duration`, cut short by shutdown. Returns whether to
+    /// carry on.
+    pub async fn sleep(&self, duration: Duration) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => !self.is_triggered(),
+            _ = self.triggered() => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sleeps_are_cut_short_by_shutdown() {
+        let shutdown = Shutdown::new();
+        assert!(shutdown.sleep(Duration::from_millis(1)).await);
+
+        let stopper = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            stopper.trigger();
+        });
+        let started = std::time::Instant::now();
+        assert!(!shutdown.sleep(Duration::from_secs(60)).await);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(shutdown.is_triggered());
+        assert!(!shutdown.sleep(Duration::from_secs(60)).await);
+    }
+}
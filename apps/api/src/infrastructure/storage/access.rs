@@ -0,0 +1,43 @@
+//! URL selection by moderation status.
+//!
+//! Approved letterings are served from the public CDN. Anything else
+//! (PENDING, REJECTED, REPORTED) is kept in the private bucket and only
+//! reachable through short-lived signed URLs handed to the uploader and to
+//! moderators.
+
+use super::{traits::StorageService, visibility::is_private_key};
+use std::time::Duration;
+
+pub fn is_publicly_servable(status: &str) -> bool {
+    status.eq_ignore_ascii_case("APPROVED")
+}
+
+/// Return `url` unchanged for approved content, otherwise a signed URL for
+/// the same object. Approved images not yet moved out of the private bucket
+/// are signed too. Falls back to the stored URL if signing fails so that
+/// moderation tooling keeps working during a storage hiccup.
+pub async fn viewable_url(
+    storage: &dyn StorageService,
+    status: &str,
+    url: &str,
+    expires_in: Duration,
+) -> String {
+    let key = storage.key_from_url(url);
+    let private = key.as_deref().is_some_and(is_private_key);
+    if is_publicly_servable(status) && !private {
+        return url.to_string();
+    }
+
+    let Some(key) = key else {
+        tracing::warn!(url = %url, "Cannot derive storage key for signed URL");
+        return url.to_string();
+    };
+
+    match storage.signed_url(&key, expires_in).await {
+        Ok(signed) => signed,
+        Err(e) => {
+            tracing::warn!(key = %key, "Failed to sign storage URL: {}", e);
+            url.to_string()
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Content-addressed object keys.
+//!
+//! Served images are stored under `sha256/{aa}/{hash}.{ext}`, so keys can't
+//! be guessed from a lettering id and identical bytes are stored once.
+//! `storage_objects` counts the references to each key: [`put`] adds one and
+//! only uploads for the first, [`release`] drops them and hands back the keys
+//! nothing references any more. A key released to no references keeps its
+//! row, with a count of 0, as a tombstone until the object is deleted.
+//! [`lock_key`] is held from a put's check of the key until its upload and
+//! reference are committed, so identical puts and the delete of the same key
+//! can't interleave. Keys from before content addressing have no row there;
+//! they are released as before and resolve through `key_from_url` like any
+//! other.
+//! [`copy`] adds a reference to an object under another key, e.g. to move it
+//! between the private and public buckets.
+
+use super::{traits::StorageService, visibility::private_key};
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::collections::HashSet;
+
+pub const CONTENT_KEY_PREFIX: &str = "sha256/";
+
+/// Key for `data`, before any region prefix.
+pub fn content_key(data: &[u8], extension: &str) -> String {
+    let hash = format!("{:x}", Sha256::digest(data));
+    format!(
+        "{}{}/{}.{}",
+        CONTENT_KEY_PREFIX,
+        &hash[..2],
+        hash,
+        extension
+    )
+}
+
+/// Holds off every other [`put`] and storage cleanup of `key` until `tx`
+/// ends.
+pub async fn lock_key(tx: &mut Transaction<'_, Postgres>, key: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+        .bind(key)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+/// Add a reference to `key`, uploading the object from `data` unless it is
+/// already stored, and return its URL. The key stays locked until the
+/// reference is committed, so an identical put waits for the upload instead
+/// of handing out the URL of an object that isn't there yet, and a failed
+/// upload leaves no reference behind.
+async fn reference<F, Fut>(
+    db: &PgPool,
+    storage: &dyn StorageService,
+    key: &str,
+    content_type: &str,
+    size_bytes: i64,
+    data: F,
+) -> anyhow::Result<String>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = anyhow::Result<Vec<u8>>>,
+{
+    let mut tx = db.begin().await?;
+    lock_key(&mut tx, key).await?;
+    let url = if referenced(&mut tx, key).await? {
+        storage.get_url(key)
+    } else {
+        storage.upload(key, data().await?, content_type).await?
+    };
+    sqlx::query(
+        "INSERT INTO storage_objects (key, content_type, size_bytes, ref_count)
+         VALUES ($1, $2, $3, 1)
+         ON CONFLICT (key) DO UPDATE SET ref_count = storage_objects.ref_count + 1",
+    )
+    .bind(key)
+    .bind(content_type)
+    .bind(size_bytes)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(url)
+}
+
+/// Store `data` under its content key in the bucket for `country_code`,
+/// adding a reference to it, and return its URL. `private` data goes to the
+/// private bucket. Identical data already stored is not uploaded again; a
+/// tombstoned key is, since its object may already be deleted or about to
+/// be.
+pub async fn put(
+    db: &PgPool,
+    storage: &dyn StorageService,
+    data: Vec<u8>,
+    extension: &str,
+    content_type: &str,
+    country_code: Option<&str>,
+    private: bool,
+) -> anyhow::Result<String> {
+    let mut key = content_key(&data, extension);
+    if private {
+        key = private_key(&key);
+    }
+    let key = storage.key_for_country(&key, country_code);
+    let size_bytes = data.len() as i64;
+    reference(db, storage, &key, content_type, size_bytes, || async {
+        Ok(data)
+    })
+    .await
+}
+
+/// Add a reference to `to` for the object stored at `from`, copying it over
+/// unless `to` is already stored, and return `to`'s URL. `from` keeps its
+/// references; the caller releases the one it no longer needs. Objects from
+/// before content addressing are copied too, and tracked from then on.
+pub async fn copy(
+    db: &PgPool,
+    storage: &dyn StorageService,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<String> {
+    let tracked: Option<(String, i64)> =
+        sqlx::query_as("SELECT content_type, size_bytes FROM storage_objects WHERE key = $1")
+            .bind(from)
+            .fetch_optional(db)
+            .await?;
+    let download = || async {
+        storage
+            .download(from)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("no object to copy at {}", from))
+    };
+    let (content_type, size_bytes, data) = match tracked {
+        Some((content_type, size_bytes)) => (content_type, size_bytes, None),
+        None => {
+            let data = download().await?;
+            let content_type = image::guess_format(&data)
+                .map(|format| format.to_mime_type())
+                .unwrap_or("application/octet-stream");
+            (content_type.to_string(), data.len() as i64, Some(data))
+        }
+    };
+
+    reference(db, storage, to, &content_type, size_bytes, || async {
+        match data {
+            Some(data) => Ok(data),
+            None => download().await,
+        }
+    })
+    .await
+}
+
+/// Drop one reference to each of `keys` and return those to delete from
+/// storage: keys whose last reference this was, now tombstoned, and keys not
+/// tracked here.
+pub async fn release(conn: &mut PgConnection, keys: &[String]) -> Result<Vec<String>, sqlx::Error> {
+    let counted: Vec<(String, i32)> = sqlx::query_as(
+        "UPDATE storage_objects SET ref_count = GREATEST(ref_count - 1, 0)
+         WHERE key = ANY($1)
+         RETURNING key, ref_count",
+    )
+    .bind(keys)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let still_used: HashSet<&str> = counted
+        .iter()
+        .filter(|(_, refs)| *refs > 0)
+        .map(|(key, _)| key.as_str())
+        .collect();
+    Ok(keys
+        .iter()
+        .filter(|key| !still_used.contains(key.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// Whether `key` is referenced again, e.g. by an identical upload made after
+/// its last reference was released, and must not be deleted. Run under
+/// [`lock_key`] so the answer holds until `tx` ends.
+pub async fn referenced(
+    tx: &mut Transaction<'_, Postgres>,
+    key: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM storage_objects WHERE key = $1 AND ref_count > 0)",
+    )
+    .bind(key)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// Forget `key`'s tombstone once its object is deleted.
+pub async fn forget(tx: &mut Transaction<'_, Postgres>, key: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM storage_objects WHERE key = $1 AND ref_count = 0")
+        .bind(key)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_keys_are_sharded_by_hash() {
+        let key = content_key(b"hello", "webp");
+        assert_eq!(
+            key,
+            "sha256/2c/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824.webp"
+        );
+        assert_eq!(key, content_key(b"hello", "webp"));
+        assert_ne!(key, content_key(b"hello!", "webp"));
+    }
+}
@@ -0,0 +1,54 @@
+//! Storage wrapper that adds the fault injector's latency to every call.
+
+use super::traits::{ChunkedUpload, StorageService};
+use crate::infrastructure::fault_injection::FaultInjector;
+use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+
+pub struct FaultInjectingStorage {
+    inner: Arc<dyn StorageService>,
+    faults: Arc<FaultInjector>,
+}
+
+impl FaultInjectingStorage {
+    pub fn new(inner: Arc<dyn StorageService>, faults: Arc<FaultInjector>) -> Self {
+        Self { inner, faults }
+    }
+}
+
+#[async_trait]
+impl StorageService for FaultInjectingStorage {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> anyhow::Result<String> {
+        self.faults.storage_delay().await;
+        self.inner.upload(key, data, content_type).await
+    }
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        self.faults.storage_delay().await;
+        self.inner.start_chunked_upload(key, content_type).await
+    }
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.faults.storage_delay().await;
+        self.inner.delete(key).await
+    }
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.faults.storage_delay().await;
+        self.inner.download(key).await
+    }
+    fn get_url(&self, key: &str) -> String {
+        self.inner.get_url(key)
+    }
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        self.faults.storage_delay().await;
+        self.inner.signed_url(key, expires_in).await
+    }
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        self.inner.key_from_url(url)
+    }
+    fn key_for_country(&self, key: &str, country_code: Option<&str>) -> String {
+        self.inner.key_for_country(key, country_code)
+    }
+}
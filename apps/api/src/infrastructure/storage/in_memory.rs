@@ -0,0 +1,249 @@
+//! Process-local storage for `SYNTHETIC_MODE`.
+//!
+//! Objects live in memory and are served back by this process under
+//! `/synthetic-storage/`, so uploads, the ML worker's image fetch and resized
+//! renditions all run without R2. Once `max_bytes` is exceeded the oldest
+//! objects are dropped; a long load test only needs recent uploads readable.
+//! A private store, standing in for the private bucket, is served under
+//! `/synthetic-private-storage/` and only to requests with a valid signature
+//! from [`StorageService::signed_url`].
+
+use super::traits::{ChunkedUpload, StorageService};
+use async_trait::async_trait;
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+/// Path prefix the objects are served under.
+pub const ROUTE_PREFIX: &str = "/synthetic-storage";
+
+/// Path prefix the objects of a private store are served under.
+pub const PRIVATE_ROUTE_PREFIX: &str = "/synthetic-private-storage";
+
+#[derive(Default)]
+struct Objects {
+    by_key: HashMap<String, (String, Bytes)>,
+    /// Keys in upload order, for eviction.
+    order: VecDeque<String>,
+    bytes: usize,
+}
+
+/// Clones share the same objects.
+#[derive(Clone)]
+pub struct InMemoryStorage {
+    route_prefix: &'static str,
+    public_url: String,
+    max_bytes: usize,
+    /// Set on a private store: reads must be signed with it.
+    signing_secret: Option<String>,
+    objects: Arc<RwLock<Objects>>,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl InMemoryStorage {
+    /// `base_url` is this server's origin, e.g. `http://127.0.0.1:3000`.
+    pub fn new(base_url: &str, max_bytes: usize) -> Self {
+        Self {
+            route_prefix: ROUTE_PREFIX,
+            public_url: format!("{}{}", base_url.trim_end_matches('/'), ROUTE_PREFIX),
+            max_bytes,
+            signing_secret: None,
+            objects: Arc::new(RwLock::new(Objects::default())),
+        }
+    }
+
+    /// A store whose objects are only served through signed URLs, signed
+    /// with a secret that lives as long as the process.
+    pub fn new_private(base_url: &str, max_bytes: usize) -> Self {
+        Self {
+            route_prefix: PRIVATE_ROUTE_PREFIX,
+            public_url: format!("{}{}", base_url.trim_end_matches('/'), PRIVATE_ROUTE_PREFIX),
+            max_bytes,
+            signing_secret: Some(format!("{}{}", Uuid::now_v7(), Uuid::now_v7())),
+            objects: Arc::new(RwLock::new(Objects::default())),
+        }
+    }
+
+    /// Path prefix the objects are served under.
+    pub fn route_prefix(&self) -> &'static str {
+        self.route_prefix
+    }
+
+    fn signature(secret: &str, key: &str, expires: u64) -> String {
+        format!(
+            "{:x}",
+            Sha256::digest(format!("{}:{}:{}", secret, key, expires))
+        )
+    }
+
+    /// Whether a read of `key` with these query parameters may be served:
+    /// always for a public store, and for a private one only with an
+    /// unexpired signature.
+    pub fn authorizes(&self, key: &str, expires: Option<u64>, signature: Option<&str>) -> bool {
+        let Some(secret) = &self.signing_secret else {
+            return true;
+        };
+        match (expires, signature) {
+            (Some(expires), Some(signature)) => {
+                expires >= unix_now() && signature == Self::signature(secret, key, expires)
+            }
+            _ => false,
+        }
+    }
+
+    /// Content type and body of a stored object.
+    pub fn get(&self, key: &str) -> Option<(String, Bytes)> {
+        self.objects.read().unwrap().by_key.get(key).cloned()
+    }
+
+    fn put(&self, key: &str, data: Bytes, content_type: &str) {
+        let mut objects = self.objects.write().unwrap();
+        if let Some((_, old)) = objects.by_key.remove(key) {
+            objects.bytes -= old.len();
+            objects.order.retain(|k| k != key);
+        }
+        objects.bytes += data.len();
+        objects
+            .by_key
+            .insert(key.to_string(), (content_type.to_string(), data));
+        objects.order.push_back(key.to_string());
+        while objects.bytes > self.max_bytes && objects.order.len() > 1 {
+            let Some(oldest) = objects.order.pop_front() else {
+                break;
+            };
+            if let Some((_, data)) = objects.by_key.remove(&oldest) {
+                objects.bytes -= data.len();
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StorageService for InMemoryStorage {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> anyhow::Result<String> {
+        self.put(key, Bytes::from(data), content_type);
+        Ok(self.get_url(key))
+    }
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        Ok(Box::new(InMemoryChunkedUpload {
+            storage: self.clone(),
+            key: key.to_string(),
+            content_type: content_type.to_string(),
+            buf: Vec::new(),
+        }))
+    }
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let mut objects = self.objects.write().unwrap();
+        if let Some((_, data)) = objects.by_key.remove(key) {
+            objects.bytes -= data.len();
+            objects.order.retain(|k| k != key);
+        }
+        Ok(())
+    }
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.get(key).map(|(_, data)| data.to_vec()))
+    }
+    fn get_url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key)
+    }
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        let Some(secret) = &self.signing_secret else {
+            return Ok(self.get_url(key));
+        };
+        let expires = unix_now() + expires_in.as_secs();
+        Ok(format!(
+            "{}?expires={}&signature={}",
+            self.get_url(key),
+            expires,
+            Self::signature(secret, key, expires)
+        ))
+    }
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        url.strip_prefix(&self.public_url)
+            .map(|rest| rest.trim_start_matches('/'))
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+    }
+}
+
+struct InMemoryChunkedUpload {
+    storage: InMemoryStorage,
+    key: String,
+    content_type: String,
+    buf: Vec<u8>,
+}
+
+#[async_trait]
+impl ChunkedUpload for InMemoryChunkedUpload {
+    async fn write(&mut self, chunk: &[u8]) -> anyhow::Result<()> {
+        self.buf.extend_from_slice(chunk);
+        Ok(())
+    }
+    async fn finish(self: Box<Self>) -> anyhow::Result<String> {
+        let upload = *self;
+        upload
+            .storage
+            .put(&upload.key, Bytes::from(upload.buf), &upload.content_type);
+        Ok(upload.storage.get_url(&upload.key))
+    }
+    async fn abort(self: Box<Self>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn evicts_oldest_objects_over_capacity() {
+        let storage = InMemoryStorage::new("http://localhost:3000/", 10);
+        let url = storage.upload("a", vec![0; 6], "image/jpeg").await.unwrap();
+        assert_eq!(url, "http://localhost:3000/synthetic-storage/a");
+        assert_eq!(storage.key_from_url(&url).as_deref(), Some("a"));
+
+        storage.upload("b", vec![1; 6], "image/png").await.unwrap();
+        assert!(storage.get("a").is_none());
+        let (content_type, data) = storage.get("b").unwrap();
+        assert_eq!(content_type, "image/png");
+        assert_eq!(data.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn private_objects_need_an_unexpired_signature() {
+        let storage = InMemoryStorage::new_private("http://localhost:3000", 1024);
+        let url = storage.upload("a", vec![0; 6], "image/jpeg").await.unwrap();
+        assert_eq!(url, "http://localhost:3000/synthetic-private-storage/a");
+        assert!(!storage.authorizes("a", None, None));
+
+        let signed = storage
+            .signed_url("a", Duration::from_secs(60))
+            .await
+            .unwrap();
+        let query = signed.strip_prefix(&format!("{}?", url)).unwrap();
+        let (expires, signature) = query
+            .strip_prefix("expires=")
+            .and_then(|q| q.split_once("&signature="))
+            .unwrap();
+        let expires: u64 = expires.parse().unwrap();
+        assert!(storage.authorizes("a", Some(expires), Some(signature)));
+        assert!(!storage.authorizes("b", Some(expires), Some(signature)));
+        assert!(!storage.authorizes("a", Some(expires + 1), Some(signature)));
+
+        let public = InMemoryStorage::new("http://localhost:3000", 1024);
+        assert!(public.authorizes("a", None, None));
+    }
+}
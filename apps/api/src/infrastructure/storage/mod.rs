@@ -1,2 +1,9 @@
+pub mod access;
+pub mod content_addressed;
+pub mod fault_injecting;
+pub mod in_memory;
 pub mod r2_storage_service;
+pub mod regional;
 pub mod traits;
+pub mod visibility;
+pub mod zip_stream;
@@ -1,8 +1,19 @@
-use super::traits::StorageService;
+use super::traits::{ChunkedUpload, StorageService};
 use async_trait::async_trait;
 use aws_sdk_s3::{
-    Client, config::BehaviorVersion, config::Credentials, config::Region, primitives::ByteStream,
+    Client,
+    config::BehaviorVersion,
+    config::Credentials,
+    config::Region,
+    operation::get_object::GetObjectError,
+    presigning::PresigningConfig,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
 };
+use std::time::Duration;
+
+/// S3 requires every part except the last to be at least 5 MiB.
+const MULTIPART_PART_SIZE: usize = 5 * 1024 * 1024;
 
 pub struct R2StorageService {
     client: Client,
@@ -50,6 +61,22 @@ impl StorageService for R2StorageService {
             .await?;
         Ok(format!("{}/{}", self.public_url, key))
     }
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        Ok(Box::new(R2ChunkedUpload {
+            client: self.client.clone(),
+            bucket: self.bucket.clone(),
+            key: key.to_string(),
+            content_type: content_type.to_string(),
+            url: self.get_url(key),
+            upload_id: None,
+            parts: Vec::new(),
+            buffer: Vec::with_capacity(MULTIPART_PART_SIZE),
+        }))
+    }
     async fn delete(&self, key: &str) -> anyhow::Result<()> {
         self.client
             .delete_object()
@@ -59,7 +86,171 @@ impl StorageService for R2StorageService {
             .await?;
         Ok(())
     }
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let object = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(object) => object,
+            Err(e) => match e.into_service_error() {
+                GetObjectError::NoSuchKey(_) => return Ok(None),
+                e => return Err(e.into()),
+            },
+        };
+        Ok(Some(object.body.collect().await?.into_bytes().to_vec()))
+    }
     fn get_url(&self, key: &str) -> String {
         format!("{}/{}", self.public_url, key)
     }
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        let presigned = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(PresigningConfig::expires_in(expires_in)?)
+            .await?;
+        Ok(presigned.uri().to_string())
+    }
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        url.strip_prefix(self.public_url.trim_end_matches('/'))
+            .map(|rest| rest.trim_start_matches('/'))
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+    }
+}
+
+/// Buffers up to one part and only switches to a multipart upload once the
+/// object outgrows it; small objects go out as a single `PutObject`.
+struct R2ChunkedUpload {
+    client: Client,
+    bucket: String,
+    key: String,
+    content_type: String,
+    url: String,
+    upload_id: Option<String>,
+    parts: Vec<CompletedPart>,
+    buffer: Vec<u8>,
+}
+
+impl R2ChunkedUpload {
+    async fn upload_id(&mut self) -> anyhow::Result<String> {
+        if let Some(id) = &self.upload_id {
+            return Ok(id.clone());
+        }
+        let created = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .content_type(&self.content_type)
+            .send()
+            .await?;
+        let id = created
+            .upload_id()
+            .ok_or_else(|| anyhow::anyhow!("multipart upload started without an id"))?
+            .to_string();
+        self.upload_id = Some(id.clone());
+        Ok(id)
+    }
+
+    async fn send_part(&mut self, data: Vec<u8>) -> anyhow::Result<()> {
+        let upload_id = self.upload_id().await?;
+        let part_number = self.parts.len() as i32 + 1;
+        let uploaded = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(data))
+            .send()
+            .await?;
+        self.parts.push(
+            CompletedPart::builder()
+                .part_number(part_number)
+                .set_e_tag(uploaded.e_tag().map(str::to_string))
+                .build(),
+        );
+        Ok(())
+    }
+
+    async fn complete(&mut self, upload_id: String) -> anyhow::Result<()> {
+        if !self.buffer.is_empty() {
+            let last = std::mem::take(&mut self.buffer);
+            self.send_part(last).await?;
+        }
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .upload_id(upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(std::mem::take(&mut self.parts)))
+                    .build(),
+            )
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ChunkedUpload for R2ChunkedUpload {
+    async fn write(&mut self, chunk: &[u8]) -> anyhow::Result<()> {
+        self.buffer.extend_from_slice(chunk);
+        while self.buffer.len() >= MULTIPART_PART_SIZE {
+            let rest = self.buffer.split_off(MULTIPART_PART_SIZE);
+            let part = std::mem::replace(&mut self.buffer, rest);
+            self.send_part(part).await?;
+        }
+        Ok(())
+    }
+
+    async fn finish(mut self: Box<Self>) -> anyhow::Result<String> {
+        let Some(upload_id) = self.upload_id.clone() else {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&self.key)
+                .body(ByteStream::from(std::mem::take(&mut self.buffer)))
+                .content_type(&self.content_type)
+                .send()
+                .await?;
+            return Ok(self.url.clone());
+        };
+
+        // A multipart upload that never completes keeps its parts, and keeps
+        // being billed, until it is aborted.
+        match self.complete(upload_id).await {
+            Ok(()) => Ok(self.url.clone()),
+            Err(e) => {
+                self.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn abort(self: Box<Self>) {
+        let Some(upload_id) = &self.upload_id else {
+            return;
+        };
+        if let Err(e) = self
+            .client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&self.key)
+            .upload_id(upload_id)
+            .send()
+            .await
+        {
+            tracing::warn!(key = %self.key, "Failed to abort multipart upload: {}", e);
+        }
+    }
 }
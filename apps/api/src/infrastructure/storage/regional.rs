@@ -0,0 +1,182 @@
+//! Region-pinned storage across several buckets.
+//!
+//! Objects for content from a routed country are written to that region's
+//! bucket under `regions/{region}/{key}`. That prefix is the only routing
+//! metadata: every other operation resolves the bucket from the key (or, for
+//! `key_from_url`, from the bucket's public URL), so stored URLs and keys keep
+//! working whatever the routing policy says later. Keys without a known
+//! region prefix go to the primary bucket.
+
+use super::traits::{ChunkedUpload, StorageService};
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+const REGION_KEY_PREFIX: &str = "regions/";
+
+pub struct RegionalStorage {
+    primary: Arc<dyn StorageService>,
+    regions: HashMap<String, Arc<dyn StorageService>>,
+    /// Upper-case ISO country code to region name.
+    routing: HashMap<String, String>,
+}
+
+impl RegionalStorage {
+    pub fn new(primary: Arc<dyn StorageService>) -> Self {
+        Self {
+            primary,
+            regions: HashMap::new(),
+            routing: HashMap::new(),
+        }
+    }
+
+    /// Serve `countries` from `bucket`, stored under `regions/{name}/`.
+    pub fn with_region(
+        mut self,
+        name: &str,
+        bucket: Arc<dyn StorageService>,
+        countries: &[String],
+    ) -> Self {
+        for country in countries {
+            self.routing
+                .insert(country.trim().to_ascii_uppercase(), name.to_string());
+        }
+        self.regions.insert(name.to_string(), bucket);
+        self
+    }
+
+    /// Bucket holding `key`, resolved from its region prefix.
+    fn bucket_for_key(&self, key: &str) -> &dyn StorageService {
+        key.strip_prefix(REGION_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('/'))
+            .and_then(|(region, _)| self.regions.get(region))
+            .map(Arc::as_ref)
+            .unwrap_or(self.primary.as_ref())
+    }
+}
+
+#[async_trait]
+impl StorageService for RegionalStorage {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> anyhow::Result<String> {
+        self.bucket_for_key(key)
+            .upload(key, data, content_type)
+            .await
+    }
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        self.bucket_for_key(key)
+            .start_chunked_upload(key, content_type)
+            .await
+    }
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.bucket_for_key(key).delete(key).await
+    }
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.bucket_for_key(key).download(key).await
+    }
+    fn get_url(&self, key: &str) -> String {
+        self.bucket_for_key(key).get_url(key)
+    }
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        self.bucket_for_key(key).signed_url(key, expires_in).await
+    }
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        self.regions
+            .values()
+            .find_map(|bucket| bucket.key_from_url(url))
+            .or_else(|| self.primary.key_from_url(url))
+    }
+    fn key_for_country(&self, key: &str, country_code: Option<&str>) -> String {
+        country_code
+            .map(|c| c.trim().to_ascii_uppercase())
+            .and_then(|c| self.routing.get(&c))
+            .filter(|region| self.regions.contains_key(*region))
+            .map(|region| format!("{}{}/{}", REGION_KEY_PREFIX, region, key))
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Bucket(&'static str);
+
+    #[async_trait]
+    impl StorageService for Bucket {
+        async fn upload(&self, key: &str, _: Vec<u8>, _: &str) -> anyhow::Result<String> {
+            Ok(self.get_url(key))
+        }
+        async fn start_chunked_upload(
+            &self,
+            _: &str,
+            _: &str,
+        ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+            anyhow::bail!("not used")
+        }
+        async fn delete(&self, _: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn download(&self, _: &str) -> anyhow::Result<Option<Vec<u8>>> {
+            Ok(None)
+        }
+        fn get_url(&self, key: &str) -> String {
+            format!("{}/{}", self.0, key)
+        }
+        async fn signed_url(&self, key: &str, _: Duration) -> anyhow::Result<String> {
+            Ok(self.get_url(key))
+        }
+        fn key_from_url(&self, url: &str) -> Option<String> {
+            url.strip_prefix(self.0)
+                .map(|rest| rest.trim_start_matches('/').to_string())
+        }
+    }
+
+    fn storage() -> RegionalStorage {
+        RegionalStorage::new(Arc::new(Bucket("https://in.cdn"))).with_region(
+            "eu",
+            Arc::new(Bucket("https://eu.cdn")),
+            &["de".to_string(), "FR".to_string()],
+        )
+    }
+
+    #[test]
+    fn routes_keys_by_country() {
+        let storage = storage();
+        assert_eq!(
+            storage.key_for_country("letterings/a.webp", Some("de")),
+            "regions/eu/letterings/a.webp"
+        );
+        assert_eq!(
+            storage.key_for_country("letterings/a.webp", Some("IN")),
+            "letterings/a.webp"
+        );
+        assert_eq!(
+            storage.key_for_country("letterings/a.webp", None),
+            "letterings/a.webp"
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_bucket_from_key_and_url() {
+        let storage = storage();
+        let key = storage.key_for_country("thumbs/a.webp", Some("FR"));
+        let url = storage
+            .upload(&key, Vec::new(), "image/webp")
+            .await
+            .unwrap();
+        assert_eq!(url, "https://eu.cdn/regions/eu/thumbs/a.webp");
+        assert_eq!(storage.key_from_url(&url).as_deref(), Some(key.as_str()));
+
+        assert_eq!(
+            storage.get_url("thumbs/a.webp"),
+            "https://in.cdn/thumbs/a.webp"
+        );
+        assert_eq!(
+            storage.get_url("regions/us/thumbs/a.webp"),
+            "https://in.cdn/regions/us/thumbs/a.webp"
+        );
+    }
+}
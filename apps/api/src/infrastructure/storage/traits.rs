@@ -1,8 +1,39 @@
 use async_trait::async_trait;
+use std::time::Duration;
+
+/// An object being written incrementally. Implementations only hold one
+/// part in memory at a time.
+#[async_trait]
+pub trait ChunkedUpload: Send {
+    async fn write(&mut self, chunk: &[u8]) -> anyhow::Result<()>;
+    /// Flush the remaining data and return the object URL. An upload that
+    /// fails here is discarded, as by `abort`.
+    async fn finish(self: Box<Self>) -> anyhow::Result<String>;
+    /// Discard everything written so far.
+    async fn abort(self: Box<Self>);
+}
 
 #[async_trait]
 pub trait StorageService: Send + Sync {
     async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> anyhow::Result<String>;
+    /// Begin a streamed upload for objects too large to buffer.
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>>;
     async fn delete(&self, key: &str) -> anyhow::Result<()>;
+    /// Read an object back, e.g. to copy it to another key; `None` when
+    /// there is no object at `key`.
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
     fn get_url(&self, key: &str) -> String;
+    /// Time-limited URL for objects that must not be served from the public CDN.
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String>;
+    /// Map a URL returned by `upload`/`get_url` back to its object key.
+    fn key_from_url(&self, url: &str) -> Option<String>;
+    /// Key under which to store `key` for content from `country_code`, so it
+    /// lands in the nearest bucket. Single-bucket storage keeps `key` as is.
+    fn key_for_country(&self, key: &str, _country_code: Option<&str>) -> String {
+        key.to_string()
+    }
 }
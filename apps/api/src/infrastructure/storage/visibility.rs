@@ -0,0 +1,177 @@
+//! Public and private buckets.
+//!
+//! Uploads are kept out of the public buckets until they are approved: they
+//! are stored under `private/{key}`, which routes to a private bucket with no
+//! public URL. Their URLs point at the bucket itself, which answers unsigned
+//! requests with 403, so readers are handed signed URLs. On approval the
+//! object is copied to `{key}` in the public bucket, and copied back when it
+//! stops being public. The prefix is stripped before the private bucket sees
+//! the key, so an object keeps the rest of its key, including any region
+//! prefix, across the move.
+
+use super::traits::{ChunkedUpload, StorageService};
+use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+pub const PRIVATE_KEY_PREFIX: &str = "private/";
+
+/// `key` in the private bucket.
+pub fn private_key(key: &str) -> String {
+    if is_private_key(key) {
+        key.to_string()
+    } else {
+        format!("{}{}", PRIVATE_KEY_PREFIX, key)
+    }
+}
+
+/// `key` in the public bucket.
+pub fn public_key(key: &str) -> &str {
+    key.strip_prefix(PRIVATE_KEY_PREFIX).unwrap_or(key)
+}
+
+pub fn is_private_key(key: &str) -> bool {
+    key.starts_with(PRIVATE_KEY_PREFIX)
+}
+
+/// Key of the upload exactly as received, which is never public.
+pub fn original_key(id: Uuid) -> String {
+    private_key(&legacy_original_key(id))
+}
+
+/// Where originals were kept before they moved to the private bucket.
+pub fn legacy_original_key(id: Uuid) -> String {
+    format!("originals/{}", id)
+}
+
+/// Routes `private/` keys to the private buckets and everything else to the
+/// public ones.
+pub struct PrivateBucketStorage {
+    public: Arc<dyn StorageService>,
+    private: Arc<dyn StorageService>,
+}
+
+impl PrivateBucketStorage {
+    pub fn new(public: Arc<dyn StorageService>, private: Arc<dyn StorageService>) -> Self {
+        Self { public, private }
+    }
+
+    /// Bucket holding `key`, and the key within it.
+    fn bucket_for_key<'a>(&self, key: &'a str) -> (&dyn StorageService, &'a str) {
+        match key.strip_prefix(PRIVATE_KEY_PREFIX) {
+            Some(rest) => (self.private.as_ref(), rest),
+            None => (self.public.as_ref(), key),
+        }
+    }
+}
+
+#[async_trait]
+impl StorageService for PrivateBucketStorage {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> anyhow::Result<String> {
+        let (bucket, key) = self.bucket_for_key(key);
+        bucket.upload(key, data, content_type).await
+    }
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        let (bucket, key) = self.bucket_for_key(key);
+        bucket.start_chunked_upload(key, content_type).await
+    }
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        let (bucket, key) = self.bucket_for_key(key);
+        bucket.delete(key).await
+    }
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let (bucket, key) = self.bucket_for_key(key);
+        bucket.download(key).await
+    }
+    fn get_url(&self, key: &str) -> String {
+        let (bucket, key) = self.bucket_for_key(key);
+        bucket.get_url(key)
+    }
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        let (bucket, key) = self.bucket_for_key(key);
+        bucket.signed_url(key, expires_in).await
+    }
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        self.private
+            .key_from_url(url)
+            .map(|key| private_key(&key))
+            .or_else(|| self.public.key_from_url(url))
+    }
+    fn key_for_country(&self, key: &str, country_code: Option<&str>) -> String {
+        match key.strip_prefix(PRIVATE_KEY_PREFIX) {
+            Some(rest) => private_key(&self.private.key_for_country(rest, country_code)),
+            None => self.public.key_for_country(key, country_code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::storage::in_memory::InMemoryStorage;
+
+    fn storage() -> (PrivateBucketStorage, InMemoryStorage, InMemoryStorage) {
+        let public = InMemoryStorage::new("http://localhost:3000", 1024);
+        let private = InMemoryStorage::new_private("http://localhost:3000", 1024);
+        (
+            PrivateBucketStorage::new(Arc::new(public.clone()), Arc::new(private.clone())),
+            public,
+            private,
+        )
+    }
+
+    #[test]
+    fn keys_move_between_buckets_by_prefix() {
+        assert_eq!(
+            private_key("sha256/ab/ab.webp"),
+            "private/sha256/ab/ab.webp"
+        );
+        assert_eq!(private_key("private/a"), "private/a");
+        assert_eq!(public_key("private/regions/eu/a"), "regions/eu/a");
+        assert_eq!(public_key("a"), "a");
+        let id = Uuid::now_v7();
+        assert!(is_private_key(&original_key(id)));
+        assert_eq!(public_key(&original_key(id)), legacy_original_key(id));
+    }
+
+    #[tokio::test]
+    async fn private_keys_land_in_the_private_bucket() {
+        let (storage, public, private) = storage();
+        let url = storage
+            .upload("private/sha256/ab/ab.webp", vec![1, 2], "image/webp")
+            .await
+            .unwrap();
+        assert_eq!(
+            url,
+            "http://localhost:3000/synthetic-private-storage/sha256/ab/ab.webp"
+        );
+        assert!(private.get("sha256/ab/ab.webp").is_some());
+        assert!(public.get("sha256/ab/ab.webp").is_none());
+        assert_eq!(
+            storage.key_from_url(&url).as_deref(),
+            Some("private/sha256/ab/ab.webp")
+        );
+        assert_eq!(
+            storage.download("private/sha256/ab/ab.webp").await.unwrap(),
+            Some(vec![1, 2])
+        );
+
+        let url = storage
+            .upload("sha256/ab/ab.webp", vec![1, 2], "image/webp")
+            .await
+            .unwrap();
+        assert_eq!(
+            url,
+            "http://localhost:3000/synthetic-storage/sha256/ab/ab.webp"
+        );
+        assert!(public.get("sha256/ab/ab.webp").is_some());
+        assert_eq!(
+            storage.key_from_url(&url).as_deref(),
+            Some("sha256/ab/ab.webp")
+        );
+    }
+}
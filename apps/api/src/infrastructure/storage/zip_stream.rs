@@ -0,0 +1,214 @@
+//! Minimal streaming ZIP writer.
+//!
+//! Entries are stored uncompressed (images are already compressed) and use
+//! trailing data descriptors, so an entry can be written before its size and
+//! CRC are known. The writer does no I/O: each call returns the bytes to
+//! append, which lets callers feed a `ChunkedUpload` without buffering whole
+//! files. Archives are ZIP32, so the total size is capped at 4 GiB.
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const DATA_DESCRIPTOR_SIG: u32 = 0x0807_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4b50;
+const VERSION: u16 = 20;
+/// Bit 3: sizes follow in a data descriptor. Bit 11: names are UTF-8.
+const FLAGS: u16 = 0x0808;
+const STORED: u16 = 0;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+struct OpenEntry {
+    entry: Entry,
+    hasher: crc32fast::Hasher,
+    size: u64,
+}
+
+pub struct ZipStream {
+    entries: Vec<Entry>,
+    open: Option<OpenEntry>,
+    offset: u64,
+    dos_time: u16,
+    dos_date: u16,
+}
+
+impl ZipStream {
+    /// All entries share `modified` as their timestamp.
+    pub fn new(modified: DateTime<Utc>) -> Self {
+        let year = (modified.year().clamp(1980, 2107) - 1980) as u16;
+        Self {
+            entries: Vec::new(),
+            open: None,
+            offset: 0,
+            dos_time: ((modified.hour() as u16) << 11)
+                | ((modified.minute() as u16) << 5)
+                | (modified.second() as u16 / 2),
+            dos_date: (year << 9) | ((modified.month() as u16) << 5) | modified.day() as u16,
+        }
+    }
+
+    /// Start a new entry, closing the previous one if it is still open.
+    pub fn begin_entry(&mut self, name: &str) -> anyhow::Result<Vec<u8>> {
+        let mut out = self.end_entry()?;
+        let offset = self.checked_offset()?;
+        let header_start = out.len();
+
+        out.extend_from_slice(&LOCAL_HEADER_SIG.to_le_bytes());
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&FLAGS.to_le_bytes());
+        out.extend_from_slice(&STORED.to_le_bytes());
+        out.extend_from_slice(&self.dos_time.to_le_bytes());
+        out.extend_from_slice(&self.dos_date.to_le_bytes());
+        out.extend_from_slice(&[0; 12]); // crc, compressed and uncompressed size
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+        self.offset += (out.len() - header_start) as u64;
+
+        self.open = Some(OpenEntry {
+            entry: Entry {
+                name: name.to_string(),
+                crc: 0,
+                size: 0,
+                offset,
+            },
+            hasher: crc32fast::Hasher::new(),
+            size: 0,
+        });
+        Ok(out)
+    }
+
+    /// Account for entry data the caller is about to append as-is.
+    pub fn write(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        let open = self
+            .open
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("no zip entry is open"))?;
+        open.hasher.update(data);
+        open.size += data.len() as u64;
+        self.offset += data.len() as u64;
+        self.checked_offset().map(|_| ())
+    }
+
+    /// Close the open entry (if any) and return its data descriptor.
+    pub fn end_entry(&mut self) -> anyhow::Result<Vec<u8>> {
+        let Some(open) = self.open.take() else {
+            return Ok(Vec::new());
+        };
+        let mut entry = open.entry;
+        entry.crc = open.hasher.finalize();
+        entry.size = u32::try_from(open.size)
+            .map_err(|_| anyhow::anyhow!("zip entry {} exceeds 4 GiB", entry.name))?;
+
+        let mut out = Vec::with_capacity(16);
+        out.extend_from_slice(&DATA_DESCRIPTOR_SIG.to_le_bytes());
+        out.extend_from_slice(&entry.crc.to_le_bytes());
+        out.extend_from_slice(&entry.size.to_le_bytes());
+        out.extend_from_slice(&entry.size.to_le_bytes());
+        self.offset += out.len() as u64;
+        self.entries.push(entry);
+        Ok(out)
+    }
+
+    /// Close the archive and return the central directory.
+    pub fn finish(mut self) -> anyhow::Result<Vec<u8>> {
+        let mut out = self.end_entry()?;
+        let directory_offset = self.checked_offset()?;
+        let directory_start = out.len();
+
+        for entry in &self.entries {
+            out.extend_from_slice(&CENTRAL_HEADER_SIG.to_le_bytes());
+            out.extend_from_slice(&VERSION.to_le_bytes()); // made by
+            out.extend_from_slice(&VERSION.to_le_bytes()); // needed
+            out.extend_from_slice(&FLAGS.to_le_bytes());
+            out.extend_from_slice(&STORED.to_le_bytes());
+            out.extend_from_slice(&self.dos_time.to_le_bytes());
+            out.extend_from_slice(&self.dos_date.to_le_bytes());
+            out.extend_from_slice(&entry.crc.to_le_bytes());
+            out.extend_from_slice(&entry.size.to_le_bytes());
+            out.extend_from_slice(&entry.size.to_le_bytes());
+            out.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            out.extend_from_slice(&entry.offset.to_le_bytes());
+            out.extend_from_slice(entry.name.as_bytes());
+        }
+
+        let directory_size = (out.len() - directory_start) as u32;
+        let count = u16::try_from(self.entries.len())
+            .map_err(|_| anyhow::anyhow!("too many zip entries"))?;
+        out.extend_from_slice(&END_OF_CENTRAL_DIR_SIG.to_le_bytes());
+        out.extend_from_slice(&[0; 4]); // disk numbers
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        out.extend_from_slice(&directory_size.to_le_bytes());
+        out.extend_from_slice(&directory_offset.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes());
+        Ok(out)
+    }
+
+    /// Archive bytes returned or accounted for so far.
+    pub fn bytes_written(&self) -> u64 {
+        self.offset
+    }
+
+    fn checked_offset(&self) -> anyhow::Result<u32> {
+        u32::try_from(self.offset).map_err(|_| anyhow::anyhow!("zip archive exceeds 4 GiB"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(buf: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([buf[at], buf[at + 1]])
+    }
+
+    fn u32_at(buf: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(buf[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn writes_a_readable_directory() {
+        let mut zip = ZipStream::new(Utc::now());
+        let mut archive = zip.begin_entry("a.txt").unwrap();
+        zip.write(b"hello").unwrap();
+        archive.extend_from_slice(b"hello");
+        archive.extend(zip.begin_entry("images/b.bin").unwrap());
+        zip.write(&[1, 2, 3]).unwrap();
+        archive.extend_from_slice(&[1, 2, 3]);
+        archive.extend(zip.finish().unwrap());
+
+        let eocd = archive.len() - 22;
+        assert_eq!(u32_at(&archive, eocd), END_OF_CENTRAL_DIR_SIG);
+        assert_eq!(u16_at(&archive, eocd + 10), 2);
+        let directory = u32_at(&archive, eocd + 16) as usize;
+        assert_eq!(directory + u32_at(&archive, eocd + 12) as usize, eocd);
+
+        // First central record points back at a local header and carries the
+        // CRC-32 of "hello".
+        assert_eq!(u32_at(&archive, directory), CENTRAL_HEADER_SIG);
+        assert_eq!(u32_at(&archive, directory + 16), 0x3610_a686);
+        assert_eq!(u32_at(&archive, directory + 24), 5);
+        assert_eq!(
+            u32_at(&archive, u32_at(&archive, directory + 42) as usize),
+            LOCAL_HEADER_SIG
+        );
+
+        let second = directory + 46 + "a.txt".len();
+        let offset = u32_at(&archive, second + 42) as usize;
+        assert_eq!(u32_at(&archive, offset), LOCAL_HEADER_SIG);
+        assert_eq!(&archive[offset + 30..offset + 42], b"images/b.bin");
+    }
+
+    #[test]
+    fn data_outside_an_entry_is_rejected() {
+        assert!(ZipStream::new(Utc::now()).write(b"x").is_err());
+    }
+}
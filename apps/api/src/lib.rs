@@ -1,3 +1,5 @@
+#![recursion_limit = "256"]
+
 pub mod application;
 pub mod config;
 pub mod domain;
@@ -1,26 +1,80 @@
 use api::{
-    config::Config,
+    config::{Config, StorageRegionConfig},
     infrastructure::{
-        cache::redis_cache::RedisCache, database::pool::create_pool,
-        ml::onnx_text_detector::OnnxTextDetector, queue::redis_queue::RedisQueue,
-        repositories::sqlx_lettering_repository::SqlxLetteringRepository,
+        cache::{
+            invalidation::CacheInvalidator, redis_cache::RedisCache, tiered_cache::TieredCache,
+        },
+        cdn::cloudflare_purge::CloudflarePurger,
+        database::{pool::create_pool, schema_check::check_schema},
+        email::http_mailer::HttpMailer,
+        fault_injection::FaultInjector,
+        feature_flags,
+        geocoding::ip_geolocation::IpGeolocator,
+        leadership::LeaderElection,
+        ml::circuit_breaker::CircuitBreaker,
+        ml::onnx_style_classifier::OnnxStyleClassifier,
+        ml::onnx_text_detector::OnnxTextDetector,
+        ml::remote_inference_cache::RemoteInferenceCache,
+        ml::tesseract_service::TesseractService,
+        monitoring::{PerformanceMonitor, heartbeat::Heartbeats, throttle::WorkerThrottle},
+        queue::{jobs::JobRunner, redis_queue::RedisQueue},
+        repositories::sqlx_lettering_repository::{SearchRankingWeights, SqlxLetteringRepository},
         repositories::sqlx_social_repository::SqlxSocialRepository,
-        security::virus_scanner::VirusScanner, storage::r2_storage_service::R2StorageService,
+        security::{app_attestation::AppAttestationVerifier, virus_scanner::VirusScanner},
+        shutdown::Shutdown,
+        storage::{
+            fault_injecting::FaultInjectingStorage, in_memory::InMemoryStorage,
+            r2_storage_service::R2StorageService, regional::RegionalStorage,
+            traits::StorageService, visibility::PrivateBucketStorage,
+        },
+    },
+    presentation::http::{
+        handlers::{
+            cities, gallery, geo, social::NEXT_CURSOR_HEADER, synthetic_storage, ws::WsDrain,
+        },
+        middleware::rate_limit,
+        routes::create_router,
+        state::AppState,
     },
-    presentation::http::{routes::create_router, state::AppState},
     workers::{
-        analytics_worker::AnalyticsWorker, ml_processor::MlProcessor,
+        activity_fanout::ActivityFanout,
+        analytics_worker::AnalyticsWorker,
+        backup_exporter::BackupExporter,
+        backup_snapshot::BackupSnapshotWorker,
+        cache_warmer::CacheWarmer,
+        cdn_purge_retry::CdnPurgeRetryWorker,
+        follow_notifier::FollowNotifier,
+        geo_retention::GeoRetentionWorker,
+        image_publisher::ImagePublisherWorker,
+        integrity_verifier::IntegrityVerifier,
+        ip_anonymizer::IpAnonymizer,
+        like_digest::LikeDigestWorker,
+        ml_processor::{ConfidenceThresholds, MlProcessor},
+        ml_reprocess::MlReprocessWorker,
+        model_watcher::ModelWatcher,
+        notification_delivery::NotificationDelivery,
         pending_auto_approve::PendingAutoApproveWorker,
+        pending_escalation::PendingEscalationWorker,
+        print_bundle::PrintBundleWorker,
+        review_priority::ReviewPriorityWorker,
+        saved_search_notifier::SavedSearchNotifier,
+        scheduler::Scheduler,
+        storage_gc::StorageGcWorker,
+        weekly_digest::WeeklyDigestWorker,
     },
 };
 use axum::extract::DefaultBodyLimit;
-use http::{HeaderValue, Method, header};
+use futures_util::future::join_all;
+use http::{HeaderName, HeaderValue, Method, header};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 use tower_http::cors::{AllowOrigin, CorsLayer};
 use tower_http::set_header::SetResponseHeaderLayer;
 
+/// Cap on what `SYNTHETIC_MODE` keeps in memory; older objects are evicted.
+const SYNTHETIC_STORAGE_MAX_BYTES: usize = 512 * 1024 * 1024;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
@@ -33,27 +87,132 @@ async fn main() -> anyhow::Result<()> {
 
     tracing_subscriber::fmt().with_env_filter(env_filter).init();
 
-    let config = Config::from_env()?;
+    let mut config = Config::from_env()?;
+    config.apply_synthetic_mode();
+    if config.synthetic_mode {
+        tracing::warn!(
+            "SYNTHETIC_MODE is set: storage is in memory and external integrations are disabled"
+        );
+    }
     let db = create_pool(&config.database_url, config.database_max_connections).await?;
     let mut migrator = sqlx::migrate!("./migrations");
     migrator.set_ignore_missing(config.ignore_missing_migrations);
     migrator.run(&db).await?;
 
+    let schema = check_schema(&db, &migrator).await?;
+    if schema.is_compatible() {
+        tracing::info!("Schema check passed: {}", schema);
+    } else if config.enforce_schema_check {
+        tracing::error!("Schema check failed: {}", schema);
+        anyhow::bail!("refusing to serve traffic: {}", schema);
+    } else {
+        tracing::warn!("Schema check failed, serving anyway: {}", schema);
+    }
+
+    let timezone_known: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1)")
+            .bind(&config.day_boundary_timezone)
+            .fetch_one(&db)
+            .await?;
+    if !timezone_known {
+        anyhow::bail!(
+            "DAY_BOUNDARY_TIMEZONE `{}` is not a time zone the database knows",
+            config.day_boundary_timezone
+        );
+    }
+
     let redis = redis::Client::open(config.redis_url.clone())?;
-    let cache = Arc::new(RedisCache::new(redis.clone()));
-    let queue = Arc::new(RedisQueue::new(redis.clone()));
-    let storage = Arc::new(
-        R2StorageService::new(
-            config.r2_access_key_id.clone(),
-            config.r2_secret_access_key.clone(),
-            config.r2_endpoint.clone(),
-            config.r2_region.clone(),
-            config.r2_force_path_style,
-            config.r2_bucket_name.clone(),
+    match rate_limit::sync_overrides(&db, &redis).await {
+        Ok(count) => tracing::info!("Loaded {} rate limit overrides", count),
+        Err(e) => tracing::warn!("Failed to load rate limit overrides: {}", e),
+    }
+    match feature_flags::sync_flags(&db, &redis).await {
+        Ok(count) => tracing::info!("Loaded {} feature flags", count),
+        Err(e) => tracing::warn!("Failed to load feature flags: {}", e),
+    }
+    let faults = FaultInjector::from_config(&config);
+    let mut cache = RedisCache::new(redis.clone());
+    let mut queue = RedisQueue::new(redis.clone())
+        .with_ml_max_attempts(config.ml_max_attempts)
+        .with_idempotency_window(Duration::from_secs(config.job_idempotency_window_seconds));
+    if let Some(faults) = &faults {
+        cache = cache.with_fault_injector(faults.clone());
+        queue = queue.with_fault_injector(faults.clone());
+    }
+    let cache = Arc::new(cache);
+    let queue = Arc::new(queue);
+    // Each bucket has a private twin holding images until they are approved.
+    let mut public_storage = RegionalStorage::new(
+        r2_bucket(
+            &config,
+            None,
+            &config.r2_bucket_name,
             config.r2_public_url.clone(),
         )
         .await?,
     );
+    let mut private_storage = RegionalStorage::new(
+        r2_bucket(
+            &config,
+            None,
+            &config.r2_private_bucket_name,
+            private_bucket_url(&config.r2_endpoint, &config.r2_private_bucket_name),
+        )
+        .await?,
+    );
+    for region in &config.storage_regions {
+        let endpoint = region.endpoint.as_deref().unwrap_or(&config.r2_endpoint);
+        public_storage = public_storage.with_region(
+            &region.name,
+            r2_bucket(
+                &config,
+                Some(region),
+                &region.bucket_name,
+                region.public_url.clone(),
+            )
+            .await?,
+            &region.countries,
+        );
+        private_storage = private_storage.with_region(
+            &region.name,
+            r2_bucket(
+                &config,
+                Some(region),
+                &region.private_bucket_name,
+                private_bucket_url(endpoint, &region.private_bucket_name),
+            )
+            .await?,
+            &region.countries,
+        );
+        tracing::info!(
+            region = %region.name,
+            countries = ?region.countries,
+            "Regional storage bucket configured"
+        );
+    }
+    let memory_storage = config.synthetic_mode.then(|| {
+        let base_url = format!("http://127.0.0.1:{}", config.port);
+        (
+            Arc::new(InMemoryStorage::new(&base_url, SYNTHETIC_STORAGE_MAX_BYTES)),
+            Arc::new(InMemoryStorage::new_private(
+                &base_url,
+                SYNTHETIC_STORAGE_MAX_BYTES,
+            )),
+        )
+    });
+    let storage: Arc<dyn StorageService> = match &memory_storage {
+        Some((public, private)) => {
+            Arc::new(PrivateBucketStorage::new(public.clone(), private.clone()))
+        }
+        None => Arc::new(PrivateBucketStorage::new(
+            Arc::new(public_storage),
+            Arc::new(private_storage),
+        )),
+    };
+    let storage: Arc<dyn StorageService> = match &faults {
+        Some(faults) => Arc::new(FaultInjectingStorage::new(storage, faults.clone())),
+        None => storage,
+    };
 
     let virus_scanner = Arc::new(VirusScanner::new(
         config.enable_virus_scan,
@@ -65,36 +224,252 @@ async fn main() -> anyhow::Result<()> {
 
     let (tx, _) = broadcast::channel(100);
     let broadcaster = Arc::new(tx);
-    let detector = Arc::new(OnnxTextDetector::new(
-        &config.ml_model_path,
-        config.enable_ml_processing,
-    )?);
+    let performance = Arc::new(PerformanceMonitor::new());
+    let hot_cache = Arc::new(
+        TieredCache::new(
+            cache.clone(),
+            redis.clone(),
+            config.local_cache_max_entries,
+            Duration::from_secs(config.local_cache_ttl_seconds),
+        )
+        .with_performance_monitor(performance.clone()),
+    );
+    let invalidator = CacheInvalidator::new(db.clone(), hot_cache.clone());
+    let detector = Arc::new(
+        OnnxTextDetector::new(
+            &config.ml_model_path,
+            config.enable_ml_processing,
+            config.ml_golden_image_path.clone(),
+            &config.ml_execution_providers,
+            config.ml_gpu_device_id,
+        )?
+        .with_performance_monitor(performance.clone()),
+    );
+
+    let ocr = Arc::new(TesseractService::new(
+        config.enable_tesseract_fallback,
+        &config.tesseract_languages,
+    ));
+
+    let cdn_purger = Arc::new(CloudflarePurger::new(
+        config.cloudflare_zone_id.clone(),
+        config.cloudflare_api_token.clone(),
+        queue.clone(),
+    ));
+
+    let ip_geolocator = Arc::new(IpGeolocator::new(
+        db.clone(),
+        config.ip_geo_lookup_url.clone(),
+        config.ip_geo_refresh_days,
+    ));
+
+    let ws_drain = Arc::new(WsDrain::new(config.ws_reconnect_hint_ms));
 
     let state = AppState {
         db: db.clone(),
         redis,
-        cache,
+        cache: cache.clone(),
+        hot_cache: hot_cache.clone(),
         storage,
         ml_detector: detector.clone(),
+        onnx_detector: detector.clone(),
+        ocr: ocr.clone(),
         queue,
         virus_scanner,
+        attestation: Arc::new(AppAttestationVerifier::from_config(&config)),
         config: config.clone(),
-        lettering_repo: Arc::new(SqlxLetteringRepository::new(db.clone())),
+        lettering_repo: Arc::new(
+            SqlxLetteringRepository::new(db.clone())
+                .with_ranking(SearchRankingWeights::from(&config))
+                .with_invalidator(invalidator.clone())
+                .with_negative_cache(cache.clone(), config.not_found_cache_ttl_seconds),
+        ),
         social_repo: Arc::new(SqlxSocialRepository::new(db.clone())),
         ws_broadcaster: broadcaster.clone(),
+        ws_drain: ws_drain.clone(),
+        cdn_purger: cdn_purger.clone(),
+        ip_geolocator: ip_geolocator.clone(),
+        performance: performance.clone(),
+        faults: faults.clone(),
     };
 
-    let ml_worker = MlProcessor::new(
+    // Every worker beats into Redis for GET /api/v1/admin/workers, and
+    // winds down once `shutdown` fires; `main` waits on `workers` before
+    // exiting.
+    let heartbeats = Heartbeats::new(state.redis.clone());
+    let shutdown = Shutdown::new();
+    let mut workers = Vec::new();
+
+    let invalidation_shutdown = shutdown.clone();
+    workers.push(tokio::spawn(async move {
+        hot_cache
+            .listen_for_invalidations(invalidation_shutdown)
+            .await
+    }));
+
+    if config.cache_warm_interval_seconds > 0 {
+        let warmer = CacheWarmer::new(
+            state.clone(),
+            Duration::from_secs(config.cache_warm_interval_seconds),
+        )
+        .with_target("front_page", gallery::warm_front_page)
+        .with_target("top_cities", cities::warm_top_cities)
+        .with_target("world_map", geo::warm_world_map)
+        .with_cache_invalidator(invalidator.clone())
+        .with_heartbeats(heartbeats.clone())
+        .with_shutdown(shutdown.clone());
+        workers.push(tokio::spawn(async move { warmer.start().await }));
+    }
+
+    let remote_cache = Arc::new(
+        RemoteInferenceCache::new(
+            db.clone(),
+            state.redis.clone(),
+            config.huggingface_daily_call_budget,
+        )
+        .with_performance_monitor(performance.clone()),
+    );
+    let hf_breaker = Arc::new(
+        CircuitBreaker::new(
+            "huggingface",
+            config.huggingface_breaker_failure_threshold,
+            Duration::from_secs(config.huggingface_breaker_cool_down_seconds),
+        )
+        .with_performance_monitor(performance.clone()),
+    );
+    if config.enable_ml_processing && config.enable_ml_model_watch {
+        let model_watcher = ModelWatcher::new(
+            detector.clone(),
+            config.ml_model_path.clone(),
+            config.ml_model_watch_interval_seconds,
+        )
+        .with_heartbeats(heartbeats.clone())
+        .with_shutdown(shutdown.clone());
+        workers.push(tokio::spawn(async move { model_watcher.start().await }));
+    }
+
+    let shadow_detector = match config
+        .ml_shadow_model_path
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+    {
+        Some(path) if config.enable_ml_processing => Some(Arc::new(
+            OnnxTextDetector::new(
+                path,
+                true,
+                config.ml_golden_image_path.clone(),
+                &config.ml_execution_providers,
+                config.ml_gpu_device_id,
+            )?
+            .with_performance_monitor(performance.clone()),
+        )),
+        _ => None,
+    };
+
+    let style_classifier = Arc::new(OnnxStyleClassifier::new(
+        &config.ml_style_model_path,
+        config.enable_ml_processing,
+        config.ml_style_min_confidence,
+    )?);
+
+    let mut ml_worker = MlProcessor::new(
         db.clone(),
         detector,
+        shadow_detector,
+        style_classifier,
+        ocr,
         state.queue.clone(),
         config.huggingface_token.clone(),
+        remote_cache,
+        hf_breaker,
+        ConfidenceThresholds {
+            text: config.ml_text_confidence_threshold,
+            style: config.ml_style_confidence_threshold,
+            script: config.ml_script_confidence_threshold,
+        },
+        config.ml_batch_size,
         broadcaster,
-    );
-    tokio::spawn(async move { ml_worker.start().await });
+    )
+    .with_concurrency(config.ml_concurrency)
+    .with_performance_monitor(performance.clone())
+    .with_heartbeats(heartbeats.clone())
+    .with_shutdown(shutdown.clone())
+    .with_cache_invalidator(invalidator.clone())
+    .with_storage(state.storage.clone());
+    if let Some(faults) = &faults {
+        ml_worker = ml_worker.with_fault_injector(faults.clone());
+    }
+    workers.push(tokio::spawn(async move { ml_worker.start().await }));
 
-    let analytics = AnalyticsWorker::new(db.clone());
-    tokio::spawn(async move { analytics.start().await });
+    let notifications = JobRunner::new(
+        state.queue.notifications().clone(),
+        NotificationDelivery::new(db.clone()),
+    )
+    .with_heartbeats(heartbeats.clone(), "notification_delivery")
+    .with_shutdown(shutdown.clone());
+    workers.push(tokio::spawn(async move { notifications.start().await }));
+
+    // Workers that sweep shared tables run on one instance at a time; the
+    // others stand by to take over when it dies.
+    let leadership = if config.leader_lease_seconds > 0 {
+        LeaderElection::new(
+            state.redis.clone(),
+            Duration::from_secs(config.leader_lease_seconds),
+        )
+    } else {
+        LeaderElection::local()
+    }
+    .with_shutdown(shutdown.clone());
+
+    let ml_reprocess = MlReprocessWorker::new(
+        db.clone(),
+        state.queue.clone(),
+        config.ml_reprocess_rate_per_minute,
+    )
+    .with_heartbeats(heartbeats.clone())
+    .with_shutdown(shutdown.clone());
+    let leader = leadership.clone();
+    workers.push(tokio::spawn(async move {
+        leader.run("ml_reprocess", || ml_reprocess.start()).await
+    }));
+
+    // Shared by the periodic maintenance workers below; the ML worker and CDN
+    // purge retries run unthrottled since users wait on them.
+    let throttle = WorkerThrottle::spawn(performance.clone(), db.clone())
+        .with_heartbeats(heartbeats.clone())
+        .with_shutdown(shutdown.clone());
+
+    // Periodic maintenance runs on cron schedules, each tick on one instance.
+    let mut scheduler = Scheduler::new(db.clone(), state.redis.clone(), config.schedules.clone())
+        .with_throttle(throttle.clone())
+        .with_task(
+            AnalyticsWorker::new(db.clone())
+                .with_timezone(config.day_boundary_timezone.clone())
+                .with_throttle(throttle.clone()),
+        );
+
+    let print_bundles = PrintBundleWorker::new(
+        db.clone(),
+        state.storage.clone(),
+        config.public_base_url.clone(),
+    )
+    .with_heartbeats(heartbeats.clone())
+    .with_shutdown(shutdown.clone());
+    workers.push(tokio::spawn(async move { print_bundles.start().await }));
+
+    let backup_snapshots = BackupSnapshotWorker::new(db.clone(), state.storage.clone())
+        .with_heartbeats(heartbeats.clone())
+        .with_shutdown(shutdown.clone());
+    workers.push(tokio::spawn(async move { backup_snapshots.start().await }));
+
+    let storage_gc =
+        StorageGcWorker::new(db.clone(), state.storage.clone()).with_throttle(throttle.clone());
+    workers.push(tokio::spawn(async move { storage_gc.start().await }));
+
+    let image_publisher = ImagePublisherWorker::new(db.clone(), state.storage.clone())
+        .with_throttle(throttle.clone())
+        .with_cache_invalidator(invalidator.clone());
+    workers.push(tokio::spawn(async move { image_publisher.start().await }));
 
     if config.enable_pending_auto_approve {
         let pending_worker = PendingAutoApproveWorker::new(
@@ -103,8 +478,192 @@ async fn main() -> anyhow::Result<()> {
             config.pending_auto_approve_minutes,
             config.pending_auto_approve_interval_seconds,
             config.pending_auto_approve_batch_size,
+            config.pending_auto_approve_exclusions.clone(),
+            config.pending_auto_approve_dry_run,
+        )
+        .with_performance_monitor(performance.clone())
+        .with_throttle(throttle.clone())
+        .with_cache_invalidator(invalidator.clone())
+        .with_storage(state.storage.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader
+                .run("pending_auto_approve", || pending_worker.start())
+                .await
+        }));
+    }
+
+    if config.enable_pending_escalation {
+        let escalation_worker = PendingEscalationWorker::new(
+            db.clone(),
+            config.pending_escalation_hours,
+            config.pending_escalation_interval_seconds,
+            !config.pending_escalation_reviewers.is_empty(),
+        )
+        .with_performance_monitor(performance.clone())
+        .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader
+                .run("pending_escalation", || escalation_worker.start())
+                .await
+        }));
+    }
+
+    if config.enable_review_priority {
+        let priority_worker = ReviewPriorityWorker::new(
+            db.clone(),
+            config.report_anonymous_weight,
+            config.review_priority_interval_seconds,
+        )
+        .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader
+                .run("review_priority", || priority_worker.start())
+                .await
+        }));
+    }
+
+    if cdn_purger.is_enabled() {
+        let purge_worker = CdnPurgeRetryWorker::new(cdn_purger, state.queue.clone())
+            .with_heartbeats(heartbeats.clone())
+            .with_shutdown(shutdown.clone());
+        workers.push(tokio::spawn(async move { purge_worker.start().await }));
+    }
+
+    scheduler = scheduler.with_task(GeoRetentionWorker::new(
+        ip_geolocator,
+        config.ip_geo_retention_days,
+    ));
+
+    if let Some(bucket) = config
+        .backup_r2_bucket_name
+        .clone()
+        .filter(|b| !b.trim().is_empty())
+    {
+        let backup_storage = Arc::new(
+            R2StorageService::new(
+                config
+                    .backup_r2_access_key_id
+                    .clone()
+                    .unwrap_or_else(|| config.r2_access_key_id.clone()),
+                config
+                    .backup_r2_secret_access_key
+                    .clone()
+                    .unwrap_or_else(|| config.r2_secret_access_key.clone()),
+                config
+                    .backup_r2_endpoint
+                    .clone()
+                    .unwrap_or_else(|| config.r2_endpoint.clone()),
+                config
+                    .backup_r2_region
+                    .clone()
+                    .unwrap_or_else(|| config.r2_region.clone()),
+                config.r2_force_path_style,
+                bucket.clone(),
+                format!("backup://{}", bucket),
+            )
+            .await?,
         );
-        tokio::spawn(async move { pending_worker.start().await });
+        scheduler = scheduler.with_task(BackupExporter::new(
+            db.clone(),
+            state.storage.clone(),
+            backup_storage,
+        ));
+    }
+    workers.push(tokio::spawn(async move { scheduler.start().await }));
+
+    if config.enable_ip_anonymization {
+        let anonymizer = IpAnonymizer::new(
+            db.clone(),
+            config.ip_retention_days,
+            config.ip_anonymization_mode,
+            config.ip_anonymization_interval_seconds,
+        )
+        .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader.run("ip_anonymizer", || anonymizer.start()).await
+        }));
+    }
+
+    if config.enable_saved_search_notifications {
+        let saved_searches =
+            SavedSearchNotifier::new(db.clone(), config.saved_search_interval_seconds)
+                .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader
+                .run("saved_search_notifier", || saved_searches.start())
+                .await
+        }));
+    }
+
+    if config.enable_follow_notifications {
+        let follows = FollowNotifier::new(db.clone(), config.follow_notification_interval_seconds)
+            .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader.run("follow_notifier", || follows.start()).await
+        }));
+    }
+
+    if config.enable_like_digest {
+        let like_digests = LikeDigestWorker::new(
+            db.clone(),
+            config.like_digest_window_minutes,
+            config.like_digest_interval_seconds,
+        )
+        .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader.run("like_digest", || like_digests.start()).await
+        }));
+    }
+
+    if config.enable_activity_feed {
+        let activity = ActivityFanout::new(db.clone(), config.activity_feed_interval_seconds)
+            .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader.run("activity_fanout", || activity.start()).await
+        }));
+    }
+
+    let mailer = Arc::new(HttpMailer::new(
+        config.email_api_url.clone(),
+        config.email_api_key.clone(),
+        config.email_from.clone(),
+    ));
+    if config.enable_weekly_digest && mailer.is_enabled() {
+        let digests = WeeklyDigestWorker::new(
+            db.clone(),
+            mailer,
+            config.public_base_url.clone(),
+            config.weekly_digest_interval_seconds,
+        )
+        .with_throttle(throttle.clone());
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader.run("weekly_digest", || digests.start()).await
+        }));
+    }
+
+    if config.enable_integrity_verification {
+        let integrity_worker = IntegrityVerifier::new(
+            db.clone(),
+            state.storage.clone(),
+            config.integrity_verification_interval_seconds,
+            config.integrity_verification_sample_size,
+        )
+        .with_throttle(throttle);
+        let leader = leadership.clone();
+        workers.push(tokio::spawn(async move {
+            leader
+                .run("integrity_verifier", || integrity_worker.start())
+                .await
+        }));
     }
 
     // Configure CORS
@@ -120,6 +679,7 @@ async fn main() -> anyhow::Result<()> {
                 Method::OPTIONS,
             ])
             .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION, header::ACCEPT])
+            .expose_headers([HeaderName::from_static(NEXT_CURSOR_HEADER)])
             .max_age(Duration::from_secs(3600))
     } else {
         // Production: use explicitly configured origins.
@@ -155,10 +715,17 @@ async fn main() -> anyhow::Result<()> {
                 Method::OPTIONS,
             ])
             .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION, header::ACCEPT])
+            .expose_headers([HeaderName::from_static(NEXT_CURSOR_HEADER)])
             .max_age(Duration::from_secs(3600))
     };
 
-    let app = create_router(state)
+    let mut app = create_router(state);
+    if let Some((public, private)) = memory_storage {
+        app = app
+            .merge(synthetic_storage::router(public))
+            .merge(synthetic_storage::router(private));
+    }
+    let app = app
         .layer(DefaultBodyLimit::max(20 * 1024 * 1024))
         .layer(cors)
         .layer(SetResponseHeaderLayer::overriding(
@@ -177,12 +744,80 @@ async fn main() -> anyhow::Result<()> {
     let addr = format!("{}:{}", config.host, config.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     tracing::info!("ARCHIVE ONLINE AT {}", addr);
+    let draining = ws_drain.clone();
+    let stopping = shutdown.clone();
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown(async move {
+            shutdown_signal().await;
+            draining.begin();
+            stopping.trigger();
+        })
         .await?;
+
+    // Upgraded WebSocket connections outlive `serve`; give their clients
+    // until the drain timeout to acknowledge the close frame.
+    let open = ws_drain.open_connections();
+    if open > 0 {
+        tracing::info!(open, "Draining WebSocket connections");
+        let remaining = ws_drain
+            .wait(Duration::from_secs(config.ws_drain_timeout_seconds))
+            .await;
+        if remaining > 0 {
+            tracing::warn!(remaining, "WebSocket drain timed out, dropping connections");
+        }
+    }
+
+    // Workers saw `shutdown` fire with `serve`; let them finish the jobs in
+    // hand. Anything cut off here is picked up again once its visibility
+    // timeout passes.
+    tracing::info!(workers = workers.len(), "Draining background workers");
+    let drain = Duration::from_secs(config.worker_drain_timeout_seconds);
+    if tokio::time::timeout(drain, join_all(workers))
+        .await
+        .is_err()
+    {
+        tracing::warn!("Worker drain timed out, unfinished jobs will be retried");
+    }
+    heartbeats.clear().await;
     Ok(())
 }
 
+/// An R2 bucket reached with the primary `R2_*` settings, or with those
+/// `region` overrides.
+async fn r2_bucket(
+    config: &Config,
+    region: Option<&StorageRegionConfig>,
+    bucket: &str,
+    public_url: String,
+) -> anyhow::Result<Arc<dyn StorageService>> {
+    let setting = |value: Option<&Option<String>>, default: &String| {
+        value
+            .and_then(Option::clone)
+            .unwrap_or_else(|| default.clone())
+    };
+    Ok(Arc::new(
+        R2StorageService::new(
+            setting(region.map(|r| &r.access_key_id), &config.r2_access_key_id),
+            setting(
+                region.map(|r| &r.secret_access_key),
+                &config.r2_secret_access_key,
+            ),
+            setting(region.map(|r| &r.endpoint), &config.r2_endpoint),
+            setting(region.map(|r| &r.region), &config.r2_region),
+            config.r2_force_path_style,
+            bucket.to_string(),
+            public_url,
+        )
+        .await?,
+    ))
+}
+
+/// URL of a private bucket's objects. It has no public domain, so this is
+/// the bucket's API endpoint, which refuses unsigned reads.
+fn private_bucket_url(endpoint: &str, bucket: &str) -> String {
+    format!("{}/{}", endpoint.trim_end_matches('/'), bucket)
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
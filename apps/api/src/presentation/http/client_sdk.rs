@@ -0,0 +1,373 @@
+//! Route table for generated API clients.
+//!
+//! Handler DTOs derive `ts_rs::TS` with `#[ts(export)]`, so `cargo test`
+//! writes them to `bindings/` next to the domain types. [`ENDPOINTS`] ties
+//! them to routes, and the test below renders it as `bindings/Endpoints.ts`:
+//! a typed map from operation name to method, path, query, body and response
+//! that the web and mobile clients build their request helpers on. Adding a
+//! typed route means deriving `TS` on its DTOs and listing it here.
+
+/// One route with the exported type names it takes and returns. `response`
+/// may end in `[]` for a JSON array.
+#[derive(Debug, Clone, Copy)]
+pub struct Endpoint {
+    pub name: &'static str,
+    pub method: &'static str,
+    /// Path as routed, with `{param}` placeholders.
+    pub path: &'static str,
+    pub query: Option<&'static str>,
+    pub body: Option<&'static str>,
+    pub response: &'static str,
+}
+
+const fn get(
+    name: &'static str,
+    path: &'static str,
+    query: Option<&'static str>,
+    response: &'static str,
+) -> Endpoint {
+    Endpoint {
+        name,
+        method: "GET",
+        path,
+        query,
+        body: None,
+        response,
+    }
+}
+
+pub const ENDPOINTS: &[Endpoint] = &[
+    get(
+        "discoverLetterings",
+        "/api/v1/letterings/discover",
+        Some("DiscoverQuery"),
+        "DiscoverResponse",
+    ),
+    get(
+        "listScripts",
+        "/api/v1/browse/scripts",
+        Some("BrowseFacetsQuery"),
+        "BrowseFacet[]",
+    ),
+    get(
+        "getScriptLetterings",
+        "/api/v1/browse/scripts/{script}",
+        Some("BrowseLetteringsQuery"),
+        "PaginatedResponse",
+    ),
+    get(
+        "listStyles",
+        "/api/v1/browse/styles",
+        Some("BrowseFacetsQuery"),
+        "BrowseFacet[]",
+    ),
+    get(
+        "getStyleLetterings",
+        "/api/v1/browse/styles/{style}",
+        Some("BrowseLetteringsQuery"),
+        "PaginatedResponse",
+    ),
+    get(
+        "getLeaderboards",
+        "/api/v1/leaderboards/{period}",
+        None,
+        "Leaderboards",
+    ),
+    get(
+        "getNearbyLetterings",
+        "/api/v1/letterings/nearby",
+        Some("NearbyLetteringsQuery"),
+        "NearbyLettering[]",
+    ),
+    get(
+        "getMapFeatures",
+        "/api/v1/letterings/map",
+        Some("MapQuery"),
+        "MapFeaturesResponse",
+    ),
+    get(
+        "listComments",
+        "/api/v1/letterings/{id}/comments",
+        Some("CommentListParams"),
+        "CommentView[]",
+    ),
+    get(
+        "getLetteringContext",
+        "/api/v1/letterings/{id}/context",
+        Some("AgeAckQuery"),
+        "LetteringContext",
+    ),
+    Endpoint {
+        name: "shareLettering",
+        method: "POST",
+        path: "/api/v1/letterings/{id}/share",
+        query: None,
+        body: Some("ShareLinkRequest"),
+        response: "ShareLink",
+    },
+    get(
+        "getMapMarkers",
+        "/api/v1/geo/markers",
+        Some("MarkersQuery"),
+        "Marker[]",
+    ),
+    get(
+        "getNearbyMarkers",
+        "/api/v1/geo/nearby",
+        Some("NearbyQuery"),
+        "Marker[]",
+    ),
+    get(
+        "getCoverage",
+        "/api/v1/geo/coverage",
+        Some("CoverageQuery"),
+        "CoveragePoint[]",
+    ),
+    get(
+        "getRegionAggregates",
+        "/api/v1/regions/{country_code}/aggregates",
+        None,
+        "RegionAggregates",
+    ),
+    get(
+        "listMyLetterings",
+        "/api/v1/me/letterings",
+        Some("MyUploadsQuery"),
+        "MyUploadsResponse",
+    ),
+    Endpoint {
+        name: "updateMyLettering",
+        method: "PATCH",
+        path: "/api/v1/me/letterings/{id}",
+        query: None,
+        body: Some("UpdateMyUploadRequest"),
+        response: "MyUploadItem",
+    },
+    get(
+        "getMyLetteringTimeline",
+        "/api/v1/me/letterings/{id}/timeline",
+        None,
+        "MyUploadTimelineResponse",
+    ),
+    get(
+        "listNotifications",
+        "/api/v1/me/notifications",
+        Some("NotificationsQuery"),
+        "NotificationsResponse",
+    ),
+    get(
+        "listSavedSearches",
+        "/api/v1/me/saved-searches",
+        None,
+        "SavedSearchItem[]",
+    ),
+    Endpoint {
+        name: "createSavedSearch",
+        method: "POST",
+        path: "/api/v1/me/saved-searches",
+        query: None,
+        body: Some("SavedSearchRequest"),
+        response: "SavedSearchItem",
+    },
+    Endpoint {
+        name: "updateSavedSearch",
+        method: "PUT",
+        path: "/api/v1/me/saved-searches/{id}",
+        query: None,
+        body: Some("SavedSearchRequest"),
+        response: "SavedSearchItem",
+    },
+    get("listFollows", "/api/v1/me/follows", None, "FollowItem[]"),
+    get(
+        "listBookmarks",
+        "/api/v1/me/bookmarks",
+        Some("BookmarksQuery"),
+        "BookmarksResponse",
+    ),
+    get("getMyStats", "/api/v1/me/stats", None, "MyStatsResponse"),
+    get(
+        "getMyFeed",
+        "/api/v1/me/feed",
+        Some("FeedQuery"),
+        "ActivityPage",
+    ),
+    get(
+        "getDigestSettings",
+        "/api/v1/me/digest",
+        None,
+        "DigestSettings",
+    ),
+    Endpoint {
+        name: "updateDigestSettings",
+        method: "PUT",
+        path: "/api/v1/me/digest",
+        query: None,
+        body: Some("DigestSettingsRequest"),
+        response: "DigestSettings",
+    },
+    Endpoint {
+        name: "unsubscribeDigest",
+        method: "POST",
+        path: "/api/v1/digest/unsubscribe",
+        query: Some("UnsubscribeQuery"),
+        body: None,
+        response: "UnsubscribeResponse",
+    },
+    Endpoint {
+        name: "createFollow",
+        method: "POST",
+        path: "/api/v1/me/follows",
+        query: None,
+        body: Some("FollowRequest"),
+        response: "FollowItem",
+    },
+    get(
+        "listCollections",
+        "/api/v1/collections",
+        Some("CollectionListQuery"),
+        "Collection[]",
+    ),
+    get(
+        "listMyCollections",
+        "/api/v1/me/collections",
+        None,
+        "Collection[]",
+    ),
+    get(
+        "getCollection",
+        "/api/v1/collections/{id}",
+        Some("CollectionItemsQuery"),
+        "CollectionDetail",
+    ),
+    get(
+        "getSharedCollection",
+        "/api/v1/collections/shared/{slug}",
+        Some("CollectionItemsQuery"),
+        "CollectionDetail",
+    ),
+    Endpoint {
+        name: "createCollection",
+        method: "POST",
+        path: "/api/v1/collections",
+        query: None,
+        body: Some("CreateCollectionRequest"),
+        response: "Collection",
+    },
+    Endpoint {
+        name: "updateCollection",
+        method: "PATCH",
+        path: "/api/v1/collections/{id}",
+        query: None,
+        body: Some("UpdateCollectionRequest"),
+        response: "Collection",
+    },
+    get(
+        "getModerationQueue",
+        "/api/v1/admin/moderation",
+        Some("ModerationQuery"),
+        "ModerationQueueResponse",
+    ),
+    get(
+        "getEscalationStats",
+        "/api/v1/admin/moderation/escalations",
+        Some("EscalationStatsQuery"),
+        "EscalationStatsResponse",
+    ),
+    Endpoint {
+        name: "moderateSource",
+        method: "POST",
+        path: "/api/v1/admin/moderation/sources/action",
+        query: None,
+        body: Some("SourceModerationRequest"),
+        response: "SourceModerationResponse",
+    },
+];
+
+/// `bindings/Endpoints.ts` for [`ENDPOINTS`].
+pub fn render_typescript(endpoints: &[Endpoint]) -> String {
+    let mut imports: Vec<&str> = endpoints
+        .iter()
+        .flat_map(|e| [e.query, e.body, Some(e.response)])
+        .flatten()
+        .map(|t| t.trim_end_matches("[]"))
+        .collect();
+    imports.sort_unstable();
+    imports.dedup();
+
+    let mut out = String::from(
+        "// Generated from ENDPOINTS in src/presentation/http/client_sdk.rs by `cargo test`. Do not edit this file manually.\n",
+    );
+    for name in &imports {
+        out.push_str(&format!("import type {{ {0} }} from \"./{0}\";\n", name));
+    }
+
+    out.push_str("\nexport type Endpoints = {\n");
+    for e in endpoints {
+        out.push_str(&format!(
+            "  {}: {{ method: \"{}\"; path: \"{}\"; query: {}; body: {}; response: {} }};\n",
+            e.name,
+            e.method,
+            e.path,
+            e.query.unwrap_or("never"),
+            e.body.unwrap_or("never"),
+            e.response
+        ));
+    }
+    out.push_str("};\n\nexport const endpoints = {\n");
+    for e in endpoints {
+        out.push_str(&format!(
+            "  {}: {{ method: \"{}\", path: \"{}\" }},\n",
+            e.name, e.method, e.path
+        ));
+    }
+    out.push_str("} as const;\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::presentation::http::handlers::docs::api_docs;
+
+    /// Writes `Endpoints.ts` alongside the ts-rs exports, honouring
+    /// `TS_RS_EXPORT_DIR` the same way.
+    #[test]
+    fn export_endpoints() {
+        let dir = std::env::var("TS_RS_EXPORT_DIR").unwrap_or_else(|_| "./bindings".to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            std::path::Path::new(&dir).join("Endpoints.ts"),
+            render_typescript(ENDPOINTS),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn endpoints_are_documented_routes() {
+        let docs = api_docs().await.0;
+        for e in ENDPOINTS {
+            let operation = &docs["paths"][e.path][e.method.to_ascii_lowercase()];
+            assert!(
+                operation.is_object(),
+                "{} {} is not in the API docs",
+                e.method,
+                e.path
+            );
+        }
+    }
+
+    #[test]
+    fn renders_typed_endpoint_map() {
+        let ts = render_typescript(&[get(
+            "getCoverage",
+            "/api/v1/geo/coverage",
+            Some("CoverageQuery"),
+            "CoveragePoint[]",
+        )]);
+        assert!(ts.contains("import type { CoveragePoint } from \"./CoveragePoint\";"));
+        assert!(ts.contains(
+            "getCoverage: { method: \"GET\"; path: \"/api/v1/geo/coverage\"; query: CoverageQuery; body: never; response: CoveragePoint[] };"
+        ));
+        assert!(ts.contains("getCoverage: { method: \"GET\", path: \"/api/v1/geo/coverage\" },"));
+    }
+}
@@ -8,14 +8,24 @@ use chrono::{DateTime, Utc};
 use jsonwebtoken::{EncodingKey, Header, encode};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Postgres, QueryBuilder};
+use std::time::Duration;
+use ts_rs::TS;
 use uuid::Uuid;
 
 use crate::{
-    domain::lettering::repository::LetteringRepository,
-    presentation::http::{errors::AppError, middleware::admin::AdminClaims, state::AppState},
+    domain::lettering::{entity::Lettering, repository::LetteringRepository},
+    infrastructure::{queue::redis_queue::NotificationJob, storage::access::viewable_url},
+    presentation::http::{
+        errors::AppError, handlers::letterings, middleware::admin::AdminClaims, state::AppState,
+    },
+    workers::{
+        image_publisher::sync_visibility,
+        pending_auto_approve::{AutoApprovePreview, PendingAutoApproveWorker},
+        pending_escalation::SECONDARY_POOL,
+    },
 };
 
-async fn log_admin_action(
+pub(crate) async fn log_admin_action(
     state: &AppState,
     admin_sub: &str,
     action: &str,
@@ -43,6 +53,46 @@ async fn log_admin_action(
     }
 }
 
+/// Public URLs of a lettering's original and thumbnails, as cached at the CDN edge.
+pub(crate) fn lettering_cdn_urls(lettering: &Lettering) -> Vec<String> {
+    vec![
+        lettering.image_url.clone(),
+        lettering.thumbnail_urls.small.clone(),
+        lettering.thumbnail_urls.medium.clone(),
+        lettering.thumbnail_urls.large.clone(),
+    ]
+}
+
+/// Purge a lettering's images from the CDN after it stops being public.
+/// Their public URLs are read before the images move to the private bucket.
+pub(crate) async fn purge_lettering_from_cdn(state: &AppState, id: Uuid) {
+    let public_urls = if state.cdn_purger.is_enabled() {
+        match sqlx::query_as::<_, (String, String, String, String)>(
+            "SELECT image_url, thumbnail_small, thumbnail_medium, thumbnail_large FROM letterings WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        {
+            Ok(urls) => urls,
+            Err(e) => {
+                tracing::warn!(lettering_id = %id, "Failed to load URLs for CDN purge: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    letterings::invalidate_lettering(state, id).await;
+    letterings::discard_share_assets(state, id).await;
+    if let Some((image, small, medium, large)) = public_urls {
+        state
+            .cdn_purger
+            .purge_or_schedule(vec![image, small, medium, large])
+            .await;
+    }
+}
+
 async fn notify_lettering_owner(
     state: &AppState,
     lettering_id: Uuid,
@@ -68,26 +118,24 @@ async fn notify_lettering_owner(
             }
         };
 
-    if let Some(user_id) = owner_user_id {
-        if let Err(e) = sqlx::query(
-            "INSERT INTO notifications (id, user_id, type, title, body, metadata) VALUES ($1, $2, $3, $4, $5, $6)",
-        )
-        .bind(Uuid::now_v7())
-        .bind(user_id)
-        .bind(n_type)
-        .bind(title)
-        .bind(body)
-        .bind(metadata)
-        .execute(&state.db)
-        .await
-        {
-            tracing::error!(
-                "Failed to create notification for user {} (lettering {}): {}",
+    if let Some(user_id) = owner_user_id
+        && let Err(e) = state
+            .queue
+            .enqueue_notification(NotificationJob {
                 user_id,
-                lettering_id,
-                e
-            );
-        }
+                kind: n_type.to_string(),
+                title: title.to_string(),
+                body: Some(body.to_string()),
+                metadata,
+            })
+            .await
+    {
+        tracing::error!(
+            "Failed to queue notification for user {} (lettering {}): {}",
+            user_id,
+            lettering_id,
+            e
+        );
     }
 }
 
@@ -104,14 +152,24 @@ pub struct LoginResponse {
     pub token: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
 pub struct ModerationQuery {
     #[serde(default = "default_status")]
     pub status: String,
+    #[ts(type = "number")]
     #[serde(default = "default_limit")]
     pub limit: i64,
+    #[ts(type = "number")]
     #[serde(default)]
     pub offset: i64,
+    /// Restrict to letterings with (or without) low-confidence ML fields.
+    pub low_confidence: Option<bool>,
+    /// Restrict to uploads flagged (or not) as near-duplicates.
+    pub near_duplicate: Option<bool>,
+    /// Summarize the queue per upload source instead of listing items:
+    /// `contributor` or `ip`.
+    pub group_by: Option<String>,
 }
 
 fn default_status() -> String {
@@ -132,7 +190,8 @@ pub struct AuditLogsQuery {
     pub offset: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
 pub struct ModerationItem {
     pub id: Uuid,
     pub image_url: String,
@@ -148,12 +207,132 @@ pub struct ModerationItem {
     pub report_reasons: serde_json::Value,
     pub cultural_context: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub ml_style: Option<String>,
+    pub ml_script: Option<String>,
+    pub ml_text_confidence: Option<f32>,
+    pub ml_style_confidence: Option<f32>,
+    pub ml_script_confidence: Option<f32>,
+    pub low_confidence: bool,
+    pub low_confidence_fields: Vec<String>,
+    pub integrity_status: Option<String>,
+    pub near_duplicate_of: Option<Uuid>,
+    pub near_duplicate_distance: Option<i16>,
+    pub age_restricted: bool,
+    pub location_privacy: String,
+    /// True location; the public one may be fuzzed or the city centre.
+    pub exact_lat: Option<f64>,
+    pub exact_lng: Option<f64>,
+    /// Times the item was escalated for waiting too long.
+    pub review_priority: i32,
+    pub escalated_at: Option<DateTime<Utc>>,
+    /// `secondary` once routed to the escalation reviewer pool.
+    pub review_pool: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct ModerationQueueResponse {
     pub items: Vec<ModerationItem>,
+    /// Items, or sources when grouped.
+    #[ts(type = "number")]
     pub total: i64,
+    /// Set instead of `items` when the queue is grouped by source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub groups: Option<Vec<ModerationSourceGroup>>,
+}
+
+/// Where queued uploads came from, for spotting spam waves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModerationSource {
+    Contributor,
+    Ip,
+}
+
+impl ModerationSource {
+    fn parse(value: &str) -> Result<Self, AppError> {
+        match value.trim().to_lowercase().as_str() {
+            "contributor" => Ok(Self::Contributor),
+            "ip" => Ok(Self::Ip),
+            _ => Err(AppError::BadRequest(
+                "group_by must be one of contributor, ip".to_string(),
+            )),
+        }
+    }
+
+    /// SQL expression on `letterings` naming an upload's source.
+    fn column(self) -> &'static str {
+        match self {
+            Self::Contributor => "contributor_tag",
+            Self::Ip => "host(uploaded_by_ip)",
+        }
+    }
+}
+
+/// Thumbnails shown per source group.
+const SOURCE_SAMPLE_SIZE: usize = 4;
+
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
+pub struct ModerationSourceGroup {
+    /// Contributor tag or IP address.
+    pub source: String,
+    #[ts(type = "number")]
+    pub count: i64,
+    pub oldest_id: Uuid,
+    pub oldest_created_at: DateTime<Utc>,
+    pub newest_created_at: DateTime<Utc>,
+    /// Thumbnails of the newest few items.
+    pub sample_thumbnails: Vec<String>,
+    #[serde(skip)]
+    #[ts(skip)]
+    sample_statuses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SourceModerationRequest {
+    /// `contributor` or `ip`, as in the grouped queue.
+    pub group_by: String,
+    pub source: String,
+    /// `approve` or `reject`.
+    pub action: String,
+    pub reason: Option<String>,
+    /// Queue to act on: PENDING (default) or REPORTED.
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct SourceModerationResponse {
+    pub processed: usize,
+    pub ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NextModerationQuery {
+    /// PENDING or REPORTED; anything else means both.
+    #[serde(default = "default_status")]
+    pub status: String,
+    pub low_confidence: Option<bool>,
+    pub near_duplicate: Option<bool>,
+    /// Release this item and pass over it when picking the next one.
+    pub skip: Option<Uuid>,
+}
+
+#[derive(Debug, FromRow)]
+struct ClaimedModerationItem {
+    #[sqlx(flatten)]
+    item: ModerationItem,
+    review_claimed_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NextModerationResponse {
+    pub item: Option<ModerationItem>,
+    pub claimed_until: Option<DateTime<Utc>>,
+    /// Unreviewed items matching the filters that nobody else has claimed.
+    pub remaining: i64,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -265,58 +444,417 @@ pub async fn get_moderation_queue(
     let safe_limit = params.limit.clamp(1, 200);
     let safe_offset = params.offset.max(0);
 
-    let (items, total) = if status_filter == "ALL" {
-        let items = sqlx::query_as!(
-            ModerationItem,
-            r#"SELECT id, image_url, thumbnail_small, contributor_tag, pin_code,
-               detected_text, description, status, likes_count, comments_count,
-               report_count, report_reasons, cultural_context, created_at
-               FROM letterings
-               ORDER BY created_at DESC
-               LIMIT $1 OFFSET $2"#,
-            safe_limit,
-            safe_offset,
-        )
+    if let Some(group_by) = params.group_by.as_deref() {
+        let source = ModerationSource::parse(group_by)?;
+        return grouped_moderation_queue(&state, source, &status_filter, &params)
+            .await
+            .map(Json);
+    }
+
+    let mut items_qb = QueryBuilder::<Postgres>::new("SELECT ");
+    items_qb
+        .push(MODERATION_ITEM_COLUMNS)
+        .push(" FROM letterings WHERE 1=1");
+    push_moderation_filters(
+        &mut items_qb,
+        &status_filter,
+        params.low_confidence,
+        params.near_duplicate,
+    );
+    // The full listing shows newest first; a status-specific queue is worked
+    // most escalated, then oldest, first.
+    if status_filter == "ALL" {
+        items_qb.push(" ORDER BY created_at DESC");
+    } else {
+        items_qb.push(" ORDER BY review_priority DESC, created_at ASC");
+    }
+    items_qb
+        .push(" LIMIT ")
+        .push_bind(safe_limit)
+        .push(" OFFSET ")
+        .push_bind(safe_offset);
+
+    let mut items: Vec<ModerationItem> = items_qb
+        .build_query_as()
         .fetch_all(&state.db)
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings")
-            .fetch_one(&state.db)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+    let ttl = Duration::from_secs(state.config.signed_url_ttl_seconds);
+    for item in &mut items {
+        item.image_url =
+            viewable_url(state.storage.as_ref(), &item.status, &item.image_url, ttl).await;
+        if let Some(thumb) = item.thumbnail_small.take() {
+            item.thumbnail_small =
+                Some(viewable_url(state.storage.as_ref(), &item.status, &thumb, ttl).await);
+        }
+    }
+
+    let mut count_qb =
+        QueryBuilder::<Postgres>::new("SELECT COUNT(*)::bigint FROM letterings WHERE 1=1");
+    push_moderation_filters(
+        &mut count_qb,
+        &status_filter,
+        params.low_confidence,
+        params.near_duplicate,
+    );
+    let total: i64 = count_qb
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        (items, total)
+    Ok(Json(ModerationQueueResponse {
+        items,
+        total,
+        groups: None,
+    }))
+}
+
+/// The moderation queue summarized per source, biggest first, so a wave of
+/// uploads from one contributor or address shows up as a single row.
+async fn grouped_moderation_queue(
+    state: &AppState,
+    source: ModerationSource,
+    status_filter: &str,
+    params: &ModerationQuery,
+) -> Result<ModerationQueueResponse, AppError> {
+    let column = source.column();
+    let mut groups_qb = QueryBuilder::<Postgres>::new(format!(
+        "SELECT {column} AS source, COUNT(*)::bigint AS count,
+                (ARRAY_AGG(id ORDER BY created_at))[1] AS oldest_id,
+                MIN(created_at) AS oldest_created_at, MAX(created_at) AS newest_created_at,
+                (ARRAY_AGG(COALESCE(thumbnail_small, image_url) ORDER BY created_at DESC))[1:{SOURCE_SAMPLE_SIZE}] AS sample_thumbnails,
+                (ARRAY_AGG(status ORDER BY created_at DESC))[1:{SOURCE_SAMPLE_SIZE}] AS sample_statuses
+         FROM letterings WHERE {column} IS NOT NULL"
+    ));
+    push_moderation_filters(
+        &mut groups_qb,
+        status_filter,
+        params.low_confidence,
+        params.near_duplicate,
+    );
+    groups_qb
+        .push(" GROUP BY 1 ORDER BY count DESC, oldest_created_at LIMIT ")
+        .push_bind(params.limit.clamp(1, 200))
+        .push(" OFFSET ")
+        .push_bind(params.offset.max(0));
+    let mut groups: Vec<ModerationSourceGroup> = groups_qb
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let ttl = Duration::from_secs(state.config.signed_url_ttl_seconds);
+    for group in &mut groups {
+        let mut thumbnails = Vec::with_capacity(group.sample_thumbnails.len());
+        for (url, status) in group.sample_thumbnails.iter().zip(&group.sample_statuses) {
+            thumbnails.push(viewable_url(state.storage.as_ref(), status, url, ttl).await);
+        }
+        group.sample_thumbnails = thumbnails;
+    }
+
+    let mut count_qb = QueryBuilder::<Postgres>::new(format!(
+        "SELECT COUNT(DISTINCT {column})::bigint FROM letterings WHERE {column} IS NOT NULL"
+    ));
+    push_moderation_filters(
+        &mut count_qb,
+        status_filter,
+        params.low_confidence,
+        params.near_duplicate,
+    );
+    let total: i64 = count_qb
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(ModerationQueueResponse {
+        items: Vec::new(),
+        total,
+        groups: Some(groups),
+    })
+}
+
+fn push_moderation_filters(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    status_filter: &str,
+    low_confidence: Option<bool>,
+    near_duplicate: Option<bool>,
+) {
+    if status_filter != "ALL" {
+        qb.push(" AND status = ")
+            .push_bind(status_filter.to_string());
+    }
+    if let Some(low_confidence) = low_confidence {
+        qb.push(" AND low_confidence = ").push_bind(low_confidence);
+    }
+    match near_duplicate {
+        Some(true) => {
+            qb.push(" AND near_duplicate_of IS NOT NULL");
+        }
+        Some(false) => {
+            qb.push(" AND near_duplicate_of IS NULL");
+        }
+        None => {}
+    }
+}
+
+/// How long a claimed item stays reserved for its moderator.
+const REVIEW_CLAIM_MINUTES: i32 = 10;
+
+const MODERATION_ITEM_COLUMNS: &str = "id, image_url, thumbnail_small, contributor_tag, pin_code,
+     detected_text, description, status, likes_count, comments_count,
+     report_count, report_reasons, cultural_context, created_at,
+     ml_style, ml_script, ml_text_confidence, ml_confidence AS ml_style_confidence,
+     ml_script_confidence, low_confidence, ml_low_confidence_fields AS low_confidence_fields,
+     integrity_status, near_duplicate_of, near_duplicate_distance, age_restricted, location_privacy,
+     ST_Y(exact_location::geometry) AS exact_lat, ST_X(exact_location::geometry) AS exact_lng,
+     review_priority, escalated_at, review_pool";
+
+/// Restrict to PENDING/REPORTED items (or the requested one of those) that
+/// are unclaimed, claimed by `admin_sub`, or whose claim has lapsed. While a
+/// secondary reviewer pool is configured, items routed to it are left to its
+/// members.
+fn push_review_filters(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    admin_sub: &str,
+    secondary_reviewers: &[String],
+    params: &NextModerationQuery,
+) {
+    let status_filter = params.status.to_uppercase();
+    let status_filter = if status_filter == "PENDING" || status_filter == "REPORTED" {
+        status_filter
     } else {
-        let items = sqlx::query_as!(
-            ModerationItem,
-            r#"SELECT id, image_url, thumbnail_small, contributor_tag, pin_code,
-               detected_text, description, status, likes_count, comments_count,
-               report_count, report_reasons, cultural_context, created_at
-               FROM letterings
-               WHERE status = $1
-               ORDER BY created_at ASC
-               LIMIT $2 OFFSET $3"#,
-            status_filter,
-            safe_limit,
-            safe_offset,
+        qb.push(" AND status IN ('PENDING', 'REPORTED')");
+        "ALL".to_string()
+    };
+    push_moderation_filters(
+        qb,
+        &status_filter,
+        params.low_confidence,
+        params.near_duplicate,
+    );
+    qb.push(
+        " AND (review_claimed_by IS NULL OR review_claimed_until < NOW() OR review_claimed_by = ",
+    )
+    .push_bind(admin_sub.to_string())
+    .push(")");
+    if !secondary_reviewers.is_empty() && !secondary_reviewers.iter().any(|r| r == admin_sub) {
+        qb.push(" AND review_pool IS DISTINCT FROM ")
+            .push_bind(SECONDARY_POOL);
+    }
+    if let Some(skip) = params.skip {
+        qb.push(" AND id <> ").push_bind(skip);
+    }
+}
+
+/// Claim and return the next item to review, for keyboard-driven moderation.
+///
+/// The moderator's own live claim is resumed first; otherwise the most
+/// escalated, then most reported, then oldest, unclaimed item is reserved for
+/// `REVIEW_CLAIM_MINUTES`. Claiming is a single `FOR UPDATE SKIP LOCKED`
+/// update, so concurrent moderators never receive the same item.
+pub async fn claim_next_moderation_item(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Query(params): Query<NextModerationQuery>,
+) -> Result<Json<NextModerationResponse>, AppError> {
+    if let Some(skip) = params.skip {
+        sqlx::query(
+            "UPDATE letterings SET review_claimed_by = NULL, review_claimed_until = NULL
+             WHERE id = $1 AND review_claimed_by = $2",
         )
-        .fetch_all(&state.db)
+        .bind(skip)
+        .bind(&claims.sub)
+        .execute(&state.db)
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
+    }
 
-        let total = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM letterings WHERE status = $1",
+    let mut qb = QueryBuilder::<Postgres>::new("UPDATE letterings SET review_claimed_by = ");
+    qb.push_bind(claims.sub.clone())
+        .push(", review_claimed_until = NOW() + make_interval(mins => ")
+        .push_bind(REVIEW_CLAIM_MINUTES)
+        .push(") WHERE id = (SELECT id FROM letterings WHERE 1=1");
+    push_review_filters(
+        &mut qb,
+        &claims.sub,
+        &state.config.pending_escalation_reviewers,
+        &params,
+    );
+    qb.push(" ORDER BY COALESCE(review_claimed_by = ")
+        .push_bind(claims.sub.clone())
+        .push(
+            " AND review_claimed_until >= NOW(), false) DESC, review_priority DESC, report_count DESC, created_at ASC
+             LIMIT 1 FOR UPDATE SKIP LOCKED) RETURNING ",
         )
-        .bind(status_filter)
-        .fetch_one(&state.db)
+        .push(MODERATION_ITEM_COLUMNS)
+        .push(", review_claimed_until");
+
+    let claimed: Option<ClaimedModerationItem> = qb
+        .build_query_as()
+        .fetch_optional(&state.db)
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        (items, total)
+    let (item, claimed_until) = match claimed {
+        Some(ClaimedModerationItem {
+            mut item,
+            review_claimed_until,
+        }) => {
+            let ttl = Duration::from_secs(state.config.signed_url_ttl_seconds);
+            item.image_url =
+                viewable_url(state.storage.as_ref(), &item.status, &item.image_url, ttl).await;
+            if let Some(thumb) = item.thumbnail_small.take() {
+                item.thumbnail_small =
+                    Some(viewable_url(state.storage.as_ref(), &item.status, &thumb, ttl).await);
+            }
+            (Some(item), review_claimed_until)
+        }
+        None => (None, None),
     };
 
-    Ok(Json(ModerationQueueResponse { items, total }))
+    let mut count_qb =
+        QueryBuilder::<Postgres>::new("SELECT COUNT(*)::bigint FROM letterings WHERE 1=1");
+    push_review_filters(
+        &mut count_qb,
+        &claims.sub,
+        &state.config.pending_escalation_reviewers,
+        &params,
+    );
+    if let Some(item) = &item {
+        count_qb.push(" AND id <> ").push_bind(item.id);
+    }
+    let remaining: i64 = count_qb
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(NextModerationResponse {
+        item,
+        claimed_until,
+        remaining,
+    }))
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct EscalationStatsQuery {
+    /// Weeks to report, counting the current one (default 12, max 104).
+    #[ts(type = "number", optional)]
+    pub weeks: Option<i32>,
+}
+
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
+pub struct EscalationWeek {
+    /// Monday the week starts on (UTC).
+    pub week_start: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub escalations: i64,
+    /// Distinct letterings escalated that week.
+    #[ts(type = "number")]
+    pub letterings: i64,
+    #[ts(type = "number")]
+    pub routed_to_secondary: i64,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct EscalationStatsResponse {
+    /// Oldest week first; weeks without escalations are included.
+    pub weeks: Vec<EscalationWeek>,
+    /// Escalated letterings still waiting for review.
+    #[ts(type = "number")]
+    pub still_pending: i64,
+}
+
+/// Escalations of stale pending items per week.
+pub async fn get_escalation_stats(
+    State(state): State<AppState>,
+    Query(params): Query<EscalationStatsQuery>,
+) -> Result<Json<EscalationStatsResponse>, AppError> {
+    let weeks = params.weeks.unwrap_or(12).clamp(1, 104);
+    let weeks = sqlx::query_as::<_, EscalationWeek>(
+        "SELECT w.week_start,
+                COUNT(e.id)::bigint AS escalations,
+                COUNT(DISTINCT e.lettering_id)::bigint AS letterings,
+                COUNT(e.id) FILTER (WHERE e.review_pool = $2)::bigint AS routed_to_secondary
+         FROM generate_series(
+                  date_trunc('week', NOW()) - make_interval(weeks => $1 - 1),
+                  date_trunc('week', NOW()),
+                  INTERVAL '1 week'
+              ) AS w(week_start)
+         LEFT JOIN moderation_escalations e
+                ON e.created_at >= w.week_start AND e.created_at < w.week_start + INTERVAL '1 week'
+         GROUP BY w.week_start
+         ORDER BY w.week_start ASC",
+    )
+    .bind(weeks)
+    .bind(SECONDARY_POOL)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let still_pending: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*)::bigint FROM letterings
+         WHERE status = 'PENDING' AND review_priority - priority_boost > 0",
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(EscalationStatsResponse {
+        weeks,
+        still_pending,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AutoApprovePreviewQuery {
+    /// Review window to try instead of `PENDING_AUTO_APPROVE_MINUTES`.
+    pub minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AutoApprovePreviewResponse {
+    /// Whether the worker runs at all, and whether it only logs.
+    pub enabled: bool,
+    pub dry_run: bool,
+    #[serde(flatten)]
+    pub preview: AutoApprovePreview,
+}
+
+/// What the next auto-approval pass would approve and hold, without
+/// changing anything. `minutes` previews a different review window.
+pub async fn preview_auto_approve(
+    State(state): State<AppState>,
+    Query(params): Query<AutoApprovePreviewQuery>,
+) -> Result<Json<AutoApprovePreviewResponse>, AppError> {
+    let config = &state.config;
+    let minutes = params
+        .minutes
+        .unwrap_or(config.pending_auto_approve_minutes);
+    let preview = PendingAutoApproveWorker::new(
+        state.db.clone(),
+        state.ws_broadcaster.clone(),
+        minutes,
+        config.pending_auto_approve_interval_seconds,
+        config.pending_auto_approve_batch_size,
+        config.pending_auto_approve_exclusions.clone(),
+        true,
+    )
+    .preview()
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(AutoApprovePreviewResponse {
+        enabled: config.enable_pending_auto_approve,
+        dry_run: config.pending_auto_approve_dry_run,
+        preview,
+    }))
 }
 
 pub async fn approve_lettering(
@@ -343,6 +881,7 @@ pub async fn approve_lettering(
         return Err(AppError::NotFound("Lettering not found".to_string()));
     }
 
+    letterings::invalidate_lettering(&state, id).await;
     log_admin_action(
         &state,
         &claims.sub,
@@ -395,6 +934,8 @@ pub async fn reject_lettering(
         return Err(AppError::NotFound("Lettering not found".to_string()));
     }
 
+    purge_lettering_from_cdn(&state, id).await;
+
     log_admin_action(
         &state,
         &claims.sub,
@@ -439,32 +980,12 @@ pub async fn delete_any_lettering(
     )
     .await;
 
-    // Clean up R2 storage
-    let url_parts: Vec<&str> = lettering.image_url.split('/').collect();
-    if let Some(filename) = url_parts.last() {
-        let _ = state
-            .storage
-            .delete(&format!("letterings/{}", filename))
-            .await;
-        let _ = state
-            .storage
-            .delete(&format!("thumbnails/small/{}", filename))
-            .await;
-        let _ = state
-            .storage
-            .delete(&format!("thumbnails/medium/{}", filename))
-            .await;
-        let _ = state
-            .storage
-            .delete(&format!("thumbnails/large/{}", filename))
-            .await;
-    }
+    letterings::delete_lettering_record(&state, &lettering).await?;
 
     state
-        .lettering_repo
-        .delete(id)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .cdn_purger
+        .purge_or_schedule(lettering_cdn_urls(&lettering))
+        .await;
 
     log_admin_action(
         &state,
@@ -480,16 +1001,63 @@ pub async fn delete_any_lettering(
 }
 
 /// "Keep & Clear": Resets report_count to 0, clears reasons, restores status to APPROVED
+#[derive(Debug, Deserialize)]
+pub struct AgeRestrictionRequest {
+    pub age_restricted: bool,
+}
+
+/// Set or lift the age restriction on a lettering. The moderator's decision
+/// sticks: the NSFW classifier won't change it afterwards.
+pub async fn set_age_restriction(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<AgeRestrictionRequest>,
+) -> Result<StatusCode, AppError> {
+    let result = sqlx::query(
+        "UPDATE letterings
+         SET age_restricted = $2, age_restricted_by = 'moderator', updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(body.age_restricted)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Lettering not found".to_string()));
+    }
+
+    letterings::invalidate_lettering(&state, id).await;
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "SET_AGE_RESTRICTION",
+        Some(id),
+        serde_json::json!({ "age_restricted": body.age_restricted }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn clear_reports(
     State(state): State<AppState>,
     Extension(claims): Extension<AdminClaims>,
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
     let result = sqlx::query(
-        r#"UPDATE letterings
+        r#"WITH closed AS (
+            UPDATE lettering_reports SET cleared_at = NOW()
+            WHERE lettering_id = $1 AND cleared_at IS NULL
+        )
+        UPDATE letterings
         SET report_count = 0,
             report_reasons = '[]'::jsonb,
-            status = 'APPROVED',
+            status = COALESCE(auto_hidden_from_status, 'APPROVED'),
+            auto_hidden_from_status = NULL,
+            auto_hidden_at = NULL,
             moderation_reason = 'Reports cleared after moderator review',
             moderated_at = NOW(),
             moderated_by = $2,
@@ -506,6 +1074,7 @@ pub async fn clear_reports(
         return Err(AppError::NotFound("Lettering not found".to_string()));
     }
 
+    letterings::invalidate_lettering(&state, id).await;
     log_admin_action(
         &state,
         &claims.sub,
@@ -534,20 +1103,23 @@ pub async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsRespon
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let pending = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings WHERE status = 'PENDING'")
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let pending =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings WHERE status = 'PENDING'")
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let approved = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings WHERE status = 'APPROVED'")
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let approved =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings WHERE status = 'APPROVED'")
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let rejected = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings WHERE status = 'REJECTED'")
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let rejected =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM letterings WHERE status = 'REJECTED'")
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
     let cities = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM cities")
         .fetch_one(&state.db)
@@ -575,6 +1147,296 @@ pub async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsRespon
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CountryStatsQuery {
+    #[serde(default = "default_stats_days")]
+    pub days: i32,
+}
+
+fn default_stats_days() -> i32 {
+    30
+}
+
+#[derive(Debug, FromRow)]
+struct CountryStatsRow {
+    country_code: String,
+    uploads: i64,
+    approved: i64,
+    reported: i64,
+    active_contributors: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CountryStatsItem {
+    pub country_code: String,
+    pub uploads: i64,
+    pub approved: i64,
+    pub approval_rate: f64,
+    pub active_contributors: i64,
+    pub reported: i64,
+    pub report_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CountryStatsResponse {
+    pub days: i32,
+    pub items: Vec<CountryStatsItem>,
+}
+
+pub async fn get_stats_by_country(
+    State(state): State<AppState>,
+    Query(params): Query<CountryStatsQuery>,
+) -> Result<Json<CountryStatsResponse>, AppError> {
+    let days = params.days.clamp(1, 365);
+
+    // Contributors are counted by account when available, falling back to
+    // the free-form tag for anonymous uploads.
+    let rows = sqlx::query_as::<_, CountryStatsRow>(
+        "SELECT c.country_code,
+                COUNT(*)::bigint AS uploads,
+                COUNT(*) FILTER (WHERE l.status = 'APPROVED')::bigint AS approved,
+                COUNT(*) FILTER (WHERE l.report_count > 0)::bigint AS reported,
+                COUNT(DISTINCT COALESCE(l.user_id::text, l.contributor_tag))::bigint AS active_contributors
+         FROM letterings l
+         JOIN cities c ON c.id = l.city_id
+         WHERE l.created_at >= NOW() - make_interval(days => $1)
+         GROUP BY c.country_code
+         ORDER BY uploads DESC, c.country_code ASC",
+    )
+    .bind(days)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let items = rows
+        .into_iter()
+        .map(|r| {
+            let uploads = r.uploads.max(1) as f64;
+            CountryStatsItem {
+                approval_rate: r.approved as f64 / uploads,
+                report_rate: r.reported as f64 / uploads,
+                country_code: r.country_code,
+                uploads: r.uploads,
+                approved: r.approved,
+                active_contributors: r.active_contributors,
+                reported: r.reported,
+            }
+        })
+        .collect();
+
+    Ok(Json(CountryStatsResponse { days, items }))
+}
+
+/// Countries whose share of reports exceeds their share of uploads by this
+/// factor are flagged for review.
+const REPORT_SKEW_THRESHOLD: f64 = 3.0;
+const REPORT_SKEW_MIN_REPORTS: i64 = 10;
+
+#[derive(Debug, FromRow)]
+struct RequestGeoRow {
+    country_code: String,
+    uploads: i64,
+    logins: i64,
+    reports: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestGeoItem {
+    pub country_code: String,
+    pub uploads: i64,
+    pub logins: i64,
+    pub reports: i64,
+    pub report_share: f64,
+    pub upload_share: f64,
+    /// Reports are disproportionate to uploads from the same country.
+    pub report_skew_flagged: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequestGeoResponse {
+    pub days: i32,
+    pub items: Vec<RequestGeoItem>,
+}
+
+/// Request counts by the client's IP-derived country, from
+/// `ip_geo_annotations`.
+pub async fn get_request_geo_stats(
+    State(state): State<AppState>,
+    Query(params): Query<CountryStatsQuery>,
+) -> Result<Json<RequestGeoResponse>, AppError> {
+    let days = params.days.clamp(1, 365);
+
+    let rows = sqlx::query_as::<_, RequestGeoRow>(
+        "SELECT country_code,
+                COUNT(*) FILTER (WHERE event_type = 'UPLOAD')::bigint AS uploads,
+                COUNT(*) FILTER (WHERE event_type = 'LOGIN')::bigint AS logins,
+                COUNT(*) FILTER (WHERE event_type = 'REPORT')::bigint AS reports
+         FROM ip_geo_annotations
+         WHERE created_at >= NOW() - make_interval(days => $1)
+         GROUP BY country_code
+         ORDER BY COUNT(*) DESC, country_code ASC",
+    )
+    .bind(days)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let total_uploads = rows.iter().map(|r| r.uploads).sum::<i64>().max(1) as f64;
+    let total_reports = rows.iter().map(|r| r.reports).sum::<i64>().max(1) as f64;
+
+    let items = rows
+        .into_iter()
+        .map(|r| {
+            let report_share = r.reports as f64 / total_reports;
+            let upload_share = r.uploads as f64 / total_uploads;
+            RequestGeoItem {
+                report_skew_flagged: r.reports >= REPORT_SKEW_MIN_REPORTS
+                    && report_share > upload_share * REPORT_SKEW_THRESHOLD,
+                report_share,
+                upload_share,
+                country_code: r.country_code,
+                uploads: r.uploads,
+                logins: r.logins,
+                reports: r.reports,
+            }
+        })
+        .collect();
+
+    Ok(Json(RequestGeoResponse { days, items }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModeratorStatsQuery {
+    #[serde(default = "default_stats_days")]
+    pub days: i32,
+}
+
+#[derive(Debug, FromRow)]
+struct ModeratorStatsRow {
+    admin_sub: String,
+    approvals: i64,
+    rejections: i64,
+    deletions: i64,
+    report_clears: i64,
+    overturned: i64,
+    avg_handling_seconds: Option<f64>,
+    last_action_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModeratorStatsItem {
+    pub admin_sub: String,
+    pub approvals: i64,
+    pub rejections: i64,
+    pub deletions: i64,
+    pub report_clears: i64,
+    pub total_decisions: i64,
+    pub overturned: i64,
+    pub overturn_rate: f64,
+    pub avg_handling_seconds: Option<f64>,
+    pub share_of_workload: f64,
+    pub last_action_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModeratorStatsResponse {
+    pub days: i32,
+    pub total_decisions: i64,
+    pub items: Vec<ModeratorStatsItem>,
+}
+
+/// Per-moderator decision counts derived from `admin_audit_logs`.
+///
+/// There is no formal appeal flow, so a decision counts as overturned when a
+/// different admin later reaches the opposite verdict (approve vs reject) on
+/// the same lettering. Handling time is measured from upload to an approve or
+/// reject decision; deleted letterings are excluded from the average.
+pub async fn get_moderator_stats(
+    State(state): State<AppState>,
+    Query(params): Query<ModeratorStatsQuery>,
+) -> Result<Json<ModeratorStatsResponse>, AppError> {
+    let days = params.days.clamp(1, 365);
+
+    let rows = sqlx::query_as::<_, ModeratorStatsRow>(
+        "WITH decisions AS (
+             SELECT a.id, a.admin_sub, a.lettering_id, a.created_at,
+                    CASE
+                        WHEN a.action IN ('APPROVE_LETTERING', 'BULK_APPROVE_LETTERING') THEN 'APPROVE'
+                        WHEN a.action IN ('REJECT_LETTERING', 'BULK_REJECT_LETTERING') THEN 'REJECT'
+                        WHEN a.action IN ('DELETE_LETTERING', 'BULK_DELETE_LETTERING') THEN 'DELETE'
+                        ELSE 'CLEAR_REPORTS'
+                    END AS verdict
+             FROM admin_audit_logs a
+             WHERE a.action IN (
+                     'APPROVE_LETTERING', 'BULK_APPROVE_LETTERING',
+                     'REJECT_LETTERING', 'BULK_REJECT_LETTERING',
+                     'DELETE_LETTERING', 'BULK_DELETE_LETTERING',
+                     'CLEAR_REPORTS', 'BULK_CLEAR_REPORTS'
+                 )
+               AND a.created_at >= NOW() - make_interval(days => $1)
+         )
+         SELECT d.admin_sub,
+                COUNT(*) FILTER (WHERE d.verdict = 'APPROVE')::bigint AS approvals,
+                COUNT(*) FILTER (WHERE d.verdict = 'REJECT')::bigint AS rejections,
+                COUNT(*) FILTER (WHERE d.verdict = 'DELETE')::bigint AS deletions,
+                COUNT(*) FILTER (WHERE d.verdict = 'CLEAR_REPORTS')::bigint AS report_clears,
+                COUNT(*) FILTER (
+                    WHERE d.verdict IN ('APPROVE', 'REJECT')
+                      AND EXISTS (
+                          SELECT 1 FROM decisions later
+                          WHERE later.lettering_id = d.lettering_id
+                            AND later.admin_sub <> d.admin_sub
+                            AND later.created_at > d.created_at
+                            AND later.verdict IN ('APPROVE', 'REJECT')
+                            AND later.verdict <> d.verdict
+                      )
+                )::bigint AS overturned,
+                (AVG(EXTRACT(EPOCH FROM (d.created_at - l.created_at)))
+                    FILTER (WHERE d.verdict IN ('APPROVE', 'REJECT')))::float8 AS avg_handling_seconds,
+                MAX(d.created_at) AS last_action_at
+         FROM decisions d
+         LEFT JOIN letterings l ON l.id = d.lettering_id
+         GROUP BY d.admin_sub
+         ORDER BY COUNT(*) DESC, d.admin_sub ASC",
+    )
+    .bind(days)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let total_decisions: i64 = rows
+        .iter()
+        .map(|r| r.approvals + r.rejections + r.deletions + r.report_clears)
+        .sum();
+
+    let items = rows
+        .into_iter()
+        .map(|r| {
+            let decisions = r.approvals + r.rejections + r.deletions + r.report_clears;
+            let verdicts = (r.approvals + r.rejections).max(1) as f64;
+            ModeratorStatsItem {
+                total_decisions: decisions,
+                overturn_rate: r.overturned as f64 / verdicts,
+                share_of_workload: decisions as f64 / total_decisions.max(1) as f64,
+                admin_sub: r.admin_sub,
+                approvals: r.approvals,
+                rejections: r.rejections,
+                deletions: r.deletions,
+                report_clears: r.report_clears,
+                overturned: r.overturned,
+                avg_handling_seconds: r.avg_handling_seconds,
+                last_action_at: r.last_action_at,
+            }
+        })
+        .collect();
+
+    Ok(Json(ModeratorStatsResponse {
+        days,
+        total_decisions,
+        items,
+    }))
+}
+
 pub async fn list_audit_logs(
     State(state): State<AppState>,
     Query(params): Query<AuditLogsQuery>,
@@ -651,6 +1513,163 @@ pub async fn list_audit_logs(
     }))
 }
 
+/// Approve or reject every queued upload from one source in a single call.
+pub async fn moderate_source(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(body): Json<SourceModerationRequest>,
+) -> Result<Json<SourceModerationResponse>, AppError> {
+    let source = ModerationSource::parse(&body.group_by)?;
+    let value = body.source.trim();
+    if value.is_empty() {
+        return Err(AppError::BadRequest("source cannot be empty".to_string()));
+    }
+    let status = body
+        .status
+        .as_deref()
+        .map(str::to_uppercase)
+        .unwrap_or_else(|| "PENDING".to_string());
+    if status != "PENDING" && status != "REPORTED" {
+        return Err(AppError::BadRequest(
+            "status must be one of PENDING, REPORTED".to_string(),
+        ));
+    }
+    let reason = body
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or("Rejected with the rest of its source");
+
+    let column = source.column();
+    let (sql, audit_action, n_type, title, message) =
+        match body.action.trim().to_lowercase().as_str() {
+            "approve" => (
+                format!(
+                    "UPDATE letterings
+                 SET status = 'APPROVED',
+                     moderation_reason = 'Approved with the rest of its source',
+                     moderated_at = NOW(),
+                     moderated_by = $3,
+                     updated_at = NOW()
+                 WHERE {column} = $1 AND status = $2
+                 RETURNING id"
+                ),
+                "SOURCE_APPROVE_LETTERING",
+                "MODERATION_APPROVED",
+                "Your upload was approved",
+                "Your lettering contribution has been approved and is now publicly visible.",
+            ),
+            "reject" => (
+                format!(
+                    "UPDATE letterings
+                 SET status = 'REJECTED',
+                     moderation_reason = $4,
+                     moderated_at = NOW(),
+                     moderated_by = $3,
+                     updated_at = NOW()
+                 WHERE {column} = $1 AND status = $2
+                 RETURNING id"
+                ),
+                "SOURCE_REJECT_LETTERING",
+                "MODERATION_REJECTED",
+                "Your upload was rejected",
+                "Your lettering contribution was rejected by moderation.",
+            ),
+            _ => {
+                return Err(AppError::BadRequest(
+                    "action must be one of approve, reject".to_string(),
+                ));
+            }
+        };
+    let rejecting = n_type == "MODERATION_REJECTED";
+
+    let mut query = sqlx::query_scalar::<_, Uuid>(&sql)
+        .bind(value)
+        .bind(&status)
+        .bind(&claims.sub);
+    if rejecting {
+        query = query.bind(reason);
+    }
+    let ids: Vec<Uuid> = query
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let details = if rejecting {
+        serde_json::json!({ "group_by": body.group_by, "source": value, "reason": reason })
+    } else {
+        serde_json::json!({ "group_by": body.group_by, "source": value })
+    };
+    let audit_ids: Vec<Uuid> = ids.iter().map(|_| Uuid::now_v7()).collect();
+    if let Err(e) = sqlx::query(
+        "INSERT INTO admin_audit_logs (id, admin_sub, action, lettering_id, metadata)
+         SELECT a.id, $3, $4, a.lettering_id, $5 FROM unnest($1::uuid[], $2::uuid[]) AS a(id, lettering_id)",
+    )
+    .bind(&audit_ids)
+    .bind(&ids)
+    .bind(&claims.sub)
+    .bind(audit_action)
+    .bind(&details)
+    .execute(&state.db)
+    .await
+    {
+        tracing::error!("Failed to log {} for {} letterings: {}", audit_action, ids.len(), e);
+    }
+
+    let owners = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "SELECT id, user_id FROM letterings WHERE id = ANY($1) AND user_id IS NOT NULL",
+    )
+    .bind(&ids)
+    .fetch_all(&state.db)
+    .await;
+    let queued = match owners {
+        Ok(owners) => {
+            let notifications = owners
+                .into_iter()
+                .map(|(lettering_id, user_id)| NotificationJob {
+                    user_id,
+                    kind: n_type.to_string(),
+                    title: title.to_string(),
+                    body: Some(message.to_string()),
+                    metadata: if rejecting {
+                        serde_json::json!({ "lettering_id": lettering_id, "reason": reason })
+                    } else {
+                        serde_json::json!({ "lettering_id": lettering_id })
+                    },
+                })
+                .collect();
+            state.queue.enqueue_notifications(notifications).await
+        }
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = queued {
+        tracing::error!("Failed to notify owners of {} letterings: {}", ids.len(), e);
+    }
+
+    if rejecting {
+        for id in &ids {
+            purge_lettering_from_cdn(&state, *id).await;
+        }
+    } else {
+        let invalidator = state.lettering_repo.invalidator();
+        sync_visibility(&state.db, state.storage.as_ref(), invalidator, &ids).await;
+        invalidator.letterings_changed(&ids).await;
+    }
+
+    tracing::info!(
+        source = %value,
+        group_by = %body.group_by,
+        count = ids.len(),
+        "{}",
+        audit_action
+    );
+    Ok(Json(SourceModerationResponse {
+        processed: ids.len(),
+        ids,
+    }))
+}
+
 pub async fn bulk_lettering_action(
     State(state): State<AppState>,
     Extension(claims): Extension<AdminClaims>,
@@ -700,6 +1719,7 @@ pub async fn bulk_lettering_action(
                 if result.rows_affected() == 0 {
                     Err(AppError::NotFound("Lettering not found".to_string()))
                 } else {
+                    letterings::invalidate_lettering(&state, id).await;
                     log_admin_action(
                         &state,
                         &claims.sub,
@@ -739,6 +1759,7 @@ pub async fn bulk_lettering_action(
                 if result.rows_affected() == 0 {
                     Err(AppError::NotFound("Lettering not found".to_string()))
                 } else {
+                    purge_lettering_from_cdn(&state, id).await;
                     log_admin_action(
                         &state,
                         &claims.sub,
@@ -761,10 +1782,16 @@ pub async fn bulk_lettering_action(
             }
             "keep" => {
                 let result = sqlx::query(
-                    r#"UPDATE letterings
+                    r#"WITH closed AS (
+                           UPDATE lettering_reports SET cleared_at = NOW()
+                           WHERE lettering_id = $1 AND cleared_at IS NULL
+                       )
+                       UPDATE letterings
                        SET report_count = 0,
                            report_reasons = '[]'::jsonb,
-                           status = 'APPROVED',
+                           status = COALESCE(auto_hidden_from_status, 'APPROVED'),
+                           auto_hidden_from_status = NULL,
+                           auto_hidden_at = NULL,
                            moderation_reason = 'Reports cleared after moderator review',
                            moderated_at = NOW(),
                            moderated_by = $2,
@@ -779,6 +1806,7 @@ pub async fn bulk_lettering_action(
                 if result.rows_affected() == 0 {
                     Err(AppError::NotFound("Lettering not found".to_string()))
                 } else {
+                    letterings::invalidate_lettering(&state, id).await;
                     log_admin_action(
                         &state,
                         &claims.sub,
@@ -817,31 +1845,12 @@ pub async fn bulk_lettering_action(
                 )
                 .await;
 
-                let url_parts: Vec<&str> = lettering.image_url.split('/').collect();
-                if let Some(filename) = url_parts.last() {
-                    let _ = state
-                        .storage
-                        .delete(&format!("letterings/{}", filename))
-                        .await;
-                    let _ = state
-                        .storage
-                        .delete(&format!("thumbnails/small/{}", filename))
-                        .await;
-                    let _ = state
-                        .storage
-                        .delete(&format!("thumbnails/medium/{}", filename))
-                        .await;
-                    let _ = state
-                        .storage
-                        .delete(&format!("thumbnails/large/{}", filename))
-                        .await;
-                }
+                letterings::delete_lettering_record(&state, &lettering).await?;
 
                 state
-                    .lettering_repo
-                    .delete(id)
-                    .await
-                    .map_err(|e| AppError::Internal(e.to_string()))?;
+                    .cdn_purger
+                    .purge_or_schedule(lettering_cdn_urls(&lettering))
+                    .await;
 
                 log_admin_action(
                     &state,
@@ -0,0 +1,213 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::BTreeMap;
+
+use crate::{
+    presentation::http::{errors::AppError, state::AppState},
+    workers::analytics_worker::ALL_COUNTRIES,
+};
+
+/// Dimensions seen fewer times than this over the window are folded into the
+/// per-type totals instead of being listed, so rare searches or paths can't
+/// single anyone out.
+const MIN_REPORTED_COUNT: i64 = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct EventAnalyticsQuery {
+    #[serde(default = "default_days")]
+    pub days: i32,
+    #[serde(default = "default_top")]
+    pub top: i64,
+}
+
+fn default_days() -> i32 {
+    30
+}
+
+fn default_top() -> i64 {
+    20
+}
+
+#[derive(Debug, FromRow)]
+struct DailyTotalRow {
+    date: NaiveDate,
+    event_type: String,
+    count: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DailyEventTotals {
+    pub date: NaiveDate,
+    pub page_views: i64,
+    pub searches: i64,
+    pub map_interactions: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct EventDimensionCount {
+    pub event_type: String,
+    pub dimension: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventAnalyticsResponse {
+    pub days: i32,
+    pub min_reported_count: i64,
+    pub daily: Vec<DailyEventTotals>,
+    pub top_dimensions: Vec<EventDimensionCount>,
+}
+
+pub async fn get_event_analytics(
+    State(state): State<AppState>,
+    Query(params): Query<EventAnalyticsQuery>,
+) -> Result<Json<EventAnalyticsResponse>, AppError> {
+    let days = params.days.clamp(1, 365);
+    let top = params.top.clamp(1, 100);
+
+    let rows = sqlx::query_as::<_, DailyTotalRow>(
+        "SELECT date, event_type, SUM(count)::bigint AS count
+         FROM analytics_daily_events
+         WHERE date > local_date(NOW(), $2) - $1
+         GROUP BY date, event_type
+         ORDER BY date ASC",
+    )
+    .bind(days)
+    .bind(&state.config.day_boundary_timezone)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut by_date: BTreeMap<NaiveDate, DailyEventTotals> = BTreeMap::new();
+    for row in rows {
+        let totals = by_date.entry(row.date).or_insert_with(|| DailyEventTotals {
+            date: row.date,
+            ..Default::default()
+        });
+        match row.event_type.as_str() {
+            "page_view" => totals.page_views += row.count,
+            "search" => totals.searches += row.count,
+            "map_interaction" => totals.map_interactions += row.count,
+            _ => {}
+        }
+    }
+
+    let top_dimensions = sqlx::query_as::<_, EventDimensionCount>(
+        "SELECT event_type, dimension, count
+         FROM (
+             SELECT event_type, dimension, SUM(count)::bigint AS count,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY event_type ORDER BY SUM(count) DESC, dimension ASC
+                    ) AS rank
+             FROM analytics_daily_events
+             WHERE date > local_date(NOW(), $4) - $1
+             GROUP BY event_type, dimension
+             HAVING SUM(count) >= $2
+         ) ranked
+         WHERE rank <= $3
+         ORDER BY event_type ASC, count DESC, dimension ASC",
+    )
+    .bind(days)
+    .bind(MIN_REPORTED_COUNT)
+    .bind(top)
+    .bind(&state.config.day_boundary_timezone)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(EventAnalyticsResponse {
+        days,
+        min_reported_count: MIN_REPORTED_COUNT,
+        daily: by_date.into_values().collect(),
+        top_dimensions,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RollupQuery {
+    /// `hourly` or `daily`.
+    #[serde(default = "default_granularity")]
+    pub granularity: String,
+    #[serde(default = "default_days")]
+    pub days: i32,
+    /// Two-letter country code; all countries when absent.
+    pub country: Option<String>,
+}
+
+fn default_granularity() -> String {
+    "daily".to_string()
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct RollupBucket {
+    /// Start of the hour, or local midnight starting the day.
+    pub start: DateTime<Utc>,
+    pub uploads: i32,
+    pub approvals: i32,
+    pub likes: i32,
+    pub comments: i32,
+    pub active_contributors: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RollupResponse {
+    pub granularity: String,
+    pub days: i32,
+    pub country: Option<String>,
+    pub items: Vec<RollupBucket>,
+}
+
+/// Activity per hour or per day from the analytics rollups, oldest first.
+/// Buckets with no activity in the country are left out.
+pub async fn get_rollups(
+    State(state): State<AppState>,
+    Query(params): Query<RollupQuery>,
+) -> Result<Json<RollupResponse>, AppError> {
+    let days = params.days.clamp(1, 365);
+    let country = params
+        .country
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty());
+    let sql = match params.granularity.as_str() {
+        "hourly" => {
+            "SELECT hour_start AS start, uploads, approvals, likes, comments, active_contributors
+             FROM analytics_hourly_rollups
+             WHERE country_code = $1 AND hour_start > NOW() - make_interval(days => $2)
+             ORDER BY hour_start ASC"
+        }
+        "daily" => {
+            "SELECT date::timestamp AT TIME ZONE $3 AS start,
+                    uploads, approvals, likes, comments, active_contributors
+             FROM analytics_daily_rollups
+             WHERE country_code = $1 AND date > local_date(NOW(), $3) - $2
+             ORDER BY date ASC"
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "granularity must be hourly or daily".into(),
+            ));
+        }
+    };
+    let mut query = sqlx::query_as::<_, RollupBucket>(sql)
+        .bind(country.as_deref().unwrap_or(ALL_COUNTRIES))
+        .bind(days);
+    if params.granularity == "daily" {
+        query = query.bind(&state.config.day_boundary_timezone);
+    }
+    let items = query
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(RollupResponse {
+        granularity: params.granularity,
+        days,
+        country,
+        items,
+    }))
+}
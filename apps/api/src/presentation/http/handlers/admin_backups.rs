@@ -0,0 +1,249 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::presentation::http::{
+    errors::AppError, handlers::admin::log_admin_action, middleware::admin::AdminClaims,
+    state::AppState,
+};
+
+const MANIFEST_URL_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Deserialize)]
+pub struct BackupManifestQuery {
+    #[serde(default = "default_runs")]
+    pub runs: i64,
+    #[serde(default = "default_missing_limit")]
+    pub missing_limit: i64,
+}
+
+fn default_runs() -> i64 {
+    10
+}
+
+fn default_missing_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct BackupRunItem {
+    pub id: Uuid,
+    pub status: String,
+    pub objects_copied: i32,
+    pub objects_failed: i32,
+    pub bytes_copied: i64,
+    pub metadata_key: Option<String>,
+    pub metadata_rows: Option<i32>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct MissingBackupItem {
+    pub lettering_id: Uuid,
+    pub approved_since: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupManifestResponse {
+    pub approved_total: i64,
+    pub backed_up_total: i64,
+    pub missing_total: i64,
+    /// Fraction of approved letterings present in the secondary bucket.
+    pub completeness: f64,
+    pub backed_up_bytes: i64,
+    pub last_completed_at: Option<DateTime<Utc>>,
+    pub runs: Vec<BackupRunItem>,
+    /// Oldest approved letterings not yet copied.
+    pub missing: Vec<MissingBackupItem>,
+}
+
+pub async fn get_backup_manifest(
+    State(state): State<AppState>,
+    Query(params): Query<BackupManifestQuery>,
+) -> Result<Json<BackupManifestResponse>, AppError> {
+    let (approved_total, backed_up_total, backed_up_bytes) = sqlx::query_as::<_, (i64, i64, i64)>(
+        "SELECT COUNT(*)::bigint,
+                    COUNT(b.lettering_id)::bigint,
+                    COALESCE(SUM(b.size_bytes), 0)::bigint
+             FROM letterings l
+             LEFT JOIN backup_objects b ON b.lettering_id = l.id
+             WHERE l.status = 'APPROVED'",
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let runs = sqlx::query_as::<_, BackupRunItem>(
+        "SELECT id, status, objects_copied, objects_failed, bytes_copied,
+                metadata_key, metadata_rows, error, started_at, finished_at
+         FROM backup_runs
+         ORDER BY started_at DESC
+         LIMIT $1",
+    )
+    .bind(params.runs.clamp(1, 100))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let last_completed_at = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+        "SELECT MAX(finished_at) FROM backup_runs WHERE status = 'COMPLETED'",
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let missing = sqlx::query_as::<_, MissingBackupItem>(
+        "SELECT l.id AS lettering_id, l.updated_at AS approved_since
+         FROM letterings l
+         LEFT JOIN backup_objects b ON b.lettering_id = l.id
+         WHERE l.status = 'APPROVED' AND b.lettering_id IS NULL
+         ORDER BY l.created_at ASC
+         LIMIT $1",
+    )
+    .bind(params.missing_limit.clamp(0, 1000))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let missing_total = approved_total - backed_up_total;
+    Ok(Json(BackupManifestResponse {
+        approved_total,
+        backed_up_total,
+        missing_total,
+        completeness: if approved_total == 0 {
+            1.0
+        } else {
+            backed_up_total as f64 / approved_total as f64
+        },
+        backed_up_bytes,
+        last_completed_at,
+        runs,
+        missing,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BackupSnapshotListQuery {
+    #[serde(default = "default_runs")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct BackupSnapshotItem {
+    pub id: Uuid,
+    pub requested_by: String,
+    pub status: String,
+    /// WAL position to restore the database to.
+    pub wal_lsn: Option<String>,
+    /// `pg_current_snapshot()` the object list was read under.
+    pub pg_snapshot: Option<String>,
+    pub snapshot_at: Option<DateTime<Utc>>,
+    pub object_count: i32,
+    pub lettering_count: i32,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub manifest_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupSnapshotResponse {
+    #[serde(flatten)]
+    pub snapshot: BackupSnapshotItem,
+    /// Signed, short-lived link to the manifest once the snapshot has completed.
+    pub manifest_url: Option<String>,
+}
+
+const SNAPSHOT_COLUMNS: &str = "id, requested_by, status, wal_lsn, pg_snapshot, snapshot_at,
+     object_count, lettering_count, error, created_at, started_at, finished_at, manifest_key";
+
+/// Queue a consistent backup marker. The backup snapshot worker records the
+/// WAL position and writes the object manifest; poll
+/// `GET /api/v1/admin/backups/snapshots/{id}` for the result.
+pub async fn create_backup_snapshot(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+) -> Result<(StatusCode, Json<BackupSnapshotResponse>), AppError> {
+    let snapshot = sqlx::query_as::<_, BackupSnapshotItem>(&format!(
+        "INSERT INTO backup_snapshots (id, requested_by) VALUES ($1, $2) RETURNING {}",
+        SNAPSHOT_COLUMNS
+    ))
+    .bind(Uuid::now_v7())
+    .bind(&claims.sub)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "CREATE_BACKUP_SNAPSHOT",
+        None,
+        serde_json::json!({ "snapshot_id": snapshot.id }),
+    )
+    .await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(BackupSnapshotResponse {
+            snapshot,
+            manifest_url: None,
+        }),
+    ))
+}
+
+pub async fn list_backup_snapshots(
+    State(state): State<AppState>,
+    Query(params): Query<BackupSnapshotListQuery>,
+) -> Result<Json<Vec<BackupSnapshotItem>>, AppError> {
+    let snapshots = sqlx::query_as::<_, BackupSnapshotItem>(&format!(
+        "SELECT {} FROM backup_snapshots ORDER BY created_at DESC LIMIT $1",
+        SNAPSHOT_COLUMNS
+    ))
+    .bind(params.limit.clamp(1, 100))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(snapshots))
+}
+
+pub async fn get_backup_snapshot(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BackupSnapshotResponse>, AppError> {
+    let snapshot = sqlx::query_as::<_, BackupSnapshotItem>(&format!(
+        "SELECT {} FROM backup_snapshots WHERE id = $1",
+        SNAPSHOT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Backup snapshot not found".to_string()))?;
+
+    let manifest_url = match snapshot.manifest_key.as_deref() {
+        Some(key) if snapshot.status == "COMPLETED" => Some(
+            state
+                .storage
+                .signed_url(key, MANIFEST_URL_TTL)
+                .await
+                .map_err(|e| AppError::Storage(e.to_string()))?,
+        ),
+        _ => None,
+    };
+
+    Ok(Json(BackupSnapshotResponse {
+        snapshot,
+        manifest_url,
+    }))
+}
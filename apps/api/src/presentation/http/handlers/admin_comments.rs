@@ -8,8 +8,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Postgres, QueryBuilder};
 use uuid::Uuid;
 
-use crate::presentation::http::{
-    errors::AppError, middleware::admin::AdminClaims, state::AppState,
+use crate::{
+    infrastructure::queue::redis_queue::NotificationJob,
+    presentation::http::{errors::AppError, middleware::admin::AdminClaims, state::AppState},
 };
 
 #[derive(Debug, Deserialize)]
@@ -91,17 +92,53 @@ pub struct BulkCommentActionResponse {
     pub failed_items: Vec<BulkCommentActionFailure>,
 }
 
+/// Deletes a comment, or blanks it into a tombstone when it has replies so the
+/// thread under it stays readable.
+const DELETE_COMMENT_SQL: &str = "WITH tombstoned AS (
+       UPDATE comments SET deleted_at = NOW(), content = '', updated_at = NOW()
+       WHERE id = $1 AND EXISTS (SELECT 1 FROM comments r WHERE r.parent_id = $1)
+       RETURNING id
+     )
+     DELETE FROM comments
+     WHERE id = $1 AND NOT EXISTS (SELECT 1 FROM comments r WHERE r.parent_id = $1)";
+
+/// Set-based `DELETE_COMMENT_SQL`: comments with replies become tombstones,
+/// the rest are deleted. Returns every comment acted on.
+const BULK_DELETE_COMMENTS_SQL: &str = "WITH targets AS (
+       SELECT c.id, c.lettering_id, c.user_id,
+              EXISTS (SELECT 1 FROM comments r WHERE r.parent_id = c.id) AS has_replies
+       FROM comments c
+       WHERE c.id = ANY($1) AND c.deleted_at IS NULL
+     ),
+     tombstoned AS (
+       UPDATE comments SET deleted_at = NOW(), content = '', updated_at = NOW()
+       WHERE id IN (SELECT id FROM targets WHERE has_replies)
+     ),
+     deleted AS (
+       DELETE FROM comments WHERE id IN (SELECT id FROM targets WHERE NOT has_replies)
+     )
+     SELECT id, lettering_id, user_id FROM targets";
+
 #[derive(Debug, FromRow)]
 struct CommentOwnerRow {
     lettering_id: Uuid,
     user_id: Option<Uuid>,
 }
 
+/// A comment a bulk action applied to.
+#[derive(Debug, FromRow)]
+struct BulkCommentRow {
+    id: Uuid,
+    lettering_id: Uuid,
+    user_id: Option<Uuid>,
+}
+
 async fn recompute_comments_count(state: &AppState, lettering_id: Uuid) -> Result<(), AppError> {
     sqlx::query(
         "UPDATE letterings
          SET comments_count = (
-           SELECT COUNT(*)::int FROM comments WHERE lettering_id = $1 AND status = 'VISIBLE'
+           SELECT COUNT(*)::int FROM comments
+           WHERE lettering_id = $1 AND status = 'VISIBLE' AND deleted_at IS NULL
          )
          WHERE id = $1",
     )
@@ -141,17 +178,24 @@ async fn notify_comment_owner(
         return;
     };
 
-    let _ = sqlx::query(
-        "INSERT INTO notifications (id, user_id, type, title, body, metadata) VALUES ($1, $2, $3, $4, $5, $6)",
-    )
-    .bind(Uuid::now_v7())
-    .bind(owner_id)
-    .bind(n_type)
-    .bind(title)
-    .bind(body)
-    .bind(metadata)
-    .execute(&state.db)
-    .await;
+    if let Err(e) = state
+        .queue
+        .enqueue_notification(NotificationJob {
+            user_id: owner_id,
+            kind: n_type.to_string(),
+            title: title.to_string(),
+            body: Some(body.to_string()),
+            metadata,
+        })
+        .await
+    {
+        tracing::error!(
+            "Failed to queue {} notification for {}: {}",
+            n_type,
+            owner_id,
+            e
+        );
+    }
 }
 
 pub async fn list_comments(
@@ -185,7 +229,7 @@ pub async fn list_comments(
          FROM comments c
          JOIN letterings l ON l.id = c.lettering_id
          LEFT JOIN users u ON u.id = c.user_id
-         WHERE 1=1",
+         WHERE c.deleted_at IS NULL",
     );
 
     if status != "ALL" {
@@ -229,7 +273,7 @@ pub async fn list_comments(
     }
     items_qb
         .push(" LIMIT ")
-        .push_bind(params.limit.max(1).min(200))
+        .push_bind(params.limit.clamp(1, 200))
         .push(" OFFSET ")
         .push_bind(params.offset.max(0));
 
@@ -240,7 +284,7 @@ pub async fn list_comments(
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
     let mut count_qb = QueryBuilder::<Postgres>::new(
-        "SELECT COUNT(*)::bigint as total FROM comments c LEFT JOIN users u ON u.id = c.user_id WHERE 1=1",
+        "SELECT COUNT(*)::bigint as total FROM comments c LEFT JOIN users u ON u.id = c.user_id WHERE c.deleted_at IS NULL",
     );
 
     if status != "ALL" {
@@ -279,7 +323,7 @@ pub async fn list_comments(
     Ok(Json(AdminCommentsResponse {
         items,
         total,
-        limit: params.limit.max(1).min(200),
+        limit: params.limit.clamp(1, 200),
         offset: params.offset.max(0),
     }))
 }
@@ -291,7 +335,7 @@ pub async fn hide_comment(
     Json(body): Json<HideCommentRequest>,
 ) -> Result<StatusCode, AppError> {
     let owner = sqlx::query_as::<_, CommentOwnerRow>(
-        "SELECT lettering_id, user_id FROM comments WHERE id = $1",
+        "SELECT lettering_id, user_id FROM comments WHERE id = $1 AND deleted_at IS NULL",
     )
     .bind(id)
     .fetch_optional(&state.db)
@@ -346,7 +390,7 @@ pub async fn restore_comment(
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
     let owner = sqlx::query_as::<_, CommentOwnerRow>(
-        "SELECT lettering_id, user_id FROM comments WHERE id = $1",
+        "SELECT lettering_id, user_id FROM comments WHERE id = $1 AND deleted_at IS NULL",
     )
     .bind(id)
     .fetch_optional(&state.db)
@@ -392,7 +436,7 @@ pub async fn delete_comment(
     Path(id): Path<Uuid>,
 ) -> Result<StatusCode, AppError> {
     let owner = sqlx::query_as::<_, CommentOwnerRow>(
-        "SELECT lettering_id, user_id FROM comments WHERE id = $1",
+        "SELECT lettering_id, user_id FROM comments WHERE id = $1 AND deleted_at IS NULL",
     )
     .bind(id)
     .fetch_optional(&state.db)
@@ -400,7 +444,7 @@ pub async fn delete_comment(
     .map_err(|e| AppError::Internal(e.to_string()))?
     .ok_or_else(|| AppError::NotFound("Comment not found".to_string()))?;
 
-    sqlx::query("DELETE FROM comments WHERE id = $1")
+    sqlx::query(DELETE_COMMENT_SQL)
         .bind(id)
         .execute(&state.db)
         .await
@@ -455,144 +499,132 @@ pub async fn bulk_comment_action(
         .filter(|s| !s.is_empty())
         .unwrap_or("Hidden by moderation");
 
-    let mut processed = 0usize;
-    let mut failed_items = Vec::new();
+    let mut ids = body.ids.clone();
+    let mut seen = std::collections::HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    for id in body.ids.iter().copied() {
-        let owner = sqlx::query_as::<_, CommentOwnerRow>(
-            "SELECT lettering_id, user_id FROM comments WHERE id = $1",
+    let affected: Vec<BulkCommentRow> = match action.as_str() {
+        "hide" => sqlx::query_as(
+            "UPDATE comments
+             SET status = 'HIDDEN', needs_review = false, moderated_at = NOW(), moderated_by = $2, moderation_reason = $3, updated_at = NOW()
+             WHERE id = ANY($1) AND deleted_at IS NULL
+             RETURNING id, lettering_id, user_id",
         )
-        .bind(id)
-        .fetch_optional(&state.db)
+        .bind(&ids)
+        .bind(&claims.sub)
+        .bind(reason),
+        "restore" => sqlx::query_as(
+            "UPDATE comments
+             SET status = 'VISIBLE', needs_review = false, moderated_at = NULL, moderated_by = NULL, moderation_reason = NULL, updated_at = NOW()
+             WHERE id = ANY($1) AND deleted_at IS NULL
+             RETURNING id, lettering_id, user_id",
+        )
+        .bind(&ids),
+        _ => sqlx::query_as(BULK_DELETE_COMMENTS_SQL).bind(&ids),
+    }
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut lettering_ids: Vec<Uuid> = affected.iter().map(|row| row.lettering_id).collect();
+    lettering_ids.sort_unstable();
+    lettering_ids.dedup();
+    sqlx::query(
+        "UPDATE letterings l
+         SET comments_count = (
+           SELECT COUNT(*)::int FROM comments c
+           WHERE c.lettering_id = l.id AND c.status = 'VISIBLE' AND c.deleted_at IS NULL
+         )
+         WHERE l.id = ANY($1)",
+    )
+    .bind(&lettering_ids)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let (audit_action, n_type, title, message, details) = match action.as_str() {
+        "hide" => (
+            "BULK_HIDE_COMMENT",
+            "COMMENT_HIDDEN",
+            "Your comment was hidden",
+            "A moderator hid one of your comments due to policy concerns.",
+            serde_json::json!({ "reason": reason }),
+        ),
+        "restore" => (
+            "BULK_RESTORE_COMMENT",
+            "COMMENT_RESTORED",
+            "Your comment was restored",
+            "A moderator restored your comment.",
+            serde_json::json!({}),
+        ),
+        _ => (
+            "BULK_DELETE_COMMENT",
+            "COMMENT_DELETED",
+            "Your comment was deleted",
+            "A moderator removed one of your comments.",
+            serde_json::json!({}),
+        ),
+    };
+    let comment_ids: Vec<Uuid> = affected.iter().map(|row| row.id).collect();
+
+    let audit_ids: Vec<Uuid> = affected.iter().map(|_| Uuid::now_v7()).collect();
+    // Audited with the change itself, so a moderation is never left
+    // unrecorded.
+    sqlx::query(
+        "INSERT INTO admin_audit_logs (id, admin_sub, action, metadata, created_at)
+         SELECT a.id, $3, $4, jsonb_build_object('comment_id', a.comment_id) || $5::jsonb, NOW()
+         FROM unnest($1::uuid[], $2::uuid[]) AS a(id, comment_id)",
+    )
+    .bind(&audit_ids)
+    .bind(&comment_ids)
+    .bind(&claims.sub)
+    .bind(audit_action)
+    .bind(&details)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    tx.commit()
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        let Some(owner) = owner else {
-            failed_items.push(BulkCommentActionFailure {
-                id,
-                error: "Comment not found".to_string(),
-            });
-            continue;
-        };
-
-        let result = match action.as_str() {
-            "hide" => {
-                let update = sqlx::query(
-                    "UPDATE comments
-                     SET status = 'HIDDEN', needs_review = false, moderated_at = NOW(), moderated_by = $2, moderation_reason = $3, updated_at = NOW()
-                     WHERE id = $1",
-                )
-                .bind(id)
-                .bind(&claims.sub)
-                .bind(reason)
-                .execute(&state.db)
-                .await
-                .map_err(|e| AppError::Internal(e.to_string()));
-
-                if update.is_ok() {
-                    let _ = recompute_comments_count(&state, owner.lettering_id).await;
-                    log_admin_action(
-                        &state,
-                        &claims.sub,
-                        "BULK_HIDE_COMMENT",
-                        serde_json::json!({ "comment_id": id, "reason": reason }),
-                    )
-                    .await;
-                    notify_comment_owner(
-                        &state,
-                        owner.user_id,
-                        "COMMENT_HIDDEN",
-                        "Your comment was hidden",
-                        "A moderator hid one of your comments due to policy concerns.",
-                        serde_json::json!({ "comment_id": id, "reason": reason }),
-                    )
-                    .await;
-                }
-                update
+    let notifications: Vec<NotificationJob> = affected
+        .iter()
+        .filter_map(|row| {
+            let mut metadata = serde_json::json!({ "comment_id": row.id });
+            if let (Some(fields), Some(extra)) = (metadata.as_object_mut(), details.as_object()) {
+                fields.extend(extra.clone());
             }
-            "restore" => {
-                let update = sqlx::query(
-                    "UPDATE comments
-                     SET status = 'VISIBLE', needs_review = false, moderated_at = NULL, moderated_by = NULL, moderation_reason = NULL, updated_at = NOW()
-                     WHERE id = $1",
-                )
-                .bind(id)
-                .execute(&state.db)
-                .await
-                .map_err(|e| AppError::Internal(e.to_string()));
-
-                if update.is_ok() {
-                    let _ = recompute_comments_count(&state, owner.lettering_id).await;
-                    log_admin_action(
-                        &state,
-                        &claims.sub,
-                        "BULK_RESTORE_COMMENT",
-                        serde_json::json!({ "comment_id": id }),
-                    )
-                    .await;
-                    notify_comment_owner(
-                        &state,
-                        owner.user_id,
-                        "COMMENT_RESTORED",
-                        "Your comment was restored",
-                        "A moderator restored your comment.",
-                        serde_json::json!({ "comment_id": id }),
-                    )
-                    .await;
-                }
-                update
-            }
-            _ => {
-                let delete = sqlx::query("DELETE FROM comments WHERE id = $1")
-                    .bind(id)
-                    .execute(&state.db)
-                    .await
-                    .map_err(|e| AppError::Internal(e.to_string()));
-
-                if delete.is_ok() {
-                    let _ = recompute_comments_count(&state, owner.lettering_id).await;
-                    log_admin_action(
-                        &state,
-                        &claims.sub,
-                        "BULK_DELETE_COMMENT",
-                        serde_json::json!({ "comment_id": id }),
-                    )
-                    .await;
-                    notify_comment_owner(
-                        &state,
-                        owner.user_id,
-                        "COMMENT_DELETED",
-                        "Your comment was deleted",
-                        "A moderator removed one of your comments.",
-                        serde_json::json!({ "comment_id": id }),
-                    )
-                    .await;
-                }
-                delete
-            }
-        };
-
-        match result {
-            Ok(_) => processed += 1,
-            Err(err) => {
-                let message = match err {
-                    AppError::NotFound(msg) => msg,
-                    AppError::Forbidden(msg) => msg,
-                    AppError::BadRequest(msg) => msg,
-                    AppError::ValidationError(msg) => msg,
-                    AppError::RateLimited => "Rate limited".to_string(),
-                    AppError::Database(msg) => msg,
-                    AppError::Storage(msg) => msg,
-                    AppError::MlProcessing(msg) => msg,
-                    AppError::Queue(msg) => msg,
-                    AppError::ExternalService(msg) => msg,
-                    AppError::Internal(msg) => msg,
-                };
-                failed_items.push(BulkCommentActionFailure { id, error: message });
-            }
-        }
+            Some(NotificationJob {
+                user_id: row.user_id?,
+                kind: n_type.to_string(),
+                title: title.to_string(),
+                body: Some(message.to_string()),
+                metadata,
+            })
+        })
+        .collect();
+    let queued = notifications.len();
+    if let Err(e) = state.queue.enqueue_notifications(notifications).await {
+        tracing::error!("Failed to queue {} {} notifications: {}", queued, n_type, e);
     }
 
+    let failed_items: Vec<BulkCommentActionFailure> = ids
+        .iter()
+        .filter(|id| !comment_ids.contains(id))
+        .map(|id| BulkCommentActionFailure {
+            id: *id,
+            error: "Comment not found".to_string(),
+        })
+        .collect();
+    let processed = comment_ids.len();
+
     Ok(Json(BulkCommentActionResponse {
         requested: body.ids.len(),
         processed,
@@ -0,0 +1,56 @@
+use axum::{
+    Json,
+    extract::{Extension, State},
+};
+use std::sync::Arc;
+
+use crate::{
+    infrastructure::fault_injection::{FaultInjector, FaultSettings},
+    presentation::http::{
+        errors::AppError, handlers::admin::log_admin_action, middleware::admin::AdminClaims,
+        state::AppState,
+    },
+};
+
+fn injector(state: &AppState) -> Result<&Arc<FaultInjector>, AppError> {
+    state
+        .faults
+        .as_ref()
+        .ok_or_else(|| AppError::NotFound("Fault injection is not enabled".to_string()))
+}
+
+/// Current fault injection settings. 404 unless `ENABLE_FAULT_INJECTION` is
+/// set on a build that supports it.
+pub async fn get_fault_settings(
+    State(state): State<AppState>,
+) -> Result<Json<FaultSettings>, AppError> {
+    Ok(Json(injector(&state)?.settings()))
+}
+
+/// Replace the fault injection settings; omitted fields reset to 0. Takes
+/// effect immediately and restarts the every-k-th ML job count.
+pub async fn update_fault_settings(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(settings): Json<FaultSettings>,
+) -> Result<Json<FaultSettings>, AppError> {
+    let faults = injector(&state)?;
+    settings
+        .validate()
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let previous = faults.settings();
+    faults.update(settings);
+    tracing::warn!(?previous, current = ?settings, "Fault injection settings changed");
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "UPDATE_FAULT_INJECTION",
+        None,
+        serde_json::json!({ "previous": previous, "current": settings }),
+    )
+    .await;
+
+    Ok(Json(settings))
+}
@@ -0,0 +1,194 @@
+//! Admin control of percentage rollouts, with per-variant traffic side by
+//! side so a candidate path can be compared against the one it replaces.
+
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+};
+use chrono::{DateTime, Duration, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+use crate::{
+    infrastructure::feature_flags::{
+        FLAGS_KEY, ROLLOUT_FLAGS, RolloutFlag, STATS_RETENTION_DAYS, Variant, VariantStats,
+        find_flag, stats_key,
+    },
+    presentation::http::{
+        errors::AppError, handlers::admin::log_admin_action, middleware::admin::AdminClaims,
+        state::AppState,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct FeatureFlagStatsQuery {
+    #[serde(default = "default_days")]
+    pub days: i64,
+}
+
+fn default_days() -> i64 {
+    1
+}
+
+#[derive(Debug, FromRow)]
+struct FlagRow {
+    name: String,
+    rollout_percent: i16,
+    updated_by: String,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeatureFlagItem {
+    pub name: String,
+    pub description: String,
+    pub rollout_percent: u8,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub days: i64,
+    pub control: VariantStats,
+    pub candidate: VariantStats,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateFeatureFlagRequest {
+    /// Share of requests sent to the candidate path, 0-100.
+    pub rollout_percent: u8,
+}
+
+async fn load_flags(state: &AppState) -> Result<HashMap<String, FlagRow>, AppError> {
+    let rows = sqlx::query_as::<_, FlagRow>(
+        "SELECT name, rollout_percent, updated_by, updated_at FROM feature_flags",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(rows.into_iter().map(|r| (r.name.clone(), r)).collect())
+}
+
+async fn flag_item(
+    state: &AppState,
+    flag: &RolloutFlag,
+    row: Option<&FlagRow>,
+    days: i64,
+) -> Result<FeatureFlagItem, AppError> {
+    let mut conn = state
+        .redis
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let today = Utc::now().date_naive();
+    let mut counters: HashMap<String, u64> = HashMap::new();
+    for offset in 0..days {
+        let date = (today - Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string();
+        let daily: HashMap<String, u64> = conn
+            .hgetall(stats_key(flag.name, &date))
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        for (field, count) in daily {
+            *counters.entry(field).or_default() += count;
+        }
+    }
+
+    Ok(FeatureFlagItem {
+        name: flag.name.to_string(),
+        description: flag.description.to_string(),
+        rollout_percent: row.map_or(0, |r| r.rollout_percent.clamp(0, 100) as u8),
+        updated_by: row.map(|r| r.updated_by.clone()),
+        updated_at: row.map(|r| r.updated_at),
+        days,
+        control: VariantStats::from_counters(Variant::Control, &counters),
+        candidate: VariantStats::from_counters(Variant::Candidate, &counters),
+    })
+}
+
+/// Every rollout flag with its share and control-vs-candidate requests,
+/// error rate and latency. Counters are kept for `STATS_RETENTION_DAYS`.
+pub async fn list_feature_flags(
+    State(state): State<AppState>,
+    Query(params): Query<FeatureFlagStatsQuery>,
+) -> Result<Json<Vec<FeatureFlagItem>>, AppError> {
+    let days = params.days.clamp(1, STATS_RETENTION_DAYS);
+    let rows = load_flags(&state).await?;
+    let mut items = Vec::with_capacity(ROLLOUT_FLAGS.len());
+    for flag in ROLLOUT_FLAGS {
+        items.push(flag_item(&state, flag, rows.get(flag.name), days).await?);
+    }
+    Ok(Json(items))
+}
+
+/// Set a flag's rollout share and apply it on every replica immediately.
+pub async fn update_feature_flag(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(name): Path<String>,
+    Json(body): Json<UpdateFeatureFlagRequest>,
+) -> Result<Json<FeatureFlagItem>, AppError> {
+    let flag = find_flag(name.trim()).ok_or_else(|| {
+        AppError::NotFound(format!(
+            "Unknown feature flag; expected one of: {}",
+            ROLLOUT_FLAGS
+                .iter()
+                .map(|f| f.name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })?;
+    if body.rollout_percent > 100 {
+        return Err(AppError::BadRequest(
+            "rollout_percent must be between 0 and 100".to_string(),
+        ));
+    }
+
+    let previous = load_flags(&state)
+        .await?
+        .get(flag.name)
+        .map(|r| r.rollout_percent);
+
+    sqlx::query(
+        "INSERT INTO feature_flags (name, rollout_percent, updated_by, updated_at)
+         VALUES ($1, $2, $3, NOW())
+         ON CONFLICT (name) DO UPDATE
+         SET rollout_percent = EXCLUDED.rollout_percent,
+             updated_by = EXCLUDED.updated_by,
+             updated_at = NOW()",
+    )
+    .bind(flag.name)
+    .bind(i16::from(body.rollout_percent))
+    .bind(&claims.sub)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut conn = state
+        .redis
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let _: () = conn
+        .hset(FLAGS_KEY, flag.name, body.rollout_percent)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "UPDATE_FEATURE_FLAG",
+        None,
+        serde_json::json!({
+            "name": flag.name,
+            "previous_percent": previous,
+            "rollout_percent": body.rollout_percent,
+        }),
+    )
+    .await;
+
+    let rows = load_flags(&state).await?;
+    let item = flag_item(&state, flag, rows.get(flag.name), default_days()).await?;
+    Ok(Json(item))
+}
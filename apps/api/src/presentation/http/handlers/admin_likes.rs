@@ -0,0 +1,296 @@
+//! Cleanup of like-bombing: identities flagged by the like velocity limits,
+//! and rollback of one identity's likes over a time window.
+
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+};
+use chrono::{DateTime, Duration, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::types::ipnetwork::IpNetwork;
+use std::{collections::HashMap, str::FromStr};
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::security::like_velocity::{OFFENDERS_RETENTION_DAYS, offenders_key},
+    presentation::http::{
+        errors::AppError, handlers::admin::log_admin_action, middleware::admin::AdminClaims,
+        state::AppState,
+    },
+};
+
+/// Narrowest networks a rollback may cover, so a typo can't wipe out every
+/// like in the database.
+const MIN_IPV4_PREFIX: u8 = 16;
+const MIN_IPV6_PREFIX: u8 = 32;
+
+#[derive(Debug, Deserialize)]
+pub struct FlaggedLikesQuery {
+    #[serde(default = "default_days")]
+    pub days: i64,
+    #[serde(default = "default_top")]
+    pub top: isize,
+}
+
+fn default_days() -> i64 {
+    1
+}
+
+fn default_top() -> isize {
+    20
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlaggedLikeIdentity {
+    pub ip: String,
+    /// Like toggles refused by the velocity limits over the requested days.
+    pub blocked: u64,
+    /// Likes from this identity still standing from the same days.
+    pub likes: i64,
+    pub first_like_at: Option<DateTime<Utc>>,
+    pub last_like_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LikeRollbackRequest {
+    /// An IP address or a network in CIDR notation.
+    pub ip: String,
+    pub since: DateTime<Utc>,
+    /// Defaults to now.
+    pub until: Option<DateTime<Utc>>,
+    /// Report what would be removed without removing it.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolledBackLettering {
+    pub lettering_id: Uuid,
+    pub removed: i64,
+    /// Count after the rollback.
+    pub likes_count: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LikeRollbackResponse {
+    pub ip: String,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub dry_run: bool,
+    pub removed: i64,
+    pub letterings: Vec<RolledBackLettering>,
+}
+
+/// The network a rollback covers; a bare address is its own network.
+fn parse_identity(value: &str) -> Result<IpNetwork, AppError> {
+    let network = IpNetwork::from_str(value.trim()).map_err(|_| {
+        AppError::BadRequest("ip must be an IP address or CIDR network".to_string())
+    })?;
+    let min_prefix = match network {
+        IpNetwork::V4(_) => MIN_IPV4_PREFIX,
+        IpNetwork::V6(_) => MIN_IPV6_PREFIX,
+    };
+    if network.prefix() < min_prefix {
+        return Err(AppError::BadRequest(format!(
+            "Network is too broad; the prefix must be at least /{}",
+            min_prefix
+        )));
+    }
+    Ok(network)
+}
+
+/// Identities that ran past the like velocity limits, most refused first,
+/// with the likes they still hold from the same period.
+pub async fn list_flagged_likes(
+    State(state): State<AppState>,
+    Query(params): Query<FlaggedLikesQuery>,
+) -> Result<Json<Vec<FlaggedLikeIdentity>>, AppError> {
+    let days = params.days.clamp(1, OFFENDERS_RETENTION_DAYS);
+    let top = params.top.clamp(1, 100);
+
+    let mut conn = state
+        .redis
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let today = Utc::now().date_naive();
+    let mut blocked: HashMap<String, u64> = HashMap::new();
+    for offset in 0..days {
+        let date = (today - Duration::days(offset))
+            .format("%Y-%m-%d")
+            .to_string();
+        let daily: Vec<(String, u64)> = conn
+            .zrevrange_withscores(offenders_key(&date), 0, -1)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        for (ip, count) in daily {
+            *blocked.entry(ip).or_default() += count;
+        }
+    }
+    let mut offenders: Vec<(String, u64)> = blocked.into_iter().collect();
+    offenders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    offenders.truncate(top as usize);
+
+    let since = Utc::now() - Duration::days(days);
+    let mut items = Vec::with_capacity(offenders.len());
+    for (ip, blocked) in offenders {
+        let (likes, first_like_at, last_like_at) = match IpNetwork::from_str(&ip) {
+            Ok(network) => {
+                sqlx::query_as::<_, (i64, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>(
+                    "SELECT COUNT(*), MIN(created_at), MAX(created_at)
+                     FROM likes WHERE user_ip = $1 AND created_at >= $2",
+                )
+                .bind(network)
+                .bind(since)
+                .fetch_one(&state.db)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?
+            }
+            Err(_) => (0, None, None),
+        };
+        items.push(FlaggedLikeIdentity {
+            ip,
+            blocked,
+            likes,
+            first_like_at,
+            last_like_at,
+        });
+    }
+
+    Ok(Json(items))
+}
+
+/// Removes the likes an identity left between `since` and `until` and
+/// recounts `likes_count` of every lettering it touched from the remaining
+/// likes.
+pub async fn rollback_likes(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(body): Json<LikeRollbackRequest>,
+) -> Result<Json<LikeRollbackResponse>, AppError> {
+    let network = parse_identity(&body.ip)?;
+    let until = body.until.unwrap_or_else(Utc::now);
+    if body.since >= until {
+        return Err(AppError::BadRequest(
+            "since must be before until".to_string(),
+        ));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let removed_per_lettering = if body.dry_run {
+        "SELECT lettering_id, COUNT(*)::bigint AS removed
+         FROM likes
+         WHERE user_ip <<= $1 AND created_at >= $2 AND created_at < $3
+         GROUP BY lettering_id"
+    } else {
+        "WITH removed AS (
+             DELETE FROM likes
+             WHERE user_ip <<= $1 AND created_at >= $2 AND created_at < $3
+             RETURNING lettering_id
+         )
+         SELECT lettering_id, COUNT(*)::bigint AS removed FROM removed GROUP BY lettering_id"
+    };
+    let removed: Vec<(Uuid, i64)> = sqlx::query_as(removed_per_lettering)
+        .bind(network)
+        .bind(body.since)
+        .bind(until)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let ids: Vec<Uuid> = removed.iter().map(|(id, _)| *id).collect();
+
+    let counts: Vec<(Uuid, i32)> = if body.dry_run {
+        sqlx::query_as("SELECT id, likes_count FROM letterings WHERE id = ANY($1)")
+            .bind(&ids)
+            .fetch_all(&mut *tx)
+            .await
+    } else {
+        sqlx::query_as(
+            "UPDATE letterings l
+             SET likes_count = (SELECT COUNT(*) FROM likes WHERE lettering_id = l.id)
+             WHERE l.id = ANY($1)
+             RETURNING l.id, l.likes_count",
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await
+    }
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    let counts: HashMap<Uuid, i32> = counts.into_iter().collect();
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut letterings: Vec<RolledBackLettering> = removed
+        .into_iter()
+        .map(|(lettering_id, removed)| {
+            let current = counts.get(&lettering_id).copied().unwrap_or(0);
+            RolledBackLettering {
+                lettering_id,
+                removed,
+                likes_count: if body.dry_run {
+                    (current - removed as i32).max(0)
+                } else {
+                    current
+                },
+            }
+        })
+        .collect();
+    letterings.sort_by(|a, b| {
+        b.removed
+            .cmp(&a.removed)
+            .then_with(|| a.lettering_id.cmp(&b.lettering_id))
+    });
+    let total: i64 = letterings.iter().map(|l| l.removed).sum();
+
+    if !body.dry_run {
+        log_admin_action(
+            &state,
+            &claims.sub,
+            "ROLLBACK_LIKES",
+            None,
+            serde_json::json!({
+                "ip": network.to_string(),
+                "since": body.since,
+                "until": until,
+                "removed": total,
+                "letterings": letterings.len(),
+            }),
+        )
+        .await;
+    }
+
+    Ok(Json(LikeRollbackResponse {
+        ip: network.to_string(),
+        since: body.since,
+        until,
+        dry_run: body.dry_run,
+        removed: total,
+        letterings,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollbacks_cover_an_address_or_a_narrow_network() {
+        assert_eq!(
+            parse_identity(" 203.0.113.9 ").unwrap().to_string(),
+            "203.0.113.9/32"
+        );
+        assert!(parse_identity("203.0.113.0/24").is_ok());
+        assert!(parse_identity("2001:db8::/48").is_ok());
+        assert!(parse_identity("10.0.0.0/8").is_err());
+        assert!(parse_identity("2001::/16").is_err());
+        assert!(parse_identity("not-an-ip").is_err());
+    }
+}
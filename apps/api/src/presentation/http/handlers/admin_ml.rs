@@ -0,0 +1,956 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    config::ExecutionProviderKind,
+    infrastructure::{
+        ml::onnx_text_detector::ModelInfo,
+        monitoring::performance::CustomMetricSummary,
+        queue::{jobs::DeadLetter, redis_queue::MlJob},
+    },
+    presentation::http::{
+        errors::AppError,
+        handlers::{admin::log_admin_action, letterings},
+        middleware::admin::AdminClaims,
+        state::AppState,
+    },
+    workers::ml_reprocess::{self, ReprocessFilter},
+};
+
+const MAX_DETECTED_TEXT_LEN: usize = 2000;
+const MAX_LABEL_LEN: usize = 50;
+const MAX_REMOTE_MODEL_BYTES: usize = 512 * 1024 * 1024;
+const MODEL_DOWNLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+#[derive(Debug, Deserialize)]
+pub struct MlCorrectionRequest {
+    pub detected_text: Option<String>,
+    pub ml_style: Option<String>,
+    pub ml_script: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MlCorrectionResponse {
+    pub id: Uuid,
+    pub detected_text: Option<String>,
+    pub ml_style: Option<String>,
+    pub ml_script: Option<String>,
+    pub low_confidence_fields: Vec<String>,
+    pub corrected_fields: Vec<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct MlMetadataRow {
+    detected_text: Option<String>,
+    ml_style: Option<String>,
+    ml_script: Option<String>,
+    ml_low_confidence_fields: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrainingExportQuery {
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default = "default_export_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_export_limit() -> i64 {
+    500
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TrainingExportItem {
+    pub lettering_id: Uuid,
+    pub image_url: String,
+    pub image_hash: Option<String>,
+    pub detected_text: Option<String>,
+    pub ml_style: Option<String>,
+    pub ml_script: Option<String>,
+    /// Original model output for each corrected field, keyed by field name.
+    pub model_output: serde_json::Value,
+    pub last_corrected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrainingExportResponse {
+    pub items: Vec<TrainingExportItem>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Name used for a field in `ml_low_confidence_fields`.
+fn low_confidence_name(field: &str) -> &str {
+    match field {
+        "ml_style" => "style",
+        "ml_script" => "script",
+        other => other,
+    }
+}
+
+fn normalize_correction(
+    field: &str,
+    value: Option<String>,
+    max_len: usize,
+) -> Result<Option<String>, AppError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::BadRequest(format!("{} cannot be empty", field)));
+    }
+    if trimmed.chars().count() > max_len {
+        return Err(AppError::BadRequest(format!(
+            "{} must be at most {} characters",
+            field, max_len
+        )));
+    }
+    Ok(Some(trimmed.to_string()))
+}
+
+/// Apply human corrections to a lettering's ML metadata.
+///
+/// The first correction of a field records the model's original output so the
+/// training export always pairs the model value with the latest human value.
+/// Corrected fields are considered verified and drop out of
+/// `ml_low_confidence_fields`; the tsvector trigger re-indexes search on update.
+pub(crate) async fn apply_ml_corrections(
+    state: &AppState,
+    lettering_id: Uuid,
+    actor_type: &str,
+    actor_sub: &str,
+    body: MlCorrectionRequest,
+) -> Result<MlCorrectionResponse, AppError> {
+    let requested = [
+        (
+            "detected_text",
+            normalize_correction("detected_text", body.detected_text, MAX_DETECTED_TEXT_LEN)?,
+        ),
+        (
+            "ml_style",
+            normalize_correction("ml_style", body.ml_style, MAX_LABEL_LEN)?,
+        ),
+        (
+            "ml_script",
+            normalize_correction("ml_script", body.ml_script, MAX_LABEL_LEN)?,
+        ),
+    ];
+    if requested.iter().all(|(_, v)| v.is_none()) {
+        return Err(AppError::BadRequest("No corrections provided".to_string()));
+    }
+    let reason = body
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let existing = sqlx::query_as::<_, MlMetadataRow>(
+        "SELECT detected_text, ml_style, ml_script, ml_low_confidence_fields
+         FROM letterings
+         WHERE id = $1
+         FOR UPDATE",
+    )
+    .bind(lettering_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+
+    let mut detected_text = existing.detected_text.clone();
+    let mut ml_style = existing.ml_style.clone();
+    let mut ml_script = existing.ml_script.clone();
+    let mut corrected_fields = Vec::new();
+
+    for (field, value) in requested {
+        let Some(value) = value else { continue };
+        let current = match field {
+            "detected_text" => &mut detected_text,
+            "ml_style" => &mut ml_style,
+            _ => &mut ml_script,
+        };
+        if current.as_deref() == Some(value.as_str()) {
+            continue;
+        }
+
+        sqlx::query(
+            "INSERT INTO ml_metadata_corrections
+                 (id, lettering_id, field_name, model_value, previous_value, corrected_value, actor_type, actor_sub, reason)
+             VALUES (
+                 $1, $2, $3,
+                 COALESCE(
+                     (SELECT model_value FROM ml_metadata_corrections
+                      WHERE lettering_id = $2 AND field_name = $3
+                      ORDER BY created_at ASC LIMIT 1),
+                     $4
+                 ),
+                 $4, $5, $6, $7, $8
+             )",
+        )
+        .bind(Uuid::now_v7())
+        .bind(lettering_id)
+        .bind(field)
+        .bind(current.as_deref())
+        .bind(&value)
+        .bind(actor_type)
+        .bind(actor_sub)
+        .bind(&reason)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        *current = Some(value);
+        corrected_fields.push(field.to_string());
+    }
+
+    let verified: Vec<&str> = corrected_fields
+        .iter()
+        .map(|f| low_confidence_name(f))
+        .collect();
+    let low_confidence_fields: Vec<String> = existing
+        .ml_low_confidence_fields
+        .into_iter()
+        .filter(|f| !verified.contains(&f.as_str()))
+        .collect();
+
+    if !corrected_fields.is_empty() {
+        sqlx::query(
+            "UPDATE letterings
+             SET detected_text = $1,
+                 ml_style = $2,
+                 ml_script = $3,
+                 ml_low_confidence_fields = $4,
+                 updated_at = NOW()
+             WHERE id = $5",
+        )
+        .bind(&detected_text)
+        .bind(&ml_style)
+        .bind(&ml_script)
+        .bind(&low_confidence_fields)
+        .bind(lettering_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if !corrected_fields.is_empty() {
+        letterings::invalidate_lettering(state, lettering_id).await;
+    }
+
+    Ok(MlCorrectionResponse {
+        id: lettering_id,
+        detected_text,
+        ml_style,
+        ml_script,
+        low_confidence_fields,
+        corrected_fields,
+    })
+}
+
+pub async fn correct_ml_metadata(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<MlCorrectionRequest>,
+) -> Result<Json<MlCorrectionResponse>, AppError> {
+    let reason = body.reason.clone();
+    let response = apply_ml_corrections(&state, id, "ADMIN", &claims.sub, body).await?;
+
+    if !response.corrected_fields.is_empty() {
+        log_admin_action(
+            &state,
+            &claims.sub,
+            "CORRECT_ML_METADATA",
+            Some(id),
+            serde_json::json!({
+                "fields": response.corrected_fields,
+                "reason": reason,
+            }),
+        )
+        .await;
+    }
+
+    Ok(Json(response))
+}
+
+pub async fn export_training_corrections(
+    State(state): State<AppState>,
+    Query(params): Query<TrainingExportQuery>,
+) -> Result<Json<TrainingExportResponse>, AppError> {
+    let safe_limit = params.limit.clamp(1, 5000);
+    let safe_offset = params.offset.max(0);
+
+    let items = sqlx::query_as::<_, TrainingExportItem>(
+        "WITH first_corrections AS (
+             SELECT DISTINCT ON (lettering_id, field_name) lettering_id, field_name, model_value
+             FROM ml_metadata_corrections
+             ORDER BY lettering_id, field_name, created_at ASC
+         ),
+         latest AS (
+             SELECT lettering_id, MAX(created_at) AS last_corrected_at
+             FROM ml_metadata_corrections
+             GROUP BY lettering_id
+         )
+         SELECT l.id AS lettering_id, l.image_url, l.image_hash,
+                l.detected_text, l.ml_style, l.ml_script,
+                COALESCE(
+                    (SELECT jsonb_object_agg(fc.field_name, fc.model_value)
+                     FROM first_corrections fc
+                     WHERE fc.lettering_id = l.id),
+                    '{}'::jsonb
+                ) AS model_output,
+                latest.last_corrected_at
+         FROM latest
+         JOIN letterings l ON l.id = latest.lettering_id
+         WHERE ($1::timestamptz IS NULL OR latest.last_corrected_at >= $1)
+         ORDER BY latest.last_corrected_at ASC, l.id ASC
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(params.since)
+    .bind(safe_limit)
+    .bind(safe_offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(TrainingExportResponse {
+        items,
+        limit: safe_limit,
+        offset: safe_offset,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModelStatusResponse {
+    pub enabled: bool,
+    pub model_path: String,
+    pub golden_image_path: Option<String>,
+    /// Providers in the order they are tried; `model.execution_provider` is
+    /// the one actually in use.
+    pub execution_providers: Vec<ExecutionProviderKind>,
+    pub model: Option<ModelInfo>,
+    /// Mean per-image inference latency over the last hour, per provider.
+    pub inference_latency: Vec<CustomMetricSummary>,
+    /// `ml.*` metrics over the last hour: per-model latency, queue wait and
+    /// batch size histograms, and failure counts by cause.
+    pub metrics: Vec<CustomMetricSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModelReloadRequest {
+    /// Fetch the model from here instead of re-reading `ml_model_path`.
+    pub url: Option<String>,
+}
+
+pub async fn get_model_status(State(state): State<AppState>) -> Json<ModelStatusResponse> {
+    let (inference_latency, mut metrics): (Vec<_>, Vec<_>) = state
+        .performance
+        .get_custom_metrics_summary()
+        .await
+        .into_iter()
+        .filter(|m| m.name.starts_with("ml_inference_latency_ms.") || m.name.starts_with("ml."))
+        .partition(|m| m.name.starts_with("ml_inference_latency_ms."));
+    metrics.sort_by(|a, b| a.name.cmp(&b.name));
+    Json(ModelStatusResponse {
+        enabled: state.config.enable_ml_processing,
+        model_path: state.config.ml_model_path.clone(),
+        golden_image_path: state.config.ml_golden_image_path.clone(),
+        execution_providers: state.config.ml_execution_providers.clone(),
+        model: state.onnx_detector.model_info(),
+        inference_latency,
+        metrics,
+    })
+}
+
+async fn download_model(url: &str) -> Result<Vec<u8>, AppError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| AppError::BadRequest(format!("Invalid model URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "https" | "http") {
+        return Err(AppError::BadRequest(
+            "Model URL must be http(s)".to_string(),
+        ));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(MODEL_DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut response = client
+        .get(parsed)
+        .send()
+        .await
+        .map_err(|e| AppError::ExternalService(format!("Model download failed: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::ExternalService(format!(
+            "Model download returned HTTP {}",
+            response.status()
+        )));
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::ExternalService(format!("Model download failed: {}", e)))?
+    {
+        if bytes.len() + chunk.len() > MAX_REMOTE_MODEL_BYTES {
+            return Err(AppError::BadRequest(format!(
+                "Model exceeds {} bytes",
+                MAX_REMOTE_MODEL_BYTES
+            )));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
+
+/// Write a validated remote model over `ml_model_path` so restarts and the
+/// file watcher see the same model. Written to a sibling then renamed, so a
+/// reader never sees a partial file.
+async fn persist_model(path: &str, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp = format!("{}.tmp-{}", path, Uuid::now_v7());
+    tokio::fs::write(&tmp, bytes).await?;
+    if let Err(e) = tokio::fs::rename(&tmp, path).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Validate `bytes` and make them the production model. With `persist`, the
+/// model is also written over `ml_model_path`.
+async fn swap_in_model(
+    state: &AppState,
+    bytes: Vec<u8>,
+    source: String,
+    persist: bool,
+) -> Result<ModelInfo, AppError> {
+    let detector = state.onnx_detector.clone();
+    let (info, bytes) = tokio::task::spawn_blocking(move || {
+        detector
+            .load_model(&bytes, &source)
+            .map(|info| (info, bytes))
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .map_err(|e| AppError::ValidationError(format!("Model rejected: {}", e)))?;
+
+    let model_path = &state.config.ml_model_path;
+    if persist && let Err(e) = persist_model(model_path, &bytes).await {
+        tracing::warn!(
+            path = %model_path,
+            "Model loaded but could not be written to disk: {}",
+            e
+        );
+    }
+    Ok(info)
+}
+
+/// Load a new text detection model (from `ml_model_path` or a URL), validate
+/// it against the golden image and swap it in for subsequent inferences.
+pub async fn reload_model(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(body): Json<ModelReloadRequest>,
+) -> Result<Json<ModelInfo>, AppError> {
+    if !state.config.enable_ml_processing {
+        return Err(AppError::BadRequest(
+            "ML processing is disabled".to_string(),
+        ));
+    }
+
+    let url = body
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .map(str::to_string);
+    let model_path = state.config.ml_model_path.clone();
+
+    let (bytes, source) = match &url {
+        Some(url) => (download_model(url).await?, url.clone()),
+        None => (
+            tokio::fs::read(&model_path).await.map_err(|e| {
+                AppError::BadRequest(format!("Cannot read model file {}: {}", model_path, e))
+            })?,
+            model_path.clone(),
+        ),
+    };
+
+    let info = swap_in_model(&state, bytes, source, url.is_some()).await?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "RELOAD_ML_MODEL",
+        None,
+        serde_json::json!({
+            "source": info.source,
+            "sha256": info.sha256,
+            "size_bytes": info.size_bytes,
+        }),
+    )
+    .await;
+
+    Ok(Json(info))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShadowReportQuery {
+    #[serde(default = "default_shadow_days")]
+    pub days: i32,
+    #[serde(default = "default_disagreement_limit")]
+    pub limit: i64,
+}
+
+fn default_shadow_days() -> i32 {
+    7
+}
+
+fn default_disagreement_limit() -> i64 {
+    20
+}
+
+#[derive(Debug, FromRow)]
+struct ShadowComparisonRow {
+    production_version: Option<String>,
+    candidate_version: Option<String>,
+    samples: i64,
+    compared: i64,
+    agreed: i64,
+    candidate_errors: i64,
+    candidate_only_detections: i64,
+    production_only_detections: i64,
+    avg_production_confidence: Option<f64>,
+    avg_candidate_confidence: Option<f64>,
+    avg_production_latency_ms: Option<f64>,
+    avg_candidate_latency_ms: Option<f64>,
+    first_seen_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShadowComparison {
+    pub production_version: Option<String>,
+    pub candidate_version: Option<String>,
+    pub samples: i64,
+    pub compared: i64,
+    /// Share of compared samples where both models reported the same text.
+    pub agreement_rate: Option<f64>,
+    pub candidate_errors: i64,
+    /// Samples where only the candidate (or only production) found text.
+    pub candidate_only_detections: i64,
+    pub production_only_detections: i64,
+    pub avg_production_confidence: Option<f64>,
+    pub avg_candidate_confidence: Option<f64>,
+    pub avg_production_latency_ms: Option<f64>,
+    pub avg_candidate_latency_ms: Option<f64>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ShadowDisagreement {
+    pub lettering_id: Uuid,
+    pub production_version: Option<String>,
+    pub candidate_version: Option<String>,
+    pub production_text: Option<String>,
+    pub production_confidence: Option<f32>,
+    pub candidate_text: Option<String>,
+    pub candidate_confidence: Option<f32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShadowReportResponse {
+    pub days: i32,
+    pub shadow_model_path: Option<String>,
+    pub production_model: Option<ModelInfo>,
+    pub comparisons: Vec<ShadowComparison>,
+    pub recent_disagreements: Vec<ShadowDisagreement>,
+}
+
+/// Compare production and candidate ONNX outputs recorded in shadow mode,
+/// per version pair.
+pub async fn get_shadow_report(
+    State(state): State<AppState>,
+    Query(params): Query<ShadowReportQuery>,
+) -> Result<Json<ShadowReportResponse>, AppError> {
+    let days = params.days.clamp(1, 90);
+    let limit = params.limit.clamp(0, 200);
+
+    let rows = sqlx::query_as::<_, ShadowComparisonRow>(
+        "SELECT production_version, candidate_version,
+                COUNT(*)::bigint AS samples,
+                COUNT(*) FILTER (WHERE agreed IS NOT NULL)::bigint AS compared,
+                COUNT(*) FILTER (WHERE agreed)::bigint AS agreed,
+                COUNT(*) FILTER (WHERE candidate_error IS NOT NULL)::bigint AS candidate_errors,
+                COUNT(*) FILTER (
+                    WHERE NOT agreed AND candidate_confidence > 0
+                      AND COALESCE(production_confidence, 0) = 0
+                )::bigint AS candidate_only_detections,
+                COUNT(*) FILTER (
+                    WHERE NOT agreed AND production_confidence > 0
+                      AND COALESCE(candidate_confidence, 0) = 0
+                )::bigint AS production_only_detections,
+                AVG(production_confidence)::float8 AS avg_production_confidence,
+                AVG(candidate_confidence)::float8 AS avg_candidate_confidence,
+                AVG(production_latency_ms)::float8 AS avg_production_latency_ms,
+                AVG(candidate_latency_ms)::float8 AS avg_candidate_latency_ms,
+                MIN(created_at) AS first_seen_at,
+                MAX(created_at) AS last_seen_at
+         FROM ml_shadow_results
+         WHERE created_at >= NOW() - make_interval(days => $1)
+         GROUP BY production_version, candidate_version
+         ORDER BY last_seen_at DESC",
+    )
+    .bind(days)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let comparisons = rows
+        .into_iter()
+        .map(|r| ShadowComparison {
+            agreement_rate: (r.compared > 0).then(|| r.agreed as f64 / r.compared as f64),
+            production_version: r.production_version,
+            candidate_version: r.candidate_version,
+            samples: r.samples,
+            compared: r.compared,
+            candidate_errors: r.candidate_errors,
+            candidate_only_detections: r.candidate_only_detections,
+            production_only_detections: r.production_only_detections,
+            avg_production_confidence: r.avg_production_confidence,
+            avg_candidate_confidence: r.avg_candidate_confidence,
+            avg_production_latency_ms: r.avg_production_latency_ms,
+            avg_candidate_latency_ms: r.avg_candidate_latency_ms,
+            first_seen_at: r.first_seen_at,
+            last_seen_at: r.last_seen_at,
+        })
+        .collect();
+
+    let recent_disagreements = sqlx::query_as::<_, ShadowDisagreement>(
+        "SELECT lettering_id, production_version, candidate_version,
+                production_text, production_confidence, candidate_text, candidate_confidence,
+                created_at
+         FROM ml_shadow_results
+         WHERE agreed = FALSE AND created_at >= NOW() - make_interval(days => $1)
+         ORDER BY created_at DESC
+         LIMIT $2",
+    )
+    .bind(days)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(ShadowReportResponse {
+        days,
+        shadow_model_path: state.config.ml_shadow_model_path.clone(),
+        production_model: state.onnx_detector.model_info(),
+        comparisons,
+        recent_disagreements,
+    }))
+}
+
+/// Promote the shadow model to production: it is validated, swapped in and
+/// written over `ml_model_path`. The shadow worker keeps evaluating the same
+/// file until `ml_shadow_model_path` points at a new candidate.
+pub async fn promote_shadow_model(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+) -> Result<Json<ModelInfo>, AppError> {
+    if !state.config.enable_ml_processing {
+        return Err(AppError::BadRequest(
+            "ML processing is disabled".to_string(),
+        ));
+    }
+    let shadow_path = state
+        .config
+        .ml_shadow_model_path
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .ok_or_else(|| AppError::BadRequest("No shadow model configured".to_string()))?;
+
+    let bytes = tokio::fs::read(&shadow_path).await.map_err(|e| {
+        AppError::BadRequest(format!("Cannot read shadow model {}: {}", shadow_path, e))
+    })?;
+    let previous = state.onnx_detector.model_info().map(|info| info.sha256);
+    let info = swap_in_model(&state, bytes, shadow_path, true).await?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "PROMOTE_SHADOW_MODEL",
+        None,
+        serde_json::json!({
+            "source": info.source,
+            "sha256": info.sha256,
+            "previous_sha256": previous,
+        }),
+    )
+    .await;
+
+    Ok(Json(info))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    #[serde(default = "default_dead_letter_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+fn default_dead_letter_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetterListResponse {
+    pub items: Vec<DeadLetter<MlJob>>,
+    pub total: usize,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetterRequeueResponse {
+    pub requeued: Vec<MlJob>,
+}
+
+/// ML jobs that exhausted their retries, newest first.
+pub async fn list_ml_dead_letters(
+    State(state): State<AppState>,
+    Query(params): Query<DeadLetterQuery>,
+) -> Result<Json<DeadLetterListResponse>, AppError> {
+    let safe_limit = params.limit.clamp(1, 500);
+    let safe_offset = params.offset.max(0);
+
+    let (items, total) = state
+        .queue
+        .list_ml_dead_letters(safe_offset as isize, safe_limit as isize)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(DeadLetterListResponse {
+        items,
+        total,
+        limit: safe_limit,
+        offset: safe_offset,
+    }))
+}
+
+async fn requeue_dead_letters(
+    state: &AppState,
+    claims: &AdminClaims,
+    lettering_id: Option<Uuid>,
+) -> Result<Json<DeadLetterRequeueResponse>, AppError> {
+    let requeued = state
+        .queue
+        .requeue_ml_dead_letters(lettering_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if !requeued.is_empty() {
+        log_admin_action(
+            state,
+            &claims.sub,
+            "REQUEUE_ML_DEAD_LETTERS",
+            lettering_id,
+            serde_json::json!({
+                "lettering_ids": requeued.iter().map(|j| j.lettering_id).collect::<Vec<_>>(),
+            }),
+        )
+        .await;
+    }
+
+    Ok(Json(DeadLetterRequeueResponse { requeued }))
+}
+
+/// Requeue every dead-lettered ML job with a fresh attempt count.
+pub async fn requeue_all_ml_dead_letters(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+) -> Result<Json<DeadLetterRequeueResponse>, AppError> {
+    requeue_dead_letters(&state, &claims, None).await
+}
+
+/// Requeue the dead-lettered ML job(s) for one lettering.
+pub async fn requeue_ml_dead_letter(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(lettering_id): Path<Uuid>,
+) -> Result<Json<DeadLetterRequeueResponse>, AppError> {
+    let response = requeue_dead_letters(&state, &claims, Some(lettering_id)).await?;
+    if response.requeued.is_empty() {
+        return Err(AppError::NotFound(
+            "No dead-lettered ML job for this lettering".to_string(),
+        ));
+    }
+    Ok(response)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReprocessRunListQuery {
+    #[serde(default = "default_reprocess_run_limit")]
+    pub limit: i64,
+}
+
+fn default_reprocess_run_limit() -> i64 {
+    20
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ReprocessRunItem {
+    pub id: Uuid,
+    pub requested_by: String,
+    pub status: String,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub missing_text_only: bool,
+    pub model_version: Option<String>,
+    /// Letterings matching the filter when the run was created.
+    pub total: i32,
+    pub enqueued: i32,
+    pub processed: i32,
+    /// Jobs that ended up on the dead-letter list.
+    pub failed: i32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+const REPROCESS_RUN_COLUMNS: &str = "id, requested_by, status, created_from, created_to,
+     missing_text_only, model_version, total, enqueued, processed, failed,
+     created_at, started_at, finished_at";
+
+async fn fetch_reprocess_run(state: &AppState, id: Uuid) -> Result<ReprocessRunItem, AppError> {
+    sqlx::query_as::<_, ReprocessRunItem>(&format!(
+        "SELECT {} FROM ml_reprocess_runs WHERE id = $1",
+        REPROCESS_RUN_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Reprocess run not found".to_string()))
+}
+
+/// Queue existing letterings for another pass through the current models.
+/// The reprocess worker feeds them to the ML worker at a limited rate behind
+/// live uploads; poll `GET /api/v1/admin/ml/reprocess/{id}` for progress.
+/// Moderation status is left as it is.
+pub async fn create_reprocess_run(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(mut filter): Json<ReprocessFilter>,
+) -> Result<(StatusCode, Json<ReprocessRunItem>), AppError> {
+    filter.model_version = filter
+        .model_version
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    filter
+        .validate()
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let id = ml_reprocess::create_reprocess_run(&state.db, &claims.sub, &filter)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let run = fetch_reprocess_run(&state, id).await?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "CREATE_ML_REPROCESS_RUN",
+        None,
+        serde_json::json!({
+            "run_id": run.id,
+            "created_from": run.created_from,
+            "created_to": run.created_to,
+            "missing_text_only": run.missing_text_only,
+            "model_version": run.model_version,
+            "total": run.total,
+        }),
+    )
+    .await;
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+pub async fn list_reprocess_runs(
+    State(state): State<AppState>,
+    Query(params): Query<ReprocessRunListQuery>,
+) -> Result<Json<Vec<ReprocessRunItem>>, AppError> {
+    let runs = sqlx::query_as::<_, ReprocessRunItem>(&format!(
+        "SELECT {} FROM ml_reprocess_runs ORDER BY created_at DESC LIMIT $1",
+        REPROCESS_RUN_COLUMNS
+    ))
+    .bind(params.limit.clamp(1, 100))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(runs))
+}
+
+pub async fn get_reprocess_run(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ReprocessRunItem>, AppError> {
+    fetch_reprocess_run(&state, id).await.map(Json)
+}
+
+/// Stop feeding a run. Jobs already on the low-priority queue still run.
+pub async fn cancel_reprocess_run(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ReprocessRunItem>, AppError> {
+    let cancelled = sqlx::query(
+        "UPDATE ml_reprocess_runs SET status = 'CANCELLED', finished_at = NOW()
+         WHERE id = $1 AND status IN ('PENDING', 'RUNNING')",
+    )
+    .bind(id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .rows_affected();
+
+    let run = fetch_reprocess_run(&state, id).await?;
+    if cancelled == 0 {
+        return Err(AppError::BadRequest(format!(
+            "Reprocess run is already {}",
+            run.status
+        )));
+    }
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "CANCEL_ML_REPROCESS_RUN",
+        None,
+        serde_json::json!({ "run_id": id, "enqueued": run.enqueued }),
+    )
+    .await;
+
+    Ok(Json(run))
+}
@@ -0,0 +1,230 @@
+//! Admin management of localized city and country names.
+
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::presentation::http::{
+    errors::AppError,
+    handlers::{admin::log_admin_action, admin_region_policies::normalize_country_code},
+    locale::{SUPPORTED_LOCALES, normalize_locale},
+    middleware::admin::AdminClaims,
+    state::AppState,
+};
+
+const MAX_PLACE_NAME_CHARS: usize = 120;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PlaceNameTranslation {
+    pub locale: String,
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertPlaceNameRequest {
+    pub name: String,
+}
+
+fn parse_locale(locale: &str) -> Result<String, AppError> {
+    normalize_locale(locale).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "locale must be one of {} (optionally with a region, e.g. kn-IN)",
+            SUPPORTED_LOCALES.join(", ")
+        ))
+    })
+}
+
+fn parse_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PLACE_NAME_CHARS {
+        return Err(AppError::BadRequest(
+            "name must be between 1 and 120 characters".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+async fn ensure_city_exists(state: &AppState, id: Uuid) -> Result<(), AppError> {
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM cities WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound("City not found".to_string()))?;
+    Ok(())
+}
+
+pub async fn list_city_name_translations(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PlaceNameTranslation>>, AppError> {
+    ensure_city_exists(&state, id).await?;
+    let items = sqlx::query_as::<_, PlaceNameTranslation>(
+        "SELECT locale, name, updated_at FROM city_name_translations
+         WHERE city_id = $1
+         ORDER BY locale",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(items))
+}
+
+pub async fn upsert_city_name_translation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path((id, locale)): Path<(Uuid, String)>,
+    Json(body): Json<UpsertPlaceNameRequest>,
+) -> Result<Json<PlaceNameTranslation>, AppError> {
+    let locale = parse_locale(&locale)?;
+    let name = parse_name(&body.name)?;
+    ensure_city_exists(&state, id).await?;
+
+    let item = sqlx::query_as::<_, PlaceNameTranslation>(
+        "INSERT INTO city_name_translations (city_id, locale, name)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (city_id, locale) DO UPDATE
+         SET name = EXCLUDED.name, updated_at = NOW()
+         RETURNING locale, name, updated_at",
+    )
+    .bind(id)
+    .bind(&locale)
+    .bind(&name)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "UPSERT_CITY_NAME_TRANSLATION",
+        None,
+        serde_json::json!({ "city_id": id, "locale": locale, "name": name }),
+    )
+    .await;
+
+    Ok(Json(item))
+}
+
+pub async fn delete_city_name_translation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path((id, locale)): Path<(Uuid, String)>,
+) -> Result<StatusCode, AppError> {
+    let locale = parse_locale(&locale)?;
+    let deleted =
+        sqlx::query("DELETE FROM city_name_translations WHERE city_id = $1 AND locale = $2")
+            .bind(id)
+            .bind(&locale)
+            .execute(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound("Translation not found".to_string()));
+    }
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "DELETE_CITY_NAME_TRANSLATION",
+        None,
+        serde_json::json!({ "city_id": id, "locale": locale }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn list_country_name_translations(
+    State(state): State<AppState>,
+    Path(country_code): Path<String>,
+) -> Result<Json<Vec<PlaceNameTranslation>>, AppError> {
+    let country_code = normalize_country_code(&country_code)?;
+    let items = sqlx::query_as::<_, PlaceNameTranslation>(
+        "SELECT locale, name, updated_at FROM country_name_translations
+         WHERE country_code = $1
+         ORDER BY locale",
+    )
+    .bind(&country_code)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(items))
+}
+
+pub async fn upsert_country_name_translation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path((country_code, locale)): Path<(String, String)>,
+    Json(body): Json<UpsertPlaceNameRequest>,
+) -> Result<Json<PlaceNameTranslation>, AppError> {
+    let country_code = normalize_country_code(&country_code)?;
+    let locale = parse_locale(&locale)?;
+    let name = parse_name(&body.name)?;
+
+    let item = sqlx::query_as::<_, PlaceNameTranslation>(
+        "INSERT INTO country_name_translations (country_code, locale, name)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (country_code, locale) DO UPDATE
+         SET name = EXCLUDED.name, updated_at = NOW()
+         RETURNING locale, name, updated_at",
+    )
+    .bind(&country_code)
+    .bind(&locale)
+    .bind(&name)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "UPSERT_COUNTRY_NAME_TRANSLATION",
+        None,
+        serde_json::json!({ "country_code": country_code, "locale": locale, "name": name }),
+    )
+    .await;
+
+    Ok(Json(item))
+}
+
+pub async fn delete_country_name_translation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path((country_code, locale)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let country_code = normalize_country_code(&country_code)?;
+    let locale = parse_locale(&locale)?;
+    let deleted = sqlx::query(
+        "DELETE FROM country_name_translations WHERE country_code = $1 AND locale = $2",
+    )
+    .bind(&country_code)
+    .bind(&locale)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .rows_affected();
+    if deleted == 0 {
+        return Err(AppError::NotFound("Translation not found".to_string()));
+    }
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "DELETE_COUNTRY_NAME_TRANSLATION",
+        None,
+        serde_json::json!({ "country_code": country_code, "locale": locale }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,197 @@
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::{collections::HashSet, time::Duration};
+use uuid::Uuid;
+
+use crate::presentation::http::{
+    errors::AppError, handlers::admin::log_admin_action, middleware::admin::AdminClaims,
+    state::AppState,
+};
+
+/// Largest selection one bundle may contain.
+const MAX_BUNDLE_ITEMS: usize = 200;
+const DOWNLOAD_URL_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePrintBundleRequest {
+    pub title: Option<String>,
+    pub lettering_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrintBundleListQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct PrintBundleItem {
+    pub id: Uuid,
+    pub requested_by: String,
+    pub title: String,
+    pub lettering_ids: Vec<Uuid>,
+    pub status: String,
+    pub size_bytes: Option<i64>,
+    pub items_included: i32,
+    pub items_missing: i32,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub object_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PrintBundleResponse {
+    #[serde(flatten)]
+    pub bundle: PrintBundleItem,
+    /// Signed, short-lived link to the zip once the bundle has completed.
+    pub download_url: Option<String>,
+}
+
+const BUNDLE_COLUMNS: &str = "id, requested_by, title, lettering_ids, status, size_bytes,
+     items_included, items_missing, error, created_at, started_at, finished_at, object_key";
+
+/// Queue a print bundle. The zip is assembled by the print bundle worker;
+/// poll `GET /api/v1/admin/print-bundles/{id}` for the download link.
+pub async fn create_print_bundle(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(body): Json<CreatePrintBundleRequest>,
+) -> Result<(StatusCode, Json<PrintBundleResponse>), AppError> {
+    let mut seen = HashSet::new();
+    let lettering_ids: Vec<Uuid> = body
+        .lettering_ids
+        .into_iter()
+        .filter(|id| seen.insert(*id))
+        .collect();
+    if lettering_ids.is_empty() {
+        return Err(AppError::ValidationError(
+            "lettering_ids must not be empty".to_string(),
+        ));
+    }
+    if lettering_ids.len() > MAX_BUNDLE_ITEMS {
+        return Err(AppError::ValidationError(format!(
+            "A print bundle can contain at most {} letterings",
+            MAX_BUNDLE_ITEMS
+        )));
+    }
+
+    let approved: Vec<Uuid> =
+        sqlx::query_scalar("SELECT id FROM letterings WHERE id = ANY($1) AND status = 'APPROVED'")
+            .bind(&lettering_ids)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+    let unavailable: Vec<String> = lettering_ids
+        .iter()
+        .filter(|id| !approved.contains(id))
+        .map(Uuid::to_string)
+        .collect();
+    if !unavailable.is_empty() {
+        return Err(AppError::ValidationError(format!(
+            "Only approved letterings can be exported; not available: {}",
+            unavailable.join(", ")
+        )));
+    }
+
+    let title = body
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Print bundle {}", Utc::now().format("%Y-%m-%d")));
+
+    let bundle = sqlx::query_as::<_, PrintBundleItem>(&format!(
+        "INSERT INTO print_bundles (id, requested_by, title, lettering_ids)
+         VALUES ($1, $2, $3, $4)
+         RETURNING {}",
+        BUNDLE_COLUMNS
+    ))
+    .bind(Uuid::now_v7())
+    .bind(&claims.sub)
+    .bind(&title)
+    .bind(&lettering_ids)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "CREATE_PRINT_BUNDLE",
+        None,
+        serde_json::json!({
+            "bundle_id": bundle.id,
+            "title": bundle.title,
+            "items": bundle.lettering_ids.len(),
+        }),
+    )
+    .await;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(PrintBundleResponse {
+            bundle,
+            download_url: None,
+        }),
+    ))
+}
+
+pub async fn list_print_bundles(
+    State(state): State<AppState>,
+    Query(params): Query<PrintBundleListQuery>,
+) -> Result<Json<Vec<PrintBundleItem>>, AppError> {
+    let bundles = sqlx::query_as::<_, PrintBundleItem>(&format!(
+        "SELECT {} FROM print_bundles ORDER BY created_at DESC LIMIT $1",
+        BUNDLE_COLUMNS
+    ))
+    .bind(params.limit.clamp(1, 100))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(bundles))
+}
+
+pub async fn get_print_bundle(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PrintBundleResponse>, AppError> {
+    let bundle = sqlx::query_as::<_, PrintBundleItem>(&format!(
+        "SELECT {} FROM print_bundles WHERE id = $1",
+        BUNDLE_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Print bundle not found".to_string()))?;
+
+    let download_url = match bundle.object_key.as_deref() {
+        Some(key) if bundle.status == "COMPLETED" => Some(
+            state
+                .storage
+                .signed_url(key, DOWNLOAD_URL_TTL)
+                .await
+                .map_err(|e| AppError::Storage(e.to_string()))?,
+        ),
+        _ => None,
+    };
+
+    Ok(Json(PrintBundleResponse {
+        bundle,
+        download_url,
+    }))
+}
@@ -0,0 +1,270 @@
+use axum::{
+    Json,
+    extract::{Extension, Query, State},
+};
+use chrono::{DateTime, Duration, Utc};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+use crate::presentation::http::{
+    errors::AppError,
+    handlers::admin::log_admin_action,
+    middleware::{
+        admin::AdminClaims,
+        rate_limit::{
+            OVERRIDES_KEY, RATE_LIMITED_ROUTES, STATS_RETENTION_DAYS, default_limit, offenders_key,
+            stats_key,
+        },
+    },
+    state::AppState,
+};
+
+const MAX_LIMIT_PER_DAY: u32 = 1_000_000;
+
+#[derive(Debug, Deserialize)]
+pub struct RateLimitStatsQuery {
+    #[serde(default = "default_days")]
+    pub days: i64,
+    #[serde(default = "default_top")]
+    pub top: isize,
+}
+
+fn default_days() -> i64 {
+    1
+}
+
+fn default_top() -> isize {
+    10
+}
+
+#[derive(Debug, FromRow)]
+struct OverrideRow {
+    route: String,
+    limit_per_day: i32,
+    updated_by: String,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RateLimitOffender {
+    pub ip: String,
+    pub blocked: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RateLimitRouteStats {
+    pub route: String,
+    pub default_limit: u32,
+    pub override_limit: Option<u32>,
+    pub effective_limit: u32,
+    pub override_updated_by: Option<String>,
+    pub override_updated_at: Option<DateTime<Utc>>,
+    pub requests: u64,
+    pub blocked: u64,
+    pub top_offenders: Vec<RateLimitOffender>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RateLimitStatsResponse {
+    pub days: i64,
+    pub routes: Vec<RateLimitRouteStats>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRateLimitRequest {
+    pub route: String,
+    /// Per-IP requests per day; `0` disables the limit, `null` restores the
+    /// configured default.
+    pub limit_per_day: Option<u32>,
+}
+
+async fn load_overrides(state: &AppState) -> Result<HashMap<String, OverrideRow>, AppError> {
+    let rows = sqlx::query_as::<_, OverrideRow>(
+        "SELECT route, limit_per_day, updated_by, updated_at FROM rate_limit_overrides",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(rows.into_iter().map(|r| (r.route.clone(), r)).collect())
+}
+
+async fn route_stats(
+    state: &AppState,
+    overrides: &HashMap<String, OverrideRow>,
+    days: i64,
+    top: isize,
+) -> Result<Vec<RateLimitRouteStats>, AppError> {
+    let mut conn = state
+        .redis
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let today = Utc::now().date_naive();
+    let dates: Vec<String> = (0..days)
+        .map(|offset| {
+            (today - Duration::days(offset))
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .collect();
+
+    let mut routes = Vec::with_capacity(RATE_LIMITED_ROUTES.len());
+    for route in RATE_LIMITED_ROUTES {
+        let mut requests = 0_u64;
+        let mut blocked = 0_u64;
+        let mut offenders: HashMap<String, u64> = HashMap::new();
+
+        for date in &dates {
+            let counters: HashMap<String, u64> = conn
+                .hgetall(stats_key(date))
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            requests += counters
+                .get(&format!("{}:requests", route))
+                .copied()
+                .unwrap_or(0);
+            blocked += counters
+                .get(&format!("{}:blocked", route))
+                .copied()
+                .unwrap_or(0);
+
+            let daily: Vec<(String, f64)> = conn
+                .zrevrange_withscores(offenders_key(route, date), 0, top - 1)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            for (ip, score) in daily {
+                *offenders.entry(ip).or_default() += score as u64;
+            }
+        }
+
+        let mut top_offenders: Vec<RateLimitOffender> = offenders
+            .into_iter()
+            .map(|(ip, blocked)| RateLimitOffender { ip, blocked })
+            .collect();
+        top_offenders.sort_by(|a, b| b.blocked.cmp(&a.blocked).then_with(|| a.ip.cmp(&b.ip)));
+        top_offenders.truncate(top as usize);
+
+        let default = default_limit(&state.config, route);
+        let current = overrides.get(*route);
+        let override_limit = current.map(|o| o.limit_per_day as u32);
+        routes.push(RateLimitRouteStats {
+            route: route.to_string(),
+            default_limit: default,
+            override_limit,
+            effective_limit: override_limit.unwrap_or(default),
+            override_updated_by: current.map(|o| o.updated_by.clone()),
+            override_updated_at: current.map(|o| o.updated_at),
+            requests,
+            blocked,
+            top_offenders,
+        });
+    }
+
+    Ok(routes)
+}
+
+/// Per-route request and block counts, top offending IPs and the limits in
+/// force. Counters are kept for `STATS_RETENTION_DAYS`.
+pub async fn get_rate_limit_stats(
+    State(state): State<AppState>,
+    Query(params): Query<RateLimitStatsQuery>,
+) -> Result<Json<RateLimitStatsResponse>, AppError> {
+    let days = params.days.clamp(1, STATS_RETENTION_DAYS);
+    let top = params.top.clamp(1, 100);
+
+    let overrides = load_overrides(&state).await?;
+    let routes = route_stats(&state, &overrides, days, top).await?;
+
+    Ok(Json(RateLimitStatsResponse { days, routes }))
+}
+
+/// Persist a per-route limit override and apply it immediately.
+pub async fn update_rate_limit(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Json(body): Json<UpdateRateLimitRequest>,
+) -> Result<Json<RateLimitRouteStats>, AppError> {
+    let route = body.route.trim();
+    if !RATE_LIMITED_ROUTES.contains(&route) {
+        return Err(AppError::BadRequest(format!(
+            "route must be one of: {}",
+            RATE_LIMITED_ROUTES.join(", ")
+        )));
+    }
+    if body.limit_per_day.is_some_and(|l| l > MAX_LIMIT_PER_DAY) {
+        return Err(AppError::BadRequest(format!(
+            "limit_per_day must be at most {}",
+            MAX_LIMIT_PER_DAY
+        )));
+    }
+
+    let mut conn = state
+        .redis
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let previous = load_overrides(&state)
+        .await?
+        .get(route)
+        .map(|o| o.limit_per_day);
+
+    match body.limit_per_day {
+        Some(limit) => {
+            sqlx::query(
+                "INSERT INTO rate_limit_overrides (route, limit_per_day, updated_by, updated_at)
+                 VALUES ($1, $2, $3, NOW())
+                 ON CONFLICT (route) DO UPDATE
+                 SET limit_per_day = EXCLUDED.limit_per_day,
+                     updated_by = EXCLUDED.updated_by,
+                     updated_at = NOW()",
+            )
+            .bind(route)
+            .bind(limit as i32)
+            .bind(&claims.sub)
+            .execute(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+            let _: () = conn
+                .hset(OVERRIDES_KEY, route, limit)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+        }
+        None => {
+            sqlx::query("DELETE FROM rate_limit_overrides WHERE route = $1")
+                .bind(route)
+                .execute(&state.db)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            let _: () = conn
+                .hdel(OVERRIDES_KEY, route)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+        }
+    }
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "UPDATE_RATE_LIMIT",
+        None,
+        serde_json::json!({
+            "route": route,
+            "previous_limit": previous,
+            "limit_per_day": body.limit_per_day,
+        }),
+    )
+    .await;
+
+    let overrides = load_overrides(&state).await?;
+    let item = route_stats(&state, &overrides, 1, default_top())
+        .await?
+        .into_iter()
+        .find(|r| r.route == route)
+        .ok_or_else(|| AppError::Internal("rate-limited route missing".to_string()))?;
+
+    Ok(Json(item))
+}
@@ -7,10 +7,39 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Postgres, QueryBuilder};
 
-use crate::presentation::http::{
-    errors::AppError, middleware::admin::AdminClaims, state::AppState,
+use crate::{
+    infrastructure::{
+        cache::invalidation::{BROWSE_PREFIX, GALLERY_PREFIX, RESPONSE_PREFIX},
+        repositories::region_policy::{EffectivePolicy, effective_policy},
+    },
+    presentation::http::{errors::AppError, middleware::admin::AdminClaims, state::AppState},
 };
 
+/// How long a country's policy is served from cache; upserts invalidate it.
+const REGION_POLICY_CACHE_TTL_SECONDS: u64 = 300;
+
+fn region_policy_cache_key(country_code: &str) -> String {
+    format!("region_policy:{}", country_code)
+}
+
+/// The policy in force for `country_code`, through the hot cache.
+pub(crate) async fn region_policy(
+    state: &AppState,
+    country_code: &str,
+) -> Result<EffectivePolicy, AppError> {
+    let db = state.db.clone();
+    let country_code = country_code.to_string();
+    state
+        .hot_cache
+        .get_or_fetch(
+            &region_policy_cache_key(&country_code),
+            REGION_POLICY_CACHE_TTL_SECONDS,
+            || async move { Ok(effective_policy(&db, &country_code).await?) },
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RegionPoliciesQuery {
     pub country_code: Option<String>,
@@ -30,6 +59,7 @@ pub struct RegionPolicyItem {
     pub uploads_enabled: bool,
     pub comments_enabled: bool,
     pub discoverability_enabled: bool,
+    pub age_gate_enabled: bool,
     pub auto_moderation_level: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -48,10 +78,11 @@ pub struct UpsertRegionPolicyRequest {
     pub uploads_enabled: Option<bool>,
     pub comments_enabled: Option<bool>,
     pub discoverability_enabled: Option<bool>,
+    pub age_gate_enabled: Option<bool>,
     pub auto_moderation_level: Option<String>,
 }
 
-fn normalize_country_code(code: &str) -> Result<String, AppError> {
+pub(crate) fn normalize_country_code(code: &str) -> Result<String, AppError> {
     let normalized = code.trim().to_uppercase();
     if normalized.len() != 2 || !normalized.chars().all(|c| c.is_ascii_alphabetic()) {
         return Err(AppError::BadRequest(
@@ -82,7 +113,7 @@ pub async fn list_region_policies(
         .transpose()?;
 
     let mut qb = QueryBuilder::<Postgres>::new(
-        "SELECT country_code, uploads_enabled, comments_enabled, discoverability_enabled, auto_moderation_level, created_at, updated_at
+        "SELECT country_code, uploads_enabled, comments_enabled, discoverability_enabled, age_gate_enabled, auto_moderation_level, created_at, updated_at
          FROM region_policies",
     );
 
@@ -101,9 +132,12 @@ pub async fn list_region_policies(
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    let mut count_qb = QueryBuilder::<Postgres>::new("SELECT COUNT(*)::bigint FROM region_policies");
+    let mut count_qb =
+        QueryBuilder::<Postgres>::new("SELECT COUNT(*)::bigint FROM region_policies");
     if let Some(country_code) = &country {
-        count_qb.push(" WHERE country_code = ").push_bind(country_code);
+        count_qb
+            .push(" WHERE country_code = ")
+            .push_bind(country_code);
     }
     let total: i64 = count_qb
         .build_query_scalar()
@@ -131,15 +165,17 @@ pub async fn upsert_region_policy(
 
     sqlx::query(
         "INSERT INTO region_policies (
-            country_code, uploads_enabled, comments_enabled, discoverability_enabled, auto_moderation_level
+            country_code, uploads_enabled, comments_enabled, discoverability_enabled, auto_moderation_level,
+            age_gate_enabled
         ) VALUES (
-            $1, COALESCE($2, true), COALESCE($3, true), COALESCE($4, true), $5
+            $1, COALESCE($2, true), COALESCE($3, true), COALESCE($4, true), $5, COALESCE($6, false)
         )
         ON CONFLICT (country_code) DO UPDATE
         SET uploads_enabled = COALESCE($2, region_policies.uploads_enabled),
             comments_enabled = COALESCE($3, region_policies.comments_enabled),
             discoverability_enabled = COALESCE($4, region_policies.discoverability_enabled),
             auto_moderation_level = COALESCE($5, region_policies.auto_moderation_level),
+            age_gate_enabled = COALESCE($6, region_policies.age_gate_enabled),
             updated_at = NOW()",
     )
     .bind(&country_code)
@@ -147,12 +183,22 @@ pub async fn upsert_region_policy(
     .bind(body.comments_enabled)
     .bind(body.discoverability_enabled)
     .bind(auto_moderation_level)
+    .bind(body.age_gate_enabled)
     .execute(&state.db)
     .await
     .map_err(|e| AppError::Internal(e.to_string()))?;
+    state
+        .hot_cache
+        .invalidate(&region_policy_cache_key(&country_code))
+        .await;
+    // Discoverability and age gating decide what feeds list and which
+    // lettering pages answer.
+    for prefix in [GALLERY_PREFIX, BROWSE_PREFIX, RESPONSE_PREFIX] {
+        state.hot_cache.invalidate_prefix(prefix).await;
+    }
 
     let item = sqlx::query_as::<_, RegionPolicyItem>(
-        "SELECT country_code, uploads_enabled, comments_enabled, discoverability_enabled, auto_moderation_level, created_at, updated_at
+        "SELECT country_code, uploads_enabled, comments_enabled, discoverability_enabled, age_gate_enabled, auto_moderation_level, created_at, updated_at
          FROM region_policies
          WHERE country_code = $1",
     )
@@ -173,6 +219,7 @@ pub async fn upsert_region_policy(
         "uploads_enabled": item.uploads_enabled,
         "comments_enabled": item.comments_enabled,
         "discoverability_enabled": item.discoverability_enabled,
+        "age_gate_enabled": item.age_gate_enabled,
         "auto_moderation_level": item.auto_moderation_level
     }))
     .execute(&state.db)
@@ -0,0 +1,196 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    presentation::http::{errors::AppError, middleware::admin::AdminClaims, state::AppState},
+    workers::scheduler::{
+        CronSchedule, SCHEDULES, ScheduleSource, definition, effective_schedules,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct SchedulesQuery {
+    /// Recent runs listed per task.
+    #[serde(default = "default_runs")]
+    pub runs: i64,
+}
+
+fn default_runs() -> i64 {
+    10
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScheduledRun {
+    #[serde(skip)]
+    pub name: String,
+    pub id: Uuid,
+    pub scheduled_for: DateTime<Utc>,
+    /// Instance that took the tick.
+    pub instance: String,
+    /// `RUNNING`, `SUCCEEDED`, `FAILED` or `SKIPPED`.
+    pub status: String,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleStatus {
+    pub name: String,
+    pub description: Option<&'static str>,
+    pub default_cron: Option<&'static str>,
+    /// The schedule in effect, in UTC.
+    pub cron: Option<String>,
+    pub source: ScheduleSource,
+    pub enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    /// Newest first.
+    pub recent_runs: Vec<ScheduledRun>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchedulesResponse {
+    pub items: Vec<ScheduleStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateScheduleRequest {
+    /// Replaces the configured schedule; `null` or absent goes back to it.
+    pub cron: Option<String>,
+    /// Left unchanged when absent.
+    pub enabled: Option<bool>,
+}
+
+async fn schedule_statuses(
+    state: &AppState,
+    names: &[&str],
+    runs: i64,
+) -> Result<Vec<ScheduleStatus>, AppError> {
+    let schedules = effective_schedules(&state.db, &state.config.schedules, names)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut recent = sqlx::query_as::<_, ScheduledRun>(
+        "SELECT name, id, scheduled_for, instance, status, error, started_at, finished_at
+         FROM (
+             SELECT *, ROW_NUMBER() OVER (PARTITION BY name ORDER BY scheduled_for DESC) AS n
+             FROM scheduled_task_runs
+             WHERE name = ANY($1)
+         ) runs
+         WHERE n <= $2
+         ORDER BY name, scheduled_for DESC",
+    )
+    .bind(names)
+    .bind(runs.clamp(1, 100))
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let now = Utc::now();
+    Ok(schedules
+        .into_iter()
+        .map(|schedule| {
+            let definition = definition(&schedule.name);
+            let (recent_runs, rest): (Vec<_>, Vec<_>) =
+                recent.drain(..).partition(|r| r.name == schedule.name);
+            recent = rest;
+            ScheduleStatus {
+                description: definition.map(|d| d.description),
+                default_cron: definition.map(|d| d.default_cron),
+                cron: schedule.cron.as_ref().map(|c| c.as_str().to_string()),
+                next_run_at: schedule
+                    .cron
+                    .as_ref()
+                    .filter(|_| schedule.enabled)
+                    .and_then(|c| c.next_after(now)),
+                source: schedule.source,
+                enabled: schedule.enabled,
+                name: schedule.name,
+                recent_runs,
+            }
+        })
+        .collect())
+}
+
+/// Every scheduled task with the schedule it runs on, where that schedule
+/// came from, its next tick and its recent runs.
+pub async fn list_schedules(
+    State(state): State<AppState>,
+    Query(params): Query<SchedulesQuery>,
+) -> Result<Json<SchedulesResponse>, AppError> {
+    let mut names: Vec<&str> = SCHEDULES.iter().map(|s| s.name).collect();
+    for name in state.config.schedules.keys() {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+    let items = schedule_statuses(&state, &names, params.runs).await?;
+    Ok(Json(SchedulesResponse { items }))
+}
+
+/// Overrides a task's schedule or pauses it, on every instance from their
+/// next check.
+pub async fn update_schedule(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(name): Path<String>,
+    Json(body): Json<UpdateScheduleRequest>,
+) -> Result<Json<ScheduleStatus>, AppError> {
+    let Some(definition) = definition(&name) else {
+        return Err(AppError::NotFound(format!(
+            "No scheduled task named {}",
+            name
+        )));
+    };
+    let cron = body
+        .cron
+        .as_deref()
+        .map(CronSchedule::parse)
+        .transpose()
+        .map_err(|e| AppError::BadRequest(format!("Invalid cron: {}", e)))?;
+
+    sqlx::query(
+        "INSERT INTO scheduled_tasks (name, cron, enabled, updated_by)
+         VALUES ($1, $2, COALESCE($3, true), $4)
+         ON CONFLICT (name) DO UPDATE
+         SET cron = EXCLUDED.cron,
+             enabled = COALESCE($3, scheduled_tasks.enabled),
+             updated_by = EXCLUDED.updated_by,
+             updated_at = NOW()",
+    )
+    .bind(definition.name)
+    .bind(cron.as_ref().map(CronSchedule::as_str))
+    .bind(body.enabled)
+    .bind(&claims.sub)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let status = schedule_statuses(&state, &[definition.name], default_runs())
+        .await?
+        .pop()
+        .ok_or_else(|| AppError::Internal("schedule vanished".to_string()))?;
+
+    let _ = sqlx::query(
+        "INSERT INTO admin_audit_logs (id, admin_sub, action, metadata, created_at)
+         VALUES ($1, $2, $3, $4, NOW())",
+    )
+    .bind(Uuid::now_v7())
+    .bind(&claims.sub)
+    .bind("UPDATE_SCHEDULE")
+    .bind(serde_json::json!({
+        "name": status.name,
+        "cron": status.cron,
+        "source": status.source,
+        "enabled": status.enabled,
+    }))
+    .execute(&state.db)
+    .await;
+
+    Ok(Json(status))
+}
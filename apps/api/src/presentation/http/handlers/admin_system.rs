@@ -0,0 +1,232 @@
+//! What an on-call engineer checks first, in one call: the build that is
+//! running, how it is configured, how far its schema is migrated, which
+//! background workers run and where, and how its dependencies are doing.
+
+use axum::{Json, extract::State};
+use serde::Serialize;
+use std::{future::Future, time::Instant};
+
+use crate::{
+    config::Config,
+    infrastructure::{
+        database::schema_check::check_schema,
+        fault_injection::FaultSettings,
+        feature_flags::{ROLLOUT_FLAGS, rollout_percent},
+        leadership::current_leaders,
+        monitoring::throttle::ThrottleLevel,
+        queue::jobs::JobCounts,
+    },
+    presentation::http::{errors::AppError, state::AppState},
+};
+
+#[derive(Debug, Serialize)]
+pub struct SystemInfo {
+    pub version: &'static str,
+    /// Commit the build came from, when the build or platform provides it.
+    pub git_sha: Option<String>,
+    /// Rollout flags sending any share of requests to their candidate path.
+    pub feature_flags: Vec<ActiveFlag>,
+    /// Present only when fault injection is enabled.
+    pub fault_injection: Option<FaultSettings>,
+    /// Every setting, secrets redacted.
+    pub config: serde_json::Value,
+    pub migrations: MigrationLevel,
+    pub workers: WorkerStatus,
+    pub dependencies: Vec<DependencyHealth>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveFlag {
+    pub name: &'static str,
+    pub rollout_percent: u8,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MigrationLevel {
+    /// Newest migration applied to the database.
+    pub applied_version: Option<i64>,
+    /// Newest migration this build knows about.
+    pub known_version: i64,
+    /// Columns the code expects but the database lacks; empty when
+    /// compatible.
+    pub schema_problems: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkerStatus {
+    /// `normal`, `slowed` or `paused`, from database and resource health.
+    pub throttle: &'static str,
+    pub workers: Vec<WorkerInfo>,
+    pub ml_jobs: Option<JobCounts>,
+    pub notification_jobs: Option<JobCounts>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkerInfo {
+    pub name: &'static str,
+    /// Whether this configuration starts the worker.
+    pub enabled: bool,
+    /// Instance holding the worker's leader lease, for workers that run on
+    /// one instance at a time.
+    pub leader: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyHealth {
+    pub name: &'static str,
+    /// `up` or `down`.
+    pub status: &'static str,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Commit from the build environment, else from the platform at runtime.
+fn git_sha() -> Option<String> {
+    option_env!("GIT_SHA")
+        .map(str::to_string)
+        .or_else(|| std::env::var("GIT_SHA").ok())
+        .or_else(|| std::env::var("RAILWAY_GIT_COMMIT_SHA").ok())
+        .filter(|sha| !sha.trim().is_empty())
+}
+
+/// The background workers `main` starts, under the same conditions.
+pub(crate) fn workers(config: &Config, cdn_purge_enabled: bool) -> Vec<WorkerInfo> {
+    let email_enabled = config.email_api_url.is_some()
+        && config.email_api_key.is_some()
+        && config.email_from.is_some();
+    [
+        ("activity_fanout", config.enable_activity_feed),
+        ("backup_snapshot", true),
+        ("cdn_purge_retry", cdn_purge_enabled),
+        ("follow_notifier", config.enable_follow_notifications),
+        ("integrity_verifier", config.enable_integrity_verification),
+        ("ip_anonymizer", config.enable_ip_anonymization),
+        ("like_digest", config.enable_like_digest),
+        ("ml_processor", true),
+        ("ml_reprocess", true),
+        (
+            "model_watcher",
+            config.enable_ml_processing && config.enable_ml_model_watch,
+        ),
+        ("notification_delivery", true),
+        ("pending_auto_approve", config.enable_pending_auto_approve),
+        ("pending_escalation", config.enable_pending_escalation),
+        ("print_bundle", true),
+        ("review_priority", config.enable_review_priority),
+        (
+            "saved_search_notifier",
+            config.enable_saved_search_notifications,
+        ),
+        ("scheduler", true),
+        ("storage_gc", true),
+        (
+            "weekly_digest",
+            config.enable_weekly_digest && email_enabled,
+        ),
+    ]
+    .into_iter()
+    .map(|(name, enabled)| WorkerInfo {
+        name,
+        enabled,
+        leader: None,
+    })
+    .collect()
+}
+
+async fn probe<F, E>(name: &'static str, check: F) -> DependencyHealth
+where
+    F: Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    let started = Instant::now();
+    let result = check.await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(()) => DependencyHealth {
+            name,
+            status: "up",
+            latency_ms,
+            error: None,
+        },
+        Err(e) => DependencyHealth {
+            name,
+            status: "down",
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Build, configuration, migration level, workers and dependency health.
+/// Parts that depend on an unreachable dependency are left empty rather
+/// than failing the call; the dependency shows as down.
+pub async fn get_system_info(State(state): State<AppState>) -> Result<Json<SystemInfo>, AppError> {
+    let database_status = probe("database", async {
+        sqlx::query("SELECT 1").execute(&state.db).await.map(|_| ())
+    })
+    .await;
+    let redis_status = probe("redis", async {
+        let mut conn = state.redis.get_multiplexed_async_connection().await?;
+        let _: String = redis::cmd("PING").query_async(&mut conn).await?;
+        Ok::<_, redis::RedisError>(())
+    })
+    .await;
+
+    let migrator = sqlx::migrate!("./migrations");
+    let migrations = match check_schema(&state.db, &migrator).await {
+        Ok(report) => MigrationLevel {
+            applied_version: report.applied_version,
+            known_version: report.known_version,
+            schema_problems: report.problems,
+        },
+        Err(e) => {
+            tracing::warn!("System info: schema check failed: {}", e);
+            MigrationLevel {
+                applied_version: None,
+                known_version: migrator.iter().map(|m| m.version).max().unwrap_or(0),
+                schema_problems: vec![format!("schema check failed: {}", e)],
+            }
+        }
+    };
+
+    let mut feature_flags = Vec::new();
+    for flag in ROLLOUT_FLAGS {
+        let percent = rollout_percent(&state.redis, flag.name).await;
+        if percent > 0 {
+            feature_flags.push(ActiveFlag {
+                name: flag.name,
+                rollout_percent: percent,
+            });
+        }
+    }
+
+    let mut workers = workers(&state.config, state.cdn_purger.is_enabled());
+    let names: Vec<&str> = workers.iter().map(|w| w.name).collect();
+    match current_leaders(&state.redis, &names).await {
+        Ok(leaders) => {
+            for (worker, leader) in workers.iter_mut().zip(leaders) {
+                worker.leader = leader;
+            }
+        }
+        Err(e) => tracing::warn!("System info: failed to read worker leaders: {}", e),
+    }
+
+    let (database_health, resource_health) = state.performance.background_health().await;
+    let worker_status = WorkerStatus {
+        throttle: ThrottleLevel::from_health(&database_health, &resource_health).as_str(),
+        workers,
+        ml_jobs: state.queue.ml_jobs().counts().await.ok(),
+        notification_jobs: state.queue.notifications().counts().await.ok(),
+    };
+
+    Ok(Json(SystemInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: git_sha(),
+        feature_flags,
+        fault_injection: state.faults.as_ref().map(|f| f.settings()),
+        config: state.config.redacted(),
+        migrations,
+        workers: worker_status,
+        dependencies: vec![database_status, redis_status],
+    }))
+}
@@ -0,0 +1,91 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::presentation::http::{errors::AppError, state::AppState};
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct TimelineEvent {
+    /// `STATUS_CHANGE`, `ADMIN_ACTION`, `ML_RUN`, `ML_CORRECTION`,
+    /// `METADATA_EDIT` or `REPORT`.
+    pub kind: String,
+    pub occurred_at: DateTime<Utc>,
+    pub actor: Option<String>,
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LetteringTimelineResponse {
+    pub lettering_id: Uuid,
+    /// False once the lettering has been deleted; its admin actions remain.
+    pub exists: bool,
+    pub events: Vec<TimelineEvent>,
+}
+
+/// Every recorded event for one lettering, oldest first: status transitions,
+/// moderator actions, ML passes and corrections, owner edits and reports.
+pub async fn get_lettering_timeline(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<LetteringTimelineResponse>, AppError> {
+    let exists =
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM letterings WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let events = sqlx::query_as::<_, TimelineEvent>(
+        "SELECT 'STATUS_CHANGE' AS kind, created_at AS occurred_at,
+                COALESCE(actor_sub, actor_type) AS actor,
+                jsonb_build_object('from_status', from_status, 'to_status', to_status,
+                                   'reason', reason, 'actor_type', actor_type) AS details
+         FROM lettering_status_history WHERE lettering_id = $1
+         UNION ALL
+         SELECT 'ADMIN_ACTION', created_at, admin_sub,
+                jsonb_build_object('action', action, 'metadata', metadata)
+         FROM admin_audit_logs WHERE lettering_id = $1
+         UNION ALL
+         SELECT 'ML_RUN', created_at, model_version,
+                jsonb_build_object('detected_text', detected_text, 'text_confidence', text_confidence,
+                                   'ml_style', ml_style, 'style_confidence', style_confidence,
+                                   'ml_script', ml_script,
+                                   'low_confidence_fields', low_confidence_fields,
+                                   'detected_text_source', detected_text_source)
+         FROM ml_runs WHERE lettering_id = $1
+         UNION ALL
+         SELECT 'ML_CORRECTION', created_at, COALESCE(actor_sub, actor_type),
+                jsonb_build_object('field_name', field_name, 'model_value', model_value,
+                                   'previous_value', previous_value,
+                                   'corrected_value', corrected_value, 'reason', reason)
+         FROM ml_metadata_corrections WHERE lettering_id = $1
+         UNION ALL
+         SELECT 'METADATA_EDIT', created_at, edited_by_user_id::TEXT,
+                jsonb_build_object('field_name', field_name, 'old_value', old_value,
+                                   'new_value', new_value)
+         FROM lettering_metadata_history WHERE lettering_id = $1
+         UNION ALL
+         SELECT 'REPORT', created_at, NULL, jsonb_build_object('reason', reason, 'updated_at', updated_at)
+         FROM lettering_reports WHERE lettering_id = $1
+         ORDER BY occurred_at ASC",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if !exists && events.is_empty() {
+        return Err(AppError::NotFound("Lettering not found".to_string()));
+    }
+
+    Ok(Json(LetteringTimelineResponse {
+        lettering_id: id,
+        exists,
+        events,
+    }))
+}
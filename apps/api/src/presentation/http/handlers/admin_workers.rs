@@ -0,0 +1,83 @@
+//! Whether each background worker is alive, from the heartbeats workers
+//! write to Redis. A worker that died stops beating and shows as stale,
+//! then missing, long before the work it leaves behind piles up.
+
+use axum::{Json, extract::State};
+use serde::Serialize;
+
+use crate::{
+    infrastructure::{
+        monitoring::heartbeat::{WorkerHeartbeat, read_heartbeats},
+        queue::jobs::JobCounts,
+    },
+    presentation::http::{errors::AppError, handlers::admin_system::workers, state::AppState},
+};
+
+#[derive(Debug, Serialize)]
+pub struct WorkerReport {
+    pub name: &'static str,
+    /// Whether this configuration starts the worker.
+    pub enabled: bool,
+    /// `running` while some instance beats on time, `stale` once every
+    /// instance's beat is overdue, `missing` when none has beaten in the
+    /// last hour and `disabled` when the worker is not started.
+    pub status: &'static str,
+    /// Jobs waiting, for workers that drain a queue.
+    pub queue: Option<JobCounts>,
+    /// Summed over instances, since each instance started.
+    pub processed: u64,
+    pub failed: u64,
+    pub instances: Vec<WorkerHeartbeat>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkersResponse {
+    pub items: Vec<WorkerReport>,
+}
+
+fn status(enabled: bool, instances: &[WorkerHeartbeat]) -> &'static str {
+    if instances.iter().any(|h| !h.stale) {
+        "running"
+    } else if !instances.is_empty() {
+        "stale"
+    } else if enabled {
+        "missing"
+    } else {
+        "disabled"
+    }
+}
+
+/// Every background worker with its heartbeats, queue depth and job counts.
+pub async fn list_workers(
+    State(state): State<AppState>,
+) -> Result<Json<WorkersResponse>, AppError> {
+    let mut heartbeats = read_heartbeats(&state.redis)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let ml_jobs = state.queue.ml_jobs().counts().await.ok();
+    let notification_jobs = state.queue.notifications().counts().await.ok();
+
+    let items = workers(&state.config, state.cdn_purger.is_enabled())
+        .into_iter()
+        .map(|worker| {
+            let (instances, rest): (Vec<_>, Vec<_>) =
+                heartbeats.drain(..).partition(|h| h.worker == worker.name);
+            heartbeats = rest;
+            let queue = match worker.name {
+                "ml_processor" => ml_jobs,
+                "notification_delivery" => notification_jobs,
+                _ => None,
+            };
+            WorkerReport {
+                name: worker.name,
+                enabled: worker.enabled,
+                status: status(worker.enabled, &instances),
+                queue,
+                processed: instances.iter().map(|h| h.processed).sum(),
+                failed: instances.iter().map(|h| h.failed).sum(),
+                instances,
+            }
+        })
+        .collect();
+    Ok(Json(WorkersResponse { items }))
+}
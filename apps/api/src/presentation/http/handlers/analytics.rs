@@ -1,8 +1,28 @@
-use axum::{Json, extract::State};
-use serde::Serialize;
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
 
 use crate::presentation::http::{errors::AppError, state::AppState};
 
+pub const MAX_EVENTS_PER_REQUEST: usize = 50;
+const MAX_PATH_LEN: usize = 80;
+const MAX_SEARCH_LEN: usize = 64;
+
+/// Map interactions the frontend reports; anything else is counted as `other`.
+const MAP_ACTIONS: &[&str] = &[
+    "pan",
+    "zoom",
+    "marker_click",
+    "cluster_click",
+    "filter",
+    "locate",
+];
+
 #[derive(Debug, Serialize)]
 pub struct NeighborhoodCount {
     pub pin_code: String,
@@ -34,3 +54,189 @@ pub async fn get_neighborhoods(
 
     Ok(Json(NeighborhoodsResponse { neighborhoods }))
 }
+
+/// A single client-side event. Nothing identifying travels with it: the
+/// intake never reads cookies, IPs or user agents, and only the normalized
+/// dimension survives into the daily counters.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnalyticsEvent {
+    PageView { path: String },
+    Search { query: String },
+    MapInteraction { action: String },
+}
+
+impl AnalyticsEvent {
+    fn event_type(&self) -> &'static str {
+        match self {
+            Self::PageView { .. } => "page_view",
+            Self::Search { .. } => "search",
+            Self::MapInteraction { .. } => "map_interaction",
+        }
+    }
+
+    /// Aggregation key for the event, or `None` if it should be dropped.
+    fn dimension(&self) -> Option<String> {
+        match self {
+            Self::PageView { path } => Some(normalize_path(path)),
+            Self::Search { query } => normalize_search(query),
+            Self::MapInteraction { action } => {
+                let action = action.trim().to_ascii_lowercase();
+                Some(if MAP_ACTIONS.contains(&action.as_str()) {
+                    action
+                } else {
+                    "other".to_string()
+                })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsIntakeRequest {
+    pub events: Vec<AnalyticsEvent>,
+}
+
+/// Reduce a page path to its route shape: no origin, query or fragment, and
+/// ids collapsed to `:id` so individual letterings or profiles can't be told
+/// apart.
+fn normalize_path(raw: &str) -> String {
+    let raw = raw.trim();
+    let without_origin = match raw.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]),
+        None => raw,
+    };
+    let path = without_origin.split(['?', '#']).next().unwrap_or_default();
+
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(|segment| {
+            let looks_like_id = Uuid::parse_str(segment).is_ok()
+                || segment.chars().any(|c| c.is_ascii_digit())
+                || segment.len() > 32;
+            if looks_like_id {
+                ":id".to_string()
+            } else {
+                segment.to_lowercase()
+            }
+        })
+        .collect();
+
+    let normalized = format!("/{}", segments.join("/"));
+    normalized.chars().take(MAX_PATH_LEN).collect()
+}
+
+/// Lower-case and collapse whitespace. Queries that look like contact
+/// details are kept only as a count.
+fn normalize_search(raw: &str) -> Option<String> {
+    let query = raw
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+    let digits = query.chars().filter(|c| c.is_ascii_digit()).count();
+    if query.contains('@') || digits >= 7 {
+        return Some("(redacted)".to_string());
+    }
+    Some(query.chars().take(MAX_SEARCH_LEN).collect())
+}
+
+/// Browsers that send Do-Not-Track or Global Privacy Control are not counted.
+pub(crate) fn has_opted_out(headers: &HeaderMap) -> bool {
+    ["dnt", "sec-gpc"].iter().any(|name| {
+        headers
+            .get(*name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim() == "1")
+    })
+}
+
+pub async fn ingest_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<AnalyticsIntakeRequest>,
+) -> Result<StatusCode, AppError> {
+    if body.events.len() > MAX_EVENTS_PER_REQUEST {
+        return Err(AppError::BadRequest(format!(
+            "At most {} events per request",
+            MAX_EVENTS_PER_REQUEST
+        )));
+    }
+    if has_opted_out(&headers) {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let mut counts: HashMap<(&'static str, String), i64> = HashMap::new();
+    for event in &body.events {
+        if let Some(dimension) = event.dimension() {
+            *counts.entry((event.event_type(), dimension)).or_default() += 1;
+        }
+    }
+    if counts.is_empty() {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    let mut event_types = Vec::with_capacity(counts.len());
+    let mut dimensions = Vec::with_capacity(counts.len());
+    let mut totals = Vec::with_capacity(counts.len());
+    for ((event_type, dimension), count) in counts {
+        event_types.push(event_type.to_string());
+        dimensions.push(dimension);
+        totals.push(count);
+    }
+
+    sqlx::query(
+        "INSERT INTO analytics_daily_events (date, event_type, dimension, count)
+         SELECT local_date(NOW(), $1), t.event_type, t.dimension, t.count
+         FROM UNNEST($2::text[], $3::text[], $4::bigint[]) AS t(event_type, dimension, count)
+         ON CONFLICT (date, event_type, dimension)
+         DO UPDATE SET count = analytics_daily_events.count + EXCLUDED.count",
+    )
+    .bind(&state.config.day_boundary_timezone)
+    .bind(&event_types)
+    .bind(&dimensions)
+    .bind(&totals)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_are_reduced_to_route_shape() {
+        assert_eq!(
+            normalize_path(
+                "https://example.com/Lettering/0190f3b2-7c1e-7000-8000-000000000000?ref=x"
+            ),
+            "/lettering/:id"
+        );
+        assert_eq!(normalize_path("/collections/42#top"), "/collections/:id");
+        assert_eq!(normalize_path(""), "/");
+    }
+
+    #[test]
+    fn contact_like_searches_are_redacted() {
+        assert_eq!(
+            normalize_search("  Hand   Painted "),
+            Some("hand painted".to_string())
+        );
+        assert_eq!(
+            normalize_search("me@example.com"),
+            Some("(redacted)".to_string())
+        );
+        assert_eq!(
+            normalize_search("+91 98765 43210"),
+            Some("(redacted)".to_string())
+        );
+        assert_eq!(normalize_search("   "), None);
+    }
+}
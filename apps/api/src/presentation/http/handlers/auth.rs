@@ -6,10 +6,14 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-use crate::presentation::http::{
-    errors::AppError,
-    middleware::user::{UserClaims, decode_required_user_claims},
-    state::AppState,
+use crate::{
+    infrastructure::geocoding::ip_geolocation::GeoEvent,
+    presentation::http::{
+        errors::AppError,
+        handlers::upload::extract_client_ip,
+        middleware::user::{UserClaims, decode_required_user_claims},
+        state::AppState,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -96,10 +100,10 @@ pub async fn register(
     .await;
 
     if let Err(e) = insert_result {
-        if let sqlx::Error::Database(db_err) = &e {
-            if db_err.code().as_deref() == Some("23505") {
-                return Err(AppError::BadRequest("Email already registered".to_string()));
-            }
+        if let sqlx::Error::Database(db_err) = &e
+            && db_err.code().as_deref() == Some("23505")
+        {
+            return Err(AppError::BadRequest("Email already registered".to_string()));
         }
         return Err(AppError::Internal(e.to_string()));
     }
@@ -118,6 +122,7 @@ pub async fn register(
 
 pub async fn login_user(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(body): Json<LoginRequest>,
 ) -> Result<Json<AuthResponse>, AppError> {
     let email = body.email.trim().to_lowercase();
@@ -150,6 +155,12 @@ pub async fn login_user(
     };
     let token = issue_user_token(&state, &user)?;
 
+    state.ip_geolocator.spawn_annotate(
+        GeoEvent::Login,
+        Some(user.id),
+        extract_client_ip(&headers).map(|ip| ip.ip()),
+    );
+
     Ok(Json(AuthResponse { token, user }))
 }
 
@@ -1,6 +1,7 @@
 use axum::{
     Json,
     extract::{Path, Query, State},
+    http::HeaderMap,
 };
 use reqwest::header::USER_AGENT;
 use serde::{Deserialize, Serialize};
@@ -8,13 +9,21 @@ use sqlx::{FromRow, Postgres, QueryBuilder};
 use std::time::Duration;
 use uuid::Uuid;
 
-use crate::presentation::http::{errors::AppError, state::AppState};
+use crate::{
+    infrastructure::cache::invalidation::city_prefix,
+    presentation::http::{errors::AppError, locale::request_locales, state::AppState},
+};
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct City {
     pub id: Uuid,
+    /// Canonical (English) name.
     pub name: String,
+    /// Name in the request's `Accept-Language`, falling back to `name`.
+    pub display_name: String,
     pub country_code: String,
+    /// Country name in the request's `Accept-Language`, falling back to the code.
+    pub country_name: String,
     pub center_lat: Option<f64>,
     pub center_lng: Option<f64>,
     pub default_zoom: Option<i32>,
@@ -57,33 +66,44 @@ fn city_discovery_user_agent(state: &AppState) -> String {
 
 pub async fn list_cities(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(params): Query<CityListQuery>,
 ) -> Result<Json<Vec<City>>, AppError> {
+    let locales = request_locales(&headers);
     let q = params.q.as_deref().map(str::trim).filter(|s| !s.is_empty());
 
-    if params.discover {
-        if let Some(query) = q {
-            if query.len() >= 2 {
-                let _ = discover_and_cache_cities(
-                    &state,
-                    query,
-                    params.country_code.as_deref(),
-                    params.limit.clamp(1, 50),
-                )
-                .await;
-            }
-        }
+    if params.discover
+        && let Some(query) = q
+        && query.len() >= 2
+    {
+        let _ = discover_and_cache_cities(
+            &state,
+            query,
+            params.country_code.as_deref(),
+            params.limit.clamp(1, 50),
+        )
+        .await;
     }
 
-    let mut qb = QueryBuilder::<Postgres>::new(
-        "SELECT id, name, country_code, center_lat, center_lng, default_zoom, description, cover_image_url, is_active FROM cities",
+    let mut qb = QueryBuilder::<Postgres>::new("SELECT id, name, localized_city_name(id, name, ");
+    qb.push_bind(locales.clone());
+    qb.push(") AS display_name, country_code, localized_country_name(country_code, ");
+    qb.push_bind(locales);
+    qb.push(
+        ") AS country_name, center_lat, center_lng, default_zoom, description, cover_image_url, is_active FROM cities",
     );
 
     let mut has_where = false;
 
     if let Some(query) = q {
-        qb.push(" WHERE name ILIKE ");
-        qb.push_bind(format!("%{}%", query));
+        let pattern = format!("%{}%", query);
+        qb.push(" WHERE (name ILIKE ");
+        qb.push_bind(pattern.clone());
+        qb.push(
+            " OR EXISTS (SELECT 1 FROM city_name_translations t WHERE t.city_id = cities.id AND t.name ILIKE ",
+        );
+        qb.push_bind(pattern);
+        qb.push("))");
         has_where = true;
     }
 
@@ -116,41 +136,96 @@ pub async fn list_cities(
     Ok(Json(cities))
 }
 
+/// How long a city page is served from cache. Pages are keyed by id and
+/// requested locales; changes to the city's letterings drop them, while
+/// edits to place names show within this.
+const CITY_CACHE_TTL_SECONDS: u64 = 300;
+
+/// Cities with the most approved letterings whose pages the cache warmer
+/// keeps filled.
+const WARM_TOP_CITIES: i64 = 20;
+
 pub async fn get_city(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(id): Path<Uuid>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let city: City = sqlx::query_as(
-        "SELECT id, name, country_code, center_lat, center_lng, default_zoom, description, cover_image_url, is_active FROM cities WHERE id = $1",
-    )
-    .bind(id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?
-    .ok_or_else(|| AppError::NotFound("City not found".into()))?;
-
-    let count: (Option<i64>,) = sqlx::query_as(
-        "SELECT COUNT(*) FROM letterings WHERE city_id = $1 AND status = 'APPROVED'",
-    )
-    .bind(id)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
+    let (city, lettering_count) = city_page(&state, id, request_locales(&headers))
+        .await?
+        .ok_or_else(|| AppError::NotFound("City not found".into()))?;
 
     Ok(Json(serde_json::json!({
         "id": city.id,
         "name": city.name,
+        "display_name": city.display_name,
         "country_code": city.country_code,
+        "country_name": city.country_name,
         "center_lat": city.center_lat,
         "center_lng": city.center_lng,
         "default_zoom": city.default_zoom,
         "description": city.description,
         "cover_image_url": city.cover_image_url,
         "is_active": city.is_active,
-        "lettering_count": count.0.unwrap_or(0),
+        "lettering_count": lettering_count,
     })))
 }
 
+/// Fills the cached pages, in the default locale, of the cities with the
+/// most approved letterings.
+pub async fn warm_top_cities(state: AppState) -> Result<(), AppError> {
+    let top: Vec<Uuid> = sqlx::query_scalar(
+        "SELECT city_id FROM letterings
+         WHERE status = 'APPROVED'
+         GROUP BY city_id
+         ORDER BY COUNT(*) DESC
+         LIMIT $1",
+    )
+    .bind(WARM_TOP_CITIES)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    for id in top {
+        city_page(&state, id, request_locales(&HeaderMap::new())).await?;
+    }
+    Ok(())
+}
+
+/// The city and its approved lettering count, from cache when there.
+async fn city_page(
+    state: &AppState,
+    id: Uuid,
+    locales: Vec<String>,
+) -> Result<Option<(City, i64)>, AppError> {
+    let key = format!("{}{}", city_prefix(id), locales.join(","));
+    let db = state.db.clone();
+    state
+        .hot_cache
+        .get_or_fetch(&key, CITY_CACHE_TTL_SECONDS, || async move {
+            let city: Option<City> = sqlx::query_as(
+                "SELECT id, name, localized_city_name(id, name, $2) AS display_name, country_code,
+                        localized_country_name(country_code, $2) AS country_name,
+                        center_lat, center_lng, default_zoom, description, cover_image_url, is_active
+                 FROM cities WHERE id = $1",
+            )
+            .bind(id)
+            .bind(locales)
+            .fetch_optional(&db)
+            .await?;
+            let Some(city) = city else {
+                return Ok(None);
+            };
+            let count: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM letterings WHERE city_id = $1 AND status = 'APPROVED'",
+            )
+            .bind(id)
+            .fetch_one(&db)
+            .await?;
+            Ok(Some((city, count)))
+        })
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
 #[derive(Debug, Serialize, FromRow)]
 pub struct CityNeighborhoodStat {
     pub pin_code: String,
@@ -298,6 +373,7 @@ async fn fetch_wikipedia_summary(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn upsert_city(
     state: &AppState,
     name: &str,
@@ -344,7 +420,10 @@ pub async fn discover_and_cache_cities(
     let user_agent = city_discovery_user_agent(state);
     let mut result = CitySyncResult::default();
 
-    let client = match reqwest::Client::builder().timeout(Duration::from_secs(12)).build() {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(12))
+        .build()
+    {
         Ok(c) => c,
         Err(err) => {
             tracing::warn!("city discovery client init failed: {}", err);
@@ -397,7 +476,11 @@ pub async fn discover_and_cache_cities(
         }
     };
 
-    for place in places.into_iter().filter(is_city_like).take(limit.clamp(1, 50) as usize) {
+    for place in places
+        .into_iter()
+        .filter(is_city_like)
+        .take(limit.clamp(1, 50) as usize)
+    {
         let name = city_name_from_place(&place);
         if name.eq_ignore_ascii_case("unknown") {
             continue;
@@ -0,0 +1,313 @@
+//! User-curated collections of letterings.
+//!
+//! A collection belongs to the account that made it and only the owner can
+//! edit it. Private collections are visible to their owner alone; public ones
+//! are listed, opened by id and shared under a slug link. Only publicly
+//! listed letterings can be added, and items hidden later (rejected, or in a
+//! country that turned discoverability off) drop out of every listing.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::domain::social::{
+    collection::{
+        Collection, CollectionDraft, CollectionItem, MAX_COLLECTION_DESCRIPTION_CHARS,
+        MAX_COLLECTION_NAME_CHARS,
+    },
+    repository::SocialRepository,
+};
+use crate::presentation::http::{
+    errors::AppError,
+    middleware::user::{decode_optional_user_claims, decode_required_user_claims},
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CollectionListQuery {
+    #[serde(default = "default_limit")]
+    #[ts(type = "number", optional)]
+    pub limit: i64,
+    #[serde(default)]
+    #[ts(type = "number", optional)]
+    pub offset: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CollectionItemsQuery {
+    /// Include age-restricted letterings in gated regions.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CreateCollectionRequest {
+    pub name: String,
+    #[ts(optional)]
+    pub description: Option<String>,
+    /// Defaults to false.
+    #[ts(optional)]
+    pub is_public: Option<bool>,
+}
+
+/// Fields left out keep their value; an empty `description` clears it.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UpdateCollectionRequest {
+    #[ts(optional)]
+    pub name: Option<String>,
+    #[ts(optional)]
+    pub description: Option<String>,
+    #[ts(optional)]
+    pub is_public: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ReorderCollectionRequest {
+    /// Letterings to put first, in order; the rest keep their order after.
+    pub lettering_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct CollectionDetail {
+    #[serde(flatten)]
+    pub collection: Collection,
+    pub items: Vec<CollectionItem>,
+}
+
+fn user_id(headers: &HeaderMap, state: &AppState) -> Result<Uuid, AppError> {
+    let claims = decode_required_user_claims(headers, &state.config.jwt_secret)?;
+    Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Forbidden("Invalid token subject".to_string()))
+}
+
+fn normalize_draft(
+    name: String,
+    description: Option<String>,
+    is_public: bool,
+) -> Result<CollectionDraft, AppError> {
+    let name = name.trim().to_string();
+    if !(1..=MAX_COLLECTION_NAME_CHARS).contains(&name.chars().count()) {
+        return Err(AppError::BadRequest(format!(
+            "name must be between 1 and {} characters",
+            MAX_COLLECTION_NAME_CHARS
+        )));
+    }
+    let description = description
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+    if description
+        .as_ref()
+        .is_some_and(|d| d.chars().count() > MAX_COLLECTION_DESCRIPTION_CHARS)
+    {
+        return Err(AppError::BadRequest(format!(
+            "description must be {} characters or less",
+            MAX_COLLECTION_DESCRIPTION_CHARS
+        )));
+    }
+    Ok(CollectionDraft {
+        name,
+        description,
+        is_public,
+    })
+}
+
+/// Whether the client behind `headers` owns `collection`.
+fn is_owner(headers: &HeaderMap, state: &AppState, collection: &Collection) -> bool {
+    decode_optional_user_claims(headers, &state.config.jwt_secret)
+        .and_then(|claims| Uuid::parse_str(&claims.sub).ok())
+        .is_some_and(|id| collection.owner_id == Some(id))
+}
+
+async fn detail(
+    state: &AppState,
+    collection: Collection,
+    params: &CollectionItemsQuery,
+) -> Result<Json<CollectionDetail>, AppError> {
+    let items = state
+        .social_repo
+        .list_collection_items(collection.id, params.age_ack.unwrap_or(false))
+        .await?;
+    Ok(Json(CollectionDetail { collection, items }))
+}
+
+/// Public collections, newest first.
+pub async fn list_collections(
+    State(state): State<AppState>,
+    Query(params): Query<CollectionListQuery>,
+) -> Result<Json<Vec<Collection>>, AppError> {
+    let collections = state
+        .social_repo
+        .list_public_collections(params.limit.clamp(1, 100), params.offset.max(0))
+        .await?;
+    Ok(Json(collections))
+}
+
+/// The current user's collections, public and private, last edited first.
+pub async fn list_my_collections(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<Collection>>, AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    let collections = state.social_repo.list_user_collections(owner_id).await?;
+    Ok(Json(collections))
+}
+
+pub async fn create_collection(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<CreateCollectionRequest>,
+) -> Result<(StatusCode, Json<Collection>), AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    let draft = normalize_draft(body.name, body.description, body.is_public.unwrap_or(false))?;
+    let collection = state
+        .social_repo
+        .create_collection(owner_id, &draft)
+        .await?;
+    Ok((StatusCode::CREATED, Json(collection)))
+}
+
+/// A collection with its items. Private collections are only found by their
+/// owner.
+pub async fn get_collection(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<CollectionItemsQuery>,
+    headers: HeaderMap,
+) -> Result<Json<CollectionDetail>, AppError> {
+    let collection = state
+        .social_repo
+        .find_collection(id)
+        .await?
+        .filter(|c| c.is_public || is_owner(&headers, &state, c))
+        .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
+    detail(&state, collection, &params).await
+}
+
+/// A public collection by its share slug.
+pub async fn get_shared_collection(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<CollectionItemsQuery>,
+) -> Result<Json<CollectionDetail>, AppError> {
+    let collection = state
+        .social_repo
+        .find_shared_collection(slug.trim())
+        .await?
+        .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
+    detail(&state, collection, &params).await
+}
+
+/// Rename, describe or change the visibility of one of the current user's
+/// collections. The first time it is made public it gets a share slug.
+pub async fn update_collection(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(body): Json<UpdateCollectionRequest>,
+) -> Result<Json<Collection>, AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    let current = state
+        .social_repo
+        .find_collection(id)
+        .await?
+        .filter(|c| c.owner_id == Some(owner_id))
+        .ok_or_else(|| AppError::NotFound("Collection not found".to_string()))?;
+
+    let draft = normalize_draft(
+        body.name.unwrap_or(current.name),
+        body.description.or(current.description),
+        body.is_public.unwrap_or(current.is_public),
+    )?;
+    let collection = state
+        .social_repo
+        .update_collection(id, owner_id, &draft)
+        .await?;
+    Ok(Json(collection))
+}
+
+pub async fn delete_collection(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    state.social_repo.delete_collection(id, owner_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Append an approved lettering to one of the current user's collections.
+pub async fn add_to_collection(
+    State(state): State<AppState>,
+    Path((collection_id, lettering_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    state
+        .social_repo
+        .add_collection_item(collection_id, owner_id, lettering_id)
+        .await?;
+    Ok(StatusCode::CREATED)
+}
+
+pub async fn remove_from_collection(
+    State(state): State<AppState>,
+    Path((collection_id, lettering_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    state
+        .social_repo
+        .remove_collection_item(collection_id, owner_id, lettering_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn reorder_collection(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(body): Json<ReorderCollectionRequest>,
+) -> Result<StatusCode, AppError> {
+    let owner_id = user_id(&headers, &state)?;
+    state
+        .social_repo
+        .reorder_collection(id, owner_id, &body.lettering_ids)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drafts_are_trimmed_and_bounded() {
+        let draft = normalize_draft(
+            "  Hand-painted signs of Mysuru ".to_string(),
+            Some("   ".to_string()),
+            true,
+        )
+        .unwrap();
+        assert_eq!(draft.name, "Hand-painted signs of Mysuru");
+        assert_eq!(draft.description, None);
+
+        assert!(normalize_draft("  ".to_string(), None, false).is_err());
+        assert!(normalize_draft("x".repeat(81), None, false).is_err());
+        assert!(normalize_draft("Signs".to_string(), Some("x".repeat(501)), false).is_err());
+    }
+}
@@ -1,11 +1,16 @@
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::header,
+    response::IntoResponse,
 };
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use uuid::Uuid;
 
+use crate::domain::contributor::leaderboard::{
+    LeaderboardEntry as RankedContributor, LeaderboardMetric, LeaderboardPeriod, Leaderboards,
+};
 use crate::presentation::http::{errors::AppError, state::AppState};
 
 // --- Leaderboard ---
@@ -38,158 +43,70 @@ pub async fn get_leaderboard(
     ))
 }
 
-// --- Collections ---
-
-#[derive(Serialize, sqlx::FromRow)]
-pub struct CollectionRow {
-    pub id: Uuid,
-    pub name: String,
-    pub description: Option<String>,
-    pub creator_tag: String,
-    pub is_public: bool,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-}
-
-#[derive(Serialize)]
-pub struct CollectionResponse {
-    pub id: Uuid,
-    pub name: String,
-    pub description: Option<String>,
-    pub creator_tag: String,
-    pub is_public: bool,
-    pub item_count: i64,
-    pub created_at: chrono::DateTime<chrono::Utc>,
-}
-
-#[derive(Deserialize)]
-pub struct CreateCollectionRequest {
-    pub name: String,
-    pub description: Option<String>,
-    pub creator_tag: String,
-}
-
-pub async fn list_collections(
-    State(state): State<AppState>,
-) -> Result<Json<Vec<CollectionResponse>>, AppError> {
-    let rows: Vec<(Uuid, String, Option<String>, String, bool, chrono::DateTime<chrono::Utc>, Option<i64>)> = sqlx::query_as(
-        "SELECT c.id, c.name, c.description, c.creator_tag, c.is_public, c.created_at, COUNT(ci.lettering_id) FROM collections c LEFT JOIN collection_items ci ON ci.collection_id = c.id WHERE c.is_public = true GROUP BY c.id ORDER BY c.created_at DESC"
-    )
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
-
-    Ok(Json(
-        rows.into_iter()
-            .map(
-                |(id, name, description, creator_tag, is_public, created_at, item_count)| {
-                    CollectionResponse {
-                        id,
-                        name,
-                        description,
-                        creator_tag,
-                        is_public,
-                        item_count: item_count.unwrap_or(0),
-                        created_at,
-                    }
-                },
-            )
-            .collect(),
-    ))
-}
+/// How long computed leaderboards are served from cache. The analytics worker
+/// recomputes them hourly.
+const LEADERBOARDS_CACHE_TTL: u64 = 300;
 
-pub async fn create_collection(
+/// Weekly, monthly or all-time leaderboards by approved uploads, likes
+/// received and cities covered, as last computed by the analytics worker.
+pub async fn get_leaderboards(
     State(state): State<AppState>,
-    Json(body): Json<CreateCollectionRequest>,
-) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
-    let name = body.name.trim().to_string();
-    if name.is_empty() {
-        return Err(AppError::BadRequest("Collection name required".into()));
-    }
-
-    let id = Uuid::now_v7();
-    sqlx::query(
-        "INSERT INTO collections (id, name, description, creator_tag) VALUES ($1, $2, $3, $4)",
-    )
-    .bind(id)
-    .bind(&name)
-    .bind(&body.description)
-    .bind(&body.creator_tag)
-    .execute(&state.db)
-    .await
-    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
+    Path(period): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let period = LeaderboardPeriod::parse(&period).ok_or_else(|| {
+        AppError::BadRequest("period must be one of weekly, monthly, all-time".to_string())
+    })?;
+
+    let db = state.db.clone();
+    let leaderboards = state
+        .cache
+        .get_or_fetch(
+            &format!("leaderboards:{}", period.as_str()),
+            LEADERBOARDS_CACHE_TTL,
+            || async move {
+                let rows: Vec<(String, i32, String, i64, DateTime<Utc>)> = sqlx::query_as(
+                    "SELECT metric, rank, contributor_tag, value, computed_at
+                     FROM leaderboard_entries
+                     WHERE period = $1
+                     ORDER BY metric, rank, contributor_tag",
+                )
+                .bind(period.as_str())
+                .fetch_all(&db)
+                .await?;
+
+                let computed_at = rows.iter().map(|row| row.4).max();
+                let mut boards = Leaderboards {
+                    period: period.as_str().to_string(),
+                    most_approved: Vec::new(),
+                    most_liked: Vec::new(),
+                    most_cities: Vec::new(),
+                    computed_at,
+                };
+                for (metric, rank, contributor_tag, value, _) in rows {
+                    let board = match LeaderboardMetric::parse(&metric) {
+                        Some(LeaderboardMetric::ApprovedUploads) => &mut boards.most_approved,
+                        Some(LeaderboardMetric::Likes) => &mut boards.most_liked,
+                        Some(LeaderboardMetric::Cities) => &mut boards.most_cities,
+                        None => continue,
+                    };
+                    board.push(RankedContributor {
+                        rank,
+                        contributor_tag,
+                        value,
+                    });
+                }
+                Ok(boards)
+            },
+        )
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to load leaderboards: {}", e)))?;
 
     Ok((
-        StatusCode::CREATED,
-        Json(serde_json::json!({ "id": id, "name": name })),
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(leaderboards),
     ))
 }
 
-pub async fn get_collection(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let collection: CollectionRow = sqlx::query_as(
-        "SELECT id, name, description, creator_tag, is_public, created_at FROM collections WHERE id = $1"
-    )
-    .bind(id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?
-    .ok_or_else(|| AppError::NotFound("Collection not found".into()))?;
-
-    let items: Vec<(Uuid, String, String, Option<String>, String)> = sqlx::query_as(
-        "SELECT l.id, l.image_url, l.thumbnail_small, l.detected_text, l.contributor_tag FROM collection_items ci JOIN letterings l ON l.id = ci.lettering_id WHERE ci.collection_id = $1 ORDER BY l.created_at DESC"
-    )
-    .bind(id)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
-
-    Ok(Json(serde_json::json!({
-        "id": collection.id,
-        "name": collection.name,
-        "description": collection.description,
-        "creator_tag": collection.creator_tag,
-        "is_public": collection.is_public,
-        "created_at": collection.created_at,
-        "items": items.into_iter().map(|(id, image_url, thumbnail, detected_text, contributor_tag)| serde_json::json!({
-            "id": id,
-            "image_url": image_url,
-            "thumbnail": thumbnail,
-            "detected_text": detected_text,
-            "contributor_tag": contributor_tag,
-        })).collect::<Vec<_>>(),
-    })))
-}
-
-pub async fn add_to_collection(
-    State(state): State<AppState>,
-    Path((collection_id, lettering_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, AppError> {
-    sqlx::query("INSERT INTO collection_items (collection_id, lettering_id) VALUES ($1, $2) ON CONFLICT DO NOTHING")
-        .bind(collection_id)
-        .bind(lettering_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
-
-    Ok(StatusCode::CREATED)
-}
-
-pub async fn remove_from_collection(
-    State(state): State<AppState>,
-    Path((collection_id, lettering_id)): Path<(Uuid, Uuid)>,
-) -> Result<StatusCode, AppError> {
-    sqlx::query("DELETE FROM collection_items WHERE collection_id = $1 AND lettering_id = $2")
-        .bind(collection_id)
-        .bind(lettering_id)
-        .execute(&state.db)
-        .await
-        .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
-
-    Ok(StatusCode::NO_CONTENT)
-}
-
 // --- Challenges ---
 
 #[derive(Serialize, sqlx::FromRow)]
@@ -0,0 +1,337 @@
+//! Photographer/documenter credits on uploads.
+//!
+//! An uploader may credit someone else for the photo, by name or by linking
+//! their account. The credit is shown next to, not instead of, the contributor
+//! tag. A linked user is notified and can dispute it; a disputed credit is
+//! hidden until an admin keeps or removes it.
+
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::queue::redis_queue::NotificationJob,
+    presentation::http::{
+        errors::AppError,
+        handlers::admin::log_admin_action,
+        middleware::{admin::AdminClaims, user::decode_required_user_claims},
+        state::AppState,
+    },
+};
+
+const MAX_CREDIT_NAME_CHARS: usize = 80;
+const MAX_DISPUTE_REASON_CHARS: usize = 500;
+
+/// Credit chosen at upload, after validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadCredit {
+    pub name: String,
+    pub user_id: Option<Uuid>,
+}
+
+/// Public view of a credit, included in lettering details.
+#[derive(Debug, Serialize)]
+pub struct CreditView {
+    pub name: String,
+    /// Whether the credit is linked to an account.
+    pub linked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisputeCreditRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct CreditDispute {
+    pub lettering_id: Uuid,
+    pub contributor_tag: String,
+    pub thumbnail_small: Option<String>,
+    pub credit_name: Option<String>,
+    pub credit_user_id: Option<Uuid>,
+    pub credit_dispute_reason: Option<String>,
+    pub credit_disputed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveCreditDisputeRequest {
+    /// `keep` restores the credit, `remove` drops it.
+    pub action: String,
+}
+
+/// Trimmed credit name, or why it's not acceptable.
+pub fn validate_credit_name(raw: &str) -> Result<String, &'static str> {
+    let name = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.chars().count() < 2 {
+        return Err("credit_name must be at least 2 characters");
+    }
+    if name.chars().count() > MAX_CREDIT_NAME_CHARS {
+        return Err("credit_name must be at most 80 characters");
+    }
+    if name.chars().any(char::is_control) {
+        return Err("credit_name must not contain control characters");
+    }
+    let lower = name.to_lowercase();
+    if lower.contains("://") || lower.contains("www.") {
+        return Err("credit_name must not contain links");
+    }
+    Ok(name)
+}
+
+/// Validates the `credit_name` / `credit_user_id` upload fields. A linked user
+/// must exist and not be the uploader; without a name their display name is
+/// used.
+pub async fn resolve_upload_credit(
+    state: &AppState,
+    credit_name: Option<&str>,
+    credit_user_id: Option<&str>,
+    uploader: Option<Uuid>,
+) -> Result<Option<UploadCredit>, AppError> {
+    let name = credit_name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(validate_credit_name)
+        .transpose()
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let user_id = credit_user_id
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .map(|u| {
+            Uuid::from_str(u)
+                .map_err(|_| AppError::BadRequest("credit_user_id must be a valid UUID".into()))
+        })
+        .transpose()?;
+
+    let Some(user_id) = user_id else {
+        return Ok(name.map(|name| UploadCredit {
+            name,
+            user_id: None,
+        }));
+    };
+    if uploader == Some(user_id) {
+        return Err(AppError::BadRequest(
+            "credit_user_id must be someone other than the uploader".into(),
+        ));
+    }
+    let display_name = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT NULLIF(TRIM(display_name), '') FROM users WHERE id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::BadRequest("Credited user not found".into()))?;
+
+    let name = match (name, display_name) {
+        (Some(name), _) => name,
+        (None, Some(display_name)) => display_name,
+        (None, None) => {
+            return Err(AppError::BadRequest(
+                "Credited user has no display name; provide credit_name".into(),
+            ));
+        }
+    };
+    Ok(Some(UploadCredit {
+        name,
+        user_id: Some(user_id),
+    }))
+}
+
+/// Stores the credit on a new upload and tells a linked user about it.
+pub async fn record_upload_credit(
+    state: &AppState,
+    lettering_id: Uuid,
+    credit: &UploadCredit,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE letterings SET credit_name = $1, credit_user_id = $2 WHERE id = $3")
+        .bind(&credit.name)
+        .bind(credit.user_id)
+        .bind(lettering_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to record credit: {}", e)))?;
+
+    if let Some(user_id) = credit.user_id
+        && let Err(e) = state
+            .queue
+            .enqueue_notification(NotificationJob {
+                user_id,
+                kind: "LETTERING_CREDITED".to_string(),
+                title: "You were credited on a lettering".to_string(),
+                body: Some(
+                    "An uploader credited you as the photographer. If that's wrong, you can dispute it."
+                        .to_string(),
+                ),
+                metadata: serde_json::json!({ "lettering_id": lettering_id, "credit_name": credit.name }),
+            })
+            .await
+    {
+        tracing::error!(
+            "Failed to notify credited user {} (lettering {}): {}",
+            user_id,
+            lettering_id,
+            e
+        );
+    }
+    Ok(())
+}
+
+/// The credit to show publicly, if any and not under dispute.
+pub async fn public_credit(
+    state: &AppState,
+    lettering_id: Uuid,
+) -> Result<Option<CreditView>, AppError> {
+    let row = sqlx::query_as::<_, (Option<String>, bool)>(
+        "SELECT credit_name, credit_user_id IS NOT NULL
+         FROM letterings
+         WHERE id = $1 AND credit_disputed_at IS NULL",
+    )
+    .bind(lettering_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(row.and_then(|(name, linked)| name.map(|name| CreditView { name, linked })))
+}
+
+/// Lets the credited user dispute a credit; it's hidden until an admin
+/// resolves the dispute.
+pub async fn dispute_credit(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(body): Json<DisputeCreditRequest>,
+) -> Result<StatusCode, AppError> {
+    let claims = decode_required_user_claims(&headers, &state.config.jwt_secret)?;
+    let user_id = Uuid::from_str(&claims.sub)
+        .map_err(|_| AppError::Forbidden("Invalid token subject".to_string()))?;
+
+    let reason = body
+        .reason
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    if reason
+        .as_ref()
+        .is_some_and(|r| r.chars().count() > MAX_DISPUTE_REASON_CHARS)
+    {
+        return Err(AppError::BadRequest(
+            "reason must be at most 500 characters".into(),
+        ));
+    }
+
+    let credited_user = sqlx::query_scalar::<_, Option<Uuid>>(
+        "SELECT credit_user_id FROM letterings WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+    if credited_user != Some(user_id) {
+        return Err(AppError::Forbidden(
+            "Only the credited user can dispute this credit".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE letterings
+         SET credit_disputed_at = COALESCE(credit_disputed_at, NOW()), credit_dispute_reason = $1
+         WHERE id = $2",
+    )
+    .bind(reason)
+    .bind(id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Open credit disputes, oldest first.
+pub async fn list_credit_disputes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CreditDispute>>, AppError> {
+    let disputes = sqlx::query_as::<_, CreditDispute>(
+        "SELECT id AS lettering_id, contributor_tag, thumbnail_small, credit_name, credit_user_id,
+                credit_dispute_reason, credit_disputed_at
+         FROM letterings
+         WHERE credit_disputed_at IS NOT NULL
+         ORDER BY credit_disputed_at ASC
+         LIMIT 200",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(Json(disputes))
+}
+
+/// Admin decision on a disputed credit.
+pub async fn resolve_credit_dispute(
+    State(state): State<AppState>,
+    Extension(claims): Extension<AdminClaims>,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ResolveCreditDisputeRequest>,
+) -> Result<StatusCode, AppError> {
+    let sql = match body.action.as_str() {
+        "keep" => {
+            "UPDATE letterings SET credit_disputed_at = NULL, credit_dispute_reason = NULL
+             WHERE id = $1 AND credit_disputed_at IS NOT NULL"
+        }
+        "remove" => {
+            "UPDATE letterings
+             SET credit_name = NULL, credit_user_id = NULL,
+                 credit_disputed_at = NULL, credit_dispute_reason = NULL
+             WHERE id = $1 AND credit_disputed_at IS NOT NULL"
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "action must be keep or remove".to_string(),
+            ));
+        }
+    };
+    let updated = sqlx::query(sql)
+        .bind(id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .rows_affected();
+    if updated == 0 {
+        return Err(AppError::NotFound(
+            "No disputed credit on this lettering".to_string(),
+        ));
+    }
+
+    log_admin_action(
+        &state,
+        &claims.sub,
+        "RESOLVE_CREDIT_DISPUTE",
+        Some(id),
+        serde_json::json!({ "action": body.action }),
+    )
+    .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_credit_name;
+
+    #[test]
+    fn validates_credit_names() {
+        assert_eq!(
+            validate_credit_name("  Asha   Rao ").as_deref(),
+            Ok("Asha Rao")
+        );
+        assert!(validate_credit_name("A").is_err());
+        assert!(validate_credit_name(&"x".repeat(81)).is_err());
+        assert!(validate_credit_name("Asha\u{7}Rao").is_err());
+        assert!(validate_credit_name("see https://example.com").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+//! Weekly email digest subscriptions.
+//!
+//! Digests are opt-in: `PUT /me/digest` creates the subscription with the
+//! user's time zone, language and preferred send hour, and the weekly digest
+//! worker mails it. Every digest links to the unsubscribe route with the
+//! subscription's token, so recipients can opt out without signing in.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::HeaderMap,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::presentation::http::{
+    errors::AppError,
+    locale::{DEFAULT_LOCALE, normalize_locale},
+    middleware::user::decode_required_user_claims,
+    state::AppState,
+};
+
+const DEFAULT_TIMEZONE: &str = "UTC";
+const DEFAULT_SEND_HOUR: i32 = 9;
+
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
+pub struct DigestSettings {
+    pub enabled: bool,
+    /// IANA time zone the send hour is in.
+    pub timezone: String,
+    /// Language of the digest; untranslated languages get English.
+    pub locale: String,
+    /// Local hour (0-23) on Mondays the digest is sent at.
+    pub send_hour: i32,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct DigestSettingsRequest {
+    pub enabled: bool,
+    /// Left unchanged when omitted.
+    #[ts(optional)]
+    pub timezone: Option<String>,
+    #[ts(optional)]
+    pub locale: Option<String>,
+    #[ts(optional)]
+    pub send_hour: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct UnsubscribeQuery {
+    pub token: Uuid,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct UnsubscribeResponse {
+    pub unsubscribed: bool,
+}
+
+fn user_id(headers: &HeaderMap, state: &AppState) -> Result<Uuid, AppError> {
+    let claims = decode_required_user_claims(headers, &state.config.jwt_secret)?;
+    Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Forbidden("Invalid token subject".to_string()))
+}
+
+/// The current user's digest settings; defaults, disabled, if they never
+/// opted in.
+pub async fn get_digest_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<DigestSettings>, AppError> {
+    let user_id = user_id(&headers, &state)?;
+    let settings = sqlx::query_as::<_, DigestSettings>(
+        "SELECT enabled, timezone, locale, send_hour
+         FROM digest_subscriptions WHERE user_id = $1",
+    )
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(settings.unwrap_or_else(|| DigestSettings {
+        enabled: false,
+        timezone: DEFAULT_TIMEZONE.to_string(),
+        locale: DEFAULT_LOCALE.to_string(),
+        send_hour: DEFAULT_SEND_HOUR,
+    })))
+}
+
+/// Opt in or out of the weekly digest and set when and in which language
+/// it is sent.
+pub async fn update_digest_settings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<DigestSettingsRequest>,
+) -> Result<Json<DigestSettings>, AppError> {
+    let user_id = user_id(&headers, &state)?;
+
+    let timezone = match payload.timezone.as_deref().map(str::trim) {
+        Some(tz) => {
+            let known: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM pg_timezone_names WHERE name = $1)",
+            )
+            .bind(tz)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+            if !known {
+                return Err(AppError::BadRequest(
+                    "timezone must be an IANA time zone name".to_string(),
+                ));
+            }
+            Some(tz.to_string())
+        }
+        None => None,
+    };
+    // Digests are written per language, so only the base tag is kept.
+    let locale = match payload.locale.as_deref() {
+        Some(tag) => Some(
+            normalize_locale(tag)
+                .and_then(|l| l.split('-').next().map(str::to_string))
+                .ok_or_else(|| AppError::BadRequest("Unsupported locale".to_string()))?,
+        ),
+        None => None,
+    };
+    if let Some(hour) = payload.send_hour
+        && !(0..=23).contains(&hour)
+    {
+        return Err(AppError::BadRequest(
+            "send_hour must be between 0 and 23".to_string(),
+        ));
+    }
+
+    let settings = sqlx::query_as::<_, DigestSettings>(
+        "INSERT INTO digest_subscriptions (user_id, enabled, timezone, locale, send_hour)
+         VALUES ($1, $2, COALESCE($3, $6), COALESCE($4, $7), COALESCE($5, $8))
+         ON CONFLICT (user_id) DO UPDATE SET
+             enabled = EXCLUDED.enabled,
+             timezone = COALESCE($3, digest_subscriptions.timezone),
+             locale = COALESCE($4, digest_subscriptions.locale),
+             send_hour = COALESCE($5, digest_subscriptions.send_hour),
+             updated_at = NOW()
+         RETURNING enabled, timezone, locale, send_hour",
+    )
+    .bind(user_id)
+    .bind(payload.enabled)
+    .bind(timezone)
+    .bind(locale)
+    .bind(payload.send_hour)
+    .bind(DEFAULT_TIMEZONE)
+    .bind(DEFAULT_LOCALE)
+    .bind(DEFAULT_SEND_HOUR)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(settings))
+}
+
+/// Turns off the digest of the subscription a digest email was sent for.
+/// Served for GET (the link in the email) and POST (one-click unsubscribe
+/// from mail clients); repeating it is harmless.
+pub async fn unsubscribe_digest(
+    State(state): State<AppState>,
+    Query(params): Query<UnsubscribeQuery>,
+) -> Result<Json<UnsubscribeResponse>, AppError> {
+    let updated = sqlx::query(
+        "UPDATE digest_subscriptions SET enabled = false, updated_at = NOW()
+         WHERE unsubscribe_token = $1",
+    )
+    .bind(params.token)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound("Subscription not found".to_string()));
+    }
+    Ok(Json(UnsubscribeResponse { unsubscribed: true }))
+}
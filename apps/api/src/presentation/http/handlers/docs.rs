@@ -1,53 +1,837 @@
 use axum::Json;
+use serde_json::{Map, Value, json};
 
-pub async fn api_docs() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
+/// Documented routes, each with the summary of every method it answers.
+const ROUTES: &[(&str, &[(&str, &str)])] = &[
+    ("/health", &[("get", "Health check")]),
+    (
+        "/ws/feed",
+        &[(
+            "get",
+            "WebSocket feed of new uploads; on shutdown the server closes it with code 1012 and a JSON reason {\"reconnect_after_ms\": n} to wait before reconnecting, and refuses new connections with 503 and Retry-After while draining",
+        )],
+    ),
+    (
+        "/api/v1/letterings",
+        &[(
+            "get",
+            "List letterings; color=#RRGGBB matches palettes within tolerance (CIE Lab ΔE, 1-50, default 12); age-restricted items in gated regions need age_ack=true; sends an ETag and answers If-None-Match with 304 when unchanged; anonymous responses are replayed for RESPONSE_CACHE_TTLS feed (default 30s) with X-Cache: HIT or MISS",
+        )],
+    ),
+    (
+        "/api/v1/letterings/search",
+        &[(
+            "get",
+            "Search letterings (lang=en|hi|kn|ta|bn|ar selects the stemmer and per-script index, other locales match unstemmed; age_ack=true includes age-restricted items in gated regions; lat/lng feed the proximity term of the SEARCH_WEIGHT_* ranking)",
+        )],
+    ),
+    (
+        "/api/v1/letterings/upload",
+        &[(
+            "post",
+            "Upload lettering; optional lat/lng (defaults to the city centre) and location_privacy=exact|fuzzed|city for what public views and exports show; optional credit_name and/or credit_user_id to credit the photographer; mobile apps send X-Client-Platform (ios|android) and X-App-Attestation, and browsers send a Turnstile token in X-Web-Challenge; while any platform's attestation is enforced, an upload without a verified token gets 403, or 503 if the provider can't be asked; 429 once the contributor tag reaches the daily upload limit; responds with processing_delayed: true while the ML queue is backed up past ML_MAX_QUEUE_DEPTH",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/credit/dispute",
+        &[(
+            "post",
+            "Credited user disputes a photographer credit (optional reason); the credit is hidden until an admin resolves it",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}",
+        &[
+            (
+                "get",
+                "Get lettering by id with city_name/country_name localized by Accept-Language; 403 for an age-restricted item in a gated region unless age_ack=true; the lettering is cached for up to a minute, dropped on moderation and edits, and a 404 for NOT_FOUND_CACHE_TTL_SECONDS; anonymous responses are replayed for RESPONSE_CACHE_TTLS lettering (default 5 minutes) until moderation or edits, with X-Cache as for list; ETag and If-None-Match as for list",
+            ),
+            ("delete", "Delete lettering by id"),
+        ],
+    ),
+    (
+        "/api/v1/letterings/{id}/comments",
+        &[
+            (
+                "get",
+                "List comments for lettering as an array: sort=newest|oldest|top, cursor from the X-Next-Cursor header of the previous page (absent on the last page), limit (default 20, max 100), parent_id for replies; deleted comments with replies appear as tombstones",
+            ),
+            (
+                "post",
+                "Add comment for lettering (authenticated user); parent_id replies to a top-level comment",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/comments/{id}/reactions",
+        &[(
+            "post",
+            "Toggle reaction kind=like|love|laugh|insightful on a comment (authenticated user)",
+        )],
+    ),
+    (
+        "/api/v1/letterings/discover",
+        &[(
+            "get",
+            "Random approved letterings (limit 1-50, default 20) drawn by table sampling; pass the returned seen token back to skip letterings already shown; age_ack as for list",
+        )],
+    ),
+    (
+        "/api/v1/browse/scripts",
+        &[(
+            "get",
+            "Scripts among public letterings with count and the most liked lettering as cover, largest first; city_id, age_ack as for list",
+        )],
+    ),
+    (
+        "/api/v1/browse/scripts/{script}",
+        &[(
+            "get",
+            "Public letterings in one script, paginated like list (limit, offset, city_id, sort_by, age_ack)",
+        )],
+    ),
+    (
+        "/api/v1/browse/styles",
+        &[(
+            "get",
+            "Styles among public letterings with count and the most liked lettering as cover, largest first; city_id, age_ack as for list",
+        )],
+    ),
+    (
+        "/api/v1/browse/styles/{style}",
+        &[(
+            "get",
+            "Public letterings in one style, paginated like list (limit, offset, city_id, sort_by, age_ack)",
+        )],
+    ),
+    (
+        "/api/v1/contributors/{tag}",
+        &[(
+            "get",
+            "Contributor profile: their letterings (limit, offset) with total_count and follower_count; pages are cached and dropped when any of their letterings changes; ETag and If-None-Match as for list",
+        )],
+    ),
+    (
+        "/api/v1/leaderboards/{period}",
+        &[(
+            "get",
+            "Top contributors for period=weekly|monthly|all-time by approved uploads, likes received and cities covered; recomputed hourly, cached for 5 minutes",
+        )],
+    ),
+    (
+        "/api/v1/letterings/nearby",
+        &[(
+            "get",
+            "Approved letterings within radius metres (default 1000, max 50000) of lat/lng, nearest first with distance_m and city_name localized by Accept-Language; age_ack=true includes age-restricted items in gated regions",
+        )],
+    ),
+    (
+        "/api/v1/letterings/map",
+        &[(
+            "get",
+            "Approved letterings in bbox=min_lng,min_lat,max_lng,max_lat: count + centroid clusters below zoom 15, individual points from zoom 15; age_ack as for nearby; views spanning 180 degrees of longitude or more are the whole world and cached",
+        )],
+    ),
+    ("/api/v1/letterings/{id}/like", &[("post", "Toggle like")]),
+    (
+        "/api/v1/letterings/{id}/bookmark",
+        &[
+            (
+                "post",
+                "Bookmark a lettering privately (201 new, 200 existing)",
+            ),
+            ("delete", "Remove a bookmark"),
+        ],
+    ),
+    (
+        "/api/v1/letterings/{id}/report",
+        &[(
+            "post",
+            "Report a lettering; one open report per reporter (account, else client IP), so reporting again replaces the reason without adding to report_count; weighted reports reaching REPORT_AUTO_HIDE_THRESHOLD within the window hide the item as REPORTED",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/similar",
+        &[(
+            "get",
+            "Get visually similar letterings (embedding ANN search, metadata fallback)",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/og-image",
+        &[(
+            "get",
+            "Open Graph share card PNG (photo + detected text + contributor + city) for approved letterings, cached in storage",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/context",
+        &[(
+            "get",
+            "Detail screen context in one cached response: nearby, same-contributor and same-style letterings plus city info (age_ack)",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/processing",
+        &[(
+            "get",
+            "Upload progress: status of the virus_scan, thumbnails and ml stages (PENDING, RUNNING, RETRYING, DONE, FAILED or SKIPPED), the last ML error and whether all are complete; changes are also sent on /ws/feed as PROCESSING events with id, stage and status",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/share",
+        &[(
+            "post",
+            "Short share URL (tagged with an optional channel) and the stored Open Graph image URL for an approved lettering; renders the card if needed",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/qr",
+        &[(
+            "get",
+            "QR code (format=png|svg, size up to 2048) linking to an approved lettering's short link, for plaques",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/download",
+        &[("get", "Redirect to original image")],
+    ),
+    (
+        "/images/{id}",
+        &[(
+            "get",
+            "Resized rendition of an approved lettering (w, h up to 2048, format=jpeg|png|webp), rendered from the original and cached in storage",
+        )],
+    ),
+    (
+        "/s/{code}",
+        &[(
+            "get",
+            "Resolve a lettering short link to its page, counting the share channel (c query param)",
+        )],
+    ),
+    (
+        "/api/v1/letterings/{id}/revisits",
+        &[
+            ("get", "Get revisit links for lettering"),
+            ("post", "Create revisit link for lettering"),
+        ],
+    ),
+    (
+        "/api/v1/geo/markers",
+        &[(
+            "get",
+            "Get map markers (age_ack=true includes age-restricted items in gated regions; lat/lng feed the proximity term of the SEARCH_WEIGHT_* ranking)",
+        )],
+    ),
+    (
+        "/api/v1/geo/nearby",
+        &[("get", "Get nearby markers (age_ack as for markers)")],
+    ),
+    (
+        "/api/v1/geo/coverage",
+        &[(
+            "get",
+            "Get pin-code coverage data (age_ack as for markers; city_name follows Accept-Language)",
+        )],
+    ),
+    (
+        "/api/v1/cities",
+        &[(
+            "get",
+            "List cities (supports search/discovery; display_name and country_name follow Accept-Language, falling back to English then the canonical name)",
+        )],
+    ),
+    (
+        "/api/v1/cities/{id}",
+        &[(
+            "get",
+            "Get city detail (localized display_name/country_name as for the list); cached for up to 5 minutes per locale",
+        )],
+    ),
+    (
+        "/api/v1/cities/{id}/stats",
+        &[(
+            "get",
+            "Get city neighborhood stats; anonymous responses are replayed for RESPONSE_CACHE_TTLS stats (default 60s) until a lettering in the city changes, with X-Cache as for list",
+        )],
+    ),
+    (
+        "/api/v1/regions/{country_code}/aggregates",
+        &[(
+            "get",
+            "Approved lettering counts per city, style and script for a country, names localized by Accept-Language; in countries without discoverability buckets under 3 are folded into other",
+        )],
+    ),
+    (
+        "/api/v1/admin/cities/discover",
+        &[(
+            "post",
+            "Admin: discover cities using Nominatim + Wikipedia enrichment",
+        )],
+    ),
+    (
+        "/api/v1/admin/cities/bootstrap-capitals",
+        &[(
+            "post",
+            "Admin: bootstrap global capitals using REST Countries + Wikipedia enrichment",
+        )],
+    ),
+    (
+        "/api/v1/admin/cities/{id}/translations",
+        &[("get", "Admin: list localized names for a city")],
+    ),
+    (
+        "/api/v1/admin/cities/{id}/translations/{locale}",
+        &[
+            (
+                "put",
+                "Admin: set a city's name in a locale (en, hi, kn, ta, bn, ar, optionally with a region)",
+            ),
+            ("delete", "Admin: remove a city name translation"),
+        ],
+    ),
+    (
+        "/api/v1/admin/countries/{country_code}/translations",
+        &[("get", "Admin: list localized names for a country")],
+    ),
+    (
+        "/api/v1/admin/countries/{country_code}/translations/{locale}",
+        &[
+            ("put", "Admin: set a country's name in a locale"),
+            ("delete", "Admin: remove a country name translation"),
+        ],
+    ),
+    ("/api/v1/docs", &[("get", "OpenAPI spec")]),
+    (
+        "/api/v1/auth/register",
+        &[("post", "Register user account")],
+    ),
+    ("/api/v1/auth/login", &[("post", "Login user account")]),
+    ("/api/v1/auth/me", &[("get", "Get current user profile")]),
+    (
+        "/api/v1/me/letterings",
+        &[("get", "List current user's uploads")],
+    ),
+    (
+        "/api/v1/me/letterings/{id}",
+        &[(
+            "patch",
+            "Edit description, contributor tag or pin code of one of the current user's uploads",
+        )],
+    ),
+    (
+        "/api/v1/me/letterings/{id}/timeline",
+        &[(
+            "get",
+            "Status and metadata history of one of the current user's uploads",
+        )],
+    ),
+    (
+        "/api/v1/me/notifications",
+        &[("get", "List current user's notifications")],
+    ),
+    (
+        "/api/v1/me/saved-searches",
+        &[
+            ("get", "List current user's saved searches"),
+            (
+                "post",
+                "Save a search (query, script, style, city_id and/or lat/lng/radius_m area); new approvals matching it raise a SAVED_SEARCH_MATCHES notification unless notify=false",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/me/saved-searches/{id}",
+        &[
+            ("put", "Replace one of the current user's saved searches"),
+            ("delete", "Delete one of the current user's saved searches"),
+        ],
+    ),
+    (
+        "/api/v1/me/follows",
+        &[
+            ("get", "List contributors the current user follows"),
+            (
+                "post",
+                "Follow a contributor_tag or a user_id; their new approvals raise a FOLLOWED_CONTRIBUTOR_APPROVALS notification",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/me/follows/{id}",
+        &[("delete", "Unfollow a contributor")],
+    ),
+    (
+        "/api/v1/me/bookmarks",
+        &[(
+            "get",
+            "The current user's bookmarks, newest first (limit/offset)",
+        )],
+    ),
+    (
+        "/api/v1/me/feed",
+        &[(
+            "get",
+            "The current user's activity feed, newest first: approvals from followed contributors, replies to their comments and likes on their uploads (cursor pagination)",
+        )],
+    ),
+    (
+        "/api/v1/me/digest",
+        &[
+            (
+                "get",
+                "The current user's weekly email digest settings (enabled, timezone, locale, send_hour)",
+            ),
+            (
+                "put",
+                "Opt in or out of the weekly email digest; timezone (IANA), locale and send_hour (0-23, Mondays) are kept when omitted",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/digest/unsubscribe",
+        &[
+            (
+                "get",
+                "Turn off the weekly digest for the subscription token in a digest email",
+            ),
+            (
+                "post",
+                "One-click unsubscribe (RFC 8058) for the subscription token in a digest email",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/me/stats",
+        &[(
+            "get",
+            "The current user's upload counts by status, likes and comments received, cities covered, current upload streak and strikes (rejections in the last 90 days); cached for 2 minutes",
+        )],
+    ),
+    (
+        "/api/v1/me/collections",
+        &[(
+            "get",
+            "List the current user's collections, public and private",
+        )],
+    ),
+    (
+        "/api/v1/collections",
+        &[
+            ("get", "List public collections (limit/offset)"),
+            (
+                "post",
+                "Create a collection (name, description, is_public); public ones get a share slug",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/collections/{id}",
+        &[
+            (
+                "get",
+                "A collection with its publicly listed letterings in order; private ones only for their owner",
+            ),
+            (
+                "patch",
+                "Owner: rename, describe or change the visibility of a collection",
+            ),
+            ("delete", "Owner: delete a collection"),
+        ],
+    ),
+    (
+        "/api/v1/collections/{id}/order",
+        &[(
+            "put",
+            "Owner: move lettering_ids to the front in that order; other items follow",
+        )],
+    ),
+    (
+        "/api/v1/collections/{collection_id}/items/{lettering_id}",
+        &[
+            (
+                "post",
+                "Owner: append an approved lettering to a collection",
+            ),
+            ("delete", "Owner: remove a lettering from a collection"),
+        ],
+    ),
+    (
+        "/api/v1/collections/shared/{slug}",
+        &[("get", "A public collection by its share slug")],
+    ),
+    (
+        "/api/v1/admin/moderation",
+        &[(
+            "get",
+            "Admin: moderation queue (status/low_confidence/near_duplicate filters); group_by=contributor|ip returns per-source summaries in groups",
+        )],
+    ),
+    (
+        "/api/v1/admin/moderation/auto-approve/preview",
+        &[(
+            "get",
+            "Admin: which pending items the next auto-approval pass would approve and which it would hold and why, without changing anything (minutes overrides the review window)",
+        )],
+    ),
+    (
+        "/api/v1/admin/moderation/escalations",
+        &[(
+            "get",
+            "Admin: weekly counts of stale pending items escalated (weeks=12), and how many escalated items are still pending",
+        )],
+    ),
+    (
+        "/api/v1/admin/moderation/sources/action",
+        &[(
+            "post",
+            "Admin: approve or reject every PENDING (or REPORTED) upload from one contributor or IP",
+        )],
+    ),
+    (
+        "/api/v1/admin/letterings/{id}/age-restriction",
+        &[(
+            "put",
+            "Admin: set or lift the age restriction; a moderator decision overrides the NSFW classifier",
+        )],
+    ),
+    (
+        "/api/v1/admin/moderation/next",
+        &[(
+            "get",
+            "Admin: claim the next unreviewed item (own claim first, then most escalated, most reported and oldest; items routed to the secondary pool go to its reviewers) for 10 minutes; status/low_confidence/near_duplicate filters, skip releases an item",
+        )],
+    ),
+    (
+        "/api/v1/admin/letterings/{id}/clear-reports",
+        &[(
+            "post",
+            "Admin: close open reports; an auto-hidden item returns to the status it had before",
+        )],
+    ),
+    (
+        "/api/v1/admin/letterings/{id}/timeline",
+        &[(
+            "get",
+            "Admin: chronological status changes, moderator actions, ML runs and corrections, owner edits and reports for a lettering",
+        )],
+    ),
+    (
+        "/api/v1/admin/letterings/{id}/ml-metadata",
+        &[(
+            "patch",
+            "Admin: correct detected_text/ml_style/ml_script, keeping original model output in history",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/model",
+        &[(
+            "get",
+            "Admin: currently loaded text detection model (source, sha256, load time, golden-image validation, execution provider, per-provider inference latency and ML worker metrics: per-model latency, queue wait, batch size and failure causes)",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/model/reload",
+        &[(
+            "post",
+            "Admin: load a model from ml_model_path or a URL, validate against the golden image and hot-swap it",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/shadow-report",
+        &[(
+            "get",
+            "Admin: production vs shadow model agreement, confidence and latency per version pair, with recent disagreements (days window)",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/shadow/promote",
+        &[(
+            "post",
+            "Admin: validate the shadow model and promote it to production",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/dead-letters",
+        &[(
+            "get",
+            "Admin: ML jobs moved to the dead-letter list after exhausting their retries, with the last error",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/dead-letters/requeue",
+        &[(
+            "post",
+            "Admin: requeue every dead-lettered ML job with a fresh attempt count",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/dead-letters/{lettering_id}/requeue",
+        &[(
+            "post",
+            "Admin: requeue the dead-lettered ML job for one lettering on the high-priority queue, ahead of any backlog",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/reprocess",
+        &[
+            (
+                "get",
+                "Admin: list corpus ML reprocessing runs with progress",
+            ),
+            (
+                "post",
+                "Admin: reprocess existing letterings through the current models, filtered by created_from/created_to, missing_text_only or model_version; rate limited and queued as bulk work behind live uploads",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/admin/ml/reprocess/{id}",
+        &[(
+            "get",
+            "Admin: progress of one ML reprocessing run (total, enqueued, processed, failed)",
+        )],
+    ),
+    (
+        "/api/v1/admin/ml/reprocess/{id}/cancel",
+        &[("post", "Admin: stop feeding an ML reprocessing run")],
+    ),
+    (
+        "/api/v1/admin/ml/training-export",
+        &[(
+            "get",
+            "Admin: export human-corrected ML metadata paired with original model output",
+        )],
+    ),
+    (
+        "/api/v1/admin/stats/by-country",
+        &[(
+            "get",
+            "Admin: uploads, approval rate, active contributors and report rate per country (days window)",
+        )],
+    ),
+    (
+        "/api/v1/admin/stats/request-geo",
+        &[(
+            "get",
+            "Admin: uploads, logins and reports by IP-derived country with report-skew abuse flag (days window)",
+        )],
+    ),
+    (
+        "/api/v1/admin/stats/moderators",
+        &[(
+            "get",
+            "Admin: per-moderator approvals, rejections, overturn rate and average handling time from audit logs (days window)",
+        )],
+    ),
+    (
+        "/api/v1/admin/backups/manifest",
+        &[(
+            "get",
+            "Admin: secondary-bucket backup completeness, recent runs and approved letterings not yet copied",
+        )],
+    ),
+    (
+        "/api/v1/admin/backups/snapshots",
+        &[
+            ("get", "Admin: recent consistent backup snapshots"),
+            (
+                "post",
+                "Admin: queue a backup snapshot recording the WAL LSN and the storage keys referenced at that point, written as a manifest to storage",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/admin/backups/snapshots/{id}",
+        &[(
+            "get",
+            "Admin: backup snapshot marker with a signed manifest link once completed",
+        )],
+    ),
+    (
+        "/api/v1/admin/print-bundles",
+        &[
+            ("get", "Admin: recent print bundles"),
+            (
+                "post",
+                "Admin: queue a print bundle (originals, metadata sheet and attribution) for selected approved letterings",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/admin/print-bundles/{id}",
+        &[(
+            "get",
+            "Admin: print bundle status with a signed download link once completed",
+        )],
+    ),
+    (
+        "/api/v1/analytics/events",
+        &[(
+            "post",
+            "Cookie-less event intake (page_view/search/map_interaction); stored only as daily aggregate counts, honours DNT and Sec-GPC",
+        )],
+    ),
+    (
+        "/api/v1/admin/analytics/events",
+        &[(
+            "get",
+            "Admin: daily first-party event totals and top normalized paths/searches/map actions above a minimum count (days window)",
+        )],
+    ),
+    (
+        "/api/v1/admin/analytics/rollups",
+        &[(
+            "get",
+            "Admin: uploads, approvals, likes, comments and active contributors per hour or day (granularity=hourly|daily, days window, optional country), read from rollups the analytics_rollup task keeps up to date",
+        )],
+    ),
+    (
+        "/api/v1/admin/faults",
+        &[
+            (
+                "get",
+                "Admin: fault injection settings (404 unless ENABLE_FAULT_INJECTION is set on a build with fault injection)",
+            ),
+            (
+                "put",
+                "Admin: set injected Redis error percent, storage latency and every-k-th ML job failure for resilience testing",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/admin/system/info",
+        &[(
+            "get",
+            "Admin: build version and git SHA, active rollout flags, configuration with secrets redacted, migration level, background workers with their current leader instances and job queues, and database and Redis health",
+        )],
+    ),
+    (
+        "/api/v1/admin/schedules",
+        &[(
+            "get",
+            "Admin: scheduled tasks with their UTC cron schedule and its source (default, config or database), whether enabled, next tick and recent runs (runs=N per task, default 10)",
+        )],
+    ),
+    (
+        "/api/v1/admin/workers",
+        &[(
+            "get",
+            "Admin: each background worker's status (running, stale, missing or disabled), queue depth, processed and failed job counts and per-instance heartbeats with last beat and current job",
+        )],
+    ),
+    (
+        "/api/v1/admin/schedules/{name}",
+        &[(
+            "put",
+            "Admin: override a scheduled task's cron (null restores the configured schedule) and/or enable or pause it; applies on every instance from its next check",
+        )],
+    ),
+    (
+        "/api/v1/admin/feature-flags",
+        &[(
+            "get",
+            "Admin: rollout flags with their candidate share and control-vs-candidate requests, error rate, mean and p95 latency (days window, max 7)",
+        )],
+    ),
+    (
+        "/api/v1/admin/feature-flags/{name}",
+        &[(
+            "put",
+            "Admin: set the share (rollout_percent 0-100) of requests routed to a flag's candidate path; clients stay in the same bucket as it grows",
+        )],
+    ),
+    (
+        "/api/v1/admin/rate-limits",
+        &[
+            (
+                "get",
+                "Admin: per-route rate-limit request/block counts, top offending IPs and current limits",
+            ),
+            (
+                "put",
+                "Admin: set or clear a persisted per-route rate-limit override",
+            ),
+        ],
+    ),
+    (
+        "/api/v1/admin/likes/flagged",
+        &[(
+            "get",
+            "Admin: IPs refused by the like velocity limits, with the likes they still hold (days window, max 7)",
+        )],
+    ),
+    (
+        "/api/v1/admin/likes/rollback",
+        &[(
+            "post",
+            "Admin: remove likes from an IP or CIDR network between since and until and recount likes_count (dry_run reports only)",
+        )],
+    ),
+    (
+        "/api/v1/admin/credit-disputes",
+        &[(
+            "get",
+            "Admin: open photographer credit disputes, oldest first",
+        )],
+    ),
+    (
+        "/api/v1/admin/letterings/{id}/credit/resolve",
+        &[(
+            "post",
+            "Admin: resolve a credit dispute with action=keep|remove",
+        )],
+    ),
+    (
+        "/api/v1/admin/comments",
+        &[(
+            "get",
+            "Admin: list comments for moderation (status/search/review filters, score sorting)",
+        )],
+    ),
+    (
+        "/api/v1/admin/comments/{id}/hide",
+        &[("post", "Admin: hide comment and resolve review flag")],
+    ),
+    (
+        "/api/v1/admin/comments/{id}/restore",
+        &[("post", "Admin: restore comment")],
+    ),
+    (
+        "/api/v1/admin/comments/{id}",
+        &[("delete", "Admin: delete comment")],
+    ),
+    (
+        "/api/v1/admin/region-policies",
+        &[("get", "Admin: list region policies")],
+    ),
+    (
+        "/api/v1/admin/region-policies/{country_code}",
+        &[(
+            "put",
+            "Admin: upsert region policy for a country code (discoverability_enabled=false makes the country aggregate-only: its letterings 404 on every item endpoint and only appear in region aggregates; age_gate_enabled hides age-restricted items unless the client sends age_ack)",
+        )],
+    ),
+];
+
+pub async fn api_docs() -> Json<Value> {
+    let paths: Map<String, Value> = ROUTES
+        .iter()
+        .map(|(path, operations)| {
+            let operations: Map<String, Value> = operations
+                .iter()
+                .map(|(method, summary)| (method.to_string(), json!({ "summary": summary })))
+                .collect();
+            (path.to_string(), Value::Object(operations))
+        })
+        .collect();
+    Json(json!({
         "openapi": "3.0.0",
         "info": {
             "title": "Through Your Letters API",
             "version": "1.0.0"
         },
-        "paths": {
-            "/health": { "get": { "summary": "Health check" } },
-            "/api/v1/letterings": { "get": { "summary": "List letterings" } },
-            "/api/v1/letterings/search": { "get": { "summary": "Search letterings (supports lang query for locale-aware search)" } },
-            "/api/v1/letterings/upload": { "post": { "summary": "Upload lettering" } },
-            "/api/v1/letterings/{id}": {
-                "get": { "summary": "Get lettering by id" },
-                "delete": { "summary": "Delete lettering by id" }
-            },
-            "/api/v1/letterings/{id}/comments": {
-                "get": { "summary": "List comments for lettering" },
-                "post": { "summary": "Add comment for lettering (authenticated user)" }
-            },
-            "/api/v1/letterings/{id}/like": { "post": { "summary": "Toggle like" } },
-            "/api/v1/letterings/{id}/similar": { "get": { "summary": "Get similar letterings" } },
-            "/api/v1/letterings/{id}/download": { "get": { "summary": "Redirect to original image" } },
-            "/api/v1/letterings/{id}/revisits": {
-                "get": { "summary": "Get revisit links for lettering" },
-                "post": { "summary": "Create revisit link for lettering" }
-            },
-            "/api/v1/geo/markers": { "get": { "summary": "Get map markers" } },
-            "/api/v1/geo/nearby": { "get": { "summary": "Get nearby markers" } },
-            "/api/v1/geo/coverage": { "get": { "summary": "Get pin-code coverage data" } },
-            "/api/v1/cities": { "get": { "summary": "List cities (supports search/discovery)" } },
-            "/api/v1/cities/{id}": { "get": { "summary": "Get city detail" } },
-            "/api/v1/cities/{id}/stats": { "get": { "summary": "Get city neighborhood stats" } },
-            "/api/v1/admin/cities/discover": { "post": { "summary": "Admin: discover cities using Nominatim + Wikipedia enrichment" } },
-            "/api/v1/admin/cities/bootstrap-capitals": { "post": { "summary": "Admin: bootstrap global capitals using REST Countries + Wikipedia enrichment" } },
-            "/api/v1/docs": { "get": { "summary": "OpenAPI spec" } },
-            "/api/v1/auth/register": { "post": { "summary": "Register user account" } },
-            "/api/v1/auth/login": { "post": { "summary": "Login user account" } },
-            "/api/v1/auth/me": { "get": { "summary": "Get current user profile" } },
-            "/api/v1/me/letterings": { "get": { "summary": "List current user's uploads" } },
-            "/api/v1/me/notifications": { "get": { "summary": "List current user's notifications" } },
-            "/api/v1/admin/comments": { "get": { "summary": "Admin: list comments for moderation (status/search/review filters, score sorting)" } },
-            "/api/v1/admin/comments/{id}/hide": { "post": { "summary": "Admin: hide comment and resolve review flag" } },
-            "/api/v1/admin/comments/{id}/restore": { "post": { "summary": "Admin: restore comment" } },
-            "/api/v1/admin/comments/{id}": { "delete": { "summary": "Admin: delete comment" } },
-            "/api/v1/admin/region-policies": { "get": { "summary": "Admin: list region policies" } },
-            "/api/v1/admin/region-policies/{country_code}": { "put": { "summary": "Admin: upsert region policy for a country code" } },
-            "/ws/feed": { "get": { "summary": "WebSocket live feed" } }
-        }
+        "paths": paths
     }))
 }
@@ -1,17 +1,28 @@
 use crate::{
-    application::get_letterings::dto::PaginatedResponse,
+    application::get_letterings::dto::{DiscoverResponse, PaginatedResponse},
     domain::lettering::entity::Lettering,
+    infrastructure::{
+        cache::invalidation::{BROWSE_PREFIX, GALLERY_PREFIX},
+        imaging::color_palette::{
+            DEFAULT_MATCH_TOLERANCE, MAX_MATCH_TOLERANCE, hex_to_lab, lab_bins_within,
+            normalize_hex_color,
+        },
+        repositories::region_policy::{PUBLIC_LETTERING_JOINS, push_public_filter},
+    },
     presentation::http::{errors::AppError, state::AppState},
 };
 use axum::{
     Json,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
 };
-use serde::Deserialize;
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::{Postgres, QueryBuilder};
 use std::time::Instant;
 use tracing::{debug, error, info, instrument, warn};
+use ts_rs::TS;
 use uuid::Uuid;
 
 /// Query parameters for gallery endpoint with validation and defaults.
@@ -37,8 +48,19 @@ pub struct GalleryQuery {
     /// Filter by visual style category (e.g., "modern", "traditional") (optional)
     style: Option<String>,
 
+    /// Filter by a dominant palette colour, `#RRGGBB` or `RRGGBB` (optional)
+    color: Option<String>,
+
+    /// How far a palette colour may be from `color`, in CIE76 ΔE (1-50,
+    /// default 12) (optional)
+    tolerance: Option<f64>,
+
     /// Sort order: "newest" (default), "oldest", "popular" (optional)
     sort_by: Option<String>,
+
+    /// Include age-restricted letterings in regions that gate them; set once
+    /// the client has shown its age gate (optional)
+    age_ack: Option<bool>,
 }
 
 /// Default pagination limit for gallery queries.
@@ -50,15 +72,22 @@ fn default_limit() -> i64 {
 /// Maximum allowed pagination limit to prevent resource exhaustion.
 const MAX_LIMIT: i64 = 100;
 
-/// Cache key prefix for gallery queries.
-const GALLERY_CACHE_PREFIX: &str = "gallery:";
-
 /// Cache TTL for gallery results in seconds (5 minutes).
 const GALLERY_CACHE_TTL: usize = 300;
 
+/// The `tolerance` a colour search runs with.
+fn color_tolerance(params: &GalleryQuery) -> f64 {
+    params
+        .tolerance
+        .filter(|t| t.is_finite())
+        .unwrap_or(DEFAULT_MATCH_TOLERANCE)
+        .clamp(1.0, MAX_MATCH_TOLERANCE)
+}
+
 /// Applies filter conditions to gallery query based on provided parameters.
 ///
 /// Ensures only approved letterings from discoverable regions are included,
+/// minus age-restricted ones where the region gates them and `age_ack` is unset,
 /// with optional filtering by location, visual characteristics, or content type.
 ///
 /// # Arguments
@@ -66,10 +95,8 @@ const GALLERY_CACHE_TTL: usize = 300;
 /// * `params` - User-provided filter parameters
 fn apply_gallery_filters(qb: &mut QueryBuilder<'_, Postgres>, params: &GalleryQuery) {
     // Base filters: only approved letterings from discoverable regions
-    qb.push(
-        " WHERE l.status = 'APPROVED'
-          AND COALESCE(rp.discoverability_enabled, true)",
-    );
+    qb.push(" WHERE ");
+    push_public_filter(qb, params.age_ack.unwrap_or(false));
 
     // Optional city/region filter
     if let Some(city_id) = params.city_id {
@@ -85,7 +112,9 @@ fn apply_gallery_filters(qb: &mut QueryBuilder<'_, Postgres>, params: &GalleryQu
         .filter(|s| !s.is_empty())
     {
         debug!("Filtering by script: {}", script);
-        qb.push(" AND l.ml_script = ").push_bind(script.to_string());
+        qb.push(" AND l.ml_script = ")
+            .push_bind(script.to_string())
+            .push(" AND NOT ('script' = ANY(l.ml_low_confidence_fields))");
     }
 
     // Optional visual style filter (with sanitization)
@@ -96,7 +125,30 @@ fn apply_gallery_filters(qb: &mut QueryBuilder<'_, Postgres>, params: &GalleryQu
         .filter(|s| !s.is_empty())
     {
         debug!("Filtering by style: {}", style);
-        qb.push(" AND l.ml_style = ").push_bind(style.to_string());
+        qb.push(" AND l.ml_style = ")
+            .push_bind(style.to_string())
+            .push(" AND NOT ('style' = ANY(l.ml_low_confidence_fields))");
+    }
+
+    // Optional palette colour filter (validated by the handler)
+    // The Lab cells around the colour narrow candidates through the
+    // `ml_color_bins` index before the exact distance check.
+    if let Some(color) = params.color.as_deref().and_then(normalize_hex_color)
+        && let Some(lab) = hex_to_lab(&color)
+    {
+        let tolerance = color_tolerance(params);
+        debug!("Filtering by color: {} within {}", color, tolerance);
+        qb.push(" AND l.ml_color_bins && ")
+            .push_bind(lab_bins_within(lab, tolerance))
+            .push(
+                " AND EXISTS (SELECT 1 FROM jsonb_array_elements_text(l.ml_color_palette) p(color)
+                              WHERE p.color ~ '^#[0-9A-Fa-f]{6}$'
+                                AND hex_color_lab_distance(p.color, ",
+            )
+            .push_bind(color)
+            .push(") <= ")
+            .push_bind(tolerance)
+            .push(")");
     }
 }
 
@@ -106,8 +158,8 @@ fn apply_gallery_filters(qb: &mut QueryBuilder<'_, Postgres>, params: &GalleryQu
 /// efficient caching and cache invalidation.
 fn generate_cache_key(params: &GalleryQuery) -> String {
     format!(
-        "{}{}:{}:{}:{}:{}:{}",
-        GALLERY_CACHE_PREFIX,
+        "{}{}:{}:{}:{}:{}:{}:{}:{}:{}",
+        GALLERY_PREFIX,
         params.limit,
         params.offset,
         params
@@ -116,7 +168,14 @@ fn generate_cache_key(params: &GalleryQuery) -> String {
             .unwrap_or_else(|| "all".to_string()),
         params.script.as_deref().unwrap_or("all"),
         params.style.as_deref().unwrap_or("all"),
-        params.sort_by.as_deref().unwrap_or("newest")
+        params
+            .color
+            .as_deref()
+            .and_then(normalize_hex_color)
+            .unwrap_or_else(|| "all".to_string()),
+        color_tolerance(params),
+        params.sort_by.as_deref().unwrap_or("newest"),
+        params.age_ack.unwrap_or(false)
     )
 }
 
@@ -132,7 +191,10 @@ fn generate_cache_key(params: &GalleryQuery) -> String {
 /// - `city_id`: Filter by city UUID (optional)
 /// - `script`: Filter by script type (optional)
 /// - `style`: Filter by visual style (optional)
+/// - `color`: Filter by palette colour (optional)
+/// - `tolerance`: Lab distance for `color`, 1-50, default 12 (optional)
 /// - `sort_by`: Sort order - "newest", "oldest", "popular" (optional)
+/// - `age_ack`: Include age-restricted letterings in gated regions (optional)
 ///
 /// # Returns
 /// Paginated response containing lettering entities and metadata
@@ -144,7 +206,7 @@ fn generate_cache_key(params: &GalleryQuery) -> String {
     limit = params.limit,
     offset = params.offset,
     city_id = ?params.city_id,
-    has_filters = !(params.script.is_none() && params.style.is_none())
+    has_filters = !(params.script.is_none() && params.style.is_none() && params.color.is_none())
 ))]
 pub async fn get_letterings(
     State(state): State<AppState>,
@@ -154,6 +216,13 @@ pub async fn get_letterings(
     let start_time = Instant::now();
 
     // Validate and sanitize input parameters
+    if let Some(color) = params.color.as_deref().filter(|c| !c.trim().is_empty())
+        && normalize_hex_color(color).is_none()
+    {
+        return Err(AppError::BadRequest(
+            "color must be a hex colour like #FF8800".to_string(),
+        ));
+    }
     let safe_limit = params.limit.clamp(1, MAX_LIMIT);
     let safe_offset = params.offset.max(0);
 
@@ -169,19 +238,56 @@ pub async fn get_letterings(
         safe_limit, safe_offset
     );
 
+    let response = fetch_gallery_page(&state, params).await?;
+
+    let duration = start_time.elapsed();
+    info!(
+        "Gallery request completed in {:?}, returned {} of {} letterings",
+        duration,
+        response.letterings.len(),
+        response.total
+    );
+
+    Ok(Json(response))
+}
+
+/// Fills the cached first page of the gallery as the web client opens it:
+/// newest first, and most liked for what is trending.
+pub async fn warm_front_page(state: AppState) -> Result<(), AppError> {
+    for sort_by in [None, Some("popular")] {
+        let params = GalleryQuery {
+            limit: default_limit(),
+            offset: 0,
+            city_id: None,
+            script: None,
+            style: None,
+            color: None,
+            tolerance: None,
+            sort_by: sort_by.map(str::to_string),
+            age_ack: None,
+        };
+        fetch_gallery_page(&state, params).await?;
+    }
+    Ok(())
+}
+
+/// One cached page of the gallery for already validated `params`.
+async fn fetch_gallery_page(
+    state: &AppState,
+    params: GalleryQuery,
+) -> Result<PaginatedResponse, AppError> {
+    let safe_limit = params.limit.clamp(1, MAX_LIMIT);
+    let safe_offset = params.offset.max(0);
     let cache_key = generate_cache_key(&params);
     let db = state.db.clone();
 
-    let response = state
+    state
         .cache
         .get_or_fetch(&cache_key, GALLERY_CACHE_TTL as u64, || async move {
             // Count query
-            let mut count_qb = QueryBuilder::<Postgres>::new(
-                "SELECT COUNT(*)::bigint
-                 FROM letterings l
-                 JOIN cities c ON c.id = l.city_id
-                 LEFT JOIN region_policies rp ON rp.country_code = c.country_code",
-            );
+            let mut count_qb =
+                QueryBuilder::<Postgres>::new("SELECT COUNT(*)::bigint FROM letterings l ");
+            count_qb.push(PUBLIC_LETTERING_JOINS);
             apply_gallery_filters(&mut count_qb, &params);
 
             let total: i64 = count_qb
@@ -202,10 +308,9 @@ pub async fn get_letterings(
                         l.cultural_context, l.report_count, l.report_reasons,
                         l.likes_count, l.comments_count, l.uploaded_by_ip,
                         ST_AsText(l.location) AS location
-                 FROM letterings l
-                 JOIN cities c ON c.id = l.city_id
-                 LEFT JOIN region_policies rp ON rp.country_code = c.country_code",
+                 FROM letterings l ",
             );
+            data_qb.push(PUBLIC_LETTERING_JOINS);
             apply_gallery_filters(&mut data_qb, &params);
 
             let order_by = match params.sort_by.as_deref() {
@@ -240,17 +345,408 @@ pub async fn get_letterings(
         .map_err(|e| {
             error!("Gallery fetch failed: {}", e);
             AppError::Internal(format!("Failed to retrieve letterings: {}", e))
-        })?;
+        })
+}
 
-    let duration = start_time.elapsed();
-    info!(
-        "Gallery request completed in {:?}, returned {} of {} letterings",
-        duration,
-        response.letterings.len(),
-        response.total
+/// ML classification a browse page is built around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BrowseFacetKind {
+    Script,
+    Style,
+}
+
+impl BrowseFacetKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Script => "script",
+            Self::Style => "style",
+        }
+    }
+
+    /// Column holding the classification; its low-confidence field name is
+    /// [`as_str`](Self::as_str).
+    fn column(self) -> &'static str {
+        match self {
+            Self::Script => "ml_script",
+            Self::Style => "ml_style",
+        }
+    }
+}
+
+/// Cache TTL for browse indexes in seconds (15 minutes).
+const BROWSE_FACETS_CACHE_TTL: u64 = 900;
+
+/// Query parameters for the script and style browse indexes.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct BrowseFacetsQuery {
+    /// Count only letterings in this city (optional)
+    #[ts(optional)]
+    pub city_id: Option<Uuid>,
+
+    /// Include age-restricted letterings in regions that gate them (optional)
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+/// One script or style with how many public letterings have it.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, TS)]
+#[ts(export)]
+pub struct BrowseFacet {
+    pub value: String,
+    #[ts(type = "number")]
+    pub count: i64,
+    /// Most liked lettering with this value, used as the browse tile.
+    pub cover_lettering_id: Uuid,
+    pub cover_image_url: String,
+}
+
+/// Query parameters for a browse page's letterings.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct BrowseLetteringsQuery {
+    /// Maximum number of results to return (1-100, default 50)
+    #[serde(default = "default_limit")]
+    #[ts(type = "number", optional)]
+    pub limit: i64,
+
+    /// Number of results to skip for pagination (default 0)
+    #[serde(default)]
+    #[ts(type = "number", optional)]
+    pub offset: i64,
+
+    /// Filter by specific city/region UUID (optional)
+    #[ts(optional)]
+    pub city_id: Option<Uuid>,
+
+    /// Sort order: "newest" (default), "oldest", "popular" (optional)
+    #[ts(optional)]
+    pub sort_by: Option<String>,
+
+    /// Include age-restricted letterings in regions that gate them (optional)
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+/// Every value of `kind` among public letterings, largest first. Values the
+/// classifier was unsure of are left out, as in the gallery filters.
+async fn browse_facets(
+    state: &AppState,
+    kind: BrowseFacetKind,
+    params: BrowseFacetsQuery,
+) -> Result<Vec<BrowseFacet>, AppError> {
+    let age_ack = params.age_ack.unwrap_or(false);
+    let cache_key = format!(
+        "{}{}:{}:{}",
+        BROWSE_PREFIX,
+        kind.as_str(),
+        params
+            .city_id
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| "all".to_string()),
+        age_ack
     );
+    let db = state.db.clone();
 
-    Ok(Json(response))
+    state
+        .cache
+        .get_or_fetch(&cache_key, BROWSE_FACETS_CACHE_TTL, || async move {
+            let column = kind.column();
+            let mut qb = QueryBuilder::<Postgres>::new(format!(
+                "SELECT l.{column} AS value, COUNT(*)::bigint AS count,
+                        (ARRAY_AGG(l.id ORDER BY l.likes_count DESC, l.created_at DESC))[1]
+                            AS cover_lettering_id,
+                        (ARRAY_AGG(l.thumbnail_medium ORDER BY l.likes_count DESC, l.created_at DESC))[1]
+                            AS cover_image_url
+                 FROM letterings l "
+            ));
+            qb.push(PUBLIC_LETTERING_JOINS).push(" WHERE ");
+            push_public_filter(&mut qb, age_ack);
+            qb.push(format!(
+                " AND l.{column} IS NOT NULL AND NOT ('{}' = ANY(l.ml_low_confidence_fields))",
+                kind.as_str()
+            ));
+            if let Some(city_id) = params.city_id {
+                qb.push(" AND l.city_id = ").push_bind(city_id);
+            }
+            qb.push(" GROUP BY l.")
+                .push(column)
+                .push(" ORDER BY count DESC, value");
+
+            qb.build_query_as()
+                .fetch_all(&db)
+                .await
+                .map_err(|e| anyhow::anyhow!("Browse {} query failed: {}", kind.as_str(), e))
+        })
+        .await
+        .map_err(|e| {
+            error!("Browse index fetch failed: {}", e);
+            AppError::Internal(format!("Failed to list {}s: {}", kind.as_str(), e))
+        })
+}
+
+/// The gallery narrowed to one value of `kind`.
+async fn browse_letterings(
+    state: &AppState,
+    kind: BrowseFacetKind,
+    value: String,
+    params: BrowseLetteringsQuery,
+) -> Result<PaginatedResponse, AppError> {
+    let value = value.trim().to_string();
+    if value.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "{} cannot be empty",
+            kind.as_str()
+        )));
+    }
+    let (script, style) = match kind {
+        BrowseFacetKind::Script => (Some(value), None),
+        BrowseFacetKind::Style => (None, Some(value)),
+    };
+    fetch_gallery_page(
+        state,
+        GalleryQuery {
+            limit: params.limit,
+            offset: params.offset,
+            city_id: params.city_id,
+            script,
+            style,
+            color: None,
+            tolerance: None,
+            sort_by: params.sort_by,
+            age_ack: params.age_ack,
+        },
+    )
+    .await
+}
+
+/// Scripts found among public letterings, with counts and a cover image.
+pub async fn list_scripts(
+    State(state): State<AppState>,
+    Query(params): Query<BrowseFacetsQuery>,
+) -> Result<Json<Vec<BrowseFacet>>, AppError> {
+    browse_facets(&state, BrowseFacetKind::Script, params)
+        .await
+        .map(Json)
+}
+
+/// Styles found among public letterings, with counts and a cover image.
+pub async fn list_styles(
+    State(state): State<AppState>,
+    Query(params): Query<BrowseFacetsQuery>,
+) -> Result<Json<Vec<BrowseFacet>>, AppError> {
+    browse_facets(&state, BrowseFacetKind::Style, params)
+        .await
+        .map(Json)
+}
+
+/// Paginated public letterings in one script.
+pub async fn get_script_letterings(
+    State(state): State<AppState>,
+    Path(script): Path<String>,
+    Query(params): Query<BrowseLetteringsQuery>,
+) -> Result<Json<PaginatedResponse>, AppError> {
+    browse_letterings(&state, BrowseFacetKind::Script, script, params)
+        .await
+        .map(Json)
+}
+
+/// Paginated public letterings in one style.
+pub async fn get_style_letterings(
+    State(state): State<AppState>,
+    Path(style): Path<String>,
+    Query(params): Query<BrowseLetteringsQuery>,
+) -> Result<Json<PaginatedResponse>, AppError> {
+    browse_letterings(&state, BrowseFacetKind::Style, style, params)
+        .await
+        .map(Json)
+}
+
+/// Query parameters for the random discovery endpoint.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct DiscoverQuery {
+    /// Letterings to return (1-50, default 20)
+    #[ts(type = "number | null")]
+    pub limit: Option<i64>,
+
+    /// `seen` token from the previous discover response; those letterings
+    /// are not served again (optional)
+    #[ts(optional)]
+    pub seen: Option<String>,
+
+    /// Include age-restricted letterings in regions that gate them (optional)
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+const DISCOVER_DEFAULT_LIMIT: i64 = 20;
+const DISCOVER_MAX_LIMIT: i64 = 50;
+
+/// Sampled rows fetched per requested lettering: the sample is drawn before
+/// status, region and seen filtering.
+const DISCOVER_OVERSAMPLE: i64 = 8;
+
+/// Fewest table pages a sample should touch, so one request isn't drawn from
+/// a couple of neighbouring pages (i.e. uploads from the same hour).
+const DISCOVER_MIN_PAGES: f64 = 32.0;
+
+/// Samples tried, each four times larger, before returning what was found.
+const DISCOVER_ATTEMPTS: usize = 3;
+
+/// Bits in a `seen` filter: about 400 ids at a 1% false-positive rate, after
+/// which clients see some items skipped rather than repeated.
+const SEEN_FILTER_BITS: usize = 4096;
+const SEEN_FILTER_HASHES: u64 = 7;
+
+/// Bloom filter of lettering ids a client has already been shown, carried
+/// by the client as URL-safe base64 so discovery needs no server state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeenFilter {
+    bits: Vec<u8>,
+}
+
+impl Default for SeenFilter {
+    fn default() -> Self {
+        Self {
+            bits: vec![0; SEEN_FILTER_BITS / 8],
+        }
+    }
+}
+
+impl SeenFilter {
+    pub fn decode(token: &str) -> Option<Self> {
+        let bits = URL_SAFE_NO_PAD.decode(token.trim()).ok()?;
+        (bits.len() == SEEN_FILTER_BITS / 8).then_some(Self { bits })
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(&self.bits)
+    }
+
+    /// Bit positions by double hashing one SHA-256 of the id.
+    fn positions(id: &Uuid) -> impl Iterator<Item = usize> + use<> {
+        let digest = Sha256::digest(id.as_bytes());
+        let mut h1 = [0u8; 8];
+        let mut h2 = [0u8; 8];
+        h1.copy_from_slice(&digest[..8]);
+        h2.copy_from_slice(&digest[8..16]);
+        let (h1, h2) = (u64::from_be_bytes(h1), u64::from_be_bytes(h2) | 1);
+        (0..SEEN_FILTER_HASHES)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % SEEN_FILTER_BITS as u64) as usize)
+    }
+
+    pub fn insert(&mut self, id: &Uuid) {
+        for bit in Self::positions(id) {
+            self.bits[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    pub fn contains(&self, id: &Uuid) -> bool {
+        Self::positions(id).all(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+    }
+}
+
+/// Share of `letterings`, in percent, to sample for `wanted` rows, from the
+/// planner's row and page estimates (no count over the table).
+fn discover_sample_percent(wanted: i64, estimated_rows: f64, pages: f64) -> f64 {
+    if estimated_rows <= 0.0 || pages <= 0.0 {
+        return 100.0;
+    }
+    let by_rows = wanted as f64 / estimated_rows;
+    let by_pages = DISCOVER_MIN_PAGES / pages;
+    (by_rows.max(by_pages) * 100.0).min(100.0)
+}
+
+/// Returns a random sample of approved, discoverable letterings the client
+/// hasn't been shown yet.
+///
+/// Rows are drawn with `TABLESAMPLE SYSTEM` rather than sorting the whole
+/// table by `random()`; only the sample is shuffled. When filtering leaves
+/// too few, a larger sample is tried. The response's `seen` token adds the
+/// served ids to the one passed in.
+///
+/// # Errors
+/// Returns `AppError::BadRequest` for a malformed `seen` token.
+pub async fn discover_letterings(
+    State(state): State<AppState>,
+    Query(params): Query<DiscoverQuery>,
+) -> Result<Json<DiscoverResponse>, AppError> {
+    let limit = params
+        .limit
+        .unwrap_or(DISCOVER_DEFAULT_LIMIT)
+        .clamp(1, DISCOVER_MAX_LIMIT);
+    let mut seen = match params.seen.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(token) => SeenFilter::decode(token).ok_or_else(|| {
+            AppError::BadRequest(
+                "seen must be the token returned by a previous discover response".to_string(),
+            )
+        })?,
+        None => SeenFilter::default(),
+    };
+
+    let (estimated_rows, pages): (f32, i32) = sqlx::query_as(
+        "SELECT reltuples, relpages FROM pg_class WHERE oid = 'letterings'::regclass",
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let wanted = limit * DISCOVER_OVERSAMPLE;
+    let mut percent = discover_sample_percent(wanted, f64::from(estimated_rows), f64::from(pages));
+    let mut letterings: Vec<Lettering> = Vec::with_capacity(limit as usize);
+    for _ in 0..DISCOVER_ATTEMPTS {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT l.id, l.city_id, l.contributor_tag, l.image_url,
+                    l.thumbnail_small, l.thumbnail_medium, l.thumbnail_large,
+                    l.pin_code, l.status, l.created_at, l.updated_at,
+                    l.detected_text, l.description, l.image_hash,
+                    l.ml_style, l.ml_script, l.ml_confidence, l.ml_color_palette,
+                    l.cultural_context, l.report_count, l.report_reasons,
+                    l.likes_count, l.comments_count, l.uploaded_by_ip,
+                    ST_AsText(l.location) AS location
+             FROM letterings l TABLESAMPLE SYSTEM (",
+        );
+        qb.push_bind(percent as f32)
+            .push(") ")
+            .push(PUBLIC_LETTERING_JOINS)
+            .push(" WHERE ");
+        push_public_filter(&mut qb, params.age_ack.unwrap_or(false));
+        qb.push(" ORDER BY random() LIMIT ").push_bind(wanted);
+
+        let rows: Vec<LetteringRow> = qb
+            .build_query_as()
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(format!("Discover query failed: {}", e)))?;
+
+        for row in rows {
+            if letterings.len() as i64 >= limit {
+                break;
+            }
+            // Inserting as we go also drops repeats between attempts.
+            if seen.contains(&row.id) {
+                continue;
+            }
+            seen.insert(&row.id);
+            letterings.push(row.into());
+        }
+        if letterings.len() as i64 >= limit || percent >= 100.0 {
+            break;
+        }
+        percent = (percent * 4.0).min(100.0);
+    }
+
+    debug!(
+        "Discover returned {} letterings from a {:.3}% sample",
+        letterings.len(),
+        percent
+    );
+
+    Ok(Json(DiscoverResponse {
+        letterings,
+        seen: seen.encode(),
+    }))
 }
 
 /// Database row representation for lettering entities from gallery queries.
@@ -306,7 +802,7 @@ impl From<LetteringRow> for Lettering {
                 let lat: f64 = parts.next()?.parse().ok()?;
 
                 // Validate coordinate bounds
-                if lng >= -180.0 && lng <= 180.0 && lat >= -90.0 && lat <= 90.0 {
+                if (-180.0..=180.0).contains(&lng) && (-90.0..=90.0).contains(&lat) {
                     Some(vec![lng, lat])
                 } else {
                     warn!("Invalid coordinates parsed: lng={}, lat={}", lng, lat);
@@ -388,3 +884,44 @@ impl From<LetteringRow> for Lettering {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seen_filter_round_trips_and_remembers_ids() {
+        let shown: Vec<Uuid> = (0..50).map(|_| Uuid::now_v7()).collect();
+        let mut seen = SeenFilter::default();
+        for id in &shown {
+            seen.insert(id);
+        }
+
+        let decoded = SeenFilter::decode(&seen.encode()).unwrap();
+        assert_eq!(decoded, seen);
+        assert!(shown.iter().all(|id| decoded.contains(id)));
+
+        let false_positives = (0..1_000)
+            .filter(|_| decoded.contains(&Uuid::now_v7()))
+            .count();
+        assert!(false_positives < 10);
+    }
+
+    #[test]
+    fn rejects_malformed_seen_tokens() {
+        assert_eq!(SeenFilter::decode("not base64!"), None);
+        assert_eq!(SeenFilter::decode(&URL_SAFE_NO_PAD.encode([0u8; 16])), None);
+    }
+
+    #[test]
+    fn sample_covers_enough_rows_and_pages() {
+        // Unanalysed or tiny tables are read whole.
+        assert_eq!(discover_sample_percent(160, -1.0, 0.0), 100.0);
+        assert_eq!(discover_sample_percent(160, 100.0, 2.0), 100.0);
+        // Large tables: whichever of rows or pages needs more.
+        let by_pages = discover_sample_percent(160, 1_000_000.0, 10_000.0);
+        assert!((by_pages - 0.32).abs() < 1e-9);
+        let by_rows = discover_sample_percent(16_000, 1_000_000.0, 10_000.0);
+        assert!((by_rows - 1.6).abs() < 1e-9);
+    }
+}
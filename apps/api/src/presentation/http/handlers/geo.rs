@@ -1,13 +1,24 @@
-use crate::presentation::http::{errors::AppError, state::AppState};
+use crate::{
+    infrastructure::{
+        cache::invalidation::MAP_PREFIX,
+        repositories::region_policy::{
+            PUBLIC_LETTERING_JOINS, public_filter_sql, push_public_filter,
+        },
+    },
+    presentation::http::{errors::AppError, locale::request_locales, state::AppState},
+};
 use axum::{
     Json,
     extract::{Query, State},
+    http::HeaderMap,
 };
 use serde::{Deserialize, Serialize};
-use sqlx::{Postgres, QueryBuilder};
+use sqlx::{FromRow, Postgres, QueryBuilder};
+use ts_rs::TS;
 use uuid::Uuid;
 
-#[derive(Serialize)]
+#[derive(Serialize, TS)]
+#[ts(export)]
 pub struct Marker {
     pub id: uuid::Uuid,
     pub lat: f64,
@@ -15,34 +26,187 @@ pub struct Marker {
     pub thumbnail: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, TS)]
+#[ts(export)]
 pub struct CoveragePoint {
     pub pin_code: String,
     pub city_id: uuid::Uuid,
+    /// In the request's `Accept-Language`, falling back to the canonical name.
     pub city_name: String,
     pub lat: f64,
     pub lng: f64,
+    #[ts(type = "number")]
     pub count: i64,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, TS)]
+#[ts(export)]
 pub struct NearbyQuery {
     pub lat: f64,
     pub lng: f64,
     pub radius_m: f64,
+    /// Set once the client has shown its age gate; includes age-restricted
+    /// letterings in regions that gate them.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+/// Largest radius `/letterings/nearby` searches, in metres.
+const MAX_NEARBY_RADIUS_M: f64 = 50_000.0;
+const DEFAULT_NEARBY_RADIUS_M: f64 = 1_000.0;
+
+#[derive(Deserialize, TS)]
+#[ts(export)]
+pub struct NearbyLetteringsQuery {
+    pub lat: f64,
+    pub lng: f64,
+    /// Search radius in metres.
+    pub radius: Option<f64>,
+    #[ts(type = "number | null")]
+    pub limit: Option<i64>,
+    /// Set once the client has shown its age gate; includes age-restricted
+    /// letterings in regions that gate them.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+#[derive(Serialize, FromRow, TS)]
+#[ts(export)]
+pub struct NearbyLettering {
+    pub id: Uuid,
+    pub lat: f64,
+    pub lng: f64,
+    pub thumbnail: String,
+    pub detected_text: Option<String>,
+    /// In the request's `Accept-Language`, falling back to the canonical name.
+    pub city_name: String,
+    pub distance_m: f64,
+}
+
+/// From this zoom level up `/letterings/map` returns individual points.
+const MAP_POINTS_MIN_ZOOM: u8 = 15;
+const MAP_MAX_FEATURES: i64 = 2000;
+
+/// How long a world view of the map is served from cache; changes to any
+/// lettering drop it sooner. Other viewports are too varied to cache.
+const MAP_CACHE_TTL_SECONDS: u64 = 300;
+
+/// World views up to this zoom are kept warm by the cache warmer.
+const WARM_MAP_MAX_ZOOM: u8 = 3;
+
+#[derive(Deserialize, TS)]
+#[ts(export)]
+pub struct MapQuery {
+    /// `min_lng,min_lat,max_lng,max_lat`
+    pub bbox: String,
+    pub zoom: u8,
+    /// Set once the client has shown its age gate; includes age-restricted
+    /// letterings in regions that gate them.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
 }
 
-#[derive(Deserialize, Default)]
+/// A viewport, in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BoundingBox {
+    min_lng: f64,
+    min_lat: f64,
+    max_lng: f64,
+    max_lat: f64,
+}
+
+impl BoundingBox {
+    fn parse(value: &str) -> Result<Self, String> {
+        let parts: Vec<f64> = value
+            .split(',')
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| "bbox must be four numbers".to_string())?;
+        let [min_lng, min_lat, max_lng, max_lat] = parts[..] else {
+            return Err("bbox must be min_lng,min_lat,max_lng,max_lat".to_string());
+        };
+        if !(-180.0..=180.0).contains(&min_lng)
+            || !(-180.0..=180.0).contains(&max_lng)
+            || !(-90.0..=90.0).contains(&min_lat)
+            || !(-90.0..=90.0).contains(&max_lat)
+        {
+            return Err("bbox is outside [-180, 180] x [-90, 90]".to_string());
+        }
+        if min_lng >= max_lng || min_lat >= max_lat {
+            return Err("bbox minimums must be below its maximums".to_string());
+        }
+        Ok(Self {
+            min_lng,
+            min_lat,
+            max_lng,
+            max_lat,
+        })
+    }
+
+    /// A geography box spanning more than a hemisphere wraps the wrong way
+    /// round, so such viewports are treated as the whole world.
+    fn is_world(&self) -> bool {
+        self.max_lng - self.min_lng >= 180.0
+    }
+}
+
+/// Distance, in degrees, within which points merge into one cluster: about
+/// a quarter of a 256px tile at `zoom`.
+fn cluster_radius_deg(zoom: u8) -> f64 {
+    90.0 / 2f64.powi(zoom.min(MAP_POINTS_MIN_ZOOM) as i32)
+}
+
+/// A map feature: a cluster of letterings, or a single one.
+#[derive(Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MapFeature {
+    Cluster {
+        lat: f64,
+        lng: f64,
+        #[ts(type = "number")]
+        count: i64,
+    },
+    Point {
+        id: Uuid,
+        lat: f64,
+        lng: f64,
+        thumbnail: String,
+    },
+}
+
+#[derive(Serialize, TS)]
+#[ts(export)]
+pub struct MapFeaturesResponse {
+    pub zoom: u8,
+    pub clustered: bool,
+    pub features: Vec<MapFeature>,
+}
+
+#[derive(Deserialize, Default, TS)]
+#[ts(export)]
 pub struct MarkersQuery {
     pub city_id: Option<Uuid>,
+    #[ts(type = "number | null")]
     pub limit: Option<i64>,
+    /// Set once the client has shown its age gate; includes age-restricted
+    /// letterings in regions that gate them.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
 }
 
-#[derive(Deserialize, Default)]
+#[derive(Deserialize, Default, TS)]
+#[ts(export)]
 pub struct CoverageQuery {
     pub city_id: Option<Uuid>,
+    #[ts(type = "number | null")]
     pub min_count: Option<i64>,
+    #[ts(type = "number | null")]
     pub limit: Option<i64>,
+    /// Set once the client has shown its age gate; includes age-restricted
+    /// letterings in regions that gate them.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
 }
 
 pub async fn get_all_markers(
@@ -51,12 +215,10 @@ pub async fn get_all_markers(
 ) -> Result<Json<Vec<Marker>>, AppError> {
     let mut qb = QueryBuilder::<Postgres>::new(
         "SELECT l.id, COALESCE(l.thumbnail_small, '') as thumbnail_small, ST_Y(l.location::geometry) as lat, ST_X(l.location::geometry) as lng
-         FROM letterings l
-         JOIN cities c ON c.id = l.city_id
-         LEFT JOIN region_policies rp ON rp.country_code = c.country_code
-         WHERE l.status = 'APPROVED'
-           AND COALESCE(rp.discoverability_enabled, true)",
+         FROM letterings l ",
     );
+    qb.push(PUBLIC_LETTERING_JOINS).push(" WHERE ");
+    push_public_filter(&mut qb, params.age_ack.unwrap_or(false));
 
     if let Some(city_id) = params.city_id {
         qb.push(" AND l.city_id = ");
@@ -88,18 +250,19 @@ pub async fn get_nearby_markers(
     State(state): State<AppState>,
     Query(q): Query<NearbyQuery>,
 ) -> Result<Json<Vec<Marker>>, AppError> {
-    let rows: Vec<(Uuid, String, f64, f64)> = sqlx::query_as(
+    let rows: Vec<(Uuid, String, f64, f64)> = sqlx::query_as(&format!(
         r#"SELECT l.id, COALESCE(l.thumbnail_small, '') as thumbnail_small, ST_Y(l.location::geometry) as lat, ST_X(l.location::geometry) as lng
            FROM letterings l
-           JOIN cities c ON c.id = l.city_id
-           LEFT JOIN region_policies rp ON rp.country_code = c.country_code
-           WHERE l.status = 'APPROVED'
-             AND COALESCE(rp.discoverability_enabled, true)
+           {joins}
+           WHERE {public}
              AND ST_DWithin(l.location, ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography, $3)"#,
-    )
+        joins = PUBLIC_LETTERING_JOINS,
+        public = public_filter_sql(4),
+    ))
     .bind(q.lng)
     .bind(q.lat)
     .bind(q.radius_m)
+    .bind(q.age_ack.unwrap_or(false))
     .fetch_all(&state.db)
     .await
     .map_err(|e| AppError::Internal(e.to_string()))?;
@@ -116,25 +279,196 @@ pub async fn get_nearby_markers(
     ))
 }
 
+/// Approved letterings within `radius` metres of a point, nearest first. The
+/// `ST_DWithin` filter is answered from the GiST index on `location`.
+pub async fn get_nearby_letterings(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(q): Query<NearbyLetteringsQuery>,
+) -> Result<Json<Vec<NearbyLettering>>, AppError> {
+    if !(-90.0..=90.0).contains(&q.lat) || !(-180.0..=180.0).contains(&q.lng) {
+        return Err(AppError::BadRequest(
+            "lat must be within [-90, 90] and lng within [-180, 180]".to_string(),
+        ));
+    }
+    let radius = q.radius.unwrap_or(DEFAULT_NEARBY_RADIUS_M);
+    if !radius.is_finite() || radius <= 0.0 {
+        return Err(AppError::BadRequest(
+            "radius must be a positive number of metres".to_string(),
+        ));
+    }
+    let radius = radius.min(MAX_NEARBY_RADIUS_M);
+
+    let sql = format!(
+        r#"SELECT l.id, COALESCE(l.thumbnail_small, '') as thumbnail,
+                  ST_Y(l.location::geometry) as lat, ST_X(l.location::geometry) as lng,
+                  l.detected_text, localized_city_name(c.id, c.name, $6) as city_name,
+                  ST_Distance(l.location, p.point) as distance_m
+           FROM letterings l
+           {joins}
+           CROSS JOIN (SELECT ST_SetSRID(ST_MakePoint($1, $2), 4326)::geography AS point) p
+           WHERE {public}
+             AND ST_DWithin(l.location, p.point, $3)
+           ORDER BY distance_m ASC, l.id
+           LIMIT $4"#,
+        joins = PUBLIC_LETTERING_JOINS,
+        public = public_filter_sql(5),
+    );
+    let letterings: Vec<NearbyLettering> = sqlx::query_as(&sql)
+        .bind(q.lng)
+        .bind(q.lat)
+        .bind(radius)
+        .bind(q.limit.unwrap_or(50).clamp(1, 200))
+        .bind(q.age_ack.unwrap_or(false))
+        .bind(request_locales(&headers))
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(letterings))
+}
+
+/// Approved letterings in a viewport. Below `MAP_POINTS_MIN_ZOOM` they are
+/// grouped with `ST_ClusterDBSCAN` into count + centroid clusters, so a zoomed
+/// out map gets a few hundred features instead of every lettering; a cluster
+/// of one comes back as a point. World views are cached.
+pub async fn get_map_features(
+    State(state): State<AppState>,
+    Query(q): Query<MapQuery>,
+) -> Result<Json<MapFeaturesResponse>, AppError> {
+    let bbox = BoundingBox::parse(&q.bbox).map_err(AppError::BadRequest)?;
+    let age_ack = q.age_ack.unwrap_or(false);
+    let features = if bbox.is_world() {
+        world_map_features(&state, q.zoom, age_ack).await?
+    } else {
+        map_features(&state.db, bbox, q.zoom, age_ack)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+    };
+
+    Ok(Json(MapFeaturesResponse {
+        zoom: q.zoom,
+        clustered: q.zoom < MAP_POINTS_MIN_ZOOM,
+        features,
+    }))
+}
+
+/// Features of the whole world at `zoom`, from cache when there.
+async fn world_map_features(
+    state: &AppState,
+    zoom: u8,
+    age_ack: bool,
+) -> Result<Vec<MapFeature>, AppError> {
+    let key = format!("{}world:{}:{}", MAP_PREFIX, zoom, age_ack);
+    let db = state.db.clone();
+    let world = BoundingBox {
+        min_lng: -180.0,
+        min_lat: -90.0,
+        max_lng: 180.0,
+        max_lat: 90.0,
+    };
+    state
+        .cache
+        .get_or_fetch(&key, MAP_CACHE_TTL_SECONDS, || async move {
+            Ok(map_features(&db, world, zoom, age_ack).await?)
+        })
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Fills the cached world views of the zoomed out map.
+pub async fn warm_world_map(state: AppState) -> Result<(), AppError> {
+    for zoom in 0..=WARM_MAP_MAX_ZOOM {
+        world_map_features(&state, zoom, false).await?;
+    }
+    Ok(())
+}
+
+async fn map_features(
+    db: &sqlx::PgPool,
+    bbox: BoundingBox,
+    zoom: u8,
+    age_ack: bool,
+) -> Result<Vec<MapFeature>, sqlx::Error> {
+    let rows: Vec<(i64, f64, f64, Uuid, String)> = sqlx::query_as(&format!(
+        r#"WITH pts AS (
+               SELECT l.id, COALESCE(l.thumbnail_small, '') AS thumbnail,
+                      l.location::geometry AS geom
+               FROM letterings l
+               {joins}
+               WHERE {public}
+                 AND l.location IS NOT NULL
+                 AND ($5 OR l.location && ST_MakeEnvelope($1, $2, $3, $4, 4326)::geography)
+           ),
+           grouped AS (
+               SELECT *,
+                      CASE WHEN $6 THEN ST_ClusterDBSCAN(geom, eps => $7, minpoints => 1) OVER ()
+                           ELSE row_number() OVER ()::int END AS cluster_id
+               FROM pts
+           )
+           SELECT COUNT(*)::bigint AS count,
+                  ST_Y(ST_Centroid(ST_Collect(geom))) AS lat,
+                  ST_X(ST_Centroid(ST_Collect(geom))) AS lng,
+                  (array_agg(id))[1] AS id,
+                  (array_agg(thumbnail))[1] AS thumbnail
+           FROM grouped
+           GROUP BY cluster_id
+           ORDER BY count DESC, id
+           LIMIT $8"#,
+        joins = PUBLIC_LETTERING_JOINS,
+        public = public_filter_sql(9),
+    ))
+    .bind(bbox.min_lng)
+    .bind(bbox.min_lat)
+    .bind(bbox.max_lng)
+    .bind(bbox.max_lat)
+    .bind(bbox.is_world())
+    .bind(zoom < MAP_POINTS_MIN_ZOOM)
+    .bind(cluster_radius_deg(zoom))
+    .bind(MAP_MAX_FEATURES)
+    .bind(age_ack)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(count, lat, lng, id, thumbnail)| {
+            if count == 1 {
+                MapFeature::Point {
+                    id,
+                    lat,
+                    lng,
+                    thumbnail,
+                }
+            } else {
+                MapFeature::Cluster { lat, lng, count }
+            }
+        })
+        .collect())
+}
+
 pub async fn get_coverage(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Query(params): Query<CoverageQuery>,
 ) -> Result<Json<Vec<CoveragePoint>>, AppError> {
     let mut qb = QueryBuilder::<Postgres>::new(
-        "SELECT l.pin_code, l.city_id, c.name, AVG(ST_Y(l.location::geometry))::double precision as lat, AVG(ST_X(l.location::geometry))::double precision as lng, COUNT(*)::bigint as count
-         FROM letterings l
-         JOIN cities c ON c.id = l.city_id
-         LEFT JOIN region_policies rp ON rp.country_code = c.country_code
-         WHERE l.status = 'APPROVED'
-           AND COALESCE(rp.discoverability_enabled, true)",
+        "SELECT l.pin_code, l.city_id, localized_city_name(c.id, c.name, ",
+    );
+    qb.push_bind(request_locales(&headers));
+    qb.push(
+        "), AVG(ST_Y(l.location::geometry))::double precision as lat, AVG(ST_X(l.location::geometry))::double precision as lng, COUNT(*)::bigint as count
+         FROM letterings l ",
     );
+    qb.push(PUBLIC_LETTERING_JOINS).push(" WHERE ");
+    push_public_filter(&mut qb, params.age_ack.unwrap_or(false));
 
     if let Some(city_id) = params.city_id {
         qb.push(" AND l.city_id = ");
         qb.push_bind(city_id);
     }
 
-    qb.push(" GROUP BY l.pin_code, l.city_id, c.name");
+    qb.push(" GROUP BY l.pin_code, l.city_id, c.id, c.name");
 
     if let Some(min_count) = params.min_count {
         qb.push(" HAVING COUNT(*) >= ");
@@ -152,14 +486,52 @@ pub async fn get_coverage(
 
     Ok(Json(
         rows.into_iter()
-            .map(|(pin_code, city_id, city_name, lat, lng, count)| CoveragePoint {
-                pin_code,
-                city_id,
-                city_name,
-                lat,
-                lng,
-                count,
-            })
+            .map(
+                |(pin_code, city_id, city_name, lat, lng, count)| CoveragePoint {
+                    pin_code,
+                    city_id,
+                    city_name,
+                    lat,
+                    lng,
+                    count,
+                },
+            )
             .collect(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_bbox() {
+        let bbox = BoundingBox::parse("77.5, 12.9, 77.7, 13.1").unwrap();
+        assert_eq!(
+            bbox,
+            BoundingBox {
+                min_lng: 77.5,
+                min_lat: 12.9,
+                max_lng: 77.7,
+                max_lat: 13.1,
+            }
+        );
+        assert!(!bbox.is_world());
+        assert!(BoundingBox::parse("-180,-90,180,90").unwrap().is_world());
+
+        assert!(BoundingBox::parse("77.5,12.9,77.7").is_err());
+        assert!(BoundingBox::parse("77.7,12.9,77.5,13.1").is_err());
+        assert!(BoundingBox::parse("77.5,12.9,77.7,95").is_err());
+        assert!(BoundingBox::parse("a,b,c,d").is_err());
+    }
+
+    #[test]
+    fn cluster_radius_halves_per_zoom_level() {
+        assert_eq!(cluster_radius_deg(0), 90.0);
+        assert_eq!(cluster_radius_deg(3), 11.25);
+        assert_eq!(
+            cluster_radius_deg(20),
+            cluster_radius_deg(MAP_POINTS_MIN_ZOOM)
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Redirect, Response},
+};
+use serde::Deserialize;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::{
+        imaging::resize::{VariantFormat, normalize_dimension, render_variant},
+        storage::{
+            access::is_publicly_servable,
+            visibility::{legacy_original_key, original_key},
+        },
+    },
+    presentation::http::{errors::AppError, handlers::letterings::CachedAsset, state::AppState},
+};
+
+const VARIANT_CACHE_TTL_SECONDS: u64 = 30 * 86_400;
+const SOURCE_FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const SOURCE_URL_TTL: Duration = Duration::from_secs(300);
+const MAX_SOURCE_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct ImageVariantQuery {
+    /// Maximum width in pixels, rounded up to a multiple of 32 and capped at 2048.
+    pub w: Option<u32>,
+    /// Maximum height in pixels, rounded the same way as `w`.
+    pub h: Option<u32>,
+    pub format: Option<VariantFormat>,
+}
+
+fn variant_key(id: Uuid, width: Option<u32>, height: Option<u32>, format: VariantFormat) -> String {
+    let edge = |d: Option<u32>| {
+        d.map(|d| d.to_string())
+            .unwrap_or_else(|| "auto".to_string())
+    };
+    format!(
+        "variants/{}/{}x{}.{}",
+        id,
+        edge(width),
+        edge(height),
+        format.extension()
+    )
+}
+
+pub(crate) fn variant_cache_key(storage_key: &str) -> String {
+    format!("image_variant:{}", storage_key)
+}
+
+/// Remove every stored variant of a lettering along with its cached URL.
+pub(crate) async fn discard_image_variants(state: &AppState, id: Uuid) {
+    let keys = match sqlx::query_scalar::<_, String>(
+        "DELETE FROM image_variants WHERE lettering_id = $1 RETURNING storage_key",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(keys) => keys,
+        Err(e) => {
+            tracing::warn!(lettering_id = %id, "Failed to list image variants: {}", e);
+            return;
+        }
+    };
+    for key in keys {
+        let _ = state.storage.delete(&key).await;
+        let _ = state.cache.delete(&variant_cache_key(&key)).await;
+    }
+}
+
+/// Download the stored original, falling back to where originals used to be
+/// kept and then to the archived display image for letterings uploaded
+/// before originals were kept.
+async fn fetch_source(state: &AppState, id: Uuid, image_url: &str) -> Result<Vec<u8>, AppError> {
+    let client = reqwest::Client::builder()
+        .timeout(SOURCE_FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut sources = Vec::with_capacity(3);
+    for key in [original_key(id), legacy_original_key(id)] {
+        match state.storage.signed_url(&key, SOURCE_URL_TTL).await {
+            Ok(url) => sources.push(url),
+            Err(e) => tracing::warn!(lettering_id = %id, "Failed to sign original URL: {}", e),
+        }
+    }
+    match state.storage.key_from_url(image_url) {
+        Some(key) => match state.storage.signed_url(&key, SOURCE_URL_TTL).await {
+            Ok(url) => sources.push(url),
+            Err(e) => tracing::warn!(lettering_id = %id, "Failed to sign image URL: {}", e),
+        },
+        None => sources.push(image_url.to_string()),
+    }
+
+    for url in sources {
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to fetch image: {}", e)))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(AppError::ExternalService(format!(
+                "Image fetch returned HTTP {}",
+                response.status()
+            )));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to read image: {}", e)))?;
+        if bytes.len() > MAX_SOURCE_BYTES {
+            return Err(AppError::ExternalService("Image too large".to_string()));
+        }
+        return Ok(bytes.to_vec());
+    }
+
+    Err(AppError::NotFound("Image not found in storage".to_string()))
+}
+
+/// Resized rendition of an approved lettering, e.g. `/images/{id}?w=640&format=webp`.
+///
+/// The image is scaled to fit `w` x `h` without upscaling. Variants are
+/// rendered from the stored original on first request, kept in R2 under
+/// `variants/{id}/`, and later requests redirect to the stored copy with a
+/// `?v=` derived from the original's hash.
+pub async fn get_image_variant(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ImageVariantQuery>,
+) -> Result<Response, AppError> {
+    let format = params.format.unwrap_or(VariantFormat::Jpeg);
+    let width = normalize_dimension(params.w);
+    let height = normalize_dimension(params.h);
+
+    let (image_url, image_hash) = sqlx::query_as::<_, (String, String, Option<String>)>(
+        "SELECT status, image_url, image_hash FROM letterings
+         WHERE id = $1 AND city_discoverable(city_id)",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .filter(|(status, _, _)| is_publicly_servable(status))
+    .map(|(_, image_url, image_hash)| (image_url, image_hash))
+    .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+
+    let version = image_hash
+        .as_deref()
+        .unwrap_or(image_url.as_str())
+        .chars()
+        .take(16)
+        .collect::<String>();
+    let storage_key = variant_key(id, width, height, format);
+    let cache_key = variant_cache_key(&storage_key);
+    if let Ok(Some(cached)) = state.cache.get::<CachedAsset>(&cache_key).await
+        && cached.version == version
+    {
+        return Ok(Redirect::temporary(&format!("{}?v={}", cached.url, version)).into_response());
+    }
+
+    let source = fetch_source(&state, id, &image_url).await?;
+    let body = tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory(&source)?;
+        render_variant(&image, width, height, format)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .map_err(|e| AppError::Internal(format!("Failed to resize image: {}", e)))?;
+
+    match state
+        .storage
+        .upload(&storage_key, body.clone(), format.content_type())
+        .await
+    {
+        Ok(url) => {
+            if let Err(e) = sqlx::query(
+                "INSERT INTO image_variants (lettering_id, storage_key) VALUES ($1, $2)
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(id)
+            .bind(&storage_key)
+            .execute(&state.db)
+            .await
+            {
+                tracing::warn!(lettering_id = %id, "Failed to record image variant: {}", e);
+            }
+            let cached = CachedAsset { version, url };
+            if let Err(e) = state
+                .cache
+                .set(&cache_key, &cached, VARIANT_CACHE_TTL_SECONDS)
+                .await
+            {
+                tracing::warn!(lettering_id = %id, "Failed to cache image variant URL: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!(lettering_id = %id, "Failed to store image variant: {}", e),
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type()),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        body,
+    )
+        .into_response())
+}
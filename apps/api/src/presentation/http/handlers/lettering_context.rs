@@ -0,0 +1,228 @@
+//! Everything the lettering detail screen shows around a lettering, in one
+//! response: letterings nearby, by the same contributor and in the same
+//! style, and the city it is in.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::HeaderMap,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::{
+        cache::invalidation::lettering_context_prefix,
+        repositories::region_policy::{PUBLIC_LETTERING_JOINS, public_filter_sql},
+    },
+    presentation::http::{
+        errors::AppError, handlers::letterings::AgeAckQuery, locale::request_locales,
+        state::AppState,
+    },
+};
+
+const CONTEXT_CACHE_TTL: u64 = 120;
+
+/// Letterings listed per section.
+const SECTION_LIMIT: i64 = 8;
+
+/// How far away a lettering may be to count as nearby.
+const NEARBY_RADIUS_M: f64 = 2_000.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct ContextLettering {
+    pub id: Uuid,
+    pub thumbnail: String,
+    pub detected_text: Option<String>,
+    pub contributor_tag: String,
+    pub ml_style: Option<String>,
+    /// Metres from the lettering; only set in `nearby`.
+    pub distance_m: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct ContextCity {
+    pub id: Uuid,
+    pub name: String,
+    /// Name in the request's `Accept-Language`, falling back to `name`.
+    pub display_name: String,
+    pub country_code: String,
+    pub country_name: String,
+    #[ts(type = "number")]
+    pub lettering_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LetteringContext {
+    /// Nearest first.
+    pub nearby: Vec<ContextLettering>,
+    /// Newest first.
+    pub same_contributor: Vec<ContextLettering>,
+    /// Newest first; empty until the lettering's style is detected.
+    pub same_style: Vec<ContextLettering>,
+    pub city: ContextCity,
+}
+
+#[derive(Debug, FromRow)]
+struct ContextSource {
+    contributor_tag: String,
+    ml_style: Option<String>,
+    city_id: Uuid,
+}
+
+/// Lists publicly listed letterings other than `$1`, with the age gate at
+/// `$2`, matching `condition` (which may use `$3`) and ordered by `order`.
+/// `distance_m` is measured from lettering `$1`.
+fn section_sql(condition: &str, order: &str) -> String {
+    format!(
+        r#"SELECT l.id, COALESCE(l.thumbnail_small, '') AS thumbnail,
+                  l.detected_text, l.contributor_tag, l.ml_style,
+                  {distance} AS distance_m
+           FROM letterings l
+           {joins}
+           CROSS JOIN (SELECT location FROM letterings WHERE id = $1) src
+           WHERE l.id <> $1 AND {public} AND {condition}
+           ORDER BY {order}, l.id
+           LIMIT {limit}"#,
+        distance = if order.starts_with("distance_m") {
+            "ST_Distance(l.location, src.location)"
+        } else {
+            "NULL::float8"
+        },
+        joins = PUBLIC_LETTERING_JOINS,
+        public = public_filter_sql(2),
+        limit = SECTION_LIMIT,
+    )
+}
+
+async fn section(
+    db: &PgPool,
+    id: Uuid,
+    age_ack: bool,
+    condition: &str,
+    order: &str,
+    arg: Option<&str>,
+) -> Result<Vec<ContextLettering>, sqlx::Error> {
+    let sql = section_sql(condition, order);
+    let mut query = sqlx::query_as::<_, ContextLettering>(&sql)
+        .bind(id)
+        .bind(age_ack);
+    if let Some(arg) = arg {
+        query = query.bind(arg);
+    }
+    query.fetch_all(db).await
+}
+
+/// Nearby, same-contributor and same-style letterings plus city details for
+/// a publicly listed lettering, cached briefly per age gate and language.
+pub async fn get_lettering_context(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(gate): Query<AgeAckQuery>,
+    headers: HeaderMap,
+) -> Result<Json<LetteringContext>, AppError> {
+    let age_ack = gate.age_ack.unwrap_or(false);
+    let locales = request_locales(&headers);
+
+    let source = sqlx::query_as::<_, ContextSource>(&format!(
+        "SELECT l.contributor_tag, l.ml_style, l.city_id
+         FROM letterings l
+         {}
+         WHERE l.id = $1 AND {}",
+        PUBLIC_LETTERING_JOINS,
+        public_filter_sql(2),
+    ))
+    .bind(id)
+    .bind(age_ack)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+
+    let cache_key = format!(
+        "{}{}:{}",
+        lettering_context_prefix(id),
+        age_ack,
+        locales.join(",")
+    );
+    let db = state.db.clone();
+    let context = state
+        .cache
+        .get_or_fetch(&cache_key, CONTEXT_CACHE_TTL, || async move {
+            let nearby = section(
+                &db,
+                id,
+                age_ack,
+                &format!("ST_DWithin(l.location, src.location, {})", NEARBY_RADIUS_M),
+                "distance_m ASC",
+                None,
+            )
+            .await?;
+            let same_contributor = section(
+                &db,
+                id,
+                age_ack,
+                "l.contributor_tag = $3",
+                "l.created_at DESC",
+                Some(&source.contributor_tag),
+            )
+            .await?;
+            let same_style = match source.ml_style.as_deref() {
+                Some(style) => {
+                    section(
+                        &db,
+                        id,
+                        age_ack,
+                        "l.ml_style = $3",
+                        "l.created_at DESC",
+                        Some(style),
+                    )
+                    .await?
+                }
+                None => Vec::new(),
+            };
+            let city = sqlx::query_as::<_, ContextCity>(
+                "SELECT c.id, c.name, localized_city_name(c.id, c.name, $2) AS display_name,
+                        c.country_code, localized_country_name(c.country_code, $2) AS country_name,
+                        (SELECT COUNT(*) FROM letterings
+                         WHERE city_id = c.id AND status = 'APPROVED')::bigint AS lettering_count
+                 FROM cities c WHERE c.id = $1",
+            )
+            .bind(source.city_id)
+            .bind(&locales)
+            .fetch_one(&db)
+            .await?;
+
+            Ok(LetteringContext {
+                nearby,
+                same_contributor,
+                same_style,
+                city,
+            })
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to load lettering context: {}", e)))?;
+
+    Ok(Json(context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_nearby_measures_distance() {
+        let nearby = section_sql("true", "distance_m ASC");
+        assert!(nearby.contains("ST_Distance(l.location, src.location) AS distance_m"));
+
+        let newest = section_sql("l.ml_style = $3", "l.created_at DESC");
+        assert!(newest.contains("NULL::float8 AS distance_m"));
+        assert!(newest.contains("l.id <> $1"));
+        assert!(newest.contains(&public_filter_sql(2)));
+    }
+}
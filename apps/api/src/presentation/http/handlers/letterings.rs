@@ -1,31 +1,89 @@
 use axum::{
     Json,
-    extract::{Path, Query, State},
-    http::{HeaderMap, StatusCode},
-    response::{IntoResponse, Redirect},
+    extract::{FromRequestParts, Path, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
 };
-use serde::Deserialize;
-use sqlx::Row;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, Row};
+use std::{collections::HashSet, net::IpAddr, time::Duration};
+use ts_rs::TS;
 use uuid::Uuid;
 
 use crate::{
-    domain::lettering::repository::LetteringRepository,
+    domain::lettering::{entity::Lettering, repository::LetteringRepository},
+    infrastructure::{
+        cache::invalidation::{LetteringChange, contributor_prefix, lettering_key},
+        geocoding::ip_geolocation::GeoEvent,
+        imaging::{
+            qr_code::{self, QrCode},
+            share_card::render_share_card,
+        },
+        monitoring::AlertSeverity,
+        repositories::region_policy::is_publicly_viewable,
+        storage::{
+            access::{is_publicly_servable, viewable_url},
+            content_addressed,
+            visibility::{legacy_original_key, original_key},
+        },
+    },
     presentation::http::{
-        errors::AppError, middleware::user::decode_optional_user_claims, state::AppState,
+        errors::AppError,
+        handlers::{
+            admin::{lettering_cdn_urls, purge_lettering_from_cdn},
+            analytics::has_opted_out,
+            credits,
+            images::{discard_image_variants, variant_cache_key},
+            short_links::{lettering_short_url, normalize_channel, public_base, short_url},
+            upload::extract_client_ip,
+        },
+        locale::request_locales,
+        middleware::user::decode_optional_user_claims,
+        state::AppState,
+    },
+    workers::{
+        image_publisher::sync_visibility,
+        storage_gc::{run_cleanup, schedule_cleanup},
     },
 };
 
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct AgeAckQuery {
+    /// Set once the client has shown its age gate.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
 pub async fn get_lettering(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(gate): Query<AgeAckQuery>,
     headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let lettering = state
-        .lettering_repo
-        .find_by_id(id)
+    let repo = state.lettering_repo.clone();
+    // Ids that don't exist, often old links retried by scrapers, are cached
+    // too, but only briefly.
+    let not_found_ttl = state.config.not_found_cache_ttl_seconds;
+    let lettering: Option<Lettering> = state
+        .hot_cache
+        .get_or_fetch_with_ttl(
+            &lettering_key(id),
+            |found: &Option<Lettering>| {
+                if found.is_some() {
+                    LETTERING_CACHE_TTL_SECONDS
+                } else {
+                    not_found_ttl
+                }
+            },
+            || async move { repo.find_by_id(id).await.map_err(anyhow::Error::from) },
+        )
         .await
-        .map_err(|e| AppError::Internal(e.to_string()))?
-        .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let lettering =
+        lettering.ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
 
     let owner_user_id: Option<Uuid> =
         sqlx::query_scalar::<_, Option<Uuid>>("SELECT user_id FROM letterings WHERE id = $1")
@@ -41,15 +99,124 @@ pub async fn get_lettering(
         .and_then(|owner| requester_user_id.map(|requester| requester == owner))
         .unwrap_or(false);
 
+    // Letterings that aren't approved, and any in regions without
+    // discoverability, which are aggregate-only, answer as if they did not
+    // exist. Age-restricted letterings in gating regions need the client's
+    // age gate first. Owners always see their own. Place names follow
+    // Accept-Language.
+    if !is_owner
+        && !is_publicly_viewable(&state.db, id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+    {
+        return Err(AppError::NotFound("Lettering not found".to_string()));
+    }
+    let (status, age_restricted, age_gated, city_name, country_name) =
+        sqlx::query_as::<_, (String, bool, bool, Option<String>, Option<String>)>(
+            "SELECT l.status,
+                    l.age_restricted, COALESCE(rp.age_gate_enabled, false),
+                    localized_city_name(c.id, c.name, $2),
+                    localized_country_name(c.country_code, $2)
+             FROM letterings l
+             LEFT JOIN cities c ON c.id = l.city_id
+             LEFT JOIN region_policies rp ON rp.country_code = c.country_code
+             WHERE l.id = $1",
+        )
+        .bind(id)
+        .bind(request_locales(&headers))
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if age_restricted && age_gated && !is_owner && !gate.age_ack.unwrap_or(false) {
+        return Err(AppError::Forbidden(
+            "Age acknowledgment required; retry with age_ack=true".to_string(),
+        ));
+    }
+
+    if !is_owner {
+        count_view(&state, id, &headers).await;
+    }
+
+    let short_url = lettering_short_url(&state, id).await?;
+    let credit = credits::public_credit(&state, id).await?;
+
+    // Images of a lettering that isn't approved are in the private bucket,
+    // so its owner is handed signed URLs.
+    let mut lettering = lettering;
+    let ttl = Duration::from_secs(state.config.signed_url_ttl_seconds);
+    let storage = state.storage.as_ref();
+    let thumbnails = &mut lettering.thumbnail_urls;
+    for url in [
+        &mut lettering.image_url,
+        &mut thumbnails.small,
+        &mut thumbnails.medium,
+        &mut thumbnails.large,
+    ] {
+        *url = viewable_url(storage, &status, url, ttl).await;
+    }
+
     let mut value =
         serde_json::to_value(&lettering).map_err(|e| AppError::Internal(e.to_string()))?;
     if let Some(obj) = value.as_object_mut() {
         obj.insert("is_owner".to_string(), serde_json::Value::Bool(is_owner));
+        obj.insert("short_url".to_string(), serde_json::json!(short_url));
+        obj.insert("credit".to_string(), serde_json::json!(credit));
+        obj.insert(
+            "age_restricted".to_string(),
+            serde_json::Value::Bool(age_restricted),
+        );
+        obj.insert("city_name".to_string(), serde_json::json!(city_name));
+        obj.insert("country_name".to_string(), serde_json::json!(country_name));
     }
 
     Ok(Json(value))
 }
 
+/// Counted for moderation priority: reported letterings that are being
+/// looked at are reviewed first.
+async fn count_view(state: &AppState, id: Uuid, headers: &HeaderMap) {
+    if has_opted_out(headers) {
+        return;
+    }
+    let counted = sqlx::query(
+        "INSERT INTO lettering_view_counts (lettering_id, hour, count)
+         VALUES ($1, date_trunc('hour', NOW()), 1)
+         ON CONFLICT (lettering_id, hour)
+         DO UPDATE SET count = lettering_view_counts.count + 1",
+    )
+    .bind(id)
+    .execute(&state.db)
+    .await;
+    if let Err(e) = counted {
+        tracing::warn!(lettering_id = %id, "Failed to count lettering view: {}", e);
+    }
+}
+
+/// Counts a view of a lettering detail the response cache replayed. Only
+/// anonymous requests are replayed, so the viewer is never the owner.
+pub fn count_replayed_view(state: AppState, req: Request) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let (mut parts, _) = req.into_parts();
+        if let Ok(Path(id)) = Path::<Uuid>::from_request_parts(&mut parts, &state).await {
+            count_view(&state, id, &parts.headers).await;
+        }
+    })
+}
+
+/// Item-level reads answer as if a lettering did not exist unless it is
+/// approved and its region allows discovery; other regions only show up in
+/// aggregate counts.
+pub(crate) async fn ensure_discoverable(state: &AppState, id: Uuid) -> Result<(), AppError> {
+    if is_publicly_viewable(&state.db, id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+    {
+        Ok(())
+    } else {
+        Err(AppError::NotFound("Lettering not found".to_string()))
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ContributorQuery {
     #[serde(default = "default_limit")]
@@ -62,46 +229,103 @@ fn default_limit() -> i64 {
     50
 }
 
+/// How long a page of a contributor's letterings is cached; changes to any
+/// of their letterings drop it sooner.
+const CONTRIBUTOR_CACHE_TTL_SECONDS: u64 = 300;
+
 pub async fn get_contributor_letterings(
     State(state): State<AppState>,
     Path(tag): Path<String>,
     Query(params): Query<ContributorQuery>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let count = state
-        .lettering_repo
-        .count_by_contributor(&tag)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
-    let letterings = state
-        .lettering_repo
-        .find_by_contributor(&tag, params.limit, params.offset)
+    let cache_key = format!(
+        "{}{}:{}",
+        contributor_prefix(&tag),
+        params.limit,
+        params.offset
+    );
+    let repo = state.lettering_repo.clone();
+    let fetched_tag = tag.clone();
+    let (count, letterings): (i64, Vec<Lettering>) = state
+        .cache
+        .get_or_fetch(&cache_key, CONTRIBUTOR_CACHE_TTL_SECONDS, || async move {
+            let count = repo.count_by_contributor(&fetched_tag).await?;
+            let letterings = repo
+                .find_by_contributor(&fetched_tag, params.limit, params.offset)
+                .await?;
+            Ok((count, letterings))
+        })
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
+    let follower_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM follows WHERE contributor_tag = $1")
+            .bind(&tag)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
     Ok(Json(serde_json::json!({
         "contributor_tag": tag,
         "total_count": count,
+        "follower_count": follower_count,
         "letterings": letterings,
     })))
 }
 
+const SIMILAR_LIMIT: i64 = 6;
+
+#[allow(clippy::type_complexity)]
 pub async fn get_similar(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    // Fetch the source lettering's metadata
-    let source: Option<(Option<String>, Option<String>, String)> =
-        sqlx::query_as("SELECT ml_style, ml_script, pin_code FROM letterings WHERE id = $1")
-            .bind(id)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
+    // Fetch the source lettering's metadata; nothing is similar to a
+    // lettering that can't be opened.
+    let source: Option<(Option<String>, Option<String>, String)> = sqlx::query_as(
+        "SELECT ml_style, ml_script, pin_code FROM letterings
+         WHERE id = $1 AND city_discoverable(city_id)",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
 
     let Some((ml_style, ml_script, pin_code)) = source else {
         return Ok(Json(serde_json::json!({ "similar": [] })));
     };
 
-    // Find similar by matching style, script, or pin_code (excluding self)
+    let nearest = state
+        .lettering_repo
+        .find_similar(id, SIMILAR_LIMIT)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if !nearest.is_empty() {
+        let similar: Vec<serde_json::Value> = nearest
+            .into_iter()
+            .map(|(l, similarity)| {
+                let (style, script) = l
+                    .ml_metadata
+                    .map(|m| (m.style, m.script))
+                    .unwrap_or_default();
+                serde_json::json!({
+                    "id": l.id,
+                    "image_url": l.image_url,
+                    "thumbnail": l.thumbnail_urls.small,
+                    "detected_text": l.detected_text,
+                    "ml_style": style,
+                    "ml_script": script,
+                    "similarity": similarity,
+                })
+            })
+            .collect();
+        return Ok(Json(
+            serde_json::json!({ "similar": similar, "method": "embedding" }),
+        ));
+    }
+
+    // No embedding yet (not processed, or processed before embeddings were
+    // stored): fall back to matching style, script, or pin_code.
+
     let rows: Vec<(
         Uuid,
         String,
@@ -112,7 +336,7 @@ pub async fn get_similar(
     )> = sqlx::query_as(
         r#"SELECT id, image_url, thumbnail_small, detected_text, ml_style, ml_script
            FROM letterings
-           WHERE id != $1 AND status = 'APPROVED'
+           WHERE id != $1 AND status = 'APPROVED' AND city_discoverable(city_id)
              AND (ml_style = $2 OR ml_script = $3 OR pin_code = $4)
            ORDER BY
              CASE WHEN ml_style = $2 AND ml_script = $3 THEN 0
@@ -120,12 +344,13 @@ pub async fn get_similar(
                   WHEN ml_script = $3 THEN 2
                   ELSE 3 END,
              created_at DESC
-           LIMIT 6"#,
+           LIMIT $5"#,
     )
     .bind(id)
     .bind(&ml_style)
     .bind(&ml_script)
     .bind(&pin_code)
+    .bind(SIMILAR_LIMIT)
     .fetch_all(&state.db)
     .await
     .map_err(|e: sqlx::Error| AppError::Internal(e.to_string()))?;
@@ -144,13 +369,16 @@ pub async fn get_similar(
         })
         .collect();
 
-    Ok(Json(serde_json::json!({ "similar": similar })))
+    Ok(Json(
+        serde_json::json!({ "similar": similar, "method": "metadata" }),
+    ))
 }
 
 pub async fn download_lettering(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> Result<Redirect, AppError> {
+    ensure_discoverable(&state, id).await?;
     let lettering = state
         .lettering_repo
         .find_by_id(id)
@@ -206,44 +434,71 @@ pub async fn delete_lettering(
         ));
     }
 
-    // Delete from Cloudflare R2
-    let url_parts: Vec<&str> = lettering.image_url.split('/').collect();
-    if let Some(filename) = url_parts.last() {
-        let key = format!("letterings/{}", filename);
-        if let Err(e) = state.storage.delete(&key).await {
-            tracing::error!("Failed to delete R2 object {}: {}", key, e);
-        }
-        let _ = state
-            .storage
-            .delete(&format!("thumbnails/small/{}", filename))
-            .await;
-        let _ = state
-            .storage
-            .delete(&format!("thumbnails/medium/{}", filename))
-            .await;
-        let _ = state
-            .storage
-            .delete(&format!("thumbnails/large/{}", filename))
-            .await;
-    }
+    // Cascades to likes and comments; storage objects go once it commits.
+    delete_lettering_record(&state, &lettering).await?;
 
-    // Delete from database (cascades to likes, comments)
     state
-        .lettering_repo
-        .delete(id)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        .cdn_purger
+        .purge_or_schedule(lettering_cdn_urls(&lettering))
+        .await;
 
     tracing::info!(lettering_id = %id, "Lettering deleted successfully");
 
     Ok(StatusCode::NO_CONTENT)
 }
 
-/// Report an artifact. Increments report_count and appends the reason.
-/// Items crossing the threshold (3 reports) are automatically hidden (REPORTED status).
+/// Who filed a report: the account when signed in, else a salted hash of the
+/// client IP (so no address is kept). `None` when neither is known.
+fn reporter_key(user_id: Option<Uuid>, ip: Option<IpAddr>, secret: &str) -> Option<String> {
+    if let Some(user_id) = user_id {
+        return Some(format!("user:{}", user_id));
+    }
+    let ip = ip?;
+    let digest = Sha256::digest(format!("{}:{}", secret, ip).as_bytes());
+    Some(format!("ip:{:x}", digest)[..35].to_string())
+}
+
+/// How much one open report counts towards auto-hiding: a signed-in reporter
+/// is 1, anyone else (including reports older than reporter keys) is
+/// `anonymous_weight`.
+fn report_weight(reporter_key: Option<&str>, anonymous_weight: f64) -> f64 {
+    match reporter_key {
+        Some(key) if key.starts_with("user:") => 1.0,
+        _ => anonymous_weight.max(0.0),
+    }
+}
+
+/// Weighted open reports on a lettering within the auto-hide window.
+async fn recent_report_weight(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    state: &AppState,
+    id: Uuid,
+) -> Result<f64, AppError> {
+    let keys = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT reporter_key FROM lettering_reports
+         WHERE lettering_id = $1 AND cleared_at IS NULL
+           AND created_at >= NOW() - make_interval(hours => $2)",
+    )
+    .bind(id)
+    .bind(state.config.report_auto_hide_window_hours.max(1))
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(keys
+        .iter()
+        .map(|key| report_weight(key.as_deref(), state.config.report_anonymous_weight))
+        .sum())
+}
+
+/// Report an artifact. A reporter's first report increments report_count and
+/// appends the reason; reporting again only replaces their reason.
+/// Once weighted reports within the configured window reach
+/// `report_auto_hide_threshold`, the item is hidden (REPORTED status) until
+/// moderators clear the reports, and an urgent moderation alert is raised.
 pub async fn report_lettering(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
     Json(body): Json<ReportRequest>,
 ) -> Result<StatusCode, AppError> {
     let reason = body.reason.trim().to_string();
@@ -253,25 +508,149 @@ pub async fn report_lettering(
         ));
     }
 
-    let result = sqlx::query!(
+    let client_ip = extract_client_ip(&headers).map(|ip| ip.ip());
+    let user_id = decode_optional_user_claims(&headers, &state.config.jwt_secret)
+        .and_then(|claims| Uuid::parse_str(&claims.sub).ok());
+    let reporter = reporter_key(user_id, client_ip, &state.config.jwt_secret);
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Serialises reports on one lettering, so two concurrent repeats can't
+    // both count as first reports.
+    sqlx::query_scalar::<_, Uuid>("SELECT id FROM letterings WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+
+    let previous = match &reporter {
+        Some(key) => sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, reason FROM lettering_reports
+             WHERE lettering_id = $1 AND reporter_key = $2 AND cleared_at IS NULL",
+        )
+        .bind(id)
+        .bind(key)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?,
+        None => None,
+    };
+
+    if let Some((report_id, previous_reason)) = previous {
+        sqlx::query("UPDATE lettering_reports SET reason = $2, updated_at = NOW() WHERE id = $1")
+            .bind(report_id)
+            .bind(&reason)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        // Swap the reporter's last reason for the new one.
+        sqlx::query(
+            r#"UPDATE letterings
+               SET report_reasons = COALESCE(
+                       (SELECT jsonb_agg(t.e ORDER BY t.ord)
+                        FROM jsonb_array_elements(report_reasons) WITH ORDINALITY AS t(e, ord)
+                        WHERE t.ord IS DISTINCT FROM (
+                            SELECT MAX(p.ord)
+                            FROM jsonb_array_elements(report_reasons) WITH ORDINALITY AS p(e, ord)
+                            WHERE p.e = to_jsonb($2::text)
+                        )),
+                       '[]'::jsonb
+                   ) || jsonb_build_array($3::text),
+                   updated_at = NOW()
+               WHERE id = $1"#,
+        )
+        .bind(id)
+        .bind(&previous_reason)
+        .bind(&reason)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        tracing::info!(lettering_id = %id, "Repeat report updated reason");
+        return Ok(StatusCode::OK);
+    }
+
+    sqlx::query(
+        "INSERT INTO lettering_reports (id, lettering_id, reason, reporter_key) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(Uuid::now_v7())
+    .bind(id)
+    .bind(&reason)
+    .bind(&reporter)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    sqlx::query(
         r#"UPDATE letterings
         SET report_count = report_count + 1,
             report_reasons = report_reasons || $2::jsonb,
-            status = CASE WHEN report_count + 1 >= 3 THEN 'REPORTED' ELSE status END,
             updated_at = NOW()
         WHERE id = $1"#,
-        id,
-        serde_json::json!([reason]),
     )
-    .execute(&state.db)
+    .bind(id)
+    .bind(serde_json::json!([reason]))
+    .execute(&mut *tx)
     .await
     .map_err(|e| AppError::Internal(e.to_string()))?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound("Lettering not found".to_string()));
+    let threshold = state.config.report_auto_hide_threshold;
+    let mut hidden_with = None;
+    if threshold > 0.0 {
+        let weight = recent_report_weight(&mut tx, &state, id).await?;
+        if weight >= threshold {
+            let hidden = sqlx::query(
+                r#"UPDATE letterings
+                SET auto_hidden_from_status = status,
+                    auto_hidden_at = NOW(),
+                    status = 'REPORTED',
+                    moderation_reason = 'Hidden automatically after reports'
+                WHERE id = $1 AND status NOT IN ('REPORTED', 'REJECTED')"#,
+            )
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .rows_affected();
+            if hidden > 0 {
+                hidden_with = Some(weight);
+            }
+        }
     }
 
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
     tracing::info!(lettering_id = %id, "Lettering reported");
+    if let Some(weight) = hidden_with {
+        state
+            .performance
+            .create_alert(
+                AlertSeverity::Critical,
+                "Lettering hidden after reports",
+                &format!(
+                    "Lettering {} reached a report weight of {} within {} hours and was hidden pending review",
+                    id, weight, state.config.report_auto_hide_window_hours
+                ),
+                "lettering_report_weight",
+                threshold,
+                weight,
+            )
+            .await;
+        purge_lettering_from_cdn(&state, id).await;
+    }
+    state
+        .ip_geolocator
+        .spawn_annotate(GeoEvent::Report, Some(id), client_ip);
     Ok(StatusCode::OK)
 }
 
@@ -314,7 +693,8 @@ pub async fn get_revisits(
            FROM location_revisits lr
            JOIN letterings o ON o.id = lr.original_lettering_id
            JOIN letterings r ON r.id = lr.revisit_lettering_id
-           WHERE lr.original_lettering_id = $1 OR lr.revisit_lettering_id = $1
+           WHERE (lr.original_lettering_id = $1 OR lr.revisit_lettering_id = $1)
+             AND city_discoverable(o.city_id) AND city_discoverable(r.city_id)
            ORDER BY lr.created_at DESC"#,
     )
     .bind(id)
@@ -345,3 +725,533 @@ pub async fn get_revisits(
 
     Ok(Json(serde_json::json!({ "revisits": revisits })))
 }
+
+const SHARE_CARD_CACHE_TTL_SECONDS: u64 = 30 * 86_400;
+const SHARE_CARD_FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Lifetime of the signed URL a photo still in the private bucket is fetched
+/// by for its share card.
+const SHARE_CARD_SOURCE_URL_TTL: Duration = Duration::from_secs(300);
+const MAX_SHARE_PHOTO_BYTES: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, FromRow)]
+struct ShareCardSource {
+    status: String,
+    short_id: i64,
+    detected_text: Option<String>,
+    contributor_tag: String,
+    thumbnail_large: String,
+    city_name: String,
+}
+
+/// A share card either already in storage, addressed by its versioned URL,
+/// or rendered just now; `url` is unset if storing it failed.
+enum ShareCard {
+    Stored { url: String },
+    Rendered { png: Vec<u8>, url: Option<String> },
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct ShareLinkRequest {
+    /// Share channel the link is for, e.g. `whatsapp`; tags the short URL so
+    /// clicks are counted per channel.
+    #[ts(optional)]
+    pub channel: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct ShareLink {
+    pub short_url: String,
+    /// Open Graph preview image for the link.
+    pub image_url: String,
+}
+
+/// A rendered image stored in R2, keyed by a version derived from its inputs.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CachedAsset {
+    pub(crate) version: String,
+    pub(crate) url: String,
+}
+
+fn share_card_key(id: Uuid) -> String {
+    format!("og/{}.png", id)
+}
+
+fn share_card_cache_key(id: Uuid) -> String {
+    format!("og_card:{}", id)
+}
+
+/// How long a lettering read by id is served from cache. Whatever changes
+/// it drops it through the cache invalidator; counts catch up within this.
+const LETTERING_CACHE_TTL_SECONDS: u64 = 60;
+
+/// Drop every cached read showing a lettering, on every instance, after it
+/// changes. A change of status first moves its images to the bucket the new
+/// status calls for.
+pub(crate) async fn invalidate_lettering(state: &AppState, id: Uuid) {
+    let invalidator = state.lettering_repo.invalidator();
+    let moved = sync_visibility(&state.db, state.storage.as_ref(), invalidator, &[id]).await;
+    if moved.is_empty() {
+        invalidator.letterings_changed(&[id]).await;
+    }
+}
+
+/// Remove a lettering's rendered share card, QR codes and resized variants,
+/// e.g. once it is deleted or no longer public.
+pub(crate) async fn discard_share_assets(state: &AppState, id: Uuid) {
+    discard_image_variants(state, id).await;
+    let _ = state.storage.delete(&share_card_key(id)).await;
+    let _ = state.cache.delete(&share_card_cache_key(id)).await;
+    for format in [QrFormat::Png, QrFormat::Svg] {
+        for size in QR_SIZES {
+            let _ = state.storage.delete(&qr_code_key(id, format, size)).await;
+            let _ = state
+                .cache
+                .delete(&qr_code_cache_key(id, format, size))
+                .await;
+        }
+    }
+}
+
+/// Storage keys of everything kept for a lettering apart from its resized
+/// variants, which are tracked in `image_variants`. Each key is listed once,
+/// as content-addressed keys hold one reference per lettering however many
+/// of its URLs point at them.
+fn lettering_object_keys(state: &AppState, lettering: &Lettering) -> Vec<String> {
+    let mut keys = Vec::new();
+    if let Some(filename) = lettering.image_url.rsplit('/').next() {
+        keys.push(
+            state
+                .storage
+                .key_from_url(&lettering.image_url)
+                .unwrap_or_else(|| format!("letterings/{}", filename)),
+        );
+        for size in ["small", "medium", "large"] {
+            keys.push(format!("thumbnails/{}/{}", size, filename));
+        }
+    }
+    let thumbnails = &lettering.thumbnail_urls;
+    for url in [&thumbnails.small, &thumbnails.medium, &thumbnails.large] {
+        if let Some(key) = state.storage.key_from_url(url) {
+            keys.push(key);
+        }
+    }
+    keys.push(original_key(lettering.id));
+    keys.push(legacy_original_key(lettering.id));
+    keys.push(share_card_key(lettering.id));
+    for format in [QrFormat::Png, QrFormat::Svg] {
+        for size in QR_SIZES {
+            keys.push(qr_code_key(lettering.id, format, size));
+        }
+    }
+    let mut seen = HashSet::new();
+    keys.retain(|key| seen.insert(key.clone()));
+    keys
+}
+
+/// Delete a lettering's record, then its stored files.
+///
+/// The row is removed in the same transaction that records its objects for
+/// cleanup, so a failed delete leaves both the record and its files intact.
+/// Objects are deleted only after the commit; any that fail are retried by
+/// the storage GC worker.
+pub(crate) async fn delete_lettering_record(
+    state: &AppState,
+    lettering: &Lettering,
+) -> Result<(), AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let variant_keys: Vec<String> = sqlx::query_scalar(
+        "DELETE FROM image_variants WHERE lettering_id = $1 RETURNING storage_key",
+    )
+    .bind(lettering.id)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    let deleted = sqlx::query("DELETE FROM letterings WHERE id = $1")
+        .bind(lettering.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound("Lettering not found".to_string()));
+    }
+
+    let mut keys = content_addressed::release(&mut tx, &lettering_object_keys(state, lettering))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    keys.extend(variant_keys.iter().cloned());
+    let job = schedule_cleanup(&mut tx, Some(lettering.id), keys)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    state
+        .lettering_repo
+        .invalidator()
+        .lettering_changed(&LetteringChange::from(lettering))
+        .await;
+    let _ = state
+        .cache
+        .delete(&share_card_cache_key(lettering.id))
+        .await;
+    for format in [QrFormat::Png, QrFormat::Svg] {
+        for size in QR_SIZES {
+            let _ = state
+                .cache
+                .delete(&qr_code_cache_key(lettering.id, format, size))
+                .await;
+        }
+    }
+    for key in &variant_keys {
+        let _ = state.cache.delete(&variant_cache_key(key)).await;
+    }
+
+    if let Err(e) = run_cleanup(&state.db, state.storage.as_ref(), &job).await {
+        tracing::warn!(job_id = %job.id, "Failed to record storage cleanup: {}", e);
+    }
+    Ok(())
+}
+
+/// The publicly servable lettering a share card is rendered from.
+async fn share_card_source(state: &AppState, id: Uuid) -> Result<ShareCardSource, AppError> {
+    sqlx::query_as::<_, ShareCardSource>(
+        "SELECT l.status, l.short_id, l.detected_text, l.contributor_tag, l.thumbnail_large,
+                c.name AS city_name
+         FROM letterings l
+         JOIN cities c ON c.id = l.city_id
+         WHERE l.id = $1 AND city_discoverable(l.city_id)",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .filter(|s| is_publicly_servable(&s.status))
+    .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))
+}
+
+/// The stored share card for `source`, rendering and storing it first if it
+/// is missing or out of date.
+///
+/// Cards are stored in R2 under `og/{id}.png`. The version is derived from
+/// the inputs, so a changed photo, text, tag or city triggers a re-render
+/// and a new `?v=` on the URL keeps CDNs from serving the old card.
+async fn share_card(
+    state: &AppState,
+    id: Uuid,
+    source: ShareCardSource,
+) -> Result<ShareCard, AppError> {
+    let fingerprint = format!(
+        "{}\n{}\n{}\n{}",
+        source.thumbnail_large,
+        source.detected_text.as_deref().unwrap_or_default(),
+        source.contributor_tag,
+        source.city_name
+    );
+    let version = format!("{:x}", Sha256::digest(fingerprint.as_bytes()))[..16].to_string();
+
+    let cache_key = share_card_cache_key(id);
+    if let Ok(Some(cached)) = state.cache.get::<CachedAsset>(&cache_key).await
+        && cached.version == version
+    {
+        return Ok(ShareCard::Stored {
+            url: format!("{}?v={}", cached.url, version),
+        });
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(SHARE_CARD_FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    // Only approved letterings have share cards, but their photo may not
+    // have left the private bucket yet.
+    let photo_url = viewable_url(
+        state.storage.as_ref(),
+        "APPROVED",
+        &source.thumbnail_large,
+        SHARE_CARD_SOURCE_URL_TTL,
+    )
+    .await;
+    let response = client
+        .get(&photo_url)
+        .send()
+        .await
+        .map_err(|e| AppError::ExternalService(format!("Failed to fetch photo: {}", e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::ExternalService(format!(
+            "Photo fetch returned HTTP {}",
+            response.status()
+        )));
+    }
+    let photo = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::ExternalService(format!("Failed to read photo: {}", e)))?;
+    if photo.len() > MAX_SHARE_PHOTO_BYTES {
+        return Err(AppError::ExternalService("Photo too large".to_string()));
+    }
+
+    let (text, tag, city) = (
+        source.detected_text,
+        source.contributor_tag,
+        source.city_name,
+    );
+    let png = tokio::task::spawn_blocking(move || {
+        let photo = image::load_from_memory(&photo)?;
+        render_share_card(&photo, text.as_deref(), &tag, &city)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .map_err(|e| AppError::Internal(format!("Failed to render share card: {}", e)))?;
+
+    let url = match state
+        .storage
+        .upload(&share_card_key(id), png.clone(), "image/png")
+        .await
+    {
+        Ok(url) => {
+            let versioned = format!("{}?v={}", url, version);
+            let cached = CachedAsset { version, url };
+            if let Err(e) = state
+                .cache
+                .set(&cache_key, &cached, SHARE_CARD_CACHE_TTL_SECONDS)
+                .await
+            {
+                tracing::warn!(lettering_id = %id, "Failed to cache share card URL: {}", e);
+            }
+            Some(versioned)
+        }
+        Err(e) => {
+            tracing::warn!(lettering_id = %id, "Failed to store share card: {}", e);
+            None
+        }
+    };
+
+    Ok(ShareCard::Rendered { png, url })
+}
+
+/// Open Graph share card for an approved lettering: the photo with its
+/// detected text, contributor and city.
+///
+/// Cards are rendered on first request; later requests redirect to the
+/// stored copy.
+pub async fn get_share_card(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let source = share_card_source(&state, id).await?;
+    match share_card(&state, id, source).await? {
+        ShareCard::Stored { url } => Ok(Redirect::temporary(&url).into_response()),
+        ShareCard::Rendered { png, .. } => Ok((
+            [
+                (header::CONTENT_TYPE, "image/png"),
+                (header::CACHE_CONTROL, "public, max-age=86400"),
+            ],
+            png,
+        )
+            .into_response()),
+    }
+}
+
+/// Short link and Open Graph image for sharing an approved lettering.
+///
+/// The card is rendered and stored before responding, so crawlers that
+/// unfurl the link (WhatsApp, X, ...) find it ready. If it couldn't be
+/// stored, `image_url` points at the card endpoint instead.
+pub async fn share_lettering(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ShareLinkRequest>,
+) -> Result<Json<ShareLink>, AppError> {
+    let source = share_card_source(&state, id).await?;
+    let mut short = short_url(&state.config, source.short_id);
+    if let Some(channel) = payload.channel.as_deref().map(str::trim)
+        && !channel.is_empty()
+    {
+        short = format!("{}?c={}", short, normalize_channel(Some(channel)));
+    }
+
+    let image_url = match share_card(&state, id, source).await? {
+        ShareCard::Stored { url } | ShareCard::Rendered { url: Some(url), .. } => url,
+        ShareCard::Rendered { url: None, .. } => format!(
+            "{}/api/v1/letterings/{}/og-image",
+            public_base(&state.config),
+            id
+        ),
+    };
+
+    Ok(Json(ShareLink {
+        short_url: short,
+        image_url,
+    }))
+}
+
+/// Rendered QR sizes in pixels; requests are rounded up to the next one so
+/// only a handful of variants are ever stored.
+const QR_SIZES: [u32; 4] = [256, 512, 1024, 2048];
+const QR_DEFAULT_SIZE: u32 = 512;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QrFormat {
+    Png,
+    Svg,
+}
+
+impl QrFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QrQuery {
+    pub format: Option<QrFormat>,
+    /// Desired width in pixels, rounded up to one of 256, 512, 1024 or 2048.
+    pub size: Option<u32>,
+}
+
+fn qr_size(requested: Option<u32>) -> u32 {
+    let requested = requested.unwrap_or(QR_DEFAULT_SIZE);
+    QR_SIZES
+        .into_iter()
+        .find(|&s| s >= requested)
+        .unwrap_or(QR_SIZES[QR_SIZES.len() - 1])
+}
+
+fn qr_code_key(id: Uuid, format: QrFormat, size: u32) -> String {
+    format!("qr/{}/{}.{}", id, size, format.extension())
+}
+
+fn qr_code_cache_key(id: Uuid, format: QrFormat, size: u32) -> String {
+    format!("qr_code:{}:{}:{}", id, format.extension(), size)
+}
+
+/// QR code pointing at an approved lettering's short link, tagged with the
+/// `qr` share channel, for printing on physical plaques.
+///
+/// Codes are cached in R2 under `qr/{id}/{size}.{png|svg}` the same way as
+/// share cards.
+pub async fn get_qr_code(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<QrQuery>,
+) -> Result<Response, AppError> {
+    let format = params.format.unwrap_or(QrFormat::Png);
+    let size = qr_size(params.size);
+
+    let (_, short_id) = sqlx::query_as::<_, (String, i64)>(
+        "SELECT status, short_id FROM letterings WHERE id = $1 AND city_discoverable(city_id)",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .filter(|(status, _)| is_publicly_servable(status))
+    .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+
+    let target = format!("{}?c=qr", short_url(&state.config, short_id));
+    if !target.starts_with("http") {
+        return Err(AppError::Internal(
+            "PUBLIC_BASE_URL must be set to generate QR codes".to_string(),
+        ));
+    }
+    let version = format!("{:x}", Sha256::digest(target.as_bytes()))[..16].to_string();
+
+    let cache_key = qr_code_cache_key(id, format, size);
+    if let Ok(Some(cached)) = state.cache.get::<CachedAsset>(&cache_key).await
+        && cached.version == version
+    {
+        return Ok(Redirect::temporary(&format!("{}?v={}", cached.url, version)).into_response());
+    }
+
+    let qr = QrCode::encode(target.as_bytes())
+        .map_err(|e| AppError::Internal(format!("Failed to encode QR code: {}", e)))?;
+    let body = match format {
+        QrFormat::Png => {
+            let modules = (qr.size() + 2 * qr_code::QUIET_ZONE) as u32;
+            qr.to_png(size / modules)
+                .map_err(|e| AppError::Internal(format!("Failed to render QR code: {}", e)))?
+        }
+        QrFormat::Svg => qr.to_svg(size).into_bytes(),
+    };
+
+    match state
+        .storage
+        .upload(
+            &qr_code_key(id, format, size),
+            body.clone(),
+            format.content_type(),
+        )
+        .await
+    {
+        Ok(url) => {
+            let cached = CachedAsset { version, url };
+            if let Err(e) = state
+                .cache
+                .set(&cache_key, &cached, SHARE_CARD_CACHE_TTL_SECONDS)
+                .await
+            {
+                tracing::warn!(lettering_id = %id, "Failed to cache QR code URL: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!(lettering_id = %id, "Failed to store QR code: {}", e),
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type()),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{report_weight, reporter_key};
+    use uuid::Uuid;
+
+    #[test]
+    fn signed_in_reports_weigh_more() {
+        assert_eq!(report_weight(Some("user:0190a1b2"), 0.5), 1.0);
+        assert_eq!(report_weight(Some("ip:abc123"), 0.5), 0.5);
+        assert_eq!(report_weight(None, 0.25), 0.25);
+        assert_eq!(report_weight(None, -1.0), 0.0);
+    }
+
+    #[test]
+    fn reporter_key_prefers_account_and_hides_ip() {
+        let user = Uuid::now_v7();
+        let ip = "203.0.113.7".parse().ok();
+
+        assert_eq!(
+            reporter_key(Some(user), ip, "secret"),
+            Some(format!("user:{}", user))
+        );
+        let by_ip = reporter_key(None, ip, "secret").unwrap();
+        assert!(by_ip.starts_with("ip:"));
+        assert!(!by_ip.contains("203.0.113.7"));
+        assert_eq!(reporter_key(None, ip, "secret"), Some(by_ip.clone()));
+        assert_ne!(reporter_key(None, ip, "other"), Some(by_ip));
+        assert_eq!(reporter_key(None, None, "secret"), None);
+    }
+}
@@ -4,19 +4,31 @@ use axum::{
     http::HeaderMap,
     http::StatusCode,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::time::Duration;
+use ts_rs::TS;
 use uuid::Uuid;
 
+use crate::domain::social::{
+    activity::{ActivityCursor, ActivityPage},
+    bookmark::Bookmark,
+    repository::SocialRepository,
+};
+use crate::infrastructure::{cache::invalidation::LetteringChange, storage::access::viewable_url};
 use crate::presentation::http::{
-    errors::AppError, middleware::user::decode_required_user_claims, state::AppState,
+    errors::AppError, handlers::letterings, middleware::user::decode_required_user_claims,
+    state::AppState,
 };
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
 pub struct MyUploadsQuery {
+    #[ts(type = "number")]
     #[serde(default = "default_limit")]
     pub limit: i64,
+    #[ts(type = "number")]
     #[serde(default)]
     pub offset: i64,
     pub status: Option<String>,
@@ -30,7 +42,8 @@ fn safe_limit_offset(limit: i64, offset: i64) -> (i64, i64) {
     (limit.clamp(1, 100), offset.max(0))
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
 pub struct MyUploadItem {
     pub id: Uuid,
     pub image_url: String,
@@ -50,15 +63,20 @@ pub struct MyUploadItem {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct MyUploadsResponse {
     pub items: Vec<MyUploadItem>,
+    #[ts(type = "number")]
     pub total: i64,
+    #[ts(type = "number")]
     pub limit: i64,
+    #[ts(type = "number")]
     pub offset: i64,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
 pub struct UpdateMyUploadRequest {
     pub description: Option<String>,
     pub contributor_tag: Option<String>,
@@ -73,7 +91,8 @@ struct MyUploadEditableRow {
     pin_code: String,
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
 pub struct MyUploadStatusHistoryItem {
     pub id: Uuid,
     pub from_status: Option<String>,
@@ -84,7 +103,8 @@ pub struct MyUploadStatusHistoryItem {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
 pub struct MyUploadMetadataHistoryItem {
     pub id: Uuid,
     pub field_name: String,
@@ -93,13 +113,15 @@ pub struct MyUploadMetadataHistoryItem {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct MyUploadTimelineResponse {
     pub status_history: Vec<MyUploadStatusHistoryItem>,
     pub metadata_history: Vec<MyUploadMetadataHistoryItem>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
 pub struct NotificationItem {
     pub id: Uuid,
     pub r#type: String,
@@ -110,23 +132,71 @@ pub struct NotificationItem {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
 pub struct NotificationsQuery {
+    #[ts(type = "number")]
     #[serde(default = "default_limit")]
     pub limit: i64,
+    #[ts(type = "number")]
     #[serde(default)]
     pub offset: i64,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
 pub struct NotificationsResponse {
     pub items: Vec<NotificationItem>,
+    #[ts(type = "number")]
     pub total: i64,
+    #[ts(type = "number")]
     pub unread: i64,
+    #[ts(type = "number")]
     pub limit: i64,
+    #[ts(type = "number")]
     pub offset: i64,
 }
 
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct BookmarksQuery {
+    #[ts(type = "number")]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[ts(type = "number")]
+    #[serde(default)]
+    pub offset: i64,
+    /// Include age-restricted letterings in gated regions.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
+#[derive(Debug, Serialize, TS)]
+#[ts(export)]
+pub struct BookmarksResponse {
+    pub items: Vec<Bookmark>,
+    #[ts(type = "number")]
+    pub total: i64,
+    #[ts(type = "number")]
+    pub limit: i64,
+    #[ts(type = "number")]
+    pub offset: i64,
+}
+
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct FeedQuery {
+    /// `next_cursor` of the previous page.
+    #[ts(optional)]
+    pub cursor: Option<String>,
+    #[ts(type = "number")]
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    /// Include age-restricted letterings in gated regions.
+    #[ts(optional)]
+    pub age_ack: Option<bool>,
+}
+
 fn parse_user_id(headers: &HeaderMap, state: &AppState) -> Result<Uuid, AppError> {
     let claims = decode_required_user_claims(headers, &state.config.jwt_secret)?;
     Uuid::parse_str(&claims.sub)
@@ -157,7 +227,7 @@ fn normalize_optional_contributor_tag(value: Option<String>) -> Result<Option<St
         Some(v) => {
             let trimmed = v.trim().to_string();
             let len = trimmed.chars().count();
-            if len < 2 || len > 30 {
+            if !(2..=30).contains(&len) {
                 return Err(AppError::BadRequest(
                     "contributor_tag must be between 2 and 30 characters".to_string(),
                 ));
@@ -244,6 +314,21 @@ pub async fn list_my_letterings(
         (items, total)
     };
 
+    // Non-approved uploads aren't on the public CDN; hand the owner signed URLs.
+    let ttl = Duration::from_secs(state.config.signed_url_ttl_seconds);
+    let mut items = items;
+    for item in &mut items {
+        item.image_url =
+            viewable_url(state.storage.as_ref(), &item.status, &item.image_url, ttl).await;
+        item.thumbnail_small = viewable_url(
+            state.storage.as_ref(),
+            &item.status,
+            &item.thumbnail_small,
+            ttl,
+        )
+        .await;
+    }
+
     Ok(Json(MyUploadsResponse {
         items,
         total,
@@ -264,12 +349,12 @@ pub async fn update_my_lettering(
         return Err(AppError::BadRequest("No updates provided".to_string()));
     }
 
-    if let Some(pin) = body.pin_code.as_deref() {
-        if pin.len() != 6 || !pin.chars().all(|c| c.is_ascii_digit()) {
-            return Err(AppError::BadRequest(
-                "pin_code must be 6 digits".to_string(),
-            ));
-        }
+    if let Some(pin) = body.pin_code.as_deref()
+        && (pin.len() != 6 || !pin.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(AppError::BadRequest(
+            "pin_code must be 6 digits".to_string(),
+        ));
     }
 
     let existing = sqlx::query_as::<_, MyUploadEditableRow>(
@@ -319,6 +404,13 @@ pub async fn update_my_lettering(
         return Ok(Json(current));
     }
 
+    // The page of the tag it leaves goes stale too.
+    let previous = changed_contributor.then(|| LetteringChange {
+        id: existing.id,
+        city_id: None,
+        contributor_tag: Some(existing.contributor_tag.clone()),
+    });
+
     let mut tx = state
         .db
         .begin()
@@ -391,6 +483,14 @@ pub async fn update_my_lettering(
     tx.commit()
         .await
         .map_err(|e| AppError::Internal(e.to_string()))?;
+    letterings::invalidate_lettering(&state, existing.id).await;
+    if let Some(previous) = &previous {
+        state
+            .lettering_repo
+            .invalidator()
+            .lettering_changed(previous)
+            .await;
+    }
 
     tracing::info!(
         user_id = %user_id,
@@ -522,3 +622,726 @@ pub async fn mark_notification_read(
 
     Ok(StatusCode::OK)
 }
+
+/// Most saved searches one user can keep.
+const MAX_SAVED_SEARCHES: i64 = 20;
+const MAX_SAVED_SEARCH_RADIUS_M: f64 = 50_000.0;
+
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
+pub struct SavedSearchItem {
+    pub id: Uuid,
+    pub name: String,
+    pub query: Option<String>,
+    pub script: Option<String>,
+    pub style: Option<String>,
+    pub city_id: Option<Uuid>,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+    pub radius_m: Option<f64>,
+    pub notify: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body for creating or replacing a saved search. At least one of `query`,
+/// `script`, `style`, `city_id` or the `lat`/`lng`/`radius_m` area is needed.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct SavedSearchRequest {
+    pub name: String,
+    pub query: Option<String>,
+    pub script: Option<String>,
+    pub style: Option<String>,
+    pub city_id: Option<Uuid>,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+    pub radius_m: Option<f64>,
+    /// Defaults to true.
+    pub notify: Option<bool>,
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn normalize_saved_search(body: SavedSearchRequest) -> Result<SavedSearchRequest, AppError> {
+    let name = body.name.trim().to_string();
+    if !(1..=80).contains(&name.chars().count()) {
+        return Err(AppError::BadRequest(
+            "name must be between 1 and 80 characters".to_string(),
+        ));
+    }
+    let query = trimmed(body.query);
+    if query.as_ref().is_some_and(|q| q.chars().count() > 200) {
+        return Err(AppError::BadRequest(
+            "query must be 200 characters or less".to_string(),
+        ));
+    }
+    let script = trimmed(body.script).map(|s| s.to_lowercase());
+    let style = trimmed(body.style).map(|s| s.to_lowercase());
+
+    match (body.lat, body.lng, body.radius_m) {
+        (None, None, None) => {}
+        (Some(lat), Some(lng), Some(radius_m)) => {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+                return Err(AppError::BadRequest(
+                    "lat must be within [-90, 90] and lng within [-180, 180]".to_string(),
+                ));
+            }
+            if !radius_m.is_finite() || radius_m <= 0.0 || radius_m > MAX_SAVED_SEARCH_RADIUS_M {
+                return Err(AppError::BadRequest(
+                    "radius_m must be between 0 and 50000 metres".to_string(),
+                ));
+            }
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "lat, lng and radius_m must be given together".to_string(),
+            ));
+        }
+    }
+
+    if query.is_none()
+        && script.is_none()
+        && style.is_none()
+        && body.city_id.is_none()
+        && body.lat.is_none()
+    {
+        return Err(AppError::BadRequest(
+            "A saved search needs a query, filter or area".to_string(),
+        ));
+    }
+
+    Ok(SavedSearchRequest {
+        name,
+        query,
+        script,
+        style,
+        city_id: body.city_id,
+        lat: body.lat,
+        lng: body.lng,
+        radius_m: body.radius_m,
+        notify: body.notify,
+    })
+}
+
+const SAVED_SEARCH_COLUMNS: &str = "id, name, query, script, style, city_id,
+    ST_Y(center::geometry) AS lat, ST_X(center::geometry) AS lng, radius_m,
+    notify, created_at, updated_at";
+
+pub async fn list_saved_searches(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<SavedSearchItem>>, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+
+    let items = sqlx::query_as::<_, SavedSearchItem>(&format!(
+        "SELECT {} FROM saved_searches WHERE user_id = $1 ORDER BY created_at DESC",
+        SAVED_SEARCH_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(items))
+}
+
+/// New matches are only notified for approvals after the search is saved.
+pub async fn create_saved_search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<SavedSearchRequest>,
+) -> Result<(StatusCode, Json<SavedSearchItem>), AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+    let body = normalize_saved_search(body)?;
+
+    let existing =
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM saved_searches WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+    if existing >= MAX_SAVED_SEARCHES {
+        return Err(AppError::BadRequest(format!(
+            "You can keep at most {} saved searches",
+            MAX_SAVED_SEARCHES
+        )));
+    }
+
+    let item = sqlx::query_as::<_, SavedSearchItem>(&format!(
+        "INSERT INTO saved_searches (id, user_id, name, query, script, style, city_id, center, radius_m, notify)
+         VALUES ($1, $2, $3, $4, $5, $6, $7,
+                 CASE WHEN $8::float8 IS NULL THEN NULL
+                      ELSE ST_SetSRID(ST_MakePoint($9, $8), 4326)::geography END,
+                 $10, COALESCE($11, true))
+         RETURNING {}",
+        SAVED_SEARCH_COLUMNS
+    ))
+    .bind(Uuid::now_v7())
+    .bind(user_id)
+    .bind(&body.name)
+    .bind(&body.query)
+    .bind(&body.script)
+    .bind(&body.style)
+    .bind(body.city_id)
+    .bind(body.lat)
+    .bind(body.lng)
+    .bind(body.radius_m)
+    .bind(body.notify)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+            AppError::BadRequest("City not found".to_string())
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok((StatusCode::CREATED, Json(item)))
+}
+
+/// Replaces a saved search. Changing it doesn't re-notify earlier approvals.
+pub async fn update_saved_search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(body): Json<SavedSearchRequest>,
+) -> Result<Json<SavedSearchItem>, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+    let body = normalize_saved_search(body)?;
+
+    let item = sqlx::query_as::<_, SavedSearchItem>(&format!(
+        "UPDATE saved_searches
+         SET name = $3, query = $4, script = $5, style = $6, city_id = $7,
+             center = CASE WHEN $8::float8 IS NULL THEN NULL
+                           ELSE ST_SetSRID(ST_MakePoint($9, $8), 4326)::geography END,
+             radius_m = $10, notify = COALESCE($11, notify), updated_at = NOW()
+         WHERE id = $1 AND user_id = $2
+         RETURNING {}",
+        SAVED_SEARCH_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .bind(&body.name)
+    .bind(&body.query)
+    .bind(&body.script)
+    .bind(&body.style)
+    .bind(body.city_id)
+    .bind(body.lat)
+    .bind(body.lng)
+    .bind(body.radius_m)
+    .bind(body.notify)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+            AppError::BadRequest("City not found".to_string())
+        }
+        _ => AppError::Internal(e.to_string()),
+    })?
+    .ok_or_else(|| AppError::NotFound("Saved search not found".to_string()))?;
+
+    Ok(Json(item))
+}
+
+pub async fn delete_saved_search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+
+    let result = sqlx::query("DELETE FROM saved_searches WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Saved search not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Most contributors one user can follow.
+const MAX_FOLLOWS: i64 = 200;
+
+#[derive(Debug, Serialize, FromRow, TS)]
+#[ts(export)]
+pub struct FollowItem {
+    pub id: Uuid,
+    pub contributor_tag: Option<String>,
+    pub user_id: Option<Uuid>,
+    /// Display name of the followed account, if any.
+    pub display_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Body for following a contributor: exactly one of `contributor_tag` or
+/// `user_id`.
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct FollowRequest {
+    pub contributor_tag: Option<String>,
+    pub user_id: Option<Uuid>,
+}
+
+fn normalize_follow(body: FollowRequest, follower_id: Uuid) -> Result<FollowRequest, AppError> {
+    let contributor_tag = normalize_optional_contributor_tag(body.contributor_tag)?;
+    match (&contributor_tag, body.user_id) {
+        (Some(_), None) => {}
+        (None, Some(user_id)) if user_id == follower_id => {
+            return Err(AppError::BadRequest(
+                "You can't follow yourself".to_string(),
+            ));
+        }
+        (None, Some(_)) => {}
+        _ => {
+            return Err(AppError::BadRequest(
+                "Give exactly one of contributor_tag or user_id".to_string(),
+            ));
+        }
+    }
+    Ok(FollowRequest {
+        contributor_tag,
+        user_id: body.user_id,
+    })
+}
+
+const FOLLOW_SELECT: &str = "SELECT f.id, f.contributor_tag, f.followed_user_id AS user_id,
+        u.display_name, f.created_at
+    FROM follows f
+    LEFT JOIN users u ON u.id = f.followed_user_id";
+
+pub async fn list_follows(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FollowItem>>, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+
+    let items = sqlx::query_as::<_, FollowItem>(&format!(
+        "{} WHERE f.follower_id = $1 ORDER BY f.created_at DESC",
+        FOLLOW_SELECT
+    ))
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(items))
+}
+
+/// Follows a contributor tag or account. Only approvals after the follow are
+/// notified. Following an already followed contributor returns the existing
+/// follow.
+pub async fn create_follow(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<FollowRequest>,
+) -> Result<(StatusCode, Json<FollowItem>), AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+    let body = normalize_follow(body, user_id)?;
+
+    let existing = sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM follows
+         WHERE follower_id = $1 AND (contributor_tag = $2 OR followed_user_id = $3)",
+    )
+    .bind(user_id)
+    .bind(&body.contributor_tag)
+    .bind(body.user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let (status, id) = match existing {
+        Some(id) => (StatusCode::OK, id),
+        None => {
+            let count =
+                sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM follows WHERE follower_id = $1")
+                    .bind(user_id)
+                    .fetch_one(&state.db)
+                    .await
+                    .map_err(|e| AppError::Internal(e.to_string()))?;
+            if count >= MAX_FOLLOWS {
+                return Err(AppError::BadRequest(format!(
+                    "You can follow at most {} contributors",
+                    MAX_FOLLOWS
+                )));
+            }
+
+            let id = Uuid::now_v7();
+            sqlx::query(
+                "INSERT INTO follows (id, follower_id, contributor_tag, followed_user_id)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(id)
+            .bind(user_id)
+            .bind(&body.contributor_tag)
+            .bind(body.user_id)
+            .execute(&state.db)
+            .await
+            .map_err(|e| match &e {
+                sqlx::Error::Database(db) if db.is_foreign_key_violation() => {
+                    AppError::NotFound("User not found".to_string())
+                }
+                sqlx::Error::Database(db) if db.is_unique_violation() => {
+                    AppError::BadRequest("Already following this contributor".to_string())
+                }
+                _ => AppError::Internal(e.to_string()),
+            })?;
+            (StatusCode::CREATED, id)
+        }
+    };
+
+    let item = sqlx::query_as::<_, FollowItem>(&format!("{} WHERE f.id = $1", FOLLOW_SELECT))
+        .bind(id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok((status, Json(item)))
+}
+
+pub async fn delete_follow(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+
+    let result = sqlx::query("DELETE FROM follows WHERE id = $1 AND follower_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Follow not found".to_string()));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The current user's bookmarks, newest first. Letterings that have since
+/// been hidden drop out until they are listed again.
+pub async fn list_bookmarks(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<BookmarksQuery>,
+) -> Result<Json<BookmarksResponse>, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+    let (safe_limit, safe_offset) = safe_limit_offset(params.limit, params.offset);
+
+    let (items, total) = state
+        .social_repo
+        .list_bookmarks(
+            user_id,
+            safe_limit,
+            safe_offset,
+            params.age_ack.unwrap_or(false),
+        )
+        .await?;
+
+    Ok(Json(BookmarksResponse {
+        items,
+        total,
+        limit: safe_limit,
+        offset: safe_offset,
+    }))
+}
+
+/// The current user's activity feed, newest first: approvals from
+/// contributors they follow, replies to their comments and likes on their
+/// uploads, as fanned out by the activity worker.
+pub async fn get_my_feed(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<FeedQuery>,
+) -> Result<Json<ActivityPage>, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+    let cursor = match params.cursor.as_deref() {
+        Some(raw) => Some(
+            ActivityCursor::decode(raw)
+                .ok_or_else(|| AppError::BadRequest("Invalid cursor".into()))?,
+        ),
+        None => None,
+    };
+    let (limit, _) = safe_limit_offset(params.limit, 0);
+
+    let page = state
+        .social_repo
+        .list_activity(user_id, cursor, limit, params.age_ack.unwrap_or(false))
+        .await?;
+    Ok(Json(page))
+}
+
+/// How long a contributor's stats are served from cache.
+const MY_STATS_CACHE_TTL: u64 = 120;
+
+/// Rejected uploads count as strikes for this many days.
+const STRIKE_WINDOW_DAYS: i32 = 90;
+
+/// Strikes at which a contributor is warned, and at which their standing is
+/// at risk.
+const STRIKE_WARNING: i64 = 1;
+const STRIKE_AT_RISK: i64 = 3;
+
+#[derive(Debug, Default, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct UploadStatusCounts {
+    #[ts(type = "number")]
+    pub total: i64,
+    #[ts(type = "number")]
+    pub pending: i64,
+    #[ts(type = "number")]
+    pub approved: i64,
+    #[ts(type = "number")]
+    pub rejected: i64,
+    #[ts(type = "number")]
+    pub reported: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct StrikeStatus {
+    /// Uploads rejected by moderation within the window.
+    #[ts(type = "number")]
+    pub strikes: i64,
+    pub window_days: i32,
+    /// `good_standing`, `warning` or `at_risk`.
+    pub standing: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MyStatsResponse {
+    pub uploads: UploadStatusCounts,
+    /// Across all of the user's uploads.
+    #[ts(type = "number")]
+    pub likes_received: i64,
+    #[ts(type = "number")]
+    pub comments_received: i64,
+    /// Distinct cities with an approved upload.
+    #[ts(type = "number")]
+    pub cities_covered: i64,
+    /// Consecutive days (UTC) with an upload, up to today or yesterday.
+    #[ts(type = "number")]
+    pub current_streak_days: i64,
+    pub strikes: StrikeStatus,
+}
+
+#[derive(Debug, FromRow)]
+struct ReceivedTotals {
+    likes_received: i64,
+    comments_received: i64,
+    cities_covered: i64,
+    strikes: i64,
+}
+
+/// Days in a row with an upload, ending today or, if nothing was uploaded
+/// yet today, yesterday. `days` holds distinct upload dates, newest first.
+fn current_streak(days: &[NaiveDate], today: NaiveDate) -> i64 {
+    let Some(&latest) = days.first() else {
+        return 0;
+    };
+    if latest != today && Some(latest) != today.checked_sub_days(Days::new(1)) {
+        return 0;
+    }
+    let mut streak = 1;
+    for pair in days.windows(2) {
+        if pair[0].checked_sub_days(Days::new(1)) != Some(pair[1]) {
+            break;
+        }
+        streak += 1;
+    }
+    streak
+}
+
+fn standing(strikes: i64) -> &'static str {
+    if strikes >= STRIKE_AT_RISK {
+        "at_risk"
+    } else if strikes >= STRIKE_WARNING {
+        "warning"
+    } else {
+        "good_standing"
+    }
+}
+
+/// The current user's contribution stats for their profile dashboard.
+pub async fn get_my_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<MyStatsResponse>, AppError> {
+    let user_id = parse_user_id(&headers, &state)?;
+    let db = state.db.clone();
+
+    let stats = state
+        .cache
+        .get_or_fetch(
+            &format!("me_stats:{}", user_id),
+            MY_STATS_CACHE_TTL,
+            || async move {
+                let uploads = sqlx::query_as::<_, UploadStatusCounts>(
+                    "SELECT COUNT(*)::bigint AS total,
+                            COUNT(*) FILTER (WHERE status = 'PENDING')::bigint AS pending,
+                            COUNT(*) FILTER (WHERE status = 'APPROVED')::bigint AS approved,
+                            COUNT(*) FILTER (WHERE status = 'REJECTED')::bigint AS rejected,
+                            COUNT(*) FILTER (WHERE status = 'REPORTED')::bigint AS reported
+                     FROM letterings WHERE user_id = $1",
+                )
+                .bind(user_id)
+                .fetch_one(&db)
+                .await?;
+
+                let totals = sqlx::query_as::<_, ReceivedTotals>(
+                    "SELECT COALESCE(SUM(likes_count), 0)::bigint AS likes_received,
+                            COALESCE(SUM(comments_count), 0)::bigint AS comments_received,
+                            COUNT(DISTINCT city_id) FILTER (WHERE status = 'APPROVED')::bigint
+                                AS cities_covered,
+                            COUNT(*) FILTER (
+                                WHERE status = 'REJECTED' AND moderated_by IS NOT NULL
+                                  AND moderated_at >= NOW() - make_interval(days => $2)
+                            )::bigint AS strikes
+                     FROM letterings WHERE user_id = $1",
+                )
+                .bind(user_id)
+                .bind(STRIKE_WINDOW_DAYS)
+                .fetch_one(&db)
+                .await?;
+
+                // A streak can't be longer than the days it is looked for in.
+                let days: Vec<NaiveDate> = sqlx::query_scalar(
+                    "SELECT DISTINCT (created_at AT TIME ZONE 'UTC')::date AS day
+                     FROM letterings
+                     WHERE user_id = $1 AND created_at >= NOW() - INTERVAL '366 days'
+                     ORDER BY day DESC",
+                )
+                .bind(user_id)
+                .fetch_all(&db)
+                .await?;
+
+                Ok(MyStatsResponse {
+                    uploads,
+                    likes_received: totals.likes_received,
+                    comments_received: totals.comments_received,
+                    cities_covered: totals.cities_covered,
+                    current_streak_days: current_streak(&days, Utc::now().date_naive()),
+                    strikes: StrikeStatus {
+                        strikes: totals.strikes,
+                        window_days: STRIKE_WINDOW_DAYS,
+                        standing: standing(totals.strikes).to_string(),
+                    },
+                })
+            },
+        )
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to load stats: {}", e)))?;
+
+    Ok(Json(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn streak_counts_consecutive_days_up_to_yesterday() {
+        assert_eq!(current_streak(&[], date(10)), 0);
+        assert_eq!(current_streak(&[date(10), date(9), date(8)], date(10)), 3);
+        assert_eq!(current_streak(&[date(9), date(8), date(6)], date(10)), 2);
+        assert_eq!(current_streak(&[date(8), date(7)], date(10)), 0);
+    }
+
+    #[test]
+    fn standing_follows_strikes() {
+        assert_eq!(standing(0), "good_standing");
+        assert_eq!(standing(STRIKE_WARNING), "warning");
+        assert_eq!(standing(STRIKE_AT_RISK), "at_risk");
+    }
+
+    fn request() -> SavedSearchRequest {
+        SavedSearchRequest {
+            name: "  Ghost signs ".to_string(),
+            query: Some("  ".to_string()),
+            script: Some(" Devanagari".to_string()),
+            style: None,
+            city_id: None,
+            lat: None,
+            lng: None,
+            radius_m: None,
+            notify: None,
+        }
+    }
+
+    #[test]
+    fn normalizes_saved_searches() {
+        let normalized = normalize_saved_search(request()).unwrap();
+        assert_eq!(normalized.name, "Ghost signs");
+        assert_eq!(normalized.query, None);
+        assert_eq!(normalized.script.as_deref(), Some("devanagari"));
+
+        let empty = SavedSearchRequest {
+            script: None,
+            ..request()
+        };
+        assert!(normalize_saved_search(empty).is_err());
+
+        let partial_area = SavedSearchRequest {
+            lat: Some(12.9),
+            lng: Some(77.6),
+            ..request()
+        };
+        assert!(normalize_saved_search(partial_area).is_err());
+
+        let too_wide = SavedSearchRequest {
+            lat: Some(12.9),
+            lng: Some(77.6),
+            radius_m: Some(80_000.0),
+            ..request()
+        };
+        assert!(normalize_saved_search(too_wide).is_err());
+    }
+
+    #[test]
+    fn follows_need_exactly_one_target() {
+        let me = Uuid::now_v7();
+        let tag = normalize_follow(
+            FollowRequest {
+                contributor_tag: Some(" ghost_signs ".to_string()),
+                user_id: None,
+            },
+            me,
+        )
+        .unwrap();
+        assert_eq!(tag.contributor_tag.as_deref(), Some("ghost_signs"));
+
+        let both = FollowRequest {
+            contributor_tag: Some("ghost_signs".to_string()),
+            user_id: Some(Uuid::now_v7()),
+        };
+        assert!(normalize_follow(both, me).is_err());
+
+        let neither = FollowRequest {
+            contributor_tag: None,
+            user_id: None,
+        };
+        assert!(normalize_follow(neither, me).is_err());
+
+        let myself = FollowRequest {
+            contributor_tag: None,
+            user_id: Some(me),
+        };
+        assert!(normalize_follow(myself, me).is_err());
+    }
+}
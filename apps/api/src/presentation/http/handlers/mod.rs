@@ -1,18 +1,40 @@
 pub mod admin;
+pub mod admin_analytics;
+pub mod admin_backups;
 pub mod admin_cities;
 pub mod admin_comments;
+pub mod admin_faults;
+pub mod admin_feature_flags;
+pub mod admin_likes;
+pub mod admin_ml;
+pub mod admin_place_names;
+pub mod admin_print_bundles;
+pub mod admin_rate_limits;
 pub mod admin_region_policies;
+pub mod admin_schedules;
+pub mod admin_system;
+pub mod admin_timeline;
+pub mod admin_workers;
 pub mod analytics;
 pub mod auth;
 pub mod cities;
+pub mod collections;
 pub mod community;
+pub mod credits;
+pub mod digest;
 pub mod docs;
 pub mod gallery;
 pub mod geo;
 pub mod health;
+pub mod images;
+pub mod lettering_context;
 pub mod letterings;
 pub mod me;
+pub mod processing;
+pub mod regions;
 pub mod search;
+pub mod short_links;
 pub mod social;
+pub mod synthetic_storage;
 pub mod upload;
 pub mod ws;
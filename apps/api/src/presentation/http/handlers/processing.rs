@@ -0,0 +1,25 @@
+//! Upload progress, for clients waiting on an upload to finish processing.
+//! The same changes are pushed on the WebSocket feed as `PROCESSING` events.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use uuid::Uuid;
+
+use crate::{
+    infrastructure::repositories::processing_jobs::{self, ProcessingJob},
+    presentation::http::{errors::AppError, state::AppState},
+};
+
+/// Status of each processing stage of an upload.
+pub async fn get_processing(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ProcessingJob>, AppError> {
+    processing_jobs::find(&state.db, id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound("No processing record for this lettering".into()))
+}
@@ -0,0 +1,212 @@
+//! Per-country statistics.
+//!
+//! Countries whose region policy disables discoverability are aggregate-only:
+//! their letterings never appear in item-level endpoints, but counts per
+//! city, style and script are still published here. For those countries
+//! buckets smaller than `MIN_AGGREGATE_BUCKET` are folded into `other` so a
+//! single lettering can't be singled out.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::HeaderMap,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::presentation::http::{
+    errors::AppError, handlers::admin_region_policies::normalize_country_code,
+    locale::request_locales, state::AppState,
+};
+
+const REGION_AGGREGATES_CACHE_TTL: u64 = 900;
+
+/// Smallest bucket listed for an aggregate-only country.
+pub const MIN_AGGREGATE_BUCKET: i64 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct CityCount {
+    pub city_id: Uuid,
+    /// Name in the request's `Accept-Language`.
+    pub name: String,
+    #[ts(type = "number")]
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, TS)]
+#[ts(export)]
+pub struct ValueCount {
+    pub value: String,
+    #[ts(type = "number")]
+    pub count: i64,
+}
+
+/// Buckets of one dimension, largest first. `other` counts letterings in
+/// buckets left out for being too small.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Breakdown<T> {
+    pub items: Vec<T>,
+    #[ts(type = "number")]
+    pub other: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RegionAggregates {
+    pub country_code: String,
+    pub country_name: String,
+    /// Whether individual letterings of this country can be listed.
+    pub discoverable: bool,
+    /// Approved letterings in the country.
+    #[ts(type = "number")]
+    pub total: i64,
+    pub cities: Breakdown<CityCount>,
+    pub styles: Breakdown<ValueCount>,
+    pub scripts: Breakdown<ValueCount>,
+}
+
+/// Keep buckets of at least `min` letterings; the rest only add to `other`.
+fn fold_small<T>(items: Vec<T>, count: impl Fn(&T) -> i64, min: i64) -> Breakdown<T> {
+    let (items, small): (Vec<T>, Vec<T>) = items.into_iter().partition(|item| count(item) >= min);
+    Breakdown {
+        other: small.iter().map(&count).sum(),
+        items,
+    }
+}
+
+/// Counts of approved letterings per value of `column`, skipping values the
+/// classifier was unsure of (`field` in `ml_low_confidence_fields`).
+async fn value_counts(
+    db: &PgPool,
+    country_code: &str,
+    column: &str,
+    field: &str,
+) -> Result<Vec<ValueCount>, sqlx::Error> {
+    sqlx::query_as::<_, ValueCount>(&format!(
+        "SELECT l.{column} AS value, COUNT(*)::bigint AS count
+         FROM letterings l
+         JOIN cities c ON c.id = l.city_id
+         WHERE c.country_code = $1 AND l.status = 'APPROVED'
+           AND l.{column} IS NOT NULL
+           AND NOT ('{field}' = ANY(l.ml_low_confidence_fields))
+         GROUP BY l.{column}
+         ORDER BY count DESC, value"
+    ))
+    .bind(country_code)
+    .fetch_all(db)
+    .await
+}
+
+/// Letterings per city, style and script in one country. Available for
+/// every country, including those where individual letterings are hidden.
+pub async fn get_region_aggregates(
+    State(state): State<AppState>,
+    Path(country_code): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<RegionAggregates>, AppError> {
+    let country_code = normalize_country_code(&country_code)?;
+    let locales = request_locales(&headers);
+
+    let (known, discoverable, country_name) = sqlx::query_as::<_, (bool, bool, String)>(
+        "SELECT EXISTS(SELECT 1 FROM cities WHERE country_code = $1),
+                COALESCE((SELECT discoverability_enabled FROM region_policies WHERE country_code = $1), true),
+                localized_country_name($1, $2)",
+    )
+    .bind(&country_code)
+    .bind(&locales)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    if !known {
+        return Err(AppError::NotFound("Country not found".to_string()));
+    }
+
+    let min_bucket = if discoverable {
+        1
+    } else {
+        MIN_AGGREGATE_BUCKET
+    };
+    let cache_key = format!(
+        "region_aggregates:{}:{}:{}",
+        country_code,
+        discoverable,
+        locales.join(",")
+    );
+    let db = state.db.clone();
+    let aggregates = state
+        .cache
+        .get_or_fetch(&cache_key, REGION_AGGREGATES_CACHE_TTL, || async move {
+            let cities = sqlx::query_as::<_, CityCount>(
+                "SELECT c.id AS city_id, localized_city_name(c.id, c.name, $2) AS name,
+                        COUNT(*)::bigint AS count
+                 FROM letterings l
+                 JOIN cities c ON c.id = l.city_id
+                 WHERE c.country_code = $1 AND l.status = 'APPROVED'
+                 GROUP BY c.id, c.name
+                 ORDER BY count DESC, name",
+            )
+            .bind(&country_code)
+            .bind(&locales)
+            .fetch_all(&db)
+            .await?;
+            let styles = value_counts(&db, &country_code, "ml_style", "style").await?;
+            let scripts = value_counts(&db, &country_code, "ml_script", "script").await?;
+
+            Ok(RegionAggregates {
+                total: cities.iter().map(|c| c.count).sum(),
+                cities: fold_small(cities, |c| c.count, min_bucket),
+                styles: fold_small(styles, |s| s.count, min_bucket),
+                scripts: fold_small(scripts, |s| s.count, min_bucket),
+                country_code,
+                country_name,
+                discoverable,
+            })
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to aggregate region: {}", e)))?;
+
+    Ok(Json(aggregates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(values: &[(&str, i64)]) -> Vec<ValueCount> {
+        values
+            .iter()
+            .map(|(value, count)| ValueCount {
+                value: value.to_string(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn small_buckets_fold_into_other() {
+        let folded = fold_small(
+            counts(&[
+                ("sign-painting", 9),
+                ("neon", 3),
+                ("mosaic", 2),
+                ("carved", 1),
+            ]),
+            |v| v.count,
+            MIN_AGGREGATE_BUCKET,
+        );
+        let kept: Vec<&str> = folded.items.iter().map(|v| v.value.as_str()).collect();
+        assert_eq!(kept, ["sign-painting", "neon"]);
+        assert_eq!(folded.other, 3);
+    }
+
+    #[test]
+    fn discoverable_countries_list_every_bucket() {
+        let folded = fold_small(counts(&[("neon", 2), ("carved", 1)]), |v| v.count, 1);
+        assert_eq!(folded.items.len(), 2);
+        assert_eq!(folded.other, 0);
+    }
+}
@@ -5,11 +5,7 @@ use axum::{
 };
 use serde::Deserialize;
 
-use crate::{
-    domain::lettering::entity::Lettering,
-    infrastructure::repositories::sqlx_lettering_repository::SqlxLetteringRepository,
-    presentation::http::state::AppState,
-};
+use crate::{domain::lettering::entity::Lettering, presentation::http::state::AppState};
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
@@ -17,6 +13,11 @@ pub struct SearchQuery {
     #[serde(default = "default_limit")]
     limit: i64,
     lang: Option<String>,
+    age_ack: Option<bool>,
+    /// Searcher's position; lets nearer letterings rank higher when the
+    /// proximity weight is set.
+    lat: Option<f64>,
+    lng: Option<f64>,
 }
 
 fn default_limit() -> i64 {
@@ -27,12 +28,22 @@ pub async fn search_letterings(
     State(state): State<AppState>,
     Query(params): Query<SearchQuery>,
 ) -> Result<Json<Vec<Lettering>>, StatusCode> {
-    let repository = SqlxLetteringRepository::new(state.db.clone());
-    let results = repository
+    let near = match (params.lat, params.lng) {
+        (Some(lat), Some(lng))
+            if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng) =>
+        {
+            Some((lat, lng))
+        }
+        _ => None,
+    };
+    let results = state
+        .lettering_repo
         .search_with_locale(
             &params.q,
             params.lang.as_deref(),
             params.limit.clamp(1, 100),
+            params.age_ack.unwrap_or(false),
+            near,
         )
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
@@ -0,0 +1,157 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Redirect,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    presentation::http::{errors::AppError, handlers::analytics::has_opted_out, state::AppState},
+};
+
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Longest code that still fits in an `i64`.
+const MAX_CODE_LEN: usize = 11;
+
+/// Share channels the frontend tags links with; anything else is `other`.
+const CHANNELS: &[&str] = &[
+    "copy", "native", "qr", "email", "whatsapp", "telegram", "x", "facebook", "bluesky", "reddit",
+];
+
+pub fn encode_short_code(mut id: i64) -> String {
+    if id <= 0 {
+        return "0".to_string();
+    }
+    let mut code = Vec::new();
+    while id > 0 {
+        code.push(BASE62[(id % 62) as usize]);
+        id /= 62;
+    }
+    code.reverse();
+    String::from_utf8(code).unwrap_or_default()
+}
+
+pub fn decode_short_code(code: &str) -> Option<i64> {
+    if code.is_empty() || code.len() > MAX_CODE_LEN {
+        return None;
+    }
+    code.bytes().try_fold(0i64, |id, b| {
+        let digit = BASE62.iter().position(|&c| c == b)? as i64;
+        id.checked_mul(62)?.checked_add(digit)
+    })
+}
+
+/// Site origin without a trailing slash; empty when unset so links stay
+/// relative to whichever host served them.
+pub(crate) fn public_base(config: &Config) -> &str {
+    config
+        .public_base_url
+        .as_deref()
+        .unwrap_or_default()
+        .trim_end_matches('/')
+}
+
+pub fn short_url(config: &Config, short_id: i64) -> String {
+    format!("{}/s/{}", public_base(config), encode_short_code(short_id))
+}
+
+/// Look up the short URL for a lettering.
+pub(crate) async fn lettering_short_url(
+    state: &AppState,
+    lettering_id: Uuid,
+) -> Result<Option<String>, AppError> {
+    let short_id: Option<i64> = sqlx::query_scalar("SELECT short_id FROM letterings WHERE id = $1")
+        .bind(lettering_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(short_id.map(|id| short_url(&state.config, id)))
+}
+
+pub(crate) fn normalize_channel(raw: Option<&str>) -> String {
+    match raw.map(|c| c.trim().to_ascii_lowercase()) {
+        None => "direct".to_string(),
+        Some(c) if c.is_empty() => "direct".to_string(),
+        Some(c) if CHANNELS.contains(&c.as_str()) => c,
+        Some(_) => "other".to_string(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShortLinkQuery {
+    /// Share channel the link was handed out through.
+    pub c: Option<String>,
+}
+
+pub async fn redirect_short_link(
+    State(state): State<AppState>,
+    Path(code): Path<String>,
+    Query(params): Query<ShortLinkQuery>,
+    headers: HeaderMap,
+) -> Result<Redirect, AppError> {
+    let short_id = decode_short_code(&code)
+        .ok_or_else(|| AppError::NotFound("Short link not found".to_string()))?;
+
+    // Only approved letterings in discoverable regions resolve, so
+    // sequential codes can't be walked to discover content still in
+    // moderation or in aggregate-only regions.
+    let lettering_id: Uuid = sqlx::query_scalar(
+        "SELECT id FROM letterings
+         WHERE short_id = $1 AND status = 'APPROVED' AND city_discoverable(city_id)",
+    )
+    .bind(short_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+    .ok_or_else(|| AppError::NotFound("Short link not found".to_string()))?;
+
+    if !has_opted_out(&headers) {
+        let channel = normalize_channel(params.c.as_deref());
+        if let Err(e) = sqlx::query(
+            "INSERT INTO lettering_share_clicks (lettering_id, date, channel, count)
+             VALUES ($1, $2, $3, 1)
+             ON CONFLICT (lettering_id, date, channel)
+             DO UPDATE SET count = lettering_share_clicks.count + 1",
+        )
+        .bind(lettering_id)
+        .bind(Utc::now().date_naive())
+        .bind(&channel)
+        .execute(&state.db)
+        .await
+        {
+            tracing::warn!(lettering_id = %lettering_id, "Failed to record share click: {}", e);
+        }
+    }
+
+    Ok(Redirect::temporary(&format!(
+        "{}/lettering/{}",
+        public_base(&state.config),
+        lettering_id
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_codes_round_trip() {
+        for id in [1, 61, 62, 238_328, 9_876_543_210, i64::MAX] {
+            assert_eq!(decode_short_code(&encode_short_code(id)), Some(id));
+        }
+        assert_eq!(encode_short_code(238_328), "1000");
+        assert_eq!(decode_short_code("abc-"), None);
+        assert_eq!(decode_short_code("zzzzzzzzzzz"), None);
+    }
+
+    #[test]
+    fn unknown_channels_are_bucketed() {
+        assert_eq!(normalize_channel(None), "direct");
+        assert_eq!(normalize_channel(Some("WhatsApp")), "whatsapp");
+        assert_eq!(normalize_channel(Some("my-newsletter")), "other");
+    }
+}
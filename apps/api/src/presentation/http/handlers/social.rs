@@ -1,16 +1,47 @@
-use crate::domain::social::repository::SocialRepository;
-use crate::infrastructure::security::comment_moderator::assess_comment_content;
+use crate::domain::lettering::errors::DomainError;
+use crate::domain::social::{
+    comment::{CommentCursor, CommentListQuery, CommentSort, REACTION_KINDS},
+    repository::SocialRepository,
+};
+use crate::infrastructure::security::{comment_moderator::assess_comment_content, like_velocity};
 use crate::presentation::http::{
-    errors::AppError, middleware::user::decode_required_user_claims, state::AppState,
+    errors::AppError,
+    handlers::{admin_region_policies::region_policy, letterings::ensure_discoverable},
+    middleware::user::decode_required_user_claims,
+    state::AppState,
 };
 use axum::{
     Json,
-    extract::{Path, State},
-    http::HeaderMap,
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
 };
+use serde::Deserialize;
 use std::str::FromStr;
+use ts_rs::TS;
 use uuid::Uuid;
 
+#[derive(Debug, Deserialize, TS)]
+#[ts(export)]
+pub struct CommentListParams {
+    #[serde(default)]
+    #[ts(optional)]
+    pub sort: Option<CommentSort>,
+    /// `next_cursor` from the previous page.
+    #[ts(optional)]
+    pub cursor: Option<String>,
+    #[serde(default = "default_comment_limit")]
+    #[ts(type = "number", optional)]
+    pub limit: i64,
+    /// Lists the replies under this comment instead of top-level comments.
+    #[ts(optional)]
+    pub parent_id: Option<Uuid>,
+}
+
+fn default_comment_limit() -> i64 {
+    20
+}
+
 fn extract_client_ip(headers: &HeaderMap) -> String {
     headers
         .get("x-forwarded-for")
@@ -48,9 +79,8 @@ fn apply_region_moderation_policy(
                 assessment.review_priority = assessment.review_priority.max(85);
                 assessment.moderated_by = Some("AUTO_MODERATOR".to_string());
                 if assessment.moderation_reason.is_none() {
-                    assessment.moderation_reason = Some(
-                        "Auto-hidden under strict regional moderation policy".to_string(),
-                    );
+                    assessment.moderation_reason =
+                        Some("Auto-hidden under strict regional moderation policy".to_string());
                 }
             } else if assessment.moderation_score >= 25 {
                 assessment.needs_review = true;
@@ -63,17 +93,16 @@ fn apply_region_moderation_policy(
                 }
             }
         }
-        "relaxed" => {
-            if assessment.status == "HIDDEN" && !has_severe && assessment.moderation_score < 90 {
-                assessment.status = "VISIBLE".to_string();
-                assessment.auto_flagged = false;
-                assessment.needs_review = true;
-                assessment.review_priority = assessment.review_priority.max(65);
-                assessment.moderated_by = None;
-                assessment.moderation_reason = Some(
-                    "Visible under relaxed regional policy but queued for review".to_string(),
-                );
-            }
+        "relaxed"
+            if assessment.status == "HIDDEN" && !has_severe && assessment.moderation_score < 90 =>
+        {
+            assessment.status = "VISIBLE".to_string();
+            assessment.auto_flagged = false;
+            assessment.needs_review = true;
+            assessment.review_priority = assessment.review_priority.max(65);
+            assessment.moderated_by = None;
+            assessment.moderation_reason =
+                Some("Visible under relaxed regional policy but queued for review".to_string());
         }
         _ => {}
     }
@@ -87,6 +116,13 @@ pub async fn like_lettering(
     headers: HeaderMap,
 ) -> Result<Json<serde_json::Value>, AppError> {
     let ip = extract_client_ip(&headers);
+    let windows = [
+        (60, state.config.like_velocity_per_minute),
+        (3_600, state.config.like_velocity_per_hour),
+    ];
+    if !like_velocity::allow_like(&state.redis, &ip, &windows).await {
+        return Err(AppError::RateLimited);
+    }
     let (liked, count) = state
         .social_repo
         .toggle_like(id, &ip)
@@ -97,6 +133,39 @@ pub async fn like_lettering(
     ))
 }
 
+/// Bookmark a lettering for the signed-in user: 201 when new, 200 when it
+/// was already bookmarked.
+pub async fn bookmark_lettering(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<serde_json::Value>), AppError> {
+    let claims = decode_required_user_claims(&headers, &state.config.jwt_secret)?;
+    let user_id = Uuid::from_str(&claims.sub)
+        .map_err(|_| AppError::Forbidden("Invalid token subject".to_string()))?;
+
+    let added = state.social_repo.add_bookmark(user_id, id).await?;
+    let status = if added {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    Ok((status, Json(serde_json::json!({ "bookmarked": true }))))
+}
+
+pub async fn remove_bookmark(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let claims = decode_required_user_claims(&headers, &state.config.jwt_secret)?;
+    let user_id = Uuid::from_str(&claims.sub)
+        .map_err(|_| AppError::Forbidden("Invalid token subject".to_string()))?;
+
+    state.social_repo.remove_bookmark(user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub async fn add_comment(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -121,26 +190,51 @@ pub async fn add_comment(
         ));
     }
 
-    let region_policy = sqlx::query_as::<_, (bool, String)>(
-        "SELECT COALESCE(rp.comments_enabled, true) AS comments_enabled,
-                COALESCE(rp.auto_moderation_level, 'standard') AS auto_moderation_level
-         FROM letterings l
-         JOIN cities c ON c.id = l.city_id
-         LEFT JOIN region_policies rp ON rp.country_code = c.country_code
-         WHERE l.id = $1",
+    let country_code: String = sqlx::query_scalar(
+        "SELECT c.country_code FROM letterings l JOIN cities c ON c.id = l.city_id WHERE l.id = $1",
     )
     .bind(id)
     .fetch_optional(&state.db)
     .await
     .map_err(|e| AppError::Internal(e.to_string()))?
     .ok_or_else(|| AppError::NotFound("Lettering not found".to_string()))?;
+    let policy = region_policy(&state, &country_code).await?;
 
-    if !region_policy.0 {
+    if !policy.comments_enabled {
         return Err(AppError::Forbidden(
             "Comments are disabled for this region".to_string(),
         ));
     }
 
+    let parent_id = match body.get("parent_id").filter(|v| !v.is_null()) {
+        Some(v) => Some(
+            v.as_str()
+                .and_then(|s| Uuid::from_str(s).ok())
+                .ok_or_else(|| AppError::BadRequest("Invalid parent_id".into()))?,
+        ),
+        None => None,
+    };
+    if let Some(parent_id) = parent_id {
+        // Replies are one level deep and only under comments people can see.
+        let replyable = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(
+                SELECT 1 FROM comments
+                WHERE id = $1 AND lettering_id = $2 AND parent_id IS NULL
+                  AND status = 'VISIBLE' AND deleted_at IS NULL
+             )",
+        )
+        .bind(parent_id)
+        .bind(id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+        if !replyable {
+            return Err(AppError::BadRequest(
+                "Replies must be to a visible top-level comment on this lettering".into(),
+            ));
+        }
+    }
+
     let ip = extract_client_ip(&headers);
 
     // Rate limit: 1 comment per 30s per user per lettering
@@ -167,9 +261,11 @@ pub async fn add_comment(
 
     let comment = state
         .social_repo
-        .add_comment(id, user_id, content.to_string(), Some(&ip), {
-            let assessment =
-                apply_region_moderation_policy(assess_comment_content(content), &region_policy.1);
+        .add_comment(id, parent_id, user_id, content.to_string(), Some(&ip), {
+            let assessment = apply_region_moderation_policy(
+                assess_comment_content(content),
+                &policy.auto_moderation_level,
+            );
             crate::domain::social::comment::CommentModerationInput {
                 status: assessment.status,
                 moderation_score: assessment.moderation_score,
@@ -186,14 +282,78 @@ pub async fn add_comment(
     Ok(Json(serde_json::to_value(comment).unwrap()))
 }
 
+/// Response header with the `cursor` of the next page of comments; absent
+/// on the last page. The body stays a bare array of comments, as it was
+/// before the listing was paginated.
+pub const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+
 pub async fn get_comments(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Query(params): Query<CommentListParams>,
+) -> Result<Response, AppError> {
+    ensure_discoverable(&state, id).await?;
+    let cursor = match params.cursor.as_deref() {
+        Some(raw) => Some(
+            CommentCursor::decode(raw)
+                .ok_or_else(|| AppError::BadRequest("Invalid cursor".into()))?,
+        ),
+        None => None,
+    };
+    let query = CommentListQuery {
+        parent_id: params.parent_id,
+        sort: params.sort.unwrap_or_default(),
+        cursor,
+        limit: params.limit.clamp(1, 100),
+    };
+    let page = state
+        .social_repo
+        .list_comments(id, &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ValidationError(msg) => AppError::BadRequest(msg),
+            e => AppError::Internal(e.to_string()),
+        })?;
+    let mut response = Json(page.items).into_response();
+    if let Some(cursor) = page
+        .next_cursor
+        .and_then(|c| HeaderValue::from_str(&c).ok())
+    {
+        response.headers_mut().insert(NEXT_CURSOR_HEADER, cursor);
+    }
+    Ok(response)
+}
+
+pub async fn react_to_comment(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(body): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let comments = state
+    let claims = decode_required_user_claims(&headers, &state.config.jwt_secret)?;
+    let user_id = Uuid::from_str(&claims.sub)
+        .map_err(|_| AppError::Forbidden("Invalid token subject".to_string()))?;
+
+    let kind = body
+        .get("kind")
+        .and_then(|v| v.as_str())
+        .filter(|k| REACTION_KINDS.contains(k))
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "kind must be one of: {}",
+                REACTION_KINDS.join(", ")
+            ))
+        })?;
+
+    let (reacted, count) = state
         .social_repo
-        .get_comments(id)
+        .toggle_comment_reaction(id, user_id, kind)
         .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
-    Ok(Json(serde_json::to_value(comments).unwrap()))
+        .map_err(|e| match e {
+            DomainError::NotFound(msg) => AppError::NotFound(msg),
+            e => AppError::Internal(e.to_string()),
+        })?;
+    Ok(Json(
+        serde_json::json!({ "reacted": reacted, "kind": kind, "reactions_count": count }),
+    ))
 }
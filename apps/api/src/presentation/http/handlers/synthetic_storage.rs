@@ -0,0 +1,43 @@
+use crate::{
+    infrastructure::storage::in_memory::InMemoryStorage, presentation::http::errors::AppError,
+};
+use axum::{
+    Router,
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Routes serving `InMemoryStorage` objects; only mounted in `SYNTHETIC_MODE`.
+pub fn router(storage: Arc<InMemoryStorage>) -> Router {
+    Router::new()
+        .route(
+            &format!("{}/{{*key}}", storage.route_prefix()),
+            get(get_object),
+        )
+        .with_state(storage)
+}
+
+/// Signature of a URL from `signed_url`, needed for a private store.
+#[derive(Debug, Deserialize)]
+struct SignedQuery {
+    expires: Option<u64>,
+    signature: Option<String>,
+}
+
+async fn get_object(
+    State(storage): State<Arc<InMemoryStorage>>,
+    Path(key): Path<String>,
+    Query(signed): Query<SignedQuery>,
+) -> Result<Response, AppError> {
+    if !storage.authorizes(&key, signed.expires, signed.signature.as_deref()) {
+        return Err(AppError::Forbidden("Object is private".to_string()));
+    }
+    let (content_type, data) = storage
+        .get(&key)
+        .ok_or_else(|| AppError::NotFound("Object not found".to_string()))?;
+    Ok(([(header::CONTENT_TYPE, content_type)], data).into_response())
+}
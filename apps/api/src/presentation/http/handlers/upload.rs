@@ -1,22 +1,308 @@
 use crate::{
-    domain::lettering::repository::LetteringRepository,
-    infrastructure::queue::redis_queue::MlJob,
+    config::QuotaWindow,
+    domain::lettering::{
+        entity::{Coordinates, LocationPrivacy},
+        repository::LetteringRepository,
+    },
+    infrastructure::{
+        geocoding::ip_geolocation::GeoEvent,
+        imaging::{
+            color_palette::{PALETTE_SIZE, dominant_colors},
+            perceptual_hash::{MAX_INDEXED_DISTANCE, dhash, hash_bands, to_db},
+        },
+        ml::tesseract_service,
+        queue::{jobs::Priority, redis_queue::MlJob},
+        repositories::processing_jobs::{self, Stage, StageStatus},
+        storage::{
+            content_addressed,
+            traits::{ChunkedUpload, StorageService},
+            visibility::original_key,
+        },
+    },
     presentation::http::{
-        errors::AppError, middleware::user::decode_optional_user_claims, state::AppState,
+        errors::AppError,
+        handlers::{admin_region_policies::region_policy, credits, letterings},
+        middleware::user::decode_optional_user_claims,
+        state::AppState,
     },
 };
 use axum::{
     Json,
-    extract::{Multipart, State},
+    extract::{Multipart, State, multipart::Field},
     http::HeaderMap,
 };
-use image::{ImageFormat, imageops::FilterType};
+use chrono::{DateTime, Utc};
+use image::{DynamicImage, ImageFormat, ImageReader, imageops::FilterType};
 use sha2::{Digest, Sha256};
 use sqlx::types::ipnetwork::IpNetwork;
-use std::{io::Cursor, str::FromStr};
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use uuid::Uuid;
 
-fn extract_client_ip(headers: &HeaderMap) -> Option<IpNetwork> {
+/// Matches the request body limit; enforced while streaming so oversized
+/// uploads are cut off without being buffered.
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Enough leading bytes to recognise PNG, JPEG and WebP containers.
+const SNIFF_LEN: usize = 12;
+
+fn sniff_image_type(head: &[u8]) -> Option<&'static str> {
+    match head {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [
+            b'R',
+            b'I',
+            b'F',
+            b'F',
+            _,
+            _,
+            _,
+            _,
+            b'W',
+            b'E',
+            b'B',
+            b'P',
+            ..,
+        ] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Start of the window `upload_daily_limit` is counted over: the last 24
+/// hours, or since local midnight in the uploader's digest time zone, else
+/// `day_boundary_timezone`.
+async fn quota_window_start(
+    state: &AppState,
+    uploader: Option<Uuid>,
+) -> Result<DateTime<Utc>, AppError> {
+    match state.config.upload_quota_window {
+        QuotaWindow::Rolling => Ok(Utc::now() - chrono::Duration::hours(24)),
+        QuotaWindow::LocalDay => sqlx::query_scalar(
+            "SELECT local_day_start(NOW(), COALESCE(
+                 (SELECT timezone FROM digest_subscriptions WHERE user_id = $1),
+                 $2
+             ))",
+        )
+        .bind(uploader)
+        .bind(&state.config.day_boundary_timezone)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string())),
+    }
+}
+
+/// High for signed-in contributors with enough approved uploads, so their
+/// uploads don't wait behind a backlog; normal otherwise or if the count
+/// can't be read.
+async fn ml_priority(state: &AppState, uploader: Option<Uuid>) -> Priority {
+    let threshold = state.config.ml_trusted_contributor_approvals;
+    let Some(user_id) = uploader.filter(|_| threshold > 0) else {
+        return Priority::Normal;
+    };
+    let trusted = sqlx::query_scalar::<_, bool>(
+        "SELECT COUNT(*) >= $2 FROM (
+             SELECT 1 FROM letterings
+             WHERE user_id = $1 AND status = 'APPROVED'
+             LIMIT $2
+         ) approved",
+    )
+    .bind(user_id)
+    .bind(threshold)
+    .fetch_one(&state.db)
+    .await;
+    match trusted {
+        Ok(true) => Priority::High,
+        Ok(false) => Priority::Normal,
+        Err(e) => {
+            tracing::warn!("Failed to check contributor trust for {}: {}", user_id, e);
+            Priority::Normal
+        }
+    }
+}
+
+/// Whether the ML queue is backed up past `ML_MAX_QUEUE_DEPTH`, in which
+/// case the upload is still accepted but reported as delayed. Records the
+/// depth either way, which raises a warning while it is past the limit.
+async fn ml_processing_delayed(state: &AppState) -> bool {
+    let max_depth = state.config.ml_max_queue_depth;
+    if max_depth == 0 {
+        return false;
+    }
+    match state.queue.ml_upload_backlog().await {
+        Ok(depth) => {
+            state
+                .performance
+                .record_ml_queue_depth(depth, max_depth)
+                .await;
+            depth > max_depth
+        }
+        Err(e) => {
+            tracing::warn!("Failed to read ML queue depth: {}", e);
+            false
+        }
+    }
+}
+
+/// The uploaded original, spooled to a temp file for scanning and decoding.
+/// Dropping it removes the temp file and, once `store` has copied it to
+/// storage and unless `keep` was called, the stored original.
+struct StreamedImage {
+    path: PathBuf,
+    original_hash: String,
+    content_type: &'static str,
+    storage: Arc<dyn StorageService>,
+    key: String,
+    stored: bool,
+    keep: bool,
+}
+
+impl StreamedImage {
+    /// Copy the spooled file to the original's key. Only call this once the
+    /// file has passed the virus scan.
+    async fn store(&mut self) -> Result<(), AppError> {
+        let mut upload = self
+            .storage
+            .start_chunked_upload(&self.key, self.content_type)
+            .await?;
+        if let Err(e) = copy_file_chunks(&self.path, upload.as_mut()).await {
+            upload.abort().await;
+            return Err(e);
+        }
+        upload.finish().await?;
+        self.stored = true;
+        Ok(())
+    }
+
+    fn keep(&mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for StreamedImage {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+        if self.stored && !self.keep {
+            let storage = self.storage.clone();
+            let key = std::mem::take(&mut self.key);
+            tokio::spawn(async move {
+                if let Err(e) = storage.delete(&key).await {
+                    tracing::warn!("Failed to remove abandoned original {}: {}", key, e);
+                }
+            });
+        }
+    }
+}
+
+/// Bytes read from the spooled file per storage write.
+const STORE_CHUNK_LEN: usize = 1024 * 1024;
+
+async fn copy_file_chunks(path: &Path, upload: &mut dyn ChunkedUpload) -> Result<(), AppError> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read spooled upload: {}", e)))?;
+    let mut chunk = vec![0; STORE_CHUNK_LEN];
+    loop {
+        let read = file
+            .read(&mut chunk)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to read spooled upload: {}", e)))?;
+        if read == 0 {
+            return Ok(());
+        }
+        upload.write(&chunk[..read]).await?;
+    }
+}
+
+/// Spool the field to `path`, returning its hash and image type.
+async fn copy_image_chunks(
+    field: &mut Field<'_>,
+    path: &Path,
+) -> Result<(String, &'static str), AppError> {
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to spool upload: {}", e)))?;
+    let mut hasher = Sha256::new();
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    let mut content_type = None;
+    let mut size = 0usize;
+
+    loop {
+        let chunk = field
+            .chunk()
+            .await
+            .map_err(|_| AppError::BadRequest("Byte error".into()))?;
+        let done = chunk.is_none();
+        let chunk = chunk.unwrap_or_default();
+
+        size += chunk.len();
+        if size > MAX_IMAGE_BYTES {
+            return Err(AppError::BadRequest(format!(
+                "Image exceeds {} MB",
+                MAX_IMAGE_BYTES / (1024 * 1024)
+            )));
+        }
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to spool upload: {}", e)))?;
+
+        // Reject anything unrecognised as soon as its first bytes are in.
+        if content_type.is_none() {
+            head.extend_from_slice(&chunk);
+            if head.len() >= SNIFF_LEN || done {
+                content_type = Some(
+                    sniff_image_type(&head)
+                        .ok_or_else(|| AppError::BadRequest("Invalid image format".into()))?,
+                );
+            }
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to spool upload: {}", e)))?;
+    let content_type =
+        content_type.ok_or_else(|| AppError::BadRequest("Invalid image format".into()))?;
+    Ok((format!("{:x}", hasher.finalize()), content_type))
+}
+
+/// Stream the `image` field to a temp file, hashing and type-checking it on
+/// the way, so the request body is never held in memory. Nothing reaches
+/// storage until [`StreamedImage::store`].
+async fn stream_image_field(
+    state: &AppState,
+    mut field: Field<'_>,
+    id: Uuid,
+) -> Result<StreamedImage, AppError> {
+    let path = std::env::temp_dir().join(format!("tyl-upload-{}", id));
+    match copy_image_chunks(&mut field, &path).await {
+        Ok((original_hash, content_type)) => Ok(StreamedImage {
+            path,
+            original_hash,
+            content_type,
+            storage: state.storage.clone(),
+            key: original_key(id),
+            stored: false,
+            keep: false,
+        }),
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            Err(e)
+        }
+    }
+}
+
+pub(crate) fn extract_client_ip(headers: &HeaderMap) -> Option<IpNetwork> {
     let raw = headers
         .get("x-forwarded-for")
         .and_then(|v| v.to_str().ok())
@@ -47,6 +333,7 @@ async fn approve_without_ml(
     .execute(&state.db)
     .await
     .map_err(|e| AppError::Internal(format!("Auto-approval failed: {}", e)))?;
+    letterings::invalidate_lettering(state, lettering_id).await;
 
     let _ = state
         .ws_broadcaster
@@ -54,16 +341,83 @@ async fn approve_without_ml(
     Ok(())
 }
 
+/// Fill in `detected_text` with Tesseract for an upload approved without ML.
+/// Runs in the background so the upload response isn't held up by OCR.
+fn spawn_ocr_fallback(state: &AppState, lettering_id: Uuid, image: &DynamicImage) {
+    if !state.ocr.is_active() {
+        return;
+    }
+    let (db, ocr, image) = (state.db.clone(), state.ocr.clone(), image.clone());
+    tokio::spawn(async move {
+        let result = match ocr.detect_text(image).await {
+            Ok(Some(result)) => result,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(lettering_id = %lettering_id, "Tesseract OCR failed: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = sqlx::query(
+            "UPDATE letterings
+             SET detected_text = $1, ml_text_confidence = $2, detected_text_source = $3,
+                 updated_at = NOW()
+             WHERE id = $4 AND COALESCE(detected_text, '') = ''",
+        )
+        .bind(&result.detected_text)
+        .bind(result.confidence)
+        .bind(tesseract_service::SOURCE)
+        .bind(lettering_id)
+        .execute(&db)
+        .await
+        {
+            tracing::warn!(lettering_id = %lettering_id, "Failed to store OCR text: {}", e);
+        }
+    });
+}
+
+/// Closest existing lettering within `near_duplicate_max_distance` bits of
+/// `hash`, with its distance.
+async fn find_near_duplicate(state: &AppState, hash: u64) -> Result<Option<(Uuid, i16)>, AppError> {
+    let max_distance = state
+        .config
+        .near_duplicate_max_distance
+        .min(MAX_INDEXED_DISTANCE);
+    if max_distance == 0 {
+        return Ok(None);
+    }
+
+    sqlx::query_as::<_, (Uuid, i16)>(
+        "SELECT id, bit_count((perceptual_hash # $1)::bit(64))::smallint AS distance
+         FROM letterings
+         WHERE perceptual_hash_bands && $2
+           AND bit_count((perceptual_hash # $1)::bit(64)) <= $3
+         ORDER BY distance ASC, created_at ASC
+         LIMIT 1",
+    )
+    .bind(to_db(hash))
+    .bind(hash_bands(hash))
+    .bind(i64::from(max_distance))
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(format!("Near-duplicate lookup failed: {}", e)))
+}
+
 pub async fn upload_lettering(
     State(state): State<AppState>,
     headers: HeaderMap,
     mut multipart: Multipart,
 ) -> Result<Json<serde_json::Value>, AppError> {
-    let mut image_data = None;
+    let id = Uuid::now_v7();
+    let mut streamed = None;
     let mut contributor = String::new();
     let mut pin = String::new();
     let mut desc = None;
     let mut city_id = None;
+    let mut privacy = None;
+    let mut lat = None;
+    let mut lng = None;
+    let mut credit_name = None;
+    let mut credit_user_id = None;
 
     while let Some(field) = multipart
         .next_field()
@@ -71,18 +425,18 @@ pub async fn upload_lettering(
         .map_err(|_| AppError::BadRequest("Field error".into()))?
     {
         match field.name().unwrap_or("") {
-            "image" => {
-                image_data = Some(
-                    field
-                        .bytes()
-                        .await
-                        .map_err(|_| AppError::BadRequest("Byte error".into()))?,
-                )
+            "image" if streamed.is_none() => {
+                streamed = Some(stream_image_field(&state, field, id).await?)
             }
             "contributor_tag" => contributor = field.text().await.unwrap_or_default(),
             "pin_code" => pin = field.text().await.unwrap_or_default(),
             "description" => desc = Some(field.text().await.unwrap_or_default()),
             "city_id" => city_id = Some(field.text().await.unwrap_or_default()),
+            "location_privacy" => privacy = Some(field.text().await.unwrap_or_default()),
+            "lat" => lat = Some(field.text().await.unwrap_or_default()),
+            "lng" => lng = Some(field.text().await.unwrap_or_default()),
+            "credit_name" => credit_name = Some(field.text().await.unwrap_or_default()),
+            "credit_user_id" => credit_user_id = Some(field.text().await.unwrap_or_default()),
             _ => {}
         }
     }
@@ -106,37 +460,84 @@ pub async fn upload_lettering(
         }
     });
 
-    let data = image_data.ok_or(AppError::BadRequest("Missing image".into()))?;
+    let privacy = match privacy.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => LocationPrivacy::parse(p).ok_or_else(|| {
+            AppError::BadRequest("location_privacy must be exact, fuzzed or city".into())
+        })?,
+        None => LocationPrivacy::Exact,
+    };
+
+    let parse_coord = |value: Option<String>| match value.as_deref().map(str::trim) {
+        Some(v) if !v.is_empty() => v
+            .parse::<f64>()
+            .map(Some)
+            .map_err(|_| AppError::BadRequest("lat and lng must be numbers".into())),
+        _ => Ok(None),
+    };
+    let exact_point = match (parse_coord(lat)?, parse_coord(lng)?) {
+        (Some(lat), Some(lng)) => {
+            if !Coordinates::new_point(lng, lat).is_valid() {
+                return Err(AppError::BadRequest("lat/lng out of range".into()));
+            }
+            Some((lng, lat))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(AppError::BadRequest(
+                "lat and lng must be given together".into(),
+            ));
+        }
+    };
+
+    let mut streamed = streamed.ok_or(AppError::BadRequest("Missing image".into()))?;
 
     let city_id = city_id
         .as_deref()
         .filter(|s| !s.trim().is_empty())
         .and_then(|s| Uuid::parse_str(s).ok())
-        .ok_or_else(|| AppError::BadRequest("city_id is required and must be a valid UUID".into()))?;
+        .ok_or_else(|| {
+            AppError::BadRequest("city_id is required and must be a valid UUID".into())
+        })?;
 
-    let upload_allowed = sqlx::query_scalar::<_, Option<bool>>(
-        "SELECT COALESCE(rp.uploads_enabled, true)
-         FROM cities c
-         LEFT JOIN region_policies rp ON rp.country_code = c.country_code
-         WHERE c.id = $1",
-    )
-    .bind(city_id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|e| AppError::Internal(e.to_string()))?
-    .flatten()
-    .ok_or_else(|| AppError::BadRequest("City not found".to_string()))?;
+    let country_code: String = sqlx::query_scalar("SELECT country_code FROM cities WHERE id = $1")
+        .bind(city_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::BadRequest("City not found".to_string()))?;
 
-    if !upload_allowed {
+    if !region_policy(&state, &country_code).await?.uploads_enabled {
         return Err(AppError::Forbidden(
             "Uploads are disabled for this region".to_string(),
         ));
     }
 
+    let uploader = decode_optional_user_claims(&headers, &state.config.jwt_secret)
+        .and_then(|claims| Uuid::parse_str(&claims.sub).ok());
+    let credit = credits::resolve_upload_credit(
+        &state,
+        credit_name.as_deref(),
+        credit_user_id.as_deref(),
+        uploader,
+    )
+    .await?;
+
+    let limit = state.config.upload_daily_limit;
+    if limit > 0 {
+        let since = quota_window_start(&state, uploader).await?;
+        let uploaded = state
+            .lettering_repo
+            .count_by_contributor_since(&contributor, since)
+            .await?;
+        if uploaded >= i64::from(limit) {
+            return Err(AppError::RateLimited);
+        }
+    }
+
     // Virus Scanning
     let is_safe = state
         .virus_scanner
-        .scan(&data)
+        .scan_file(&streamed.path)
         .await
         .map_err(|e| AppError::Internal(format!("Scanner failure: {}", e)))?;
 
@@ -146,10 +547,36 @@ pub async fn upload_lettering(
         ));
     }
 
-    let id = Uuid::now_v7();
-    let img = image::load_from_memory(&data)
+    let original_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (SELECT 1 FROM letterings WHERE original_hash = $1)",
+    )
+    .bind(&streamed.original_hash)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    if original_exists {
+        return Err(AppError::BadRequest(
+            "This exact image has already been archived".into(),
+        ));
+    }
+
+    let img = ImageReader::open(&streamed.path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| AppError::Internal(format!("Failed to read spooled upload: {}", e)))?
+        .decode()
         .map_err(|_| AppError::BadRequest("Invalid image format".into()))?;
 
+    let perceptual_hash = dhash(&img);
+    let near_duplicate = find_near_duplicate(&state, perceptual_hash).await?;
+    if let Some((duplicate_of, distance)) = near_duplicate {
+        tracing::info!(
+            lettering_id = %id,
+            duplicate_of = %duplicate_of,
+            distance,
+            "Near-duplicate upload held for moderator review"
+        );
+    }
+
     // Process Original
     let mut buf = Cursor::new(Vec::new());
     img.resize(1200, 1200, FilterType::Lanczos3)
@@ -174,29 +601,43 @@ pub async fn upload_lettering(
         ));
     }
 
-    let image_url = state
-        .storage
-        .upload(
-            &format!("letterings/{}.webp", id),
-            image_bytes,
-            "image/webp",
-        )
-        .await?;
+    // Only clean, new content reaches storage, so nothing is written for an
+    // upload turned away above.
+    streamed.store().await?;
+
+    // Served images are content-addressed and go to the bucket nearest the
+    // city's country, in its private twin until the lettering is approved.
+    // The original is keyed by lettering id alone and stays in the primary
+    // private bucket.
+    let image_url = content_addressed::put(
+        &state.db,
+        state.storage.as_ref(),
+        image_bytes,
+        "webp",
+        "image/webp",
+        Some(&country_code),
+        true,
+    )
+    .await?;
 
-    // Generate Thumbnail
+    // Generate Thumbnail, and the colour palette from it
     let mut thumb_buf = Cursor::new(Vec::new());
-    img.thumbnail(400, 400)
+    let thumbnail = img.thumbnail(400, 400);
+    let color_palette = dominant_colors(&thumbnail, PALETTE_SIZE);
+    thumbnail
         .write_to(&mut thumb_buf, ImageFormat::WebP)
         .map_err(|e| AppError::Internal(format!("Failed to encode thumbnail to WebP: {}", e)))?;
 
-    let thumb_url = state
-        .storage
-        .upload(
-            &format!("thumbs/{}.webp", id),
-            thumb_buf.into_inner(),
-            "image/webp",
-        )
-        .await?;
+    let thumb_url = content_addressed::put(
+        &state.db,
+        state.storage.as_ref(),
+        thumb_buf.into_inner(),
+        "webp",
+        "image/webp",
+        Some(&country_code),
+        true,
+    )
+    .await?;
 
     // let (mut lng, mut lat) = crate::infrastructure::geocoding::coordinates_for_pincode(&pin);
     // if (lng - 77.5946).abs() < 0.0001 && (lat - 12.9716).abs() < 0.0001 {
@@ -204,7 +645,7 @@ pub async fn upload_lettering(
     //         .fetch_optional(&state.db)
     //         .await
     //         .unwrap_or(None);
-    
+
     //     if let Some(row) = city_row {
     //         if let (Some(c_lat), Some(c_lng)) = (row.center_lat, row.center_lng) {
     //             lat = c_lat;
@@ -213,78 +654,189 @@ pub async fn upload_lettering(
     //     }
     // }
     // Fetch city coordinates for geolocation
-    let city_coords = sqlx::query_as::<_, (f64, f64)>(
-            "SELECT center_lng, center_lat FROM cities WHERE id = $1"
-        )
-        .bind(city_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| {
-            tracing::error!("Database error fetching city: {}", e);
-            AppError::Internal(format!("Failed to fetch city coordinates: {}", e))
-        })?;
-    let final_lng = city_coords.0; 
-    let final_lat = city_coords.1;
+    let city_coords =
+        sqlx::query_as::<_, (f64, f64)>("SELECT center_lng, center_lat FROM cities WHERE id = $1")
+            .bind(city_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database error fetching city: {}", e);
+                AppError::Internal(format!("Failed to fetch city coordinates: {}", e))
+            })?;
+    // Without a pinned point the lettering sits at the city centre, as before.
+    let exact_point = exact_point.unwrap_or(city_coords);
+    let (final_lng, final_lat) = privacy.public_point(id, exact_point, city_coords);
 
     let lettering = crate::domain::lettering::entity::Lettering {
-            id,
-            city_id,
-            contributor_tag: contributor,
-            image_url: image_url.clone(),
-            thumbnail_urls: crate::domain::lettering::entity::ThumbnailUrls {
-                small: thumb_url.clone(),
-                medium: thumb_url.clone(),
-                large: image_url.clone(),
-            },
-            location: crate::domain::lettering::entity::Coordinates {
-                r#type: "Point".into(),
-                coordinates: vec![final_lng, final_lat],
-            },
-            pin_code: pin,
-            description: desc,
-            image_hash: Some(image_hash),
-            uploaded_by_ip: extract_client_ip(&headers),
-            ..Default::default()
-        };
+        id,
+        city_id,
+        contributor_tag: contributor,
+        image_url: image_url.clone(),
+        thumbnail_urls: crate::domain::lettering::entity::ThumbnailUrls {
+            small: thumb_url.clone(),
+            medium: thumb_url.clone(),
+            large: image_url.clone(),
+        },
+        location: crate::domain::lettering::entity::Coordinates {
+            r#type: "Point".into(),
+            coordinates: vec![final_lng, final_lat],
+        },
+        pin_code: pin,
+        description: desc,
+        image_hash: Some(image_hash),
+        uploaded_by_ip: extract_client_ip(&headers),
+        ..Default::default()
+    };
 
     state.lettering_repo.create(&lettering).await?;
 
+    sqlx::query(
+        "UPDATE letterings
+         SET original_hash = $1, perceptual_hash = $2, perceptual_hash_bands = $3,
+             near_duplicate_of = $4, near_duplicate_distance = $5, ml_color_palette = $6,
+             location_privacy = $7, exact_location = ST_GeogFromText($8)
+         WHERE id = $9",
+    )
+    .bind(&streamed.original_hash)
+    .bind(to_db(perceptual_hash))
+    .bind(hash_bands(perceptual_hash))
+    .bind(near_duplicate.map(|(duplicate_of, _)| duplicate_of))
+    .bind(near_duplicate.map(|(_, distance)| distance))
+    .bind(serde_json::json!(color_palette))
+    .bind(privacy.as_str())
+    .bind(format!("POINT({} {})", exact_point.0, exact_point.1))
+    .bind(id)
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to record upload metadata: {}", e)))?;
+    if let Some(credit) = &credit {
+        credits::record_upload_credit(&state, id, credit).await?;
+    }
+    streamed.keep();
+
+    state.ip_geolocator.spawn_annotate(
+        GeoEvent::Upload,
+        Some(id),
+        lettering.uploaded_by_ip.map(|ip| ip.ip()),
+    );
+
     // Attach user ownership if authenticated
-    if let Some(claims) = decode_optional_user_claims(&headers, &state.config.jwt_secret) {
-        if let Ok(user_id) = Uuid::parse_str(&claims.sub) {
-            sqlx::query("UPDATE letterings SET user_id = $1 WHERE id = $2")
-                .bind(user_id)
-                .bind(id)
-                .execute(&state.db)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Failed to attach user ownership for lettering {}: {}", id, e);
-                    AppError::Internal("Failed to link user ownership".into())
-                })?;
-        }
+    if let Some(claims) = decode_optional_user_claims(&headers, &state.config.jwt_secret)
+        && let Ok(user_id) = Uuid::parse_str(&claims.sub)
+    {
+        sqlx::query("UPDATE letterings SET user_id = $1 WHERE id = $2")
+            .bind(user_id)
+            .bind(id)
+            .execute(&state.db)
+            .await
+            .map_err(|e| {
+                tracing::error!(
+                    "Failed to attach user ownership for lettering {}: {}",
+                    id,
+                    e
+                );
+                AppError::Internal("Failed to link user ownership".into())
+            })?;
     }
 
+    // The scan and thumbnails are done by now; ML is tracked from here on.
+    let ml_stage = if state.config.enable_ml_processing {
+        StageStatus::Pending
+    } else {
+        StageStatus::Skipped
+    };
+    if let Err(e) = processing_jobs::create(&state.db, id, ml_stage).await {
+        tracing::warn!("Failed to record processing status for {}: {}", id, e);
+    }
+
+    // Near-duplicates still go through ML but stay PENDING until a moderator
+    // looks at them.
+    let held_for_review = near_duplicate.is_some();
+    let mut processing_delayed = false;
     if state.config.enable_ml_processing {
+        let priority = ml_priority(&state, uploader).await;
         if let Err(err) = state
             .queue
-            .enqueue_ml_job(MlJob {
-                lettering_id: id,
-                image_url,
-            })
+            .enqueue_ml_job_with_priority(
+                MlJob {
+                    lettering_id: id,
+                    image_url,
+                    reprocess_run_id: None,
+                },
+                priority,
+            )
             .await
         {
             tracing::warn!("ML queue enqueue failed for {}: {}", id, err);
-            // Fallback: approve without ML processing with empty detected text
-            approve_without_ml(&state, id, "").await?;
-            return Ok(Json(serde_json::json!({ "id": id, "status": "approved", "message": "Uploaded successfully but ML processing unavailable" })));
+            if let Err(e) = processing_jobs::set_stage(
+                &state.db,
+                &state.ws_broadcaster,
+                &[id],
+                Stage::Ml,
+                StageStatus::Skipped,
+                Some("ML queue unavailable"),
+            )
+            .await
+            {
+                tracing::warn!("Failed to record processing status for {}: {}", id, e);
+            }
+            if !held_for_review {
+                // Fallback: approve without ML processing with empty detected text
+                approve_without_ml(&state, id, "").await?;
+                spawn_ocr_fallback(&state, id, &img);
+                return Ok(Json(
+                    serde_json::json!({ "id": id, "status": "approved", "message": "Uploaded successfully but ML processing unavailable" }),
+                ));
+            }
+        } else {
+            processing_delayed = ml_processing_delayed(&state).await;
         }
-    } else {
+    } else if !held_for_review {
         // ML processing is disabled - approve immediately with empty detected text
         approve_without_ml(&state, id, "").await?;
-        return Ok(Json(serde_json::json!({ "id": id, "status": "approved", "message": "Uploaded successfully (ML processing disabled)" })));
+        spawn_ocr_fallback(&state, id, &img);
+        return Ok(Json(
+            serde_json::json!({ "id": id, "status": "approved", "message": "Uploaded successfully (ML processing disabled)" }),
+        ));
+    }
+
+    if held_for_review {
+        return Ok(Json(
+            serde_json::json!({ "id": id, "status": "pending_review", "message": "Uploaded; held for review as a possible duplicate of an existing lettering" }),
+        ));
+    }
+
+    if processing_delayed {
+        return Ok(Json(
+            serde_json::json!({ "id": id, "status": "processing", "processing_delayed": true, "message": "Uploaded; ML processing is running behind and may take longer than usual" }),
+        ));
     }
 
     Ok(Json(
         serde_json::json!({ "id": id, "status": "processing" }),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::sniff_image_type;
+
+    #[test]
+    fn sniffs_supported_containers() {
+        assert_eq!(
+            sniff_image_type(b"\x89PNG\r\n\x1a\n\0\0\0\0"),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_image_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            sniff_image_type(b"RIFF\0\0\0\0WEBPVP8 "),
+            Some("image/webp")
+        );
+        assert_eq!(sniff_image_type(b"RIFF\0\0\0\0WAVEfmt "), None);
+        assert_eq!(sniff_image_type(b"GIF89a"), None);
+        assert_eq!(sniff_image_type(b""), None);
+    }
+}
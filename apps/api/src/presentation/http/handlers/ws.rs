@@ -1,21 +1,214 @@
+//! Live feed of new uploads over WebSocket.
+//!
+//! On shutdown [`WsDrain::begin`] closes every open feed with a close frame
+//! carrying a reconnect hint, and `main` waits for the clients to acknowledge
+//! it, up to the drain timeout, before the process exits.
+
 use crate::presentation::http::state::AppState;
 use axum::{
     extract::{
         State,
-        ws::{Message, WebSocketUpgrade},
+        ws::{CloseFrame, Message, WebSocketUpgrade},
     },
-    response::IntoResponse,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
 };
 use futures_util::{SinkExt, StreamExt};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::sync::{Notify, watch};
+
+/// "Service Restart": the server is going away and clients should reconnect
+/// after a moment.
+const CLOSE_SERVICE_RESTART: u16 = 1012;
+
+/// Tracks open feed connections and tells them to close on shutdown.
+pub struct WsDrain {
+    draining: watch::Sender<bool>,
+    open: AtomicUsize,
+    closed: Notify,
+    accepted: AtomicU64,
+    reconnect_hint_ms: u64,
+}
+
+impl WsDrain {
+    pub fn new(reconnect_hint_ms: u64) -> Self {
+        Self {
+            draining: watch::Sender::new(false),
+            open: AtomicUsize::new(0),
+            closed: Notify::new(),
+            accepted: AtomicU64::new(0),
+            reconnect_hint_ms: reconnect_hint_ms.max(1),
+        }
+    }
+
+    /// Close every open connection and refuse new ones.
+    pub fn begin(&self) {
+        self.draining.send_replace(true);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        *self.draining.borrow()
+    }
+
+    pub fn open_connections(&self) -> usize {
+        self.open.load(Ordering::Acquire)
+    }
+
+    /// Wait until every connection has closed or `timeout` has passed, and
+    /// return how many are still open.
+    pub async fn wait(&self, timeout: Duration) -> usize {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let closed = self.closed.notified();
+            tokio::pin!(closed);
+            closed.as_mut().enable();
+            let open = self.open_connections();
+            if open == 0 {
+                return 0;
+            }
+            if tokio::time::timeout_at(deadline, closed).await.is_err() {
+                return self.open_connections();
+            }
+        }
+    }
+
+    fn track(self: &Arc<Self>) -> Connection {
+        self.open.fetch_add(1, Ordering::AcqRel);
+        Connection {
+            drain: self.clone(),
+            seq: self.accepted.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    async fn drained(&self) {
+        let mut draining = self.draining.subscribe();
+        let _ = draining.wait_for(|d| *d).await;
+    }
+}
 
-pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> impl IntoResponse {
-    ws.on_upgrade(|socket| async move {
-        let (mut sender, _) = socket.split();
+/// An open connection, counted until dropped.
+struct Connection {
+    drain: Arc<WsDrain>,
+    seq: u64,
+}
+
+impl Connection {
+    /// Between one and two times the configured hint, spread by the order
+    /// connections were accepted in so clients don't all come back at once.
+    fn reconnect_after_ms(&self) -> u64 {
+        reconnect_after_ms(self.drain.reconnect_hint_ms, self.seq)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if self.drain.open.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.drain.closed.notify_waiters();
+        }
+    }
+}
+
+fn reconnect_after_ms(hint_ms: u64, seq: u64) -> u64 {
+    // Knuth's multiplicative hash scatters consecutive connections across
+    // the window.
+    hint_ms + seq.wrapping_mul(2_654_435_761) % hint_ms
+}
+
+fn close_frame(reconnect_after_ms: u64) -> CloseFrame {
+    CloseFrame {
+        code: CLOSE_SERVICE_RESTART,
+        reason: format!(r#"{{"reconnect_after_ms":{}}}"#, reconnect_after_ms).into(),
+    }
+}
+
+pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    let drain = state.ws_drain.clone();
+    if drain.is_draining() {
+        let retry_after = drain.reconnect_hint_ms.div_ceil(1000).to_string();
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, retry_after)],
+        )
+            .into_response();
+    }
+
+    ws.on_upgrade(move |socket| async move {
+        let connection = drain.track();
+        let (mut sender, mut receiver) = socket.split();
         let mut rx = state.ws_broadcaster.subscribe();
-        while let Ok(msg) = rx.recv().await {
-            if sender.send(Message::Text(msg.into())).await.is_err() {
-                break;
+        let drained = drain.drained();
+        tokio::pin!(drained);
+        loop {
+            tokio::select! {
+                msg = rx.recv() => {
+                    let Ok(msg) = msg else { break };
+                    if sender.send(Message::Text(msg.into())).await.is_err() {
+                        break;
+                    }
+                }
+                incoming = receiver.next() => match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+                _ = &mut drained => {
+                    let frame = close_frame(connection.reconnect_after_ms());
+                    if sender.send(Message::Close(Some(frame))).await.is_ok() {
+                        // Wait for the client's close frame so the handshake
+                        // completes; `main` stops waiting at the drain timeout.
+                        while let Some(Ok(msg)) = receiver.next().await {
+                            if matches!(msg, Message::Close(_)) {
+                                break;
+                            }
+                        }
+                    }
+                    break;
+                }
             }
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconnect_hints_are_spread_over_one_to_two_times_the_hint() {
+        let hints: Vec<u64> = (0..100).map(|seq| reconnect_after_ms(1000, seq)).collect();
+        assert!(hints.iter().all(|h| (1000..2000).contains(h)));
+        assert_ne!(hints[0], hints[1]);
+        assert_eq!(reconnect_after_ms(1, 7), 1);
+    }
+
+    #[test]
+    fn close_frame_fits_the_reason_limit() {
+        let frame = close_frame(u64::MAX);
+        assert_eq!(frame.code, CLOSE_SERVICE_RESTART);
+        assert!(frame.reason.as_str().len() <= 123);
+        assert!(frame.reason.as_str().contains("reconnect_after_ms"));
+    }
+
+    #[tokio::test]
+    async fn wait_returns_once_connections_close() {
+        let drain = Arc::new(WsDrain::new(1000));
+        assert_eq!(drain.wait(Duration::from_millis(10)).await, 0);
+
+        let connection = drain.track();
+        assert_eq!(drain.wait(Duration::from_millis(10)).await, 1);
+
+        drain.begin();
+        assert!(drain.is_draining());
+        let closer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(connection);
+        });
+        assert_eq!(drain.wait(Duration::from_secs(5)).await, 0);
+        closer.await.unwrap();
+    }
+}
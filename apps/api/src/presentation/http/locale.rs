@@ -0,0 +1,89 @@
+//! Display-name locale for city and country names.
+//!
+//! `Accept-Language` is turned into a fallback chain of supported locales,
+//! most preferred first: each requested tag, then its base language, then
+//! English. Queries pass the chain to `localized_city_name` /
+//! `localized_country_name`, which fall back to the canonical name.
+
+use axum::http::{HeaderMap, header};
+
+/// Locales place names are translated into.
+pub const SUPPORTED_LOCALES: &[&str] = &["en", "hi", "kn", "ta", "bn", "ar"];
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Lower-cased `xx` or `xx-yy` tag, if it's one we translate into.
+pub fn normalize_locale(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-").to_ascii_lowercase();
+    let base = tag.split('-').next().unwrap_or_default();
+    SUPPORTED_LOCALES.contains(&base).then_some(tag)
+}
+
+/// Fallback chain for an `Accept-Language` value.
+pub fn locale_chain(accept_language: Option<&str>) -> Vec<String> {
+    let mut ranked: Vec<(f32, usize, &str)> = accept_language
+        .unwrap_or_default()
+        .split(',')
+        .enumerate()
+        .filter_map(|(i, part)| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let q = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && tag != "*" && q > 0.0).then_some((q, i, tag))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut chain: Vec<String> = Vec::new();
+    for (_, _, tag) in ranked {
+        let Some(tag) = normalize_locale(tag) else {
+            continue;
+        };
+        let base = tag.split('-').next().unwrap_or_default().to_string();
+        for locale in [tag, base] {
+            if !chain.contains(&locale) {
+                chain.push(locale);
+            }
+        }
+    }
+    if !chain.iter().any(|l| l == DEFAULT_LOCALE) {
+        chain.push(DEFAULT_LOCALE.to_string());
+    }
+    chain
+}
+
+/// Fallback chain for a request.
+pub fn request_locales(headers: &HeaderMap) -> Vec<String> {
+    locale_chain(
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_fallback_chain_from_accept_language() {
+        assert_eq!(locale_chain(None), vec!["en"]);
+        assert_eq!(
+            locale_chain(Some("kn-IN, hi;q=0.8, fr;q=0.9, en;q=0.5")),
+            vec!["kn-in", "kn", "hi", "en"]
+        );
+        assert_eq!(
+            locale_chain(Some("ta;q=0.4, bn_BD;q=0.7, ar;q=0")),
+            vec!["bn-bd", "bn", "ta", "en"]
+        );
+        assert_eq!(locale_chain(Some("*, hi;q=abc")), vec!["en"]);
+    }
+
+    #[test]
+    fn normalizes_supported_locales() {
+        assert_eq!(normalize_locale(" HI_in ").as_deref(), Some("hi-in"));
+        assert_eq!(normalize_locale("ar").as_deref(), Some("ar"));
+        assert_eq!(normalize_locale("fr"), None);
+    }
+}
@@ -0,0 +1,93 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    config::AttestationMode,
+    infrastructure::security::app_attestation::{AttestationOutcome, ClientPlatform},
+    presentation::http::{errors::AppError, state::AppState},
+};
+
+/// `ios` or `android` from the apps; anything else is treated as web. It
+/// only picks which check runs.
+pub const PLATFORM_HEADER: &str = "x-client-platform";
+
+/// DeviceCheck device token (iOS) or Play Integrity token (Android).
+pub const ATTESTATION_HEADER: &str = "x-app-attestation";
+
+/// Cloudflare Turnstile token, from browsers.
+pub const WEB_CHALLENGE_HEADER: &str = "x-web-challenge";
+
+/// Checks every upload against the strictest configured attestation mode.
+/// An app upload passes with its platform's attestation token; anything else
+/// needs a Turnstile token. `monitor` only logs failures; `enforce` refuses
+/// the upload with 403, or with 503 when the provider can't be asked
+/// (misconfigured or unreachable), so an upload is never let through
+/// unchecked.
+pub async fn app_attestation_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let mode = state.attestation.mode();
+    if mode == AttestationMode::Off {
+        return Ok(next.run(request).await);
+    }
+    let enforced = mode == AttestationMode::Enforce;
+
+    let headers = request.headers();
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    };
+    // An app without its attestation token is challenged like a browser, so
+    // naming a platform never skips a check.
+    let (platform, token) = match ClientPlatform::from_header(
+        headers.get(PLATFORM_HEADER).and_then(|v| v.to_str().ok()),
+    ) {
+        platform @ (ClientPlatform::Ios | ClientPlatform::Android)
+            if header(ATTESTATION_HEADER).is_some() =>
+        {
+            (platform, header(ATTESTATION_HEADER))
+        }
+        _ => (ClientPlatform::Web, header(WEB_CHALLENGE_HEADER)),
+    };
+
+    let failure = match token {
+        None => "missing attestation token".to_string(),
+        Some(token) => match state.attestation.verify(platform, token).await {
+            Ok(AttestationOutcome::Verified) => return Ok(next.run(request).await),
+            Ok(AttestationOutcome::Rejected(reason)) => reason,
+            Err(e) => {
+                tracing::warn!(
+                    platform = platform.as_str(),
+                    enforced,
+                    "App attestation unavailable: {}",
+                    e
+                );
+                if enforced {
+                    return Err(AppError::ExternalService(
+                        "App attestation unavailable".to_string(),
+                    ));
+                }
+                return Ok(next.run(request).await);
+            }
+        },
+    };
+
+    tracing::warn!(
+        platform = platform.as_str(),
+        enforced,
+        "App attestation failed: {}",
+        failure
+    );
+    if enforced {
+        return Err(AppError::Forbidden("App attestation failed".to_string()));
+    }
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,92 @@
+//! Conditional GETs for read endpoints the mobile app polls.
+//!
+//! Successful responses get an `ETag` hashed from their body. A request
+//! whose `If-None-Match` lists that tag gets an empty `304 Not Modified`
+//! instead, so polling a page that has not changed downloads only headers.
+//! The handler still runs; this saves bandwidth, not work.
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::Request,
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Strong entity tag for a response body.
+pub fn etag_for(body: &[u8]) -> String {
+    let digest = format!("{:x}", Sha256::digest(body));
+    format!("\"{}\"", &digest[..32])
+}
+
+/// Whether `If-None-Match` values list `etag`, comparing weakly as the
+/// header calls for.
+fn none_match(conditions: &[HeaderValue], etag: &str) -> bool {
+    conditions
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+pub async fn etag_middleware(req: Request, next: Next) -> Response {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return next.run(req).await;
+    }
+    let conditions: Vec<HeaderValue> = req
+        .headers()
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .cloned()
+        .collect();
+
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK || response.headers().contains_key(header::ETAG) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let etag = etag_for(&bytes);
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+    if none_match(&conditions, &etag) {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        parts.headers.remove(header::CONTENT_TYPE);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_none_match_lists_are_compared_weakly() {
+        let etag = etag_for(b"{}");
+        assert_eq!(etag.len(), 34);
+        assert_eq!(etag, etag_for(b"{}"));
+        assert_ne!(etag, etag_for(b"[]"));
+
+        let header = |value: &str| vec![HeaderValue::from_str(value).unwrap()];
+        assert!(none_match(&header(&etag), &etag));
+        assert!(none_match(
+            &header(&format!("\"other\", W/{}", etag)),
+            &etag
+        ));
+        assert!(none_match(&header("*"), &etag));
+        assert!(!none_match(&header("\"other\""), &etag));
+        assert!(!none_match(&[], &etag));
+    }
+}
@@ -1,5 +1,8 @@
 pub mod admin;
+pub mod app_attestation;
+pub mod etag;
 pub mod logging;
 pub mod rate_limit;
 pub mod request_id;
+pub mod response_cache;
 pub mod user;
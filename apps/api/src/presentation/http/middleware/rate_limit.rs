@@ -1,12 +1,40 @@
 use axum::{
-    extract::{Request, State},
+    extract::{MatchedPath, Request, State},
     http::{HeaderMap, StatusCode},
     middleware::Next,
     response::Response,
 };
 use redis::AsyncCommands;
+use sqlx::PgPool;
 
-use crate::presentation::http::state::AppState;
+use crate::{config::Config, presentation::http::state::AppState};
+
+/// Routes wrapped by `rate_limit_middleware`, identified by their matched path.
+pub const RATE_LIMITED_ROUTES: &[&str] = &["/api/v1/letterings/upload", "/api/v1/analytics/events"];
+
+/// Redis hash of route -> per-IP daily limit, mirrored from `rate_limit_overrides`.
+pub const OVERRIDES_KEY: &str = "rate_limit_overrides";
+
+/// Analytics counters are only kept for a week.
+pub const STATS_RETENTION_DAYS: i64 = 7;
+
+const STATS_TTL_SECONDS: i64 = (STATS_RETENTION_DAYS + 1) * 86_400;
+
+pub fn stats_key(date: &str) -> String {
+    format!("rate_limit_stats:{}", date)
+}
+
+pub fn offenders_key(route: &str, date: &str) -> String {
+    format!("rate_limit_offenders:{}:{}", route, date)
+}
+
+/// Limit applied to `route` when no override has been set.
+pub fn default_limit(config: &Config, route: &str) -> u32 {
+    match route {
+        "/api/v1/analytics/events" => config.rate_limit_analytics_events_per_ip,
+        _ => config.rate_limit_uploads_per_ip,
+    }
+}
 
 fn extract_client_ip(headers: &HeaderMap) -> String {
     headers
@@ -26,17 +54,35 @@ fn extract_client_ip(headers: &HeaderMap) -> String {
         .to_string()
 }
 
+/// Copy persisted overrides into Redis so they survive a cache flush.
+pub async fn sync_overrides(db: &PgPool, redis: &redis::Client) -> anyhow::Result<usize> {
+    let rows =
+        sqlx::query_as::<_, (String, i32)>("SELECT route, limit_per_day FROM rate_limit_overrides")
+            .fetch_all(db)
+            .await?;
+
+    let mut conn = redis.get_multiplexed_async_connection().await?;
+    let _: () = conn.del(OVERRIDES_KEY).await?;
+    for (route, limit) in &rows {
+        let _: () = conn.hset(OVERRIDES_KEY, route, *limit).await?;
+    }
+    Ok(rows.len())
+}
+
 pub async fn rate_limit_middleware(
     State(state): State<AppState>,
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
     let ip = extract_client_ip(request.headers());
-    if state.config.rate_limit_uploads_per_ip == 0 || ip == "127.0.0.1" || ip == "::1" {
+    if ip == "127.0.0.1" || ip == "::1" {
         return Ok(next.run(request).await);
     }
-    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
-    let key = format!("rate_limit:{}:{}", ip, date);
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
 
     let mut conn = state
         .redis
@@ -44,6 +90,18 @@ pub async fn rate_limit_middleware(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    let override_limit: Option<u32> = conn
+        .hget(OVERRIDES_KEY, &route)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let limit = override_limit.unwrap_or_else(|| default_limit(&state.config, &route));
+    if limit == 0 {
+        return Ok(next.run(request).await);
+    }
+
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let key = format!("rate_limit:{}:{}:{}", route, ip, date);
+
     let count: u32 = conn
         .incr(&key, 1_u32)
         .await
@@ -56,7 +114,29 @@ pub async fn rate_limit_middleware(
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     }
 
-    if count > state.config.rate_limit_uploads_per_ip {
+    let blocked = count > limit;
+
+    // Analytics are best effort and must never fail the request.
+    let stats = stats_key(&date);
+    let mut pipe = redis::pipe();
+    pipe.hincr(&stats, format!("{}:requests", route), 1_u32)
+        .ignore()
+        .expire(&stats, STATS_TTL_SECONDS)
+        .ignore();
+    if blocked {
+        let offenders = offenders_key(&route, &date);
+        pipe.hincr(&stats, format!("{}:blocked", route), 1_u32)
+            .ignore()
+            .zincr(&offenders, &ip, 1_u32)
+            .ignore()
+            .expire(&offenders, STATS_TTL_SECONDS)
+            .ignore();
+    }
+    if let Err(e) = pipe.query_async::<()>(&mut conn).await {
+        tracing::warn!("Failed to record rate limit stats: {}", e);
+    }
+
+    if blocked {
         return Err(StatusCode::TOO_MANY_REQUESTS);
     }
 
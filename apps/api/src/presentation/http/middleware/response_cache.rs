@@ -0,0 +1,306 @@
+//! Whole-response caching for public read endpoints.
+//!
+//! Each cached endpoint has a name and a TTL. The defaults are in
+//! [`CACHED_ENDPOINTS`]; `RESPONSE_CACHE_TTLS` overrides them per endpoint,
+//! and a TTL of 0 turns caching off for that endpoint. Successful anonymous
+//! GETs are kept in Redis by path, query and `Accept-Language`, and replayed
+//! until they expire or the cache invalidator drops them because a lettering
+//! behind them changed. Requests with an `Authorization` header always reach
+//! the handler, since they may be shown more. Responses say `X-Cache: HIT`
+//! when replayed and `X-Cache: MISS` otherwise. A replayed response carries
+//! the headers the handler set, apart from hop-by-hop ones and cookies.
+//! Handlers with side effects
+//! that must happen on every read, such as counting views, register a
+//! [`ReplayHook`].
+
+use crate::{
+    config::Config,
+    infrastructure::cache::invalidation::response_prefix,
+    presentation::http::{locale::request_locales, state::AppState},
+};
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Says whether a response was replayed from cache.
+pub const X_CACHE: &str = "x-cache";
+
+/// An endpoint whose responses can be cached, with the TTL it gets unless
+/// overridden.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CachedEndpoint {
+    pub name: &'static str,
+    pub default_ttl_seconds: u64,
+    pub description: &'static str,
+}
+
+pub const CACHED_ENDPOINTS: &[CachedEndpoint] = &[
+    CachedEndpoint {
+        name: "feed",
+        default_ttl_seconds: 30,
+        description: "Gallery feed, GET /api/v1/letterings",
+    },
+    CachedEndpoint {
+        name: "lettering",
+        default_ttl_seconds: 300,
+        description: "Lettering detail, GET /api/v1/letterings/{id}",
+    },
+    CachedEndpoint {
+        name: "stats",
+        default_ttl_seconds: 60,
+        description: "City neighbourhood stats, GET /api/v1/cities/{id}/stats",
+    },
+];
+
+/// `endpoint=seconds` pairs separated by `;`, as in the
+/// `RESPONSE_CACHE_TTLS` setting.
+pub fn parse_response_cache_ttls(value: &str) -> Result<BTreeMap<String, u64>, String> {
+    let mut ttls = BTreeMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((name, seconds)) = entry.split_once('=') else {
+            return Err(format!("`{}` must be endpoint=seconds", entry));
+        };
+        let name = name.trim();
+        if !CACHED_ENDPOINTS.iter().any(|e| e.name == name) {
+            let known: Vec<&str> = CACHED_ENDPOINTS.iter().map(|e| e.name).collect();
+            return Err(format!(
+                "`{}` is not a cached endpoint; expected one of {}",
+                name,
+                known.join(", ")
+            ));
+        }
+        let seconds = seconds
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("`{}` must be a whole number of seconds", seconds.trim()))?;
+        if ttls.insert(name.to_string(), seconds).is_some() {
+            return Err(format!("`{}` is listed twice", name));
+        }
+    }
+    Ok(ttls)
+}
+
+/// Seconds responses of `endpoint` are cached for under `config`.
+pub fn ttl_for(config: &Config, endpoint: &str) -> u64 {
+    config
+        .response_cache_ttls
+        .get(endpoint)
+        .copied()
+        .or_else(|| {
+            CACHED_ENDPOINTS
+                .iter()
+                .find(|e| e.name == endpoint)
+                .map(|e| e.default_ttl_seconds)
+        })
+        .unwrap_or(0)
+}
+
+fn response_key(uri: &Uri, locales: &[String]) -> String {
+    format!(
+        "{}{}#{}",
+        response_prefix(uri.path()),
+        uri.query().unwrap_or_default(),
+        locales.join(",")
+    )
+}
+
+/// Headers that only describe one connection or one client, and are never
+/// replayed. `Content-Length` is worked out again from the cached body.
+const UNCACHED_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "set-cookie",
+    "content-length",
+];
+
+/// Headers of `headers` worth replaying with a cached body.
+fn replayable_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|(name, _)| !UNCACHED_HEADERS.contains(&name.as_str()))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl CachedResponse {
+    fn into_response(self) -> Response {
+        let mut response = Response::new(Body::from(self.body));
+        for (name, value) in self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                response.headers_mut().append(name, value);
+            }
+        }
+        response
+    }
+}
+
+/// Run with a request whose response is replayed, for side effects the
+/// handler would have had.
+pub type ReplayHook = fn(AppState, Request) -> BoxFuture<'static, ()>;
+
+/// State of the middleware on one endpoint's route.
+#[derive(Clone)]
+pub struct ResponseCache {
+    state: AppState,
+    endpoint: &'static str,
+    on_replay: Option<ReplayHook>,
+}
+
+impl ResponseCache {
+    pub fn new(state: &AppState, endpoint: &'static str) -> Self {
+        Self {
+            state: state.clone(),
+            endpoint,
+            on_replay: None,
+        }
+    }
+
+    /// Call `hook` whenever a response is replayed instead of handled.
+    pub fn with_replay_hook(mut self, hook: ReplayHook) -> Self {
+        self.on_replay = Some(hook);
+        self
+    }
+}
+
+fn with_x_cache(mut response: Response, value: &'static str) -> Response {
+    response
+        .headers_mut()
+        .insert(X_CACHE, HeaderValue::from_static(value));
+    response
+}
+
+pub async fn response_cache_middleware(
+    State(cache): State<ResponseCache>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let ttl = ttl_for(&cache.state.config, cache.endpoint);
+    if ttl == 0 || req.method() != Method::GET || req.headers().contains_key(header::AUTHORIZATION)
+    {
+        return next.run(req).await;
+    }
+    let key = response_key(req.uri(), &request_locales(req.headers()));
+    match cache.state.cache.get::<CachedResponse>(&key).await {
+        Ok(Some(cached)) => {
+            if let Some(hook) = cache.on_replay {
+                hook(cache.state.clone(), req).await;
+            }
+            return with_x_cache(cached.into_response(), "HIT");
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to read cached response for {}: {}", key, e),
+    }
+
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK {
+        return with_x_cache(response, "MISS");
+    }
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for caching: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    // Only text bodies are kept; anything else is served uncached.
+    if let Ok(text) = std::str::from_utf8(&bytes) {
+        let cached = CachedResponse {
+            headers: replayable_headers(&parts.headers),
+            body: text.to_string(),
+        };
+        if let Err(e) = cache.state.cache.set(&key, &cached, ttl).await {
+            tracing::warn!("Failed to cache response for {}: {}", key, e);
+        }
+    }
+    with_x_cache(Response::from_parts(parts, Body::from(bytes)), "MISS")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_name_known_endpoints_once() {
+        let ttls = parse_response_cache_ttls(" feed=10 ; lettering=0;").unwrap();
+        assert_eq!(ttls["feed"], 10);
+        assert_eq!(ttls["lettering"], 0);
+        assert!(!ttls.contains_key("stats"));
+        assert!(parse_response_cache_ttls("").unwrap().is_empty());
+
+        assert!(parse_response_cache_ttls("feed").is_err());
+        assert!(parse_response_cache_ttls("comments=30").is_err());
+        assert!(parse_response_cache_ttls("feed=soon").is_err());
+        assert!(parse_response_cache_ttls("feed=10;feed=20").is_err());
+    }
+
+    #[test]
+    fn keys_separate_queries_and_locales_under_the_path() {
+        let uri: Uri = "/api/v1/letterings?limit=5".parse().unwrap();
+        let key = response_key(&uri, &["kn".to_string(), "en".to_string()]);
+        assert_eq!(key, "response:/api/v1/letterings?limit=5#kn,en");
+        assert!(key.starts_with(&response_prefix("/api/v1/letterings")));
+
+        let detail: Uri = "/api/v1/letterings/abc".parse().unwrap();
+        assert!(!response_key(&detail, &[]).starts_with(&response_prefix("/api/v1/letterings")));
+    }
+
+    #[test]
+    fn replays_headers_but_not_hop_by_hop_ones_or_cookies() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static("kn"));
+        headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+        headers.append(header::VARY, HeaderValue::from_static("accept-encoding"));
+        headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive"));
+        headers.insert(
+            header::TRANSFER_ENCODING,
+            HeaderValue::from_static("chunked"),
+        );
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("2"));
+        headers.insert(header::SET_COOKIE, HeaderValue::from_static("session=abc"));
+
+        let cached = CachedResponse {
+            headers: replayable_headers(&headers),
+            body: "{}".to_string(),
+        };
+        let response = cached.into_response();
+        let replayed = response.headers();
+        assert_eq!(replayed[header::CONTENT_TYPE], "application/json");
+        assert_eq!(replayed[header::CONTENT_LANGUAGE], "kn");
+        assert_eq!(replayed.get_all(header::VARY).iter().count(), 2);
+        for dropped in [
+            header::CONNECTION,
+            header::TRANSFER_ENCODING,
+            header::CONTENT_LENGTH,
+            header::SET_COOKIE,
+        ] {
+            assert!(!replayed.contains_key(&dropped), "{} was replayed", dropped);
+        }
+    }
+}
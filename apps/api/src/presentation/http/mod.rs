@@ -1,5 +1,7 @@
+pub mod client_sdk;
 pub mod errors;
 pub mod handlers;
+pub mod locale;
 pub mod middleware;
 pub mod routes;
 pub mod state;
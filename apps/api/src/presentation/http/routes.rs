@@ -1,11 +1,18 @@
 use super::{
     handlers::{
-        admin, admin_cities, admin_comments, admin_region_policies, analytics, auth, cities,
-        community, docs, gallery, geo, health, letterings, me, search, social, upload, ws,
+        admin, admin_analytics, admin_backups, admin_cities, admin_comments, admin_faults,
+        admin_feature_flags, admin_likes, admin_ml, admin_place_names, admin_print_bundles,
+        admin_rate_limits, admin_region_policies, admin_schedules, admin_system, admin_timeline,
+        admin_workers, analytics, auth, cities, collections, community, credits, digest, docs,
+        gallery, geo, health, images, lettering_context, letterings, me, processing, regions,
+        search, short_links, social, upload, ws,
     },
     middleware::admin::require_admin,
+    middleware::app_attestation::app_attestation_middleware,
+    middleware::etag::etag_middleware,
     middleware::rate_limit::rate_limit_middleware,
     middleware::request_id::request_id_middleware,
+    middleware::response_cache::{ResponseCache, response_cache_middleware},
     state::AppState,
 };
 use axum::{
@@ -16,6 +23,22 @@ use axum::{
 pub fn create_router(state: AppState) -> Router {
     let admin_routes = Router::new()
         .route("/api/v1/admin/moderation", get(admin::get_moderation_queue))
+        .route(
+            "/api/v1/admin/moderation/next",
+            get(admin::claim_next_moderation_item),
+        )
+        .route(
+            "/api/v1/admin/moderation/auto-approve/preview",
+            get(admin::preview_auto_approve),
+        )
+        .route(
+            "/api/v1/admin/moderation/escalations",
+            get(admin::get_escalation_stats),
+        )
+        .route(
+            "/api/v1/admin/moderation/sources/action",
+            post(admin::moderate_source),
+        )
         .route(
             "/api/v1/admin/letterings/{id}/approve",
             post(admin::approve_lettering),
@@ -28,14 +51,38 @@ pub fn create_router(state: AppState) -> Router {
             "/api/v1/admin/letterings/{id}",
             delete(admin::delete_any_lettering),
         )
+        .route(
+            "/api/v1/admin/letterings/{id}/age-restriction",
+            put(admin::set_age_restriction),
+        )
         .route(
             "/api/v1/admin/letterings/{id}/clear-reports",
             post(admin::clear_reports),
         )
+        .route(
+            "/api/v1/admin/letterings/{id}/timeline",
+            get(admin_timeline::get_lettering_timeline),
+        )
+        .route(
+            "/api/v1/admin/credit-disputes",
+            get(credits::list_credit_disputes),
+        )
+        .route(
+            "/api/v1/admin/letterings/{id}/credit/resolve",
+            post(credits::resolve_credit_dispute),
+        )
         .route(
             "/api/v1/admin/letterings/bulk",
             post(admin::bulk_lettering_action),
         )
+        .route(
+            "/api/v1/admin/letterings/{id}/ml-metadata",
+            patch(admin_ml::correct_ml_metadata),
+        )
+        .route(
+            "/api/v1/admin/ml/training-export",
+            get(admin_ml::export_training_corrections),
+        )
         .route(
             "/api/v1/admin/cities/discover",
             post(admin_cities::discover_cities),
@@ -44,6 +91,24 @@ pub fn create_router(state: AppState) -> Router {
             "/api/v1/admin/cities/bootstrap-capitals",
             post(admin_cities::bootstrap_capitals),
         )
+        .route(
+            "/api/v1/admin/cities/{id}/translations",
+            get(admin_place_names::list_city_name_translations),
+        )
+        .route(
+            "/api/v1/admin/cities/{id}/translations/{locale}",
+            put(admin_place_names::upsert_city_name_translation)
+                .delete(admin_place_names::delete_city_name_translation),
+        )
+        .route(
+            "/api/v1/admin/countries/{country_code}/translations",
+            get(admin_place_names::list_country_name_translations),
+        )
+        .route(
+            "/api/v1/admin/countries/{country_code}/translations/{locale}",
+            put(admin_place_names::upsert_country_name_translation)
+                .delete(admin_place_names::delete_country_name_translation),
+        )
         .route("/api/v1/admin/comments", get(admin_comments::list_comments))
         .route(
             "/api/v1/admin/comments/{id}/hide",
@@ -71,10 +136,132 @@ pub fn create_router(state: AppState) -> Router {
         )
         .route("/api/v1/admin/audit-logs", get(admin::list_audit_logs))
         .route("/api/v1/admin/stats", get(admin::get_stats))
+        .route(
+            "/api/v1/admin/stats/by-country",
+            get(admin::get_stats_by_country),
+        )
+        .route(
+            "/api/v1/admin/stats/request-geo",
+            get(admin::get_request_geo_stats),
+        )
+        .route(
+            "/api/v1/admin/stats/moderators",
+            get(admin::get_moderator_stats),
+        )
+        .route(
+            "/api/v1/admin/backups/manifest",
+            get(admin_backups::get_backup_manifest),
+        )
+        .route(
+            "/api/v1/admin/backups/snapshots",
+            get(admin_backups::list_backup_snapshots).post(admin_backups::create_backup_snapshot),
+        )
+        .route(
+            "/api/v1/admin/backups/snapshots/{id}",
+            get(admin_backups::get_backup_snapshot),
+        )
+        .route(
+            "/api/v1/admin/print-bundles",
+            get(admin_print_bundles::list_print_bundles)
+                .post(admin_print_bundles::create_print_bundle),
+        )
+        .route(
+            "/api/v1/admin/print-bundles/{id}",
+            get(admin_print_bundles::get_print_bundle),
+        )
+        .route(
+            "/api/v1/admin/feature-flags",
+            get(admin_feature_flags::list_feature_flags),
+        )
+        .route(
+            "/api/v1/admin/feature-flags/{name}",
+            put(admin_feature_flags::update_feature_flag),
+        )
+        .route(
+            "/api/v1/admin/rate-limits",
+            get(admin_rate_limits::get_rate_limit_stats).put(admin_rate_limits::update_rate_limit),
+        )
+        .route(
+            "/api/v1/admin/likes/flagged",
+            get(admin_likes::list_flagged_likes),
+        )
+        .route(
+            "/api/v1/admin/likes/rollback",
+            post(admin_likes::rollback_likes),
+        )
+        .route("/api/v1/admin/ml/model", get(admin_ml::get_model_status))
+        .route(
+            "/api/v1/admin/ml/model/reload",
+            post(admin_ml::reload_model),
+        )
+        .route(
+            "/api/v1/admin/ml/shadow-report",
+            get(admin_ml::get_shadow_report),
+        )
+        .route(
+            "/api/v1/admin/ml/shadow/promote",
+            post(admin_ml::promote_shadow_model),
+        )
+        .route(
+            "/api/v1/admin/ml/dead-letters",
+            get(admin_ml::list_ml_dead_letters),
+        )
+        .route(
+            "/api/v1/admin/ml/dead-letters/requeue",
+            post(admin_ml::requeue_all_ml_dead_letters),
+        )
+        .route(
+            "/api/v1/admin/ml/dead-letters/{lettering_id}/requeue",
+            post(admin_ml::requeue_ml_dead_letter),
+        )
+        .route(
+            "/api/v1/admin/ml/reprocess",
+            get(admin_ml::list_reprocess_runs).post(admin_ml::create_reprocess_run),
+        )
+        .route(
+            "/api/v1/admin/ml/reprocess/{id}",
+            get(admin_ml::get_reprocess_run),
+        )
+        .route(
+            "/api/v1/admin/ml/reprocess/{id}/cancel",
+            post(admin_ml::cancel_reprocess_run),
+        )
+        .route(
+            "/api/v1/admin/analytics/events",
+            get(admin_analytics::get_event_analytics),
+        )
+        .route(
+            "/api/v1/admin/analytics/rollups",
+            get(admin_analytics::get_rollups),
+        )
+        .route(
+            "/api/v1/admin/faults",
+            get(admin_faults::get_fault_settings).put(admin_faults::update_fault_settings),
+        )
+        .route(
+            "/api/v1/admin/system/info",
+            get(admin_system::get_system_info),
+        )
+        .route(
+            "/api/v1/admin/schedules",
+            get(admin_schedules::list_schedules),
+        )
+        .route(
+            "/api/v1/admin/schedules/{name}",
+            put(admin_schedules::update_schedule),
+        )
+        .route("/api/v1/admin/workers", get(admin_workers::list_workers))
         .route_layer(middleware::from_fn_with_state(state.clone(), require_admin));
 
-    let upload_routes = Router::new()
-        .route("/api/v1/letterings/upload", post(upload::upload_lettering))
+    let rate_limited_routes = Router::new()
+        .route(
+            "/api/v1/letterings/upload",
+            post(upload::upload_lettering).route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                app_attestation_middleware,
+            )),
+        )
+        .route("/api/v1/analytics/events", post(analytics::ingest_events))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             rate_limit_middleware,
@@ -84,11 +271,32 @@ pub fn create_router(state: AppState) -> Router {
         // Health
         .route("/health", get(health::health_check))
         // Letterings CRUD
-        .route("/api/v1/letterings", get(gallery::get_letterings))
+        .route(
+            "/api/v1/letterings",
+            get(gallery::get_letterings)
+                .route_layer(middleware::from_fn_with_state(
+                    ResponseCache::new(&state, "feed"),
+                    response_cache_middleware,
+                ))
+                .route_layer(middleware::from_fn(etag_middleware)),
+        )
         .route("/api/v1/letterings/search", get(search::search_letterings))
+        .route(
+            "/api/v1/letterings/discover",
+            get(gallery::discover_letterings),
+        )
+        .route("/api/v1/letterings/nearby", get(geo::get_nearby_letterings))
+        .route("/api/v1/letterings/map", get(geo::get_map_features))
         .route(
             "/api/v1/letterings/{id}",
-            get(letterings::get_lettering).delete(letterings::delete_lettering),
+            get(letterings::get_lettering)
+                .route_layer(middleware::from_fn_with_state(
+                    ResponseCache::new(&state, "lettering")
+                        .with_replay_hook(letterings::count_replayed_view),
+                    response_cache_middleware,
+                ))
+                .route_layer(middleware::from_fn(etag_middleware))
+                .delete(letterings::delete_lettering),
         )
         .route(
             "/api/v1/letterings/{id}/report",
@@ -98,14 +306,49 @@ pub fn create_router(state: AppState) -> Router {
             "/api/v1/letterings/{id}/download",
             get(letterings::download_lettering),
         )
+        .route(
+            "/api/v1/letterings/{id}/og-image",
+            get(letterings::get_share_card),
+        )
+        .route(
+            "/api/v1/letterings/{id}/share",
+            post(letterings::share_lettering),
+        )
+        .route("/api/v1/letterings/{id}/qr", get(letterings::get_qr_code))
+        .route(
+            "/api/v1/letterings/{id}/credit/dispute",
+            post(credits::dispute_credit),
+        )
         .route(
             "/api/v1/letterings/{id}/similar",
             get(letterings::get_similar),
         )
+        .route(
+            "/api/v1/letterings/{id}/context",
+            get(lettering_context::get_lettering_context),
+        )
+        .route(
+            "/api/v1/letterings/{id}/processing",
+            get(processing::get_processing),
+        )
+        .route("/s/{code}", get(short_links::redirect_short_link))
+        .route("/images/{id}", get(images::get_image_variant))
+        // Browse
+        .route("/api/v1/browse/scripts", get(gallery::list_scripts))
+        .route(
+            "/api/v1/browse/scripts/{script}",
+            get(gallery::get_script_letterings),
+        )
+        .route("/api/v1/browse/styles", get(gallery::list_styles))
+        .route(
+            "/api/v1/browse/styles/{style}",
+            get(gallery::get_style_letterings),
+        )
         // Contributors
         .route(
             "/api/v1/contributors/{tag}",
-            get(letterings::get_contributor_letterings),
+            get(letterings::get_contributor_letterings)
+                .route_layer(middleware::from_fn(etag_middleware)),
         )
         // Analytics
         .route(
@@ -114,10 +357,18 @@ pub fn create_router(state: AppState) -> Router {
         )
         // Social
         .route("/api/v1/letterings/{id}/like", post(social::like_lettering))
+        .route(
+            "/api/v1/letterings/{id}/bookmark",
+            post(social::bookmark_lettering).delete(social::remove_bookmark),
+        )
         .route(
             "/api/v1/letterings/{id}/comments",
             post(social::add_comment).get(social::get_comments),
         )
+        .route(
+            "/api/v1/comments/{id}/reactions",
+            post(social::react_to_comment),
+        )
         // Geo
         .route("/api/v1/geo/markers", get(geo::get_all_markers))
         .route("/api/v1/geo/nearby", get(geo::get_nearby_markers))
@@ -127,20 +378,53 @@ pub fn create_router(state: AppState) -> Router {
             "/api/v1/community/leaderboard",
             get(community::get_leaderboard),
         )
+        .route(
+            "/api/v1/leaderboards/{period}",
+            get(community::get_leaderboards),
+        )
+        // Collections
         .route(
             "/api/v1/collections",
-            get(community::list_collections).post(community::create_collection),
+            get(collections::list_collections).post(collections::create_collection),
+        )
+        .route(
+            "/api/v1/collections/{id}",
+            get(collections::get_collection)
+                .patch(collections::update_collection)
+                .delete(collections::delete_collection),
+        )
+        .route(
+            "/api/v1/collections/{id}/order",
+            put(collections::reorder_collection),
         )
-        .route("/api/v1/collections/{id}", get(community::get_collection))
         .route(
             "/api/v1/collections/{collection_id}/items/{lettering_id}",
-            post(community::add_to_collection).delete(community::remove_from_collection),
+            post(collections::add_to_collection).delete(collections::remove_from_collection),
+        )
+        .route(
+            "/api/v1/collections/shared/{slug}",
+            get(collections::get_shared_collection),
+        )
+        .route(
+            "/api/v1/me/collections",
+            get(collections::list_my_collections),
         )
         .route("/api/v1/challenges", get(community::list_challenges))
         // Cities
         .route("/api/v1/cities", get(cities::list_cities))
         .route("/api/v1/cities/{id}", get(cities::get_city))
-        .route("/api/v1/cities/{id}/stats", get(cities::get_city_stats))
+        .route(
+            "/api/v1/cities/{id}/stats",
+            get(cities::get_city_stats).route_layer(middleware::from_fn_with_state(
+                ResponseCache::new(&state, "stats"),
+                response_cache_middleware,
+            )),
+        )
+        // Regions
+        .route(
+            "/api/v1/regions/{country_code}/aggregates",
+            get(regions::get_region_aggregates),
+        )
         // Docs
         .route("/api/v1/docs", get(docs::api_docs))
         // Auth
@@ -159,6 +443,30 @@ pub fn create_router(state: AppState) -> Router {
             "/api/v1/me/notifications/{id}/read",
             post(me::mark_notification_read),
         )
+        .route(
+            "/api/v1/me/saved-searches",
+            get(me::list_saved_searches).post(me::create_saved_search),
+        )
+        .route(
+            "/api/v1/me/saved-searches/{id}",
+            put(me::update_saved_search).delete(me::delete_saved_search),
+        )
+        .route(
+            "/api/v1/me/follows",
+            get(me::list_follows).post(me::create_follow),
+        )
+        .route("/api/v1/me/follows/{id}", delete(me::delete_follow))
+        .route("/api/v1/me/bookmarks", get(me::list_bookmarks))
+        .route("/api/v1/me/stats", get(me::get_my_stats))
+        .route("/api/v1/me/feed", get(me::get_my_feed))
+        .route(
+            "/api/v1/me/digest",
+            get(digest::get_digest_settings).put(digest::update_digest_settings),
+        )
+        .route(
+            "/api/v1/digest/unsubscribe",
+            get(digest::unsubscribe_digest).post(digest::unsubscribe_digest),
+        )
         // Revisits
         .route(
             "/api/v1/letterings/{id}/revisits",
@@ -169,7 +477,7 @@ pub fn create_router(state: AppState) -> Router {
         // Admin login (unprotected)
         .route("/api/v1/admin/login", post(admin::login))
         // Admin (protected by JWT middleware)
-        .merge(upload_routes)
+        .merge(rate_limited_routes)
         .merge(admin_routes)
         .layer(middleware::from_fn(request_id_middleware))
         .with_state(state)
@@ -1,16 +1,24 @@
 use crate::{
     config::Config,
     infrastructure::{
-        cache::redis_cache::RedisCache,
-        ml::traits::MlService,
+        cache::{redis_cache::RedisCache, tiered_cache::TieredCache},
+        cdn::cloudflare_purge::CloudflarePurger,
+        fault_injection::FaultInjector,
+        geocoding::ip_geolocation::IpGeolocator,
+        ml::{
+            onnx_text_detector::OnnxTextDetector, tesseract_service::TesseractService,
+            traits::MlService,
+        },
+        monitoring::PerformanceMonitor,
         queue::redis_queue::RedisQueue,
         repositories::{
             sqlx_lettering_repository::SqlxLetteringRepository,
             sqlx_social_repository::SqlxSocialRepository,
         },
-        security::virus_scanner::VirusScanner,
+        security::{app_attestation::AppAttestationVerifier, virus_scanner::VirusScanner},
         storage::traits::StorageService,
     },
+    presentation::http::handlers::ws::WsDrain,
 };
 use sqlx::PgPool;
 use std::sync::Arc;
@@ -21,12 +29,28 @@ pub struct AppState {
     pub db: PgPool,
     pub redis: redis::Client,
     pub cache: Arc<RedisCache>,
+    /// In-process tier over `cache` for lettering, city and region policy
+    /// reads.
+    pub hot_cache: Arc<TieredCache>,
     pub storage: Arc<dyn StorageService>,
     pub ml_detector: Arc<dyn MlService>,
+    /// Concrete local model, for runtime reloads from the admin API.
+    pub onnx_detector: Arc<OnnxTextDetector>,
+    /// OCR fallback for uploads approved without ML.
+    pub ocr: Arc<TesseractService>,
     pub queue: Arc<RedisQueue>,
     pub virus_scanner: Arc<VirusScanner>,
+    /// Per-platform app attestation of mobile uploads.
+    pub attestation: Arc<AppAttestationVerifier>,
     pub config: Config,
     pub lettering_repo: Arc<SqlxLetteringRepository>,
     pub social_repo: Arc<SqlxSocialRepository>,
     pub ws_broadcaster: Arc<broadcast::Sender<String>>,
+    /// Closes open feed connections on shutdown.
+    pub ws_drain: Arc<WsDrain>,
+    pub cdn_purger: Arc<CloudflarePurger>,
+    pub ip_geolocator: Arc<IpGeolocator>,
+    pub performance: Arc<PerformanceMonitor>,
+    /// Present only when fault injection is enabled.
+    pub faults: Option<Arc<FaultInjector>>,
 }
@@ -0,0 +1,192 @@
+use crate::infrastructure::monitoring::throttle::WorkerThrottle;
+use sqlx::PgPool;
+use std::time::Duration;
+
+const WORKER_NAME: &str = "activity_fanout";
+
+/// Outbox rows fanned out per transaction.
+const BATCH_SIZE: i64 = 500;
+
+/// Processed outbox rows are kept this long for debugging, then deleted.
+const OUTBOX_RETENTION_DAYS: i32 = 7;
+
+/// Feed events older than this are deleted.
+const EVENT_RETENTION_DAYS: i32 = 180;
+
+/// Turns the `activity_outbox` rows the database triggers write into
+/// `activity_events` for each user they concern:
+/// - `FOLLOWED_UPLOAD`: followers of the uploader's tag or account, once the
+///   lettering is publicly listed, at most once per lettering;
+/// - `COMMENT_REPLY`: the author of the comment replied to, unless they
+///   replied to themselves;
+/// - `LETTERING_LIKED`: the uploader, at most once a day per lettering since
+///   likes carry no actor worth listing one by one.
+pub struct ActivityFanout {
+    db: PgPool,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl ActivityFanout {
+    pub fn new(db: PgPool, interval_seconds: u64) -> Self {
+        Self {
+            db,
+            interval_seconds: interval_seconds.max(5),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(written) if written > 0 => {
+                    tracing::debug!(written, "Activity events written");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Activity fan-out pass failed: {}", e),
+            }
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Drains the outbox and returns how many feed events were written.
+    pub async fn run_once(&self) -> Result<u64, sqlx::Error> {
+        let mut written = 0;
+        loop {
+            let (claimed, events) = self.fan_out_batch().await?;
+            written += events;
+            if claimed < BATCH_SIZE {
+                break;
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                break;
+            }
+        }
+        self.prune().await?;
+        Ok(written)
+    }
+
+    /// Fans out one batch of outbox rows in a transaction, so a failed pass
+    /// leaves them to be retried. Returns the rows claimed and events written.
+    async fn fan_out_batch(&self) -> Result<(i64, u64), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM activity_outbox
+             WHERE processed_at IS NULL
+             ORDER BY id
+             LIMIT $1
+             FOR UPDATE SKIP LOCKED",
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await?;
+        if ids.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let uploads = sqlx::query(
+            "INSERT INTO activity_events
+                 (id, user_id, outbox_id, kind, lettering_id, actor_user_id, created_at)
+             SELECT DISTINCT ON (o.id, f.follower_id)
+                    uuid_generate_v4(), f.follower_id, o.id, o.kind, o.lettering_id, l.user_id,
+                    o.occurred_at
+             FROM activity_outbox o
+             JOIN letterings l ON l.id = o.lettering_id
+             JOIN follows f
+               ON f.contributor_tag = l.contributor_tag
+               OR f.followed_user_id = l.user_id
+             WHERE o.id = ANY($1) AND o.kind = 'FOLLOWED_UPLOAD'
+               AND l.status = 'APPROVED' AND city_discoverable(l.city_id)
+               AND l.user_id IS DISTINCT FROM f.follower_id
+               AND NOT EXISTS (
+                   SELECT 1 FROM activity_events e
+                   WHERE e.user_id = f.follower_id AND e.lettering_id = o.lettering_id
+                     AND e.kind = 'FOLLOWED_UPLOAD'
+               )
+             ON CONFLICT (outbox_id, user_id) DO NOTHING",
+        )
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+
+        let replies = sqlx::query(
+            "INSERT INTO activity_events
+                 (id, user_id, outbox_id, kind, lettering_id, comment_id, actor_user_id, created_at)
+             SELECT uuid_generate_v4(), parent.user_id, o.id, o.kind, o.lettering_id, o.comment_id,
+                    o.actor_user_id, o.occurred_at
+             FROM activity_outbox o
+             JOIN comments reply ON reply.id = o.comment_id
+             JOIN comments parent ON parent.id = reply.parent_id
+             WHERE o.id = ANY($1) AND o.kind = 'COMMENT_REPLY'
+               AND parent.user_id IS NOT NULL
+               AND parent.user_id IS DISTINCT FROM o.actor_user_id
+             ON CONFLICT (outbox_id, user_id) DO NOTHING",
+        )
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+
+        let likes = sqlx::query(
+            "INSERT INTO activity_events
+                 (id, user_id, outbox_id, kind, lettering_id, created_at)
+             SELECT DISTINCT ON (l.user_id, o.lettering_id)
+                    uuid_generate_v4(), l.user_id, o.id, o.kind, o.lettering_id, o.occurred_at
+             FROM activity_outbox o
+             JOIN letterings l ON l.id = o.lettering_id
+             WHERE o.id = ANY($1) AND o.kind = 'LETTERING_LIKED'
+               AND l.user_id IS NOT NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM activity_events e
+                   WHERE e.user_id = l.user_id AND e.lettering_id = o.lettering_id
+                     AND e.kind = 'LETTERING_LIKED'
+                     AND e.created_at > o.occurred_at - INTERVAL '1 day'
+               )
+             ORDER BY l.user_id, o.lettering_id, o.id DESC
+             ON CONFLICT (outbox_id, user_id) DO NOTHING",
+        )
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE activity_outbox SET processed_at = NOW() WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok((
+            ids.len() as i64,
+            uploads.rows_affected() + replies.rows_affected() + likes.rows_affected(),
+        ))
+    }
+
+    async fn prune(&self) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "DELETE FROM activity_outbox
+             WHERE processed_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(OUTBOX_RETENTION_DAYS)
+        .execute(&self.db)
+        .await?;
+        sqlx::query(
+            "DELETE FROM activity_events
+             WHERE created_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(EVENT_RETENTION_DAYS)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
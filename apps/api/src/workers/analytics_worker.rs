@@ -1,22 +1,345 @@
+use super::scheduler::ScheduledTask;
+use crate::domain::contributor::leaderboard::{LeaderboardMetric, LeaderboardPeriod};
+use crate::infrastructure::monitoring::throttle::WorkerThrottle;
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
-use std::time::Duration;
+
+/// Contributors kept per leaderboard.
+const LEADERBOARD_SIZE: i64 = 50;
+
+/// How far back the first pass, or one after a long outage, fills hourly
+/// rollups.
+const HOURLY_BACKFILL_DAYS: i32 = 7;
+
+/// How far back the first pass fills daily rollups.
+const DAILY_BACKFILL_DAYS: i32 = 365;
+
+/// Hourly rollups older than this are deleted; daily ones are kept.
+const HOURLY_RETENTION_DAYS: i32 = 90;
+
+/// Rollup row counting every country.
+pub const ALL_COUNTRIES: &str = "*";
+
+/// The time buckets activity is rolled up into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rollup {
+    Hourly,
+    /// Local days in the worker's time zone, bound at `$2`.
+    Daily,
+}
+
+impl Rollup {
+    pub const ALL: [Rollup; 2] = [Rollup::Hourly, Rollup::Daily];
+
+    fn table(self) -> &'static str {
+        match self {
+            Rollup::Hourly => "analytics_hourly_rollups",
+            Rollup::Daily => "analytics_daily_rollups",
+        }
+    }
+
+    fn bucket_column(self) -> &'static str {
+        match self {
+            Rollup::Hourly => "hour_start",
+            Rollup::Daily => "date",
+        }
+    }
+
+    /// The bucket holding the instant `at`.
+    fn bucket_of(self, at: &str) -> String {
+        match self {
+            Rollup::Hourly => format!("date_trunc('hour', {})", at),
+            Rollup::Daily => format!("local_date({}, $2)", at),
+        }
+    }
+
+    /// Every bucket from the one holding `$1` to the current one.
+    fn series(self) -> &'static str {
+        match self {
+            Rollup::Hourly => {
+                "SELECT generate_series(date_trunc('hour', $1::timestamptz), date_trunc('hour', NOW()), interval '1 hour') AS bucket"
+            }
+            Rollup::Daily => {
+                "SELECT generate_series(local_date($1, $2)::timestamp, local_date(NOW(), $2)::timestamp, interval '1 day')::date AS bucket"
+            }
+        }
+    }
+
+    /// Where a pass starts: one bucket before the latest computed, so the
+    /// bucket that was still open is finished off, or the backfill window
+    /// for an empty table. Daily rollups take the time zone at `$1`.
+    fn resume_sql(self) -> String {
+        match self {
+            Rollup::Hourly => format!(
+                "SELECT GREATEST(
+                     COALESCE(MAX(hour_start) - interval '1 hour', '-infinity'),
+                     date_trunc('hour', NOW()) - make_interval(days => {})
+                 )
+                 FROM analytics_hourly_rollups
+                 WHERE country_code = '{}'",
+                HOURLY_BACKFILL_DAYS, ALL_COUNTRIES
+            ),
+            Rollup::Daily => format!(
+                "SELECT (COALESCE(MAX(date) - 1, local_date(NOW(), $1) - {}))::timestamp AT TIME ZONE $1
+                 FROM analytics_daily_rollups
+                 WHERE country_code = '{}'",
+                DAILY_BACKFILL_DAYS, ALL_COUNTRIES
+            ),
+        }
+    }
+
+    /// Recounts every bucket from the one holding `$1`: a row per country
+    /// with activity and an all-countries row per bucket, zero or not.
+    fn insert_sql(self) -> String {
+        let bucket = self.bucket_of("at");
+        format!(
+            "WITH events AS (
+                 SELECT l.created_at AS at, c.country_code, 'upload' AS kind,
+                        l.contributor_tag AS contributor
+                 FROM letterings l JOIN cities c ON c.id = l.city_id
+                 WHERE l.created_at >= {start}
+                 UNION ALL
+                 SELECT h.created_at, c.country_code, 'approval', NULL
+                 FROM lettering_status_history h
+                 JOIN letterings l ON l.id = h.lettering_id
+                 JOIN cities c ON c.id = l.city_id
+                 WHERE h.to_status = 'APPROVED' AND h.created_at >= {start}
+                 UNION ALL
+                 SELECT k.created_at, c.country_code, 'like', NULL
+                 FROM likes k
+                 JOIN letterings l ON l.id = k.lettering_id
+                 JOIN cities c ON c.id = l.city_id
+                 WHERE k.created_at >= {start}
+                 UNION ALL
+                 SELECT m.created_at, c.country_code, 'comment', NULL
+                 FROM comments m
+                 JOIN letterings l ON l.id = m.lettering_id
+                 JOIN cities c ON c.id = l.city_id
+                 WHERE m.created_at >= {start}
+             ),
+             counted AS (
+                 SELECT bucket,
+                        CASE WHEN GROUPING(country_code) = 1 THEN '{all}' ELSE country_code END AS country_code,
+                        COUNT(*) FILTER (WHERE kind = 'upload')::int AS uploads,
+                        COUNT(*) FILTER (WHERE kind = 'approval')::int AS approvals,
+                        COUNT(*) FILTER (WHERE kind = 'like')::int AS likes,
+                        COUNT(*) FILTER (WHERE kind = 'comment')::int AS comments,
+                        COUNT(DISTINCT contributor)::int AS active_contributors
+                 FROM (SELECT {bucket} AS bucket, country_code, kind, contributor FROM events) bucketed
+                 GROUP BY GROUPING SETS ((bucket, country_code), (bucket))
+             )
+             INSERT INTO {table} ({column}, country_code, uploads, approvals, likes, comments, active_contributors)
+             SELECT s.bucket, '{all}', COALESCE(c.uploads, 0), COALESCE(c.approvals, 0),
+                    COALESCE(c.likes, 0), COALESCE(c.comments, 0), COALESCE(c.active_contributors, 0)
+             FROM ({series}) s
+             LEFT JOIN counted c ON c.bucket = s.bucket AND c.country_code = '{all}'
+             UNION ALL
+             SELECT bucket, country_code, uploads, approvals, likes, comments, active_contributors
+             FROM counted
+             WHERE country_code <> '{all}'",
+            start = self.start_sql(),
+            all = ALL_COUNTRIES,
+            bucket = bucket,
+            table = self.table(),
+            column = self.bucket_column(),
+            series = self.series(),
+        )
+    }
+
+    /// The first instant of the bucket holding `$1`.
+    fn start_sql(self) -> &'static str {
+        match self {
+            Rollup::Hourly => "date_trunc('hour', $1::timestamptz)",
+            Rollup::Daily => "local_day_start($1, $2)",
+        }
+    }
+}
+
+/// Per-contributor score for `metric` as `(contributor_tag, value)`, counting
+/// publicly listed letterings only. `$3` is the window in days, NULL for all
+/// time.
+fn leaderboard_scores_sql(metric: LeaderboardMetric) -> &'static str {
+    match metric {
+        LeaderboardMetric::ApprovedUploads => {
+            "SELECT l.contributor_tag, COUNT(*)::bigint AS value
+             FROM letterings l
+             WHERE l.status = 'APPROVED' AND city_discoverable(l.city_id)
+               AND ($3::int IS NULL OR l.created_at >= NOW() - make_interval(days => $3))
+             GROUP BY l.contributor_tag"
+        }
+        LeaderboardMetric::Likes => {
+            "SELECT l.contributor_tag, COUNT(*)::bigint AS value
+             FROM likes k
+             JOIN letterings l ON l.id = k.lettering_id
+             WHERE l.status = 'APPROVED' AND city_discoverable(l.city_id)
+               AND ($3::int IS NULL OR k.created_at >= NOW() - make_interval(days => $3))
+             GROUP BY l.contributor_tag"
+        }
+        LeaderboardMetric::Cities => {
+            "SELECT l.contributor_tag, COUNT(DISTINCT l.city_id)::bigint AS value
+             FROM letterings l
+             WHERE l.status = 'APPROVED' AND city_discoverable(l.city_id)
+               AND ($3::int IS NULL OR l.created_at >= NOW() - make_interval(days => $3))
+             GROUP BY l.contributor_tag"
+        }
+    }
+}
 
 pub struct AnalyticsWorker {
     db: PgPool,
+    /// IANA time zone whose midnight starts a day in the daily rollups.
+    timezone: String,
+    throttle: WorkerThrottle,
 }
 impl AnalyticsWorker {
     pub fn new(db: PgPool) -> Self {
-        Self { db }
-    }
-    pub async fn start(&self) {
-        loop {
-            // Simplified query to ensure no unknown column errors
-            let _ = sqlx::query!(
-                "INSERT INTO daily_stats (date, uploads_count) 
-                 VALUES (CURRENT_DATE, (SELECT COUNT(*) FROM letterings WHERE created_at::date = CURRENT_DATE)::int)
-                 ON CONFLICT (date) DO UPDATE SET uploads_count = EXCLUDED.uploads_count"
-            ).execute(&self.db).await;
-            tokio::time::sleep(Duration::from_secs(3600)).await;
+        Self {
+            db,
+            timezone: "UTC".to_string(),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Roll up daily activity by days in `timezone` instead of UTC.
+    pub fn with_timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = timezone.into();
+        self
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Recounts hourly and daily rollups from the last finished bucket up
+    /// to the current one, each granularity in one transaction, and drops
+    /// expired hourly rows.
+    pub async fn record_rollups(&self) -> Result<(), sqlx::Error> {
+        for rollup in Rollup::ALL {
+            self.record_rollup(rollup).await?;
+            if !self.throttle.between_batches("analytics").await {
+                return Ok(());
+            }
+        }
+        sqlx::query(
+            "DELETE FROM analytics_hourly_rollups
+             WHERE hour_start < NOW() - make_interval(days => $1)",
+        )
+        .bind(HOURLY_RETENTION_DAYS)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    async fn record_rollup(&self, rollup: Rollup) -> Result<(), sqlx::Error> {
+        let resume = rollup.resume_sql();
+        let query = sqlx::query_scalar::<_, DateTime<Utc>>(&resume);
+        let from = match rollup {
+            Rollup::Hourly => query,
+            Rollup::Daily => query.bind(&self.timezone),
+        }
+        .fetch_one(&self.db)
+        .await?;
+        let mut tx = self.db.begin().await?;
+        let delete = format!(
+            "DELETE FROM {} WHERE {} >= {}",
+            rollup.table(),
+            rollup.bucket_column(),
+            rollup.bucket_of("$1::timestamptz")
+        );
+        let insert = rollup.insert_sql();
+        for sql in [&delete, &insert] {
+            let query = sqlx::query(sql).bind(from);
+            let query = match rollup {
+                Rollup::Hourly => query,
+                Rollup::Daily => query.bind(&self.timezone),
+            };
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await
+    }
+
+    /// Recomputes every period's leaderboards into `leaderboard_entries`,
+    /// replacing a period's rows in one transaction so readers never see it
+    /// half-written.
+    pub async fn refresh_leaderboards(&self) -> Result<(), sqlx::Error> {
+        for period in LeaderboardPeriod::ALL {
+            let mut tx = self.db.begin().await?;
+            sqlx::query("DELETE FROM leaderboard_entries WHERE period = $1")
+                .bind(period.as_str())
+                .execute(&mut *tx)
+                .await?;
+            for metric in LeaderboardMetric::ALL {
+                sqlx::query(&format!(
+                    "INSERT INTO leaderboard_entries (period, metric, contributor_tag, rank, value)
+                     SELECT $1, $2, contributor_tag, rank, value
+                     FROM (
+                         SELECT contributor_tag, value,
+                                RANK() OVER (ORDER BY value DESC)::int AS rank,
+                                ROW_NUMBER() OVER (ORDER BY value DESC, contributor_tag) AS position
+                         FROM ({}) scores
+                         WHERE value > 0
+                     ) ranked
+                     WHERE position <= $4",
+                    leaderboard_scores_sql(metric)
+                ))
+                .bind(period.as_str())
+                .bind(metric.as_str())
+                .bind(period.window_days())
+                .bind(LEADERBOARD_SIZE)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            if !self.throttle.between_batches("analytics").await {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for AnalyticsWorker {
+    fn name(&self) -> &'static str {
+        "analytics_rollup"
+    }
+
+    /// Refreshes leaderboards even when the rollups fail.
+    async fn run(&self) -> anyhow::Result<()> {
+        let rollups = self.record_rollups().await;
+        let leaderboards = self.refresh_leaderboards().await;
+        rollups.context("analytics rollup failed")?;
+        leaderboards.context("leaderboard refresh failed")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leaderboards_only_count_public_letterings() {
+        for metric in LeaderboardMetric::ALL {
+            let sql = leaderboard_scores_sql(metric);
+            assert!(sql.contains("l.status = 'APPROVED'"));
+            assert!(sql.contains("city_discoverable(l.city_id)"));
+            assert!(sql.contains("$3::int IS NULL"));
+        }
+    }
+
+    #[test]
+    fn only_daily_rollups_take_the_time_zone() {
+        for rollup in Rollup::ALL {
+            let sql = rollup.insert_sql();
+            assert!(sql.contains(rollup.table()));
+            assert!(sql.contains("GROUPING SETS"));
+            assert_eq!(sql.contains("$2"), rollup == Rollup::Daily);
+            assert_eq!(rollup.resume_sql().contains("$1"), rollup == Rollup::Daily);
         }
     }
 }
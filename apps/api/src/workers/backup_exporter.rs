@@ -0,0 +1,309 @@
+use super::scheduler::ScheduledTask;
+use crate::infrastructure::storage::{
+    traits::StorageService,
+    visibility::{legacy_original_key, original_key},
+};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// Objects copied per pass; the remainder is picked up on the next run.
+const BATCH_SIZE: i64 = 500;
+const METADATA_PAGE_SIZE: i64 = 1000;
+const FETCH_URL_TTL: Duration = Duration::from_secs(900);
+
+#[derive(Debug, FromRow)]
+struct BackupCandidate {
+    id: Uuid,
+    image_url: String,
+}
+
+#[derive(Debug, Default)]
+struct RunTotals {
+    copied: i32,
+    failed: i32,
+    bytes: i64,
+}
+
+/// Copies newly approved images and a JSONL dump of approved metadata to a
+/// secondary bucket.
+///
+/// Each image is streamed from a signed primary URL into a chunked upload so
+/// memory stays flat regardless of object size. The preserved original is
+/// preferred; letterings uploaded before originals were kept fall back to the
+/// archived WebP. Progress is recorded in `backup_runs` / `backup_objects`,
+/// which back the admin manifest endpoint.
+pub struct BackupExporter {
+    db: PgPool,
+    primary: Arc<dyn StorageService>,
+    backup: Arc<dyn StorageService>,
+    client: reqwest::Client,
+}
+
+impl BackupExporter {
+    pub fn new(
+        db: PgPool,
+        primary: Arc<dyn StorageService>,
+        backup: Arc<dyn StorageService>,
+    ) -> Self {
+        Self {
+            db,
+            primary,
+            backup,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(300))
+                .build()
+                .unwrap(),
+        }
+    }
+
+    async fn run_once(&self, client: &reqwest::Client) -> anyhow::Result<()> {
+        let run_id = Uuid::now_v7();
+        sqlx::query("INSERT INTO backup_runs (id) VALUES ($1)")
+            .bind(run_id)
+            .execute(&self.db)
+            .await?;
+
+        match self.export(client, run_id).await {
+            Ok((totals, metadata_key, metadata_rows)) => {
+                sqlx::query(
+                    "UPDATE backup_runs
+                     SET status = 'COMPLETED', objects_copied = $2, objects_failed = $3,
+                         bytes_copied = $4, metadata_key = $5, metadata_rows = $6,
+                         finished_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(run_id)
+                .bind(totals.copied)
+                .bind(totals.failed)
+                .bind(totals.bytes)
+                .bind(&metadata_key)
+                .bind(metadata_rows)
+                .execute(&self.db)
+                .await?;
+                tracing::info!(
+                    run_id = %run_id,
+                    copied = totals.copied,
+                    failed = totals.failed,
+                    metadata_rows,
+                    "Backup export completed"
+                );
+                Ok(())
+            }
+            Err(e) => {
+                sqlx::query(
+                    "UPDATE backup_runs SET status = 'FAILED', error = $2, finished_at = NOW() WHERE id = $1",
+                )
+                .bind(run_id)
+                .bind(e.to_string())
+                .execute(&self.db)
+                .await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn export(
+        &self,
+        client: &reqwest::Client,
+        run_id: Uuid,
+    ) -> anyhow::Result<(RunTotals, String, i32)> {
+        let candidates = sqlx::query_as::<_, BackupCandidate>(
+            "SELECT l.id, l.image_url
+             FROM letterings l
+             LEFT JOIN backup_objects b ON b.lettering_id = l.id
+             WHERE l.status = 'APPROVED' AND b.lettering_id IS NULL
+             ORDER BY l.created_at ASC
+             LIMIT $1",
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut totals = RunTotals::default();
+        for candidate in candidates {
+            match self.copy_lettering(client, run_id, &candidate).await {
+                Ok(bytes) => {
+                    totals.copied += 1;
+                    totals.bytes += bytes;
+                }
+                Err(e) => {
+                    totals.failed += 1;
+                    tracing::warn!(lettering_id = %candidate.id, "Backup copy failed: {}", e);
+                }
+            }
+        }
+
+        let (metadata_key, metadata_rows) = self.dump_metadata(run_id).await?;
+        Ok((totals, metadata_key, metadata_rows))
+    }
+
+    async fn copy_lettering(
+        &self,
+        client: &reqwest::Client,
+        run_id: Uuid,
+        candidate: &BackupCandidate,
+    ) -> anyhow::Result<i64> {
+        let mut sources = vec![
+            original_key(candidate.id),
+            legacy_original_key(candidate.id),
+        ];
+        if let Some(archived) = self.primary.key_from_url(&candidate.image_url) {
+            sources.push(archived);
+        }
+
+        for source_key in sources {
+            let backup_key = format!("images/{}", source_key);
+            let Some((sha256, size)) = self.copy_object(client, &source_key, &backup_key).await?
+            else {
+                continue;
+            };
+
+            sqlx::query(
+                "INSERT INTO backup_objects (lettering_id, run_id, source_key, backup_key, sha256, size_bytes)
+                 VALUES ($1, $2, $3, $4, $5, $6)
+                 ON CONFLICT (lettering_id) DO NOTHING",
+            )
+            .bind(candidate.id)
+            .bind(run_id)
+            .bind(&source_key)
+            .bind(&backup_key)
+            .bind(&sha256)
+            .bind(size)
+            .execute(&self.db)
+            .await?;
+            return Ok(size);
+        }
+
+        anyhow::bail!("no source object found in primary storage")
+    }
+
+    /// Stream one object across buckets. Returns `None` if the source is missing.
+    async fn copy_object(
+        &self,
+        client: &reqwest::Client,
+        source_key: &str,
+        backup_key: &str,
+    ) -> anyhow::Result<Option<(String, i64)>> {
+        let url = self.primary.signed_url(source_key, FETCH_URL_TTL).await?;
+        let mut response = client.get(&url).send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            anyhow::bail!("source fetch returned HTTP {}", status);
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+
+        let mut upload = self
+            .backup
+            .start_chunked_upload(backup_key, &content_type)
+            .await?;
+        let mut hasher = Sha256::new();
+        let mut size = 0i64;
+
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    upload.abort().await;
+                    return Err(e.into());
+                }
+            };
+            hasher.update(&chunk);
+            size += chunk.len() as i64;
+            if let Err(e) = upload.write(&chunk).await {
+                upload.abort().await;
+                return Err(e);
+            }
+        }
+        upload.finish().await?;
+
+        Ok(Some((format!("{:x}", hasher.finalize()), size)))
+    }
+
+    /// Write every approved lettering as one JSON object per line. Uploader
+    /// IPs are deliberately left out.
+    async fn dump_metadata(&self, run_id: Uuid) -> anyhow::Result<(String, i32)> {
+        let key = format!(
+            "metadata/{}-{}.jsonl",
+            chrono::Utc::now().format("%Y-%m-%d"),
+            run_id
+        );
+        let mut upload = self
+            .backup
+            .start_chunked_upload(&key, "application/x-ndjson")
+            .await?;
+
+        let mut after = Uuid::nil();
+        let mut rows = 0i32;
+        loop {
+            let page = match sqlx::query_as::<_, (Uuid, String)>(
+                "SELECT x.id, to_jsonb(x)::text
+                 FROM (
+                     SELECT l.id, l.city_id, l.contributor_tag, l.image_url, l.image_hash,
+                            l.original_hash, l.pin_code, ST_AsText(l.location) AS location,
+                            ST_AsText(l.exact_location) AS exact_location, l.location_privacy,
+                            l.description, l.detected_text, l.cultural_context,
+                            l.ml_style, l.ml_script, l.ml_confidence,
+                            b.backup_key, b.sha256 AS backup_sha256,
+                            l.created_at, l.updated_at
+                     FROM letterings l
+                     LEFT JOIN backup_objects b ON b.lettering_id = l.id
+                     WHERE l.status = 'APPROVED' AND l.id > $1
+                     ORDER BY l.id ASC
+                     LIMIT $2
+                 ) x",
+            )
+            .bind(after)
+            .bind(METADATA_PAGE_SIZE)
+            .fetch_all(&self.db)
+            .await
+            {
+                Ok(page) => page,
+                Err(e) => {
+                    upload.abort().await;
+                    return Err(e.into());
+                }
+            };
+            let Some((last_id, _)) = page.last() else {
+                break;
+            };
+            after = *last_id;
+
+            let mut buf = String::new();
+            for (_, line) in &page {
+                buf.push_str(line);
+                buf.push('\n');
+            }
+            rows += page.len() as i32;
+            if let Err(e) = upload.write(buf.as_bytes()).await {
+                upload.abort().await;
+                return Err(e);
+            }
+        }
+
+        upload.finish().await?;
+        Ok((key, rows))
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for BackupExporter {
+    fn name(&self) -> &'static str {
+        "backup_export"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.run_once(&self.client).await
+    }
+}
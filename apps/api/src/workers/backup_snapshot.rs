@@ -0,0 +1,296 @@
+use crate::infrastructure::{
+    monitoring::heartbeat::Heartbeats,
+    shutdown::Shutdown,
+    storage::{
+        traits::{ChunkedUpload, StorageService},
+        visibility::original_key,
+    },
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "backup_snapshot";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const PAGE_SIZE: i64 = 1000;
+/// A RUNNING snapshot older than this is assumed orphaned by a restart.
+const STALE_AFTER_MINUTES: i32 = 60;
+
+#[derive(Debug, FromRow)]
+struct SnapshotMarker {
+    wal_lsn: Option<String>,
+    pg_snapshot: String,
+    snapshot_at: DateTime<Utc>,
+}
+
+#[derive(Debug, FromRow)]
+struct LetteringObjects {
+    id: Uuid,
+    image_url: String,
+    thumbnail_small: String,
+    thumbnail_medium: String,
+    thumbnail_large: String,
+    variant_keys: Vec<String>,
+}
+
+impl LetteringObjects {
+    /// Object keys this row references, labelled by kind. URLs that don't
+    /// belong to our storage (e.g. seeded external images) are skipped, as
+    /// are repeated keys such as a thumbnail reused for several sizes.
+    fn keys(&self, storage: &dyn StorageService) -> Vec<(&'static str, String)> {
+        let mut keys: Vec<(&'static str, String)> = Vec::new();
+        let urls = [
+            ("image", &self.image_url),
+            ("thumbnail", &self.thumbnail_small),
+            ("thumbnail", &self.thumbnail_medium),
+            ("thumbnail", &self.thumbnail_large),
+        ];
+        for (kind, url) in urls {
+            if let Some(key) = storage.key_from_url(url) {
+                keys.push((kind, key));
+            }
+        }
+        keys.push(("original", original_key(self.id)));
+        keys.extend(self.variant_keys.iter().map(|k| ("variant", k.clone())));
+
+        let mut seen = std::collections::HashSet::new();
+        keys.retain(|(_, key)| seen.insert(key.clone()));
+        keys
+    }
+}
+
+#[derive(Debug, Default)]
+struct ManifestTotals {
+    objects: i32,
+    letterings: i32,
+}
+
+/// Writes consistent backup markers requested through the admin API.
+///
+/// Everything is read inside one REPEATABLE READ transaction: the WAL LSN and
+/// `pg_current_snapshot()` recorded on the marker describe exactly the state
+/// the object list was taken from. The manifest is JSON lines in storage: a
+/// header with the marker, then one line per referenced object key. Restoring
+/// the database to that LSN and the bucket to those keys gives a matching
+/// pair. Originals are listed by their expected key; letterings uploaded
+/// before originals were kept won't have one.
+pub struct BackupSnapshotWorker {
+    db: PgPool,
+    storage: Arc<dyn StorageService>,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl BackupSnapshotWorker {
+    pub fn new(db: PgPool, storage: Arc<dyn StorageService>) -> Self {
+        Self {
+            db,
+            storage,
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Beat while polling and name the snapshot being taken.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Once `shutdown` fires, finish the snapshot in hand and stop claiming.
+    /// One cut off by the drain timeout is claimed again once stale.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn start(&self) {
+        while !self.shutdown.is_triggered() {
+            match self.claim_next().await {
+                Ok(Some(id)) => {
+                    self.heartbeats
+                        .working_on(
+                            WORKER_NAME,
+                            &format!("snapshot {}", id),
+                            Duration::from_secs(STALE_AFTER_MINUTES as u64 * 60),
+                        )
+                        .await;
+                    self.run(id).await
+                }
+                Ok(None) => {
+                    self.heartbeats.beat(WORKER_NAME, POLL_INTERVAL).await;
+                    self.shutdown.sleep(POLL_INTERVAL).await;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to claim backup snapshot: {}", e);
+                    self.shutdown.sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn claim_next(&self) -> anyhow::Result<Option<Uuid>> {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            "UPDATE backup_snapshots SET status = 'RUNNING', started_at = NOW()
+             WHERE id = (
+                 SELECT id FROM backup_snapshots
+                 WHERE status = 'PENDING'
+                    OR (status = 'RUNNING' AND started_at < NOW() - make_interval(mins => $1))
+                 ORDER BY created_at ASC
+                 LIMIT 1
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id",
+        )
+        .bind(STALE_AFTER_MINUTES)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(id)
+    }
+
+    async fn run(&self, id: Uuid) {
+        let key = format!(
+            "backups/snapshots/{}-{}.jsonl",
+            Utc::now().format("%Y-%m-%d"),
+            id
+        );
+        let result = match self.snapshot(id, &key).await {
+            Ok((marker, totals)) => {
+                tracing::info!(
+                    snapshot_id = %id,
+                    wal_lsn = ?marker.wal_lsn,
+                    objects = totals.objects,
+                    "Backup snapshot completed"
+                );
+                sqlx::query(
+                    "UPDATE backup_snapshots
+                     SET status = 'COMPLETED', wal_lsn = $2, pg_snapshot = $3, snapshot_at = $4,
+                         manifest_key = $5, object_count = $6, lettering_count = $7,
+                         error = NULL, finished_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(id)
+                .bind(&marker.wal_lsn)
+                .bind(&marker.pg_snapshot)
+                .bind(marker.snapshot_at)
+                .bind(&key)
+                .bind(totals.objects)
+                .bind(totals.letterings)
+                .execute(&self.db)
+                .await
+            }
+            Err(e) => {
+                tracing::error!(snapshot_id = %id, "Backup snapshot failed: {}", e);
+                sqlx::query(
+                    "UPDATE backup_snapshots SET status = 'FAILED', error = $2, finished_at = NOW() WHERE id = $1",
+                )
+                .bind(id)
+                .bind(e.to_string())
+                .execute(&self.db)
+                .await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(snapshot_id = %id, "Failed to record backup snapshot status: {}", e);
+        }
+    }
+
+    async fn snapshot(
+        &self,
+        id: Uuid,
+        key: &str,
+    ) -> anyhow::Result<(SnapshotMarker, ManifestTotals)> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        let marker = sqlx::query_as::<_, SnapshotMarker>(
+            "SELECT (CASE WHEN pg_is_in_recovery() THEN pg_last_wal_replay_lsn()
+                          ELSE pg_current_wal_lsn() END)::text AS wal_lsn,
+                    pg_current_snapshot()::text AS pg_snapshot,
+                    NOW() AS snapshot_at",
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut upload = self
+            .storage
+            .start_chunked_upload(key, "application/x-ndjson")
+            .await?;
+        match self
+            .write_manifest(&mut tx, id, &marker, upload.as_mut())
+            .await
+        {
+            Ok(totals) => {
+                upload.finish().await?;
+                tx.commit().await?;
+                Ok((marker, totals))
+            }
+            Err(e) => {
+                upload.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_manifest(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+        marker: &SnapshotMarker,
+        upload: &mut dyn ChunkedUpload,
+    ) -> anyhow::Result<ManifestTotals> {
+        let header = serde_json::json!({
+            "type": "snapshot",
+            "snapshot_id": id,
+            "wal_lsn": marker.wal_lsn,
+            "pg_snapshot": marker.pg_snapshot,
+            "snapshot_at": marker.snapshot_at,
+        });
+        upload.write(format!("{}\n", header).as_bytes()).await?;
+
+        let mut totals = ManifestTotals::default();
+        let mut after = Uuid::nil();
+        loop {
+            let page = sqlx::query_as::<_, LetteringObjects>(
+                "SELECT l.id, l.image_url, l.thumbnail_small, l.thumbnail_medium, l.thumbnail_large,
+                        ARRAY(
+                            SELECT v.storage_key FROM image_variants v
+                            WHERE v.lettering_id = l.id
+                            ORDER BY v.storage_key
+                        ) AS variant_keys
+                 FROM letterings l
+                 WHERE l.id > $1
+                 ORDER BY l.id ASC
+                 LIMIT $2",
+            )
+            .bind(after)
+            .bind(PAGE_SIZE)
+            .fetch_all(&mut **tx)
+            .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = last.id;
+
+            let mut buf = String::new();
+            for row in &page {
+                for (kind, key) in row.keys(self.storage.as_ref()) {
+                    let line = serde_json::json!({
+                        "type": "object",
+                        "key": key,
+                        "kind": kind,
+                        "lettering_id": row.id,
+                    });
+                    buf.push_str(&line.to_string());
+                    buf.push('\n');
+                    totals.objects += 1;
+                }
+            }
+            totals.letterings += page.len() as i32;
+            upload.write(buf.as_bytes()).await?;
+        }
+        Ok(totals)
+    }
+}
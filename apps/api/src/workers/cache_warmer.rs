@@ -0,0 +1,151 @@
+//! Keeps the heaviest public reads in cache.
+//!
+//! Each target fills one or more cached responses through the same path a
+//! request would, so a pass costs a Redis read per key that is still there
+//! and a full query per key that is not. Passes run at startup, every
+//! `CACHE_WARM_INTERVAL_SECONDS`, and shortly after the [`CacheInvalidator`]
+//! drops reads on this instance, so neither the first user after a deploy
+//! nor the first after a moderation change pays for the query. Every
+//! instance warms; the cache's stampede protection keeps them from running
+//! the same query at once.
+
+use crate::infrastructure::{
+    cache::invalidation::CacheInvalidator, monitoring::heartbeat::Heartbeats, shutdown::Shutdown,
+};
+use futures_util::future::BoxFuture;
+use std::{fmt::Display, future::Future, time::Duration};
+
+const WORKER_NAME: &str = "cache_warmer";
+
+/// Wait after a change before warming, so a burst of changes such as a
+/// bulk approval is warmed once.
+const SETTLE_DELAY: Duration = Duration::from_secs(2);
+
+type WarmFn<C> = Box<dyn Fn(C) -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+struct Target<C> {
+    name: &'static str,
+    warm: WarmFn<C>,
+}
+
+/// Fills the cache for each target with a clone of `context`.
+pub struct CacheWarmer<C> {
+    context: C,
+    targets: Vec<Target<C>>,
+    interval: Duration,
+    invalidator: CacheInvalidator,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl<C: Clone + Send + Sync + 'static> CacheWarmer<C> {
+    pub fn new(context: C, interval: Duration) -> Self {
+        Self {
+            context,
+            targets: Vec::new(),
+            interval,
+            invalidator: CacheInvalidator::disabled(),
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Warm `name` by calling `warm` on each pass.
+    pub fn with_target<F, Fut, E>(mut self, name: &'static str, warm: F) -> Self
+    where
+        F: Fn(C) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        self.targets.push(Target {
+            name,
+            warm: Box::new(move |context| {
+                let warming = warm(context);
+                Box::pin(async move { warming.await.map_err(|e| e.to_string()) })
+            }),
+        });
+        self
+    }
+
+    /// Warm again shortly after `invalidator` drops reads.
+    pub fn with_cache_invalidator(mut self, invalidator: CacheInvalidator) -> Self {
+        self.invalidator = invalidator;
+        self
+    }
+
+    /// Beat between passes and name the target being warmed.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Stop after the pass in hand once `shutdown` fires.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn start(&self) {
+        while !self.shutdown.is_triggered() {
+            self.warm_once().await;
+            self.heartbeats.beat(WORKER_NAME, self.interval).await;
+            tokio::select! {
+                _ = self.shutdown.sleep(self.interval) => {}
+                _ = self.invalidator.changed() => {
+                    self.shutdown.sleep(SETTLE_DELAY).await;
+                }
+            }
+        }
+    }
+
+    /// Warms every target, returning how many failed. Failures are logged
+    /// and leave the next reader to fill the cache.
+    pub async fn warm_once(&self) -> usize {
+        let mut failed = 0;
+        for target in &self.targets {
+            if self.shutdown.is_triggered() {
+                break;
+            }
+            self.heartbeats
+                .working_on(WORKER_NAME, target.name, self.interval)
+                .await;
+            if let Err(e) = (target.warm)(self.context.clone()).await {
+                tracing::warn!(target = target.name, "Cache warming failed: {}", e);
+                failed += 1;
+            }
+        }
+        self.heartbeats
+            .count(
+                WORKER_NAME,
+                (self.targets.len() - failed) as u64,
+                failed as u64,
+            )
+            .await;
+        failed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    #[tokio::test]
+    async fn every_target_is_warmed_and_failures_counted() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let warmer = CacheWarmer::new(calls.clone(), Duration::from_secs(60))
+            .with_target("ok", |calls: Arc<AtomicU32>| async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>(())
+            })
+            .with_target("broken", |calls: Arc<AtomicU32>| async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err("database unavailable")
+            });
+        assert_eq!(warmer.warm_once().await, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,94 @@
+use crate::infrastructure::{
+    cdn::cloudflare_purge::{CloudflarePurger, retry_delay},
+    monitoring::heartbeat::Heartbeats,
+    queue::redis_queue::RedisQueue,
+    shutdown::Shutdown,
+};
+use std::{sync::Arc, time::Duration};
+
+const WORKER_NAME: &str = "cdn_purge_retry";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Give up after this many attempts; by then the edge TTL is the lesser evil.
+const MAX_ATTEMPTS: u32 = 8;
+
+pub struct CdnPurgeRetryWorker {
+    purger: Arc<CloudflarePurger>,
+    queue: Arc<RedisQueue>,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl CdnPurgeRetryWorker {
+    pub fn new(purger: Arc<CloudflarePurger>, queue: Arc<RedisQueue>) -> Self {
+        Self {
+            purger,
+            queue,
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Beat between polls and count purges that went through or failed.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Once `shutdown` fires, finish the purges already taken and stop.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn start(&self) {
+        while !self.shutdown.is_triggered() {
+            match self.queue.take_due_cdn_purges(20).await {
+                Ok(jobs) => {
+                    for mut job in jobs {
+                        match self.purger.purge(&job.urls).await {
+                            Ok(()) => {
+                                self.heartbeats.count(WORKER_NAME, 1, 0).await;
+                                tracing::info!(
+                                    job_id = %job.id,
+                                    attempts = job.attempts + 1,
+                                    "CDN purge retry succeeded"
+                                );
+                            }
+                            Err(e) if job.attempts + 1 >= MAX_ATTEMPTS => {
+                                self.heartbeats.count(WORKER_NAME, 0, 1).await;
+                                tracing::error!(
+                                    job_id = %job.id,
+                                    urls = ?job.urls,
+                                    "CDN purge abandoned after {} attempts: {}",
+                                    job.attempts + 1,
+                                    e
+                                );
+                            }
+                            Err(e) => {
+                                self.heartbeats.count(WORKER_NAME, 0, 1).await;
+                                job.attempts += 1;
+                                tracing::warn!(
+                                    job_id = %job.id,
+                                    attempts = job.attempts,
+                                    "CDN purge retry failed: {}",
+                                    e
+                                );
+                                if let Err(e) = self
+                                    .queue
+                                    .schedule_cdn_purge(&job, retry_delay(job.attempts))
+                                    .await
+                                {
+                                    tracing::error!(job_id = %job.id, "Failed to reschedule CDN purge: {}", e);
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to read CDN purge retry queue: {}", e),
+            }
+            self.heartbeats.beat(WORKER_NAME, POLL_INTERVAL).await;
+            self.shutdown.sleep(POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::infrastructure::{
+    monitoring::throttle::WorkerThrottle,
+    repositories::region_policy::{PUBLIC_LETTERING_JOINS, push_public_filter},
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use std::time::Duration;
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "follow_notifier";
+
+/// Follows evaluated per batch.
+const BATCH_SIZE: i64 = 200;
+
+/// Lettering ids listed in one notification's metadata.
+const MAX_NOTIFIED_IDS: i64 = 10;
+
+/// One follow: a contributor tag or an account.
+#[derive(Debug, Clone, FromRow)]
+pub struct FollowTarget {
+    pub id: Uuid,
+    pub follower_id: Uuid,
+    pub contributor_tag: Option<String>,
+    pub followed_user_id: Option<Uuid>,
+    /// How the contributor is named in the notification.
+    pub label: String,
+    pub last_checked_at: DateTime<Utc>,
+}
+
+/// Public letterings by the followed contributor. Expects `letterings l` with
+/// `PUBLIC_LETTERING_JOINS`; age-restricted items in gating regions are left
+/// out since a notification can't carry the client's age acknowledgment.
+pub fn push_follow_filters(qb: &mut QueryBuilder<'_, Postgres>, follow: &FollowTarget) {
+    qb.push(" AND ");
+    push_public_filter(qb, false);
+    // A tag can be shared, so the follower's own uploads under it are skipped.
+    qb.push(" AND l.user_id IS DISTINCT FROM ")
+        .push_bind(follow.follower_id);
+
+    match (&follow.contributor_tag, follow.followed_user_id) {
+        (Some(tag), _) => {
+            qb.push(" AND l.contributor_tag = ").push_bind(tag.clone());
+        }
+        (None, Some(user_id)) => {
+            qb.push(" AND l.user_id = ").push_bind(user_id);
+        }
+        (None, None) => {
+            qb.push(" AND false");
+        }
+    }
+}
+
+/// Notifies followers when letterings by contributors they follow are
+/// approved: one `FOLLOWED_CONTRIBUTOR_APPROVALS` notification per follow per
+/// pass, however many letterings were approved.
+pub struct FollowNotifier {
+    db: PgPool,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl FollowNotifier {
+    pub fn new(db: PgPool, interval_seconds: u64) -> Self {
+        Self {
+            db,
+            interval_seconds: interval_seconds.max(60),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(notified) if notified > 0 => {
+                    tracing::info!(notified, "Follow notifications sent");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Follow notification pass failed: {}", e),
+            }
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Evaluates every follow against approvals up to the start of the pass,
+    /// oldest check first, and returns how many notifications were sent.
+    pub async fn run_once(&self) -> Result<usize, sqlx::Error> {
+        let until: DateTime<Utc> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&self.db)
+            .await?;
+        let mut notified = 0;
+        loop {
+            let follows = sqlx::query_as::<_, FollowTarget>(
+                "SELECT f.id, f.follower_id, f.contributor_tag, f.followed_user_id,
+                        COALESCE(f.contributor_tag, u.display_name, 'a contributor you follow') AS label,
+                        f.last_checked_at
+                 FROM follows f
+                 LEFT JOIN users u ON u.id = f.followed_user_id
+                 WHERE f.last_checked_at < $1
+                 ORDER BY f.last_checked_at
+                 LIMIT $2",
+            )
+            .bind(until)
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await?;
+
+            for follow in &follows {
+                if self.evaluate(follow, until).await? {
+                    notified += 1;
+                }
+            }
+            if (follows.len() as i64) < BATCH_SIZE {
+                return Ok(notified);
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                return Ok(notified);
+            }
+        }
+    }
+
+    /// Notifies about one follow's new approvals, if any, and moves its check
+    /// mark to `until`.
+    async fn evaluate(
+        &self,
+        follow: &FollowTarget,
+        until: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT l.id, COUNT(*) OVER ()::bigint AS total FROM letterings l ",
+        );
+        qb.push(PUBLIC_LETTERING_JOINS).push(
+            " WHERE EXISTS (
+                 SELECT 1 FROM lettering_status_history h
+                 WHERE h.lettering_id = l.id AND h.to_status = 'APPROVED'
+                   AND h.created_at > ",
+        );
+        qb.push_bind(follow.last_checked_at)
+            .push(" AND h.created_at <= ")
+            .push_bind(until)
+            .push(")");
+        push_follow_filters(&mut qb, follow);
+        qb.push(" ORDER BY l.created_at DESC LIMIT ")
+            .push_bind(MAX_NOTIFIED_IDS);
+
+        let matches: Vec<(Uuid, i64)> = qb.build_query_as().fetch_all(&self.db).await?;
+
+        let mut tx = self.db.begin().await?;
+        if let Some(&(_, total)) = matches.first() {
+            let ids: Vec<Uuid> = matches.iter().map(|(id, _)| *id).collect();
+            let body = if total == 1 {
+                format!("{} has a newly approved lettering.", follow.label)
+            } else {
+                format!("{} has {} newly approved letterings.", follow.label, total)
+            };
+            sqlx::query(
+                "INSERT INTO notifications (id, user_id, type, title, body, metadata)
+                 VALUES ($1, $2, 'FOLLOWED_CONTRIBUTOR_APPROVALS', $3, $4, $5)",
+            )
+            .bind(Uuid::now_v7())
+            .bind(follow.follower_id)
+            .bind(format!("New from {}", follow.label))
+            .bind(body)
+            .bind(serde_json::json!({
+                "follow_id": follow.id,
+                "contributor_tag": follow.contributor_tag,
+                "user_id": follow.followed_user_id,
+                "count": total,
+                "lettering_ids": ids,
+            }))
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("UPDATE follows SET last_checked_at = $2 WHERE id = $1")
+            .bind(follow.id)
+            .bind(until)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(!matches.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follow(contributor_tag: Option<&str>, followed_user_id: Option<Uuid>) -> FollowTarget {
+        FollowTarget {
+            id: Uuid::nil(),
+            follower_id: Uuid::nil(),
+            contributor_tag: contributor_tag.map(str::to_string),
+            followed_user_id,
+            label: "Ghost signs".to_string(),
+            last_checked_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn filters_on_the_followed_target() {
+        let mut qb = QueryBuilder::<Postgres>::new("SELECT l.id FROM letterings l WHERE true");
+        push_follow_filters(&mut qb, &follow(Some("ghost_signs"), None));
+        let sql = qb.sql();
+        assert!(sql.contains("l.status = 'APPROVED'"));
+        assert!(sql.contains("l.contributor_tag = "));
+        assert!(!sql.contains("l.user_id = "));
+
+        let mut qb = QueryBuilder::<Postgres>::new("SELECT l.id FROM letterings l WHERE true");
+        push_follow_filters(&mut qb, &follow(None, Some(Uuid::now_v7())));
+        let sql = qb.sql();
+        assert!(sql.contains("l.user_id = "));
+        assert!(!sql.contains("l.contributor_tag = "));
+    }
+}
@@ -0,0 +1,34 @@
+use super::scheduler::ScheduledTask;
+use crate::infrastructure::geocoding::ip_geolocation::IpGeolocator;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Deletes geo annotations and cached IP lookups past the retention window.
+pub struct GeoRetentionWorker {
+    geolocator: Arc<IpGeolocator>,
+    retention_days: i32,
+}
+
+impl GeoRetentionWorker {
+    pub fn new(geolocator: Arc<IpGeolocator>, retention_days: i32) -> Self {
+        Self {
+            geolocator,
+            retention_days: retention_days.max(1),
+        }
+    }
+}
+
+#[async_trait]
+impl ScheduledTask for GeoRetentionWorker {
+    fn name(&self) -> &'static str {
+        "geo_retention"
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        let (annotations, cache) = self.geolocator.prune(self.retention_days).await?;
+        if annotations + cache > 0 {
+            tracing::info!(annotations, cache, "Pruned expired IP geolocation data");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,260 @@
+//! Moves letterings' images between the private and public buckets.
+//!
+//! A lettering's image and thumbnails are public exactly while it is
+//! approved. [`sync_visibility`] moves them as soon as a status changes;
+//! [`ImagePublisherWorker`] sweeps up letterings a failed or missed move left
+//! in the wrong bucket, and originals still at their old public key.
+
+use super::storage_gc::{run_cleanup, schedule_cleanup};
+use crate::infrastructure::{
+    cache::invalidation::CacheInvalidator,
+    monitoring::throttle::WorkerThrottle,
+    storage::{
+        content_addressed,
+        traits::StorageService,
+        visibility::{legacy_original_key, original_key, private_key, public_key},
+    },
+};
+use sqlx::{FromRow, PgPool};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "image_publisher";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Letterings moved per batch.
+const BATCH_SIZE: i64 = 50;
+
+#[derive(Debug, FromRow)]
+struct Placement {
+    status: String,
+    image_url: String,
+    thumbnail_small: String,
+    thumbnail_medium: String,
+    thumbnail_large: String,
+    images_public: bool,
+}
+
+/// Move the images of each of `ids` to the bucket its status calls for and
+/// drop cached reads of those that moved. Returns the letterings moved; one
+/// that fails is logged and left for the worker's next pass.
+pub async fn sync_visibility(
+    db: &PgPool,
+    storage: &dyn StorageService,
+    invalidator: &CacheInvalidator,
+    ids: &[Uuid],
+) -> Vec<Uuid> {
+    let mut moved = Vec::new();
+    for &id in ids {
+        match move_images(db, storage, id).await {
+            Ok(true) => moved.push(id),
+            Ok(false) => {}
+            Err(e) => tracing::warn!(lettering_id = %id, "Failed to move images: {}", e),
+        }
+    }
+    if !moved.is_empty() {
+        invalidator.letterings_changed(&moved).await;
+    }
+    moved
+}
+
+/// Copy one lettering's images to the other bucket, point it at the copies
+/// and release the objects left behind. The row stays locked throughout, so
+/// a status change in the meantime waits and then moves them again.
+async fn move_images(db: &PgPool, storage: &dyn StorageService, id: Uuid) -> anyhow::Result<bool> {
+    let mut tx = db.begin().await?;
+    let Some(placement) = sqlx::query_as::<_, Placement>(
+        "SELECT status, image_url, thumbnail_small, thumbnail_medium, thumbnail_large, images_public
+         FROM letterings WHERE id = $1
+         FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(false);
+    };
+    let public = placement.status == "APPROVED";
+    if placement.images_public == public {
+        return Ok(false);
+    }
+
+    // URLs outside our storage, such as seeded images, stay as they are.
+    let mut copies: HashMap<String, String> = HashMap::new();
+    let mut left_behind = Vec::new();
+    for url in [
+        &placement.image_url,
+        &placement.thumbnail_small,
+        &placement.thumbnail_medium,
+        &placement.thumbnail_large,
+    ] {
+        if copies.contains_key(url) {
+            continue;
+        }
+        let Some(key) = storage.key_from_url(url) else {
+            continue;
+        };
+        let target = if public {
+            public_key(&key).to_string()
+        } else {
+            private_key(&key)
+        };
+        if target == key {
+            continue;
+        }
+        let copy = content_addressed::copy(db, storage, &key, &target).await?;
+        copies.insert(url.clone(), copy);
+        left_behind.push(key);
+    }
+    let moved = |url: &String| copies.get(url).unwrap_or(url).clone();
+
+    sqlx::query(
+        "UPDATE letterings
+         SET image_url = $2, thumbnail_small = $3, thumbnail_medium = $4,
+             thumbnail_large = $5, images_public = $6
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(moved(&placement.image_url))
+    .bind(moved(&placement.thumbnail_small))
+    .bind(moved(&placement.thumbnail_medium))
+    .bind(moved(&placement.thumbnail_large))
+    .bind(public)
+    .execute(&mut *tx)
+    .await?;
+    let unused = content_addressed::release(&mut tx, &left_behind).await?;
+    let cleanup = if unused.is_empty() {
+        None
+    } else {
+        Some(schedule_cleanup(&mut tx, Some(id), unused).await?)
+    };
+    tx.commit().await?;
+
+    if let Some(job) = cleanup
+        && let Err(e) = run_cleanup(db, storage, &job).await
+    {
+        tracing::warn!(lettering_id = %id, "Failed to delete moved images: {}", e);
+    }
+    tracing::debug!(lettering_id = %id, public, "Moved lettering images");
+    Ok(true)
+}
+
+/// Move an original uploaded before originals were kept private to its
+/// private key. Letterings without one are simply marked done.
+async fn hide_original(db: &PgPool, storage: &dyn StorageService, id: Uuid) -> anyhow::Result<()> {
+    let legacy = legacy_original_key(id);
+    if let Some(data) = storage.download(&legacy).await? {
+        let content_type = image::guess_format(&data)
+            .map(|format| format.to_mime_type())
+            .unwrap_or("application/octet-stream");
+        storage
+            .upload(&original_key(id), data, content_type)
+            .await?;
+        storage.delete(&legacy).await?;
+    }
+    sqlx::query("UPDATE letterings SET original_private = true WHERE id = $1")
+        .bind(id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Moves images left in the wrong bucket and originals left public.
+pub struct ImagePublisherWorker {
+    db: PgPool,
+    storage: Arc<dyn StorageService>,
+    throttle: WorkerThrottle,
+    invalidator: CacheInvalidator,
+}
+
+impl ImagePublisherWorker {
+    pub fn new(db: PgPool, storage: Arc<dyn StorageService>) -> Self {
+        Self {
+            db,
+            storage,
+            throttle: WorkerThrottle::unthrottled(),
+            invalidator: CacheInvalidator::disabled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Drop cached reads of the letterings each pass moves.
+    pub fn with_cache_invalidator(mut self, invalidator: CacheInvalidator) -> Self {
+        self.invalidator = invalidator;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(moved) if moved > 0 => {
+                    tracing::info!(moved, "Image publisher pass finished");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Image publisher pass failed: {}", e),
+            }
+            if !self.throttle.pace(WORKER_NAME, POLL_INTERVAL).await {
+                break;
+            }
+        }
+    }
+
+    /// Moves every misplaced lettering and returns how many moved. One that
+    /// fails is logged and left for the next pass.
+    pub async fn run_once(&self) -> Result<usize, sqlx::Error> {
+        let mut moved = 0;
+        let mut after = Uuid::nil();
+        loop {
+            let batch: Vec<(Uuid, bool, bool)> = sqlx::query_as(
+                "SELECT id, images_public <> (status = 'APPROVED'), original_private
+                 FROM letterings
+                 WHERE (images_public <> (status = 'APPROVED') OR NOT original_private)
+                   AND id > $1
+                 ORDER BY id
+                 LIMIT $2",
+            )
+            .bind(after)
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await?;
+
+            let misplaced: Vec<Uuid> = batch
+                .iter()
+                .filter(|(_, misplaced, _)| *misplaced)
+                .map(|(id, _, _)| *id)
+                .collect();
+            moved += sync_visibility(
+                &self.db,
+                self.storage.as_ref(),
+                &self.invalidator,
+                &misplaced,
+            )
+            .await
+            .len();
+            for &(id, _, original_private) in &batch {
+                if !original_private
+                    && let Err(e) = hide_original(&self.db, self.storage.as_ref(), id).await
+                {
+                    tracing::warn!(lettering_id = %id, "Failed to hide original: {}", e);
+                }
+            }
+            let Some(&(last, _, _)) = batch.last() else {
+                break;
+            };
+            after = last;
+            if (batch.len() as i64) < BATCH_SIZE {
+                break;
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                break;
+            }
+        }
+        Ok(moved)
+    }
+}
@@ -0,0 +1,197 @@
+use crate::infrastructure::{
+    monitoring::{Alert, AlertSeverity, throttle::WorkerThrottle},
+    storage::traits::StorageService,
+};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// Signed URLs only need to outlive a single download.
+const FETCH_URL_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, FromRow)]
+struct IntegrityCandidate {
+    id: Uuid,
+    image_url: String,
+    image_hash: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IntegrityOutcome {
+    Ok,
+    Missing,
+    Corrupt,
+}
+
+impl IntegrityOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Missing => "MISSING",
+            Self::Corrupt => "CORRUPT",
+        }
+    }
+}
+
+/// Re-downloads a sample of stored originals and compares their SHA-256
+/// against `letterings.image_hash`.
+///
+/// Rows are sampled least-recently-checked first so the whole archive is
+/// covered over time. Missing or corrupt objects are flagged on the row and
+/// reported through a monitoring `Alert`; transient fetch errors are skipped
+/// and retried on a later pass.
+pub struct IntegrityVerifier {
+    db: PgPool,
+    storage: Arc<dyn StorageService>,
+    interval_seconds: u64,
+    sample_size: i64,
+    throttle: WorkerThrottle,
+}
+
+impl IntegrityVerifier {
+    pub fn new(
+        db: PgPool,
+        storage: Arc<dyn StorageService>,
+        interval_seconds: u64,
+        sample_size: i64,
+    ) -> Self {
+        Self {
+            db,
+            storage,
+            interval_seconds: interval_seconds.max(60),
+            sample_size: sample_size.max(1),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        loop {
+            if let Err(e) = self.run_once(&client).await {
+                tracing::error!("Image integrity verification pass failed: {}", e);
+            }
+            if !self
+                .throttle
+                .pace(
+                    "integrity_verifier",
+                    Duration::from_secs(self.interval_seconds),
+                )
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    async fn run_once(&self, client: &reqwest::Client) -> anyhow::Result<()> {
+        let candidates = sqlx::query_as::<_, IntegrityCandidate>(
+            "SELECT id, image_url, image_hash
+             FROM letterings
+             WHERE image_hash IS NOT NULL
+             ORDER BY integrity_checked_at ASC NULLS FIRST, created_at ASC
+             LIMIT $1",
+        )
+        .bind(self.sample_size)
+        .fetch_all(&self.db)
+        .await?;
+
+        let checked = candidates.len();
+        let mut missing = Vec::new();
+        let mut corrupt = Vec::new();
+
+        for candidate in candidates {
+            let outcome = match self.verify(client, &candidate).await {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    tracing::warn!(
+                        lettering_id = %candidate.id,
+                        "Integrity check skipped: {}",
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            sqlx::query(
+                "UPDATE letterings SET integrity_status = $1, integrity_checked_at = NOW() WHERE id = $2",
+            )
+            .bind(outcome.as_str())
+            .bind(candidate.id)
+            .execute(&self.db)
+            .await?;
+
+            match outcome {
+                IntegrityOutcome::Missing => missing.push(candidate.id),
+                IntegrityOutcome::Corrupt => corrupt.push(candidate.id),
+                IntegrityOutcome::Ok => {}
+            }
+        }
+
+        let failures = missing.len() + corrupt.len();
+        if failures > 0 {
+            let alert = Alert::new(
+                AlertSeverity::Critical,
+                "Stored image integrity failure",
+                &format!(
+                    "{} of {} sampled objects failed verification (missing: {:?}, corrupt: {:?})",
+                    failures, checked, missing, corrupt
+                ),
+                "storage_integrity_failures",
+                0.0,
+                failures as f64,
+            );
+            let payload = serde_json::to_string(&alert).unwrap_or_default();
+            tracing::error!(alert = %payload, "{}", alert.title);
+        } else {
+            tracing::debug!(checked, "Image integrity verification pass clean");
+        }
+
+        Ok(())
+    }
+
+    async fn verify(
+        &self,
+        client: &reqwest::Client,
+        candidate: &IntegrityCandidate,
+    ) -> anyhow::Result<IntegrityOutcome> {
+        // Always go through a signed URL: non-approved objects aren't public.
+        let url = match self.storage.key_from_url(&candidate.image_url) {
+            Some(key) => self.storage.signed_url(&key, FETCH_URL_TTL).await?,
+            None => candidate.image_url.clone(),
+        };
+
+        let response = client.get(&url).send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(IntegrityOutcome::Missing);
+        }
+        if !status.is_success() {
+            anyhow::bail!("object fetch returned HTTP {}", status);
+        }
+
+        let bytes = response.bytes().await?;
+        if bytes.is_empty() {
+            return Ok(IntegrityOutcome::Missing);
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(&bytes);
+        let actual = format!("{:x}", hasher.finalize());
+
+        if actual.eq_ignore_ascii_case(&candidate.image_hash) {
+            Ok(IntegrityOutcome::Ok)
+        } else {
+            Ok(IntegrityOutcome::Corrupt)
+        }
+    }
+}
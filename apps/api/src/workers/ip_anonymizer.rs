@@ -0,0 +1,171 @@
+use crate::{config::IpAnonymizationMode, infrastructure::monitoring::throttle::WorkerThrottle};
+use sqlx::PgPool;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Recorded as `admin_sub` on the per-run audit entry.
+pub const AUDIT_ACTOR: &str = "system:ip-anonymizer";
+
+/// Rows updated per statement, keeping lock hold times short.
+const BATCH_SIZE: i64 = 1000;
+
+/// IPv4 addresses keep their /24, IPv6 their /48.
+fn truncated_ip(column: &str) -> String {
+    format!(
+        "CASE family({c}) WHEN 4 THEN network(set_masklen({c}, 24))::inet \
+         ELSE network(set_masklen({c}, 48))::inet END",
+        c = column
+    )
+}
+
+fn not_yet_truncated(column: &str) -> String {
+    format!(
+        "masklen({c}) > CASE family({c}) WHEN 4 THEN 24 ELSE 48 END",
+        c = column
+    )
+}
+
+#[derive(Debug, Default)]
+pub struct AnonymizationRun {
+    pub letterings: u64,
+    pub comments: u64,
+}
+
+/// Anonymizes stored client IPs older than the retention window.
+///
+/// Covers `letterings.uploaded_by_ip` and `comments.user_ip`. `likes.user_ip`
+/// is left alone: it is the per-lettering dedup key and a truncated value
+/// would collide under its unique constraint. Each run writes an
+/// `ANONYMIZE_IPS` entry to `admin_audit_logs` with the affected row counts.
+pub struct IpAnonymizer {
+    db: PgPool,
+    retention_days: i32,
+    mode: IpAnonymizationMode,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl IpAnonymizer {
+    pub fn new(
+        db: PgPool,
+        retention_days: i32,
+        mode: IpAnonymizationMode,
+        interval_seconds: u64,
+    ) -> Self {
+        Self {
+            db,
+            retention_days: retention_days.max(1),
+            mode,
+            interval_seconds: interval_seconds.max(60),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            if let Err(e) = self.run_once().await {
+                tracing::error!("IP anonymization run failed: {}", e);
+            }
+            if !self
+                .throttle
+                .pace("ip_anonymizer", Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    pub async fn run_once(&self) -> anyhow::Result<AnonymizationRun> {
+        let started = Instant::now();
+        let run = AnonymizationRun {
+            letterings: self
+                .anonymize_column("letterings", "uploaded_by_ip")
+                .await?,
+            comments: self.anonymize_column("comments", "user_ip").await?,
+        };
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        tracing::info!(
+            letterings = run.letterings,
+            comments = run.comments,
+            duration_ms,
+            mode = self.mode.as_str(),
+            "IP anonymization run completed"
+        );
+
+        sqlx::query(
+            "INSERT INTO admin_audit_logs (id, admin_sub, action, metadata, created_at)
+             VALUES ($1, $2, 'ANONYMIZE_IPS', $3, NOW())",
+        )
+        .bind(Uuid::now_v7())
+        .bind(AUDIT_ACTOR)
+        .bind(serde_json::json!({
+            "mode": self.mode.as_str(),
+            "retention_days": self.retention_days,
+            "letterings": run.letterings,
+            "comments": run.comments,
+            "duration_ms": duration_ms,
+        }))
+        .execute(&self.db)
+        .await?;
+
+        Ok(run)
+    }
+
+    async fn anonymize_column(&self, table: &str, column: &str) -> anyhow::Result<u64> {
+        let (target, pending) = match self.mode {
+            IpAnonymizationMode::Truncate => (truncated_ip(column), not_yet_truncated(column)),
+            IpAnonymizationMode::Null => ("NULL".to_string(), "TRUE".to_string()),
+        };
+        let sql = format!(
+            "WITH batch AS (
+                 SELECT id FROM {table}
+                 WHERE {column} IS NOT NULL
+                   AND created_at < NOW() - make_interval(days => $1)
+                   AND {pending}
+                 LIMIT $2
+             )
+             UPDATE {table} SET {column} = {target}
+             WHERE id IN (SELECT id FROM batch)"
+        );
+
+        let mut total = 0;
+        loop {
+            let affected = sqlx::query(&sql)
+                .bind(self.retention_days)
+                .bind(BATCH_SIZE)
+                .execute(&self.db)
+                .await?
+                .rows_affected();
+            total += affected;
+            if affected < BATCH_SIZE as u64 {
+                return Ok(total);
+            }
+            if !self.throttle.between_batches("ip_anonymizer").await {
+                return Ok(total);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncation_sql_targets_column() {
+        assert_eq!(
+            not_yet_truncated("user_ip"),
+            "masklen(user_ip) > CASE family(user_ip) WHEN 4 THEN 24 ELSE 48 END"
+        );
+        assert!(truncated_ip("uploaded_by_ip").contains("set_masklen(uploaded_by_ip, 24)"));
+        assert!(truncated_ip("uploaded_by_ip").contains("set_masklen(uploaded_by_ip, 48)"));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::infrastructure::monitoring::throttle::WorkerThrottle;
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "like_digest";
+
+/// Letterings digested per batch.
+const BATCH_SIZE: i64 = 200;
+
+/// Characters of detected text quoted in a digest.
+const QUOTE_CHARS: usize = 60;
+
+#[derive(Debug, FromRow)]
+struct PendingDigest {
+    lettering_id: Uuid,
+    user_id: Uuid,
+    detected_text: Option<String>,
+    new_likes: i64,
+    notification_id: Option<Uuid>,
+}
+
+/// "12 people liked your upload".
+pub fn digest_title(likes: i64) -> String {
+    if likes == 1 {
+        "Someone liked your upload".to_string()
+    } else {
+        format!("{} people liked your upload", likes)
+    }
+}
+
+fn digest_body(detected_text: Option<&str>) -> Option<String> {
+    let text = detected_text.map(str::trim).filter(|t| !t.is_empty())?;
+    let quoted: String = text.chars().take(QUOTE_CHARS).collect();
+    if quoted.len() < text.len() {
+        Some(format!("\"{}…\"", quoted))
+    } else {
+        Some(format!("\"{}\"", quoted))
+    }
+}
+
+/// Notifies uploaders about likes on their letterings in batches rather than
+/// one by one: a `LETTERING_LIKES` notification per lettering at most once
+/// per window, counting the likes since the last one. While that previous
+/// notification is unread the new likes are added to it instead, so a
+/// popular upload keeps a single notification with a growing count.
+///
+/// The first digest for a lettering counts only likes within the window, so
+/// likes from before the worker ran are never announced.
+pub struct LikeDigestWorker {
+    db: PgPool,
+    window_minutes: i32,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl LikeDigestWorker {
+    pub fn new(db: PgPool, window_minutes: i32, interval_seconds: u64) -> Self {
+        Self {
+            db,
+            window_minutes: window_minutes.max(0),
+            interval_seconds: interval_seconds.max(30),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(digested) if digested > 0 => {
+                    tracing::debug!(digested, "Like digests sent");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Like digest pass failed: {}", e),
+            }
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Digests likes up to the start of the pass on every lettering whose
+    /// window has passed, and returns how many letterings were digested.
+    pub async fn run_once(&self) -> Result<u64, sqlx::Error> {
+        let until: DateTime<Utc> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&self.db)
+            .await?;
+        let mut after = Uuid::nil();
+        let mut digested = 0;
+        loop {
+            let batch = sqlx::query_as::<_, PendingDigest>(
+                "SELECT l.id AS lettering_id, l.user_id, l.detected_text,
+                        COUNT(*)::bigint AS new_likes, m.notification_id
+                 FROM letterings l
+                 JOIN likes k ON k.lettering_id = l.id
+                 LEFT JOIN like_digest_marks m ON m.lettering_id = l.id
+                 WHERE l.id > $1 AND l.user_id IS NOT NULL
+                   AND k.created_at > COALESCE(m.notified_through, $2 - make_interval(mins => $3))
+                   AND k.created_at <= $2
+                   AND (m.notified_at IS NULL OR m.notified_at <= $2 - make_interval(mins => $3))
+                 GROUP BY l.id, m.notification_id
+                 ORDER BY l.id
+                 LIMIT $4",
+            )
+            .bind(after)
+            .bind(until)
+            .bind(self.window_minutes)
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after = last.lettering_id;
+
+            for pending in &batch {
+                self.digest(pending, until).await?;
+                digested += 1;
+            }
+
+            if (batch.len() as i64) < BATCH_SIZE {
+                break;
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                break;
+            }
+        }
+        Ok(digested)
+    }
+
+    /// Adds `pending`'s likes to its unread digest, or sends a new one, and
+    /// marks them notified.
+    async fn digest(
+        &self,
+        pending: &PendingDigest,
+        until: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let unread: Option<i64> = match pending.notification_id {
+            Some(id) => {
+                sqlx::query_scalar(
+                    "SELECT COALESCE((metadata->>'count')::bigint, 0) FROM notifications
+                     WHERE id = $1 AND user_id = $2 AND is_read = false
+                     FOR UPDATE",
+                )
+                .bind(id)
+                .bind(pending.user_id)
+                .fetch_optional(&mut *tx)
+                .await?
+            }
+            None => None,
+        };
+        let body = digest_body(pending.detected_text.as_deref());
+
+        let notification_id = match (pending.notification_id, unread) {
+            (Some(id), Some(count)) => {
+                let total = count + pending.new_likes;
+                sqlx::query(
+                    "UPDATE notifications
+                     SET title = $2, body = $3,
+                         metadata = jsonb_set(metadata, '{count}', to_jsonb($4::bigint)),
+                         created_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(id)
+                .bind(digest_title(total))
+                .bind(&body)
+                .bind(total)
+                .execute(&mut *tx)
+                .await?;
+                id
+            }
+            _ => {
+                let id = Uuid::now_v7();
+                sqlx::query(
+                    "INSERT INTO notifications (id, user_id, type, title, body, metadata)
+                     VALUES ($1, $2, 'LETTERING_LIKES', $3, $4, $5)",
+                )
+                .bind(id)
+                .bind(pending.user_id)
+                .bind(digest_title(pending.new_likes))
+                .bind(&body)
+                .bind(serde_json::json!({
+                    "lettering_id": pending.lettering_id,
+                    "count": pending.new_likes,
+                }))
+                .execute(&mut *tx)
+                .await?;
+                id
+            }
+        };
+
+        sqlx::query(
+            "INSERT INTO like_digest_marks (lettering_id, notified_through, notified_at, notification_id)
+             VALUES ($1, $2, NOW(), $3)
+             ON CONFLICT (lettering_id) DO UPDATE SET
+                 notified_through = EXCLUDED.notified_through,
+                 notified_at = EXCLUDED.notified_at,
+                 notification_id = EXCLUDED.notification_id",
+        )
+        .bind(pending.lettering_id)
+        .bind(until)
+        .bind(notification_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_count_people() {
+        assert_eq!(digest_title(1), "Someone liked your upload");
+        assert_eq!(digest_title(12), "12 people liked your upload");
+    }
+
+    #[test]
+    fn bodies_quote_detected_text() {
+        assert_eq!(digest_body(Some(" CHAI ")).as_deref(), Some("\"CHAI\""));
+        assert_eq!(digest_body(Some("  ")), None);
+        assert_eq!(digest_body(None), None);
+
+        let long = "ಅ".repeat(QUOTE_CHARS + 5);
+        let body = digest_body(Some(&long)).unwrap();
+        assert!(body.ends_with("…\""));
+        assert_eq!(body.chars().count(), QUOTE_CHARS + 3);
+    }
+}
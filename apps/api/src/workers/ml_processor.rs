@@ -1,33 +1,273 @@
 use crate::infrastructure::{
-    ml::onnx_text_detector::OnnxTextDetector, ml::traits::MlService, queue::redis_queue::RedisQueue,
+    cache::invalidation::CacheInvalidator,
+    fault_injection::{FaultInjector, InjectedFault},
+    imaging::color_palette::{PALETTE_SIZE, dominant_colors},
+    imaging::image_embedding::{EMBEDDING_VERSION, embed, to_pgvector},
+    ml::circuit_breaker::{CircuitBreaker, CircuitOpen},
+    ml::onnx_style_classifier::OnnxStyleClassifier,
+    ml::onnx_text_detector::OnnxTextDetector,
+    ml::remote_inference_cache::RemoteInferenceCache,
+    ml::tesseract_service::{self, TesseractService},
+    ml::traits::{MlService, TextDetectionResult},
+    monitoring::{BusinessEvent, PerformanceMonitor, heartbeat::Heartbeats},
+    queue::jobs::{ClaimRotation, Claimed, Failure},
+    queue::redis_queue::{MlJob, RedisQueue},
+    repositories::processing_jobs::{self, Stage, StageStatus},
+    shutdown::Shutdown,
+    storage::{traits::StorageService, visibility::is_private_key},
 };
+use crate::workers::image_publisher::sync_visibility;
+use bytes::Bytes;
+use futures_util::future::join_all;
 use reqwest::StatusCode;
 use sqlx::PgPool;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::broadcast;
+use tracing::Instrument;
+use uuid::Uuid;
 
 pub struct MlProcessor {
     db: PgPool,
     detector: Arc<OnnxTextDetector>,
+    /// Candidate model evaluated alongside `detector`; its output is recorded
+    /// in `ml_shadow_results` but never used.
+    shadow: Option<Arc<OnnxTextDetector>>,
+    style_classifier: Arc<OnnxStyleClassifier>,
+    ocr: Arc<TesseractService>,
     queue: Arc<RedisQueue>,
     hf_token: Option<String>,
+    remote_cache: Arc<RemoteInferenceCache>,
+    /// Skips HuggingFace during outages so jobs go straight to ONNX.
+    hf_breaker: Arc<CircuitBreaker>,
+    thresholds: ConfidenceThresholds,
+    batch_size: usize,
+    /// Batches run at once, each claiming its own jobs.
+    concurrency: usize,
+    /// Drains high-priority jobs first while still giving normal and bulk
+    /// jobs a turn.
+    rotation: ClaimRotation,
     broadcaster: Arc<broadcast::Sender<String>>,
+    performance: Option<Arc<PerformanceMonitor>>,
+    faults: Option<Arc<FaultInjector>>,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+    invalidator: CacheInvalidator,
+    /// Signs fetches of images still in the private bucket, and publishes
+    /// the images of letterings the results approve.
+    storage: Option<Arc<dyn StorageService>>,
+}
+
+/// Lifetime of the signed URL an image in the private bucket is fetched by.
+const FETCH_URL_TTL: Duration = Duration::from_secs(300);
+
+const HF_PROVIDER: &str = "huggingface";
+const HF_MODEL: &str = "microsoft/trocr-base-handwritten";
+
+/// TrOCR via the inference API returns text without a score. Treat it as
+/// reasonably trustworthy but below anything a local model reports as certain.
+const HF_TEXT_CONFIDENCE: f32 = 0.8;
+
+const WORKER_NAME: &str = "ml_processor";
+
+/// How long a claimed batch may take before its heartbeat goes overdue.
+const BATCH_BEAT_WITHIN: Duration = Duration::from_secs(300);
+
+/// How often an idle processor beats.
+const IDLE_BEAT_WITHIN: Duration = Duration::from_secs(30);
+
+/// Every this many batches start at the normal queue even while
+/// high-priority jobs are waiting.
+const NORMAL_TURN_EVERY: u64 = 4;
+
+/// Every this many batches start at the bulk queue even while others are
+/// waiting.
+const BULK_TURN_EVERY: u64 = 10;
+
+/// Stored as the text when every detector fails.
+pub const PLACEHOLDER_TEXT: &str = "Handcrafted Lettering";
+
+/// Text chosen for a job, with the model that produced it.
+struct DetectedText {
+    text: String,
+    confidence: f32,
+    model_version: Option<String>,
+    /// `huggingface`, `onnx` or `tesseract`; `None` for the placeholder.
+    source: Option<&'static str>,
+}
+
+/// One image's production and candidate ONNX outputs.
+struct ShadowSample {
+    production: Option<TextDetectionResult>,
+    candidate: anyhow::Result<TextDetectionResult>,
+}
+
+/// Shadow results count as agreeing when both models report the same text.
+fn texts_agree(production: &str, candidate: &str) -> bool {
+    production.trim().eq_ignore_ascii_case(candidate.trim())
+}
+
+/// Per-field confidence cut-offs. Fields below their threshold are still
+/// stored, but listed in `ml_low_confidence_fields` so they stay out of search
+/// and show up in the moderation queue for manual correction.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfidenceThresholds {
+    pub text: f32,
+    pub style: f32,
+    pub script: f32,
+}
+
+impl ConfidenceThresholds {
+    pub fn low_confidence_fields(
+        &self,
+        text_confidence: f32,
+        style_confidence: f32,
+        script_confidence: Option<f32>,
+    ) -> Vec<String> {
+        let mut fields = Vec::new();
+        if text_confidence < self.text {
+            fields.push("detected_text".to_string());
+        }
+        if style_confidence < self.style {
+            fields.push("style".to_string());
+        }
+        if script_confidence.is_some_and(|c| c < self.script) {
+            fields.push("script".to_string());
+        }
+        fields
+    }
 }
 
 impl MlProcessor {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         db: PgPool,
         detector: Arc<OnnxTextDetector>,
+        shadow: Option<Arc<OnnxTextDetector>>,
+        style_classifier: Arc<OnnxStyleClassifier>,
+        ocr: Arc<TesseractService>,
         queue: Arc<RedisQueue>,
         hf_token: Option<String>,
+        remote_cache: Arc<RemoteInferenceCache>,
+        hf_breaker: Arc<CircuitBreaker>,
+        thresholds: ConfidenceThresholds,
+        batch_size: usize,
         broadcaster: Arc<broadcast::Sender<String>>,
     ) -> Self {
         Self {
             db,
             detector,
+            shadow,
+            style_classifier,
+            ocr,
             queue,
             hf_token,
+            remote_cache,
+            hf_breaker,
+            thresholds,
+            batch_size: batch_size.max(1),
+            concurrency: 1,
+            rotation: ClaimRotation::new(NORMAL_TURN_EVERY, BULK_TURN_EVERY),
             broadcaster,
+            performance: None,
+            faults: None,
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+            invalidator: CacheInvalidator::disabled(),
+            storage: None,
+        }
+    }
+
+    /// Run up to `concurrency` batches at once. Each claims its own jobs, so
+    /// the queue drains faster where the model has spare throughput.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Fail every k-th job as configured, before it is fetched.
+    pub fn with_fault_injector(mut self, faults: Arc<FaultInjector>) -> Self {
+        self.faults = Some(faults);
+        self
+    }
+
+    /// Record batch sizes, queue wait, remote and OCR latency, failure causes
+    /// and job outcomes in `performance`. ONNX latency is recorded by the
+    /// detector itself.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
+    }
+
+    /// Beat while idle, name the letterings of each batch while working on
+    /// it, and count processed and failed jobs.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Once `shutdown` fires, finish the batch in hand and stop claiming.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Drop cached reads of each lettering once its results are stored,
+    /// as they may approve it.
+    pub fn with_cache_invalidator(mut self, invalidator: CacheInvalidator) -> Self {
+        self.invalidator = invalidator;
+        self
+    }
+
+    /// Fetch images in the private bucket through signed URLs, and move the
+    /// images of letterings the results approve to the public bucket.
+    pub fn with_storage(mut self, storage: Arc<dyn StorageService>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    async fn record_failure_cause(&self, cause: &str) {
+        if let Some(performance) = &self.performance {
+            performance.record_ml_failure(cause).await;
+        }
+    }
+
+    async fn record_latency(&self, model: &str, duration: Duration) {
+        if let Some(performance) = &self.performance {
+            performance
+                .record_ml_model_latency(model, 1, duration)
+                .await;
+        }
+    }
+
+    async fn record_outcome(&self, success: bool, processing_time: Duration) {
+        if let Some(performance) = &self.performance {
+            performance
+                .record_business_event(BusinessEvent::MlProcessingCompleted {
+                    success,
+                    processing_time_ms: processing_time.as_millis() as u64,
+                })
+                .await;
+        }
+    }
+
+    async fn record_batch(&self, jobs: &[Claimed<MlJob>]) {
+        let Some(performance) = &self.performance else {
+            return;
+        };
+        performance.record_ml_batch_size(jobs.len()).await;
+        // Retries re-enter the queue with their original timestamp, so only
+        // first attempts measure queueing.
+        let now = chrono::Utc::now();
+        for job in jobs.iter().filter(|job| job.envelope.attempts == 0) {
+            let enqueued_at = job.envelope.enqueued_at;
+            if let Some(wait) = enqueued_at.and_then(|at| (now - at).to_std().ok()) {
+                performance
+                    .record_ml_queue_wait(job.priority.as_str(), wait)
+                    .await;
+            }
         }
     }
 
@@ -36,34 +276,436 @@ impl MlProcessor {
             .timeout(Duration::from_secs(60))
             .build()
             .unwrap();
-        loop {
-            if let Ok(Some(job)) = self.queue.dequeue_ml_job().await {
-                if let Err(e) = self.process_job(&client, &job).await {
-                    tracing::error!(
-                        lettering_id = %job.lettering_id,
-                        image_url = %job.image_url,
-                        "ML processing failed: {}. Job will NOT be retried — lettering remains in current status.",
+        join_all((0..self.concurrency).map(|_| self.run_batches(&client))).await;
+    }
+
+    /// Claims and processes batches one after another until shutdown.
+    async fn run_batches(&self, client: &reqwest::Client) {
+        while !self.shutdown.is_triggered() {
+            if let Err(e) = self.queue.ml_jobs().release_due(50).await {
+                tracing::warn!("Failed to release due ML retries: {}", e);
+            }
+            let order = self.rotation.next_order();
+            if let Ok(jobs) = self
+                .queue
+                .ml_jobs()
+                .claim_in_order(self.batch_size, &order)
+                .await
+                && !jobs.is_empty()
+            {
+                let letterings: Vec<String> = jobs
+                    .iter()
+                    .map(|job| job.job().lettering_id.to_string())
+                    .collect();
+                self.heartbeats
+                    .working_on(
+                        WORKER_NAME,
+                        &format!("letterings {}", letterings.join(", ")),
+                        BATCH_BEAT_WITHIN,
+                    )
+                    .await;
+                self.process_batch(client, jobs).await;
+            }
+            self.heartbeats.beat(WORKER_NAME, IDLE_BEAT_WITHIN).await;
+            self.shutdown.sleep(Duration::from_millis(500)).await;
+        }
+    }
+
+    /// Schedule a retry with backoff, or dead-letter the job once it has used
+    /// up its attempts. Until it succeeds the lettering keeps its current
+    /// status (likely PENDING).
+    async fn handle_job_failure(&self, claimed: &Claimed<MlJob>, e: &anyhow::Error) {
+        let job = claimed.job();
+        self.heartbeats.count(WORKER_NAME, 0, 1).await;
+        match self.queue.ml_jobs().fail(claimed, e).await {
+            Ok(Failure::Retrying(delay)) => {
+                tracing::warn!(
+                    lettering_id = %job.lettering_id,
+                    retry_in_secs = delay.as_secs(),
+                    "ML processing failed, will retry: {}",
+                    e
+                );
+                self.record_stage(
+                    std::iter::once(job),
+                    StageStatus::Retrying,
+                    Some(&e.to_string()),
+                )
+                .await;
+            }
+            Ok(Failure::DeadLettered) => {
+                tracing::error!(
+                    lettering_id = %job.lettering_id,
+                    image_url = %job.image_url,
+                    "ML processing failed, moved job to the dead-letter list: {}",
+                    e
+                );
+                if let Some(run_id) = job.reprocess_run_id {
+                    self.record_reprocess_outcome(run_id, false).await;
+                }
+                self.record_stage(
+                    std::iter::once(job),
+                    StageStatus::Failed,
+                    Some(&e.to_string()),
+                )
+                .await;
+                self.record_outcome(false, Duration::ZERO).await;
+            }
+            Ok(Failure::Expired) => {}
+            Err(e) => tracing::error!(
+                lettering_id = %job.lettering_id,
+                "Failed to schedule ML retry: {}",
+                e
+            ),
+        }
+    }
+
+    /// Move the ML stage of the uploads among `jobs` to `status`.
+    /// Reprocessing jobs have no upload waiting on them and are left out.
+    async fn record_stage<'a>(
+        &self,
+        jobs: impl Iterator<Item = &'a MlJob>,
+        status: StageStatus,
+        error: Option<&str>,
+    ) {
+        let uploads: Vec<Uuid> = jobs
+            .filter(|job| job.reprocess_run_id.is_none())
+            .map(|job| job.lettering_id)
+            .collect();
+        if let Err(e) = processing_jobs::set_stage(
+            &self.db,
+            &self.broadcaster,
+            &uploads,
+            Stage::Ml,
+            status,
+            error,
+        )
+        .await
+        {
+            tracing::warn!("Failed to record ML processing status: {}", e);
+        }
+    }
+
+    /// Count a finished reprocessing job against its run's progress.
+    async fn record_reprocess_outcome(&self, run_id: Uuid, succeeded: bool) {
+        let column = if succeeded { "processed" } else { "failed" };
+        if let Err(e) = sqlx::query(&format!(
+            "UPDATE ml_reprocess_runs SET {0} = {0} + 1 WHERE id = $1",
+            column
+        ))
+        .bind(run_id)
+        .execute(&self.db)
+        .await
+        {
+            tracing::warn!(run_id = %run_id, "Failed to record reprocess progress: {}", e);
+        }
+    }
+
+    /// Process a batch of claimed jobs, acknowledging each once its results
+    /// are stored.
+    ///
+    /// Images are fetched and sent to HuggingFace concurrently; every job that
+    /// still needs text then goes through one batched ONNX inference call, and
+    /// the remaining per-job work and persistence run concurrently again. A
+    /// failure only fails its own job.
+    async fn process_batch(&self, client: &reqwest::Client, jobs: Vec<Claimed<MlJob>>) {
+        let started = Instant::now();
+        self.record_batch(&jobs).await;
+        self.record_stage(jobs.iter().map(|job| job.job()), StageStatus::Running, None)
+            .await;
+        let fetched = join_all(jobs.iter().map(|job| async move {
+            if let Some(faults) = &self.faults {
+                faults.ml_job()?;
+            }
+            self.fetch_image(client, job.job())
+                .instrument(job.span())
+                .await
+        }))
+        .await;
+        let mut ready: Vec<(Claimed<MlJob>, Bytes)> = Vec::with_capacity(jobs.len());
+        for (job, result) in jobs.into_iter().zip(fetched) {
+            match result {
+                Ok(bytes) => ready.push((job, bytes)),
+                Err(e) => {
+                    let cause = if e.is::<InjectedFault>() {
+                        "injected"
+                    } else {
+                        "image_fetch"
+                    };
+                    self.record_failure_cause(cause).await;
+                    self.handle_job_failure(&job, &e).await;
+                }
+            }
+        }
+        if ready.is_empty() {
+            return;
+        }
+
+        // 1. Text detection: HuggingFace (primary) -> ONNX (fallback) -> default
+        let remote = join_all(
+            ready
+                .iter()
+                .map(|(_, bytes)| self.remote_text_detection(client, bytes)),
+        )
+        .await;
+
+        // With a shadow model configured, production ONNX runs on every image
+        // so the comparison is like-for-like even where HuggingFace answered.
+        let local_indices: Vec<usize> = remote
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| self.shadow.is_some() || text.is_none())
+            .map(|(i, _)| i)
+            .collect();
+        let (mut local, production_latency_ms) =
+            Self::run_local(&self.detector, &ready, &local_indices).await;
+        let production_version = Self::model_version(&self.detector);
+
+        let shadow_samples = match &self.shadow {
+            Some(shadow) => {
+                let all: Vec<usize> = (0..ready.len()).collect();
+                let (candidate, candidate_latency_ms) = Self::run_local(shadow, &ready, &all).await;
+                let samples: Vec<ShadowSample> = candidate
+                    .into_iter()
+                    .zip(&local)
+                    .map(|(candidate, production)| ShadowSample {
+                        production: production.as_ref().and_then(|r| r.as_ref().ok()).cloned(),
+                        candidate: candidate
+                            .unwrap_or_else(|| Err(anyhow::anyhow!("image was not processed"))),
+                    })
+                    .collect();
+                Some((Self::model_version(shadow), samples, candidate_latency_ms))
+            }
+            None => None,
+        };
+
+        let texts: Vec<DetectedText> = remote
+            .into_iter()
+            .zip(local.iter_mut())
+            .map(|(remote, local)| match remote {
+                Some((text, confidence)) => DetectedText {
+                    text,
+                    confidence,
+                    model_version: Some(format!("{}:{}", HF_PROVIDER, HF_MODEL)),
+                    source: Some(HF_PROVIDER),
+                },
+                None => match Self::local_text(local.take()) {
+                    Some((text, confidence)) => DetectedText {
+                        text,
+                        confidence,
+                        model_version: production_version.clone(),
+                        source: Some("onnx"),
+                    },
+                    None => {
+                        // Step 3: Last resort fallback
+                        tracing::info!("All detection methods exhausted, using default text");
+                        DetectedText {
+                            text: PLACEHOLDER_TEXT.to_string(),
+                            confidence: 0.0,
+                            model_version: None,
+                            source: None,
+                        }
+                    }
+                },
+            })
+            .collect();
+
+        let texts = join_all(ready.iter().zip(texts).map(|((job, bytes), text)| {
+            self.ocr_fallback(job.job(), bytes, text)
+                .instrument(job.span())
+        }))
+        .await;
+        for _ in texts.iter().filter(|text| text.source.is_none()) {
+            self.record_failure_cause("no_text_detected").await;
+        }
+
+        let outcomes = join_all(ready.iter().zip(texts).map(|((job, bytes), text)| {
+            self.complete_job(job.job(), bytes, text)
+                .instrument(job.span())
+        }))
+        .await;
+        for ((job, _), outcome) in ready.iter().zip(outcomes) {
+            match outcome {
+                Ok(()) => {
+                    if let Err(e) = self.queue.ml_jobs().ack(job).await {
+                        tracing::warn!(
+                            lettering_id = %job.job().lettering_id,
+                            "Failed to acknowledge ML job: {}",
+                            e
+                        );
+                    }
+                    self.heartbeats.count(WORKER_NAME, 1, 0).await;
+                    self.record_outcome(true, started.elapsed()).await
+                }
+                Err(e) => {
+                    self.record_failure_cause("persist").await;
+                    self.handle_job_failure(job, &e).await;
+                }
+            }
+        }
+
+        if let Some((candidate_version, samples, candidate_latency_ms)) = shadow_samples {
+            let writes = ready.iter().zip(samples).map(|((job, _), sample)| {
+                self.record_shadow_result(
+                    job.job(),
+                    production_version.as_deref(),
+                    candidate_version.as_deref(),
+                    sample,
+                    production_latency_ms,
+                    candidate_latency_ms,
+                )
+            });
+            for ((job, _), outcome) in ready.iter().zip(join_all(writes).await) {
+                if let Err(e) = outcome {
+                    tracing::warn!(
+                        lettering_id = %job.job().lettering_id,
+                        "Failed to record shadow model result: {}",
                         e
                     );
-                    // TODO: Consider a dead-letter queue or retry mechanism.
-                    // Right now a failed job is lost. The lettering stays in its
-                    // current status (likely PENDING) and won't be auto-approved
-                    // until the pending_auto_approve worker picks it up.
                 }
             }
-            tokio::time::sleep(Duration::from_millis(500)).await;
         }
     }
 
-    async fn process_job(
+    /// Replace missing or low-confidence text with Tesseract's reading when
+    /// that is more confident.
+    async fn ocr_fallback(&self, job: &MlJob, bytes: &[u8], text: DetectedText) -> DetectedText {
+        if !self.ocr.is_active() || text.confidence >= self.thresholds.text {
+            return text;
+        }
+        let Ok(image) = image::load_from_memory(bytes) else {
+            return text;
+        };
+        let started = Instant::now();
+        let result = self.ocr.detect_text(image).await;
+        if result.is_ok() {
+            self.record_latency(tesseract_service::SOURCE, started.elapsed())
+                .await;
+        }
+        match result {
+            Ok(Some(result)) if result.confidence > text.confidence => {
+                tracing::info!(
+                    lettering_id = %job.lettering_id,
+                    confidence = result.confidence,
+                    "Using Tesseract text over low-confidence detection"
+                );
+                DetectedText {
+                    text: result.detected_text,
+                    confidence: result.confidence,
+                    model_version: Some(tesseract_service::SOURCE.to_string()),
+                    source: Some(tesseract_service::SOURCE),
+                }
+            }
+            Ok(_) => text,
+            Err(e) => {
+                tracing::warn!(lettering_id = %job.lettering_id, "Tesseract OCR failed: {}", e);
+                self.record_failure_cause("tesseract").await;
+                text
+            }
+        }
+    }
+
+    /// Version label stored with results: `onnx:` plus the model hash prefix.
+    fn model_version(detector: &OnnxTextDetector) -> Option<String> {
+        detector
+            .model_info()
+            .map(|info| format!("onnx:{}", &info.sha256[..12]))
+    }
+
+    /// Run `detector` over the images at `indices` in one batch. Returns a
+    /// result slot per job (`None` where not requested) and the mean latency
+    /// per image in milliseconds.
+    async fn run_local(
+        detector: &OnnxTextDetector,
+        ready: &[(Claimed<MlJob>, Bytes)],
+        indices: &[usize],
+    ) -> (Vec<Option<anyhow::Result<TextDetectionResult>>>, f32) {
+        let mut slots: Vec<Option<anyhow::Result<TextDetectionResult>>> =
+            ready.iter().map(|_| None).collect();
+        if indices.is_empty() {
+            return (slots, 0.0);
+        }
+
+        let images: Vec<&[u8]> = indices.iter().map(|&i| ready[i].1.as_ref()).collect();
+        tracing::debug!(batch = images.len(), "Running batched ONNX text detection");
+        let started = Instant::now();
+        let results = detector.detect_text_batch(&images).await;
+        let latency_ms = started.elapsed().as_secs_f32() * 1000.0 / images.len() as f32;
+
+        for (&i, result) in indices.iter().zip(results) {
+            slots[i] = Some(result);
+        }
+        (slots, latency_ms)
+    }
+
+    async fn record_shadow_result(
         &self,
-        client: &reqwest::Client,
-        job: &crate::infrastructure::queue::redis_queue::MlJob,
+        job: &MlJob,
+        production_version: Option<&str>,
+        candidate_version: Option<&str>,
+        sample: ShadowSample,
+        production_latency_ms: f32,
+        candidate_latency_ms: f32,
     ) -> anyhow::Result<()> {
+        let (candidate_text, candidate_confidence, candidate_error) = match sample.candidate {
+            Ok(result) => (Some(result.detected_text), Some(result.confidence), None),
+            Err(e) => (None, None, Some(e.to_string())),
+        };
+        let agreed = match (&sample.production, &candidate_text) {
+            (Some(production), Some(candidate)) => {
+                Some(texts_agree(&production.detected_text, candidate))
+            }
+            _ => None,
+        };
+
+        sqlx::query(
+            "INSERT INTO ml_shadow_results
+                 (id, lettering_id, production_version, candidate_version,
+                  production_text, production_confidence, production_latency_ms,
+                  candidate_text, candidate_confidence, candidate_latency_ms,
+                  candidate_error, agreed)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+        )
+        .bind(Uuid::now_v7())
+        .bind(job.lettering_id)
+        .bind(production_version)
+        .bind(candidate_version)
+        .bind(sample.production.as_ref().map(|r| r.detected_text.as_str()))
+        .bind(sample.production.as_ref().map(|r| r.confidence))
+        .bind(production_latency_ms)
+        .bind(candidate_text)
+        .bind(candidate_confidence)
+        .bind(candidate_latency_ms)
+        .bind(candidate_error)
+        .bind(agreed)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// URL the job's image can be fetched from right now. It may have moved
+    /// bucket since the job was queued, and is signed while private.
+    async fn image_fetch_url(&self, job: &MlJob) -> anyhow::Result<String> {
+        let Some(storage) = &self.storage else {
+            return Ok(job.image_url.clone());
+        };
+        let current: Option<String> =
+            sqlx::query_scalar("SELECT image_url FROM letterings WHERE id = $1")
+                .bind(job.lettering_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let url = current.unwrap_or_else(|| job.image_url.clone());
+        match storage.key_from_url(&url) {
+            Some(key) if is_private_key(&key) => storage.signed_url(&key, FETCH_URL_TTL).await,
+            _ => Ok(url),
+        }
+    }
+
+    async fn fetch_image(&self, client: &reqwest::Client, job: &MlJob) -> anyhow::Result<Bytes> {
         // Fetch image bytes — fail the job if we can't get the image.
         // An empty body is NOT acceptable; it would produce garbage ML results.
+        let url = self.image_fetch_url(job).await?;
         let response =
-            client.get(&job.image_url).send().await.map_err(|e| {
+            client.get(&url).send().await.map_err(|e| {
                 anyhow::anyhow!("Failed to fetch image from {}: {}", job.image_url, e)
             })?;
 
@@ -80,15 +722,47 @@ impl MlProcessor {
             anyhow::bail!("Image fetch returned empty body from {}", job.image_url);
         }
 
-        // 1. Text detection: HuggingFace (primary) -> ONNX (fallback) -> default
-        let detected_text_str = self.detect_text_with_fallback(client, &bytes).await;
+        Ok(bytes)
+    }
+
+    async fn complete_job(
+        &self,
+        job: &MlJob,
+        bytes: &[u8],
+        detected: DetectedText,
+    ) -> anyhow::Result<()> {
+        let DetectedText {
+            text: detected_text_str,
+            confidence: text_confidence,
+            model_version,
+            source: text_source,
+        } = detected;
 
-        // 2. Color extraction (local heuristic)
-        let colors = self.extract_colors(&bytes);
-        let palette = serde_json::to_value(&colors).unwrap_or_default();
+        // 2. Colour palette, computed exactly as at upload so reprocessing
+        //    replaces palettes from older heuristics with the same result.
+        let palette = image::load_from_memory(bytes)
+            .map(|img| dominant_colors(&img.thumbnail(400, 400), PALETTE_SIZE))
+            .unwrap_or_default();
 
-        // 3. Style classification (local heuristic, single call)
-        let (style, style_confidence) = match self.detector.classify_style(&bytes).await {
+        // 3. Style classification: the ONNX classifier when loaded and
+        //    confident enough, otherwise the local heuristic.
+        let classified = match self.style_classifier.classify(bytes) {
+            Ok(classified) => classified,
+            Err(e) => {
+                tracing::warn!(
+                    lettering_id = %job.lettering_id,
+                    "Style model failed: {}. Using heuristic.",
+                    e
+                );
+                self.record_failure_cause("style_model").await;
+                None
+            }
+        };
+        let classification = match classified {
+            Some(c) => Ok(c),
+            None => self.detector.classify_style(bytes).await,
+        };
+        let (style, style_confidence) = match classification {
             Ok(c) => (c.style, c.confidence),
             Err(e) => {
                 tracing::warn!(
@@ -100,15 +774,59 @@ impl MlProcessor {
             }
         };
 
-        // 4. Script detection from recognized text
-        let script = Self::detect_script(&detected_text_str);
+        // 4. Script detection from recognized text. The script can only be as
+        //    trustworthy as the text it was derived from.
+        let (script, script_confidence) = match Self::detect_script(&detected_text_str) {
+            Some((s, share)) => (Some(s), Some(share * text_confidence)),
+            None => (None, None),
+        };
+
+        let low_confidence_fields = self.thresholds.low_confidence_fields(
+            text_confidence,
+            style_confidence,
+            script_confidence,
+        );
+        if !low_confidence_fields.is_empty() {
+            tracing::info!(
+                lettering_id = %job.lettering_id,
+                fields = ?low_confidence_fields,
+                "ML results below confidence threshold; flagged for review"
+            );
+        }
 
-        // 5. Persist results — this is the whole point of the worker.
-        //    If this fails, the job has effectively failed.
-        sqlx::query!(
-            "UPDATE letterings SET detected_text = $1, ml_color_palette = $2, ml_style = $3, ml_script = $4, ml_confidence = $5, status = 'APPROVED', updated_at = NOW() WHERE id = $6",
-            &detected_text_str, palette, &style, script, style_confidence, job.lettering_id
+        // 5. Visual embedding for "more like this". Missing embeddings only
+        //    leave the lettering out of similarity search.
+        let embedding = image::load_from_memory(bytes)
+            .map(|img| to_pgvector(&embed(&img)))
+            .map_err(|e| {
+                tracing::warn!(
+                    lettering_id = %job.lettering_id,
+                    "Image embedding skipped: {}",
+                    e
+                );
+            })
+            .ok();
+
+        // 6. Persist results — this is the whole point of the worker.
+        //    If this fails, the job has effectively failed. Reprocessing
+        //    refreshes ML fields only; moderation decisions stand.
+        sqlx::query(
+            "UPDATE letterings SET detected_text = $1, ml_color_palette = $2, ml_style = $3, ml_script = $4, ml_confidence = $5, ml_text_confidence = $6, ml_script_confidence = $7, ml_low_confidence_fields = $8, ml_model_version = $9, image_embedding = $11::vector, image_embedding_version = $12, detected_text_source = $13, status = CASE WHEN near_duplicate_of IS NULL AND NOT $14 THEN 'APPROVED' ELSE status END, updated_at = NOW() WHERE id = $10",
         )
+        .bind(&detected_text_str)
+        .bind(serde_json::json!(palette))
+        .bind(&style)
+        .bind(&script)
+        .bind(style_confidence)
+        .bind(text_confidence)
+        .bind(script_confidence)
+        .bind(&low_confidence_fields)
+        .bind(&model_version)
+        .bind(job.lettering_id)
+        .bind(&embedding)
+        .bind(embedding.as_ref().map(|_| EMBEDDING_VERSION))
+        .bind(text_source)
+        .bind(job.reprocess_run_id.is_some())
         .execute(&self.db)
         .await
         .map_err(|e| anyhow::anyhow!(
@@ -116,7 +834,47 @@ impl MlProcessor {
             job.lettering_id, e
         ))?;
 
-        // 6. Broadcast to WebSocket clients.
+        // Keep this pass's output for the moderation timeline. Best effort:
+        // the results above are already stored.
+        if let Err(e) = sqlx::query(
+            "INSERT INTO ml_runs (id, lettering_id, model_version, detected_text, text_confidence, ml_style, style_confidence, ml_script, low_confidence_fields, detected_text_source)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(Uuid::now_v7())
+        .bind(job.lettering_id)
+        .bind(&model_version)
+        .bind(&detected_text_str)
+        .bind(text_confidence)
+        .bind(&style)
+        .bind(style_confidence)
+        .bind(&script)
+        .bind(&low_confidence_fields)
+        .bind(text_source)
+        .execute(&self.db)
+        .await
+        {
+            tracing::warn!(lettering_id = %job.lettering_id, "Failed to record ML run: {}", e);
+        }
+
+        if let Some(run_id) = job.reprocess_run_id {
+            self.record_reprocess_outcome(run_id, true).await;
+        }
+        self.record_stage(std::iter::once(job), StageStatus::Done, None)
+            .await;
+        if let Some(storage) = &self.storage {
+            sync_visibility(
+                &self.db,
+                storage.as_ref(),
+                &self.invalidator,
+                &[job.lettering_id],
+            )
+            .await;
+        }
+        self.invalidator
+            .letterings_changed(&[job.lettering_id])
+            .await;
+
+        // 7. Broadcast to WebSocket clients.
         //    send() returns Err only when there are zero receivers, which is
         //    normal if no one is connected. That's not an error condition.
         let _ = self
@@ -133,21 +891,26 @@ impl MlProcessor {
         Ok(())
     }
 
-    /// Detect text using cascading strategy:
-    /// 1. HuggingFace API (primary, if token configured)
-    /// 2. ONNX local model (fallback)
+    /// Text detection uses a cascading strategy:
+    /// 1. HuggingFace API (primary, if token configured; cached by image hash
+    ///    and capped by a daily call budget)
+    /// 2. ONNX local model (fallback, batched across the jobs that need it)
     /// 3. Default string (last resort)
-    async fn detect_text_with_fallback(
+    ///
+    /// Tesseract, when enabled, then replaces any result below the text
+    /// confidence threshold that it reads more confidently.
+    ///
+    /// This is step 1. `None` means the job should fall through to ONNX.
+    async fn remote_text_detection(
         &self,
         client: &reqwest::Client,
         image_data: &[u8],
-    ) -> String {
-        // Step 1: Try HuggingFace first
+    ) -> Option<(String, f32)> {
         if self.hf_token.is_some() {
-            match self.huggingface_ocr(client, image_data).await {
+            match self.cached_huggingface_ocr(client, image_data).await {
                 Ok(text) if !text.trim().is_empty() => {
                     tracing::info!("HuggingFace OCR succeeded: '{}'", text);
-                    return text;
+                    return Some((text, HF_TEXT_CONFIDENCE));
                 }
                 Ok(text) => {
                     // Model returned successfully but with empty/whitespace text.
@@ -157,6 +920,9 @@ impl MlProcessor {
                         text
                     );
                 }
+                Err(e) if e.is::<CircuitOpen>() => {
+                    tracing::debug!("{}. Falling back to ONNX.", e);
+                }
                 Err(e) => {
                     // Infrastructure failure — the model didn't even get a chance.
                     // This is a different situation from "model sees no text."
@@ -173,32 +939,80 @@ impl MlProcessor {
                  Set HUGGINGFACE_TOKEN env var for best results."
             );
         }
+        None
+    }
 
-        // Step 2: Fall back to ONNX local detection
-        match self.detector.detect_text(image_data).await {
-            Ok(result)
+    /// Step 2: use the ONNX result if it found anything meaningful.
+    fn local_text(result: Option<anyhow::Result<TextDetectionResult>>) -> Option<(String, f32)> {
+        match result {
+            Some(Ok(result))
                 if !result.detected_text.is_empty()
                     && result.detected_text != "No text detected"
                     && result.confidence > 0.0 =>
             {
                 tracing::info!("ONNX fallback detected text: '{}'", result.detected_text);
-                return result.detected_text;
+                return Some((result.detected_text, result.confidence));
             }
-            Ok(result) => {
+            Some(Ok(result)) => {
                 tracing::debug!(
                     "ONNX detected no meaningful text (text='{}', confidence={})",
                     result.detected_text,
                     result.confidence
                 );
             }
-            Err(e) => {
+            Some(Err(e)) => {
                 tracing::warn!("ONNX detection failed: {}", e);
             }
+            None => {
+                tracing::warn!("ONNX batch returned fewer results than images");
+            }
+        }
+        None
+    }
+
+    /// HuggingFace OCR behind the remote inference cache, circuit breaker and
+    /// daily budget.
+    ///
+    /// Successful responses (including empty text) are cached so retries and
+    /// reprocessing of the same image never pay for a second remote call.
+    /// Cache hits are served even while the breaker is open.
+    async fn cached_huggingface_ocr(
+        &self,
+        client: &reqwest::Client,
+        data: &[u8],
+    ) -> anyhow::Result<String> {
+        let image_hash = RemoteInferenceCache::image_hash(data);
+
+        if let Some(text) = self.remote_cache.get(HF_PROVIDER, &image_hash).await {
+            tracing::debug!(image_hash = %image_hash, "HuggingFace OCR cache hit");
+            return Ok(text);
+        }
+
+        if let Err(e) = self.hf_breaker.acquire().await {
+            self.record_failure_cause("huggingface_circuit_open").await;
+            return Err(e.into());
+        }
+
+        if !self.remote_cache.try_consume_budget(HF_PROVIDER).await {
+            self.record_failure_cause("huggingface_budget").await;
+            anyhow::bail!("HuggingFace daily call budget exhausted");
         }
 
-        // Step 3: Last resort fallback
-        tracing::info!("All detection methods exhausted, using default text");
-        "Handcrafted Lettering".to_string()
+        let started = Instant::now();
+        let text = match self.huggingface_ocr(client, data).await {
+            Ok(text) => {
+                self.hf_breaker.record_success().await;
+                self.record_latency(HF_PROVIDER, started.elapsed()).await;
+                text
+            }
+            Err(e) => {
+                self.hf_breaker.record_failure().await;
+                self.record_failure_cause("huggingface").await;
+                return Err(e);
+            }
+        };
+        self.remote_cache.put(HF_PROVIDER, &image_hash, &text).await;
+        Ok(text)
     }
 
     /// Call HuggingFace Inference API for handwritten text OCR.
@@ -215,13 +1029,13 @@ impl MlProcessor {
             .hf_token
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("HuggingFace token not configured"))?;
-        let url = "https://api-inference.huggingface.co/models/microsoft/trocr-base-handwritten";
+        let url = format!("https://api-inference.huggingface.co/models/{}", HF_MODEL);
 
         let mut last_error = None;
 
         for attempt in 0..3 {
             let res = client
-                .post(url)
+                .post(&url)
                 .header("Authorization", format!("Bearer {}", token))
                 .body(data.to_vec())
                 .send()
@@ -286,7 +1100,9 @@ impl MlProcessor {
             .unwrap_or_else(|| anyhow::anyhow!("HuggingFace OCR failed after 3 attempts")))
     }
 
-    fn detect_script(text: &str) -> Option<String> {
+    /// Dominant script in `text`, with the share of script-bearing
+    /// characters that belong to it.
+    fn detect_script(text: &str) -> Option<(String, f32)> {
         let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
         for ch in text.chars() {
             let script = match ch as u32 {
@@ -307,32 +1123,27 @@ impl MlProcessor {
                 *counts.entry(s).or_insert(0) += 1;
             }
         }
+        let total: usize = counts.values().sum();
         counts
             .into_iter()
             .max_by_key(|(_, c)| *c)
-            .map(|(s, _)| s.to_string())
-    }
-
-    fn extract_colors(&self, data: &[u8]) -> Vec<String> {
-        if let Ok(img) = image::load_from_memory(data).map(|i| i.to_rgb8()) {
-            let mut counts = std::collections::HashMap::new();
-            for y in (0..img.height()).step_by(25) {
-                for x in (0..img.width()).step_by(25) {
-                    let p = img.get_pixel(x, y);
-                    let hex = format!(
-                        "#{:02X}{:02X}{:02X}",
-                        (p[0] / 32) * 32,
-                        (p[1] / 32) * 32,
-                        (p[2] / 32) * 32
-                    );
-                    *counts.entry(hex).or_insert(0) += 1;
-                }
-            }
-            let mut v: Vec<_> = counts.into_iter().collect();
-            v.sort_by(|a, b| b.1.cmp(&a.1));
-            v.into_iter().take(3).map(|(k, _)| k).collect()
-        } else {
-            vec![]
-        }
+            .map(|(s, c)| (s.to_string(), c as f32 / total as f32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shadow_agreement_ignores_case_and_padding() {
+        assert!(texts_agree(
+            "Detected 2 text regions",
+            " detected 2 text regions"
+        ));
+        assert!(!texts_agree(
+            "Detected 2 text regions",
+            "Detected 3 text regions"
+        ));
     }
 }
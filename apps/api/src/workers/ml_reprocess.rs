@@ -0,0 +1,254 @@
+use crate::{
+    infrastructure::{
+        monitoring::heartbeat::Heartbeats,
+        queue::redis_queue::{MlJob, RedisQueue},
+        shutdown::Shutdown,
+    },
+    workers::ml_processor::PLACEHOLDER_TEXT,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::{FromRow, PgPool};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "ml_reprocess";
+const TICK: Duration = Duration::from_secs(10);
+const TICKS_PER_MINUTE: u32 = 6;
+
+/// Which letterings a reprocessing run covers. Unset filters match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReprocessFilter {
+    /// Created at or after this time.
+    pub created_from: Option<DateTime<Utc>>,
+    /// Created before this time.
+    pub created_to: Option<DateTime<Utc>>,
+    /// Only letterings without usable text: none, blank, or the placeholder
+    /// stored when every detector failed.
+    #[serde(default)]
+    pub missing_text_only: bool,
+    /// Only letterings last processed by this `ml_model_version`.
+    pub model_version: Option<String>,
+}
+
+impl ReprocessFilter {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if let (Some(from), Some(to)) = (self.created_from, self.created_to)
+            && from >= to
+        {
+            return Err("created_from must be before created_to");
+        }
+        Ok(())
+    }
+}
+
+/// `WHERE` clause shared by the count at creation and the worker's pages;
+/// binds the filter as `$1`–`$5`.
+const FILTER_SQL: &str = "($1::timestamptz IS NULL OR l.created_at >= $1)
+     AND ($2::timestamptz IS NULL OR l.created_at < $2)
+     AND (NOT $3 OR l.detected_text IS NULL OR btrim(l.detected_text) = ''
+          OR (l.detected_text = $5 AND l.detected_text_source IS NULL))
+     AND ($4::text IS NULL OR l.ml_model_version = $4)";
+
+#[derive(Debug, FromRow)]
+struct ActiveRun {
+    id: Uuid,
+    status: String,
+    created_from: Option<DateTime<Utc>>,
+    created_to: Option<DateTime<Utc>>,
+    missing_text_only: bool,
+    model_version: Option<String>,
+    cursor_id: Option<Uuid>,
+}
+
+impl ActiveRun {
+    fn filter(&self) -> ReprocessFilter {
+        ReprocessFilter {
+            created_from: self.created_from,
+            created_to: self.created_to,
+            missing_text_only: self.missing_text_only,
+            model_version: self.model_version.clone(),
+        }
+    }
+}
+
+/// Record a reprocessing run for the worker to pick up, with `total` set to
+/// the number of letterings matching `filter` now. Used by the admin API and
+/// the `ml_reprocess` command.
+pub async fn create_reprocess_run(
+    db: &PgPool,
+    requested_by: &str,
+    filter: &ReprocessFilter,
+) -> anyhow::Result<Uuid> {
+    filter.validate().map_err(anyhow::Error::msg)?;
+    let id = Uuid::now_v7();
+    sqlx::query(&format!(
+        "INSERT INTO ml_reprocess_runs
+             (id, requested_by, created_from, created_to, missing_text_only, model_version, total)
+         SELECT $6, $7, $1, $2, $3, $4, COUNT(*)::int
+         FROM letterings l
+         WHERE {}",
+        FILTER_SQL
+    ))
+    .bind(filter.created_from)
+    .bind(filter.created_to)
+    .bind(filter.missing_text_only)
+    .bind(&filter.model_version)
+    .bind(PLACEHOLDER_TEXT)
+    .bind(id)
+    .bind(requested_by)
+    .execute(db)
+    .await?;
+    Ok(id)
+}
+
+/// Feeds reprocessing runs to the ML worker, oldest run first.
+///
+/// Jobs go on the low-priority queue, which the ML worker only reads while no
+/// upload is waiting. On top of that the feed is paced at `rate_per_minute`
+/// and paused whenever a minute's worth is still queued, so a run never builds
+/// a backlog that outlives a cancellation. Each tick locks the run row, so
+/// several API instances share the work without enqueueing a lettering twice.
+/// A run is COMPLETED once everything is queued; `processed` and `failed`
+/// catch up as the ML worker finishes.
+pub struct MlReprocessWorker {
+    db: PgPool,
+    queue: Arc<RedisQueue>,
+    rate_per_minute: u32,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl MlReprocessWorker {
+    pub fn new(db: PgPool, queue: Arc<RedisQueue>, rate_per_minute: u32) -> Self {
+        Self {
+            db,
+            queue,
+            rate_per_minute: rate_per_minute.max(1),
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            if let Err(e) = self.tick().await {
+                tracing::error!("ML reprocess tick failed: {}", e);
+            }
+            self.heartbeats.beat(WORKER_NAME, TICK).await;
+            if !self.shutdown.sleep(TICK).await {
+                break;
+            }
+        }
+    }
+
+    async fn tick(&self) -> anyhow::Result<()> {
+        let backlog = self.queue.ml_reprocess_backlog().await?;
+        if backlog >= self.rate_per_minute as usize {
+            return Ok(());
+        }
+        let quota = self.rate_per_minute.div_ceil(TICKS_PER_MINUTE) as i64;
+
+        let mut tx = self.db.begin().await?;
+        let Some(run) = sqlx::query_as::<_, ActiveRun>(
+            "SELECT id, status, created_from, created_to, missing_text_only, model_version, cursor_id
+             FROM ml_reprocess_runs
+             WHERE status IN ('PENDING', 'RUNNING')
+             ORDER BY created_at ASC
+             LIMIT 1
+             FOR UPDATE SKIP LOCKED",
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(());
+        };
+        if run.status == "PENDING" {
+            tracing::info!(run_id = %run.id, "ML reprocess run started");
+        }
+
+        let filter = run.filter();
+        let page = sqlx::query_as::<_, (Uuid, String)>(&format!(
+            "SELECT l.id, l.image_url
+             FROM letterings l
+             WHERE l.id > $6 AND {}
+             ORDER BY l.id ASC
+             LIMIT $7",
+            FILTER_SQL
+        ))
+        .bind(filter.created_from)
+        .bind(filter.created_to)
+        .bind(filter.missing_text_only)
+        .bind(&filter.model_version)
+        .bind(PLACEHOLDER_TEXT)
+        .bind(run.cursor_id.unwrap_or(Uuid::nil()))
+        .bind(quota)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let jobs: Vec<MlJob> = page
+            .iter()
+            .map(|(id, image_url)| MlJob {
+                lettering_id: *id,
+                image_url: image_url.clone(),
+                reprocess_run_id: Some(run.id),
+            })
+            .collect();
+        self.queue.enqueue_ml_reprocess_jobs(&jobs).await?;
+
+        let done = (page.len() as i64) < quota;
+        sqlx::query(
+            "UPDATE ml_reprocess_runs
+             SET status = CASE WHEN $4 THEN 'COMPLETED' ELSE 'RUNNING' END,
+                 started_at = COALESCE(started_at, NOW()),
+                 finished_at = CASE WHEN $4 THEN NOW() ELSE finished_at END,
+                 cursor_id = COALESCE($2, cursor_id),
+                 enqueued = enqueued + $3
+             WHERE id = $1",
+        )
+        .bind(run.id)
+        .bind(page.last().map(|(id, _)| *id))
+        .bind(page.len() as i32)
+        .bind(done)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        if done {
+            tracing::info!(run_id = %run.id, "ML reprocess run fully enqueued");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_date_range() {
+        let at = Utc::now();
+        let filter = ReprocessFilter {
+            created_from: Some(at),
+            created_to: Some(at),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_err());
+
+        let open_ended = ReprocessFilter {
+            created_from: Some(at),
+            ..Default::default()
+        };
+        assert!(open_ended.validate().is_ok());
+    }
+}
@@ -1,3 +1,24 @@
+pub mod activity_fanout;
 pub mod analytics_worker;
+pub mod backup_exporter;
+pub mod backup_snapshot;
+pub mod cache_warmer;
+pub mod cdn_purge_retry;
+pub mod follow_notifier;
+pub mod image_publisher;
+pub mod geo_retention;
+pub mod integrity_verifier;
+pub mod ip_anonymizer;
+pub mod like_digest;
 pub mod ml_processor;
+pub mod ml_reprocess;
+pub mod model_watcher;
+pub mod notification_delivery;
 pub mod pending_auto_approve;
+pub mod pending_escalation;
+pub mod print_bundle;
+pub mod review_priority;
+pub mod saved_search_notifier;
+pub mod scheduler;
+pub mod storage_gc;
+pub mod weekly_digest;
@@ -0,0 +1,88 @@
+use crate::infrastructure::{
+    ml::onnx_text_detector::OnnxTextDetector, monitoring::heartbeat::Heartbeats, shutdown::Shutdown,
+};
+use std::{sync::Arc, time::Duration, time::SystemTime};
+
+/// Polls `ml_model_path` and hot-reloads the ONNX model when the file
+/// changes.
+///
+/// A modification time change alone is not enough: the file is hashed and
+/// only reloaded when it differs from the model currently loaded, so a model
+/// that was just pushed through the admin endpoint (and persisted to the same
+/// path) isn't loaded twice. Candidates that fail validation are logged and
+/// the previous model keeps serving.
+pub struct ModelWatcher {
+    detector: Arc<OnnxTextDetector>,
+    model_path: String,
+    interval_seconds: u64,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl ModelWatcher {
+    pub fn new(detector: Arc<OnnxTextDetector>, model_path: String, interval_seconds: u64) -> Self {
+        Self {
+            detector,
+            model_path,
+            interval_seconds: interval_seconds.max(5),
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn start(&self) {
+        let mut last_modified = self.modified_at().await;
+        loop {
+            let interval = Duration::from_secs(self.interval_seconds);
+            self.heartbeats.beat("model_watcher", interval).await;
+            if !self.shutdown.sleep(interval).await {
+                break;
+            }
+
+            let modified = self.modified_at().await;
+            if modified.is_none() || modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            if let Err(e) = self.reload_if_changed().await {
+                tracing::error!(
+                    path = %self.model_path,
+                    "Model file changed but reload failed: {}. Keeping current model.",
+                    e
+                );
+            }
+        }
+    }
+
+    async fn modified_at(&self) -> Option<SystemTime> {
+        tokio::fs::metadata(&self.model_path)
+            .await
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    async fn reload_if_changed(&self) -> anyhow::Result<()> {
+        let bytes = tokio::fs::read(&self.model_path).await?;
+        let current = self.detector.model_info().map(|info| info.sha256);
+        if current.as_deref() == Some(OnnxTextDetector::model_hash(&bytes).as_str()) {
+            tracing::debug!(path = %self.model_path, "Model file touched but unchanged");
+            return Ok(());
+        }
+
+        let detector = self.detector.clone();
+        let source = self.model_path.clone();
+        tokio::task::spawn_blocking(move || detector.load_model(&bytes, &source)).await??;
+        Ok(())
+    }
+}
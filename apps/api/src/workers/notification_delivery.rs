@@ -0,0 +1,42 @@
+use crate::infrastructure::queue::{
+    jobs::{Envelope, JobHandler},
+    redis_queue::NotificationJob,
+};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Inserts queued notifications. The job id doubles as the notification id,
+/// so a job run twice still notifies once; notifications for users deleted
+/// since they were queued are dropped.
+pub struct NotificationDelivery {
+    db: PgPool,
+}
+
+impl NotificationDelivery {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl JobHandler for NotificationDelivery {
+    type Job = NotificationJob;
+
+    async fn handle(&self, job: &Envelope<NotificationJob>) -> anyhow::Result<()> {
+        let notification = &job.job;
+        sqlx::query(
+            "INSERT INTO notifications (id, user_id, type, title, body, metadata)
+             SELECT $1, u.id, $3, $4, $5, $6 FROM users u WHERE u.id = $2
+             ON CONFLICT (id) DO NOTHING",
+        )
+        .bind(job.id)
+        .bind(notification.user_id)
+        .bind(&notification.kind)
+        .bind(&notification.title)
+        .bind(&notification.body)
+        .bind(&notification.metadata)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,14 +1,102 @@
-use sqlx::{PgPool, Row};
-use std::{sync::Arc, time::Duration};
+use crate::{
+    config::AutoApproveExclusion,
+    infrastructure::{
+        cache::invalidation::CacheInvalidator,
+        monitoring::{PerformanceMonitor, throttle::WorkerThrottle},
+        storage::traits::StorageService,
+    },
+    workers::image_publisher::sync_visibility,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
+const WORKER_NAME: &str = "pending_auto_approve";
+
+/// `admin_sub` on the audit entries this worker writes.
+const AUDIT_ACTOR: &str = "AUTO_APPROVER";
+
+/// A stale pending item with the signals each exclusion checks.
+#[derive(Debug, FromRow)]
+struct Candidate {
+    id: Uuid,
+    contributor_tag: String,
+    created_at: DateTime<Utc>,
+    reported: bool,
+    low_confidence: bool,
+    nsfw: bool,
+    first_time_contributor: bool,
+    restricted_region: bool,
+}
+
+impl Candidate {
+    fn matches(&self, exclusion: AutoApproveExclusion) -> bool {
+        match exclusion {
+            AutoApproveExclusion::Reported => self.reported,
+            AutoApproveExclusion::LowConfidence => self.low_confidence,
+            AutoApproveExclusion::Nsfw => self.nsfw,
+            AutoApproveExclusion::FirstTimeContributor => self.first_time_contributor,
+            AutoApproveExclusion::RestrictedRegion => self.restricted_region,
+        }
+    }
+}
+
+/// First configured exclusion the candidate hits, in configuration order.
+fn exclusion_for(
+    candidate: &Candidate,
+    exclusions: &[AutoApproveExclusion],
+) -> Option<AutoApproveExclusion> {
+    exclusions.iter().copied().find(|&e| candidate.matches(e))
+}
+
+/// One candidate of the next pass and what the pass would do with it.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewItem {
+    pub id: Uuid,
+    pub contributor_tag: String,
+    pub created_at: DateTime<Utc>,
+    /// The exclusion holding it for review; `None` when it would be
+    /// approved.
+    pub excluded_by: Option<AutoApproveExclusion>,
+}
+
+/// What the next pass would do, without doing it.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoApprovePreview {
+    pub stale_after_minutes: i64,
+    pub batch_size: i64,
+    pub exclusions: Vec<AutoApproveExclusion>,
+    pub would_approve: Vec<PreviewItem>,
+    pub would_hold: Vec<PreviewItem>,
+    /// Pending items the pass leaves alone: too recent, possible
+    /// duplicates, or already held by an earlier pass.
+    pub not_yet_stale: i64,
+    pub near_duplicates: i64,
+    pub previously_held: i64,
+}
+
+/// The next pass's decisions.
+struct Plan {
+    candidates: Vec<Candidate>,
+    approve: Vec<Uuid>,
+    skipped: HashMap<AutoApproveExclusion, Vec<Uuid>>,
+}
+
 pub struct PendingAutoApproveWorker {
     db: PgPool,
     broadcaster: Arc<broadcast::Sender<String>>,
     stale_after_minutes: i64,
     interval_seconds: u64,
     batch_size: i64,
+    exclusions: Vec<AutoApproveExclusion>,
+    dry_run: bool,
+    performance: Option<Arc<PerformanceMonitor>>,
+    throttle: WorkerThrottle,
+    invalidator: CacheInvalidator,
+    storage: Option<Arc<dyn StorageService>>,
 }
 
 impl PendingAutoApproveWorker {
@@ -18,6 +106,8 @@ impl PendingAutoApproveWorker {
         stale_after_minutes: i64,
         interval_seconds: u64,
         batch_size: i64,
+        exclusions: Vec<AutoApproveExclusion>,
+        dry_run: bool,
     ) -> Self {
         Self {
             db,
@@ -25,43 +115,326 @@ impl PendingAutoApproveWorker {
             stale_after_minutes: stale_after_minutes.max(1),
             interval_seconds: interval_seconds.max(10),
             batch_size: batch_size.max(1),
+            exclusions,
+            dry_run,
+            performance: None,
+            throttle: WorkerThrottle::unthrottled(),
+            invalidator: CacheInvalidator::disabled(),
+            storage: None,
         }
     }
 
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Record approved and per-reason skipped counts in `performance`.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
+    }
+
+    /// Drop cached reads of the letterings each pass approves.
+    pub fn with_cache_invalidator(mut self, invalidator: CacheInvalidator) -> Self {
+        self.invalidator = invalidator;
+        self
+    }
+
+    /// Move the images of approved letterings to the public bucket right
+    /// away rather than on the image publisher's next pass.
+    pub fn with_storage(mut self, storage: Arc<dyn StorageService>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
     pub async fn start(&self) {
         loop {
-            if let Ok(rows) = sqlx::query(
-                "WITH stale AS (
-                    SELECT id
-                    FROM letterings
-                    WHERE status = 'PENDING'
-                      AND created_at < NOW() - ($1::int * INTERVAL '1 minute')
-                    ORDER BY created_at ASC
-                    LIMIT $2
-                )
-                UPDATE letterings
-                SET detected_text = COALESCE(detected_text, $3),
-                    status = 'APPROVED',
-                    updated_at = NOW()
-                WHERE id IN (SELECT id FROM stale)
-                RETURNING id",
+            if let Err(e) = self.run_once().await {
+                tracing::warn!("Pending auto-approve pass failed: {}", e);
+            }
+
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// The oldest stale pending items, up to one batch, and which of them
+    /// the exclusions hold back.
+    async fn plan(&self) -> Result<Plan, sqlx::Error> {
+        let candidates = sqlx::query_as::<_, Candidate>(
+            "SELECT l.id, l.contributor_tag, l.created_at,
+                    l.report_count > 0 AS reported,
+                    COALESCE(l.low_confidence, false) AS low_confidence,
+                    l.nsfw_flagged AS nsfw,
+                    NOT EXISTS (
+                        SELECT 1 FROM letterings prior
+                        WHERE prior.status = 'APPROVED'
+                          AND prior.id <> l.id
+                          AND (prior.user_id = l.user_id
+                               OR (l.user_id IS NULL AND prior.contributor_tag = l.contributor_tag))
+                    ) AS first_time_contributor,
+                    COALESCE(rp.auto_moderation_level = 'strict', false) AS restricted_region
+             FROM letterings l
+             LEFT JOIN region_policies rp ON rp.country_code = UPPER(l.upload_country_code)
+             WHERE l.status = 'PENDING'
+               AND l.near_duplicate_of IS NULL
+               AND l.auto_approve_skip_reason IS NULL
+               AND l.created_at < NOW() - ($1::int * INTERVAL '1 minute')
+             ORDER BY l.created_at ASC
+             LIMIT $2",
+        )
+        .bind(self.stale_after_minutes)
+        .bind(self.batch_size)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut approve = Vec::new();
+        let mut skipped: HashMap<AutoApproveExclusion, Vec<Uuid>> = HashMap::new();
+        for candidate in &candidates {
+            match exclusion_for(candidate, &self.exclusions) {
+                Some(reason) => skipped.entry(reason).or_default().push(candidate.id),
+                None => approve.push(candidate.id),
+            }
+        }
+        Ok(Plan {
+            candidates,
+            approve,
+            skipped,
+        })
+    }
+
+    /// Which items the next pass would approve and which it would hold for
+    /// review and why, plus the pending items it would not consider. Changes
+    /// nothing, so operators can tune the window and exclusions before
+    /// enabling the worker.
+    pub async fn preview(&self) -> Result<AutoApprovePreview, sqlx::Error> {
+        let plan = self.plan().await?;
+        let (not_yet_stale, near_duplicates, previously_held) =
+            sqlx::query_as::<_, (i64, i64, i64)>(
+                "SELECT
+                     COUNT(*) FILTER (
+                         WHERE near_duplicate_of IS NULL AND auto_approve_skip_reason IS NULL
+                           AND created_at >= NOW() - ($1::int * INTERVAL '1 minute')
+                     ),
+                     COUNT(*) FILTER (WHERE near_duplicate_of IS NOT NULL),
+                     COUNT(*) FILTER (
+                         WHERE near_duplicate_of IS NULL AND auto_approve_skip_reason IS NOT NULL
+                     )
+                 FROM letterings
+                 WHERE status = 'PENDING'",
             )
             .bind(self.stale_after_minutes)
-            .bind(self.batch_size)
-            .bind("Street Discovery")
-            .fetch_all(&self.db)
-            .await
-            {
-                for row in rows {
-                    if let Ok(id) = row.try_get::<Uuid, _>("id") {
-                        let _ = self
-                            .broadcaster
-                            .send(serde_json::json!({ "type": "PROCESSED", "id": id }).to_string());
-                    }
+            .fetch_one(&self.db)
+            .await?;
+
+        let (mut would_approve, mut would_hold) = (Vec::new(), Vec::new());
+        for candidate in plan.candidates {
+            let excluded_by = exclusion_for(&candidate, &self.exclusions);
+            let item = PreviewItem {
+                id: candidate.id,
+                contributor_tag: candidate.contributor_tag,
+                created_at: candidate.created_at,
+                excluded_by,
+            };
+            match excluded_by {
+                Some(_) => would_hold.push(item),
+                None => would_approve.push(item),
+            }
+        }
+        Ok(AutoApprovePreview {
+            stale_after_minutes: self.stale_after_minutes,
+            batch_size: self.batch_size,
+            exclusions: self.exclusions.clone(),
+            would_approve,
+            would_hold,
+            not_yet_stale,
+            near_duplicates,
+            previously_held,
+        })
+    }
+
+    /// Items that hit an exclusion are tagged with the reason and left pending
+    /// for a moderator; later passes no longer consider them. Each approval is
+    /// written to the audit log as `AUTO_APPROVE` and the owner is notified.
+    ///
+    /// In dry-run mode the pass only logs what it would do.
+    async fn run_once(&self) -> Result<(), sqlx::Error> {
+        let Plan {
+            approve, skipped, ..
+        } = self.plan().await?;
+
+        if self.dry_run {
+            for id in &approve {
+                tracing::info!(lettering_id = %id, "Dry run: would auto-approve");
+            }
+            for (reason, ids) in &skipped {
+                for id in ids {
+                    tracing::info!(
+                        lettering_id = %id,
+                        reason = reason.as_str(),
+                        "Dry run: would hold for review"
+                    );
                 }
             }
+            self.record_counts("would_approve", approve.len(), "would_skip", &skipped)
+                .await;
+            return Ok(());
+        }
+
+        for (reason, ids) in &skipped {
+            sqlx::query(
+                "UPDATE letterings SET auto_approve_skip_reason = $2
+                 WHERE id = ANY($1) AND status = 'PENDING'",
+            )
+            .bind(ids)
+            .bind(reason.as_str())
+            .execute(&self.db)
+            .await?;
+        }
+
+        let approved = sqlx::query_scalar::<_, Uuid>(
+            "WITH approved AS (
+                UPDATE letterings
+                SET detected_text = COALESCE(detected_text, $2),
+                    status = 'APPROVED',
+                    moderation_reason = $3,
+                    updated_at = NOW()
+                WHERE id = ANY($1) AND status = 'PENDING'
+                RETURNING id, user_id
+            ),
+            audit AS (
+                INSERT INTO admin_audit_logs (id, admin_sub, action, lettering_id, metadata)
+                SELECT uuid_generate_v4(), $4, 'AUTO_APPROVE', id,
+                       jsonb_build_object('stale_after_minutes', $5::int)
+                FROM approved
+            ),
+            notified AS (
+                INSERT INTO notifications (id, user_id, type, title, body, metadata)
+                SELECT uuid_generate_v4(), user_id, 'MODERATION_APPROVED',
+                       'Your upload was approved',
+                       'Your lettering contribution was approved automatically and is now publicly visible.',
+                       jsonb_build_object('lettering_id', id, 'automatic', true)
+                FROM approved
+                WHERE user_id IS NOT NULL
+            )
+            SELECT id FROM approved",
+        )
+        .bind(&approve)
+        .bind("Street Discovery")
+        .bind("Auto-approved after pending review window")
+        .bind(AUDIT_ACTOR)
+        .bind(self.stale_after_minutes)
+        .fetch_all(&self.db)
+        .await?;
 
-            tokio::time::sleep(Duration::from_secs(self.interval_seconds)).await;
+        if let Some(storage) = &self.storage {
+            sync_visibility(&self.db, storage.as_ref(), &self.invalidator, &approved).await;
         }
+        self.invalidator.letterings_changed(&approved).await;
+        for id in &approved {
+            let _ = self
+                .broadcaster
+                .send(serde_json::json!({ "type": "PROCESSED", "id": id }).to_string());
+        }
+
+        if !skipped.is_empty() {
+            tracing::info!(
+                approved = approved.len(),
+                skipped = ?skipped.iter().map(|(r, ids)| (r.as_str(), ids.len())).collect::<Vec<_>>(),
+                "Pending auto-approve held items for review"
+            );
+        }
+        self.record_counts("approved", approved.len(), "skipped", &skipped)
+            .await;
+        Ok(())
+    }
+
+    async fn record_counts(
+        &self,
+        approved_event: &str,
+        approved: usize,
+        skipped_prefix: &str,
+        skipped: &HashMap<AutoApproveExclusion, Vec<Uuid>>,
+    ) {
+        let Some(performance) = &self.performance else {
+            return;
+        };
+        performance
+            .record_worker_count(WORKER_NAME, approved_event, approved)
+            .await;
+        for (reason, ids) in skipped {
+            performance
+                .record_worker_count(
+                    WORKER_NAME,
+                    &format!("{}.{}", skipped_prefix, reason.as_str()),
+                    ids.len(),
+                )
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate() -> Candidate {
+        Candidate {
+            id: Uuid::nil(),
+            contributor_tag: "tester".to_string(),
+            created_at: Utc::now(),
+            reported: false,
+            low_confidence: false,
+            nsfw: false,
+            first_time_contributor: false,
+            restricted_region: false,
+        }
+    }
+
+    #[test]
+    fn clean_candidates_are_approved() {
+        assert_eq!(
+            exclusion_for(&candidate(), &AutoApproveExclusion::ALL),
+            None
+        );
+    }
+
+    #[test]
+    fn first_configured_exclusion_wins() {
+        let c = Candidate {
+            nsfw: true,
+            first_time_contributor: true,
+            ..candidate()
+        };
+        assert_eq!(
+            exclusion_for(&c, &AutoApproveExclusion::ALL),
+            Some(AutoApproveExclusion::Nsfw)
+        );
+        assert_eq!(
+            exclusion_for(
+                &c,
+                &[
+                    AutoApproveExclusion::FirstTimeContributor,
+                    AutoApproveExclusion::Nsfw
+                ]
+            ),
+            Some(AutoApproveExclusion::FirstTimeContributor)
+        );
+    }
+
+    #[test]
+    fn disabled_exclusions_are_ignored() {
+        let c = Candidate {
+            reported: true,
+            ..candidate()
+        };
+        assert_eq!(exclusion_for(&c, &[AutoApproveExclusion::Nsfw]), None);
     }
 }
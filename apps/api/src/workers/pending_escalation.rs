@@ -0,0 +1,211 @@
+use crate::infrastructure::monitoring::{
+    Alert, AlertSeverity, PerformanceMonitor, throttle::WorkerThrottle,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "pending_escalation";
+
+/// Items escalated per pass.
+const BATCH_SIZE: i64 = 200;
+
+/// Escalation level from which the moderator alert is raised as critical.
+const CRITICAL_LEVEL: i32 = 3;
+
+/// `review_pool` of items routed to the secondary reviewers.
+pub const SECONDARY_POOL: &str = "secondary";
+
+/// A pending lettering that was just escalated.
+#[derive(Debug, FromRow)]
+struct Escalated {
+    id: Uuid,
+    level: i32,
+    review_pool: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Moderator alert for one pass's escalations.
+fn escalation_alert(escalated: &[Escalated], stale_after_hours: i64) -> Alert {
+    let highest = escalated.iter().map(|e| e.level).max().unwrap_or(0);
+    let oldest = escalated.iter().map(|e| e.created_at).min();
+    let severity = if highest >= CRITICAL_LEVEL {
+        AlertSeverity::Critical
+    } else {
+        AlertSeverity::Warning
+    };
+    Alert::new(
+        severity,
+        "Pending letterings escalated",
+        &format!(
+            "{} item(s) waited over {}h per level for review (highest level {}, oldest pending since {})",
+            escalated.len(),
+            stale_after_hours,
+            highest,
+            oldest.map(|t| t.to_rfc3339()).unwrap_or_default()
+        ),
+        "moderation_pending_escalations",
+        0.0,
+        escalated.len() as f64,
+    )
+}
+
+/// Escalates letterings left pending too long. An item is escalated once it
+/// has waited `stale_after_hours`, then again each further period: its review
+/// priority goes up one level, it is routed to the secondary reviewer pool
+/// when one is configured, and moderators are alerted. The level is the
+/// priority apart from the review priority job's `priority_boost`.
+pub struct PendingEscalationWorker {
+    db: PgPool,
+    stale_after_hours: i64,
+    interval_seconds: u64,
+    route_to_secondary: bool,
+    performance: Option<Arc<PerformanceMonitor>>,
+    throttle: WorkerThrottle,
+}
+
+impl PendingEscalationWorker {
+    pub fn new(
+        db: PgPool,
+        stale_after_hours: i64,
+        interval_seconds: u64,
+        route_to_secondary: bool,
+    ) -> Self {
+        Self {
+            db,
+            stale_after_hours: stale_after_hours.max(1),
+            interval_seconds: interval_seconds.max(60),
+            route_to_secondary,
+            performance: None,
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Record escalated and routed counts in `performance`.
+    pub fn with_performance_monitor(mut self, performance: Arc<PerformanceMonitor>) -> Self {
+        self.performance = Some(performance);
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            if let Err(e) = self.run_once().await {
+                tracing::warn!("Pending escalation pass failed: {}", e);
+            }
+
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Every step is recorded in `moderation_escalations` for the weekly
+    /// escalation metrics.
+    async fn run_once(&self) -> Result<(), sqlx::Error> {
+        let pool = self.route_to_secondary.then_some(SECONDARY_POOL);
+        let mut escalated = Vec::new();
+        loop {
+            let batch = sqlx::query_as::<_, Escalated>(
+                "WITH due AS (
+                    SELECT id FROM letterings
+                    WHERE status = 'PENDING'
+                      AND created_at < NOW() - ($1::int * (review_priority - priority_boost + 1) * INTERVAL '1 hour')
+                    ORDER BY created_at ASC
+                    LIMIT $2
+                    FOR UPDATE SKIP LOCKED
+                ),
+                escalated AS (
+                    UPDATE letterings l
+                    SET review_priority = l.review_priority + 1,
+                        escalated_at = NOW(),
+                        review_pool = COALESCE($3, l.review_pool)
+                    FROM due
+                    WHERE l.id = due.id
+                    RETURNING l.id, l.review_priority - l.priority_boost AS level, l.review_pool,
+                              l.created_at
+                ),
+                logged AS (
+                    INSERT INTO moderation_escalations (id, lettering_id, level, review_pool, pending_since)
+                    SELECT uuid_generate_v4(), id, level, review_pool, created_at FROM escalated
+                )
+                SELECT id, level, review_pool, created_at FROM escalated",
+            )
+            .bind(self.stale_after_hours)
+            .bind(BATCH_SIZE)
+            .bind(pool)
+            .fetch_all(&self.db)
+            .await?;
+
+            let done = (batch.len() as i64) < BATCH_SIZE;
+            escalated.extend(batch);
+            if done {
+                break;
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                break;
+            }
+        }
+
+        if escalated.is_empty() {
+            return Ok(());
+        }
+
+        let alert = escalation_alert(&escalated, self.stale_after_hours);
+        let payload = serde_json::to_string(&alert).unwrap_or_default();
+        tracing::warn!(
+            alert = %payload,
+            ids = ?escalated.iter().map(|e| e.id).collect::<Vec<_>>(),
+            "{}",
+            alert.title
+        );
+
+        if let Some(performance) = &self.performance {
+            let routed = escalated
+                .iter()
+                .filter(|e| e.review_pool.as_deref() == Some(SECONDARY_POOL))
+                .count();
+            performance
+                .record_worker_count(WORKER_NAME, "escalated", escalated.len())
+                .await;
+            performance
+                .record_worker_count(WORKER_NAME, "routed_secondary", routed)
+                .await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escalated(level: i32) -> Escalated {
+        Escalated {
+            id: Uuid::nil(),
+            level,
+            review_pool: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn alert_turns_critical_at_repeated_escalation() {
+        let alert = escalation_alert(&[escalated(1), escalated(2)], 24);
+        assert_eq!(alert.severity, AlertSeverity::Warning);
+        assert_eq!(alert.current_value, 2.0);
+
+        let alert = escalation_alert(&[escalated(1), escalated(CRITICAL_LEVEL)], 24);
+        assert_eq!(alert.severity, AlertSeverity::Critical);
+    }
+}
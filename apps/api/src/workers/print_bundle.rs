@@ -0,0 +1,386 @@
+use crate::infrastructure::{
+    monitoring::heartbeat::Heartbeats,
+    shutdown::Shutdown,
+    storage::{
+        traits::{ChunkedUpload, StorageService},
+        visibility::{legacy_original_key, original_key},
+        zip_stream::ZipStream,
+    },
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use std::{fmt::Write as _, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "print_bundle";
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+const FETCH_URL_TTL: Duration = Duration::from_secs(900);
+/// A RUNNING bundle older than this is assumed orphaned by a restart.
+const STALE_AFTER_MINUTES: i32 = 60;
+
+#[derive(Debug, FromRow)]
+struct ClaimedBundle {
+    id: Uuid,
+    title: String,
+    lettering_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, FromRow)]
+struct BundleItem {
+    id: Uuid,
+    image_url: String,
+    detected_text: Option<String>,
+    description: Option<String>,
+    cultural_context: Option<String>,
+    contributor_tag: String,
+    city: String,
+    pin_code: String,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    ml_style: Option<String>,
+    ml_script: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Bytes written and items that made it into the archive.
+struct BundleTotals {
+    size_bytes: i64,
+    included: i32,
+    missing: i32,
+}
+
+/// Assembles print bundles requested through the admin API.
+///
+/// Each bundle is one zip streamed straight into storage: the preserved
+/// originals (falling back to the archived WebP), a `metadata.csv` sheet and
+/// an `ATTRIBUTION.txt` crediting every contributor. Letterings that were
+/// deleted or unapproved after the request are skipped and counted as missing.
+pub struct PrintBundleWorker {
+    db: PgPool,
+    storage: Arc<dyn StorageService>,
+    public_base_url: Option<String>,
+    heartbeats: Heartbeats,
+    shutdown: Shutdown,
+}
+
+impl PrintBundleWorker {
+    pub fn new(
+        db: PgPool,
+        storage: Arc<dyn StorageService>,
+        public_base_url: Option<String>,
+    ) -> Self {
+        Self {
+            db,
+            storage,
+            public_base_url,
+            heartbeats: Heartbeats::disabled(),
+            shutdown: Shutdown::new(),
+        }
+    }
+
+    /// Beat while polling and name the bundle being built.
+    pub fn with_heartbeats(mut self, heartbeats: Heartbeats) -> Self {
+        self.heartbeats = heartbeats;
+        self
+    }
+
+    /// Once `shutdown` fires, finish the bundle in hand and stop claiming.
+    /// One cut off by the drain timeout is claimed again once stale.
+    pub fn with_shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    pub async fn start(&self) {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(300))
+            .build()
+            .unwrap();
+        while !self.shutdown.is_triggered() {
+            match self.claim_next().await {
+                Ok(Some(bundle)) => {
+                    self.heartbeats
+                        .working_on(
+                            WORKER_NAME,
+                            &format!("bundle {}", bundle.id),
+                            Duration::from_secs(STALE_AFTER_MINUTES as u64 * 60),
+                        )
+                        .await;
+                    self.run(&client, bundle).await
+                }
+                Ok(None) => {
+                    self.heartbeats.beat(WORKER_NAME, POLL_INTERVAL).await;
+                    self.shutdown.sleep(POLL_INTERVAL).await;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to claim print bundle: {}", e);
+                    self.shutdown.sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn claim_next(&self) -> anyhow::Result<Option<ClaimedBundle>> {
+        let bundle = sqlx::query_as::<_, ClaimedBundle>(
+            "UPDATE print_bundles SET status = 'RUNNING', started_at = NOW()
+             WHERE id = (
+                 SELECT id FROM print_bundles
+                 WHERE status = 'PENDING'
+                    OR (status = 'RUNNING' AND started_at < NOW() - make_interval(mins => $1))
+                 ORDER BY created_at ASC
+                 LIMIT 1
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING id, title, lettering_ids",
+        )
+        .bind(STALE_AFTER_MINUTES)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(bundle)
+    }
+
+    async fn run(&self, client: &reqwest::Client, bundle: ClaimedBundle) {
+        let key = format!("print-bundles/{}.zip", bundle.id);
+        let result = match self.build(client, &bundle, &key).await {
+            Ok(totals) => {
+                tracing::info!(
+                    bundle_id = %bundle.id,
+                    included = totals.included,
+                    missing = totals.missing,
+                    "Print bundle completed"
+                );
+                sqlx::query(
+                    "UPDATE print_bundles
+                     SET status = 'COMPLETED', object_key = $2, size_bytes = $3,
+                         items_included = $4, items_missing = $5, error = NULL,
+                         finished_at = NOW()
+                     WHERE id = $1",
+                )
+                .bind(bundle.id)
+                .bind(&key)
+                .bind(totals.size_bytes)
+                .bind(totals.included)
+                .bind(totals.missing)
+                .execute(&self.db)
+                .await
+            }
+            Err(e) => {
+                tracing::error!(bundle_id = %bundle.id, "Print bundle failed: {}", e);
+                sqlx::query(
+                    "UPDATE print_bundles SET status = 'FAILED', error = $2, finished_at = NOW() WHERE id = $1",
+                )
+                .bind(bundle.id)
+                .bind(e.to_string())
+                .execute(&self.db)
+                .await
+            }
+        };
+        if let Err(e) = result {
+            tracing::error!(bundle_id = %bundle.id, "Failed to record print bundle status: {}", e);
+        }
+    }
+
+    async fn build(
+        &self,
+        client: &reqwest::Client,
+        bundle: &ClaimedBundle,
+        key: &str,
+    ) -> anyhow::Result<BundleTotals> {
+        let items = sqlx::query_as::<_, BundleItem>(
+            "SELECT l.id, l.image_url, l.detected_text, l.description, l.cultural_context,
+                    l.contributor_tag, c.name AS city, l.pin_code,
+                    ST_Y(l.location::geometry) AS latitude, ST_X(l.location::geometry) AS longitude,
+                    l.ml_style, l.ml_script, l.created_at
+             FROM letterings l
+             JOIN cities c ON c.id = l.city_id
+             WHERE l.id = ANY($1) AND l.status = 'APPROVED'
+             ORDER BY array_position($1, l.id)",
+        )
+        .bind(&bundle.lettering_ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut upload = self
+            .storage
+            .start_chunked_upload(key, "application/zip")
+            .await?;
+        match self
+            .write_archive(client, bundle, &items, upload.as_mut())
+            .await
+        {
+            Ok(totals) => {
+                upload.finish().await?;
+                Ok(totals)
+            }
+            Err(e) => {
+                upload.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_archive(
+        &self,
+        client: &reqwest::Client,
+        bundle: &ClaimedBundle,
+        items: &[BundleItem],
+        upload: &mut dyn ChunkedUpload,
+    ) -> anyhow::Result<BundleTotals> {
+        let mut zip = ZipStream::new(Utc::now());
+        let mut files = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            files.push(
+                self.write_image(client, &mut zip, upload, index, item)
+                    .await?,
+            );
+        }
+
+        let base = self
+            .public_base_url
+            .as_deref()
+            .unwrap_or_default()
+            .trim_end_matches('/');
+        let documents = [
+            ("metadata.csv", metadata_sheet(items, &files, base)),
+            ("ATTRIBUTION.txt", attribution(&bundle.title, items, &files)),
+        ];
+        for (name, body) in documents {
+            upload.write(&zip.begin_entry(name)?).await?;
+            zip.write(body.as_bytes())?;
+            upload.write(body.as_bytes()).await?;
+        }
+        let entries_size = zip.bytes_written();
+        let directory = zip.finish()?;
+        upload.write(&directory).await?;
+
+        let included = files.iter().filter(|f| f.is_some()).count() as i32;
+        Ok(BundleTotals {
+            size_bytes: (entries_size + directory.len() as u64) as i64,
+            included,
+            missing: bundle.lettering_ids.len() as i32 - included,
+        })
+    }
+
+    /// Stream one lettering's best available image into the archive. Returns
+    /// the entry name, or `None` if no source object exists.
+    async fn write_image(
+        &self,
+        client: &reqwest::Client,
+        zip: &mut ZipStream,
+        upload: &mut dyn ChunkedUpload,
+        index: usize,
+        item: &BundleItem,
+    ) -> anyhow::Result<Option<String>> {
+        let mut sources = vec![original_key(item.id), legacy_original_key(item.id)];
+        if let Some(archived) = self.storage.key_from_url(&item.image_url) {
+            sources.push(archived);
+        }
+
+        for source_key in sources {
+            let url = self.storage.signed_url(&source_key, FETCH_URL_TTL).await?;
+            let mut response = client.get(&url).send().await?;
+            let status = response.status();
+            if status == reqwest::StatusCode::NOT_FOUND {
+                continue;
+            }
+            if !status.is_success() {
+                anyhow::bail!("image fetch for {} returned HTTP {}", item.id, status);
+            }
+            let extension = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(extension_for)
+                .unwrap_or("bin");
+
+            let name = format!("images/{:03}-{}.{}", index + 1, item.id, extension);
+            upload.write(&zip.begin_entry(&name)?).await?;
+            while let Some(chunk) = response.chunk().await? {
+                zip.write(&chunk)?;
+                upload.write(&chunk).await?;
+            }
+            return Ok(Some(name));
+        }
+
+        tracing::warn!(lettering_id = %item.id, "No image found for print bundle item");
+        Ok(None)
+    }
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        "image/heic" => "heic",
+        "image/tiff" => "tif",
+        _ => "bin",
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn metadata_sheet(items: &[BundleItem], files: &[Option<String>], base: &str) -> String {
+    let mut sheet = String::from(
+        "file,id,detected_text,description,cultural_context,contributor,city,pin_code,latitude,longitude,style,script,captured_at,page_url\n",
+    );
+    for (item, file) in items.iter().zip(files) {
+        let fields = [
+            file.clone().unwrap_or_default(),
+            item.id.to_string(),
+            item.detected_text.clone().unwrap_or_default(),
+            item.description.clone().unwrap_or_default(),
+            item.cultural_context.clone().unwrap_or_default(),
+            item.contributor_tag.clone(),
+            item.city.clone(),
+            item.pin_code.clone(),
+            item.latitude.map(|v| v.to_string()).unwrap_or_default(),
+            item.longitude.map(|v| v.to_string()).unwrap_or_default(),
+            item.ml_style.clone().unwrap_or_default(),
+            item.ml_script.clone().unwrap_or_default(),
+            item.created_at.to_rfc3339(),
+            format!("{}/lettering/{}", base, item.id),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        sheet.push_str(&row.join(","));
+        sheet.push('\n');
+    }
+    sheet
+}
+
+fn attribution(title: &str, items: &[BundleItem], files: &[Option<String>]) -> String {
+    let mut text = format!(
+        "{}\nPrint bundle generated {} from Through Your Letters.\n\n\
+         Each photograph must be credited to its contributor when reproduced.\n\n",
+        title,
+        Utc::now().format("%Y-%m-%d")
+    );
+    for (item, file) in items.iter().zip(files) {
+        let Some(file) = file else { continue };
+        let _ = writeln!(
+            text,
+            "{}: photo by {}, {} ({})",
+            file, item.contributor_tag, item.city, item.pin_code
+        );
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("Bengaluru"), "Bengaluru");
+        assert_eq!(csv_field("Hand-painted, 1970s"), "\"Hand-painted, 1970s\"");
+        assert_eq!(csv_field("the \"Raja\" sign"), "\"the \"\"Raja\"\" sign\"");
+        assert_eq!(extension_for("image/jpeg; charset=binary"), "jpg");
+    }
+}
@@ -0,0 +1,312 @@
+use crate::infrastructure::monitoring::throttle::WorkerThrottle;
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "review_priority";
+
+/// Items scored per batch.
+const BATCH_SIZE: i64 = 500;
+
+/// Traffic counted towards priority.
+const VIEW_WINDOW_HOURS: i32 = 24;
+
+/// View counters older than this are deleted.
+const VIEW_RETENTION_DAYS: i32 = 7;
+
+/// What an item's moderation priority is boosted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrioritySignals {
+    /// Open reports, each weighted like for auto-hiding.
+    pub report_weight: f64,
+    /// Detail views of the lettering (for comments, the one commented on)
+    /// over the last `VIEW_WINDOW_HOURS`.
+    pub recent_views: i64,
+    /// NSFW likelihood in [0, 1]: the classifier's score for letterings,
+    /// the prefilter score for comments.
+    pub nsfw_score: f64,
+    /// Whether the public can currently see the item.
+    pub publicly_visible: bool,
+}
+
+/// Priority added on top of escalation or prefilter priority, 0 to 100.
+///
+/// Reports weigh most, up to 40. Traffic only counts while an item is
+/// public, up to 30 on a log scale, and a public item that is both reported
+/// and being viewed gets another 10: it is doing harm right now, unlike an
+/// obscure pending upload. NSFW likelihood adds up to 20.
+pub fn priority_boost(signals: &PrioritySignals) -> i32 {
+    let reports = signals.report_weight.clamp(0.0, 5.0) * 8.0;
+    let (traffic, exposure) = if signals.publicly_visible {
+        let traffic = (10.0 * (1.0 + signals.recent_views.max(0) as f64).log10()).min(30.0);
+        let exposed = signals.report_weight > 0.0 && signals.recent_views > 0;
+        (traffic, if exposed { 10.0 } else { 0.0 })
+    } else {
+        (0.0, 0.0)
+    };
+    let nsfw = signals.nsfw_score.clamp(0.0, 1.0) * 20.0;
+    ((reports + traffic + exposure + nsfw).round() as i32).clamp(0, 100)
+}
+
+#[derive(Debug, FromRow)]
+struct Candidate {
+    id: Uuid,
+    /// Whether the item is up for review; a boost left on one that no
+    /// longer is gets cleared.
+    in_review: bool,
+    report_weight: f64,
+    recent_views: i64,
+    nsfw_score: f64,
+    publicly_visible: bool,
+    priority_boost: i32,
+}
+
+impl Candidate {
+    fn boost(&self) -> i32 {
+        if !self.in_review {
+            return 0;
+        }
+        priority_boost(&PrioritySignals {
+            report_weight: self.report_weight,
+            recent_views: self.recent_views,
+            nsfw_score: self.nsfw_score,
+            publicly_visible: self.publicly_visible,
+        })
+    }
+}
+
+/// Recomputes `priority_boost` for letterings and comments that need or
+/// had a review boost, and keeps it added into their `review_priority`, so
+/// the moderation queues surface reported items people are looking at ahead
+/// of obscure pending ones.
+///
+/// Letterings are scored while pending or hidden, or when approved with
+/// reports or an NSFW flag; comments while they need review. Comments have
+/// no reports of their own.
+pub struct ReviewPriorityWorker {
+    db: PgPool,
+    report_anonymous_weight: f64,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl ReviewPriorityWorker {
+    pub fn new(db: PgPool, report_anonymous_weight: f64, interval_seconds: u64) -> Self {
+        Self {
+            db,
+            report_anonymous_weight: report_anonymous_weight.max(0.0),
+            interval_seconds: interval_seconds.max(60),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok((letterings, comments)) if letterings + comments > 0 => {
+                    tracing::debug!(letterings, comments, "Review priorities updated");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Review priority pass failed: {}", e),
+            }
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Rescores every candidate and returns how many letterings and
+    /// comments changed priority.
+    pub async fn run_once(&self) -> Result<(u64, u64), sqlx::Error> {
+        let letterings = self
+            .rescore(
+                "SELECT l.id, x.in_review,
+                        COALESCE((
+                            SELECT SUM(CASE WHEN r.reporter_key LIKE 'user:%' THEN 1.0 ELSE $3 END)
+                            FROM lettering_reports r
+                            WHERE r.lettering_id = l.id AND r.cleared_at IS NULL
+                        ), 0)::float8 AS report_weight,
+                        COALESCE((
+                            SELECT SUM(v.count) FROM lettering_view_counts v
+                            WHERE v.lettering_id = l.id
+                              AND v.hour >= NOW() - make_interval(hours => $4)
+                        ), 0)::bigint AS recent_views,
+                        COALESCE(l.nsfw_score, CASE WHEN l.nsfw_flagged THEN 1 ELSE 0 END)::float8
+                            AS nsfw_score,
+                        l.status = 'APPROVED' AND city_discoverable(l.city_id) AS publicly_visible,
+                        l.priority_boost
+                 FROM letterings l
+                 CROSS JOIN LATERAL (
+                     SELECT l.status IN ('PENDING', 'REPORTED')
+                            OR (l.status = 'APPROVED' AND (l.report_count > 0 OR l.nsfw_flagged))
+                            AS in_review
+                 ) x
+                 WHERE l.id > $1 AND (x.in_review OR l.priority_boost > 0)
+                 ORDER BY l.id
+                 LIMIT $2",
+                "UPDATE letterings l
+                 SET review_priority = l.review_priority - l.priority_boost + v.boost,
+                     priority_boost = v.boost
+                 FROM UNNEST($1::uuid[], $2::int[]) AS v(id, boost)
+                 WHERE l.id = v.id",
+            )
+            .await?;
+
+        let comments = self
+            .rescore(
+                "SELECT c.id, c.needs_review AS in_review,
+                        0::float8 AS report_weight,
+                        COALESCE((
+                            SELECT SUM(v.count) FROM lettering_view_counts v
+                            WHERE v.lettering_id = c.lettering_id
+                              AND v.hour >= NOW() - make_interval(hours => $4)
+                        ), 0)::bigint AS recent_views,
+                        LEAST(GREATEST(c.moderation_score, 0), 100)::float8 / 100 AS nsfw_score,
+                        c.status = 'VISIBLE' AND c.deleted_at IS NULL
+                            AND l.status = 'APPROVED' AND city_discoverable(l.city_id)
+                            AS publicly_visible,
+                        c.priority_boost
+                 FROM comments c
+                 JOIN letterings l ON l.id = c.lettering_id
+                 WHERE c.id > $1 AND (c.needs_review OR c.priority_boost > 0)
+                 ORDER BY c.id
+                 LIMIT $2",
+                "UPDATE comments c
+                 SET review_priority = c.review_priority - c.priority_boost + v.boost,
+                     priority_boost = v.boost
+                 FROM UNNEST($1::uuid[], $2::int[]) AS v(id, boost)
+                 WHERE c.id = v.id",
+            )
+            .await?;
+
+        sqlx::query(
+            "DELETE FROM lettering_view_counts
+             WHERE hour < NOW() - make_interval(days => $1)",
+        )
+        .bind(VIEW_RETENTION_DAYS)
+        .execute(&self.db)
+        .await?;
+
+        Ok((letterings, comments))
+    }
+
+    /// Pages through `select` by id (`$1` last id, `$2` batch size, `$3`
+    /// anonymous report weight, `$4` view window; the last two may go
+    /// unused) and writes changed boosts with `update` (`$1` ids, `$2`
+    /// boosts).
+    async fn rescore(&self, select: &str, update: &str) -> Result<u64, sqlx::Error> {
+        let mut after = Uuid::nil();
+        let mut changed = 0;
+        loop {
+            let batch = sqlx::query_as::<_, Candidate>(select)
+                .bind(after)
+                .bind(BATCH_SIZE)
+                .bind(self.report_anonymous_weight)
+                .bind(VIEW_WINDOW_HOURS)
+                .fetch_all(&self.db)
+                .await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after = last.id;
+
+            let (ids, boosts): (Vec<Uuid>, Vec<i32>) = batch
+                .iter()
+                .filter_map(|c| {
+                    let boost = c.boost();
+                    (boost != c.priority_boost).then_some((c.id, boost))
+                })
+                .unzip();
+            if !ids.is_empty() {
+                changed += sqlx::query(update)
+                    .bind(&ids)
+                    .bind(&boosts)
+                    .execute(&self.db)
+                    .await?
+                    .rows_affected();
+            }
+
+            if (batch.len() as i64) < BATCH_SIZE {
+                break;
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                break;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reported_public_items_with_traffic_outrank_obscure_pending_ones() {
+        let reported_and_viewed = PrioritySignals {
+            report_weight: 1.0,
+            recent_views: 500,
+            nsfw_score: 0.0,
+            publicly_visible: true,
+        };
+        let obscure_pending = PrioritySignals {
+            report_weight: 1.0,
+            recent_views: 0,
+            nsfw_score: 0.3,
+            publicly_visible: false,
+        };
+        assert!(priority_boost(&reported_and_viewed) > priority_boost(&obscure_pending));
+    }
+
+    #[test]
+    fn traffic_only_counts_while_public() {
+        let hidden = PrioritySignals {
+            recent_views: 10_000,
+            ..Default::default()
+        };
+        assert_eq!(priority_boost(&hidden), 0);
+        assert_eq!(
+            priority_boost(&PrioritySignals {
+                publicly_visible: true,
+                ..hidden
+            }),
+            30
+        );
+    }
+
+    #[test]
+    fn items_no_longer_in_review_lose_their_boost() {
+        let reviewed = Candidate {
+            id: Uuid::nil(),
+            in_review: false,
+            report_weight: 2.0,
+            recent_views: 100,
+            nsfw_score: 0.0,
+            publicly_visible: true,
+            priority_boost: 46,
+        };
+        assert_eq!(reviewed.boost(), 0);
+    }
+
+    #[test]
+    fn boost_is_bounded() {
+        let everything = PrioritySignals {
+            report_weight: 50.0,
+            recent_views: i64::MAX,
+            nsfw_score: 3.0,
+            publicly_visible: true,
+        };
+        assert_eq!(priority_boost(&everything), 100);
+        assert_eq!(priority_boost(&PrioritySignals::default()), 0);
+    }
+}
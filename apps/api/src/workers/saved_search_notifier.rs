@@ -0,0 +1,255 @@
+use crate::infrastructure::{
+    monitoring::throttle::WorkerThrottle,
+    repositories::region_policy::{PUBLIC_LETTERING_JOINS, push_public_filter},
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use std::time::Duration;
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "saved_search_notifier";
+
+/// Saved searches evaluated per batch.
+const BATCH_SIZE: i64 = 200;
+
+/// Lettering ids listed in one notification's metadata.
+const MAX_NOTIFIED_IDS: i64 = 10;
+
+/// The filters of one saved search.
+#[derive(Debug, Clone, FromRow)]
+pub struct SavedSearchCriteria {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub query: Option<String>,
+    pub script: Option<String>,
+    pub style: Option<String>,
+    pub city_id: Option<Uuid>,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+    pub radius_m: Option<f64>,
+    pub last_checked_at: DateTime<Utc>,
+}
+
+/// Public letterings matching `search`. Expects `letterings l` with
+/// `PUBLIC_LETTERING_JOINS`; age-restricted items in gating regions are left out
+/// since a notification can't carry the client's age acknowledgment.
+pub fn push_saved_search_filters(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    search: &SavedSearchCriteria,
+) {
+    qb.push(" AND ");
+    push_public_filter(qb, false);
+    // Nobody needs telling about their own upload.
+    qb.push(" AND l.user_id IS DISTINCT FROM ")
+        .push_bind(search.user_id);
+
+    if let Some(query) = search.query.as_deref() {
+        let like = format!("%{}%", query);
+        qb.push(" AND (l.detected_text_tsv @@ websearch_to_tsquery('english', ")
+            .push_bind(query.to_string())
+            .push(") OR (l.detected_text ILIKE ")
+            .push_bind(like.clone())
+            .push(" AND NOT ('detected_text' = ANY(l.ml_low_confidence_fields))) OR l.description ILIKE ")
+            .push_bind(like.clone())
+            .push(" OR l.contributor_tag ILIKE ")
+            .push_bind(like)
+            .push(")");
+    }
+    if let Some(script) = search.script.as_deref() {
+        qb.push(" AND l.ml_script = ")
+            .push_bind(script.to_string())
+            .push(" AND NOT ('script' = ANY(l.ml_low_confidence_fields))");
+    }
+    if let Some(style) = search.style.as_deref() {
+        qb.push(" AND l.ml_style = ")
+            .push_bind(style.to_string())
+            .push(" AND NOT ('style' = ANY(l.ml_low_confidence_fields))");
+    }
+    if let Some(city_id) = search.city_id {
+        qb.push(" AND l.city_id = ").push_bind(city_id);
+    }
+    if let (Some(lat), Some(lng), Some(radius_m)) = (search.lat, search.lng, search.radius_m) {
+        qb.push(" AND ST_DWithin(l.location, ST_SetSRID(ST_MakePoint(")
+            .push_bind(lng)
+            .push(", ")
+            .push_bind(lat)
+            .push("), 4326)::geography, ")
+            .push_bind(radius_m)
+            .push(")");
+    }
+}
+
+/// Notifies users when letterings approved since the last pass match one of
+/// their saved searches: one `SAVED_SEARCH_MATCHES` notification per search
+/// per pass, however many letterings matched.
+pub struct SavedSearchNotifier {
+    db: PgPool,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl SavedSearchNotifier {
+    pub fn new(db: PgPool, interval_seconds: u64) -> Self {
+        Self {
+            db,
+            interval_seconds: interval_seconds.max(60),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(notified) if notified > 0 => {
+                    tracing::info!(notified, "Saved search notifications sent");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Saved search pass failed: {}", e),
+            }
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Evaluates every notifying search against approvals up to the start of
+    /// the pass, oldest check first, and returns how many notifications were
+    /// sent.
+    pub async fn run_once(&self) -> Result<usize, sqlx::Error> {
+        let until: DateTime<Utc> = sqlx::query_scalar("SELECT NOW()")
+            .fetch_one(&self.db)
+            .await?;
+        let mut notified = 0;
+        loop {
+            let searches = sqlx::query_as::<_, SavedSearchCriteria>(
+                "SELECT id, user_id, name, query, script, style, city_id,
+                        ST_Y(center::geometry) AS lat, ST_X(center::geometry) AS lng, radius_m,
+                        last_checked_at
+                 FROM saved_searches
+                 WHERE notify AND last_checked_at < $1
+                 ORDER BY last_checked_at
+                 LIMIT $2",
+            )
+            .bind(until)
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await?;
+
+            for search in &searches {
+                if self.evaluate(search, until).await? {
+                    notified += 1;
+                }
+            }
+            if (searches.len() as i64) < BATCH_SIZE {
+                return Ok(notified);
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                return Ok(notified);
+            }
+        }
+    }
+
+    /// Notifies about one search's new matches, if any, and moves its check
+    /// mark to `until`.
+    async fn evaluate(
+        &self,
+        search: &SavedSearchCriteria,
+        until: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut qb = QueryBuilder::<Postgres>::new(
+            "SELECT l.id, COUNT(*) OVER ()::bigint AS total FROM letterings l ",
+        );
+        qb.push(PUBLIC_LETTERING_JOINS).push(
+            " WHERE EXISTS (
+                 SELECT 1 FROM lettering_status_history h
+                 WHERE h.lettering_id = l.id AND h.to_status = 'APPROVED'
+                   AND h.created_at > ",
+        );
+        qb.push_bind(search.last_checked_at)
+            .push(" AND h.created_at <= ")
+            .push_bind(until)
+            .push(")");
+        push_saved_search_filters(&mut qb, search);
+        qb.push(" ORDER BY l.created_at DESC LIMIT ")
+            .push_bind(MAX_NOTIFIED_IDS);
+
+        let matches: Vec<(Uuid, i64)> = qb.build_query_as().fetch_all(&self.db).await?;
+
+        let mut tx = self.db.begin().await?;
+        if let Some(&(_, total)) = matches.first() {
+            let ids: Vec<Uuid> = matches.iter().map(|(id, _)| *id).collect();
+            let body = if total == 1 {
+                "A new lettering matches your saved search.".to_string()
+            } else {
+                format!("{} new letterings match your saved search.", total)
+            };
+            sqlx::query(
+                "INSERT INTO notifications (id, user_id, type, title, body, metadata)
+                 VALUES ($1, $2, 'SAVED_SEARCH_MATCHES', $3, $4, $5)",
+            )
+            .bind(Uuid::now_v7())
+            .bind(search.user_id)
+            .bind(format!("New matches for \"{}\"", search.name))
+            .bind(body)
+            .bind(serde_json::json!({
+                "saved_search_id": search.id,
+                "count": total,
+                "lettering_ids": ids,
+            }))
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("UPDATE saved_searches SET last_checked_at = $2 WHERE id = $1")
+            .bind(search.id)
+            .bind(until)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(!matches.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_set_filters_are_applied() {
+        let search = SavedSearchCriteria {
+            id: Uuid::nil(),
+            user_id: Uuid::nil(),
+            name: "Ghost signs".to_string(),
+            query: None,
+            script: Some("devanagari".to_string()),
+            style: None,
+            city_id: None,
+            lat: Some(12.97),
+            lng: Some(77.59),
+            radius_m: Some(2_000.0),
+            last_checked_at: Utc::now(),
+        };
+        let mut qb = QueryBuilder::<Postgres>::new("SELECT l.id FROM letterings l WHERE true");
+        push_saved_search_filters(&mut qb, &search);
+        let sql = qb.sql();
+
+        assert!(sql.contains("l.status = 'APPROVED'"));
+        assert!(sql.contains("l.age_restricted"));
+        assert!(sql.contains("l.ml_script = "));
+        assert!(sql.contains("ST_DWithin"));
+        assert!(!sql.contains("websearch_to_tsquery"));
+        assert!(!sql.contains("l.ml_style"));
+        assert!(!sql.contains("l.city_id"));
+    }
+}
@@ -0,0 +1,683 @@
+//! Cron-style scheduling for periodic maintenance.
+//!
+//! Every instance runs a `Scheduler`, but each tick of a schedule fires on
+//! one of them only: whichever first takes the tick's Redis lock. Schedules
+//! default to `SCHEDULES`, can be overridden per task by the `SCHEDULES`
+//! setting and then by an admin in `scheduled_tasks`. Cron expressions are
+//! evaluated in UTC. Every fired tick is recorded in `scheduled_task_runs`.
+
+use crate::infrastructure::{leadership::instance_id, monitoring::throttle::WorkerThrottle};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Timelike, Utc};
+use futures_util::{FutureExt, future::join_all};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::{collections::BTreeMap, fmt, panic::AssertUnwindSafe, sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "scheduler";
+
+/// How often schedules are checked for due ticks. Ticks are per minute.
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Lifetime of a tick lock: long enough that an instance still checking the
+/// tick after a stall cannot take it again.
+const TICK_LOCK_TTL_SECONDS: u64 = 3600;
+
+/// A run still `RUNNING` after this long is assumed to have died with its
+/// instance and no longer holds back the task's next tick.
+const STALE_RUN_HOURS: i32 = 12;
+
+/// Years searched for a next tick before giving up on an expression
+/// that can never match, such as February 30th.
+const MAX_SEARCH_YEARS: i32 = 8;
+
+/// A task the scheduler knows about, with the schedule it runs on unless
+/// overridden.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ScheduleDefinition {
+    pub name: &'static str,
+    pub default_cron: &'static str,
+    pub description: &'static str,
+}
+
+pub const SCHEDULES: &[ScheduleDefinition] = &[
+    ScheduleDefinition {
+        name: "analytics_rollup",
+        default_cron: "0 * * * *",
+        description: "Roll up hourly and daily activity and recompute leaderboards",
+    },
+    ScheduleDefinition {
+        name: "backup_export",
+        default_cron: "0 3 * * *",
+        description: "Copy newly approved images and a metadata dump to the backup bucket",
+    },
+    ScheduleDefinition {
+        name: "geo_retention",
+        default_cron: "30 */6 * * *",
+        description: "Delete geo annotations and cached IP lookups past retention",
+    },
+];
+
+pub fn definition(name: &str) -> Option<&'static ScheduleDefinition> {
+    SCHEDULES.iter().find(|s| s.name == name)
+}
+
+/// Work run on a schedule. `name` selects its schedule.
+#[async_trait]
+pub trait ScheduledTask: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn run(&self) -> anyhow::Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronError(String);
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CronError {}
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// A standard five-field cron expression (minute, hour, day of month, month,
+/// day of week) with `*`, lists, ranges, steps, month and weekday names and
+/// the `@hourly`-style shorthands. As in cron, when both day fields are
+/// restricted a day matching either one fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, CronError> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ => expression,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(CronError(format!(
+                "`{}` must have five fields: minute hour day-of-month month day-of-week",
+                expression
+            )));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, "day of week", 0, 7, WEEKDAY_NAMES)?;
+        // 7 is Sunday too.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            expression: expression.to_string(),
+            minutes: parse_field(minute, "minute", 0, 59, &[])?,
+            hours: parse_field(hour, "hour", 0, 23, &[])?,
+            days_of_month: parse_field(day_of_month, "day of month", 1, 31, &[])?,
+            months: parse_field(month, "month", 1, 12, MONTH_NAMES)?,
+            days_of_week,
+            any_day_of_month: day_of_month.starts_with('*'),
+            any_day_of_week: day_of_week.starts_with('*'),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = self.days_of_month & (1 << date.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+
+    /// The first tick strictly after `after`, or `None` if the expression
+    /// never matches.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        let give_up = after.year() + MAX_SEARCH_YEARS;
+        while t.year() <= give_up {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?
+                    .and_hms_opt(0, 0, 0)?
+                    .and_utc();
+            } else if !self.matches_day(t.date_naive()) {
+                t = t.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + TimeDelta::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += TimeDelta::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    /// The last tick in `(after, until]`, if any. Ticks missed while the
+    /// scheduler was behind collapse into this one.
+    pub fn latest_between(
+        &self,
+        after: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let mut latest = None;
+        let mut cursor = after;
+        while let Some(tick) = self.next_after(cursor).filter(|t| *t <= until) {
+            latest = Some(tick);
+            cursor = tick;
+        }
+        latest
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn parse_field(
+    field: &str,
+    label: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u64, CronError> {
+    let invalid = || CronError(format!("invalid {} field `{}`", label, field));
+    let value = |s: &str| -> Result<u32, CronError> {
+        if let Some(i) = names.iter().position(|n| n.eq_ignore_ascii_case(s)) {
+            return Ok(min + i as u32);
+        }
+        let v: u32 = s.parse().map_err(|_| invalid())?;
+        if v < min || v > max {
+            return Err(CronError(format!(
+                "{} `{}` is outside {}-{}",
+                label, v, min, max
+            )));
+        }
+        Ok(v)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` means from 5 to the end in steps of 15.
+                None if step.is_some() => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            },
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+/// `name=cron` pairs separated by `;`, as in the `SCHEDULES` setting.
+pub fn parse_schedule_overrides(value: &str) -> Result<BTreeMap<String, String>, CronError> {
+    let mut overrides = BTreeMap::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let Some((name, cron)) = entry.split_once('=') else {
+            return Err(CronError(format!("`{}` must be name=cron", entry)));
+        };
+        let name = name.trim().to_string();
+        let cron = CronSchedule::parse(cron)?;
+        if overrides.contains_key(&name) {
+            return Err(CronError(format!("`{}` is scheduled twice", name)));
+        }
+        overrides.insert(name, cron.as_str().to_string());
+    }
+    Ok(overrides)
+}
+
+/// Where a task's schedule came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleSource {
+    Default,
+    Config,
+    Database,
+    /// No schedule anywhere; the task never fires.
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct EffectiveSchedule {
+    pub name: String,
+    pub cron: Option<CronSchedule>,
+    pub source: ScheduleSource,
+    pub enabled: bool,
+}
+
+#[derive(Debug, FromRow)]
+struct ScheduleOverride {
+    name: String,
+    cron: Option<String>,
+    enabled: bool,
+}
+
+/// The schedule each of `names` runs on: an admin override, else the
+/// configured one, else the default.
+pub async fn effective_schedules(
+    db: &PgPool,
+    config_overrides: &BTreeMap<String, String>,
+    names: &[&str],
+) -> Result<Vec<EffectiveSchedule>, sqlx::Error> {
+    let stored = sqlx::query_as::<_, ScheduleOverride>(
+        "SELECT name, cron, enabled FROM scheduled_tasks WHERE name = ANY($1)",
+    )
+    .bind(names)
+    .fetch_all(db)
+    .await?;
+
+    Ok(names
+        .iter()
+        .map(|&name| {
+            let stored = stored.iter().find(|s| s.name == name);
+            let candidates = [
+                (
+                    ScheduleSource::Database,
+                    stored.and_then(|s| s.cron.as_deref()),
+                ),
+                (
+                    ScheduleSource::Config,
+                    config_overrides.get(name).map(String::as_str),
+                ),
+                (
+                    ScheduleSource::Default,
+                    definition(name).map(|d| d.default_cron),
+                ),
+            ];
+            let (source, cron) = candidates
+                .into_iter()
+                .filter_map(|(source, cron)| {
+                    let cron = cron?;
+                    CronSchedule::parse(cron)
+                        .inspect_err(|e| {
+                            tracing::warn!(task = name, "Ignoring invalid schedule: {}", e)
+                        })
+                        .ok()
+                        .map(|cron| (source, Some(cron)))
+                })
+                .next()
+                .unwrap_or((ScheduleSource::None, None));
+            EffectiveSchedule {
+                name: name.to_string(),
+                cron,
+                source,
+                enabled: stored.is_none_or(|s| s.enabled),
+            }
+        })
+        .collect())
+}
+
+/// Fires registered tasks on their schedules. Cheap to clone; clones share
+/// the tasks.
+#[derive(Clone)]
+pub struct Scheduler {
+    db: PgPool,
+    redis: redis::Client,
+    overrides: BTreeMap<String, String>,
+    tasks: Vec<Arc<dyn ScheduledTask>>,
+    instance: String,
+    throttle: WorkerThrottle,
+}
+
+impl Scheduler {
+    /// `overrides` maps task names to cron expressions that replace their
+    /// defaults.
+    pub fn new(db: PgPool, redis: redis::Client, overrides: BTreeMap<String, String>) -> Self {
+        Self {
+            db,
+            redis,
+            overrides,
+            tasks: Vec::new(),
+            instance: instance_id().to_string(),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    pub fn with_task(mut self, task: impl ScheduledTask + 'static) -> Self {
+        self.tasks.push(Arc::new(task));
+        self
+    }
+
+    /// Name recorded on the runs this instance fires.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = instance.into();
+        self
+    }
+
+    /// Hold off firing while database or host health is down.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Ticks passed before the scheduler started are not caught up. On
+    /// shutdown, waits for the runs already fired so none is left recorded
+    /// as running.
+    pub async fn start(&self) {
+        let mut since = Utc::now();
+        let mut running: Vec<JoinHandle<()>> = Vec::new();
+        while self.throttle.pace(WORKER_NAME, POLL_INTERVAL).await {
+            running.retain(|run| !run.is_finished());
+            let now = Utc::now();
+            match self.tick(since, now).await {
+                Ok(fired) => {
+                    since = now;
+                    running.extend(fired);
+                }
+                Err(e) => tracing::warn!("Scheduler tick failed: {}", e),
+            }
+        }
+        join_all(running).await;
+    }
+
+    /// Fires every enabled task with a tick in `(since, until]` whose lock
+    /// this instance takes, returning the spawned runs.
+    pub async fn tick(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<JoinHandle<()>>, sqlx::Error> {
+        let names: Vec<&str> = self.tasks.iter().map(|t| t.name()).collect();
+        let schedules = effective_schedules(&self.db, &self.overrides, &names).await?;
+
+        let mut runs = Vec::new();
+        for (task, schedule) in self.tasks.iter().zip(schedules) {
+            let Some(cron) = schedule.cron.filter(|_| schedule.enabled) else {
+                continue;
+            };
+            let Some(due) = cron.latest_between(since, until) else {
+                continue;
+            };
+            match self.take_tick(task.name(), due).await {
+                Ok(true) => {
+                    let scheduler = self.clone();
+                    let task = task.clone();
+                    runs.push(tokio::spawn(async move {
+                        scheduler.fire(task, due).await;
+                    }));
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!(task = task.name(), "Failed to take tick lock: {}", e),
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Whether this instance is the one to fire `name` at `due`.
+    async fn take_tick(&self, name: &str, due: DateTime<Utc>) -> redis::RedisResult<bool> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        redis::cmd("SET")
+            .arg(format!("schedule_lock:{}:{}", name, due.timestamp()))
+            .arg(&self.instance)
+            .arg("NX")
+            .arg("EX")
+            .arg(TICK_LOCK_TTL_SECONDS)
+            .query_async::<Option<String>>(&mut conn)
+            .await
+            .map(|v| v.is_some())
+    }
+
+    async fn fire(&self, task: Arc<dyn ScheduledTask>, due: DateTime<Utc>) {
+        let name = task.name();
+        let span = tracing::info_span!("scheduled_task", task = name, scheduled_for = %due);
+        if let Err(e) = self.run_recorded(task, due).instrument(span).await {
+            tracing::error!(task = name, "Failed to record scheduled run: {}", e);
+        }
+    }
+
+    async fn run_recorded(
+        &self,
+        task: Arc<dyn ScheduledTask>,
+        due: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        let name = task.name();
+        let run_id = Uuid::now_v7();
+
+        // A slow run, a backup say, must not overlap its next tick.
+        let running: bool = sqlx::query_scalar(
+            "SELECT EXISTS (
+                 SELECT 1 FROM scheduled_task_runs
+                 WHERE name = $1 AND status = 'RUNNING'
+                   AND started_at > NOW() - make_interval(hours => $2)
+             )",
+        )
+        .bind(name)
+        .bind(STALE_RUN_HOURS)
+        .fetch_one(&self.db)
+        .await?;
+        if running {
+            sqlx::query(
+                "INSERT INTO scheduled_task_runs
+                     (id, name, scheduled_for, instance, status, error, finished_at)
+                 VALUES ($1, $2, $3, $4, 'SKIPPED', 'previous run still in progress', NOW())
+                 ON CONFLICT (name, scheduled_for) DO NOTHING",
+            )
+            .bind(run_id)
+            .bind(name)
+            .bind(due)
+            .bind(&self.instance)
+            .execute(&self.db)
+            .await?;
+            tracing::warn!("Skipped scheduled run; previous run still in progress");
+            return Ok(());
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO scheduled_task_runs (id, name, scheduled_for, instance)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (name, scheduled_for) DO NOTHING",
+        )
+        .bind(run_id)
+        .bind(name)
+        .bind(due)
+        .bind(&self.instance)
+        .execute(&self.db)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let error = match AssertUnwindSafe(task.run()).catch_unwind().await {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e.to_string()),
+            Err(_) => Some("task panicked".to_string()),
+        };
+        match &error {
+            None => tracing::info!("Scheduled run succeeded"),
+            Some(e) => tracing::error!("Scheduled run failed: {}", e),
+        }
+        sqlx::query(
+            "UPDATE scheduled_task_runs
+             SET status = CASE WHEN $2::text IS NULL THEN 'SUCCEEDED' ELSE 'FAILED' END,
+                 error = $2, finished_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(run_id)
+        .bind(error)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(cron: &str, after: &str) -> DateTime<Utc> {
+        CronSchedule::parse(cron)
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+    }
+
+    #[test]
+    fn next_tick_follows_each_field() {
+        assert_eq!(
+            next("* * * * *", "2026-03-01T10:15:30Z"),
+            at("2026-03-01T10:16:00Z")
+        );
+        assert_eq!(
+            next("0 * * * *", "2026-03-01T10:00:00Z"),
+            at("2026-03-01T11:00:00Z")
+        );
+        assert_eq!(
+            next("30 */6 * * *", "2026-03-01T07:00:00Z"),
+            at("2026-03-01T12:30:00Z")
+        );
+        assert_eq!(
+            next("0 3 * * *", "2026-12-31T04:00:00Z"),
+            at("2027-01-01T03:00:00Z")
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2026-03-01T00:00:00Z"),
+            at("2028-02-29T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn weekdays_and_names() {
+        // 2026-03-01 is a Sunday.
+        assert_eq!(
+            next("0 9 * * mon-fri", "2026-03-01T00:00:00Z"),
+            at("2026-03-02T09:00:00Z")
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2026-03-02T00:00:00Z"),
+            at("2026-03-08T00:00:00Z")
+        );
+        assert_eq!(
+            next("@monthly", "2026-03-15T00:00:00Z"),
+            at("2026-04-01T00:00:00Z")
+        );
+        assert_eq!(
+            next("0 0 1 jan,jul *", "2026-03-15T00:00:00Z"),
+            at("2026-07-01T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 15th, or any Monday.
+        let cron = CronSchedule::parse("0 0 15 * 1").unwrap();
+        assert_eq!(
+            cron.next_after(at("2026-03-01T00:00:00Z")),
+            Some(at("2026-03-02T00:00:00Z"))
+        );
+        assert_eq!(
+            cron.next_after(at("2026-03-09T00:00:00Z")),
+            Some(at("2026-03-15T00:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn impossible_dates_never_fire() {
+        let cron = CronSchedule::parse("0 0 30 2 *").unwrap();
+        assert_eq!(cron.next_after(at("2026-01-01T00:00:00Z")), None);
+    }
+
+    #[test]
+    fn missed_ticks_collapse_into_the_latest() {
+        let cron = CronSchedule::parse("*/10 * * * *").unwrap();
+        assert_eq!(
+            cron.latest_between(at("2026-03-01T10:00:00Z"), at("2026-03-01T10:35:00Z")),
+            Some(at("2026-03-01T10:30:00Z"))
+        );
+        assert_eq!(
+            cron.latest_between(at("2026-03-01T10:00:00Z"), at("2026-03-01T10:09:59Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for bad in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * foo *",
+        ] {
+            assert!(CronSchedule::parse(bad).is_err(), "{bad:?} parsed");
+        }
+    }
+
+    #[test]
+    fn overrides_are_validated() {
+        let overrides =
+            parse_schedule_overrides("backup_export=0 4 * * *; analytics_rollup=@hourly").unwrap();
+        assert_eq!(overrides["backup_export"], "0 4 * * *");
+        assert_eq!(overrides["analytics_rollup"], "@hourly");
+        assert!(parse_schedule_overrides("backup_export").is_err());
+        assert!(parse_schedule_overrides("a=* * * * *;a=0 * * * *").is_err());
+        assert!(parse_schedule_overrides("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn builtin_schedules_parse() {
+        for schedule in SCHEDULES {
+            assert!(CronSchedule::parse(schedule.default_cron).is_ok());
+        }
+    }
+}
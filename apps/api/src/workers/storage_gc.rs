@@ -0,0 +1,242 @@
+use crate::infrastructure::{
+    monitoring::throttle::WorkerThrottle,
+    storage::{content_addressed, traits::StorageService},
+};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "storage_gc";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Jobs claimed per batch.
+const BATCH_SIZE: i64 = 50;
+
+/// How long a claimed job is left to whoever claimed it. A job whose runner
+/// died (e.g. the API restarted right after a delete committed) is picked up
+/// again once this runs out.
+const CLAIM_LEASE_SECONDS: f64 = 600.0;
+
+/// Failed attempts after which a job is logged as an error rather than a
+/// warning. It keeps being retried at the longest delay.
+const STUCK_AFTER_ATTEMPTS: i32 = 8;
+
+/// Completed jobs are kept this long for auditing.
+const COMPLETED_RETENTION_DAYS: i32 = 30;
+
+/// Storage objects left to delete after their record was removed.
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageCleanupJob {
+    pub id: Uuid,
+    pub lettering_id: Option<Uuid>,
+    pub object_keys: Vec<String>,
+    pub attempts: i32,
+}
+
+/// Delay before retrying a job that has failed `attempts` times: one minute,
+/// doubling up to six hours.
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 10) as u32;
+    Duration::from_secs((60u64 << exponent).min(6 * 3_600))
+}
+
+/// Records `keys` for deletion as part of `tx`. The job comes back claimed
+/// by the caller, who should run it with `run_cleanup` once `tx` commits;
+/// if that never happens, the worker takes over when the claim lapses.
+pub async fn schedule_cleanup(
+    tx: &mut Transaction<'_, Postgres>,
+    lettering_id: Option<Uuid>,
+    mut keys: Vec<String>,
+) -> Result<StorageCleanupJob, sqlx::Error> {
+    let mut seen = HashSet::new();
+    keys.retain(|key| seen.insert(key.clone()));
+    sqlx::query_as::<_, StorageCleanupJob>(
+        "INSERT INTO storage_cleanup_jobs (id, lettering_id, object_keys, next_attempt_at)
+         VALUES ($1, $2, $3, NOW() + make_interval(secs => $4))
+         RETURNING id, lettering_id, object_keys, attempts",
+    )
+    .bind(Uuid::now_v7())
+    .bind(lettering_id)
+    .bind(&keys)
+    .bind(CLAIM_LEASE_SECONDS)
+    .fetch_one(&mut **tx)
+    .await
+}
+
+/// Deletes a claimed job's objects. Returns whether all of them are gone;
+/// otherwise the job keeps the keys that failed and is retried later.
+/// Content-addressed objects referenced again since the job was scheduled
+/// are left in place. Each key is checked and deleted under its
+/// [`content_addressed::lock_key`], so an identical upload can't take a
+/// reference between the check and the delete.
+pub async fn run_cleanup(
+    db: &PgPool,
+    storage: &dyn StorageService,
+    job: &StorageCleanupJob,
+) -> Result<bool, sqlx::Error> {
+    let mut remaining = Vec::new();
+    let mut last_error = None;
+    for key in &job.object_keys {
+        let mut tx = db.begin().await?;
+        content_addressed::lock_key(&mut tx, key).await?;
+        if content_addressed::referenced(&mut tx, key).await? {
+            continue;
+        }
+        match storage.delete(key).await {
+            Ok(()) => {
+                content_addressed::forget(&mut tx, key).await?;
+                tx.commit().await?;
+            }
+            Err(e) => {
+                remaining.push(key.clone());
+                last_error = Some(e.to_string());
+            }
+        }
+    }
+
+    let Some(error) = last_error else {
+        sqlx::query(
+            "UPDATE storage_cleanup_jobs
+             SET object_keys = '{}', completed_at = NOW(), last_error = NULL
+             WHERE id = $1",
+        )
+        .bind(job.id)
+        .execute(db)
+        .await?;
+        return Ok(true);
+    };
+
+    let attempts = job.attempts + 1;
+    if attempts >= STUCK_AFTER_ATTEMPTS {
+        tracing::error!(
+            job_id = %job.id,
+            lettering_id = ?job.lettering_id,
+            attempts,
+            remaining = remaining.len(),
+            "Storage cleanup keeps failing: {}",
+            error
+        );
+    } else {
+        tracing::warn!(
+            job_id = %job.id,
+            attempts,
+            remaining = remaining.len(),
+            "Storage cleanup failed, will retry: {}",
+            error
+        );
+    }
+    sqlx::query(
+        "UPDATE storage_cleanup_jobs
+         SET object_keys = $2, attempts = $3, last_error = $4,
+             next_attempt_at = NOW() + make_interval(secs => $5)
+         WHERE id = $1",
+    )
+    .bind(job.id)
+    .bind(&remaining)
+    .bind(attempts)
+    .bind(&error)
+    .bind(retry_delay(attempts).as_secs_f64())
+    .execute(db)
+    .await?;
+    Ok(false)
+}
+
+/// Retries storage cleanup jobs that failed or were never run, and prunes
+/// old completed ones.
+pub struct StorageGcWorker {
+    db: PgPool,
+    storage: Arc<dyn StorageService>,
+    throttle: WorkerThrottle,
+}
+
+impl StorageGcWorker {
+    pub fn new(db: PgPool, storage: Arc<dyn StorageService>) -> Self {
+        Self {
+            db,
+            storage,
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok((completed, failed)) if completed + failed > 0 => {
+                    tracing::info!(completed, failed, "Storage cleanup pass finished");
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Storage cleanup pass failed: {}", e),
+            }
+            if !self.throttle.pace(WORKER_NAME, POLL_INTERVAL).await {
+                break;
+            }
+        }
+    }
+
+    /// Runs every due job and returns how many completed and how many still
+    /// have objects left.
+    pub async fn run_once(&self) -> Result<(usize, usize), sqlx::Error> {
+        let (mut completed, mut failed) = (0, 0);
+        loop {
+            let jobs = sqlx::query_as::<_, StorageCleanupJob>(
+                "UPDATE storage_cleanup_jobs
+                 SET next_attempt_at = NOW() + make_interval(secs => $2)
+                 WHERE id IN (
+                     SELECT id FROM storage_cleanup_jobs
+                     WHERE completed_at IS NULL AND next_attempt_at <= NOW()
+                     ORDER BY next_attempt_at
+                     LIMIT $1
+                     FOR UPDATE SKIP LOCKED
+                 )
+                 RETURNING id, lettering_id, object_keys, attempts",
+            )
+            .bind(BATCH_SIZE)
+            .bind(CLAIM_LEASE_SECONDS)
+            .fetch_all(&self.db)
+            .await?;
+
+            for job in &jobs {
+                if run_cleanup(&self.db, self.storage.as_ref(), job).await? {
+                    completed += 1;
+                } else {
+                    failed += 1;
+                }
+            }
+            if (jobs.len() as i64) < BATCH_SIZE {
+                break;
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                break;
+            }
+        }
+
+        sqlx::query(
+            "DELETE FROM storage_cleanup_jobs
+             WHERE completed_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(COMPLETED_RETENTION_DAYS)
+        .execute(&self.db)
+        .await?;
+        Ok((completed, failed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_and_caps() {
+        assert_eq!(retry_delay(1), Duration::from_secs(60));
+        assert_eq!(retry_delay(2), Duration::from_secs(120));
+        assert_eq!(retry_delay(5), Duration::from_secs(960));
+        assert_eq!(retry_delay(30), Duration::from_secs(6 * 3_600));
+    }
+}
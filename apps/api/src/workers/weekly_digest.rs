@@ -0,0 +1,396 @@
+use crate::infrastructure::{
+    email::http_mailer::{HttpMailer, OutgoingEmail},
+    monitoring::throttle::WorkerThrottle,
+    repositories::region_policy::{PUBLIC_LETTERING_JOINS, push_public_filter},
+};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const WORKER_NAME: &str = "weekly_digest";
+
+/// Subscriptions evaluated per batch.
+const BATCH_SIZE: i64 = 100;
+
+/// New uploads within this distance of one of the contributor's own count as
+/// nearby.
+const NEARBY_RADIUS_METERS: f64 = 2_000.0;
+
+/// Nearby uploads listed in one digest.
+const MAX_NEARBY: i64 = 5;
+
+/// A subscription whose digest is due: Monday at or after its send hour in
+/// its time zone, and none sent in the last six days.
+#[derive(Debug, FromRow)]
+struct DueSubscription {
+    user_id: Uuid,
+    email: String,
+    display_name: Option<String>,
+    locale: String,
+    unsubscribe_token: Uuid,
+    /// Start of the digest's window: the last digest, or a week ago.
+    since: DateTime<Utc>,
+}
+
+/// What happened to a contributor's uploads since their last digest.
+#[derive(Debug, Default, Clone, FromRow)]
+pub struct DigestCounts {
+    pub likes: i64,
+    pub comments: i64,
+    pub approved: i64,
+    pub rejected: i64,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct NearbyUpload {
+    pub id: Uuid,
+    pub city_name: String,
+    pub contributor_tag: String,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Digest {
+    pub counts: DigestCounts,
+    pub nearby: Vec<NearbyUpload>,
+}
+
+impl Digest {
+    /// Nothing worth an email.
+    pub fn is_empty(&self) -> bool {
+        let c = &self.counts;
+        c.likes == 0
+            && c.comments == 0
+            && c.approved == 0
+            && c.rejected == 0
+            && self.nearby.is_empty()
+    }
+}
+
+/// Fixed text of a digest in one language.
+struct DigestStrings {
+    subject: &'static str,
+    greeting: &'static str,
+    likes: &'static str,
+    comments: &'static str,
+    approved: &'static str,
+    rejected: &'static str,
+    nearby: &'static str,
+    unsubscribe: &'static str,
+}
+
+const EN: DigestStrings = DigestStrings {
+    subject: "Your week on Through Your Letters",
+    greeting: "Hello",
+    likes: "New likes on your uploads",
+    comments: "New comments on your uploads",
+    approved: "Uploads approved",
+    rejected: "Uploads not approved",
+    nearby: "New letterings near yours",
+    unsubscribe: "To stop these weekly emails, open",
+};
+
+const HI: DigestStrings = DigestStrings {
+    subject: "Through Your Letters पर आपका सप्ताह",
+    greeting: "नमस्ते",
+    likes: "आपकी अपलोड पर नई पसंद",
+    comments: "आपकी अपलोड पर नई टिप्पणियाँ",
+    approved: "स्वीकृत अपलोड",
+    rejected: "अस्वीकृत अपलोड",
+    nearby: "आपकी अपलोड के पास नई लेटरिंग",
+    unsubscribe: "ये साप्ताहिक ईमेल बंद करने के लिए खोलें",
+};
+
+/// Digest text for `locale`; languages without a translation get English.
+fn strings(locale: &str) -> &'static DigestStrings {
+    match locale.split('-').next().unwrap_or_default() {
+        "hi" => &HI,
+        _ => &EN,
+    }
+}
+
+/// Subject and plain-text body of a digest. Counts are listed as
+/// `label: n` so no language needs plural rules; zero counts are left out.
+pub fn render_digest(
+    digest: &Digest,
+    display_name: Option<&str>,
+    locale: &str,
+    base_url: &str,
+    unsubscribe_url: &str,
+) -> (String, String) {
+    let s = strings(locale);
+    let mut text = match display_name.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => format!("{} {},\n\n", s.greeting, name),
+        None => format!("{},\n\n", s.greeting),
+    };
+
+    let c = &digest.counts;
+    for (label, count) in [
+        (s.likes, c.likes),
+        (s.comments, c.comments),
+        (s.approved, c.approved),
+        (s.rejected, c.rejected),
+    ] {
+        if count > 0 {
+            text.push_str(&format!("{}: {}\n", label, count));
+        }
+    }
+
+    if !digest.nearby.is_empty() {
+        text.push_str(&format!("\n{}:\n", s.nearby));
+        for upload in &digest.nearby {
+            text.push_str(&format!(
+                "- {} ({}): {}/lettering/{}\n",
+                upload.contributor_tag, upload.city_name, base_url, upload.id
+            ));
+        }
+    }
+
+    text.push_str(&format!("\n--\n{} {}\n", s.unsubscribe, unsubscribe_url));
+    (s.subject.to_string(), text)
+}
+
+/// Sends each opted-in contributor a weekly email digest of likes and
+/// comments on their uploads, moderation outcomes and new uploads near
+/// theirs. Digests go out on Monday at the subscriber's chosen hour in their
+/// own time zone and language. Empty weeks are skipped without an email; a
+/// failed send is retried on the next pass.
+pub struct WeeklyDigestWorker {
+    db: PgPool,
+    mailer: Arc<HttpMailer>,
+    public_base_url: Option<String>,
+    interval_seconds: u64,
+    throttle: WorkerThrottle,
+}
+
+impl WeeklyDigestWorker {
+    pub fn new(
+        db: PgPool,
+        mailer: Arc<HttpMailer>,
+        public_base_url: Option<String>,
+        interval_seconds: u64,
+    ) -> Self {
+        Self {
+            db,
+            mailer,
+            public_base_url,
+            interval_seconds: interval_seconds.max(60),
+            throttle: WorkerThrottle::unthrottled(),
+        }
+    }
+
+    /// Slow down or pause between passes when database or host health drops.
+    pub fn with_throttle(mut self, throttle: WorkerThrottle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    pub async fn start(&self) {
+        loop {
+            match self.run_once().await {
+                Ok(sent) if sent > 0 => tracing::info!(sent, "Weekly digests sent"),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Weekly digest pass failed: {}", e),
+            }
+            if !self
+                .throttle
+                .pace(WORKER_NAME, Duration::from_secs(self.interval_seconds))
+                .await
+            {
+                break;
+            }
+        }
+    }
+
+    /// Handles every due subscription and returns how many emails were sent.
+    pub async fn run_once(&self) -> Result<usize, sqlx::Error> {
+        let base = self
+            .public_base_url
+            .as_deref()
+            .unwrap_or_default()
+            .trim_end_matches('/')
+            .to_string();
+        let mut sent = 0;
+        let mut failed: Vec<Uuid> = Vec::new();
+        loop {
+            let due = sqlx::query_as::<_, DueSubscription>(
+                "SELECT s.user_id, u.email, u.display_name, s.locale, s.unsubscribe_token,
+                        COALESCE(s.last_sent_at, NOW() - INTERVAL '7 days') AS since
+                 FROM digest_subscriptions s
+                 JOIN users u ON u.id = s.user_id
+                 WHERE s.enabled
+                   AND EXTRACT(ISODOW FROM NOW() AT TIME ZONE s.timezone) = 1
+                   AND EXTRACT(HOUR FROM NOW() AT TIME ZONE s.timezone) >= s.send_hour
+                   AND (s.last_sent_at IS NULL OR s.last_sent_at < NOW() - INTERVAL '6 days')
+                   AND NOT (s.user_id = ANY($2))
+                 ORDER BY s.last_sent_at NULLS FIRST
+                 LIMIT $1",
+            )
+            .bind(BATCH_SIZE)
+            .bind(&failed)
+            .fetch_all(&self.db)
+            .await?;
+
+            for subscription in &due {
+                match self.deliver(subscription, &base).await {
+                    Ok(true) => sent += 1,
+                    Ok(false) => {}
+                    Err(e) => {
+                        tracing::warn!(user_id = %subscription.user_id, "Weekly digest not sent: {}", e);
+                        failed.push(subscription.user_id);
+                    }
+                }
+            }
+            if (due.len() as i64) < BATCH_SIZE {
+                return Ok(sent);
+            }
+            if !self.throttle.between_batches(WORKER_NAME).await {
+                return Ok(sent);
+            }
+        }
+    }
+
+    /// Compiles and sends one digest, then marks it sent. Returns whether an
+    /// email went out.
+    async fn deliver(&self, subscription: &DueSubscription, base: &str) -> anyhow::Result<bool> {
+        let digest = self.compile(subscription).await?;
+        let has_news = !digest.is_empty();
+        if has_news {
+            let unsubscribe_url = format!(
+                "{}/api/v1/digest/unsubscribe?token={}",
+                base, subscription.unsubscribe_token
+            );
+            let (subject, text) = render_digest(
+                &digest,
+                subscription.display_name.as_deref(),
+                &subscription.locale,
+                base,
+                &unsubscribe_url,
+            );
+            self.mailer
+                .send(&OutgoingEmail {
+                    to: subscription.email.clone(),
+                    subject,
+                    text,
+                    unsubscribe_url: Some(unsubscribe_url),
+                })
+                .await?;
+        }
+
+        sqlx::query("UPDATE digest_subscriptions SET last_sent_at = NOW() WHERE user_id = $1")
+            .bind(subscription.user_id)
+            .execute(&self.db)
+            .await?;
+        Ok(has_news)
+    }
+
+    /// Activity on the subscriber's uploads since `since`, and public uploads
+    /// by others near them.
+    async fn compile(&self, subscription: &DueSubscription) -> Result<Digest, sqlx::Error> {
+        let counts = sqlx::query_as::<_, DigestCounts>(
+            "SELECT
+                 (SELECT COUNT(*) FROM likes k
+                  JOIN letterings l ON l.id = k.lettering_id
+                  WHERE l.user_id = $1 AND k.created_at >= $2) AS likes,
+                 (SELECT COUNT(*) FROM comments cm
+                  JOIN letterings l ON l.id = cm.lettering_id
+                  WHERE l.user_id = $1 AND cm.created_at >= $2
+                    AND cm.user_id IS DISTINCT FROM $1
+                    AND cm.status = 'VISIBLE' AND cm.deleted_at IS NULL) AS comments,
+                 (SELECT COUNT(*) FROM lettering_status_history h
+                  JOIN letterings l ON l.id = h.lettering_id
+                  WHERE l.user_id = $1 AND h.created_at >= $2
+                    AND h.to_status = 'APPROVED') AS approved,
+                 (SELECT COUNT(*) FROM lettering_status_history h
+                  JOIN letterings l ON l.id = h.lettering_id
+                  WHERE l.user_id = $1 AND h.created_at >= $2
+                    AND h.to_status = 'REJECTED') AS rejected",
+        )
+        .bind(subscription.user_id)
+        .bind(subscription.since)
+        .fetch_one(&self.db)
+        .await?;
+
+        let mut locales = vec![subscription.locale.clone()];
+        if subscription.locale != "en" {
+            locales.push("en".to_string());
+        }
+        let mut qb =
+            QueryBuilder::<Postgres>::new("SELECT l.id, localized_city_name(c.id, c.name, ");
+        qb.push_bind(locales)
+            .push(") AS city_name, l.contributor_tag FROM letterings l ")
+            .push(PUBLIC_LETTERING_JOINS)
+            .push(" WHERE ");
+        // An email can't carry an age acknowledgment.
+        push_public_filter(&mut qb, false);
+        qb.push(" AND l.created_at >= ")
+            .push_bind(subscription.since)
+            .push(" AND l.user_id IS DISTINCT FROM ")
+            .push_bind(subscription.user_id)
+            .push(
+                " AND EXISTS (
+                     SELECT 1 FROM letterings mine
+                     WHERE mine.user_id = ",
+            )
+            .push_bind(subscription.user_id)
+            .push(" AND ST_DWithin(l.location, mine.location, ")
+            .push_bind(NEARBY_RADIUS_METERS)
+            .push(")) ORDER BY l.created_at DESC LIMIT ")
+            .push_bind(MAX_NEARBY);
+        let nearby = qb.build_query_as().fetch_all(&self.db).await?;
+
+        Ok(Digest { counts, nearby })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest() -> Digest {
+        Digest {
+            counts: DigestCounts {
+                likes: 4,
+                comments: 0,
+                approved: 1,
+                rejected: 0,
+            },
+            nearby: vec![NearbyUpload {
+                id: Uuid::nil(),
+                city_name: "Bengaluru".to_string(),
+                contributor_tag: "ghost_signs".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn renders_non_zero_counts_nearby_uploads_and_unsubscribe_link() {
+        let (subject, text) = render_digest(
+            &digest(),
+            Some("Asha"),
+            "en",
+            "https://example.org",
+            "https://example.org/api/v1/digest/unsubscribe?token=t",
+        );
+        assert_eq!(subject, EN.subject);
+        assert!(text.starts_with("Hello Asha,"));
+        assert!(text.contains("New likes on your uploads: 4\n"));
+        assert!(text.contains("Uploads approved: 1\n"));
+        assert!(!text.contains(EN.comments));
+        assert!(text.contains(&format!(
+            "- ghost_signs (Bengaluru): https://example.org/lettering/{}",
+            Uuid::nil()
+        )));
+        assert!(text.ends_with("https://example.org/api/v1/digest/unsubscribe?token=t\n"));
+    }
+
+    #[test]
+    fn falls_back_to_english_for_untranslated_locales() {
+        let (subject, _) = render_digest(&digest(), None, "hi-in", "", "");
+        assert_eq!(subject, HI.subject);
+        let (subject, text) = render_digest(&digest(), None, "kn", "", "");
+        assert_eq!(subject, EN.subject);
+        assert!(text.starts_with("Hello,"));
+        assert!(Digest::default().is_empty());
+    }
+}
@@ -1,8 +1,50 @@
 #[path = "integration/helpers.rs"]
 mod helpers;
+#[path = "integration/test_activity_feed.rs"]
+mod test_activity_feed;
+#[path = "integration/test_analytics_rollups.rs"]
+mod test_analytics_rollups;
+#[path = "integration/test_auto_approve_preview.rs"]
+mod test_auto_approve_preview;
+#[path = "integration/test_bookmarks.rs"]
+mod test_bookmarks;
+#[path = "integration/test_collections.rs"]
+mod test_collections;
+#[path = "integration/test_comment_moderation.rs"]
+mod test_comment_moderation;
+#[path = "integration/test_day_boundaries.rs"]
+mod test_day_boundaries;
+#[path = "integration/test_digest.rs"]
+mod test_digest;
+#[path = "integration/test_escalation.rs"]
+mod test_escalation;
+#[path = "integration/test_etags.rs"]
+mod test_etags;
+#[path = "integration/test_follows.rs"]
+mod test_follows;
 #[path = "integration/test_gallery.rs"]
 mod test_gallery;
+#[path = "integration/test_hot_cache.rs"]
+mod test_hot_cache;
+#[path = "integration/test_job_queue.rs"]
+mod test_job_queue;
+#[path = "integration/test_leader_election.rs"]
+mod test_leader_election;
+#[path = "integration/test_leaderboards.rs"]
+mod test_leaderboards;
+#[path = "integration/test_moderation_sources.rs"]
+mod test_moderation_sources;
+#[path = "integration/test_private_storage.rs"]
+mod test_private_storage;
+#[path = "integration/test_region_policy.rs"]
+mod test_region_policy;
+#[path = "integration/test_scheduler.rs"]
+mod test_scheduler;
 #[path = "integration/test_smoke_flows.rs"]
 mod test_smoke_flows;
+#[path = "integration/test_system_info.rs"]
+mod test_system_info;
 #[path = "integration/test_upload.rs"]
 mod test_upload;
+#[path = "integration/test_worker_heartbeats.rs"]
+mod test_worker_heartbeats;
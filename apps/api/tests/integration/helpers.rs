@@ -1,32 +1,46 @@
 use api::{
-    config::Config,
+    config::{
+        AttestationMode, AutoApproveExclusion, Config, ExecutionProviderKind, IpAnonymizationMode,
+        QuotaWindow,
+    },
     infrastructure::{
+        cache::{
+            invalidation::CacheInvalidator, redis_cache::RedisCache, tiered_cache::TieredCache,
+        },
+        cdn::cloudflare_purge::CloudflarePurger,
         database::pool::create_pool,
-        ml::traits::{MlService, StyleClassification, TextDetectionResult},
-        queue::redis_queue::RedisQueue,
+        geocoding::ip_geolocation::IpGeolocator,
+        monitoring::PerformanceMonitor,
+        ml::{
+            onnx_text_detector::OnnxTextDetector,
+            tesseract_service::TesseractService,
+            traits::{MlService, StyleClassification, TextDetectionResult},
+        },
+        queue::{jobs::JobRunner, redis_queue::RedisQueue},
         repositories::{
             sqlx_lettering_repository::SqlxLetteringRepository,
             sqlx_social_repository::SqlxSocialRepository,
         },
-        security::virus_scanner::VirusScanner,
-        storage::traits::StorageService,
+        security::{app_attestation::AppAttestationVerifier, virus_scanner::VirusScanner},
+        storage::traits::{ChunkedUpload, StorageService},
     },
-    presentation::http::{routes::create_router, state::AppState},
+    presentation::http::{handlers::ws::WsDrain, routes::create_router, state::AppState},
+    workers::notification_delivery::NotificationDelivery,
 };
 use async_trait::async_trait;
 use axum::{
     Router,
     body::{Body, to_bytes},
-    http::{Request, StatusCode},
+    http::{Request, StatusCode, header},
 };
 use serde::de::DeserializeOwned;
-use std::{io::Cursor, sync::Arc};
+use std::{io::Cursor, sync::Arc, time::Duration};
 use tokio::sync::broadcast;
 use tower::ServiceExt;
 use uuid::Uuid;
 
 #[derive(Clone)]
-struct TestStorage;
+pub struct TestStorage;
 
 #[async_trait]
 impl StorageService for TestStorage {
@@ -39,13 +53,53 @@ impl StorageService for TestStorage {
         Ok(format!("https://test-storage.local/{}", key))
     }
 
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        _content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        Ok(Box::new(TestChunkedUpload {
+            url: format!("https://test-storage.local/{}", key),
+        }))
+    }
+
     async fn delete(&self, _key: &str) -> anyhow::Result<()> {
         Ok(())
     }
 
+    async fn download(&self, _key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(Some(Vec::new()))
+    }
+
     fn get_url(&self, key: &str) -> String {
         format!("https://test-storage.local/{}", key)
     }
+
+    async fn signed_url(&self, key: &str, _expires_in: Duration) -> anyhow::Result<String> {
+        Ok(format!("https://test-storage.local/{}?signature=test", key))
+    }
+
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        url.strip_prefix("https://test-storage.local/")
+            .map(str::to_string)
+    }
+}
+
+struct TestChunkedUpload {
+    url: String,
+}
+
+#[async_trait]
+impl ChunkedUpload for TestChunkedUpload {
+    async fn write(&mut self, _chunk: &[u8]) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn finish(self: Box<Self>) -> anyhow::Result<String> {
+        Ok(self.url)
+    }
+
+    async fn abort(self: Box<Self>) {}
 }
 
 #[derive(Clone)]
@@ -75,6 +129,8 @@ impl MlService for TestMlService {
 
 pub struct TestApp {
     pub app: Router,
+    pub db: sqlx::PgPool,
+    pub queue: Arc<RedisQueue>,
     pub admin_email: String,
     pub admin_password: String,
 }
@@ -85,6 +141,11 @@ fn build_config(admin_password_hash: String, database_url: String) -> Config {
         database_max_connections: 5,
         redis_url: std::env::var("REDIS_URL")
             .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+        local_cache_max_entries: 1000,
+        local_cache_ttl_seconds: 30,
+        not_found_cache_ttl_seconds: 30,
+        response_cache_ttls: Default::default(),
+        cache_warm_interval_seconds: 0,
         r2_access_key_id: "test".to_string(),
         r2_secret_access_key: "test".to_string(),
         r2_endpoint: "https://test.r2.cloudflarestorage.com".to_string(),
@@ -92,6 +153,20 @@ fn build_config(admin_password_hash: String, database_url: String) -> Config {
         r2_force_path_style: false,
         r2_bucket_name: "test".to_string(),
         r2_public_url: "https://test.r2.dev".to_string(),
+        r2_private_bucket_name: "test-private".to_string(),
+        cloudflare_zone_id: None,
+        cloudflare_api_token: None,
+        email_api_url: None,
+        email_api_key: None,
+        email_from: None,
+        signed_url_ttl_seconds: 900,
+        public_base_url: None,
+        backup_r2_bucket_name: None,
+        backup_r2_endpoint: None,
+        backup_r2_region: None,
+        backup_r2_access_key_id: None,
+        backup_r2_secret_access_key: None,
+        storage_regions: Vec::new(),
         host: "127.0.0.1".to_string(),
         port: 0,
         jwt_secret: "test-jwt-secret".to_string(),
@@ -99,15 +174,105 @@ fn build_config(admin_password_hash: String, database_url: String) -> Config {
         admin_password_hash,
         city_discovery_user_agent: None,
         huggingface_token: None,
+        huggingface_daily_call_budget: 1000,
+        huggingface_breaker_failure_threshold: 5,
+        huggingface_breaker_cool_down_seconds: 60,
         enable_ml_processing: false,
         ml_model_path: "./models/text_detector.onnx".to_string(),
+        ml_shadow_model_path: None,
+        ml_golden_image_path: None,
+        enable_ml_model_watch: false,
+        ml_model_watch_interval_seconds: 30,
+        ml_text_confidence_threshold: 0.5,
+        ml_style_confidence_threshold: 0.5,
+        ml_style_model_path: "./models/style_classifier.onnx".to_string(),
+        ml_style_min_confidence: 0.4,
+        ml_script_confidence_threshold: 0.5,
+        ml_batch_size: 8,
+        ml_max_attempts: 5,
+        ml_concurrency: 1,
+        ml_max_queue_depth: 500,
+        job_idempotency_window_seconds: 3600,
+        ml_reprocess_rate_per_minute: 60,
+        ml_trusted_contributor_approvals: 20,
+        ml_execution_providers: vec![ExecutionProviderKind::Cpu],
+        ml_gpu_device_id: 0,
+        enable_tesseract_fallback: false,
+        tesseract_languages: "eng".to_string(),
         enable_virus_scan: false,
+        near_duplicate_max_distance: 6,
         rate_limit_uploads_per_ip: 1000,
+        rate_limit_analytics_events_per_ip: 1000,
+        upload_daily_limit: 0,
+        upload_quota_window: QuotaWindow::Rolling,
+        day_boundary_timezone: "UTC".to_string(),
+        app_attestation_ios: AttestationMode::Off,
+        app_attestation_android: AttestationMode::Off,
+        app_attestation_web: AttestationMode::Off,
+        turnstile_secret_key: None,
+        apple_devicecheck_team_id: None,
+        apple_devicecheck_key_id: None,
+        apple_devicecheck_private_key: None,
+        apple_devicecheck_development: false,
+        play_integrity_package_name: None,
+        play_integrity_service_account: None,
+        like_velocity_per_minute: 20,
+        like_velocity_per_hour: 200,
         enable_pending_auto_approve: false,
         pending_auto_approve_minutes: 30,
         pending_auto_approve_interval_seconds: 300,
         pending_auto_approve_batch_size: 50,
+        pending_auto_approve_dry_run: false,
+        pending_auto_approve_exclusions: AutoApproveExclusion::ALL.to_vec(),
+        enable_pending_escalation: false,
+        pending_escalation_hours: 24,
+        pending_escalation_interval_seconds: 900,
+        pending_escalation_reviewers: Vec::new(),
+        enable_review_priority: false,
+        review_priority_interval_seconds: 300,
+        report_auto_hide_threshold: 3.0,
+        report_auto_hide_window_hours: 72,
+        report_anonymous_weight: 0.5,
+        search_weight_text: 1.0,
+        search_weight_likes: 0.05,
+        search_weight_recency: 0.0,
+        search_recency_half_life_days: 180.0,
+        search_weight_proximity: 0.0,
+        search_proximity_scale_km: 5.0,
+        enable_integrity_verification: false,
+        integrity_verification_interval_seconds: 3600,
+        integrity_verification_sample_size: 20,
+        enable_saved_search_notifications: false,
+        saved_search_interval_seconds: 900,
+        enable_follow_notifications: false,
+        follow_notification_interval_seconds: 900,
+        enable_like_digest: false,
+        like_digest_window_minutes: 60,
+        like_digest_interval_seconds: 300,
+        enable_activity_feed: false,
+        activity_feed_interval_seconds: 30,
+        enable_weekly_digest: false,
+        weekly_digest_interval_seconds: 900,
+        ip_geo_lookup_url: None,
+        ip_geo_refresh_days: 30,
+        ip_geo_retention_days: 90,
+        enable_ip_anonymization: false,
+        ip_retention_days: 90,
+        ip_anonymization_mode: IpAnonymizationMode::Truncate,
+        ip_anonymization_interval_seconds: 86_400,
+        leader_lease_seconds: 0,
+        schedules: Default::default(),
         ignore_missing_migrations: true,
+        enforce_schema_check: true,
+        ws_drain_timeout_seconds: 10,
+        worker_drain_timeout_seconds: 30,
+        ws_reconnect_hint_ms: 1000,
+        enable_fault_injection: false,
+        fault_redis_error_percent: 0,
+        fault_storage_latency_ms: 0,
+        fault_ml_fail_every: 0,
+        synthetic_mode: false,
+        allowed_origins: Vec::new(),
     }
 }
 
@@ -146,29 +311,70 @@ pub async fn spawn_app() -> TestApp {
     migrator.run(&db).await.expect("migrations failed");
 
     let redis = redis::Client::open(config.redis_url.clone()).expect("invalid redis url");
-    let queue = Arc::new(RedisQueue::new(redis.clone()));
+    let queue = Arc::new(
+        RedisQueue::new(redis.clone())
+            .with_idempotency_window(Duration::from_secs(config.job_idempotency_window_seconds)),
+    );
     let (tx, _) = broadcast::channel(100);
+    let cache = Arc::new(RedisCache::new(redis.clone()));
+    let hot_cache = Arc::new(TieredCache::new(
+        cache.clone(),
+        redis.clone(),
+        config.local_cache_max_entries,
+        Duration::from_secs(config.local_cache_ttl_seconds),
+    ));
+    let invalidator = CacheInvalidator::new(db.clone(), hot_cache.clone());
 
     let state = AppState {
         db: db.clone(),
-        redis,
+        redis: redis.clone(),
+        cache: cache.clone(),
+        hot_cache,
         storage: Arc::new(TestStorage),
         ml_detector: Arc::new(TestMlService),
-        queue,
+        onnx_detector: Arc::new(OnnxTextDetector::new("", false, None, &[], 0).unwrap()),
+        ocr: Arc::new(TesseractService::new(false, "eng")),
+        queue: queue.clone(),
         virus_scanner: Arc::new(VirusScanner::new(false, None, None)),
+        attestation: Arc::new(AppAttestationVerifier::disabled()),
         config: config.clone(),
-        lettering_repo: Arc::new(SqlxLetteringRepository::new(db.clone())),
-        social_repo: Arc::new(SqlxSocialRepository::new(db)),
+        lettering_repo: Arc::new(
+            SqlxLetteringRepository::new(db.clone())
+                .with_invalidator(invalidator)
+                .with_negative_cache(cache.clone(), config.not_found_cache_ttl_seconds),
+        ),
+        social_repo: Arc::new(SqlxSocialRepository::new(db.clone())),
         ws_broadcaster: Arc::new(tx),
+        ws_drain: Arc::new(WsDrain::new(config.ws_reconnect_hint_ms)),
+        cdn_purger: Arc::new(CloudflarePurger::new(None, None, queue.clone())),
+        ip_geolocator: Arc::new(IpGeolocator::new(db.clone(), None, 30)),
+        performance: Arc::new(PerformanceMonitor::new()),
+        faults: None,
     };
 
     TestApp {
         app: create_router(state),
+        db,
+        queue,
         admin_email: config.admin_email,
         admin_password,
     }
 }
 
+/// Delivers every queued notification, as the notification runner would.
+pub async fn deliver_notifications(app: &TestApp) {
+    let runner = JobRunner::new(
+        app.queue.notifications().clone(),
+        NotificationDelivery::new(app.db.clone()),
+    );
+    while runner
+        .run_once()
+        .await
+        .expect("failed to deliver notifications")
+        > 0
+    {}
+}
+
 pub async fn send(app: &Router, req: Request<Body>) -> axum::response::Response {
     app.clone().oneshot(req).await.expect("request failed")
 }
@@ -208,6 +414,49 @@ pub fn unique_email(prefix: &str) -> String {
     format!("{}-{}@example.com", prefix, Uuid::now_v7())
 }
 
+/// Registers a new user and returns their token.
+pub async fn register_user_and_token(app: &Router) -> String {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::json!({
+                "email": unique_email("it"),
+                "password": "StrongerPass123!",
+                "display_name": "Integration Tester"
+            })
+            .to_string(),
+        ))
+        .expect("failed to build register request");
+
+    let res = expect_status(send(app, req).await, StatusCode::OK).await;
+    let body: serde_json::Value = read_json(res).await;
+    body["token"]
+        .as_str()
+        .expect("missing token in register response")
+        .to_string()
+}
+
+/// Signs in as the test admin and returns their token.
+pub async fn admin_token(app: &TestApp) -> String {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/admin/login")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::json!({ "email": app.admin_email, "password": app.admin_password })
+                .to_string(),
+        ))
+        .expect("failed to build admin login request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let body: serde_json::Value = read_json(res).await;
+    body["token"]
+        .as_str()
+        .expect("missing admin token")
+        .to_string()
+}
+
 pub fn tiny_png_bytes() -> Vec<u8> {
     let uuid_bytes = *Uuid::now_v7().as_bytes();
     let raw = vec![
@@ -0,0 +1,226 @@
+use super::helpers::{
+    TestApp, assert_status, expect_status, multipart_upload_body, read_json, send, spawn_app,
+    tiny_png_bytes, unique_email,
+};
+use api::workers::{activity_fanout::ActivityFanout, like_digest::LikeDigestWorker};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+/// A new account's token and id.
+async fn register(app: &TestApp, display_name: &str) -> (String, Uuid) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "email": unique_email("activity-feed-it"),
+                "password": "StrongerPass123!",
+                "display_name": display_name
+            })
+            .to_string(),
+        ))
+        .expect("failed to build register request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let body: Value = read_json(res).await;
+    let token = body["token"]
+        .as_str()
+        .expect("missing user token")
+        .to_string();
+    let user_id = Uuid::parse_str(body["user"]["id"].as_str().expect("missing user id"))
+        .expect("invalid user id");
+    (token, user_id)
+}
+
+async fn approved_lettering(app: &TestApp) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        "FeedTag",
+        "560001",
+        "Activity feed integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    let id = Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id");
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve lettering");
+    id
+}
+
+async fn insert_comment(
+    app: &TestApp,
+    lettering_id: Uuid,
+    user_id: Uuid,
+    parent_id: Option<Uuid>,
+    content: &str,
+) -> Uuid {
+    let id = Uuid::now_v7();
+    sqlx::query(
+        "INSERT INTO comments (id, lettering_id, user_id, parent_id, content)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(id)
+    .bind(lettering_id)
+    .bind(user_id)
+    .bind(parent_id)
+    .bind(content)
+    .execute(&app.db)
+    .await
+    .expect("failed to insert comment");
+    id
+}
+
+async fn get_feed(app: &TestApp, token: &str, query: &str) -> axum::response::Response {
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/me/feed{}", query))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build feed request");
+    send(&app.app, req).await
+}
+
+#[tokio::test]
+async fn replies_reach_the_parent_authors_feed() {
+    let app = spawn_app().await;
+    let (author_token, author_id) = register(&app, "Feed Author").await;
+    let (replier_token, replier_id) = register(&app, "Feed Replier").await;
+    let lettering_id = approved_lettering(&app).await;
+
+    let parent = insert_comment(&app, lettering_id, author_id, None, "Lovely sign").await;
+    let reply = insert_comment(&app, lettering_id, replier_id, Some(parent), "Agreed!").await;
+    // Replying to yourself is not news.
+    insert_comment(&app, lettering_id, author_id, Some(parent), "Thanks").await;
+
+    ActivityFanout::new(app.db.clone(), 30)
+        .run_once()
+        .await
+        .expect("activity fan-out failed");
+
+    let res = get_feed(&app, &author_token, "?limit=10").await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    let items = body["items"].as_array().expect("feed missing items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["kind"], json!("COMMENT_REPLY"));
+    assert_eq!(items[0]["comment_id"], json!(reply));
+    assert_eq!(items[0]["lettering_id"], json!(lettering_id));
+    assert_eq!(items[0]["actor_name"], json!("Feed Replier"));
+    assert_eq!(items[0]["comment_excerpt"], json!("Agreed!"));
+    assert_eq!(body["next_cursor"], Value::Null);
+
+    let res = get_feed(&app, &replier_token, "").await;
+    let body: Value = read_json(expect_status(res, StatusCode::OK).await).await;
+    assert_eq!(body["items"], json!([]));
+
+    let res = get_feed(&app, &author_token, "?cursor=garbage").await;
+    assert_status(res.status(), StatusCode::BAD_REQUEST);
+}
+
+async fn insert_likes(app: &TestApp, lettering_id: Uuid, subnet: u8, count: i32) {
+    sqlx::query(
+        "INSERT INTO likes (id, lettering_id, user_ip)
+         SELECT uuid_generate_v4(), $1, ('10.88.' || $2 || '.' || n)::inet
+         FROM generate_series(1, $3) AS n",
+    )
+    .bind(lettering_id)
+    .bind(subnet as i32)
+    .bind(count)
+    .execute(&app.db)
+    .await
+    .expect("failed to insert likes");
+}
+
+async fn like_notifications(app: &TestApp, user_id: Uuid) -> Vec<(Uuid, String, i64)> {
+    sqlx::query_as(
+        "SELECT id, title, (metadata->>'count')::bigint FROM notifications
+         WHERE user_id = $1 AND type = 'LETTERING_LIKES'
+         ORDER BY created_at",
+    )
+    .bind(user_id)
+    .fetch_all(&app.db)
+    .await
+    .expect("failed to load notifications")
+}
+
+/// Lets the next pass digest `lettering_id` again, as if the window passed.
+async fn end_window(app: &TestApp, lettering_id: Uuid) {
+    sqlx::query(
+        "UPDATE like_digest_marks SET notified_at = notified_at - interval '2 hours'
+         WHERE lettering_id = $1",
+    )
+    .bind(lettering_id)
+    .execute(&app.db)
+    .await
+    .expect("failed to move digest window");
+}
+
+#[tokio::test]
+async fn likes_are_batched_into_one_notification_per_window() {
+    let app = spawn_app().await;
+    let (_, owner_id) = register(&app, "Liked Uploader").await;
+    let lettering_id = approved_lettering(&app).await;
+    sqlx::query("UPDATE letterings SET user_id = $2 WHERE id = $1")
+        .bind(lettering_id)
+        .bind(owner_id)
+        .execute(&app.db)
+        .await
+        .expect("failed to assign uploader");
+    let worker = LikeDigestWorker::new(app.db.clone(), 60, 300);
+
+    insert_likes(&app, lettering_id, 1, 12).await;
+    worker.run_once().await.expect("like digest failed");
+    let notifications = like_notifications(&app, owner_id).await;
+    assert_eq!(notifications.len(), 1);
+    let (first_id, title, count) = &notifications[0];
+    assert_eq!(title, "12 people liked your upload");
+    assert_eq!(*count, 12);
+
+    // Within the window new likes wait for the next digest.
+    insert_likes(&app, lettering_id, 2, 3).await;
+    worker.run_once().await.expect("like digest failed");
+    assert_eq!(like_notifications(&app, owner_id).await[0].2, 12);
+
+    // Once it has passed they are added to the unread notification.
+    end_window(&app, lettering_id).await;
+    worker.run_once().await.expect("like digest failed");
+    let notifications = like_notifications(&app, owner_id).await;
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].0, *first_id);
+    assert_eq!(notifications[0].1, "15 people liked your upload");
+    assert_eq!(notifications[0].2, 15);
+
+    // After it was read, the next like gets a notification of its own.
+    sqlx::query("UPDATE notifications SET is_read = true WHERE id = $1")
+        .bind(first_id)
+        .execute(&app.db)
+        .await
+        .expect("failed to mark notification read");
+    insert_likes(&app, lettering_id, 3, 1).await;
+    end_window(&app, lettering_id).await;
+    worker.run_once().await.expect("like digest failed");
+    let notifications = like_notifications(&app, owner_id).await;
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications[1].1, "Someone liked your upload");
+    assert_eq!(notifications[1].2, 1);
+}
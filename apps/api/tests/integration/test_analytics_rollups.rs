@@ -0,0 +1,109 @@
+use super::helpers::{
+    TestApp, admin_token, assert_status, expect_status, multipart_upload_body, read_json, send,
+    spawn_app, tiny_png_bytes,
+};
+use api::workers::analytics_worker::AnalyticsWorker;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn upload(app: &TestApp, tag: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        tag,
+        "560001",
+        "Rollup integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+fn rollups_request(token: &str, query: &str) -> Request<Body> {
+    Request::builder()
+        .uri(format!("/api/v1/admin/analytics/rollups?{}", query))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build rollups request")
+}
+
+#[tokio::test]
+async fn rollups_count_activity_per_bucket_and_country() {
+    let app = spawn_app().await;
+    let tag = format!("ru{}", &Uuid::now_v7().simple().to_string()[20..]);
+    let lettering_id = upload(&app, &tag).await;
+    sqlx::query("INSERT INTO likes (id, lettering_id, user_ip) VALUES ($1, $2, '10.88.0.1')")
+        .bind(Uuid::now_v7())
+        .bind(lettering_id)
+        .execute(&app.db)
+        .await
+        .expect("failed to insert like");
+
+    AnalyticsWorker::new(app.db.clone())
+        .record_rollups()
+        .await
+        .expect("rollup failed");
+
+    let hourly: Vec<(String, i32, i32, i32)> = sqlx::query_as(
+        "SELECT country_code, uploads, likes, active_contributors
+         FROM analytics_hourly_rollups
+         WHERE hour_start = date_trunc('hour', NOW())
+         ORDER BY country_code",
+    )
+    .fetch_all(&app.db)
+    .await
+    .expect("failed to read hourly rollups");
+    for country in ["*", "IN"] {
+        let (_, uploads, likes, contributors) = hourly
+            .iter()
+            .find(|(c, ..)| c == country)
+            .unwrap_or_else(|| panic!("missing hourly rollup for {}", country));
+        assert!(*uploads >= 1);
+        assert!(*likes >= 1);
+        assert!(*contributors >= 1);
+    }
+
+    // Every hour in the backfill window has an all-countries row.
+    let hours: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM analytics_hourly_rollups
+         WHERE country_code = '*' AND hour_start > NOW() - interval '1 day'",
+    )
+    .fetch_one(&app.db)
+    .await
+    .expect("failed to count hourly rollups");
+    assert_eq!(hours, 24);
+
+    let token = admin_token(&app).await;
+    let res = expect_status(
+        send(
+            &app.app,
+            rollups_request(&token, "granularity=daily&days=7"),
+        )
+        .await,
+        StatusCode::OK,
+    )
+    .await;
+    let body: Value = read_json(res).await;
+    let items = body["items"].as_array().expect("items should be a list");
+    let today = items.last().expect("today's rollup missing");
+    assert!(today["uploads"].as_i64().unwrap_or_default() >= 1);
+
+    let res = send(&app.app, rollups_request(&token, "granularity=weekly")).await;
+    assert_status(res.status(), StatusCode::BAD_REQUEST);
+}
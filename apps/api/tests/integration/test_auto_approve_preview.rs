@@ -0,0 +1,108 @@
+use super::helpers::{
+    TestApp, admin_token, assert_status, expect_status, multipart_upload_body, read_json, send,
+    spawn_app, tiny_png_bytes,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn pending_lettering(app: &TestApp, contributor_tag: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        contributor_tag,
+        "560001",
+        "Auto-approve preview integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+fn find(items: &Value, id: Uuid) -> Option<&Value> {
+    items
+        .as_array()
+        .expect("preview list missing")
+        .iter()
+        .find(|item| item["id"] == id.to_string())
+}
+
+#[tokio::test]
+async fn preview_reports_what_the_next_pass_would_do_without_doing_it() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let tag = format!("Preview{}", &Uuid::now_v7().simple().to_string()[..8]);
+
+    let earlier = pending_lettering(&app, &tag).await;
+    let stale = pending_lettering(&app, &tag).await;
+    let reported = pending_lettering(&app, &tag).await;
+    let fresh = pending_lettering(&app, &tag).await;
+
+    // An approved earlier upload, so the contributor is not a first-timer.
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(earlier)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve earlier upload");
+    sqlx::query(
+        "UPDATE letterings SET created_at = TIMESTAMPTZ '2000-01-01 00:00:00+00'
+         WHERE id = ANY($1)",
+    )
+    .bind(vec![stale, reported])
+    .execute(&app.db)
+    .await
+    .expect("failed to backdate uploads");
+    sqlx::query("UPDATE letterings SET report_count = 1 WHERE id = $1")
+        .bind(reported)
+        .execute(&app.db)
+        .await
+        .expect("failed to report upload");
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/admin/moderation/auto-approve/preview?minutes=60")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build preview request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let preview: Value = read_json(res).await;
+
+    assert_eq!(preview["stale_after_minutes"], 60);
+    assert_eq!(preview["enabled"], false);
+    let approved = find(&preview["would_approve"], stale).expect("stale item not approved");
+    assert!(approved["excluded_by"].is_null());
+    let held = find(&preview["would_hold"], reported).expect("reported item not held");
+    assert_eq!(held["excluded_by"], "reported");
+    assert!(find(&preview["would_approve"], fresh).is_none());
+    assert!(find(&preview["would_hold"], fresh).is_none());
+    assert!(preview["not_yet_stale"].as_i64().expect("missing count") >= 1);
+
+    let statuses: Vec<(String, Option<String>)> = sqlx::query_as(
+        "SELECT status, auto_approve_skip_reason FROM letterings WHERE id = ANY($1)",
+    )
+    .bind(vec![stale, reported])
+    .fetch_all(&app.db)
+    .await
+    .expect("failed to read statuses");
+    assert!(
+        statuses
+            .iter()
+            .all(|(status, reason)| status == "PENDING" && reason.is_none())
+    );
+}
@@ -0,0 +1,105 @@
+use super::helpers::{
+    TestApp, assert_status, multipart_upload_body, read_json, register_user_and_token, send,
+    spawn_app, tiny_png_bytes,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn uploaded_lettering(app: &TestApp, token: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        "Bookmarked",
+        "560001",
+        "Bookmarks integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+async fn set_status(app: &TestApp, id: Uuid, status: &str) {
+    sqlx::query("UPDATE letterings SET status = $2 WHERE id = $1")
+        .bind(id)
+        .bind(status)
+        .execute(&app.db)
+        .await
+        .expect("failed to update lettering status");
+}
+
+async fn request(app: &Router, method: &str, uri: &str, token: &str) -> Response {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build request");
+    send(app, req).await
+}
+
+async fn bookmark_total(app: &Router, token: &str) -> i64 {
+    let res = request(app, "GET", "/api/v1/me/bookmarks", token).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    body["total"].as_i64().expect("bookmarks missing total")
+}
+
+#[tokio::test]
+async fn bookmarks_are_private_and_follow_listing() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let other = register_user_and_token(&app.app).await;
+    let lettering = uploaded_lettering(&app, &token).await;
+    set_status(&app, lettering, "APPROVED").await;
+    let uri = format!("/api/v1/letterings/{}/bookmark", lettering);
+
+    let res = request(&app.app, "POST", &uri, &token).await;
+    assert_status(res.status(), StatusCode::CREATED);
+    let res = request(&app.app, "POST", &uri, &token).await;
+    assert_status(res.status(), StatusCode::OK);
+    assert_eq!(bookmark_total(&app.app, &token).await, 1);
+    assert_eq!(bookmark_total(&app.app, &other).await, 0);
+
+    set_status(&app, lettering, "REJECTED").await;
+    assert_eq!(bookmark_total(&app.app, &token).await, 0);
+    let res = request(&app.app, "POST", &uri, &other).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+
+    set_status(&app, lettering, "APPROVED").await;
+    assert_eq!(bookmark_total(&app.app, &token).await, 1);
+    let res = request(&app.app, "DELETE", &uri, &token).await;
+    assert_status(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(bookmark_total(&app.app, &token).await, 0);
+}
+
+#[tokio::test]
+async fn bookmarks_need_a_signed_in_user() {
+    let app = spawn_app().await;
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/me/bookmarks")
+        .body(Body::empty())
+        .expect("failed to build request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::FORBIDDEN);
+}
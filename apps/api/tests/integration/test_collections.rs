@@ -0,0 +1,191 @@
+use super::helpers::{
+    TestApp, assert_status, multipart_upload_body, read_json, register_user_and_token, send,
+    spawn_app, tiny_png_bytes,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn approved_lettering(app: &TestApp, token: &str) -> String {
+    let tag = format!("Curated{}", &Uuid::now_v7().simple().to_string()[24..]);
+    let (boundary, body) = multipart_upload_body(
+        &tag,
+        "560103",
+        "Collections integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let payload: Value = read_json(res).await;
+    let id = payload["id"]
+        .as_str()
+        .expect("upload response missing id")
+        .to_string();
+
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(Uuid::parse_str(&id).expect("invalid lettering id"))
+        .execute(&app.db)
+        .await
+        .expect("failed to approve lettering");
+    id
+}
+
+async fn request(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> Response {
+    let mut builder = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let body = match body {
+        Some(body) => {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+            Body::from(body.to_string())
+        }
+        None => Body::empty(),
+    };
+    send(app, builder.body(body).expect("failed to build request")).await
+}
+
+#[tokio::test]
+async fn private_collections_are_shared_once_made_public() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let first = approved_lettering(&app, &token).await;
+    let second = approved_lettering(&app, &token).await;
+
+    let res = request(
+        &app.app,
+        "POST",
+        "/api/v1/collections",
+        Some(&token),
+        Some(json!({ "name": "Hand-painted signs of Mysuru" })),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::CREATED);
+    let created: Value = read_json(res).await;
+    let id = created["id"].as_str().expect("collection missing id");
+    assert_eq!(created["is_public"], json!(false));
+    assert!(created["slug"].is_null());
+
+    let uri = format!("/api/v1/collections/{}", id);
+    let res = request(&app.app, "GET", &uri, None, None).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+
+    for lettering in [&first, &second] {
+        let res = request(
+            &app.app,
+            "POST",
+            &format!("{}/items/{}", uri, lettering),
+            Some(&token),
+            None,
+        )
+        .await;
+        assert_status(res.status(), StatusCode::CREATED);
+    }
+
+    let res = request(
+        &app.app,
+        "PUT",
+        &format!("{}/order", uri),
+        Some(&token),
+        Some(json!({ "lettering_ids": [second] })),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::NO_CONTENT);
+
+    let res = request(
+        &app.app,
+        "PATCH",
+        &uri,
+        Some(&token),
+        Some(json!({ "is_public": true })),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::OK);
+    let updated: Value = read_json(res).await;
+    let slug = updated["slug"]
+        .as_str()
+        .expect("public collection missing slug");
+    assert!(slug.starts_with("hand-painted-signs-of-mysuru-"));
+
+    let res = request(
+        &app.app,
+        "GET",
+        &format!("/api/v1/collections/shared/{}", slug),
+        None,
+        None,
+    )
+    .await;
+    assert_status(res.status(), StatusCode::OK);
+    let shared: Value = read_json(res).await;
+    assert_eq!(shared["item_count"], json!(2));
+    let order: Vec<&str> = shared["items"]
+        .as_array()
+        .expect("collection missing items")
+        .iter()
+        .filter_map(|item| item["lettering_id"].as_str())
+        .collect();
+    assert_eq!(order, vec![second.as_str(), first.as_str()]);
+}
+
+#[tokio::test]
+async fn only_the_owner_edits_a_collection() {
+    let app = spawn_app().await;
+    let owner = register_user_and_token(&app.app).await;
+    let other = register_user_and_token(&app.app).await;
+    let lettering = approved_lettering(&app, &owner).await;
+
+    let res = request(
+        &app.app,
+        "POST",
+        "/api/v1/collections",
+        Some(&owner),
+        Some(json!({ "name": "Shop fronts", "is_public": true })),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::CREATED);
+    let created: Value = read_json(res).await;
+    let uri = format!(
+        "/api/v1/collections/{}",
+        created["id"].as_str().expect("collection missing id")
+    );
+
+    let res = request(
+        &app.app,
+        "POST",
+        &format!("{}/items/{}", uri, lettering),
+        Some(&other),
+        None,
+    )
+    .await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+
+    let res = request(&app.app, "DELETE", &uri, Some(&other), None).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+
+    let res = request(&app.app, "DELETE", &uri, Some(&owner), None).await;
+    assert_status(res.status(), StatusCode::NO_CONTENT);
+}
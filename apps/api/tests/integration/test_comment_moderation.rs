@@ -0,0 +1,139 @@
+use super::helpers::{
+    TestApp, admin_token, assert_status, deliver_notifications, expect_status,
+    multipart_upload_body, read_json, send, spawn_app, tiny_png_bytes, unique_email,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+/// An uploaded lettering and the id of the account that commented on it.
+async fn commented_lettering(app: &TestApp) -> (Uuid, Uuid) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "email": unique_email("comment-moderation-it"),
+                "password": "StrongerPass123!",
+                "display_name": "Comment Moderation Tester"
+            })
+            .to_string(),
+        ))
+        .expect("failed to build register request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let body: Value = read_json(res).await;
+    let token = body["token"].as_str().expect("missing user token");
+    let user_id = Uuid::parse_str(body["user"]["id"].as_str().expect("missing user id"))
+        .expect("invalid user id");
+
+    let (boundary, upload) = multipart_upload_body(
+        "ModerationTag",
+        "560001",
+        "Comment moderation artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(upload))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    let lettering_id = Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id");
+    (lettering_id, user_id)
+}
+
+async fn insert_comment(
+    app: &TestApp,
+    lettering_id: Uuid,
+    user_id: Uuid,
+    parent_id: Option<Uuid>,
+) -> Uuid {
+    let id = Uuid::now_v7();
+    sqlx::query(
+        "INSERT INTO comments (id, lettering_id, user_id, parent_id, content)
+         VALUES ($1, $2, $3, $4, 'Bulk moderation comment')",
+    )
+    .bind(id)
+    .bind(lettering_id)
+    .bind(user_id)
+    .bind(parent_id)
+    .execute(&app.db)
+    .await
+    .expect("failed to insert comment");
+    id
+}
+
+#[tokio::test]
+async fn bulk_delete_tombstones_threads_and_recounts_once() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let (lettering_id, user_id) = commented_lettering(&app).await;
+    let parent = insert_comment(&app, lettering_id, user_id, None).await;
+    let _reply = insert_comment(&app, lettering_id, user_id, Some(parent)).await;
+    let lone = insert_comment(&app, lettering_id, user_id, None).await;
+    let missing = Uuid::now_v7();
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/admin/comments/bulk")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(
+            json!({ "ids": [parent, lone, lone, missing], "action": "delete" }).to_string(),
+        ))
+        .expect("failed to build bulk request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert_eq!(body["processed"], json!(2));
+    assert_eq!(body["failed_items"][0]["id"], json!(missing));
+
+    let tombstoned: bool =
+        sqlx::query_scalar("SELECT deleted_at IS NOT NULL FROM comments WHERE id = $1")
+            .bind(parent)
+            .fetch_one(&app.db)
+            .await
+            .expect("comment with replies should stay as a tombstone");
+    assert!(tombstoned);
+
+    let comments_count: i32 =
+        sqlx::query_scalar("SELECT comments_count FROM letterings WHERE id = $1")
+            .bind(lettering_id)
+            .fetch_one(&app.db)
+            .await
+            .expect("failed to read comments_count");
+    assert_eq!(comments_count, 1);
+
+    // Notifications are queued; another test's runner may be delivering
+    // some of them concurrently.
+    let mut notified = 0;
+    for _ in 0..20 {
+        deliver_notifications(&app).await;
+        notified = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND type = 'COMMENT_DELETED'",
+        )
+        .bind(user_id)
+        .fetch_one(&app.db)
+        .await
+        .expect("failed to count notifications");
+        if notified == 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(notified, 2);
+}
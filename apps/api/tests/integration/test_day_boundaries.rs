@@ -0,0 +1,130 @@
+use super::helpers::{
+    TestApp, expect_status, multipart_upload_body, read_json, send, spawn_app, tiny_png_bytes,
+};
+use api::{
+    domain::lettering::repository::LetteringRepository,
+    infrastructure::repositories::sqlx_lettering_repository::SqlxLetteringRepository,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn day_start(app: &TestApp, at: &str, timezone: &str) -> DateTime<Utc> {
+    sqlx::query_scalar("SELECT local_day_start($1::timestamptz, $2)")
+        .bind(at)
+        .bind(timezone)
+        .fetch_one(&app.db)
+        .await
+        .expect("local_day_start failed")
+}
+
+async fn date(app: &TestApp, at: &str, timezone: &str) -> NaiveDate {
+    sqlx::query_scalar("SELECT local_date($1::timestamptz, $2)")
+        .bind(at)
+        .bind(timezone)
+        .fetch_one(&app.db)
+        .await
+        .expect("local_date failed")
+}
+
+async fn count_since(repo: &SqlxLetteringRepository, tag: &str, since: DateTime<Utc>) -> i64 {
+    repo.count_by_contributor_since(tag, since)
+        .await
+        .expect("count failed")
+}
+
+fn utc(at: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(at)
+        .expect("invalid timestamp")
+        .with_timezone(&Utc)
+}
+
+#[tokio::test]
+async fn local_days_start_at_midnight_in_the_given_time_zone() {
+    let app = spawn_app().await;
+
+    // One second either side of midnight in India, 18:30 UTC.
+    assert_eq!(
+        day_start(&app, "2026-03-01T18:29:59Z", "Asia/Kolkata").await,
+        utc("2026-02-28T18:30:00Z")
+    );
+    assert_eq!(
+        day_start(&app, "2026-03-01T18:30:00Z", "Asia/Kolkata").await,
+        utc("2026-03-01T18:30:00Z")
+    );
+    assert_eq!(
+        date(&app, "2026-03-01T18:29:59Z", "Asia/Kolkata").await,
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+    );
+    assert_eq!(
+        date(&app, "2026-03-01T18:30:00Z", "Asia/Kolkata").await,
+        NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()
+    );
+    // The same instant is still 1 March in UTC.
+    assert_eq!(
+        day_start(&app, "2026-03-01T18:30:00Z", "UTC").await,
+        utc("2026-03-01T00:00:00Z")
+    );
+
+    // Clocks go forward in New York on 8 March: that day starts at 05:00
+    // UTC, the next at 04:00 UTC.
+    assert_eq!(
+        day_start(&app, "2026-03-08T12:00:00Z", "America/New_York").await,
+        utc("2026-03-08T05:00:00Z")
+    );
+    assert_eq!(
+        day_start(&app, "2026-03-09T12:00:00Z", "America/New_York").await,
+        utc("2026-03-09T04:00:00Z")
+    );
+}
+
+#[tokio::test]
+async fn contributor_counts_respect_the_local_day_boundary() {
+    let app = spawn_app().await;
+    let tag = format!("tz{}", &Uuid::now_v7().simple().to_string()[20..]);
+    let (boundary, body) = multipart_upload_body(
+        &tag,
+        "560001",
+        "Day boundary integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    let id = Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id");
+
+    // Uploaded at 23:59:59 in India.
+    sqlx::query("UPDATE letterings SET created_at = '2026-03-01T18:29:59Z' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to backdate lettering");
+
+    let repo = SqlxLetteringRepository::new(app.db.clone());
+
+    // A second later a new day has started in India, but not in UTC.
+    let india = day_start(&app, "2026-03-01T18:30:00Z", "Asia/Kolkata").await;
+    assert_eq!(count_since(&repo, &tag, india).await, 0);
+    let utc_day = day_start(&app, "2026-03-01T18:30:00Z", "UTC").await;
+    assert_eq!(count_since(&repo, &tag, utc_day).await, 1);
+
+    // A rolling window still counts it.
+    let rolling = utc("2026-03-01T18:30:00Z") - chrono::Duration::hours(24);
+    assert_eq!(count_since(&repo, &tag, rolling).await, 1);
+}
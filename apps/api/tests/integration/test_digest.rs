@@ -0,0 +1,110 @@
+use super::helpers::{
+    TestApp, assert_status, expect_status, read_json, send, spawn_app, unique_email,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+async fn register(app: &TestApp) -> (String, Uuid) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "email": unique_email("digest-it"),
+                "password": "StrongerPass123!",
+                "display_name": "Digest Tester"
+            })
+            .to_string(),
+        ))
+        .expect("failed to build register request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let body: Value = read_json(res).await;
+    let token = body["token"]
+        .as_str()
+        .expect("missing user token")
+        .to_string();
+    let user_id = Uuid::parse_str(body["user"]["id"].as_str().expect("missing user id"))
+        .expect("invalid user id");
+    (token, user_id)
+}
+
+async fn put_settings(app: &TestApp, token: &str, settings: Value) -> axum::response::Response {
+    let req = Request::builder()
+        .method("PUT")
+        .uri("/api/v1/me/digest")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(settings.to_string()))
+        .expect("failed to build settings request");
+    send(&app.app, req).await
+}
+
+async fn get_settings(app: &TestApp, token: &str) -> Value {
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/me/digest")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build settings request");
+    read_json(expect_status(send(&app.app, req).await, StatusCode::OK).await).await
+}
+
+async fn unsubscribe(app: &TestApp, token: Uuid) -> axum::response::Response {
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/digest/unsubscribe?token={}", token))
+        .body(Body::empty())
+        .expect("failed to build unsubscribe request");
+    send(&app.app, req).await
+}
+
+#[tokio::test]
+async fn digest_opt_in_and_one_click_unsubscribe() {
+    let app = spawn_app().await;
+    let (token, user_id) = register(&app).await;
+
+    let settings = get_settings(&app, &token).await;
+    assert_eq!(settings["enabled"], json!(false));
+
+    let res = put_settings(
+        &app,
+        &token,
+        json!({ "enabled": true, "timezone": "Asia/Kolkata", "locale": "hi-IN", "send_hour": 8 }),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::OK);
+    let settings: Value = read_json(res).await;
+    assert_eq!(
+        settings,
+        json!({ "enabled": true, "timezone": "Asia/Kolkata", "locale": "hi", "send_hour": 8 })
+    );
+
+    let res = put_settings(
+        &app,
+        &token,
+        json!({ "enabled": true, "timezone": "Mars/Olympus" }),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::BAD_REQUEST);
+
+    let unsubscribe_token: Uuid =
+        sqlx::query_scalar("SELECT unsubscribe_token FROM digest_subscriptions WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&app.db)
+            .await
+            .expect("subscription missing");
+    let res = unsubscribe(&app, unsubscribe_token).await;
+    assert_status(res.status(), StatusCode::OK);
+
+    let settings = get_settings(&app, &token).await;
+    assert_eq!(settings["enabled"], json!(false));
+    assert_eq!(settings["timezone"], json!("Asia/Kolkata"));
+
+    let res = unsubscribe(&app, Uuid::now_v7()).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,136 @@
+use super::helpers::{
+    TestApp, admin_token, assert_status, expect_status, multipart_upload_body, read_json, send,
+    spawn_app, tiny_png_bytes,
+};
+use api::workers::review_priority::ReviewPriorityWorker;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn pending_lettering(app: &TestApp) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        "EscalationTag",
+        "560001",
+        "Escalation integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+async fn get(app: &TestApp, token: &str, uri: &str) -> Value {
+    let req = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    read_json(res).await
+}
+
+#[tokio::test]
+async fn escalated_items_are_served_first_and_counted_weekly() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let _older = pending_lettering(&app).await;
+    let escalated = pending_lettering(&app).await;
+
+    let before = get(&app, &token, "/api/v1/admin/moderation/escalations?weeks=1").await;
+    let before_count = before["weeks"][0]["escalations"]
+        .as_i64()
+        .expect("stats missing escalations");
+
+    sqlx::query(
+        "UPDATE letterings SET review_priority = 99, escalated_at = NOW(), review_pool = 'secondary'
+         WHERE id = $1",
+    )
+    .bind(escalated)
+    .execute(&app.db)
+    .await
+    .expect("failed to escalate lettering");
+    sqlx::query(
+        "INSERT INTO moderation_escalations (id, lettering_id, level, review_pool, pending_since)
+         VALUES ($1, $2, 99, 'secondary', NOW())",
+    )
+    .bind(Uuid::now_v7())
+    .bind(escalated)
+    .execute(&app.db)
+    .await
+    .expect("failed to record escalation");
+
+    // No secondary pool is configured, so every moderator gets routed items.
+    let next = get(&app, &token, "/api/v1/admin/moderation/next?status=PENDING").await;
+    assert_eq!(next["item"]["id"], json!(escalated));
+    assert_eq!(next["item"]["review_priority"], json!(99));
+
+    let after = get(&app, &token, "/api/v1/admin/moderation/escalations?weeks=1").await;
+    assert_eq!(after["weeks"].as_array().map(Vec::len), Some(1));
+    assert_eq!(after["weeks"][0]["escalations"], json!(before_count + 1));
+    assert!(after["still_pending"].as_i64().unwrap_or(0) >= 1);
+}
+
+#[tokio::test]
+async fn reported_public_letterings_with_traffic_outrank_obscure_pending_ones() {
+    let app = spawn_app().await;
+    let obscure = pending_lettering(&app).await;
+    let exposed = pending_lettering(&app).await;
+
+    sqlx::query("UPDATE letterings SET status = 'APPROVED', report_count = 1 WHERE id = $1")
+        .bind(exposed)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve lettering");
+    sqlx::query(
+        "INSERT INTO lettering_reports (id, lettering_id, reason, reporter_key)
+         VALUES ($1, $2, 'spam', 'user:reporter')",
+    )
+    .bind(Uuid::now_v7())
+    .bind(exposed)
+    .execute(&app.db)
+    .await
+    .expect("failed to report lettering");
+    for _ in 0..3 {
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("/api/v1/letterings/{}", exposed))
+            .body(Body::empty())
+            .expect("failed to build detail request");
+        assert_status(send(&app.app, req).await.status(), StatusCode::OK);
+    }
+
+    ReviewPriorityWorker::new(app.db.clone(), 0.5, 60)
+        .run_once()
+        .await
+        .expect("review priority pass failed");
+
+    let priority = |id: Uuid| {
+        let db = app.db.clone();
+        async move {
+            sqlx::query_scalar::<_, i32>("SELECT review_priority FROM letterings WHERE id = $1")
+                .bind(id)
+                .fetch_one(&db)
+                .await
+                .expect("failed to read review priority")
+        }
+    };
+    assert!(priority(exposed).await > priority(obscure).await);
+}
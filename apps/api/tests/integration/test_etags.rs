@@ -0,0 +1,101 @@
+use super::helpers::{
+    admin_token, assert_status, expect_status, multipart_upload_body, read_json, send, spawn_app,
+    tiny_png_bytes,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn get(app: &Router, uri: &str, if_none_match: Option<&str>) -> Response {
+    let mut req = Request::builder().method("GET").uri(uri);
+    if let Some(etag) = if_none_match {
+        req = req.header(header::IF_NONE_MATCH, etag);
+    }
+    let req = req.body(Body::empty()).expect("failed to build request");
+    send(app, req).await
+}
+
+fn etag_of(res: &Response) -> String {
+    res.headers()
+        .get(header::ETAG)
+        .expect("missing ETag")
+        .to_str()
+        .expect("invalid ETag")
+        .to_string()
+}
+
+#[tokio::test]
+async fn unchanged_reads_are_answered_with_not_modified() {
+    let app = spawn_app().await;
+    let tag = format!("Etag{}", &Uuid::now_v7().simple().to_string()[..8]);
+    let (boundary, body) = multipart_upload_body(
+        &tag,
+        "560001",
+        "ETag integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    let id = Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id");
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve upload");
+
+    for uri in [
+        format!("/api/v1/letterings/{}", id),
+        format!("/api/v1/contributors/{}", tag),
+        "/api/v1/letterings?limit=5".to_string(),
+    ] {
+        let first = get(&app.app, &uri, None).await;
+        assert_status(first.status(), StatusCode::OK);
+        let etag = etag_of(&first);
+
+        let cached = get(&app.app, &uri, Some(&etag)).await;
+        assert_status(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(etag_of(&cached), etag);
+        let body = axum::body::to_bytes(cached.into_body(), usize::MAX)
+            .await
+            .expect("failed to read body");
+        assert!(body.is_empty());
+
+        let stale = get(&app.app, &uri, Some("\"stale\"")).await;
+        assert_status(stale.status(), StatusCode::OK);
+    }
+
+    // A change to the lettering changes its tag, once moderation drops the
+    // cached response.
+    let uri = format!("/api/v1/letterings/{}", id);
+    let etag = etag_of(&get(&app.app, &uri, None).await);
+    let token = admin_token(&app).await;
+    let req = Request::builder()
+        .method("PUT")
+        .uri(format!("/api/v1/admin/letterings/{}/age-restriction", id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "age_restricted": true }).to_string()))
+        .expect("failed to build age restriction request");
+    assert_status(send(&app.app, req).await.status(), StatusCode::NO_CONTENT);
+    let changed = get(&app.app, &uri, Some(&etag)).await;
+    assert_status(changed.status(), StatusCode::OK);
+    assert_ne!(etag_of(&changed), etag);
+}
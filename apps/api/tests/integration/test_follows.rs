@@ -0,0 +1,87 @@
+use super::helpers::{assert_status, read_json, register_user_and_token, send, spawn_app};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+async fn follow(app: &Router, token: &str, body: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/me/follows")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(body.to_string()))
+        .expect("failed to build follow request");
+    let res = send(app, req).await;
+    let status = res.status();
+    (status, read_json(res).await)
+}
+
+async fn follower_count(app: &Router, tag: &str) -> i64 {
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/contributors/{}", tag))
+        .body(Body::empty())
+        .expect("failed to build contributor request");
+    let res = send(app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    body["follower_count"]
+        .as_i64()
+        .expect("contributor response missing follower_count")
+}
+
+#[tokio::test]
+async fn follow_and_unfollow_a_contributor_tag() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let tag = format!("Follow{}", &Uuid::now_v7().simple().to_string()[24..]);
+
+    let (status, created) = follow(&app.app, &token, json!({ "contributor_tag": tag })).await;
+    assert_status(status, StatusCode::CREATED);
+    assert_eq!(created["contributor_tag"].as_str(), Some(tag.as_str()));
+    assert_eq!(follower_count(&app.app, &tag).await, 1);
+
+    let (status, again) = follow(&app.app, &token, json!({ "contributor_tag": tag })).await;
+    assert_status(status, StatusCode::OK);
+    assert_eq!(again["id"], created["id"]);
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/me/follows")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build list request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let listed: Value = read_json(res).await;
+    assert_eq!(listed.as_array().map(Vec::len), Some(1));
+
+    let req = Request::builder()
+        .method("DELETE")
+        .uri(format!(
+            "/api/v1/me/follows/{}",
+            created["id"].as_str().expect("follow missing id")
+        ))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build unfollow request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::NO_CONTENT);
+    assert_eq!(follower_count(&app.app, &tag).await, 0);
+}
+
+#[tokio::test]
+async fn following_needs_exactly_one_existing_target() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+
+    let (status, _) = follow(&app.app, &token, json!({})).await;
+    assert_status(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = follow(&app.app, &token, json!({ "user_id": Uuid::now_v7() })).await;
+    assert_status(status, StatusCode::NOT_FOUND);
+}
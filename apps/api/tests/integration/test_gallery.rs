@@ -128,3 +128,54 @@ async fn gallery_sort_oldest_returns_ascending_created_at_order() {
         "expected ascending created_at for oldest sort, got {first_created} then {second_created}"
     );
 }
+
+#[tokio::test]
+async fn browse_lists_scripts_and_their_letterings() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let script = format!("script-{}", uuid::Uuid::now_v7().simple());
+    let mut ids = Vec::new();
+    for (contributor, pin_code) in [("BrowseA", "560301"), ("BrowseB", "560302")] {
+        let id = upload_artifact(&app.app, &token, contributor, pin_code).await;
+        sqlx::query(
+            "UPDATE letterings SET status = 'APPROVED', ml_script = $2, ml_low_confidence_fields = '{}'
+             WHERE id = $1::uuid",
+        )
+        .bind(&id)
+        .bind(&script)
+        .execute(&app.db)
+        .await
+        .expect("failed to classify lettering");
+        ids.push(id);
+    }
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/browse/scripts")
+        .body(Body::empty())
+        .expect("failed to build browse request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let payload: Value = read_json(res).await;
+    let facet = payload
+        .as_array()
+        .expect("browse index should be an array")
+        .iter()
+        .find(|f| f["value"] == json!(script))
+        .expect("script missing from browse index");
+    assert_eq!(facet["count"], json!(2));
+    let cover = facet["cover_lettering_id"]
+        .as_str()
+        .expect("browse facet missing cover");
+    assert!(ids.iter().any(|id| id == cover));
+
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/browse/scripts/{}?limit=10", script))
+        .body(Body::empty())
+        .expect("failed to build browse list request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let payload: Value = read_json(res).await;
+    assert_eq!(payload["total"], json!(2));
+}
@@ -0,0 +1,263 @@
+use super::helpers::{
+    TestApp, admin_token, assert_status, expect_status, multipart_upload_body, read_json, send,
+    spawn_app, tiny_png_bytes,
+};
+use api::infrastructure::cache::invalidation::{lettering_key, missing_lettering_key};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use redis::AsyncCommands;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+fn redis() -> redis::Client {
+    redis::Client::open(
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+    )
+    .expect("invalid redis url")
+}
+
+async fn get_json(app: &TestApp, uri: &str) -> Value {
+    let req = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .expect("failed to build request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    read_json(res).await
+}
+
+async fn detail(app: &TestApp, id: Uuid) -> Value {
+    get_json(app, &format!("/api/v1/letterings/{}", id)).await
+}
+
+fn unique_tag() -> String {
+    format!("Hot{}", &Uuid::now_v7().simple().to_string()[24..])
+}
+
+/// A pending upload by `tag`.
+async fn upload(app: &TestApp, tag: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        tag,
+        "560001",
+        "Hot cache integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+async fn approve(app: &TestApp, id: Uuid) {
+    let token = admin_token(app).await;
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/admin/letterings/{}/approve", id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build approve request");
+    assert_status(send(&app.app, req).await.status(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn moderation_drops_a_cached_lettering() {
+    let app = spawn_app().await;
+    let id = upload(&app, &unique_tag()).await;
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve upload");
+
+    let first = detail(&app, id).await;
+    assert_eq!(first["description"], "Hot cache integration artifact");
+
+    // Written behind the cache's back: reads keep the cached copy.
+    sqlx::query("UPDATE letterings SET description = 'Edited directly' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to edit lettering");
+    let cached = detail(&app, id).await;
+    assert_eq!(cached["description"], "Hot cache integration artifact");
+
+    // Moderating through the API drops it.
+    approve(&app, id).await;
+    let fresh = detail(&app, id).await;
+    assert_eq!(fresh["description"], "Edited directly");
+}
+
+#[tokio::test]
+async fn approval_refreshes_the_contributor_and_city_pages() {
+    let app = spawn_app().await;
+    let tag = unique_tag();
+    let id = upload(&app, &tag).await;
+    let contributor_uri = format!("/api/v1/contributors/{}", tag);
+    let city_uri = format!("/api/v1/cities/{}", DEFAULT_CITY_ID);
+
+    let pending = get_json(&app, &contributor_uri).await;
+    assert_eq!(pending["total_count"], 0);
+    let before = get_json(&app, &city_uri).await["lettering_count"]
+        .as_i64()
+        .expect("city response missing lettering_count");
+
+    approve(&app, id).await;
+    let approved = get_json(&app, &contributor_uri).await;
+    assert_eq!(approved["total_count"], 1);
+    assert_eq!(approved["letterings"][0]["id"], id.to_string());
+    // Other tests approve in the same city meanwhile.
+    let after = get_json(&app, &city_uri).await["lettering_count"]
+        .as_i64()
+        .expect("city response missing lettering_count");
+    assert!(after > before);
+}
+
+#[tokio::test]
+async fn approval_refreshes_the_cached_world_map() {
+    let app = spawn_app().await;
+    let id = upload(&app, &unique_tag()).await;
+    // Zoomed in enough for points, over the whole world.
+    let uri = "/api/v1/letterings/map?bbox=-180,-90,180,90&zoom=16";
+    let shows = |map: &Value| {
+        map["features"]
+            .as_array()
+            .expect("map response missing features")
+            .iter()
+            .any(|f| f["id"] == id.to_string())
+    };
+
+    assert!(!shows(&get_json(&app, uri).await));
+    approve(&app, id).await;
+    assert!(shows(&get_json(&app, uri).await));
+}
+
+#[tokio::test]
+async fn missing_letterings_are_remembered_briefly() {
+    let app = spawn_app().await;
+    let id = Uuid::now_v7();
+    for _ in 0..2 {
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("/api/v1/letterings/{}", id))
+            .body(Body::empty())
+            .expect("failed to build detail request");
+        assert_status(send(&app.app, req).await.status(), StatusCode::NOT_FOUND);
+    }
+
+    // Both the detail read and the repository remember the miss, for the
+    // not-found TTL rather than the usual one.
+    let mut conn = redis()
+        .get_multiplexed_async_connection()
+        .await
+        .expect("failed to connect to redis");
+    for key in [lettering_key(id), missing_lettering_key(id)] {
+        let ttl: i64 = conn.ttl(&key).await.expect("failed to read ttl");
+        assert!((1..=30).contains(&ttl), "{} has ttl {}", key, ttl);
+    }
+}
+
+/// The `X-Cache` header of a response to `uri`, read as `token` if given.
+async fn x_cache(app: &TestApp, uri: &str, token: Option<&str>) -> Option<String> {
+    let mut req = Request::builder().method("GET").uri(uri);
+    if let Some(token) = token {
+        req = req.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let req = req.body(Body::empty()).expect("failed to build request");
+    let res = send(&app.app, req).await;
+    res.headers()
+        .get("x-cache")
+        .map(|v| v.to_str().expect("invalid x-cache").to_string())
+}
+
+#[tokio::test]
+async fn lettering_responses_are_replayed_until_moderation() {
+    let app = spawn_app().await;
+    let id = upload(&app, &unique_tag()).await;
+    approve(&app, id).await;
+    let uri = format!("/api/v1/letterings/{}", id);
+
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("MISS"));
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("HIT"));
+    approve(&app, id).await;
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("MISS"));
+
+    // Signed-in readers always reach the handler.
+    let token = admin_token(&app).await;
+    assert_eq!(x_cache(&app, &uri, Some(&token)).await, None);
+
+    // Replayed reads still count as views.
+    let views: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(count), 0)::bigint FROM lettering_view_counts WHERE lettering_id = $1",
+    )
+    .bind(id)
+    .fetch_one(&app.db)
+    .await
+    .expect("failed to read view counts");
+    assert!(views >= 3, "{} views counted", views);
+}
+
+#[tokio::test]
+async fn moderation_drops_cached_responses() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let tag = unique_tag();
+
+    // Rejecting one at a time.
+    let rejected = upload(&app, &tag).await;
+    approve(&app, rejected).await;
+    let uri = format!("/api/v1/letterings/{}", rejected);
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("MISS"));
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("HIT"));
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/admin/letterings/{}/reject", rejected))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "reason": "Not lettering" }).to_string()))
+        .expect("failed to build reject request");
+    assert_status(send(&app.app, req).await.status(), StatusCode::NO_CONTENT);
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("MISS"));
+
+    // Approving a whole source.
+    let approved = upload(&app, &tag).await;
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(approved)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve upload");
+    let uri = format!("/api/v1/letterings/{}", approved);
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("MISS"));
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("HIT"));
+    sqlx::query("UPDATE letterings SET status = 'PENDING' WHERE id = $1")
+        .bind(approved)
+        .execute(&app.db)
+        .await
+        .expect("failed to requeue upload");
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/admin/moderation/sources/action")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "group_by": "contributor", "source": tag, "action": "approve" }).to_string(),
+        ))
+        .expect("failed to build source moderation request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let body: Value = read_json(res).await;
+    assert_eq!(body["ids"], json!([approved.to_string()]));
+    assert_eq!(x_cache(&app, &uri, None).await.as_deref(), Some("MISS"));
+}
@@ -0,0 +1,325 @@
+use api::infrastructure::{
+    queue::jobs::{Envelope, Failure, Job, JobHandler, JobQueue, JobRunner, Priority, RetryPolicy},
+    shutdown::Shutdown,
+};
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Flaky {
+    n: u32,
+}
+
+impl Job for Flaky {
+    const KIND: &'static str = "test_flaky";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Abandoned {
+    n: u32,
+}
+
+impl Job for Abandoned {
+    const KIND: &'static str = "test_abandoned";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Ranked {
+    n: u32,
+}
+
+impl Job for Ranked {
+    const KIND: &'static str = "test_ranked";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Draining {
+    n: u32,
+}
+
+impl Job for Draining {
+    const KIND: &'static str = "test_draining";
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Keyed {
+    key: String,
+    n: u32,
+}
+
+impl Job for Keyed {
+    const KIND: &'static str = "test_keyed";
+
+    fn idempotency_key(&self) -> Option<String> {
+        Some(self.key.clone())
+    }
+}
+
+/// Shuts the runner down from inside its first job.
+struct StopAfterFirst {
+    shutdown: Shutdown,
+}
+
+#[async_trait]
+impl JobHandler for StopAfterFirst {
+    type Job = Draining;
+
+    async fn handle(&self, _job: &Envelope<Draining>) -> anyhow::Result<()> {
+        self.shutdown.trigger();
+        Ok(())
+    }
+}
+
+const NO_RETRIES: RetryPolicy = RetryPolicy {
+    max_attempts: 1,
+    base_delay: Duration::ZERO,
+    max_delay: Duration::ZERO,
+    visibility_timeout: Duration::from_secs(60),
+};
+
+async fn fresh_queue<J: Job>(policy: RetryPolicy) -> JobQueue<J> {
+    let url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let client = redis::Client::open(url).expect("invalid redis url");
+    let mut conn = client
+        .get_multiplexed_async_connection()
+        .await
+        .expect("failed to connect to redis");
+    let keys = J::keys();
+    let _: () = conn
+        .del(&[
+            keys.high,
+            keys.normal,
+            keys.bulk,
+            keys.inflight,
+            keys.retry,
+            keys.dead,
+        ])
+        .await
+        .expect("failed to clear job keys");
+    JobQueue::new(client, policy)
+}
+
+#[tokio::test]
+async fn failing_jobs_are_retried_then_dead_lettered_and_requeued() {
+    let queue = fresh_queue::<Flaky>(RetryPolicy {
+        max_attempts: 2,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        visibility_timeout: Duration::from_secs(60),
+    })
+    .await;
+    let id = queue.enqueue(Flaky { n: 7 }).await.expect("enqueue failed");
+    let error = anyhow::anyhow!("boom");
+
+    let claimed = queue.claim(10).await.expect("claim failed");
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].envelope.id, id);
+    assert_eq!(
+        queue.fail(&claimed[0], &error).await.expect("fail failed"),
+        Failure::Retrying(Duration::ZERO)
+    );
+    assert!(queue.claim(10).await.expect("claim failed").is_empty());
+
+    assert_eq!(queue.release_due(10).await.expect("release failed"), 1);
+    let claimed = queue.claim(10).await.expect("claim failed");
+    assert_eq!(claimed[0].envelope.attempts, 1);
+    assert_eq!(
+        queue.fail(&claimed[0], &error).await.expect("fail failed"),
+        Failure::DeadLettered
+    );
+
+    let (dead, total) = queue.dead_letters(0, 10).await.expect("list failed");
+    assert_eq!(total, 1);
+    assert_eq!(dead[0].job.id, id);
+    assert_eq!(dead[0].job.attempts, 2);
+    assert_eq!(dead[0].error, "boom");
+
+    let requeued = queue
+        .requeue_dead_letters(|job| job.n == 7, None)
+        .await
+        .expect("requeue failed");
+    assert_eq!(requeued.len(), 1);
+    let claimed = queue.claim(10).await.expect("claim failed");
+    assert_eq!(claimed[0].envelope.attempts, 0);
+    assert_eq!(claimed[0].job().n, 7);
+    queue.ack(&claimed[0]).await.expect("ack failed");
+    assert_eq!(queue.release_due(10).await.expect("release failed"), 0);
+}
+
+#[tokio::test]
+async fn jobs_past_their_visibility_timeout_are_retried() {
+    let queue = fresh_queue::<Abandoned>(RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        visibility_timeout: Duration::ZERO,
+    })
+    .await;
+    queue
+        .enqueue(Abandoned { n: 1 })
+        .await
+        .expect("enqueue failed");
+
+    // Claimed by a worker that never reports back.
+    let abandoned = queue.claim(10).await.expect("claim failed");
+    assert_eq!(abandoned.len(), 1);
+
+    // Expired, then released again from the retry set.
+    assert_eq!(queue.release_due(10).await.expect("release failed"), 1);
+    assert_eq!(queue.release_due(10).await.expect("release failed"), 1);
+    let retried = queue.claim(10).await.expect("claim failed");
+    assert_eq!(retried[0].envelope.attempts, 1);
+
+    // The first worker finally gives up; its job has already moved on.
+    let error = anyhow::anyhow!("too late");
+    assert_eq!(
+        queue
+            .fail(&abandoned[0], &error)
+            .await
+            .expect("fail failed"),
+        Failure::Expired
+    );
+    queue.ack(&retried[0]).await.expect("ack failed");
+}
+
+#[tokio::test]
+async fn urgent_lists_drain_first_unless_a_lower_one_has_its_turn() {
+    let queue = fresh_queue::<Ranked>(NO_RETRIES).await;
+    queue
+        .enqueue_with_priority(Ranked { n: 3 }, Priority::Bulk)
+        .await
+        .expect("enqueue failed");
+    queue
+        .enqueue(Ranked { n: 2 })
+        .await
+        .expect("enqueue failed");
+    queue
+        .enqueue_with_priority(Ranked { n: 1 }, Priority::High)
+        .await
+        .expect("enqueue failed");
+
+    let counts = queue.counts().await.expect("counts failed");
+    assert_eq!((counts.high, counts.normal, counts.bulk), (1, 1, 1));
+
+    // A starvation turn for bulk skips the more urgent lists.
+    let bulk = queue
+        .claim_in_order(10, &[Priority::Bulk, Priority::Normal, Priority::High])
+        .await
+        .expect("claim failed");
+    assert_eq!(bulk.len(), 1);
+    assert_eq!(bulk[0].priority, Priority::Bulk);
+    assert_eq!(bulk[0].job().n, 3);
+
+    let high = queue.claim(10).await.expect("claim failed");
+    assert_eq!(high.len(), 1);
+    assert_eq!(high[0].priority, Priority::High);
+    let normal = queue.claim(10).await.expect("claim failed");
+    assert_eq!(normal[0].job().n, 2);
+    assert!(queue.claim(10).await.expect("claim failed").is_empty());
+
+    // A failed high-priority job is dead-lettered and requeued where asked.
+    let error = anyhow::anyhow!("boom");
+    assert_eq!(
+        queue.fail(&high[0], &error).await.expect("fail failed"),
+        Failure::DeadLettered
+    );
+    queue
+        .requeue_dead_letters(|_| true, Some(Priority::Bulk))
+        .await
+        .expect("requeue failed");
+    let requeued = queue.claim(10).await.expect("claim failed");
+    assert_eq!(requeued[0].priority, Priority::Bulk);
+    assert_eq!(requeued[0].job().n, 1);
+    for claimed in bulk.iter().chain(&normal).chain(&requeued) {
+        queue.ack(claimed).await.expect("ack failed");
+    }
+}
+
+#[tokio::test]
+async fn shutdown_hands_back_claimed_jobs_that_have_not_started() {
+    let queue = fresh_queue::<Draining>(NO_RETRIES).await;
+    for n in 0..3 {
+        queue.enqueue(Draining { n }).await.expect("enqueue failed");
+    }
+    let shutdown = Shutdown::new();
+    let runner = JobRunner::new(
+        queue.clone(),
+        StopAfterFirst {
+            shutdown: shutdown.clone(),
+        },
+    )
+    .with_shutdown(shutdown);
+
+    assert_eq!(runner.run_once().await.expect("run failed"), 1);
+    let counts = queue.counts().await.expect("counts failed");
+    assert_eq!((counts.normal, counts.inflight, counts.retry), (2, 0, 0));
+
+    // Handed back unrun, so no attempt was counted against them.
+    let left = queue.claim(10).await.expect("claim failed");
+    assert_eq!(left.len(), 2);
+    assert!(left.iter().all(|claimed| claimed.envelope.attempts == 0));
+    for claimed in &left {
+        queue.ack(claimed).await.expect("ack failed");
+    }
+}
+
+#[tokio::test]
+async fn repeats_within_the_idempotency_window_are_dropped() {
+    let queue = fresh_queue::<Keyed>(NO_RETRIES)
+        .await
+        .with_idempotency_window(Duration::from_secs(60));
+    let key = Uuid::now_v7().to_string();
+    let first = queue
+        .enqueue(Keyed {
+            key: key.clone(),
+            n: 1,
+        })
+        .await
+        .expect("enqueue failed");
+    let repeat = queue
+        .enqueue_with_priority(
+            Keyed {
+                key: key.clone(),
+                n: 2,
+            },
+            Priority::High,
+        )
+        .await
+        .expect("enqueue failed");
+    assert_eq!(repeat, first);
+    queue
+        .enqueue_all(vec![
+            Keyed {
+                key: key.clone(),
+                n: 3,
+            },
+            Keyed {
+                key: Uuid::now_v7().to_string(),
+                n: 4,
+            },
+        ])
+        .await
+        .expect("enqueue failed");
+
+    let counts = queue.counts().await.expect("counts failed");
+    assert_eq!((counts.high, counts.normal), (0, 2));
+    let claimed = queue.claim(10).await.expect("claim failed");
+    let mut queued: Vec<u32> = claimed.iter().map(|c| c.job().n).collect();
+    queued.sort();
+    assert_eq!(queued, vec![1, 4]);
+    for claimed in &claimed {
+        queue.ack(claimed).await.expect("ack failed");
+    }
+
+    // Finishing the job does not reopen the window.
+    assert_eq!(
+        queue
+            .enqueue(Keyed { key, n: 5 })
+            .await
+            .expect("enqueue failed"),
+        first
+    );
+}
@@ -0,0 +1,103 @@
+use api::infrastructure::leadership::{LeaderElection, current_leaders};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+const LEASE: Duration = Duration::from_millis(600);
+
+fn redis() -> redis::Client {
+    redis::Client::open(
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+    )
+    .expect("invalid redis url")
+}
+
+/// A role no earlier test run has used, so its lease is free.
+fn unique_role(prefix: &str) -> String {
+    format!("{}_{}", prefix, uuid::Uuid::now_v7().simple())
+}
+
+fn election(instance: &str) -> LeaderElection {
+    LeaderElection::new(redis(), LEASE).with_instance(instance)
+}
+
+#[tokio::test]
+async fn one_instance_leads_until_it_releases_or_its_lease_expires() {
+    let role = unique_role("test_lease");
+    let a = election("instance-a");
+    let b = election("instance-b");
+
+    assert!(a.try_acquire(&role).await.expect("acquire failed"));
+    assert!(!b.try_acquire(&role).await.expect("acquire failed"));
+    assert!(a.try_acquire(&role).await.expect("reacquire failed"));
+    assert_eq!(
+        current_leaders(&redis(), &[role.as_str()])
+            .await
+            .expect("leaders failed"),
+        vec![Some("instance-a".to_string())]
+    );
+
+    // Only the holder can renew or release.
+    assert!(!b.renew(&role).await.expect("renew failed"));
+    b.release(&role).await.expect("release failed");
+    assert!(!b.try_acquire(&role).await.expect("acquire failed"));
+
+    a.release(&role).await.expect("release failed");
+    assert!(b.try_acquire(&role).await.expect("acquire failed"));
+
+    // b stops renewing, as if it died.
+    tokio::time::sleep(LEASE + Duration::from_millis(200)).await;
+    assert!(a.try_acquire(&role).await.expect("acquire failed"));
+    assert!(!b.renew(&role).await.expect("renew failed"));
+}
+
+#[tokio::test]
+async fn work_fails_over_when_the_leader_dies() {
+    let role = unique_role("test_failover");
+    let started = Arc::new(AtomicU32::new(0));
+    let spawn = |instance: &'static str| {
+        let election = election(instance);
+        let role = role.clone();
+        let started = started.clone();
+        tokio::spawn(async move {
+            election
+                .run(&role, || async {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    std::future::pending::<()>().await
+                })
+                .await
+        })
+    };
+
+    let a = spawn("instance-a");
+    let b = spawn("instance-b");
+    tokio::time::sleep(LEASE).await;
+    assert_eq!(started.load(Ordering::SeqCst), 1);
+
+    let leader = current_leaders(&redis(), &[role.as_str()])
+        .await
+        .expect("leaders failed")
+        .pop()
+        .flatten()
+        .expect("no leader");
+    let (dead, survivor) = if leader == "instance-a" {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    dead.abort();
+
+    tokio::time::sleep(LEASE * 2).await;
+    assert_eq!(started.load(Ordering::SeqCst), 2);
+    let leader_now = current_leaders(&redis(), &[role.as_str()])
+        .await
+        .expect("leaders failed")
+        .pop()
+        .flatten();
+    assert!(leader_now.is_some_and(|l| l != leader));
+    survivor.abort();
+}
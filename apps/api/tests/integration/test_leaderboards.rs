@@ -0,0 +1,107 @@
+use super::helpers::{
+    TestApp, assert_status, expect_status, multipart_upload_body, read_json, send, spawn_app,
+    tiny_png_bytes,
+};
+use api::workers::analytics_worker::AnalyticsWorker;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::Value;
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn approved_lettering(app: &TestApp, tag: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        tag,
+        "560001",
+        "Leaderboard integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    let id = Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id");
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to approve lettering");
+    id
+}
+
+async fn get(app: &TestApp, uri: &str) -> axum::response::Response {
+    let req = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .expect("failed to build request");
+    send(&app.app, req).await
+}
+
+#[tokio::test]
+async fn analytics_pass_ranks_contributors_per_period() {
+    let app = spawn_app().await;
+    let tag = format!("lb{}", &Uuid::now_v7().simple().to_string()[20..]);
+    let lettering_id = approved_lettering(&app, &tag).await;
+    // Enough likes to make the weekly top whatever else the database holds.
+    sqlx::query(
+        "INSERT INTO likes (id, lettering_id, user_ip)
+         SELECT uuid_generate_v4(), $1, ('10.77.' || (n / 256) || '.' || (n % 256))::inet
+         FROM generate_series(0, 999) AS n",
+    )
+    .bind(lettering_id)
+    .execute(&app.db)
+    .await
+    .expect("failed to insert likes");
+
+    AnalyticsWorker::new(app.db.clone())
+        .refresh_leaderboards()
+        .await
+        .expect("leaderboard refresh failed");
+
+    let ranked: Vec<(String, String, i64)> = sqlx::query_as(
+        "SELECT period, metric, value FROM leaderboard_entries
+         WHERE contributor_tag = $1 ORDER BY period, metric",
+    )
+    .bind(&tag)
+    .fetch_all(&app.db)
+    .await
+    .expect("failed to read leaderboard entries");
+    let likes: Vec<(&str, i64)> = ranked
+        .iter()
+        .filter(|(_, metric, _)| metric == "likes")
+        .map(|(period, _, value)| (period.as_str(), *value))
+        .collect();
+    assert_eq!(
+        likes,
+        vec![("all-time", 1000), ("monthly", 1000), ("weekly", 1000)]
+    );
+
+    let res = get(&app, "/api/v1/leaderboards/weekly").await;
+    assert_status(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers()
+            .get(header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok()),
+        Some("public, max-age=300")
+    );
+    let body: Value = read_json(res).await;
+    assert_eq!(body["period"], "weekly");
+    assert!(body["most_liked"].is_array());
+    assert!(body["most_cities"].is_array());
+
+    let res = get(&app, "/api/v1/leaderboards/daily").await;
+    assert_status(res.status(), StatusCode::BAD_REQUEST);
+}
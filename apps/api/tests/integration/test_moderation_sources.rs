@@ -0,0 +1,119 @@
+use super::helpers::{
+    TestApp, admin_token, assert_status, expect_status, multipart_upload_body, read_json, send,
+    spawn_app, tiny_png_bytes,
+};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+async fn upload_as(app: &TestApp, contributor_tag: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        contributor_tag,
+        "560001",
+        "Moderation source artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+async fn status_of(app: &TestApp, id: Uuid) -> String {
+    sqlx::query_scalar("SELECT status FROM letterings WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .expect("failed to read lettering status")
+}
+
+#[tokio::test]
+async fn queue_groups_by_contributor_and_rejects_a_source_at_once() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let spammer = format!("Spammer{}", &Uuid::now_v7().simple().to_string()[24..]);
+    let bystander = format!("Bystander{}", &Uuid::now_v7().simple().to_string()[24..]);
+    let mut spam = Vec::new();
+    for _ in 0..3 {
+        spam.push(upload_as(&app, &spammer).await);
+    }
+    let kept = upload_as(&app, &bystander).await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/admin/moderation?group_by=contributor&limit=200")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build queue request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    let group = body["groups"]
+        .as_array()
+        .expect("grouped queue missing groups")
+        .iter()
+        .find(|g| g["source"] == json!(spammer))
+        .expect("spammer missing from grouped queue")
+        .clone();
+    assert_eq!(group["count"], json!(3));
+    assert_eq!(group["oldest_id"], json!(spam[0]));
+    assert_eq!(group["sample_thumbnails"].as_array().map(Vec::len), Some(3));
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/admin/moderation/sources/action")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(
+            json!({ "group_by": "contributor", "source": spammer, "action": "reject" }).to_string(),
+        ))
+        .expect("failed to build source action request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert_eq!(body["processed"], json!(3));
+
+    for id in spam {
+        assert_eq!(status_of(&app, id).await, "REJECTED");
+    }
+    assert_eq!(status_of(&app, kept).await, "PENDING");
+
+    let audited: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM admin_audit_logs
+         WHERE action = 'SOURCE_REJECT_LETTERING' AND metadata->>'source' = $1",
+    )
+    .bind(&spammer)
+    .fetch_one(&app.db)
+    .await
+    .expect("failed to count audit rows");
+    assert_eq!(audited, 3);
+}
+
+#[tokio::test]
+async fn unknown_grouping_is_rejected() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/admin/moderation?group_by=city")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build queue request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,195 @@
+use super::helpers::{
+    TestApp, TestStorage, admin_token, assert_status, expect_status, multipart_upload_body,
+    read_json, register_user_and_token, send, spawn_app, tiny_png_bytes,
+};
+use api::{
+    infrastructure::storage::{
+        content_addressed,
+        in_memory::InMemoryStorage,
+        traits::StorageService,
+        visibility::{PrivateBucketStorage, public_key},
+    },
+    presentation::http::handlers::synthetic_storage,
+    workers::image_publisher::ImagePublisherWorker,
+};
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+const PRIVATE_URL_PREFIX: &str = "https://test-storage.local/private/";
+
+async fn upload(app: &TestApp, token: &str) -> Uuid {
+    let (boundary, body) = multipart_upload_body(
+        "PrivateUploader",
+        "560001",
+        "Private until approved",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let payload: Value = read_json(res).await;
+    Uuid::parse_str(payload["id"].as_str().expect("upload response missing id"))
+        .expect("invalid lettering id")
+}
+
+async fn moderate(app: &TestApp, id: Uuid, action: &str) {
+    let token = admin_token(app).await;
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/admin/letterings/{}/{}", id, action))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "reason": "Not lettering" }).to_string()))
+        .expect("failed to build moderation request");
+    assert_status(send(&app.app, req).await.status(), StatusCode::NO_CONTENT);
+}
+
+async fn placement(app: &TestApp, id: Uuid) -> (String, String, bool) {
+    sqlx::query_as("SELECT image_url, thumbnail_large, images_public FROM letterings WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .expect("failed to read lettering")
+}
+
+async fn get_detail(app: &TestApp, id: Uuid, token: Option<&str>) -> axum::response::Response {
+    let mut req = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/letterings/{}", id));
+    if let Some(token) = token {
+        req = req.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    send(
+        &app.app,
+        req.body(Body::empty()).expect("failed to build request"),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn rejected_letterings_are_private_until_approved() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let id = upload(&app, &token).await;
+
+    let (image_url, _, images_public) = placement(&app, id).await;
+    assert!(images_public, "approved upload was left private");
+    assert!(!image_url.starts_with(PRIVATE_URL_PREFIX), "{image_url}");
+
+    moderate(&app, id, "reject").await;
+    let (image_url, thumbnail_large, images_public) = placement(&app, id).await;
+    assert!(!images_public);
+    assert!(image_url.starts_with(PRIVATE_URL_PREFIX), "{image_url}");
+    assert!(
+        thumbnail_large.starts_with(PRIVATE_URL_PREFIX),
+        "{thumbnail_large}"
+    );
+
+    // Strangers can't find it; the owner is handed signed URLs.
+    assert_status(
+        get_detail(&app, id, None).await.status(),
+        StatusCode::NOT_FOUND,
+    );
+    let res = expect_status(get_detail(&app, id, Some(&token)).await, StatusCode::OK).await;
+    let detail: Value = read_json(res).await;
+    let shown = detail["image_url"]
+        .as_str()
+        .expect("detail missing image_url");
+    assert!(shown.starts_with(PRIVATE_URL_PREFIX), "{shown}");
+    assert!(shown.ends_with("?signature=test"), "{shown}");
+
+    moderate(&app, id, "approve").await;
+    let (image_url, _, images_public) = placement(&app, id).await;
+    assert!(images_public);
+    assert!(!image_url.starts_with(PRIVATE_URL_PREFIX), "{image_url}");
+    assert_status(get_detail(&app, id, None).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn image_publisher_moves_images_a_status_change_left_behind() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let id = upload(&app, &token).await;
+    sqlx::query("UPDATE letterings SET status = 'REJECTED' WHERE id = $1")
+        .bind(id)
+        .execute(&app.db)
+        .await
+        .expect("failed to reject lettering");
+
+    ImagePublisherWorker::new(app.db.clone(), Arc::new(TestStorage))
+        .run_once()
+        .await
+        .expect("image publisher pass failed");
+    let (image_url, thumbnail_large, images_public) = placement(&app, id).await;
+    assert!(!images_public);
+    assert!(image_url.starts_with(PRIVATE_URL_PREFIX), "{image_url}");
+    assert!(
+        thumbnail_large.starts_with(PRIVATE_URL_PREFIX),
+        "{thumbnail_large}"
+    );
+}
+
+fn path_of(url: &str) -> &str {
+    url.strip_prefix("http://storage.test")
+        .expect("not a storage url")
+}
+
+async fn fetch(router: &Router, path: &str) -> StatusCode {
+    let req = Request::builder()
+        .method("GET")
+        .uri(path)
+        .body(Body::empty())
+        .expect("failed to build request");
+    send(router, req).await.status()
+}
+
+#[tokio::test]
+async fn pending_objects_have_no_public_url() {
+    let app = spawn_app().await;
+    let public = Arc::new(InMemoryStorage::new("http://storage.test", 1 << 20));
+    let private = Arc::new(InMemoryStorage::new_private("http://storage.test", 1 << 20));
+    let storage = PrivateBucketStorage::new(public.clone(), private.clone());
+    let router = synthetic_storage::router(public).merge(synthetic_storage::router(private));
+
+    let data = Uuid::now_v7().as_bytes().to_vec();
+    let url = content_addressed::put(&app.db, &storage, data, "txt", "text/plain", None, true)
+        .await
+        .expect("put failed");
+    let key = storage.key_from_url(&url).expect("unknown url");
+
+    // Neither the stored URL nor the public key serves it unsigned.
+    assert_eq!(fetch(&router, path_of(&url)).await, StatusCode::FORBIDDEN);
+    let public_url = storage.get_url(public_key(&key));
+    assert_eq!(
+        fetch(&router, path_of(&public_url)).await,
+        StatusCode::NOT_FOUND
+    );
+    let signed = storage
+        .signed_url(&key, Duration::from_secs(60))
+        .await
+        .expect("failed to sign");
+    assert_eq!(fetch(&router, path_of(&signed)).await, StatusCode::OK);
+
+    // Once copied to its public key, anyone can fetch it.
+    content_addressed::copy(&app.db, &storage, &key, public_key(&key))
+        .await
+        .expect("copy failed");
+    assert_eq!(fetch(&router, path_of(&public_url)).await, StatusCode::OK);
+}
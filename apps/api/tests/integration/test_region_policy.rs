@@ -0,0 +1,351 @@
+use super::helpers::{
+    TestApp, assert_status, multipart_upload_body, read_json, register_user_and_token, send,
+    spawn_app, tiny_png_bytes,
+};
+use api::presentation::http::handlers::short_links::encode_short_code;
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+    response::Response,
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
+
+/// User-assigned ISO code, so no seeded city shares the policy.
+const HIDDEN_COUNTRY: &str = "XA";
+const HIDDEN_LAT: f64 = -48.876;
+const HIDDEN_LNG: f64 = -123.393;
+
+/// An approved lettering in a country with discoverability disabled.
+struct HiddenLettering {
+    id: String,
+    tag: String,
+    city_id: Uuid,
+    short_code: String,
+    token: String,
+}
+
+async fn hidden_lettering(app: &TestApp) -> HiddenLettering {
+    let token = register_user_and_token(&app.app).await;
+    let tag = format!("Hidden{}", &Uuid::now_v7().simple().to_string()[24..]);
+    let (boundary, body) = multipart_upload_body(
+        &tag,
+        "560103",
+        "Region policy integration artifact",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::from(body))
+        .expect("failed to build upload request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+    let payload: Value = read_json(res).await;
+    let id = payload["id"]
+        .as_str()
+        .expect("upload response missing id")
+        .to_string();
+
+    sqlx::query(
+        "INSERT INTO region_policies (country_code, discoverability_enabled)
+         VALUES ($1, false)
+         ON CONFLICT (country_code) DO UPDATE SET discoverability_enabled = false",
+    )
+    .bind(HIDDEN_COUNTRY)
+    .execute(&app.db)
+    .await
+    .expect("failed to disable discoverability");
+
+    let city_id = Uuid::now_v7();
+    sqlx::query("INSERT INTO cities (id, name, country_code, is_active) VALUES ($1, $2, $3, true)")
+        .bind(city_id)
+        .bind(format!("Aggregate Only {}", city_id))
+        .bind(HIDDEN_COUNTRY)
+        .execute(&app.db)
+        .await
+        .expect("failed to create city");
+
+    let short_id: i64 = sqlx::query_scalar(
+        "UPDATE letterings
+         SET city_id = $2, status = 'APPROVED',
+             location = ST_SetSRID(ST_MakePoint($3, $4), 4326)::geography
+         WHERE id = $1
+         RETURNING short_id",
+    )
+    .bind(Uuid::parse_str(&id).expect("invalid lettering id"))
+    .bind(city_id)
+    .bind(HIDDEN_LNG)
+    .bind(HIDDEN_LAT)
+    .fetch_one(&app.db)
+    .await
+    .expect("failed to move lettering into the hidden country");
+
+    HiddenLettering {
+        id,
+        tag,
+        city_id,
+        short_code: encode_short_code(short_id),
+        token,
+    }
+}
+
+async fn get(app: &Router, uri: &str) -> Response {
+    let req = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .expect("failed to build request");
+    send(app, req).await
+}
+
+async fn assert_not_found(app: &Router, uri: &str) {
+    let res = get(app, uri).await;
+    assert_eq!(
+        res.status(),
+        StatusCode::NOT_FOUND,
+        "{} should not expose a lettering from an aggregate-only country",
+        uri
+    );
+}
+
+/// Every `"id"` anywhere in a response body.
+fn ids(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(items) => items.iter().flat_map(ids).collect(),
+        Value::Object(map) => map
+            .iter()
+            .flat_map(|(key, v)| match (key.as_str(), v) {
+                ("id", Value::String(id)) => vec![id.clone()],
+                _ => ids(v),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+async fn assert_not_listed(app: &Router, uri: &str, id: &str) {
+    let res = get(app, uri).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert!(
+        !ids(&body).iter().any(|listed| listed == id),
+        "{} listed a lettering from an aggregate-only country",
+        uri
+    );
+}
+
+#[tokio::test]
+async fn detail_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(&app.app, &format!("/api/v1/letterings/{}", hidden.id)).await;
+}
+
+#[tokio::test]
+async fn owner_still_sees_own_lettering() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/api/v1/letterings/{}", hidden.id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", hidden.token))
+        .body(Body::empty())
+        .expect("failed to build detail request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn download_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(
+        &app.app,
+        &format!("/api/v1/letterings/{}/download", hidden.id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn share_card_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(
+        &app.app,
+        &format!("/api/v1/letterings/{}/og-image", hidden.id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn share_link_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/v1/letterings/{}/share", hidden.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "channel": "whatsapp" }).to_string()))
+        .expect("failed to build share request");
+    let res = send(&app.app, req).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn qr_code_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(&app.app, &format!("/api/v1/letterings/{}/qr", hidden.id)).await;
+}
+
+#[tokio::test]
+async fn image_variant_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(&app.app, &format!("/images/{}?w=200", hidden.id)).await;
+}
+
+#[tokio::test]
+async fn comments_are_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(
+        &app.app,
+        &format!("/api/v1/letterings/{}/comments", hidden.id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn short_link_is_not_found() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_found(&app.app, &format!("/s/{}", hidden.short_code)).await;
+}
+
+#[tokio::test]
+async fn similar_is_empty() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    let res = get(
+        &app.app,
+        &format!("/api/v1/letterings/{}/similar", hidden.id),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert_eq!(body["similar"].as_array().map(Vec::len), Some(0));
+}
+
+#[tokio::test]
+async fn contributor_page_leaves_it_out() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    let res = get(&app.app, &format!("/api/v1/contributors/{}", hidden.tag)).await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert_eq!(body["total_count"].as_i64(), Some(0));
+    assert_eq!(body["letterings"].as_array().map(Vec::len), Some(0));
+}
+
+#[tokio::test]
+async fn gallery_leaves_it_out() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    let res = get(
+        &app.app,
+        &format!("/api/v1/letterings?city_id={}", hidden.city_id),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert_eq!(body["total"].as_i64(), Some(0));
+}
+
+#[tokio::test]
+async fn search_leaves_it_out() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_listed(
+        &app.app,
+        &format!("/api/v1/letterings/search?q={}", hidden.tag),
+        &hidden.id,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn nearby_leaves_it_out() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_listed(
+        &app.app,
+        &format!(
+            "/api/v1/letterings/nearby?lat={}&lng={}",
+            HIDDEN_LAT, HIDDEN_LNG
+        ),
+        &hidden.id,
+    )
+    .await;
+    assert_not_listed(
+        &app.app,
+        &format!(
+            "/api/v1/geo/nearby?lat={}&lng={}&radius_m=1000",
+            HIDDEN_LAT, HIDDEN_LNG
+        ),
+        &hidden.id,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn map_leaves_it_out() {
+    let app = spawn_app().await;
+    let hidden = hidden_lettering(&app).await;
+    assert_not_listed(
+        &app.app,
+        &format!(
+            "/api/v1/letterings/map?zoom=18&bbox={},{},{},{}",
+            HIDDEN_LNG - 0.01,
+            HIDDEN_LAT - 0.01,
+            HIDDEN_LNG + 0.01,
+            HIDDEN_LAT + 0.01
+        ),
+        &hidden.id,
+    )
+    .await;
+    assert_not_listed(
+        &app.app,
+        &format!("/api/v1/geo/markers?city_id={}", hidden.city_id),
+        &hidden.id,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn region_aggregates_still_count_it() {
+    let app = spawn_app().await;
+    let _hidden = hidden_lettering(&app).await;
+    let res = get(
+        &app.app,
+        &format!(
+            "/api/v1/regions/{}/aggregates",
+            HIDDEN_COUNTRY.to_lowercase()
+        ),
+    )
+    .await;
+    assert_status(res.status(), StatusCode::OK);
+    let body: Value = read_json(res).await;
+    assert_eq!(body["discoverable"].as_bool(), Some(false));
+    assert!(body["total"].as_i64().unwrap_or(0) >= 1);
+}
@@ -0,0 +1,225 @@
+use super::helpers::{TestApp, admin_token, expect_status, read_json, send, spawn_app};
+use api::workers::scheduler::{ScheduledTask, Scheduler};
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{TimeDelta, Utc};
+use serde_json::{Value, json};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+struct Counter {
+    name: &'static str,
+    runs: Arc<AtomicU32>,
+    fail: bool,
+}
+
+#[async_trait]
+impl ScheduledTask for Counter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(&self) -> anyhow::Result<()> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        if self.fail {
+            anyhow::bail!("counter failed");
+        }
+        Ok(())
+    }
+}
+
+/// A task name no earlier test run has used, so its ticks and locks are
+/// fresh.
+fn unique_name(prefix: &str) -> &'static str {
+    Box::leak(format!("{}_{}", prefix, uuid::Uuid::now_v7().simple()).into_boxed_str())
+}
+
+fn scheduler(app: &TestApp, instance: &str, task: Counter) -> Scheduler {
+    let redis = redis::Client::open(
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+    )
+    .expect("invalid redis url");
+    let overrides = BTreeMap::from([(task.name.to_string(), "* * * * *".to_string())]);
+    Scheduler::new(app.db.clone(), redis, overrides)
+        .with_instance(instance)
+        .with_task(task)
+}
+
+fn update_request(token: &str, name: &str, body: Value) -> Request<Body> {
+    Request::builder()
+        .method("PUT")
+        .uri(format!("/api/v1/admin/schedules/{}", name))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("failed to build schedule update request")
+}
+
+#[tokio::test]
+async fn each_tick_fires_on_one_instance_and_is_recorded() {
+    let app = spawn_app().await;
+    let name = unique_name("test_counter");
+    let runs = Arc::new(AtomicU32::new(0));
+    let a = scheduler(
+        &app,
+        "instance-a",
+        Counter {
+            name,
+            runs: runs.clone(),
+            fail: false,
+        },
+    );
+    let b = scheduler(
+        &app,
+        "instance-b",
+        Counter {
+            name,
+            runs: runs.clone(),
+            fail: false,
+        },
+    );
+
+    let until = Utc::now();
+    let since = until - TimeDelta::minutes(5);
+    let mut fired = a.tick(since, until).await.expect("tick failed");
+    fired.extend(b.tick(since, until).await.expect("tick failed"));
+    assert_eq!(fired.len(), 1);
+    for run in fired {
+        run.await.expect("run panicked");
+    }
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    let (status, instance): (String, String) =
+        sqlx::query_as("SELECT status, instance FROM scheduled_task_runs WHERE name = $1")
+            .bind(name)
+            .fetch_one(&app.db)
+            .await
+            .expect("run not recorded");
+    assert_eq!(status, "SUCCEEDED");
+    assert_eq!(instance, "instance-a");
+}
+
+#[tokio::test]
+async fn failed_runs_are_recorded_and_disabled_tasks_do_not_fire() {
+    let app = spawn_app().await;
+    let name = unique_name("test_failing");
+    let runs = Arc::new(AtomicU32::new(0));
+    let failing = scheduler(
+        &app,
+        "instance-a",
+        Counter {
+            name,
+            runs: runs.clone(),
+            fail: true,
+        },
+    );
+
+    let until = Utc::now();
+    for run in failing
+        .tick(until - TimeDelta::minutes(1), until)
+        .await
+        .expect("tick failed")
+    {
+        run.await.expect("run panicked");
+    }
+    let (status, error): (String, Option<String>) =
+        sqlx::query_as("SELECT status, error FROM scheduled_task_runs WHERE name = $1")
+            .bind(name)
+            .fetch_one(&app.db)
+            .await
+            .expect("run not recorded");
+    assert_eq!(status, "FAILED");
+    assert_eq!(error.as_deref(), Some("counter failed"));
+
+    sqlx::query("INSERT INTO scheduled_tasks (name, enabled) VALUES ($1, false)")
+        .bind(name)
+        .execute(&app.db)
+        .await
+        .expect("failed to disable task");
+    let later = until + TimeDelta::minutes(5);
+    let fired = failing.tick(until, later).await.expect("tick failed");
+    assert!(fired.is_empty());
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn admins_see_and_override_schedules() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+
+    let res = expect_status(
+        send(
+            &app.app,
+            Request::builder()
+                .uri("/api/v1/admin/schedules")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .expect("failed to build schedules request"),
+        )
+        .await,
+        StatusCode::OK,
+    )
+    .await;
+    let body: Value = read_json(res).await;
+    let items = body["items"].as_array().expect("items should be a list");
+    assert!(
+        items
+            .iter()
+            .any(|s| s["name"] == "geo_retention" && s["default_cron"] == "30 */6 * * *")
+    );
+
+    expect_status(
+        send(
+            &app.app,
+            update_request(&token, "geo_retention", json!({ "cron": "61 * * * *" })),
+        )
+        .await,
+        StatusCode::BAD_REQUEST,
+    )
+    .await;
+    expect_status(
+        send(
+            &app.app,
+            update_request(&token, "no_such_task", json!({ "enabled": false })),
+        )
+        .await,
+        StatusCode::NOT_FOUND,
+    )
+    .await;
+
+    let res = expect_status(
+        send(
+            &app.app,
+            update_request(&token, "geo_retention", json!({ "cron": "@daily" })),
+        )
+        .await,
+        StatusCode::OK,
+    )
+    .await;
+    let updated: Value = read_json(res).await;
+    assert_eq!(updated["cron"], json!("@daily"));
+    assert_eq!(updated["source"], json!("database"));
+    assert_eq!(updated["enabled"], json!(true));
+    assert!(updated["next_run_at"].is_string());
+
+    let res = expect_status(
+        send(
+            &app.app,
+            update_request(&token, "geo_retention", json!({ "cron": null })),
+        )
+        .await,
+        StatusCode::OK,
+    )
+    .await;
+    let restored: Value = read_json(res).await;
+    assert_eq!(restored["cron"], json!("30 */6 * * *"));
+    assert_eq!(restored["source"], json!("default"));
+}
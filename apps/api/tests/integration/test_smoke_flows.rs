@@ -161,3 +161,94 @@ async fn smoke_auth_upload_discover_comment_and_admin_comment_moderation() {
         "restored comment should be returned in visible comments list"
     );
 }
+
+#[tokio::test]
+async fn comments_page_through_the_next_cursor_header() {
+    let app = spawn_app().await;
+
+    let register_req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/auth/register")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({
+                "email": unique_email("comment-pages"),
+                "password": "StrongSmokePass123!",
+                "display_name": "Comment Pages"
+            })
+            .to_string(),
+        ))
+        .expect("failed to build register request");
+    let register_res = expect_status(send(&app.app, register_req).await, StatusCode::OK).await;
+    let register_body: Value = read_json(register_res).await;
+    let user_token = register_body["token"].as_str().expect("missing user token");
+
+    let (boundary, upload_body) = multipart_upload_body(
+        "CommentPagesTag",
+        "560301",
+        "Comment pages upload",
+        DEFAULT_CITY_ID,
+        &tiny_png_bytes(),
+    );
+    let upload_req = Request::builder()
+        .method("POST")
+        .uri("/api/v1/letterings/upload")
+        .header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={}", boundary),
+        )
+        .header(header::AUTHORIZATION, format!("Bearer {}", user_token))
+        .body(Body::from(upload_body))
+        .expect("failed to build upload request");
+    let upload_res = expect_status(send(&app.app, upload_req).await, StatusCode::OK).await;
+    let upload_payload: Value = read_json(upload_res).await;
+    let lettering_id = upload_payload["id"]
+        .as_str()
+        .expect("missing lettering id in upload response");
+
+    for n in 0..3 {
+        let add_comment_req = Request::builder()
+            .method("POST")
+            .uri(format!("/api/v1/letterings/{}/comments", lettering_id))
+            .header(header::AUTHORIZATION, format!("Bearer {}", user_token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                json!({ "content": format!("Clean paging comment number {}.", n) }).to_string(),
+            ))
+            .expect("failed to build add-comment request");
+        expect_status(send(&app.app, add_comment_req).await, StatusCode::OK).await;
+    }
+
+    // The body is still a bare array; the next page is found by header.
+    let first_req = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/api/v1/letterings/{}/comments?limit=2",
+            lettering_id
+        ))
+        .body(Body::empty())
+        .expect("failed to build get-comments request");
+    let first_res = expect_status(send(&app.app, first_req).await, StatusCode::OK).await;
+    let cursor = first_res
+        .headers()
+        .get("x-next-cursor")
+        .expect("first page should point at the next one")
+        .to_str()
+        .expect("invalid cursor header")
+        .to_string();
+    let first: Value = read_json(first_res).await;
+    assert_eq!(first.as_array().map(Vec::len), Some(2));
+
+    let second_req = Request::builder()
+        .method("GET")
+        .uri(format!(
+            "/api/v1/letterings/{}/comments?limit=2&cursor={}",
+            lettering_id, cursor
+        ))
+        .body(Body::empty())
+        .expect("failed to build get-comments request");
+    let second_res = expect_status(send(&app.app, second_req).await, StatusCode::OK).await;
+    assert!(!second_res.headers().contains_key("x-next-cursor"));
+    let second: Value = read_json(second_res).await;
+    assert_eq!(second.as_array().map(Vec::len), Some(1));
+}
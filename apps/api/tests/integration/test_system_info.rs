@@ -0,0 +1,59 @@
+use super::helpers::{admin_token, expect_status, read_json, send, spawn_app};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use serde_json::{Value, json};
+
+fn info_request(token: Option<&str>) -> Request<Body> {
+    let mut req = Request::builder().uri("/api/v1/admin/system/info");
+    if let Some(token) = token {
+        req = req.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    req.body(Body::empty())
+        .expect("failed to build system info request")
+}
+
+#[tokio::test]
+async fn system_info_reports_build_config_and_health_without_secrets() {
+    let app = spawn_app().await;
+    expect_status(
+        send(&app.app, info_request(None)).await,
+        StatusCode::UNAUTHORIZED,
+    )
+    .await;
+
+    let token = admin_token(&app).await;
+    let res = expect_status(
+        send(&app.app, info_request(Some(&token))).await,
+        StatusCode::OK,
+    )
+    .await;
+    let body: Value = read_json(res).await;
+
+    assert_eq!(body["version"], json!(env!("CARGO_PKG_VERSION")));
+    assert_eq!(body["config"]["jwt_secret"], json!("[redacted]"));
+    assert_eq!(body["config"]["admin_password_hash"], json!("[redacted]"));
+    assert_eq!(body["config"]["admin_email"], json!(app.admin_email));
+    assert_eq!(
+        body["migrations"]["applied_version"],
+        body["migrations"]["known_version"]
+    );
+
+    let database = body["dependencies"]
+        .as_array()
+        .expect("dependencies should be a list")
+        .iter()
+        .find(|d| d["name"] == "database")
+        .expect("database health missing");
+    assert_eq!(database["status"], json!("up"));
+
+    let workers = body["workers"]["workers"]
+        .as_array()
+        .expect("workers should be a list");
+    assert!(
+        workers
+            .iter()
+            .any(|w| w["name"] == "notification_delivery" && w["enabled"] == json!(true))
+    );
+}
@@ -2,12 +2,22 @@ use super::helpers::{
     assert_status, expect_status, multipart_upload_body, read_json, read_text, send, spawn_app,
     tiny_png_bytes, unique_email,
 };
+use api::{
+    infrastructure::storage::{
+        content_addressed,
+        in_memory::InMemoryStorage,
+        traits::{ChunkedUpload, StorageService},
+    },
+    workers::storage_gc::{run_cleanup, schedule_cleanup},
+};
+use async_trait::async_trait;
 use axum::{
     Router,
     body::Body,
     http::{Request, StatusCode, header},
 };
 use serde_json::{Value, json};
+use std::time::Duration;
 
 const DEFAULT_CITY_ID: &str = "0194f123-4567-7abc-8def-0123456789ab";
 
@@ -120,6 +130,26 @@ async fn authenticated_upload_is_visible_in_my_uploads() {
     );
 }
 
+#[tokio::test]
+async fn my_stats_count_a_fresh_upload() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let _ = upload_for_user(&app.app, &token).await;
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/api/v1/me/stats")
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build my stats request");
+
+    let res = expect_status(send(&app.app, req).await, StatusCode::OK).await;
+    let stats: Value = read_json(res).await;
+    assert_eq!(stats["uploads"]["total"], json!(1));
+    assert_eq!(stats["current_streak_days"], json!(1));
+    assert_eq!(stats["strikes"]["standing"], json!("good_standing"));
+}
+
 #[tokio::test]
 async fn updating_my_upload_creates_metadata_history_entries() {
     let app = spawn_app().await;
@@ -176,3 +206,273 @@ async fn updating_my_upload_creates_metadata_history_entries() {
         "expected at least one status history record"
     );
 }
+
+#[tokio::test]
+async fn deleting_my_upload_records_its_storage_cleanup() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let uploaded = upload_for_user(&app.app, &token).await;
+    let uploaded_id = uploaded["id"]
+        .as_str()
+        .expect("upload id missing")
+        .to_string();
+
+    let req = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/v1/letterings/{}", uploaded_id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build delete request");
+    expect_status(send(&app.app, req).await, StatusCode::NO_CONTENT).await;
+
+    let id = uuid::Uuid::parse_str(&uploaded_id).expect("invalid upload id");
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM letterings WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .expect("failed to count letterings");
+    assert_eq!(remaining, 0);
+
+    let (keys, completed): (Vec<String>, bool) = sqlx::query_as(
+        "SELECT object_keys, completed_at IS NOT NULL
+         FROM storage_cleanup_jobs WHERE lettering_id = $1",
+    )
+    .bind(id)
+    .fetch_one(&app.db)
+    .await
+    .expect("delete should record a storage cleanup job");
+    assert!(completed, "cleanup should run right after the delete");
+    assert!(keys.is_empty());
+}
+
+#[tokio::test]
+async fn uploads_are_stored_under_content_addressed_keys() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let uploaded = upload_for_user(&app.app, &token).await;
+    let id = uuid::Uuid::parse_str(uploaded["id"].as_str().expect("upload id missing"))
+        .expect("invalid upload id");
+
+    let (image_url, image_hash): (String, String) =
+        sqlx::query_as("SELECT image_url, image_hash FROM letterings WHERE id = $1")
+            .bind(id)
+            .fetch_one(&app.db)
+            .await
+            .expect("failed to load upload");
+    let key = format!("sha256/{}/{}.webp", &image_hash[..2], image_hash);
+    assert_eq!(image_url, format!("https://test-storage.local/{}", key));
+
+    let refs: i32 = sqlx::query_scalar("SELECT ref_count FROM storage_objects WHERE key = $1")
+        .bind(&key)
+        .fetch_one(&app.db)
+        .await
+        .expect("upload should reference its image");
+    assert_eq!(refs, 1);
+
+    let req = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/v1/letterings/{}", id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("failed to build delete request");
+    expect_status(send(&app.app, req).await, StatusCode::NO_CONTENT).await;
+
+    let tracked: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM storage_objects WHERE key = $1)")
+            .bind(&key)
+            .fetch_one(&app.db)
+            .await
+            .expect("failed to check storage objects");
+    assert!(!tracked, "the last reference should be released on delete");
+}
+
+/// Schedules and runs the cleanup of `key`, which should finish.
+async fn clean_up(db: &sqlx::PgPool, storage: &InMemoryStorage, key: &str) {
+    let mut tx = db.begin().await.expect("failed to begin");
+    let job = schedule_cleanup(&mut tx, None, vec![key.to_string()])
+        .await
+        .expect("failed to schedule cleanup");
+    tx.commit().await.expect("failed to commit");
+    let done = run_cleanup(db, storage, &job)
+        .await
+        .expect("cleanup failed");
+    assert!(done, "cleanup left objects behind");
+}
+
+#[tokio::test]
+async fn storage_cleanup_spares_keys_referenced_again() {
+    let app = spawn_app().await;
+    let storage = InMemoryStorage::new("http://storage.test", 1 << 20);
+    let data = format!("gc-race-{}", uuid::Uuid::now_v7()).into_bytes();
+
+    let url = content_addressed::put(
+        &app.db,
+        &storage,
+        data.clone(),
+        "txt",
+        "text/plain",
+        None,
+        false,
+    )
+    .await
+    .expect("first put failed");
+    let key = storage.key_from_url(&url).expect("unknown url");
+    let mut conn = app.db.acquire().await.expect("failed to acquire");
+    let released = content_addressed::release(&mut conn, std::slice::from_ref(&key))
+        .await
+        .expect("release failed");
+    assert_eq!(released, vec![key.clone()]);
+
+    // An identical upload after the release but before the cleanup uploads
+    // again, whatever became of the object, and the cleanup then leaves it.
+    storage.delete(&key).await.expect("delete failed");
+    content_addressed::put(&app.db, &storage, data, "txt", "text/plain", None, false)
+        .await
+        .expect("second put failed");
+    assert!(
+        storage.get(&key).is_some(),
+        "tombstoned key was not re-uploaded"
+    );
+    clean_up(&app.db, &storage, &key).await;
+    assert!(storage.get(&key).is_some(), "referenced object was deleted");
+
+    // Released for good, the object and its tombstone go.
+    content_addressed::release(&mut conn, std::slice::from_ref(&key))
+        .await
+        .expect("release failed");
+    clean_up(&app.db, &storage, &key).await;
+    assert!(storage.get(&key).is_none());
+    let tracked: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM storage_objects WHERE key = $1)")
+            .bind(&key)
+            .fetch_one(&app.db)
+            .await
+            .expect("failed to check storage objects");
+    assert!(!tracked);
+}
+
+/// Takes its time over uploads, so a second put can start while the first
+/// is still uploading.
+struct SlowUploads(InMemoryStorage);
+
+#[async_trait]
+impl StorageService for SlowUploads {
+    async fn upload(&self, key: &str, data: Vec<u8>, content_type: &str) -> anyhow::Result<String> {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        self.0.upload(key, data, content_type).await
+    }
+    async fn start_chunked_upload(
+        &self,
+        key: &str,
+        content_type: &str,
+    ) -> anyhow::Result<Box<dyn ChunkedUpload>> {
+        self.0.start_chunked_upload(key, content_type).await
+    }
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.0.delete(key).await
+    }
+    async fn download(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.0.download(key).await
+    }
+    fn get_url(&self, key: &str) -> String {
+        self.0.get_url(key)
+    }
+    async fn signed_url(&self, key: &str, expires_in: Duration) -> anyhow::Result<String> {
+        self.0.signed_url(key, expires_in).await
+    }
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        self.0.key_from_url(url)
+    }
+}
+
+#[tokio::test]
+async fn identical_puts_wait_for_the_first_upload() {
+    let app = spawn_app().await;
+    let storage = SlowUploads(InMemoryStorage::new("http://storage.test", 1 << 20));
+    let data = format!("put-race-{}", uuid::Uuid::now_v7()).into_bytes();
+    let put =
+        |data| content_addressed::put(&app.db, &storage, data, "txt", "text/plain", None, false);
+
+    let (first, second) = tokio::join!(put(data.clone()), async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let url = put(data.clone()).await.expect("second put failed");
+        let key = storage.key_from_url(&url).expect("unknown url");
+        assert!(
+            storage.0.get(&key).is_some(),
+            "second put returned before the object was uploaded"
+        );
+        url
+    });
+    assert_eq!(first.expect("first put failed"), second);
+
+    let key = storage.key_from_url(&second).expect("unknown url");
+    let refs: i32 = sqlx::query_scalar("SELECT ref_count FROM storage_objects WHERE key = $1")
+        .bind(&key)
+        .fetch_one(&app.db)
+        .await
+        .expect("failed to read storage objects");
+    assert_eq!(refs, 2);
+}
+
+#[tokio::test]
+async fn approved_upload_has_detail_context() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let uploaded = upload_for_user(&app.app, &token).await;
+    let id = uploaded["id"].as_str().expect("upload id missing");
+    let uri = format!("/api/v1/letterings/{}/context", id);
+
+    let get_context = |uri: String| {
+        Request::builder()
+            .method("GET")
+            .uri(uri)
+            .body(Body::empty())
+            .expect("failed to build context request")
+    };
+    let res = send(&app.app, get_context(uri.clone())).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+
+    sqlx::query("UPDATE letterings SET status = 'APPROVED' WHERE id = $1")
+        .bind(uuid::Uuid::parse_str(id).expect("invalid upload id"))
+        .execute(&app.db)
+        .await
+        .expect("failed to approve upload");
+
+    let res = expect_status(send(&app.app, get_context(uri)).await, StatusCode::OK).await;
+    let context: Value = read_json(res).await;
+    assert_eq!(context["city"]["id"], DEFAULT_CITY_ID);
+    assert!(context["nearby"].is_array());
+    assert!(
+        context["same_contributor"]
+            .as_array()
+            .expect("same_contributor missing")
+            .iter()
+            .all(|item| item["id"] != id)
+    );
+}
+
+#[tokio::test]
+async fn upload_processing_status_lists_each_stage() {
+    let app = spawn_app().await;
+    let token = register_user_and_token(&app.app).await;
+    let uploaded = upload_for_user(&app.app, &token).await;
+    let id = uploaded["id"].as_str().expect("upload id missing");
+
+    let get_processing = |id: &str| {
+        Request::builder()
+            .method("GET")
+            .uri(format!("/api/v1/letterings/{}/processing", id))
+            .body(Body::empty())
+            .expect("failed to build processing request")
+    };
+    let res = expect_status(send(&app.app, get_processing(id)).await, StatusCode::OK).await;
+    let processing: Value = read_json(res).await;
+    assert_eq!(processing["virus_scan"], "DONE");
+    assert_eq!(processing["thumbnails"], "DONE");
+    // ML is disabled in tests.
+    assert_eq!(processing["ml"], "SKIPPED");
+    assert_eq!(processing["complete"], json!(true));
+
+    let res = send(&app.app, get_processing(&uuid::Uuid::now_v7().to_string())).await;
+    assert_status(res.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,120 @@
+use super::helpers::{TestApp, admin_token, expect_status, read_json, send, spawn_app};
+use api::infrastructure::monitoring::heartbeat::{HEARTBEATS_KEY, Heartbeats, heartbeat_key};
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use chrono::{TimeDelta, Utc};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+fn redis() -> redis::Client {
+    redis::Client::open(
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string()),
+    )
+    .expect("invalid redis url")
+}
+
+async fn list_workers(app: &TestApp, token: &str) -> Vec<Value> {
+    let res = expect_status(
+        send(
+            &app.app,
+            Request::builder()
+                .uri("/api/v1/admin/workers")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .expect("failed to build workers request"),
+        )
+        .await,
+        StatusCode::OK,
+    )
+    .await;
+    let body: Value = read_json(res).await;
+    body["items"]
+        .as_array()
+        .expect("items should be a list")
+        .clone()
+}
+
+fn instance<'a>(workers: &'a [Value], worker: &str, instance: &str) -> &'a Value {
+    workers
+        .iter()
+        .find(|w| w["name"] == worker)
+        .and_then(|w| w["instances"].as_array())
+        .and_then(|instances| instances.iter().find(|i| i["instance"] == instance))
+        .expect("heartbeat not reported")
+}
+
+#[tokio::test]
+async fn workers_report_their_heartbeats_jobs_and_counts() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let name = format!("test-{}", uuid::Uuid::now_v7().simple());
+    let heartbeats = Heartbeats::new(redis()).with_instance(name.clone());
+
+    heartbeats
+        .working_on("ml_processor", "letterings 1, 2", Duration::from_secs(300))
+        .await;
+    heartbeats.count("ml_processor", 2, 1).await;
+
+    let workers = list_workers(&app, &token).await;
+    let ml = workers
+        .iter()
+        .find(|w| w["name"] == "ml_processor")
+        .expect("ml_processor missing");
+    assert_eq!(ml["status"], json!("running"));
+    assert!(ml["queue"].is_object());
+    let beat = instance(&workers, "ml_processor", &name);
+    assert_eq!(beat["current_job"], json!("letterings 1, 2"));
+    assert_eq!(beat["processed"], json!(2));
+    assert_eq!(beat["failed"], json!(1));
+    assert_eq!(beat["stale"], json!(false));
+
+    heartbeats
+        .beat("ml_processor", Duration::from_secs(30))
+        .await;
+    let workers = list_workers(&app, &token).await;
+    assert_eq!(
+        instance(&workers, "ml_processor", &name)["current_job"],
+        Value::Null
+    );
+}
+
+#[tokio::test]
+async fn overdue_heartbeats_are_stale() {
+    let app = spawn_app().await;
+    let token = admin_token(&app).await;
+    let name = format!("test-{}", uuid::Uuid::now_v7().simple());
+
+    // A heartbeat from an instance that died ten minutes ago.
+    let key = heartbeat_key("print_bundle", &name);
+    let last = Utc::now() - TimeDelta::minutes(10);
+    let mut conn = redis()
+        .get_multiplexed_async_connection()
+        .await
+        .expect("redis unavailable");
+    redis::pipe()
+        .hset_multiple(
+            &key,
+            &[
+                ("worker", "print_bundle".to_string()),
+                ("instance", name.clone()),
+                ("last_beat_at", last.to_rfc3339()),
+                ("next_beat_by", (last + TimeDelta::seconds(15)).to_rfc3339()),
+            ],
+        )
+        .ignore()
+        .expire(&key, 600)
+        .ignore()
+        .sadd(HEARTBEATS_KEY, &key)
+        .ignore()
+        .query_async::<()>(&mut conn)
+        .await
+        .expect("failed to write heartbeat");
+
+    let workers = list_workers(&app, &token).await;
+    assert_eq!(
+        instance(&workers, "print_bundle", &name)["stale"],
+        json!(true)
+    );
+}
@@ -6,7 +6,9 @@ import { useCityStore } from "../store/useCityStore";
 interface CityOption {
   id: string;
   name: string;
+  display_name: string;
   country_code: string;
+  country_name: string;
   center_lat: number | null;
   center_lng: number | null;
   default_zoom: number | null;
@@ -21,7 +23,7 @@ const mergeUniqueCities = (base: CityOption[], incoming: CityOption[]) => {
     const activeA = a.is_active ? 1 : 0;
     const activeB = b.is_active ? 1 : 0;
     if (activeA !== activeB) return activeB - activeA;
-    return a.name.localeCompare(b.name);
+    return a.display_name.localeCompare(b.display_name);
   });
 };
 
@@ -81,7 +83,7 @@ const CitySelector: React.FC = () => {
 
     setCity(
       city.id,
-      city.name,
+      city.display_name,
       city.center_lat ?? 0,
       city.center_lng ?? 0,
       city.default_zoom ?? 11,
@@ -115,7 +117,7 @@ const CitySelector: React.FC = () => {
           <option value="all">All Cities</option>
           {activeCities.map((c) => (
             <option key={c.id} value={c.id}>
-              {c.name} ({c.country_code})
+              {c.display_name} ({c.country_name})
             </option>
           ))}
         </select>
@@ -18,7 +18,7 @@ const CommunityPage: React.FC<{
   const [newCollection, setNewCollection] = useState({
     name: "",
     description: "",
-    creator_tag: "",
+    is_public: true,
   });
   const { addToast } = useToastStore();
 
@@ -60,12 +60,12 @@ const CommunityPage: React.FC<{
 
   const handleCreateCollection = async (e: React.FormEvent) => {
     e.preventDefault();
-    if (!newCollection.name.trim() || !newCollection.creator_tag.trim()) return;
+    if (!newCollection.name.trim()) return;
     try {
       await api.createCollection(newCollection);
       addToast("Collection created", "success");
       setShowCreate(false);
-      setNewCollection({ name: "", description: "", creator_tag: "" });
+      setNewCollection({ name: "", description: "", is_public: true });
       const data = await api.getCollections();
       setCollections(data);
     } catch {
@@ -200,18 +200,6 @@ const CommunityPage: React.FC<{
                     }
                     required
                   />
-                  <input
-                    placeholder="Your Name"
-                    className="w-full border-2 border-black p-3 font-black text-sm outline-none focus:border-[#cc543a]"
-                    value={newCollection.creator_tag}
-                    onChange={(e) =>
-                      setNewCollection({
-                        ...newCollection,
-                        creator_tag: e.target.value,
-                      })
-                    }
-                    required
-                  />
                   <textarea
                     placeholder="Description (optional)"
                     className="w-full border-2 border-black p-3 font-medium text-sm outline-none focus:border-[#cc543a]"
@@ -224,6 +212,19 @@ const CommunityPage: React.FC<{
                       })
                     }
                   />
+                  <label className="flex items-center gap-2 text-[10px] font-black uppercase">
+                    <input
+                      type="checkbox"
+                      checked={newCollection.is_public}
+                      onChange={(e) =>
+                        setNewCollection({
+                          ...newCollection,
+                          is_public: e.target.checked,
+                        })
+                      }
+                    />
+                    Public
+                  </label>
                   <button
                     type="submit"
                     className="bg-[#cc543a] text-white px-6 py-3 font-black text-[10px] uppercase hover:bg-black transition-colors"
@@ -251,7 +252,7 @@ const CommunityPage: React.FC<{
                         </p>
                       )}
                       <div className="flex justify-between items-center text-[9px] font-black uppercase text-slate-400">
-                        <span>By {c.creator_tag}</span>
+                        <span>By {c.creator_name || "Anonymous"}</span>
                         <span>{c.item_count} items</span>
                       </div>
                     </div>
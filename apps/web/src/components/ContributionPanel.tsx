@@ -13,6 +13,7 @@ import { useToastStore } from "../store/useToastStore";
 import { useCityStore } from "../store/useCityStore";
 import { enqueueUpload } from "../lib/offlineQueue";
 import { api } from "../lib/api";
+import type { LocationPrivacy } from "../types";
 
 type FileStatus = "pending" | "uploading" | "done" | "error";
 
@@ -47,6 +48,9 @@ const ContributionPanel: React.FC<{
     area: "Other",
     pin: "",
     desc: "",
+    credit: "",
+    privacy: "exact" as LocationPrivacy,
+    coords: null as { lat: number; lng: number } | null,
   });
   const fileRef = useRef<HTMLInputElement>(null);
   const cameraRef = useRef<HTMLInputElement>(null);
@@ -71,6 +75,10 @@ const ContributionPanel: React.FC<{
     setIsLocating(true);
     navigator.geolocation.getCurrentPosition(
       async (pos) => {
+        setForm((prev) => ({
+          ...prev,
+          coords: { lat: pos.coords.latitude, lng: pos.coords.longitude },
+        }));
         try {
           const res = await fetch(
             `https://nominatim.openstreetmap.org/reverse?format=json&lat=${pos.coords.latitude}&lon=${pos.coords.longitude}`
@@ -207,6 +215,9 @@ const ContributionPanel: React.FC<{
             pinCode: form.pin,
             description: form.desc,
             cityId: selectedCityId || "0194f123-4567-7abc-8def-0123456789ab",
+            locationPrivacy: form.privacy,
+            coords: form.coords,
+            creditName: form.credit.trim(),
           });
           queued++;
         } catch {
@@ -253,6 +264,12 @@ const ContributionPanel: React.FC<{
         "city_id",
         selectedCityId || "0194f123-4567-7abc-8def-0123456789ab",
       );
+      formData.append("location_privacy", form.privacy);
+      if (form.credit.trim()) formData.append("credit_name", form.credit.trim());
+      if (form.coords) {
+        formData.append("lat", String(form.coords.lat));
+        formData.append("lng", String(form.coords.lng));
+      }
 
       try {
         await api.upload(formData);
@@ -518,6 +535,34 @@ const ContributionPanel: React.FC<{
               value={form.desc}
               onChange={(e) => setForm({ ...form, desc: e.target.value })}
             />
+
+            <input
+              placeholder="Photo by (if someone else took it)"
+              className="w-full border-2 border-black p-4 font-black text-sm focus:border-[#cc543a] outline-none"
+              value={form.credit}
+              maxLength={80}
+              onChange={(e) => setForm({ ...form, credit: e.target.value })}
+            />
+
+            <div className="space-y-1">
+              <label className="text-[8px] font-black uppercase text-slate-400">
+                Public Location
+              </label>
+              <select
+                className="w-full border-2 border-black p-4 font-black bg-white text-sm outline-none"
+                value={form.privacy}
+                onChange={(e) =>
+                  setForm({
+                    ...form,
+                    privacy: e.target.value as LocationPrivacy,
+                  })
+                }
+              >
+                <option value="exact">Exact spot</option>
+                <option value="fuzzed">Approximate (~100m)</option>
+                <option value="city">City only</option>
+              </select>
+            </div>
           </div>
 
           <button
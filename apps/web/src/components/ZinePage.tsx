@@ -237,6 +237,11 @@ const ZinePage: React.FC<{
                 By {page.contributorName}
               </span>
             )}
+            {page.creditName && (
+              <span className="text-[9px] font-black uppercase text-slate-500">
+                Photo: {page.creditName}
+              </span>
+            )}
           </div>
         </div>
       </div>
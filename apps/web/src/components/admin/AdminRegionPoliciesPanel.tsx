@@ -84,6 +84,7 @@ const AdminRegionPoliciesPanel: React.FC = () => {
         | "uploads_enabled"
         | "comments_enabled"
         | "discoverability_enabled"
+        | "age_gate_enabled"
         | "auto_moderation_level"
       >
     >,
@@ -102,6 +103,7 @@ const AdminRegionPoliciesPanel: React.FC = () => {
         uploads_enabled: policy.uploads_enabled,
         comments_enabled: policy.comments_enabled,
         discoverability_enabled: policy.discoverability_enabled,
+        age_gate_enabled: policy.age_gate_enabled,
         auto_moderation_level: policy.auto_moderation_level,
       });
       setItems((prev) =>
@@ -130,6 +132,7 @@ const AdminRegionPoliciesPanel: React.FC = () => {
         uploads_enabled: true,
         comments_enabled: true,
         discoverability_enabled: true,
+        age_gate_enabled: false,
         auto_moderation_level: "standard",
       });
       setItems((prev) => {
@@ -213,7 +216,7 @@ const AdminRegionPoliciesPanel: React.FC = () => {
                 </button>
               </div>
 
-              <div className="grid grid-cols-1 md:grid-cols-4 gap-3">
+              <div className="grid grid-cols-1 md:grid-cols-5 gap-3">
                 <label className="flex items-center justify-between border-2 border-black px-3 py-2 text-[10px] font-black uppercase">
                   Uploads
                   <input
@@ -253,6 +256,19 @@ const AdminRegionPoliciesPanel: React.FC = () => {
                   />
                 </label>
 
+                <label className="flex items-center justify-between border-2 border-black px-3 py-2 text-[10px] font-black uppercase">
+                  Age gate
+                  <input
+                    type="checkbox"
+                    checked={policy.age_gate_enabled}
+                    onChange={(e) =>
+                      updateDraft(policy.country_code, {
+                        age_gate_enabled: e.target.checked,
+                      })
+                    }
+                  />
+                </label>
+
                 <label className="border-2 border-black px-3 py-2 text-[10px] font-black uppercase flex items-center justify-between gap-2">
                   Moderation
                   <select
@@ -56,6 +56,7 @@ export interface RegionPolicyItem {
   uploads_enabled: boolean;
   comments_enabled: boolean;
   discoverability_enabled: boolean;
+  age_gate_enabled: boolean;
   auto_moderation_level: "relaxed" | "standard" | "strict";
   created_at: string;
   updated_at: string;
@@ -296,7 +297,9 @@ export const api = {
         detected_text?: string;
         ml_style?: string;
         ml_script?: string;
+        similarity?: number;
       }>;
+      method?: "embedding" | "metadata";
     }>(`${API_BASE_URL}/api/v1/letterings/${id}/similar`);
   },
 
@@ -361,7 +364,9 @@ export const api = {
       Array<{
         id: string;
         name: string;
+        display_name: string;
         country_code: string;
+        country_name: string;
         center_lat: number | null;
         center_lng: number | null;
         default_zoom: number | null;
@@ -432,9 +437,22 @@ export const api = {
     return fetchJson<CollectionSummary[]>(`${API_BASE_URL}/api/v1/collections`);
   },
 
+  // The signed-in user's collections, private ones included
+  async getMyCollections(): Promise<CollectionSummary[]> {
+    return fetchJson<CollectionSummary[]>(
+      `${API_BASE_URL}/api/v1/me/collections`,
+      { headers: getAuthHeaders(USER_SESSION_KEY) },
+      USER_SESSION_KEY
+    );
+  },
+
   // Get single collection detail
   async getCollection(id: string): Promise<any> {
-    return fetchJson<any>(`${API_BASE_URL}/api/v1/collections/${id}`);
+    return fetchJson<any>(
+      `${API_BASE_URL}/api/v1/collections/${id}`,
+      { headers: getAuthHeaders(USER_SESSION_KEY) },
+      USER_SESSION_KEY
+    );
   },
 
   // Add lettering to a collection
@@ -456,13 +474,20 @@ export const api = {
   async createCollection(data: {
     name: string;
     description?: string;
-    creator_tag: string;
+    is_public?: boolean;
   }) {
-    return fetchJson<CollectionSummary>(`${API_BASE_URL}/api/v1/collections`, {
-      method: "POST",
-      headers: { "Content-Type": "application/json" },
-      body: JSON.stringify(data),
-    });
+    return fetchJson<CollectionSummary>(
+      `${API_BASE_URL}/api/v1/collections`,
+      {
+        method: "POST",
+        headers: {
+          "Content-Type": "application/json",
+          ...getAuthHeaders(USER_SESSION_KEY),
+        },
+        body: JSON.stringify(data),
+      },
+      USER_SESSION_KEY
+    );
   },
 
   // User auth
@@ -865,6 +890,7 @@ export const api = {
       uploads_enabled?: boolean;
       comments_enabled?: boolean;
       discoverability_enabled?: boolean;
+      age_gate_enabled?: boolean;
       auto_moderation_level?: "relaxed" | "standard" | "strict";
     },
   ) {
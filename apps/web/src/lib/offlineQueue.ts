@@ -1,5 +1,6 @@
 import { API_BASE_URL } from "../constants";
 import { USER_SESSION_KEY } from "./api";
+import type { LocationPrivacy } from "../types";
 
 const DB_NAME = "tyl-offline";
 const DB_VERSION = 1;
@@ -13,6 +14,9 @@ interface QueuedUpload {
   pinCode: string;
   description: string;
   cityId: string;
+  locationPrivacy?: LocationPrivacy;
+  coords?: { lat: number; lng: number } | null;
+  creditName?: string;
   createdAt: number;
 }
 
@@ -40,6 +44,9 @@ export async function enqueueUpload(data: {
   pinCode: string;
   description: string;
   cityId: string;
+  locationPrivacy: LocationPrivacy;
+  coords: { lat: number; lng: number } | null;
+  creditName: string;
 }): Promise<void> {
   const db = await openDB();
   return new Promise((resolve, reject) => {
@@ -84,6 +91,12 @@ export async function syncOfflineUploads(): Promise<number> {
     formData.append("pin_code", item.pinCode);
     formData.append("description", item.description);
     formData.append("city_id", item.cityId);
+    formData.append("location_privacy", item.locationPrivacy ?? "exact");
+    if (item.coords) {
+      formData.append("lat", String(item.coords.lat));
+      formData.append("lng", String(item.coords.lng));
+    }
+    if (item.creditName) formData.append("credit_name", item.creditName);
 
     try {
       const token = sessionStorage.getItem(USER_SESSION_KEY);
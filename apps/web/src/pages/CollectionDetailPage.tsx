@@ -34,7 +34,7 @@ const CollectionDetailPage: React.FC = () => {
               <h1 className="text-4xl font-black uppercase tracking-tighter">{collection.name}</h1>
             </div>
             <div className="flex items-center gap-4 mt-2 text-[10px] font-black uppercase text-slate-400 tracking-widest">
-              <span className="flex items-center gap-1"><User size={12}/> {collection.creator_tag}</span>
+              <span className="flex items-center gap-1"><User size={12}/> {collection.creator_name || "Anonymous"}</span>
               <span className="flex items-center gap-1"><Calendar size={12}/> {new Date(collection.created_at).toLocaleDateString()}</span>
               <span>{collection.items?.length || 0} Specimens</span>
             </div>
@@ -49,8 +49,8 @@ const CollectionDetailPage: React.FC = () => {
 
         <div className="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 gap-6">
           {collection.items?.map((item: any) => (
-            <Link key={item.id} to={`/lettering/${item.id}`} className="group bg-white border-2 border-black p-3 brutalist-shadow-sm hover:-translate-y-1 transition-all">
-              <img src={item.thumbnail || item.image_url} className="aspect-square w-full object-cover border border-black grayscale group-hover:grayscale-0 transition-all" alt="specimen" />
+            <Link key={item.lettering_id} to={`/lettering/${item.lettering_id}`} className="group bg-white border-2 border-black p-3 brutalist-shadow-sm hover:-translate-y-1 transition-all">
+              <img src={item.thumbnail_small || item.image_url} className="aspect-square w-full object-cover border border-black grayscale group-hover:grayscale-0 transition-all" alt="specimen" />
               <p className="text-[11px] font-black uppercase truncate mt-3">{item.detected_text || "Street Discovery"}</p>
               <p className="text-[9px] font-bold text-slate-400 mt-1">@{item.contributor_tag}</p>
             </Link>
@@ -35,6 +35,7 @@ export const mapLetteringToZinePage = (item: Lettering): ZinePageData => ({
   comments_count: item.comments_count || 0,
   ml_script: item.ml_metadata?.script,
   is_owner: item.is_owner,
+  creditName: item.credit?.name,
 });
 
 const ExplorePage: React.FC = () => {
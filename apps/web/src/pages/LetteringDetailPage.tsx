@@ -89,15 +89,21 @@ const LetteringDetailPage: React.FC = () => {
     }
   };
 
+  const shareUrl = (channel: string) => {
+    if (!lettering?.short_url) return window.location.href;
+    const url = new URL(lettering.short_url, window.location.origin);
+    url.searchParams.set("c", channel);
+    return url.toString();
+  };
+
   const handleShare = async () => {
-    const url = window.location.href;
     try {
       await navigator.share({
         title: `Through Your Letters: ${lettering?.detected_text || "Street Discovery"}`,
-        url,
+        url: shareUrl("native"),
       });
     } catch {
-      await navigator.clipboard.writeText(url);
+      await navigator.clipboard.writeText(shareUrl("copy"));
       addToast("Link copied", "success");
     }
   };
@@ -160,7 +166,15 @@ const LetteringDetailPage: React.FC = () => {
 
   return (
     <>
-      <Helmet><title>{title} | Through Your Letters</title></Helmet>
+      <Helmet>
+        <title>{title} | Through Your Letters</title>
+        <meta property="og:title" content={`${title} | Through Your Letters`} />
+        {lettering.short_url && <meta property="og:url" content={new URL(lettering.short_url, window.location.origin).toString()} />}
+        <meta property="og:image" content={`${API_BASE_URL}/api/v1/letterings/${lettering.id}/og-image`} />
+        <meta property="og:image:width" content="1200" />
+        <meta property="og:image:height" content="630" />
+        <meta name="twitter:card" content="summary_large_image" />
+      </Helmet>
 
       {lightboxOpen && <ImageLightbox imageUrl={lettering.image_url} title={title} letteringId={lettering.id} onClose={() => setLightboxOpen(false)} />}
       {isCurating && <AddToCollectionModal letteringId={lettering.id} onClose={() => { setIsCurating(false); fetchData(lettering.id); }} />}
@@ -19,8 +19,12 @@ export interface ZinePageData {
   comments_count?: number;
   ml_script?: string;
   is_owner?: boolean;
+  creditName?: string;
 }
 
+/** How precisely an upload's location is shown publicly. */
+export type LocationPrivacy = "exact" | "fuzzed" | "city";
+
 export interface Comment {
   id: string;
   lettering_id: string;
@@ -65,10 +69,12 @@ export interface CollectionSummary {
   id: string;
   name: string;
   description?: string;
-  creator_tag: string;
-  cover_image_url?: string;
+  creator_name?: string;
+  is_public: boolean;
+  slug?: string;
   item_count: number;
   created_at: string;
+  updated_at: string;
 }
 
 export interface ChallengeData {
@@ -111,6 +117,9 @@ export interface Lettering {
   report_count?: number;
   report_reasons?: string[];
   is_owner?: boolean;
+  short_url?: string | null;
+  /** Photographer credited by the uploader, when not under dispute. */
+  credit?: { name: string; linked: boolean } | null;
 }
 
 export interface NeighborhoodCount {